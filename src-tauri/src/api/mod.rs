@@ -0,0 +1,443 @@
+//! Local HTTP API
+//!
+//! An optional REST and WebSocket server on localhost, so Wingman can be
+//! scripted or driven from other tools while the app runs. It is off until
+//! turned on with `api_server_enable`, and every request but the health
+//! check needs the API token, sent as `Authorization: Bearer <token>` or,
+//! for WebSocket clients that can't set headers, a `token` query parameter.
+//! The token is kept in the OS keychain.
+//!
+//! Routes call the same command handlers the frontend does:
+//!
+//! - `GET /api/health`
+//! - `GET /api/projects`, `POST /api/tasks`
+//! - `GET /api/sessions`, `POST /api/sessions`, `GET /api/sessions/:id`
+//! - `POST /api/sessions/:id/start`, `/stop`, `/cancel`, `/messages`
+//! - `POST /api/sessions/:id/permissions/:request_id`
+//! - `GET /api/sessions/:id/replay`
+//! - `GET /api/events` (WebSocket; see [`stream`])
+//!
+//! Bodies are the types in [`types`].
+
+mod stream;
+pub mod types;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, EventId, Listener, Manager};
+use tokio::sync::{broadcast, oneshot, Mutex};
+
+use crate::claude::replay::ReplayChunk;
+use crate::commands::{self, SessionWithMessagesResponse};
+use crate::db::settings::{self, API_SERVER_ENABLED, API_SERVER_PORT};
+use crate::error::{AppError, ErrorCode};
+use crate::secrets;
+use crate::state::AppState;
+
+use self::types::{
+    Health, MessageSent, PermissionAnswer, ProjectResponse, SendMessageRequest, SessionCreateRequest,
+    SessionResponse, SessionSummaryResponse, StartSessionRequest, StreamEvent, TaskCreateRequest, TaskResponse,
+};
+
+/// Port used until another is chosen
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// Lowest port accepted; lower ones need elevated privileges on most systems
+pub const MIN_PORT: u16 = 1024;
+
+/// How long a stopping server gets to finish requests in flight
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Streamed events buffered per WebSocket client before it is told it lagged
+const EVENT_BUFFER: usize = 1024;
+
+/// The running server, if any
+#[derive(Default)]
+pub struct ApiServer {
+    running: Mutex<Option<RunningServer>>,
+}
+
+struct RunningServer {
+    port: u16,
+    shutdown: oneshot::Sender<()>,
+    task: tauri::async_runtime::JoinHandle<()>,
+    /// Listeners forwarding app events to WebSocket clients
+    listeners: Vec<EventId>,
+}
+
+impl ApiServer {
+    /// Port the server is listening on, if it is running
+    pub async fn port(&self) -> Option<u16> {
+        self.running.lock().await.as_ref().map(|server| server.port)
+    }
+
+    /// Start listening on a localhost port, replacing a running server
+    pub async fn start(&self, app: &AppHandle, port: u16, token: String) -> Result<(), AppError> {
+        let mut running = self.running.lock().await;
+        if let Some(server) = running.take() {
+            // Frees the port, in case the new server uses it too
+            server.stop(app).await;
+        }
+
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+            .await
+            .map_err(|e| AppError::with_details(
+                ErrorCode::NetworkError,
+                format!("Could not listen on port {}", port),
+                e.to_string(),
+            ))?;
+
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        let listeners = stream::forward_events(app, &events);
+        let context = ApiContext {
+            app: app.clone(),
+            token: token.into(),
+            events,
+        };
+
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+        let task = tauri::async_runtime::spawn(async move {
+            let result = axum::serve(listener, router(context))
+                .with_graceful_shutdown(async {
+                    let _ = shutdown_rx.await;
+                })
+                .await;
+            if let Err(e) = result {
+                log::warn!("API server stopped: {}", e);
+            }
+        });
+
+        log::info!("API server listening on http://127.0.0.1:{}", port);
+        *running = Some(RunningServer {
+            port,
+            shutdown,
+            task,
+            listeners,
+        });
+        Ok(())
+    }
+
+    /// Stop the server if it is running
+    pub async fn stop(&self, app: &AppHandle) {
+        if let Some(server) = self.running.lock().await.take() {
+            server.stop(app).await;
+            log::info!("API server stopped");
+        }
+    }
+}
+
+impl RunningServer {
+    async fn stop(self, app: &AppHandle) {
+        for id in self.listeners {
+            app.unlisten(id);
+        }
+        let _ = self.shutdown.send(());
+        if tokio::time::timeout(SHUTDOWN_TIMEOUT, self.task).await.is_err() {
+            log::warn!("API server didn't stop within {:?}", SHUTDOWN_TIMEOUT);
+        }
+    }
+}
+
+/// Get the API token, creating one the first time
+pub async fn token() -> Result<String, AppError> {
+    match secrets::get(secrets::API_SERVER_TOKEN).await? {
+        Some(token) => Ok(token),
+        None => rotate_token().await,
+    }
+}
+
+/// Replace the API token; a running server keeps the old one until restarted
+pub async fn rotate_token() -> Result<String, AppError> {
+    let token = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    secrets::set(secrets::API_SERVER_TOKEN, &token).await?;
+    Ok(token)
+}
+
+/// Whether the server is turned on, and the port it should use
+pub async fn configured(db: &SqlitePool) -> Result<(bool, u16), AppError> {
+    let enabled = settings::get_setting(db, API_SERVER_ENABLED).await?.as_deref() == Some("true");
+    let port = settings::get_setting(db, API_SERVER_PORT)
+        .await?
+        .and_then(|v| v.parse::<u16>().ok())
+        .filter(|port| *port >= MIN_PORT)
+        .unwrap_or(DEFAULT_PORT);
+    Ok((enabled, port))
+}
+
+/// Start the server at launch if it is turned on
+pub fn spawn_api_server(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = start_if_enabled(&app).await {
+            log::warn!("Failed to start the API server: {}", e);
+        }
+    });
+}
+
+async fn start_if_enabled(app: &AppHandle) -> Result<(), AppError> {
+    let state = app.state::<AppState>();
+    let (enabled, port) = configured(&state.db).await?;
+    if enabled {
+        state.api_server.start(app, port, token().await?).await?;
+    }
+    Ok(())
+}
+
+#[derive(Clone)]
+struct ApiContext {
+    app: AppHandle,
+    token: Arc<str>,
+    events: broadcast::Sender<StreamEvent>,
+}
+
+fn router(context: ApiContext) -> Router {
+    let api = Router::new()
+        .route("/projects", get(list_projects))
+        .route("/tasks", post(create_task))
+        .route("/sessions", get(list_sessions).post(create_session))
+        .route("/sessions/:session_id", get(load_session))
+        .route("/sessions/:session_id/start", post(start_session))
+        .route("/sessions/:session_id/stop", post(stop_session))
+        .route("/sessions/:session_id/cancel", post(cancel_response))
+        .route("/sessions/:session_id/messages", post(send_message))
+        .route("/sessions/:session_id/permissions/:request_id", post(respond_permission))
+        .route("/sessions/:session_id/replay", get(replay_session))
+        .route("/events", get(stream::events))
+        .route_layer(middleware::from_fn_with_state(context.clone(), require_token))
+        // Added after the auth layer, so it is left open
+        .route("/health", get(health));
+
+    Router::new().nest("/api", api).with_state(context)
+}
+
+/// A command error as an HTTP response
+struct ApiError(AppError);
+
+impl From<AppError> for ApiError {
+    fn from(error: AppError) -> Self {
+        Self(error)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (status_for(&self.0.code), Json(self.0)).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+fn status_for(code: &ErrorCode) -> StatusCode {
+    match code {
+        ErrorCode::NotFound
+        | ErrorCode::DatabaseNotFound
+        | ErrorCode::FileNotFound
+        | ErrorCode::DirectoryNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::InvalidInput => StatusCode::BAD_REQUEST,
+        ErrorCode::DatabaseConstraint | ErrorCode::FileAlreadyExists => StatusCode::CONFLICT,
+        ErrorCode::PermissionDenied | ErrorCode::FileAccessDenied => StatusCode::FORBIDDEN,
+        ErrorCode::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+        ErrorCode::Timeout | ErrorCode::ClaudeCliTimeout => StatusCode::GATEWAY_TIMEOUT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+async fn require_token(State(context): State<ApiContext>, request: Request, next: Next) -> Response {
+    let authorized = bearer_token(request.headers())
+        .or_else(|| query_token(request.uri().query()))
+        .is_some_and(|given| token_matches(&context.token, given));
+
+    if !authorized {
+        let error = AppError::new(ErrorCode::PermissionDenied, "Missing or invalid API token");
+        return (StatusCode::UNAUTHORIZED, Json(error)).into_response();
+    }
+    next.run(request).await
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+fn query_token(query: Option<&str>) -> Option<&str> {
+    query?.split('&').find_map(|pair| pair.strip_prefix("token="))
+}
+
+/// Compare tokens in time independent of where they differ
+fn token_matches(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn health() -> Json<Health> {
+    Json(Health {
+        name: "wingman".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    })
+}
+
+async fn list_projects(State(context): State<ApiContext>) -> ApiResult<Vec<ProjectResponse>> {
+    Ok(Json(commands::project_get_all(context.app.state()).await?))
+}
+
+async fn create_task(
+    State(context): State<ApiContext>,
+    Json(request): Json<TaskCreateRequest>,
+) -> ApiResult<TaskResponse> {
+    Ok(Json(commands::task_create(context.app.state(), request).await?))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionListQuery {
+    project_id: Option<String>,
+    limit: Option<i32>,
+    offset: Option<i32>,
+    include_archived: Option<bool>,
+}
+
+async fn list_sessions(
+    State(context): State<ApiContext>,
+    Query(query): Query<SessionListQuery>,
+) -> ApiResult<Vec<SessionSummaryResponse>> {
+    Ok(Json(
+        commands::session_list(
+            context.app.state(),
+            query.project_id,
+            query.limit,
+            query.offset,
+            query.include_archived,
+        )
+        .await?,
+    ))
+}
+
+async fn create_session(
+    State(context): State<ApiContext>,
+    Json(request): Json<SessionCreateRequest>,
+) -> ApiResult<SessionResponse> {
+    Ok(Json(commands::session_create(context.app.state(), request).await?))
+}
+
+async fn load_session(
+    State(context): State<ApiContext>,
+    Path(session_id): Path<String>,
+) -> ApiResult<SessionWithMessagesResponse> {
+    Ok(Json(commands::session_load(context.app.state(), session_id).await?))
+}
+
+async fn start_session(
+    State(context): State<ApiContext>,
+    Path(session_id): Path<String>,
+    request: Option<Json<StartSessionRequest>>,
+) -> Result<StatusCode, ApiError> {
+    let resume = request.and_then(|Json(request)| request.resume);
+    commands::session_start_cli(context.app.clone(), context.app.state(), session_id, resume).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn stop_session(
+    State(context): State<ApiContext>,
+    Path(session_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    commands::session_stop_cli(context.app.clone(), context.app.state(), session_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn cancel_response(
+    State(context): State<ApiContext>,
+    Path(session_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    commands::session_cancel_response(context.app.state(), session_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn send_message(
+    State(context): State<ApiContext>,
+    Path(session_id): Path<String>,
+    Json(request): Json<SendMessageRequest>,
+) -> ApiResult<MessageSent> {
+    let message_id = commands::session_send_message(
+        context.app.state(),
+        session_id,
+        request.content,
+        request.attachments,
+    )
+    .await?;
+    Ok(Json(MessageSent { message_id }))
+}
+
+async fn respond_permission(
+    State(context): State<ApiContext>,
+    Path((session_id, request_id)): Path<(String, String)>,
+    Json(request): Json<PermissionAnswer>,
+) -> Result<StatusCode, ApiError> {
+    commands::session_respond_permission(
+        context.app.state(),
+        session_id,
+        request_id,
+        request.allow,
+        request.message,
+    )
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Recent output, for catching up before streaming `/api/events`
+async fn replay_session(
+    State(context): State<ApiContext>,
+    Path(session_id): Path<String>,
+) -> ApiResult<Vec<ReplayChunk>> {
+    Ok(Json(commands::session_replay_recent(context.app.state(), session_id).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_matches() {
+        assert!(token_matches("abc123", "abc123"));
+        assert!(!token_matches("abc123", "abc124"));
+        assert!(!token_matches("abc123", "abc12"));
+        assert!(!token_matches("abc123", ""));
+    }
+
+    #[test]
+    fn test_request_tokens() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(bearer_token(&headers), None);
+        headers.insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+        assert_eq!(bearer_token(&headers), Some("secret"));
+        headers.insert(header::AUTHORIZATION, "Basic secret".parse().unwrap());
+        assert_eq!(bearer_token(&headers), None);
+
+        assert_eq!(query_token(Some("sessionId=s1&token=secret")), Some("secret"));
+        assert_eq!(query_token(Some("sessionId=s1")), None);
+        assert_eq!(query_token(None), None);
+    }
+
+    #[test]
+    fn test_status_for() {
+        assert_eq!(status_for(&ErrorCode::DatabaseNotFound), StatusCode::NOT_FOUND);
+        assert_eq!(status_for(&ErrorCode::InvalidInput), StatusCode::BAD_REQUEST);
+        assert_eq!(status_for(&ErrorCode::RateLimited), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(status_for(&ErrorCode::ClaudeCliError), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
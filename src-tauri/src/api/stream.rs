@@ -0,0 +1,132 @@
+//! Event Stream
+//!
+//! `GET /api/events` upgrades to a WebSocket that receives the app's
+//! session events as they are emitted, one JSON text message each:
+//! `{"event": "claude_output", "payload": {...}}`. Payloads are the same as
+//! the frontend receives. With a `sessionId` query parameter, only that
+//! session's events are sent, plus events that aren't about one session.
+//!
+//! A client that falls behind gets a `stream_lagged` event with the number
+//! of events it missed.
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::response::Response;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, EventId, Listener};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use super::types::{StreamEvent, STREAM_LAGGED_EVENT};
+use super::ApiContext;
+use crate::events::event_names;
+
+/// App events sent to WebSocket clients
+const STREAMED_EVENTS: &[&str] = &[
+    event_names::CLAUDE_OUTPUT,
+    event_names::CLAUDE_STATUS,
+    event_names::CLAUDE_ERROR,
+    event_names::CLAUDE_PERMISSION_REQUEST,
+    event_names::CLAUDE_QUEUE_STATUS,
+    event_names::SESSION_SAVED,
+];
+
+/// Listen for the streamed app events, passing them to `sender`
+pub(super) fn forward_events(app: &AppHandle, sender: &broadcast::Sender<StreamEvent>) -> Vec<EventId> {
+    STREAMED_EVENTS
+        .iter()
+        .map(|name| {
+            let sender = sender.clone();
+            let event = name.to_string();
+            app.listen_any(*name, move |emitted| {
+                let payload = serde_json::from_str(emitted.payload()).unwrap_or(Value::Null);
+                // Fails only when no client is connected
+                let _ = sender.send(StreamEvent {
+                    event: event.clone(),
+                    payload,
+                });
+            })
+        })
+        .collect()
+}
+
+/// Whether a client following `session_id` gets an event
+fn is_for(event: &StreamEvent, session_id: Option<&str>) -> bool {
+    match (session_id, event.payload.get("sessionId").and_then(Value::as_str)) {
+        (Some(wanted), Some(session)) => wanted == session,
+        _ => true,
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct EventsQuery {
+    session_id: Option<String>,
+}
+
+pub(super) async fn events(
+    State(context): State<ApiContext>,
+    Query(query): Query<EventsQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    let receiver = context.events.subscribe();
+    upgrade.on_upgrade(move |socket| send_events(socket, receiver, query.session_id))
+}
+
+async fn send_events(
+    mut socket: WebSocket,
+    mut receiver: broadcast::Receiver<StreamEvent>,
+    session_id: Option<String>,
+) {
+    loop {
+        tokio::select! {
+            received = receiver.recv() => {
+                let event = match received {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => StreamEvent {
+                        event: STREAM_LAGGED_EVENT.to_string(),
+                        payload: json!({ "skipped": skipped }),
+                    },
+                    // The server stopped
+                    Err(RecvError::Closed) => break,
+                };
+                if !is_for(&event, session_id.as_deref()) {
+                    continue;
+                }
+                let Ok(text) = serde_json::to_string(&event) else {
+                    continue;
+                };
+                if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            // Clients only send pings, which axum answers
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_for() {
+        let output = StreamEvent {
+            event: event_names::CLAUDE_OUTPUT.to_string(),
+            payload: json!({ "sessionId": "s1", "chunk": "hi" }),
+        };
+        let queue = StreamEvent {
+            event: event_names::CLAUDE_QUEUE_STATUS.to_string(),
+            payload: json!({ "running": 1, "queued": [] }),
+        };
+
+        assert!(is_for(&output, None));
+        assert!(is_for(&output, Some("s1")));
+        assert!(!is_for(&output, Some("s2")));
+        assert!(is_for(&queue, Some("s2")));
+    }
+}
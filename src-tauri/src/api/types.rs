@@ -0,0 +1,64 @@
+//! API Types
+//!
+//! Request and response bodies of the local HTTP API. The server and the
+//! `wingman-cli` binary both use these, so the two can't drift apart; the
+//! library exports them as `wingman_lib::api_types`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub use super::DEFAULT_PORT;
+pub use crate::commands::project::{ProjectResponse, TaskCreateRequest, TaskResponse};
+pub use crate::commands::session::{SessionCreateRequest, SessionResponse, SessionSummaryResponse};
+pub use crate::events::event_names;
+pub use crate::events::{ClaudeOutputPayload, ClaudePermissionRequestPayload, ClaudeStatusPayload};
+pub use crate::secrets::{API_SERVER_TOKEN, SERVICE as KEYCHAIN_SERVICE};
+
+/// `GET /api/health`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Health {
+    pub name: String,
+    pub version: String,
+}
+
+/// `POST /api/sessions/:id/start`; the body is optional
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StartSessionRequest {
+    /// Resume the session's previous conversation
+    pub resume: Option<bool>,
+}
+
+/// `POST /api/sessions/:id/messages`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SendMessageRequest {
+    pub content: String,
+    /// Paths of files to attach
+    pub attachments: Option<Vec<String>>,
+}
+
+/// Response to `POST /api/sessions/:id/messages`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageSent {
+    pub message_id: String,
+}
+
+/// `POST /api/sessions/:id/permissions/:request_id`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionAnswer {
+    pub allow: bool,
+    /// Passed on to Claude with a denial
+    pub message: Option<String>,
+}
+
+/// Event sent in place of the events a slow WebSocket client missed
+pub const STREAM_LAGGED_EVENT: &str = "stream_lagged";
+
+/// A WebSocket message from `GET /api/events`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamEvent {
+    /// App event name, e.g. `claude_output`
+    pub event: String,
+    /// The event's payload, as the frontend receives it
+    pub payload: Value,
+}
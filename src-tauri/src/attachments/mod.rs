@@ -0,0 +1,234 @@
+//! Attachments Module
+//!
+//! Copies files attached to a message into the app data directory, so they
+//! outlive the originals, and indexes them in the `message_attachments`
+//! table. The copies are referenced in the text sent to the CLI.
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+
+use crate::error::AppError;
+
+/// Most attachments accepted per message
+pub const MAX_ATTACHMENTS: usize = 10;
+
+/// Largest file accepted as an attachment
+const MAX_ATTACHMENT_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Extensions treated as images
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp"];
+
+/// A file attached to a message
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    pub id: String,
+    pub message_id: String,
+    pub file_name: String,
+    /// Where the file was attached from
+    pub original_path: String,
+    /// The copy kept in the app data directory
+    pub stored_path: String,
+    /// `image` or `file`
+    pub kind: String,
+    pub size_bytes: i64,
+    pub created_at: String,
+}
+
+/// Directory holding a session's attachments
+pub fn session_dir(data_dir: &Path, session_id: &str) -> PathBuf {
+    data_dir.join("attachments").join(session_id)
+}
+
+/// Check attachment paths before anything is stored
+///
+/// Each path must be an absolute path to a file within the size limit.
+pub async fn validate(paths: &[String]) -> Result<(), AppError> {
+    if paths.len() > MAX_ATTACHMENTS {
+        return Err(AppError::invalid_input(format!(
+            "A message can have at most {} attachments",
+            MAX_ATTACHMENTS
+        )));
+    }
+
+    for path in paths {
+        if !Path::new(path).is_absolute() {
+            return Err(AppError::invalid_input("Attachment paths must be absolute"));
+        }
+        let metadata = match tokio::fs::metadata(path).await {
+            Ok(metadata) if metadata.is_file() => metadata,
+            _ => return Err(AppError::file_not_found(path)),
+        };
+        if metadata.len() > MAX_ATTACHMENT_BYTES {
+            return Err(AppError::invalid_input(format!(
+                "Attachment {} exceeds {} MB",
+                path,
+                MAX_ATTACHMENT_BYTES / (1024 * 1024)
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Copy a message's attachments into the session's directory and record them
+///
+/// The message row must already exist. Paths should have passed [`validate`].
+pub async fn store(
+    db: &SqlitePool,
+    data_dir: &Path,
+    session_id: &str,
+    message_id: &str,
+    paths: &[String],
+) -> Result<Vec<Attachment>, AppError> {
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let dir = session_dir(data_dir, session_id).join(message_id);
+    tokio::fs::create_dir_all(&dir).await?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut attachments = Vec::with_capacity(paths.len());
+
+    for (index, path) in paths.iter().enumerate() {
+        let file_name = Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| format!("attachment-{}", index + 1));
+        // Prefix with the position so two files with the same name don't collide
+        let stored = dir.join(format!("{}-{}", index + 1, file_name));
+        let size_bytes = tokio::fs::copy(path, &stored).await? as i64;
+
+        let attachment = Attachment {
+            id: uuid::Uuid::new_v4().to_string(),
+            message_id: message_id.to_string(),
+            kind: if is_image(&file_name) { "image" } else { "file" }.to_string(),
+            file_name,
+            original_path: path.clone(),
+            stored_path: stored.to_string_lossy().to_string(),
+            size_bytes,
+            created_at: now.clone(),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO message_attachments (id, message_id, session_id, position, file_name, original_path, stored_path, kind, size_bytes, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&attachment.id)
+        .bind(message_id)
+        .bind(session_id)
+        .bind(index as i64)
+        .bind(&attachment.file_name)
+        .bind(&attachment.original_path)
+        .bind(&attachment.stored_path)
+        .bind(&attachment.kind)
+        .bind(attachment.size_bytes)
+        .bind(&attachment.created_at)
+        .execute(db)
+        .await?;
+
+        attachments.push(attachment);
+    }
+
+    Ok(attachments)
+}
+
+/// Get a message's attachments in the order they were attached
+pub async fn for_message(db: &SqlitePool, message_id: &str) -> Result<Vec<Attachment>, AppError> {
+    let rows = sqlx::query_as::<_, (String, String, String, String, String, String, i64, String)>(
+        r#"
+        SELECT id, message_id, file_name, original_path, stored_path, kind, size_bytes, created_at
+        FROM message_attachments
+        WHERE message_id = ?
+        ORDER BY position
+        "#,
+    )
+    .bind(message_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| Attachment {
+            id: row.0,
+            message_id: row.1,
+            file_name: row.2,
+            original_path: row.3,
+            stored_path: row.4,
+            kind: row.5,
+            size_bytes: row.6,
+            created_at: row.7,
+        })
+        .collect())
+}
+
+/// Append the CLI's `@path` file references for attachments to a message
+///
+/// Paths containing whitespace are quoted so the reference isn't cut short.
+pub fn with_references(content: &str, attachments: &[Attachment]) -> String {
+    if attachments.is_empty() {
+        return content.to_string();
+    }
+
+    let references: Vec<String> = attachments
+        .iter()
+        .map(|a| {
+            if a.stored_path.chars().any(char::is_whitespace) {
+                format!("@\"{}\"", a.stored_path)
+            } else {
+                format!("@{}", a.stored_path)
+            }
+        })
+        .collect();
+
+    format!("{}\n\n{}", content, references.join(" "))
+}
+
+fn is_image(file_name: &str) -> bool {
+    Path::new(file_name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attachment(stored_path: &str) -> Attachment {
+        Attachment {
+            id: "a".to_string(),
+            message_id: "m".to_string(),
+            file_name: "f".to_string(),
+            original_path: stored_path.to_string(),
+            stored_path: stored_path.to_string(),
+            kind: "image".to_string(),
+            size_bytes: 0,
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_is_image() {
+        assert!(is_image("screenshot.PNG"));
+        assert!(is_image("photo.jpeg"));
+        assert!(!is_image("notes.txt"));
+        assert!(!is_image("png"));
+    }
+
+    #[test]
+    fn test_with_references() {
+        assert_eq!(with_references("hi", &[]), "hi");
+        assert_eq!(
+            with_references(
+                "What's wrong here?",
+                &[attachment("/data/1-a.png"), attachment("/Application Support/2-b.png")]
+            ),
+            "What's wrong here?\n\n@/data/1-a.png @\"/Application Support/2-b.png\""
+        );
+    }
+}
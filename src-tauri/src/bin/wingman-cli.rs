@@ -0,0 +1,388 @@
+//! Wingman CLI
+//!
+//! Drives the running app from a shell through its local HTTP API, which
+//! has to be turned on in the app first. The API token is taken from
+//! `--token`, then `WINGMAN_API_TOKEN`, then the OS keychain entry the app
+//! keeps it in; the API's address from `--url`, then `WINGMAN_API_URL`.
+
+use std::io::Write;
+use std::process::ExitCode;
+
+use futures_util::StreamExt;
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio_tungstenite::tungstenite::Message;
+use wingman_lib::api_types::{
+    event_names, ClaudeOutputPayload, ClaudePermissionRequestPayload, ClaudeStatusPayload, MessageSent,
+    ProjectResponse, SendMessageRequest, SessionCreateRequest, SessionResponse, SessionSummaryResponse,
+    StartSessionRequest, StreamEvent, TaskCreateRequest, TaskResponse, API_SERVER_TOKEN, DEFAULT_PORT,
+    KEYCHAIN_SERVICE, STREAM_LAGGED_EVENT,
+};
+
+const USAGE: &str = "\
+Usage: wingman-cli [--url <url>] [--token <token>] <command>
+
+Commands:
+  projects                                   List projects
+  sessions [--project <id>]                  List sessions
+  task create <title> --project <id>         Create a task
+      [--sprint <id>] [--priority low|medium|high] [--description <text>]
+  session new --dir <path> [--project <id>] [--title <title>]
+                                             Create a session and start Claude in it
+  session start <session-id> [--resume]      Start Claude in a session
+  session stop <session-id>                  Stop Claude in a session
+  send <session-id> <message>                Send Claude a message
+  tail <session-id>                          Print Claude's output as it streams
+";
+
+/// Options that take no value
+const FLAGS: &[&str] = &["resume", "help"];
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    if args.flag("help") || args.positional.is_empty() {
+        print!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    match run(args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("wingman-cli: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(args: Args) -> Result<(), String> {
+    let client = Client::new(&args)?;
+    let command: Vec<&str> = args.positional.iter().map(String::as_str).collect();
+
+    match command.as_slice() {
+        ["projects"] => {
+            let projects: Vec<ProjectResponse> = client.request(Method::GET, "/projects", None::<&()>).await?;
+            for project in projects {
+                println!("{}\t{}\t{}", project.id, project.name, project.root_path);
+            }
+        }
+        ["sessions"] => {
+            let path = match args.option("project") {
+                Some(project_id) => format!("/sessions?projectId={}", project_id),
+                None => "/sessions".to_string(),
+            };
+            let sessions: Vec<SessionSummaryResponse> = client.request(Method::GET, &path, None::<&()>).await?;
+            for session in sessions {
+                println!("{}\t{}\t{} messages", session.id, session.title, session.message_count);
+            }
+        }
+        ["task", "create", title @ ..] if !title.is_empty() => {
+            let request = TaskCreateRequest {
+                project_id: args.required("project")?.to_string(),
+                sprint_id: args.option("sprint").map(str::to_string),
+                parent_task_id: None,
+                status_id: None,
+                title: title.join(" "),
+                description: args.option("description").map(str::to_string),
+                priority: args.option("priority").map(str::to_string),
+                estimated_hours: None,
+                due_date: None,
+            };
+            let task: TaskResponse = client.request(Method::POST, "/tasks", Some(&request)).await?;
+            println!("{}", task.id);
+        }
+        ["session", "new"] => {
+            let request = SessionCreateRequest {
+                working_directory: args.required("dir")?.to_string(),
+                project_id: args.option("project").map(str::to_string),
+                title: args.option("title").map(str::to_string),
+                model: None,
+                permission_mode: None,
+                extra_args: None,
+                system_prompt: None,
+                handoff_id: None,
+                task_id: None,
+            };
+            let session: SessionResponse = client.request(Method::POST, "/sessions", Some(&request)).await?;
+            client
+                .request_empty(Method::POST, &format!("/sessions/{}/start", session.id), Some(&StartSessionRequest::default()))
+                .await?;
+            println!("{}", session.id);
+        }
+        ["session", "start", session_id] => {
+            let request = StartSessionRequest {
+                resume: Some(args.flag("resume")),
+            };
+            client
+                .request_empty(Method::POST, &format!("/sessions/{}/start", session_id), Some(&request))
+                .await?;
+        }
+        ["session", "stop", session_id] => {
+            client
+                .request_empty(Method::POST, &format!("/sessions/{}/stop", session_id), None::<&()>)
+                .await?;
+        }
+        ["send", session_id, message @ ..] if !message.is_empty() => {
+            let request = SendMessageRequest {
+                content: message.join(" "),
+                attachments: None,
+            };
+            let sent: MessageSent = client
+                .request(Method::POST, &format!("/sessions/{}/messages", session_id), Some(&request))
+                .await?;
+            println!("{}", sent.message_id);
+        }
+        ["tail", session_id] => tail(&client, session_id).await?,
+        _ => return Err(format!("unknown command: {}\n\n{}", command.join(" "), USAGE)),
+    }
+
+    Ok(())
+}
+
+/// Print a session's streamed output until the app closes the stream
+async fn tail(client: &Client, session_id: &str) -> Result<(), String> {
+    let url = events_url(&client.base_url, session_id, &client.token);
+    let (mut socket, _) = tokio_tungstenite::connect_async(url.as_str())
+        .await
+        .map_err(|e| format!("could not connect to {}: {}", client.base_url, e))?;
+
+    while let Some(message) = socket.next().await {
+        let text = match message.map_err(|e| e.to_string())? {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let Ok(event) = serde_json::from_str::<StreamEvent>(&text) else {
+            continue;
+        };
+        print_event(event);
+    }
+
+    Ok(())
+}
+
+fn print_event(event: StreamEvent) {
+    match event.event.as_str() {
+        event_names::CLAUDE_OUTPUT => {
+            if let Ok(output) = serde_json::from_value::<ClaudeOutputPayload>(event.payload) {
+                print!("{}", output.chunk);
+                if output.is_complete {
+                    println!();
+                }
+                let _ = std::io::stdout().flush();
+            }
+        }
+        event_names::CLAUDE_STATUS => {
+            if let Ok(status) = serde_json::from_value::<ClaudeStatusPayload>(event.payload) {
+                match status.error {
+                    Some(error) => eprintln!("[{}: {}]", status.status, error),
+                    None => eprintln!("[{}]", status.status),
+                }
+            }
+        }
+        event_names::CLAUDE_PERMISSION_REQUEST => {
+            if let Ok(request) = serde_json::from_value::<ClaudePermissionRequestPayload>(event.payload) {
+                eprintln!("[permission requested for {} ({})]", request.tool_name, request.request_id);
+            }
+        }
+        event_names::CLAUDE_ERROR => {
+            eprintln!("[error: {}]", event.payload["error"].as_str().unwrap_or("unknown"));
+        }
+        STREAM_LAGGED_EVENT => {
+            eprintln!("[missed {} events]", event.payload["skipped"]);
+        }
+        _ => {}
+    }
+}
+
+/// WebSocket URL of a session's event stream
+fn events_url(base_url: &str, session_id: &str, token: &str) -> String {
+    let base = if let Some(rest) = base_url.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = base_url.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        base_url.to_string()
+    };
+    format!("{}/events?sessionId={}&token={}", base, session_id, token)
+}
+
+struct Client {
+    http: reqwest::Client,
+    base_url: String,
+    token: String,
+}
+
+impl Client {
+    fn new(args: &Args) -> Result<Self, String> {
+        let base_url = args
+            .option("url")
+            .map(str::to_string)
+            .or_else(|| std::env::var("WINGMAN_API_URL").ok())
+            .unwrap_or_else(|| format!("http://127.0.0.1:{}/api", DEFAULT_PORT))
+            .trim_end_matches('/')
+            .to_string();
+
+        let token = match args
+            .option("token")
+            .map(str::to_string)
+            .or_else(|| std::env::var("WINGMAN_API_TOKEN").ok())
+            .filter(|token| !token.is_empty())
+        {
+            Some(token) => token,
+            None => keyring::Entry::new(KEYCHAIN_SERVICE, API_SERVER_TOKEN)
+                .and_then(|entry| entry.get_password())
+                .map_err(|e| format!(
+                    "no API token; set WINGMAN_API_TOKEN or turn on the API server in Wingman ({})",
+                    e
+                ))?,
+        };
+
+        Ok(Self {
+            http: reqwest::Client::new(),
+            base_url,
+            token,
+        })
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&impl Serialize>,
+    ) -> Result<T, String> {
+        let response = self.send(method, path, body).await?;
+        response.json().await.map_err(|e| format!("unexpected response: {}", e))
+    }
+
+    async fn request_empty(&self, method: Method, path: &str, body: Option<&impl Serialize>) -> Result<(), String> {
+        self.send(method, path, body).await.map(|_| ())
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&impl Serialize>,
+    ) -> Result<reqwest::Response, String> {
+        let mut request = self
+            .http
+            .request(method, format!("{}{}", self.base_url, path))
+            .bearer_auth(&self.token);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+
+        let response = request.send().await.map_err(|e| {
+            if e.is_connect() {
+                format!("could not reach Wingman at {}; is the API server on?", self.base_url)
+            } else {
+                e.to_string()
+            }
+        })?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        // Errors carry the app's error as JSON
+        let message = response
+            .json::<serde_json::Value>()
+            .await
+            .ok()
+            .and_then(|error| error["message"].as_str().map(str::to_string));
+        Err(match (status, message) {
+            (StatusCode::UNAUTHORIZED, _) => "the API token was refused".to_string(),
+            (_, Some(message)) => message,
+            (status, None) => format!("request failed with {}", status),
+        })
+    }
+}
+
+/// Command-line arguments: positional words, `--name value` options, and flags
+#[derive(Debug, Default)]
+struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, String)>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                parsed.positional.push(arg);
+                continue;
+            };
+            if FLAGS.contains(&name) {
+                parsed.flags.push(name.to_string());
+                continue;
+            }
+            let value = args.next().ok_or_else(|| format!("--{} needs a value", name))?;
+            parsed.options.push((name.to_string(), value));
+        }
+        Ok(parsed)
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(option, _)| option == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn required(&self, name: &str) -> Result<&str, String> {
+        self.option(name).ok_or_else(|| format!("--{} is required", name))
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(words: &[&str]) -> Result<Args, String> {
+        Args::parse(words.iter().map(|word| word.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = args(&["task", "create", "Fix", "login", "--project", "p1", "--priority", "high"]).unwrap();
+        assert_eq!(parsed.positional, ["task", "create", "Fix", "login"]);
+        assert_eq!(parsed.option("project"), Some("p1"));
+        assert_eq!(parsed.option("priority"), Some("high"));
+        assert_eq!(parsed.option("sprint"), None);
+        assert!(parsed.required("sprint").is_err());
+
+        let parsed = args(&["session", "start", "s1", "--resume"]).unwrap();
+        assert_eq!(parsed.positional, ["session", "start", "s1"]);
+        assert!(parsed.flag("resume"));
+
+        assert!(args(&["sessions", "--project"]).is_err());
+    }
+
+    #[test]
+    fn test_events_url() {
+        assert_eq!(
+            events_url("http://127.0.0.1:7878/api", "s1", "t"),
+            "ws://127.0.0.1:7878/api/events?sessionId=s1&token=t"
+        );
+        assert_eq!(
+            events_url("https://example.test/api", "s1", "t"),
+            "wss://example.test/api/events?sessionId=s1&token=t"
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//! Checkpoints Module
+//!
+//! Snapshots files before Claude writes them so a bad run can be undone.
+//! Snapshots are stored under the app data directory and indexed in the
+//! `checkpoints` table.
+
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+
+use crate::error::AppError;
+use crate::paths::PathNormalizer;
+
+/// Tools that modify files, and the input field holding the target path
+const WRITE_TOOLS: &[(&str, &str)] = &[
+    ("Write", "file_path"),
+    ("Edit", "file_path"),
+    ("MultiEdit", "file_path"),
+    ("NotebookEdit", "notebook_path"),
+];
+
+/// Files larger than this are not snapshotted
+const MAX_SNAPSHOT_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Tool name recorded for snapshots taken just before a restore
+pub const RESTORE_TOOL: &str = "restore";
+
+/// Get the file a write tool use is about to modify
+pub fn target_path(tool: &str, input: &Value) -> Option<String> {
+    let (_, field) = WRITE_TOOLS.iter().find(|(name, _)| *name == tool)?;
+    input
+        .get(*field)
+        .and_then(|p| p.as_str())
+        .filter(|p| !p.is_empty())
+        .map(|p| p.to_string())
+}
+
+/// Directory holding a session's snapshots
+pub fn session_dir(data_dir: &Path, session_id: &str) -> PathBuf {
+    data_dir.join("checkpoints").join(session_id)
+}
+
+/// Snapshot a file's current content before it is modified
+///
+/// Relative paths are resolved against the session's working directory. A
+/// file that doesn't exist yet is recorded without a snapshot, so restoring
+/// removes it. Returns the new checkpoint ID, or `None` when nothing was
+/// recorded because the file is unchanged since the last snapshot or too large.
+pub async fn capture(
+    db: &SqlitePool,
+    data_dir: &Path,
+    session_id: &str,
+    file_path: &str,
+    tool_name: &str,
+) -> Result<Option<String>, AppError> {
+    let working_directory: String = sqlx::query_scalar("SELECT working_directory FROM sessions WHERE id = ?")
+        .bind(session_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Session", session_id))?;
+
+    let path = Path::new(working_directory.as_str()).join(file_path);
+    let file_path = path.to_string_lossy().to_string();
+
+    let content = match tokio::fs::metadata(&path).await {
+        Ok(metadata) if metadata.len() > MAX_SNAPSHOT_BYTES => {
+            log::warn!("Not snapshotting {}: file exceeds {} bytes", file_path, MAX_SNAPSHOT_BYTES);
+            return Ok(None);
+        }
+        Ok(metadata) if metadata.is_file() => Some(tokio::fs::read(&path).await?),
+        Ok(_) => return Ok(None),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    // Consecutive writes often see the same content; one snapshot is enough
+    let latest: Option<Option<String>> = sqlx::query_scalar(
+        r#"
+        SELECT snapshot_path FROM checkpoints
+        WHERE session_id = ? AND file_path = ?
+        ORDER BY created_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(session_id)
+    .bind(&file_path)
+    .fetch_optional(db)
+    .await?;
+
+    if let Some(latest) = latest {
+        let latest_content = match latest {
+            Some(snapshot) => tokio::fs::read(snapshot).await.ok(),
+            None => None,
+        };
+        if latest_content == content {
+            return Ok(None);
+        }
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let snapshot_path = match &content {
+        Some(bytes) => {
+            let dir = session_dir(data_dir, session_id);
+            tokio::fs::create_dir_all(&dir).await?;
+            let snapshot = dir.join(&id);
+            tokio::fs::write(&snapshot, bytes).await?;
+            Some(snapshot.to_string_lossy().to_string())
+        }
+        None => None,
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO checkpoints (id, session_id, file_path, snapshot_path, tool_name, created_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(session_id)
+    .bind(&file_path)
+    .bind(&snapshot_path)
+    .bind(tool_name)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(db)
+    .await?;
+
+    Ok(Some(id))
+}
+
+/// Restore a file to the content captured by a checkpoint
+///
+/// The file's current content is snapshotted first, so a restore can itself
+/// be undone. Returns the restored file's path.
+pub async fn restore(db: &SqlitePool, data_dir: &Path, checkpoint_id: &str) -> Result<String, AppError> {
+    let (session_id, file_path, snapshot_path): (String, String, Option<String>) = sqlx::query_as(
+        "SELECT session_id, file_path, snapshot_path FROM checkpoints WHERE id = ?",
+    )
+    .bind(checkpoint_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| AppError::database_not_found("Checkpoint", checkpoint_id))?;
+
+    capture(db, data_dir, &session_id, &file_path, RESTORE_TOOL).await?;
+
+    match snapshot_path {
+        Some(snapshot) => {
+            let content = tokio::fs::read(&snapshot)
+                .await
+                .map_err(|_| AppError::file_not_found(snapshot.clone()))?;
+            if let Some(parent) = Path::new(&file_path).parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(&file_path, content).await?;
+        }
+        // The file didn't exist before Claude created it
+        None => match tokio::fs::remove_file(&file_path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        },
+    }
+
+    sqlx::query("UPDATE checkpoints SET restored_at = ? WHERE id = ?")
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(checkpoint_id)
+        .execute(db)
+        .await?;
+
+    Ok(file_path)
+}
+
+/// A captured version of a file
+pub struct FileVersion {
+    pub checkpoint_id: String,
+    pub session_id: String,
+    pub file_path: String,
+    pub snapshot_path: Option<String>,
+    pub tool_name: String,
+    pub created_at: String,
+}
+
+/// List the captured versions of a file, newest first
+///
+/// `path` must be absolute. Checkpoints recorded under a different spelling
+/// of the same path (e.g. through a symlink) are included.
+pub async fn file_versions(
+    db: &SqlitePool,
+    path: &Path,
+    session_id: Option<&str>,
+) -> Result<Vec<FileVersion>, AppError> {
+    let Some(file_name) = path.file_name() else {
+        return Ok(Vec::new());
+    };
+
+    // Narrow by file name in SQL, then compare canonical paths
+    let pattern = format!("%{}", file_name.to_string_lossy());
+    let rows = sqlx::query_as::<_, (String, String, String, Option<String>, String, String)>(
+        r#"
+        SELECT id, session_id, file_path, snapshot_path, tool_name, created_at
+        FROM checkpoints
+        WHERE file_path LIKE ? AND (? IS NULL OR session_id = ?)
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(&pattern)
+    .bind(session_id)
+    .bind(session_id)
+    .fetch_all(db)
+    .await?;
+
+    let normalizer = PathNormalizer::new(path.parent().unwrap_or(path));
+    let target = normalizer.absolute(path);
+
+    Ok(rows
+        .into_iter()
+        .filter(|row| normalizer.absolute(&row.2) == target)
+        .map(|row| FileVersion {
+            checkpoint_id: row.0,
+            session_id: row.1,
+            file_path: row.2,
+            snapshot_path: row.3,
+            tool_name: row.4,
+            created_at: row.5,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_path() {
+        let input = serde_json::json!({"file_path": "/repo/src/main.rs", "content": "fn main() {}"});
+        assert_eq!(target_path("Write", &input).as_deref(), Some("/repo/src/main.rs"));
+        assert_eq!(target_path("Read", &input), None);
+
+        let notebook = serde_json::json!({"notebook_path": "analysis.ipynb"});
+        assert_eq!(target_path("NotebookEdit", &notebook).as_deref(), Some("analysis.ipynb"));
+    }
+}
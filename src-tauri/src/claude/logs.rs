@@ -0,0 +1,97 @@
+//! CLI Stderr Logs
+//!
+//! Keeps the most recent stderr output of each session's CLI process, across
+//! restarts and after the process exits, so auth failures and crashes can be
+//! inspected. Each session's log is capped by size.
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Stderr bytes kept per session
+const MAX_LOG_BYTES: usize = 64 * 1024;
+
+/// A line the CLI wrote to stderr
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliLogLine {
+    pub timestamp: String,
+    pub line: String,
+}
+
+#[derive(Default)]
+struct SessionLog {
+    bytes: usize,
+    lines: VecDeque<CliLogLine>,
+}
+
+/// Per-session stderr logs
+#[derive(Clone, Default)]
+pub struct CliLogs {
+    logs: Arc<Mutex<HashMap<String, SessionLog>>>,
+}
+
+impl CliLogs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a stderr line, dropping the oldest lines past the size cap
+    pub fn record(&self, session_id: &str, line: &str) {
+        self.record_capped(session_id, line, MAX_LOG_BYTES);
+    }
+
+    fn record_capped(&self, session_id: &str, line: &str, max_bytes: usize) {
+        let mut logs = self.logs.lock().unwrap_or_else(|e| e.into_inner());
+        let log = logs.entry(session_id.to_string()).or_default();
+
+        log.bytes += line.len();
+        log.lines.push_back(CliLogLine {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            line: line.to_string(),
+        });
+        // Always keep the newest line, even if it alone is over the cap
+        while log.bytes > max_bytes && log.lines.len() > 1 {
+            if let Some(dropped) = log.lines.pop_front() {
+                log.bytes -= dropped.line.len();
+            }
+        }
+    }
+
+    /// Get a session's logged lines, oldest first
+    pub fn lines(&self, session_id: &str) -> Vec<CliLogLine> {
+        let logs = self.logs.lock().unwrap_or_else(|e| e.into_inner());
+        logs.get(session_id)
+            .map(|log| log.lines.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Drop a session's log
+    pub fn clear(&self, session_id: &str) {
+        self.logs.lock().unwrap_or_else(|e| e.into_inner()).remove(session_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_size_cap() {
+        let logs = CliLogs::new();
+        for line in ["aaaa", "bbbb", "cccc"] {
+            logs.record_capped("s1", line, 10);
+        }
+        logs.record("s2", "other");
+
+        let lines: Vec<String> = logs.lines("s1").into_iter().map(|l| l.line).collect();
+        assert_eq!(lines, vec!["bbbb", "cccc"]);
+
+        logs.record_capped("s1", &"x".repeat(20), 10);
+        assert_eq!(logs.lines("s1").len(), 1);
+
+        logs.clear("s1");
+        assert!(logs.lines("s1").is_empty());
+        assert_eq!(logs.lines("s2").len(), 1);
+    }
+}
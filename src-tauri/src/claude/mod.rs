@@ -2,7 +2,11 @@
 //!
 //! Handles spawning and communicating with the Claude CLI.
 
+pub mod logs;
+pub mod oneshot;
 mod parser;
 mod process;
+pub mod replay;
 
-pub use process::CliManager;
+pub use parser::ClaudeTodo;
+pub use process::{CliManager, CliOptions, CliProcessInfo};
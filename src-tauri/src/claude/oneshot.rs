@@ -0,0 +1,84 @@
+//! One-shot Claude CLI Prompts
+//!
+//! Runs a single prompt through the CLI outside of any interactive session,
+//! for background jobs like summarization.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::error::{AppError, ErrorCode};
+use crate::system;
+
+/// Maximum time a one-shot prompt may run
+const ONESHOT_TIMEOUT_SECS: u64 = 180;
+
+/// Run a prompt in print mode and return the CLI's text output
+pub async fn run_oneshot(
+    claude_path: &Path,
+    working_dir: &Path,
+    prompt: &str,
+    model: Option<&str>,
+) -> Result<String, AppError> {
+    let mut cmd = Command::new(claude_path);
+    system::env::apply(&mut cmd);
+    cmd.arg("--print").arg("--output-format").arg("text");
+    if let Some(model) = model {
+        cmd.arg("--model").arg(model);
+    }
+    cmd.current_dir(working_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| AppError::claude_cli_error(format!("Failed to spawn CLI: {}", e)))?;
+
+    // Send the prompt and close stdin so the CLI knows the input is complete
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(prompt.as_bytes())
+            .await
+            .map_err(|e| AppError::claude_cli_error(format!("Failed to write prompt: {}", e)))?;
+    }
+
+    let output = tokio::time::timeout(Duration::from_secs(ONESHOT_TIMEOUT_SECS), child.wait_with_output())
+        .await
+        .map_err(|_| AppError::new(ErrorCode::ClaudeCliTimeout, "Claude CLI did not respond in time"))?
+        .map_err(|e| AppError::claude_cli_error(format!("Failed to read CLI output: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(AppError::with_details(
+            ErrorCode::ClaudeCliError,
+            "Claude CLI prompt failed",
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Extract the first JSON object from model output that may include prose
+/// or code fences around it
+pub fn extract_json_object(text: &str) -> Option<&str> {
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    (end > start).then(|| &text[start..=end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_json_object() {
+        let text = "Here you go:\n```json\n{\"goal\": \"ship it\"}\n```";
+        assert_eq!(extract_json_object(text), Some("{\"goal\": \"ship it\"}"));
+        assert_eq!(extract_json_object("no json here"), None);
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Parses the NDJSON output from Claude CLI with --print flag.
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::error::AppError;
@@ -14,18 +14,70 @@ pub enum ClaudeEvent {
     Assistant { message_id: Option<String> },
     /// Text delta (streaming content)
     TextDelta { text: String },
-    /// Tool use event
-    ToolUse { name: String, input: Value },
+    /// Tool use event, with the ID its result refers back to
+    ToolUse { id: Option<String>, name: String, input: Value },
     /// Tool result event
-    ToolResult { tool_use_id: String, content: String },
+    ToolResult { tool_use_id: String, content: String, is_error: bool },
     /// Message complete
     MessageStop,
+    /// CLI session initialized, with the ID `--resume` accepts
+    SessionInit { cli_session_id: String },
+    /// Final result of a turn, with the turn's cost when the CLI reports it
+    Result { is_error: bool, message: Option<String>, cost_usd: Option<f64> },
+    /// The CLI asks whether a tool may run, and waits for a reply on stdin
+    PermissionRequest { request_id: String, tool_name: String, input: Value },
     /// Error event
     Error { message: String },
     /// Unknown/ignored event
     Unknown,
 }
 
+/// Output fragments the CLI prints when a `--resume` ID is no longer valid
+const RESUME_FAILURE_PATTERNS: &[&str] = &[
+    "no conversation found",
+    "session not found",
+    "invalid session id",
+    "could not resume",
+];
+
+/// Output fragments the CLI prints when it isn't signed in or the login expired
+const AUTH_REQUIRED_PATTERNS: &[&str] = &[
+    "please run /login",
+    "invalid api key",
+    "oauth token has expired",
+    "authentication_error",
+    "not logged in",
+    "login required",
+];
+
+/// Stderr fragments that mean the CLI can't carry on without intervention
+const FATAL_STDERR_PATTERNS: &[&str] = &[
+    "fatal",
+    "panic",
+    "uncaught exception",
+    "unhandled promise rejection",
+    "invalid api key",
+    "authentication",
+    "unauthorized",
+    "please run /login",
+    "command not found",
+    "cannot find module",
+    "out of memory",
+];
+
+/// Name of the CLI's built-in todo list tool
+const TODO_WRITE_TOOL: &str = "TodoWrite";
+
+/// Entry in the CLI's todo list, as written by the TodoWrite tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeTodo {
+    pub content: String,
+    /// `pending`, `in_progress`, or `completed`
+    pub status: String,
+    pub active_form: Option<String>,
+}
+
 /// Raw event from Claude CLI
 #[derive(Debug, Deserialize)]
 struct RawEvent {
@@ -80,8 +132,12 @@ pub fn parse_claude_output(line: &str) -> Result<ClaudeEvent, AppError> {
                 .and_then(|n| n.as_str())
                 .unwrap_or("unknown")
                 .to_string();
+            let id = raw.data
+                .get("id")
+                .and_then(|id| id.as_str())
+                .map(|s| s.to_string());
             let input = raw.data.get("input").cloned().unwrap_or(Value::Null);
-            Ok(ClaudeEvent::ToolUse { name, input })
+            Ok(ClaudeEvent::ToolUse { id, name, input })
         }
 
         "tool_result" => {
@@ -96,7 +152,11 @@ pub fn parse_claude_output(line: &str) -> Result<ClaudeEvent, AppError> {
                 .and_then(|c| c.as_str())
                 .unwrap_or("")
                 .to_string();
-            Ok(ClaudeEvent::ToolResult { tool_use_id, content })
+            let is_error = raw.data
+                .get("is_error")
+                .and_then(|e| e.as_bool())
+                .unwrap_or(false);
+            Ok(ClaudeEvent::ToolResult { tool_use_id, content, is_error })
         }
 
         "message_stop" => {
@@ -125,6 +185,52 @@ pub fn parse_claude_output(line: &str) -> Result<ClaudeEvent, AppError> {
             Ok(ClaudeEvent::Error { message })
         }
 
+        "system" => {
+            // Session metadata; only the init event carries the session ID
+            let is_init = raw.data.get("subtype").and_then(|s| s.as_str()) == Some("init");
+            match raw.data.get("session_id").and_then(|id| id.as_str()) {
+                Some(id) if is_init => Ok(ClaudeEvent::SessionInit {
+                    cli_session_id: id.to_string(),
+                }),
+                _ => Ok(ClaudeEvent::Unknown),
+            }
+        }
+
+        "result" => {
+            // Turn result
+            let is_error = raw.data
+                .get("is_error")
+                .and_then(|e| e.as_bool())
+                .unwrap_or(false);
+            let message = raw.data
+                .get("result")
+                .and_then(|r| r.as_str())
+                .map(|s| s.to_string());
+            let cost_usd = raw.data
+                .get("total_cost_usd")
+                .and_then(|c| c.as_f64());
+            Ok(ClaudeEvent::Result { is_error, message, cost_usd })
+        }
+
+        "control_request" => {
+            // Only tool permission checks need an answer from the user
+            let request = raw.data.get("request");
+            let subtype = request.and_then(|r| r.get("subtype")).and_then(|s| s.as_str());
+            let request_id = raw.data.get("request_id").and_then(|id| id.as_str());
+            match (subtype, request_id, request) {
+                (Some("can_use_tool"), Some(request_id), Some(request)) => Ok(ClaudeEvent::PermissionRequest {
+                    request_id: request_id.to_string(),
+                    tool_name: request
+                        .get("tool_name")
+                        .and_then(|n| n.as_str())
+                        .unwrap_or("unknown")
+                        .to_string(),
+                    input: request.get("input").cloned().unwrap_or(Value::Null),
+                }),
+                _ => Ok(ClaudeEvent::Unknown),
+            }
+        }
+
         "ping" => {
             // Keep-alive ping - ignore
             Ok(ClaudeEvent::Unknown)
@@ -138,6 +244,76 @@ pub fn parse_claude_output(line: &str) -> Result<ClaudeEvent, AppError> {
     }
 }
 
+/// Build the stdin line sending a user message, for `--input-format stream-json`
+pub fn user_message(content: &str) -> String {
+    serde_json::json!({
+        "type": "user",
+        "message": { "role": "user", "content": content },
+    })
+    .to_string()
+}
+
+/// Build the stdin line answering a permission request
+///
+/// An allowed tool runs with the input it was requested with; a denied one
+/// is reported back to Claude with `message`.
+pub fn permission_response(request_id: &str, allow: bool, input: &Value, message: Option<&str>) -> String {
+    let decision = if allow {
+        serde_json::json!({ "behavior": "allow", "updatedInput": input })
+    } else {
+        serde_json::json!({
+            "behavior": "deny",
+            "message": message.unwrap_or("The user denied this tool use"),
+        })
+    };
+
+    serde_json::json!({
+        "type": "control_response",
+        "response": {
+            "subtype": "success",
+            "request_id": request_id,
+            "response": decision,
+        },
+    })
+    .to_string()
+}
+
+/// Whether CLI output reports that a `--resume` session ID is invalid
+pub fn is_resume_failure(text: &str) -> bool {
+    let text = text.to_lowercase();
+    RESUME_FAILURE_PATTERNS.iter().any(|pattern| text.contains(pattern))
+}
+
+/// Whether CLI output reports that the user needs to log in again
+pub fn is_auth_required(text: &str) -> bool {
+    let text = text.to_lowercase();
+    AUTH_REQUIRED_PATTERNS.iter().any(|pattern| text.contains(pattern))
+}
+
+/// Whether a stderr line reports a failure the user needs to see
+pub fn is_fatal_stderr(line: &str) -> bool {
+    let line = line.to_lowercase();
+    FATAL_STDERR_PATTERNS.iter().any(|pattern| line.contains(pattern))
+}
+
+/// Extract the todo list from a TodoWrite tool use
+///
+/// Returns `None` for other tools or input that doesn't match the expected shape.
+pub fn parse_todo_write(name: &str, input: &Value) -> Option<Vec<ClaudeTodo>> {
+    if name != TODO_WRITE_TOOL {
+        return None;
+    }
+
+    let todos: Vec<ClaudeTodo> = serde_json::from_value(input.get("todos")?.clone()).ok()?;
+
+    Some(
+        todos
+            .into_iter()
+            .filter(|todo| !todo.content.trim().is_empty())
+            .collect(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -164,7 +340,8 @@ mod tests {
         let line = r#"{"type":"tool_use","id":"123","name":"write_file","input":{"path":"test.txt"}}"#;
         let result = parse_claude_output(line).unwrap();
         match result {
-            ClaudeEvent::ToolUse { name, input } => {
+            ClaudeEvent::ToolUse { id, name, input } => {
+                assert_eq!(id.as_deref(), Some("123"));
                 assert_eq!(name, "write_file");
                 assert_eq!(input.get("path").and_then(|p| p.as_str()), Some("test.txt"));
             }
@@ -172,6 +349,118 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_tool_result() {
+        let line = r#"{"type":"tool_result","tool_use_id":"123","content":"No such file","is_error":true}"#;
+        match parse_claude_output(line).unwrap() {
+            ClaudeEvent::ToolResult { tool_use_id, content, is_error } => {
+                assert_eq!(tool_use_id, "123");
+                assert_eq!(content, "No such file");
+                assert!(is_error);
+            }
+            _ => panic!("Expected ToolResult"),
+        }
+    }
+
+    #[test]
+    fn test_parse_session_init() {
+        let line = r#"{"type":"system","subtype":"init","session_id":"abc-123"}"#;
+        match parse_claude_output(line).unwrap() {
+            ClaudeEvent::SessionInit { cli_session_id } => assert_eq!(cli_session_id, "abc-123"),
+            _ => panic!("Expected SessionInit"),
+        }
+    }
+
+    #[test]
+    fn test_parse_permission_request() {
+        let line = r#"{"type":"control_request","request_id":"req-1","request":{"subtype":"can_use_tool","tool_name":"Bash","input":{"command":"rm -rf build"}}}"#;
+        match parse_claude_output(line).unwrap() {
+            ClaudeEvent::PermissionRequest { request_id, tool_name, input } => {
+                assert_eq!(request_id, "req-1");
+                assert_eq!(tool_name, "Bash");
+                assert_eq!(input["command"], "rm -rf build");
+            }
+            _ => panic!("Expected PermissionRequest"),
+        }
+
+        let line = r#"{"type":"control_request","request_id":"req-2","request":{"subtype":"interrupt"}}"#;
+        assert!(matches!(parse_claude_output(line).unwrap(), ClaudeEvent::Unknown));
+    }
+
+    #[test]
+    fn test_user_message() {
+        let line: Value = serde_json::from_str(&user_message("Fix the \"login\" bug\nplease")).unwrap();
+        assert_eq!(line["type"], "user");
+        assert_eq!(line["message"]["role"], "user");
+        assert_eq!(line["message"]["content"], "Fix the \"login\" bug\nplease");
+    }
+
+    #[test]
+    fn test_permission_response() {
+        let input = serde_json::json!({"command": "ls"});
+        let allow: Value = serde_json::from_str(&permission_response("req-1", true, &input, None)).unwrap();
+        assert_eq!(allow["response"]["request_id"], "req-1");
+        assert_eq!(allow["response"]["response"]["behavior"], "allow");
+        assert_eq!(allow["response"]["response"]["updatedInput"], input);
+
+        let deny: Value = serde_json::from_str(&permission_response("req-1", false, &input, Some("Not now"))).unwrap();
+        assert_eq!(deny["response"]["response"]["behavior"], "deny");
+        assert_eq!(deny["response"]["response"]["message"], "Not now");
+    }
+
+    #[test]
+    fn test_parse_result_cost() {
+        let line = r#"{"type":"result","subtype":"success","is_error":false,"result":"Done","total_cost_usd":0.0421}"#;
+        match parse_claude_output(line).unwrap() {
+            ClaudeEvent::Result { is_error, message, cost_usd } => {
+                assert!(!is_error);
+                assert_eq!(message.as_deref(), Some("Done"));
+                assert_eq!(cost_usd, Some(0.0421));
+            }
+            _ => panic!("Expected Result"),
+        }
+    }
+
+    #[test]
+    fn test_is_resume_failure() {
+        assert!(is_resume_failure("No conversation found with session ID: abc-123"));
+        assert!(!is_resume_failure("Error: rate limited"));
+    }
+
+    #[test]
+    fn test_is_auth_required() {
+        assert!(is_auth_required("Invalid API key · Please run /login"));
+        assert!(is_auth_required("API Error: 401 {\"type\":\"error\",\"error\":{\"type\":\"authentication_error\"}}"));
+        assert!(!is_auth_required("API Error: 529 overloaded"));
+    }
+
+    #[test]
+    fn test_is_fatal_stderr() {
+        assert!(is_fatal_stderr("Invalid API key · Please run /login"));
+        assert!(is_fatal_stderr("Error: Cannot find module '@anthropic-ai/claude-code'"));
+        assert!(!is_fatal_stderr("(node:1234) ExperimentalWarning: Fetch API is experimental"));
+    }
+
+    #[test]
+    fn test_parse_todo_write() {
+        let input = serde_json::json!({
+            "todos": [
+                {"content": "Add login form", "status": "completed", "activeForm": "Adding login form"},
+                {"content": "Wire up API", "status": "in_progress", "activeForm": "Wiring up API"},
+                {"content": " ", "status": "pending"}
+            ]
+        });
+
+        let todos = parse_todo_write("TodoWrite", &input).unwrap();
+        assert_eq!(todos.len(), 2);
+        assert_eq!(todos[0].content, "Add login form");
+        assert_eq!(todos[1].status, "in_progress");
+        assert_eq!(todos[1].active_form.as_deref(), Some("Wiring up API"));
+
+        assert!(parse_todo_write("Write", &input).is_none());
+        assert!(parse_todo_write("TodoWrite", &serde_json::json!({})).is_none());
+    }
+
     #[test]
     fn test_parse_error() {
         let line = r#"{"type":"error","error":{"message":"Rate limited"}}"#;
@@ -2,32 +2,167 @@
 //!
 //! Handles spawning, communicating with, and terminating Claude CLI processes.
 
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
-use tauri::AppHandle;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::process::{Child, Command};
-use tokio::sync::RwLock;
+use tokio::process::{Child, ChildStderr, Command};
+use tokio::sync::{watch, Mutex, RwLock};
 
-use crate::error::AppError;
-use crate::events::{emit_event, event_names, ClaudeOutputPayload, ClaudeStatusPayload};
-use crate::state::ClaudeStatus;
+use crate::error::{AppError, ErrorCode};
+use crate::events::{
+    emit_event, event_names, ClaudeOutputPayload, ClaudePermissionRequestPayload, ClaudeQueueStatusPayload,
+    ClaudeStatusPayload,
+};
+use crate::state::{AppState, ClaudeStatus};
 
-use super::parser::parse_claude_output;
+use crate::checkpoints;
+use crate::hooks::{self, HookContext, HookEvent};
+use crate::secrets;
+use crate::webhooks::{self, WebhookContext, WebhookEvent};
+use crate::system;
+use crate::commands::autocommit::autocommit_turn;
+use crate::commands::claude_sync::sync_claude_todos;
+use crate::commands::session_title::title_session;
+use crate::commands::tool_call::{record_tool_result, record_tool_use};
+use crate::commands::verification::verify_turn;
+
+use crate::commands::session::{
+    record_cli_session_id, record_resume_fallback, record_turn_cost, save_message_draft,
+};
+
+use super::parser::{
+    is_auth_required, is_fatal_stderr, is_resume_failure, parse_claude_output, parse_todo_write,
+    permission_response, user_message,
+};
+use super::logs::CliLogs;
+use super::replay::OutputReplay;
 
 /// Manages active CLI processes for sessions
+///
+/// Cloning is cheap and yields a handle to the same set of processes.
+#[derive(Clone)]
 pub struct CliManager {
     /// Map of session_id -> CLI process
     processes: Arc<RwLock<HashMap<String, CliProcess>>>,
+    /// Sessions waiting for a free process slot, in arrival order
+    queue: Arc<Mutex<VecDeque<PendingStart>>>,
+    /// Maximum number of concurrent processes (0 = unlimited)
+    max_concurrent: Arc<AtomicUsize>,
+    /// Automatic restarts allowed after a crash (0 = disabled)
+    max_restarts: Arc<AtomicUsize>,
+    /// CLI executable used instead of looking up `claude` in PATH
+    binary_path: Arc<std::sync::RwLock<Option<PathBuf>>>,
+    /// Recently streamed output, replayed to windows opened mid-response
+    pub replay: OutputReplay,
+    /// Recent stderr output per session, kept after the process exits
+    pub logs: CliLogs,
+    /// Bumped when a process starts, exits, or turns busy or ready
+    status_changes: Arc<watch::Sender<()>>,
+    /// Crashed sessions waiting out their restart delay, with a flag `stop` sets
+    /// to call the restart off
+    pending_restarts: Arc<std::sync::Mutex<HashMap<String, Arc<AtomicBool>>>>,
 }
 
+/// How often the supervisor polls a process for exit
+const SUPERVISOR_POLL_MS: u64 = 500;
+
+/// Base delay before an automatic restart, doubled on each attempt
+const RESTART_BACKOFF_BASE_MS: u64 = 1000;
+
+/// Upper bound for the restart delay
+const RESTART_BACKOFF_MAX_MS: u64 = 30_000;
+
+/// Number of stderr lines kept for crash reports
+const STDERR_TAIL_LINES: usize = 20;
+
+/// How often streamed text is saved as a draft while a response is in progress
+const DRAFT_FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
 /// A single CLI process instance
 struct CliProcess {
     child: Child,
     status: ClaudeStatus,
+    /// Distinguishes this process from a later one for the same session
+    instance_id: String,
+    /// Launch parameters, kept so a crashed process can be restarted
+    working_dir: PathBuf,
+    resume_context: Option<String>,
+    options: CliOptions,
+    /// Automatic restarts performed since the session was started
+    restarts: u32,
+    /// Most recent stderr output
+    stderr_tail: Arc<Mutex<VecDeque<String>>>,
+    /// The CLI reported that the `--resume` session ID is invalid
+    resume_failed: bool,
+    /// The CLI reported that it isn't logged in
+    auth_required: bool,
+    /// Permission requests awaiting an answer, by request ID, with the tool input
+    pending_permissions: HashMap<String, serde_json::Value>,
+    /// Most recent message sent to the CLI, used to describe auto-commits
+    last_prompt: Option<String>,
+    /// When this process was spawned
+    started_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A running CLI process
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliProcessInfo {
+    pub session_id: String,
+    /// None once the process has exited but before it is reaped
+    pub pid: Option<u32>,
+    pub working_dir: String,
+    pub status: ClaudeStatus,
+    pub started_at: String,
+    pub uptime_secs: u64,
+}
+
+/// Per-session CLI launch options
+#[derive(Debug, Clone, Default)]
+pub struct CliOptions {
+    /// Model passed via `--model` (CLI default when unset)
+    pub model: Option<String>,
+    /// Permission mode passed via `--permission-mode`
+    pub permission_mode: Option<String>,
+    /// Tool rules passed via `--disallowedTools`, from the project policy
+    pub disallowed_tools: Vec<String>,
+    /// Additional arguments appended verbatim
+    pub extra_args: Vec<String>,
+    /// Instructions passed via `--append-system-prompt`
+    pub system_prompt: Option<String>,
+    /// MCP server config file passed via `--mcp-config`
+    pub mcp_config: Option<PathBuf>,
+    /// Native CLI session resumed via `--resume`; the resume context is only
+    /// sent when this is unset, and is the fallback if the ID is rejected
+    pub resume_id: Option<String>,
+}
+
+impl CliOptions {
+    /// Whether the CLI asks before running tools, so it needs the stdio permission prompt
+    fn prompts_for_permissions(&self) -> bool {
+        let bypassed = self.permission_mode.as_deref() == Some("bypassPermissions")
+            || self.extra_args.iter().any(|arg| arg == "--dangerously-skip-permissions");
+        !bypassed
+    }
+}
+
+/// A start request held back by the concurrency limit
+struct PendingStart {
+    session_id: String,
+    working_dir: PathBuf,
+    resume_context: Option<String>,
+    options: CliOptions,
+    /// Automatic restarts performed so far, when this is a restart
+    restarts: u32,
 }
 
 impl CliManager {
@@ -35,121 +170,419 @@ impl CliManager {
     pub fn new() -> Self {
         Self {
             processes: Arc::new(RwLock::new(HashMap::new())),
+            queue: Arc::new(Mutex::new(VecDeque::new())),
+            max_concurrent: Arc::new(AtomicUsize::new(0)),
+            max_restarts: Arc::new(AtomicUsize::new(0)),
+            binary_path: Arc::new(std::sync::RwLock::new(None)),
+            replay: OutputReplay::new(),
+            logs: CliLogs::new(),
+            status_changes: Arc::new(watch::channel(()).0),
+            pending_restarts: Arc::new(std::sync::Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Watch for processes starting, exiting, or changing between busy and ready
+    pub fn subscribe_status(&self) -> watch::Receiver<()> {
+        self.status_changes.subscribe()
+    }
+
+    fn status_changed(&self) {
+        self.status_changes.send_modify(|_| {});
+    }
+
+    /// Get the configured CLI executable, if PATH lookup is overridden
+    pub fn binary_path(&self) -> Option<PathBuf> {
+        self.binary_path.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Override the CLI executable (None = look up `claude` in PATH)
+    ///
+    /// Applies to processes started afterwards.
+    pub fn set_binary_path(&self, path: Option<PathBuf>) {
+        *self.binary_path.write().unwrap_or_else(|e| e.into_inner()) = path;
+    }
+
+    /// Resolve the CLI executable to launch
+    pub fn resolve_binary(&self) -> Result<PathBuf, AppError> {
+        match self.binary_path() {
+            Some(path) => Ok(path),
+            None => system::env::which("claude").ok_or_else(AppError::claude_cli_not_found),
+        }
+    }
+
+    /// Get the number of automatic restarts allowed after a crash
+    pub fn max_restarts(&self) -> usize {
+        self.max_restarts.load(Ordering::Relaxed)
+    }
+
+    /// Set the number of automatic restarts allowed after a crash (0 = disabled)
+    pub fn set_max_restarts(&self, retries: usize) {
+        self.max_restarts.store(retries, Ordering::Relaxed);
+    }
+
+    /// Get the concurrency limit (None = unlimited)
+    pub fn max_concurrent(&self) -> Option<usize> {
+        match self.max_concurrent.load(Ordering::Relaxed) {
+            0 => None,
+            n => Some(n),
+        }
+    }
+
+    /// Set the concurrency limit (None = unlimited)
+    ///
+    /// Raising the limit immediately starts queued sessions that now fit.
+    pub async fn set_max_concurrent(&self, app: Option<AppHandle>, limit: Option<usize>) {
+        self.max_concurrent.store(limit.unwrap_or(0), Ordering::Relaxed);
+        if let Some(app) = app {
+            self.start_queued(&app).await;
         }
     }
 
     /// Start a CLI process for a session
+    ///
+    /// If the concurrency limit is reached, the session is queued and
+    /// started once another process exits.
     pub async fn start(
         &self,
         app: AppHandle,
         session_id: String,
         working_dir: &Path,
         resume_context: Option<String>,
+        options: CliOptions,
     ) -> Result<(), AppError> {
-        // Check if already running
+        let pending = PendingStart {
+            session_id,
+            working_dir: working_dir.to_path_buf(),
+            resume_context,
+            options,
+            restarts: 0,
+        };
+        self.admit(&app, pending).await
+    }
+
+    /// Spawn a process now if there is a free slot, otherwise queue it
+    ///
+    /// Does nothing when the session is already running or queued.
+    async fn admit(&self, app: &AppHandle, pending: PendingStart) -> Result<(), AppError> {
+        // The queue lock doubles as the admission lock so two starts can't
+        // both claim the last free slot
+        let mut queue = self.queue.lock().await;
+
+        // Check if already running or queued
         {
             let processes = self.processes.read().await;
-            if processes.contains_key(&session_id) {
+            if processes.contains_key(&pending.session_id) {
                 return Ok(());
             }
         }
+        if queue.iter().any(|p| p.session_id == pending.session_id) {
+            return Ok(());
+        }
+
+        if !self.has_free_slot().await {
+            emit_status(app, &pending.session_id, "queued");
+            queue.push_back(pending);
+            self.emit_queue_status(app, &queue).await;
+            return Ok(());
+        }
+
+        self.spawn_process(
+            app,
+            pending.session_id,
+            pending.working_dir,
+            pending.resume_context,
+            pending.options,
+            pending.restarts,
+        )
+        .await
+    }
+
+    /// Whether another process may be spawned under the current limit
+    async fn has_free_slot(&self) -> bool {
+        match self.max_concurrent() {
+            Some(limit) => self.processes.read().await.len() < limit,
+            None => true,
+        }
+    }
+
+    /// Start queued sessions while there are free slots
+    async fn start_queued(&self, app: &AppHandle) {
+        let mut queue = self.queue.lock().await;
+        let mut changed = false;
 
-        // Emit starting status
-        emit_status(&app, &session_id, "starting");
-
-        // Find Claude CLI in PATH
-        let claude_path = which::which("claude").map_err(|_| AppError::claude_cli_not_found())?;
-
-        // Build command
-        let mut cmd = Command::new(claude_path);
-        cmd.arg("--print")
-            .current_dir(working_dir)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .kill_on_drop(true);
-
-        // Spawn process
-        let mut child = cmd
-            .spawn()
-            .map_err(|e| AppError::claude_cli_error(format!("Failed to spawn CLI: {}", e)))?;
-
-        // Send resume context if provided
-        if let Some(context) = resume_context {
-            if let Some(stdin) = child.stdin.as_mut() {
-                stdin
-                    .write_all(context.as_bytes())
-                    .await
-                    .map_err(|e| AppError::claude_cli_error(format!("Failed to write context: {}", e)))?;
-                stdin
-                    .write_all(b"\n")
-                    .await
-                    .map_err(|e| AppError::claude_cli_error(format!("Failed to write: {}", e)))?;
+        while let Some(pending) = self.next_queued(&mut queue).await {
+            changed = true;
+
+            if let Err(e) = self
+                .spawn_process(
+                    app,
+                    pending.session_id.clone(),
+                    pending.working_dir,
+                    pending.resume_context,
+                    pending.options,
+                    pending.restarts,
+                )
+                .await
+            {
+                log::error!("Failed to start queued CLI session: {}", e);
+                emit_status_with(app, &pending.session_id, "error", Some(e.message), None, None);
             }
         }
 
-        // Store process
-        {
-            let mut processes = self.processes.write().await;
-            processes.insert(
-                session_id.clone(),
-                CliProcess {
-                    child,
-                    status: ClaudeStatus::Ready,
-                },
-            );
+        if changed {
+            self.emit_queue_status(app, &queue).await;
         }
+    }
 
-        // Emit ready status
-        emit_status(&app, &session_id, "ready");
+    /// Take the next queued session if there is a free slot for it
+    async fn next_queued(&self, queue: &mut VecDeque<PendingStart>) -> Option<PendingStart> {
+        if queue.is_empty() || !self.has_free_slot().await {
+            return None;
+        }
+        queue.pop_front()
+    }
 
-        // Start output streaming in background
-        let session_id_clone = session_id.clone();
-        let app_clone = app.clone();
-        let processes_clone = self.processes.clone();
+    /// Emit a snapshot of the queue to the frontend
+    async fn emit_queue_status(&self, app: &AppHandle, queue: &VecDeque<PendingStart>) {
+        let running = self.processes.read().await.len();
+        let _ = emit_event(
+            app,
+            event_names::CLAUDE_QUEUE_STATUS,
+            ClaudeQueueStatusPayload {
+                running,
+                max_concurrent: self.max_concurrent(),
+                queued: queue.iter().map(|p| p.session_id.clone()).collect(),
+            },
+        );
+    }
 
-        tokio::spawn(async move {
-            stream_output(app_clone, session_id_clone, processes_clone).await;
-        });
+    /// Spawn the CLI process and begin streaming and supervising it
+    ///
+    /// Boxed because it is reached recursively from the supervisor task it
+    /// spawns, which would otherwise give the future an infinite type.
+    fn spawn_process<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        session_id: String,
+        working_dir: PathBuf,
+        resume_context: Option<String>,
+        options: CliOptions,
+        restarts: u32,
+    ) -> Pin<Box<dyn Future<Output = Result<(), AppError>> + Send + 'a>> {
+        Box::pin(async move {
+            // Emit starting status
+            emit_status(app, &session_id, "starting");
 
-        Ok(())
+            // Use the configured CLI, or find it in PATH
+            let claude_path = self.resolve_binary()?;
+
+            // Build command
+            let mut cmd = Command::new(claude_path);
+            system::env::apply(&mut cmd);
+            // A key saved in the keychain, unless the environment already has one
+            if std::env::var_os("ANTHROPIC_API_KEY").is_none() {
+                if let Some(api_key) = secrets::lookup(secrets::ANTHROPIC_API_KEY).await {
+                    cmd.env("ANTHROPIC_API_KEY", api_key);
+                }
+            }
+            // Messages and permission answers go in as JSON lines, and events come out as them
+            cmd.arg("--print")
+                .arg("--input-format")
+                .arg("stream-json")
+                .arg("--output-format")
+                .arg("stream-json")
+                .arg("--verbose");
+            if let Some(model) = options.model.as_deref() {
+                cmd.arg("--model").arg(model);
+            }
+            if let Some(mode) = options.permission_mode.as_deref() {
+                cmd.arg("--permission-mode").arg(mode);
+            }
+            if !options.disallowed_tools.is_empty() {
+                cmd.arg("--disallowedTools").arg(options.disallowed_tools.join(","));
+            }
+            if let Some(system_prompt) = options.system_prompt.as_deref() {
+                cmd.arg("--append-system-prompt").arg(system_prompt);
+            }
+            if let Some(mcp_config) = options.mcp_config.as_deref() {
+                cmd.arg("--mcp-config").arg(mcp_config);
+            }
+            if let Some(resume_id) = options.resume_id.as_deref() {
+                cmd.arg("--resume").arg(resume_id);
+            }
+            // Ask for tool approvals over stdio so the user can answer them
+            if options.prompts_for_permissions() {
+                cmd.arg("--permission-prompt-tool").arg("stdio");
+            }
+            cmd.args(&options.extra_args)
+                .current_dir(&working_dir)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true);
+
+            // Spawn process
+            let mut child = cmd
+                .spawn()
+                .map_err(|e| AppError::claude_cli_error(format!("Failed to spawn CLI: {}", e)))?;
+
+            // Send resume context if provided and the CLI isn't restoring the session itself
+            if let Some(context) = resume_context.as_deref().filter(|_| options.resume_id.is_none()) {
+                if let Some(stdin) = child.stdin.as_mut() {
+                    stdin
+                        .write_all(user_message(context).as_bytes())
+                        .await
+                        .map_err(|e| AppError::claude_cli_error(format!("Failed to write context: {}", e)))?;
+                    stdin
+                        .write_all(b"\n")
+                        .await
+                        .map_err(|e| AppError::claude_cli_error(format!("Failed to write: {}", e)))?;
+                }
+            }
+
+            // Drain stderr so the child never blocks on a full pipe
+            let stderr_tail = Arc::new(Mutex::new(VecDeque::new()));
+            if let Some(stderr) = child.stderr.take() {
+                tokio::spawn(collect_stderr(
+                    app.clone(),
+                    session_id.clone(),
+                    stderr,
+                    stderr_tail.clone(),
+                    self.logs.clone(),
+                ));
+            }
+
+            let instance_id = uuid::Uuid::new_v4().to_string();
+
+            // Store process
+            {
+                let mut processes = self.processes.write().await;
+                processes.insert(
+                    session_id.clone(),
+                    CliProcess {
+                        child,
+                        status: ClaudeStatus::Ready,
+                        instance_id: instance_id.clone(),
+                        working_dir,
+                        resume_context,
+                        options,
+                        restarts,
+                        stderr_tail,
+                        resume_failed: false,
+                        auth_required: false,
+                        pending_permissions: HashMap::new(),
+                        last_prompt: None,
+                        started_at: chrono::Utc::now(),
+                    },
+                );
+            }
+            self.status_changed();
+
+            // Emit ready status
+            emit_status(app, &session_id, "ready");
+
+            // Start output streaming and supervision in background
+            let manager = self.clone();
+            let app_clone = app.clone();
+            let session_id_clone = session_id.clone();
+            tokio::spawn(async move {
+                stream_output(app_clone, session_id_clone, manager).await;
+            });
+
+            let manager = self.clone();
+            let app_clone = app.clone();
+            tokio::spawn(async move {
+                supervise(app_clone, session_id, instance_id, manager).await;
+            });
+
+            Ok(())
+        })
     }
 
     /// Stop a CLI process for a session
-    pub async fn stop(&self, session_id: &str) -> Result<(), AppError> {
-        let mut processes = self.processes.write().await;
-        if let Some(mut process) = processes.remove(session_id) {
-            let _ = process.child.kill().await;
+    ///
+    /// A session still waiting in the queue is dropped from it, and the
+    /// sessions behind it are told their new positions. Stopping a running
+    /// process frees its slot for the next queued session.
+    ///
+    /// Returns whether the session was running, queued, or waiting to restart.
+    pub async fn stop(&self, app: &AppHandle, session_id: &str) -> Result<bool, AppError> {
+        // Call off a restart still waiting out its delay
+        let pending_restart = self
+            .pending_restarts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(session_id);
+        let restart_cancelled = pending_restart.is_some();
+        if let Some(cancelled) = pending_restart {
+            cancelled.store(true, Ordering::Relaxed);
+            emit_status(app, session_id, "stopped");
         }
-        Ok(())
+
+        let dequeued = {
+            let mut queue = self.queue.lock().await;
+            let queued = queue.len();
+            queue.retain(|p| p.session_id != session_id);
+            let dequeued = queue.len() != queued;
+            if dequeued {
+                emit_status(app, session_id, "stopped");
+                self.emit_queue_status(app, &queue).await;
+            }
+            dequeued
+        };
+
+        let stopped = self.remove_process(session_id).await;
+        self.replay.clear(session_id);
+        // The supervisor exits quietly for a stopped process, so the queue
+        // has to be picked up here
+        if stopped {
+            self.start_queued(app).await;
+        }
+        Ok(restart_cancelled || dequeued || stopped)
+    }
+
+    /// Kill a session's process and forget it, returning whether there was one
+    async fn remove_process(&self, session_id: &str) -> bool {
+        let Some(mut process) = self.processes.write().await.remove(session_id) else {
+            return false;
+        };
+        let _ = process.child.kill().await;
+        self.status_changed();
+        true
     }
 
     /// Send a message to the CLI process
     pub async fn send_message(&self, session_id: &str, content: &str) -> Result<(), AppError> {
         let mut processes = self.processes.write().await;
-        if let Some(process) = processes.get_mut(session_id) {
-            if let Some(stdin) = process.child.stdin.as_mut() {
-                stdin
-                    .write_all(content.as_bytes())
-                    .await
-                    .map_err(|e| AppError::claude_cli_error(format!("Failed to write: {}", e)))?;
-                stdin
-                    .write_all(b"\n")
-                    .await
-                    .map_err(|e| AppError::claude_cli_error(format!("Failed to write: {}", e)))?;
-                stdin
-                    .flush()
-                    .await
-                    .map_err(|e| AppError::claude_cli_error(format!("Failed to flush: {}", e)))?;
-
-                process.status = ClaudeStatus::Busy;
-                Ok(())
-            } else {
-                Err(AppError::claude_cli_error("CLI stdin not available"))
-            }
-        } else {
-            Err(AppError::claude_cli_error("CLI not running for session"))
-        }
+        let process = processes
+            .get_mut(session_id)
+            .ok_or_else(|| AppError::claude_cli_error("CLI not running for session"))?;
+
+        write_line(process, &user_message(content)).await?;
+        process.status = ClaudeStatus::Busy;
+        process.last_prompt = Some(content.to_string());
+        self.status_changed();
+        Ok(())
+    }
+
+    /// Answer a tool permission request from the CLI
+    pub async fn respond_permission(
+        &self,
+        session_id: &str,
+        request_id: &str,
+        allow: bool,
+        message: Option<&str>,
+    ) -> Result<(), AppError> {
+        let mut processes = self.processes.write().await;
+        let process = processes
+            .get_mut(session_id)
+            .ok_or_else(|| AppError::claude_cli_error("CLI not running for session"))?;
+
+        let input = process.pending_permissions.remove(request_id).ok_or_else(|| {
+            AppError::invalid_input(format!("No pending permission request '{}'", request_id))
+        })?;
+
+        write_line(process, &permission_response(request_id, allow, &input, message)).await
     }
 
     /// Cancel an in-progress response (send interrupt signal)
@@ -181,11 +614,19 @@ impl CliManager {
 
     /// Get the status of a CLI session
     pub async fn get_status(&self, session_id: &str) -> ClaudeStatus {
-        let processes = self.processes.read().await;
-        processes
-            .get(session_id)
-            .map(|p| p.status.clone())
-            .unwrap_or(ClaudeStatus::Stopped)
+        {
+            let processes = self.processes.read().await;
+            if let Some(process) = processes.get(session_id) {
+                return process.status.clone();
+            }
+        }
+
+        let queue = self.queue.lock().await;
+        if queue.iter().any(|p| p.session_id == session_id) {
+            ClaudeStatus::Queued
+        } else {
+            ClaudeStatus::Stopped
+        }
     }
 
     /// Check if a session has an active CLI process
@@ -193,6 +634,34 @@ impl CliManager {
         let processes = self.processes.read().await;
         processes.contains_key(session_id)
     }
+
+    /// Describe every running CLI process, longest-running first
+    pub async fn list_processes(&self) -> Vec<CliProcessInfo> {
+        let now = chrono::Utc::now();
+        let processes = self.processes.read().await;
+        let mut list: Vec<CliProcessInfo> = processes
+            .iter()
+            .map(|(session_id, process)| CliProcessInfo {
+                session_id: session_id.clone(),
+                pid: process.child.id(),
+                working_dir: process.working_dir.to_string_lossy().to_string(),
+                status: process.status.clone(),
+                started_at: process.started_at.to_rfc3339(),
+                uptime_secs: (now - process.started_at).num_seconds().max(0) as u64,
+            })
+            .collect();
+        list.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+        list
+    }
+
+    /// Find the session whose CLI process has the given pid
+    pub async fn session_for_pid(&self, pid: u32) -> Option<String> {
+        let processes = self.processes.read().await;
+        processes
+            .iter()
+            .find(|(_, process)| process.child.id() == Some(pid))
+            .map(|(session_id, _)| session_id.clone())
+    }
 }
 
 impl Default for CliManager {
@@ -202,11 +671,9 @@ impl Default for CliManager {
 }
 
 /// Stream output from the CLI process
-async fn stream_output(
-    app: AppHandle,
-    session_id: String,
-    processes: Arc<RwLock<HashMap<String, CliProcess>>>,
-) {
+async fn stream_output(app: AppHandle, session_id: String, manager: CliManager) {
+    let processes = &manager.processes;
+
     // Take stdout from the process
     let stdout = {
         let mut procs = processes.write().await;
@@ -226,6 +693,15 @@ async fn stream_output(
 
     let mut message_id = format!("msg-{}", uuid::Uuid::new_v4());
     let mut current_text = String::new();
+    // Text streamed since the draft was last saved, so a crash loses little
+    let mut last_draft_flush = std::time::Instant::now();
+    let mut draft_dirty = false;
+    // Files Claude has written since the last message finished
+    let mut written_files: Vec<String> = Vec::new();
+    // When each running tool started, keyed by tool_use ID
+    let mut tool_started: HashMap<String, std::time::Instant> = HashMap::new();
+    // Whether this process has tried to title the session yet
+    let mut title_requested = false;
 
     while let Ok(Some(line)) = lines.next_line().await {
         if line.is_empty() {
@@ -238,11 +714,28 @@ async fn stream_output(
                 match event {
                     super::parser::ClaudeEvent::Assistant { message_id: new_id } => {
                         // New message started
+                        if draft_dirty {
+                            draft_dirty = false;
+                            let state = app.state::<AppState>();
+                            if let Err(e) = save_message_draft(&state.db, &session_id, &message_id, &current_text, false).await {
+                                log::warn!("Failed to save draft for session {}: {}", session_id, e);
+                            }
+                        }
                         message_id = new_id.unwrap_or_else(|| format!("msg-{}", uuid::Uuid::new_v4()));
                         current_text.clear();
                     }
                     super::parser::ClaudeEvent::TextDelta { text } => {
                         current_text.push_str(&text);
+                        manager.replay.record(&session_id, &message_id, &text, false);
+                        draft_dirty = true;
+                        if last_draft_flush.elapsed() >= DRAFT_FLUSH_INTERVAL {
+                            last_draft_flush = std::time::Instant::now();
+                            draft_dirty = false;
+                            let state = app.state::<AppState>();
+                            if let Err(e) = save_message_draft(&state.db, &session_id, &message_id, &current_text, false).await {
+                                log::warn!("Failed to save draft for session {}: {}", session_id, e);
+                            }
+                        }
                         let _ = emit_event(
                             &app,
                             event_names::CLAUDE_OUTPUT,
@@ -254,17 +747,63 @@ async fn stream_output(
                             },
                         );
                     }
-                    super::parser::ClaudeEvent::ToolUse { name, input } => {
+                    super::parser::ClaudeEvent::ToolUse { id, name, input } => {
                         // Emit tool use as a special chunk
                         // The frontend will parse this
                         log::debug!("Tool use: {} with {:?}", name, input);
+
+                        let state = app.state::<AppState>();
+                        if let Err(e) = record_tool_use(&state.db, &session_id, &message_id, id.as_deref(), &name, &input).await {
+                            log::warn!("Failed to record {} call for session {}: {}", name, session_id, e);
+                        }
+                        if let Some(id) = id {
+                            tool_started.insert(id, std::time::Instant::now());
+                        }
+
+                        // Snapshot files before Claude writes them so the run can be undone
+                        if let Some(file_path) = checkpoints::target_path(&name, &input) {
+                            let state = app.state::<AppState>();
+                            if let Err(e) = checkpoints::capture(&state.db, &state.data_dir, &session_id, &file_path, &name).await {
+                                log::warn!("Failed to checkpoint {} for session {}: {}", file_path, session_id, e);
+                            }
+                            if !written_files.contains(&file_path) {
+                                written_files.push(file_path);
+                            }
+                        }
+
+                        // Mirror the CLI's todo list into tasks when enabled
+                        if let Some(todos) = parse_todo_write(&name, &input) {
+                            let app = app.clone();
+                            let session_id = session_id.clone();
+                            tokio::spawn(async move {
+                                if let Err(e) = sync_claude_todos(&app, &session_id, todos).await {
+                                    log::warn!("Failed to sync todos for session {}: {}", session_id, e);
+                                }
+                            });
+                        }
                     }
-                    super::parser::ClaudeEvent::ToolResult { tool_use_id, content } => {
+                    super::parser::ClaudeEvent::ToolResult { tool_use_id, content, is_error } => {
                         // Tool result received
                         log::debug!("Tool result for {}: {}", tool_use_id, content);
+
+                        let duration_ms = tool_started
+                            .remove(&tool_use_id)
+                            .map(|started| started.elapsed().as_millis() as i64);
+                        let state = app.state::<AppState>();
+                        if let Err(e) = record_tool_result(&state.db, &session_id, &tool_use_id, &content, is_error, duration_ms).await {
+                            log::warn!("Failed to record result of {} for session {}: {}", tool_use_id, session_id, e);
+                        }
                     }
                     super::parser::ClaudeEvent::MessageStop => {
                         // Message complete
+                        manager.replay.record(&session_id, &message_id, "", true);
+                        if !current_text.is_empty() {
+                            draft_dirty = false;
+                            let state = app.state::<AppState>();
+                            if let Err(e) = save_message_draft(&state.db, &session_id, &message_id, &current_text, true).await {
+                                log::warn!("Failed to save draft for session {}: {}", session_id, e);
+                            }
+                        }
                         let _ = emit_event(
                             &app,
                             event_names::CLAUDE_OUTPUT,
@@ -276,53 +815,472 @@ async fn stream_output(
                             },
                         );
                         emit_status(&app, &session_id, "ready");
+                        hooks::fire(
+                            &app,
+                            HookEvent::MessageComplete,
+                            HookContext {
+                                session_id: Some(session_id.clone()),
+                                message_id: Some(message_id.clone()),
+                                ..Default::default()
+                            },
+                        );
+
+                        // Title a new session from Claude's first response when enabled
+                        if !title_requested && !current_text.is_empty() {
+                            title_requested = true;
+                            let app = app.clone();
+                            let session_id = session_id.clone();
+                            let response = current_text.clone();
+                            tokio::spawn(async move {
+                                if let Err(e) = title_session(&app, &session_id, &response).await {
+                                    log::warn!("Failed to title session {}: {}", session_id, e);
+                                }
+                            });
+                        }
 
                         // Update process status
-                        let mut procs = processes.write().await;
-                        if let Some(process) = procs.get_mut(&session_id) {
-                            process.status = ClaudeStatus::Ready;
+                        let prompt = {
+                            let mut procs = processes.write().await;
+                            procs.get_mut(&session_id).and_then(|process| {
+                                process.status = ClaudeStatus::Ready;
+                                process.last_prompt.clone()
+                            })
+                        };
+                        manager.status_changed();
+
+                        // Commit the turn's file changes when enabled for the session
+                        let app = app.clone();
+                        let session_id = session_id.clone();
+                        let files = std::mem::take(&mut written_files);
+                        let finished_message_id = message_id.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = autocommit_turn(&app, &session_id, prompt.as_deref()).await {
+                                log::warn!("Failed to auto-commit session {}: {}", session_id, e);
+                            }
+
+                            // Check Claude's changes once they are committed
+                            if !files.is_empty() {
+                                if let Err(e) = verify_turn(&app, &session_id, &finished_message_id, files).await {
+                                    log::warn!("Failed to verify session {}: {}", session_id, e);
+                                }
+                            }
+                        });
+                    }
+                    super::parser::ClaudeEvent::SessionInit { cli_session_id } => {
+                        // Remember the ID so the session can be resumed natively
+                        let state = app.state::<AppState>();
+                        if let Err(e) = record_cli_session_id(&state.db, &session_id, &cli_session_id).await {
+                            log::warn!("Failed to store CLI session ID for {}: {}", session_id, e);
                         }
                     }
-                    super::parser::ClaudeEvent::Error { message } => {
+                    super::parser::ClaudeEvent::Result { is_error, message, cost_usd } => {
+                        if let Some(cost_usd) = cost_usd.filter(|c| *c > 0.0) {
+                            let state = app.state::<AppState>();
+                            if let Err(e) = record_turn_cost(&state.db, &session_id, cost_usd).await {
+                                log::warn!("Failed to record cost for session {}: {}", session_id, e);
+                            }
+                        }
+                        if is_error && message.as_deref().is_some_and(is_resume_failure) {
+                            let mut procs = processes.write().await;
+                            if let Some(process) = procs.get_mut(&session_id) {
+                                process.resume_failed = true;
+                            }
+                        }
+                        if let Some(message) = message.filter(|m| is_error && is_auth_required(m)) {
+                            mark_auth_required(&manager, &session_id).await;
+                            emit_cli_error(&app, &session_id, &message, ErrorCode::ClaudeCliAuthRequired, false);
+                        }
+                    }
+                    super::parser::ClaudeEvent::PermissionRequest { request_id, tool_name, input } => {
+                        {
+                            let mut procs = processes.write().await;
+                            if let Some(process) = procs.get_mut(&session_id) {
+                                process.pending_permissions.insert(request_id.clone(), input.clone());
+                            }
+                        }
                         let _ = emit_event(
                             &app,
-                            event_names::CLAUDE_ERROR,
-                            serde_json::json!({
-                                "sessionId": session_id,
-                                "error": message,
-                                "recoverable": true,
-                            }),
+                            event_names::CLAUDE_PERMISSION_REQUEST,
+                            ClaudePermissionRequestPayload {
+                                session_id: session_id.clone(),
+                                request_id,
+                                tool_name,
+                                input,
+                            },
                         );
                     }
+                    super::parser::ClaudeEvent::Error { message } => {
+                        if is_auth_required(&message) {
+                            mark_auth_required(&manager, &session_id).await;
+                            emit_cli_error(&app, &session_id, &message, ErrorCode::ClaudeCliAuthRequired, false);
+                        } else {
+                            emit_cli_error(&app, &session_id, &message, ErrorCode::ClaudeCliError, true);
+                        }
+                    }
                     super::parser::ClaudeEvent::Unknown => {
                         // Ignore unknown events
                     }
                 }
             }
             Err(e) => {
-                log::warn!("Failed to parse CLI output: {} - line: {}", e, line);
+                // The line and the parse error quoting it can hold file
+                // contents or secrets, so they stay out of warning-level logs
+                log::warn!("Failed to parse a {}-byte line of CLI output", line.len());
+                log::debug!("Failed to parse CLI output: {} - line: {}", e, line);
             }
         }
     }
 
-    // Process ended - clean up
-    {
-        let mut procs = processes.write().await;
-        procs.remove(&session_id);
+    // Keep whatever arrived before the process went away
+    if draft_dirty {
+        let state = app.state::<AppState>();
+        if let Err(e) = save_message_draft(&state.db, &session_id, &message_id, &current_text, false).await {
+            log::warn!("Failed to save draft for session {}: {}", session_id, e);
+        }
+    }
+
+    // Stdout closed - the supervisor reports the exit and cleans up
+    log::debug!("CLI output stream ended for session {}", session_id);
+}
+
+/// Collect stderr output into the session's log and crash-report tail
+///
+/// Lines that look fatal are also reported as `claude_error` events, since
+/// the CLI may otherwise fail without any output on stdout.
+async fn collect_stderr(
+    app: AppHandle,
+    session_id: String,
+    stderr: ChildStderr,
+    tail: Arc<Mutex<VecDeque<String>>>,
+    logs: CliLogs,
+) {
+    let mut lines = BufReader::new(stderr).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        log::debug!("CLI stderr: {}", line);
+        logs.record(&session_id, &line);
+        if is_auth_required(&line) {
+            emit_cli_error(&app, &session_id, &line, ErrorCode::ClaudeCliAuthRequired, false);
+        } else if is_fatal_stderr(&line) {
+            emit_cli_error(&app, &session_id, &line, ErrorCode::ClaudeCliError, false);
+        }
+
+        let mut tail = tail.lock().await;
+        if tail.len() == STDERR_TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(line);
+    }
+}
+
+/// Watch a CLI process until it exits, then report and optionally restart it
+async fn supervise(app: AppHandle, session_id: String, instance_id: String, manager: CliManager) {
+    let poll = Duration::from_millis(SUPERVISOR_POLL_MS);
+
+    let (exit_status, process) = loop {
+        tokio::time::sleep(poll).await;
+
+        let mut procs = manager.processes.write().await;
+        let Some(process) = procs.get_mut(&session_id) else {
+            // Stopped deliberately
+            return;
+        };
+        if process.instance_id != instance_id {
+            // Replaced by a newer process with its own supervisor
+            return;
+        }
+
+        match process.child.try_wait() {
+            Ok(Some(exit_status)) => {
+                let Some(process) = procs.remove(&session_id) else {
+                    return;
+                };
+                manager.status_changed();
+                break (exit_status, process);
+            }
+            Ok(None) => continue,
+            Err(e) => {
+                log::warn!("Failed to poll CLI process for session {}: {}", session_id, e);
+                continue;
+            }
+        }
+    };
+
+    let exit_code = exit_status.code();
+    let stderr_tail = {
+        let tail = process.stderr_tail.lock().await;
+        if tail.is_empty() {
+            None
+        } else {
+            Some(tail.iter().cloned().collect::<Vec<_>>().join("\n"))
+        }
+    };
+
+    // A rejected resume ID isn't a crash: start over from the transcript instead
+    let resume_rejected = process.options.resume_id.is_some()
+        && (process.resume_failed || stderr_tail.as_deref().is_some_and(is_resume_failure));
+    if resume_rejected {
+        log::warn!(
+            "CLI rejected resume ID for session {}; falling back to transcript context",
+            session_id
+        );
+        let state = app.state::<AppState>();
+        if let Err(e) = record_resume_fallback(&state.db, &session_id).await {
+            log::warn!("Failed to record resume fallback for {}: {}", session_id, e);
+        }
+
+        let pending = PendingStart {
+            session_id: session_id.clone(),
+            working_dir: process.working_dir,
+            resume_context: process.resume_context,
+            options: CliOptions {
+                resume_id: None,
+                ..process.options
+            },
+            restarts: process.restarts,
+        };
+        if let Err(e) = manager.admit(&app, pending).await {
+            log::error!("Failed to restart CLI for session {}: {}", session_id, e);
+            emit_status_with(&app, &session_id, "error", Some(e.message), None, None);
+        }
+
+        manager.start_queued(&app).await;
+        return;
+    }
+
+    let auth_required = process.auth_required || stderr_tail.as_deref().is_some_and(is_auth_required);
+
+    if exit_status.success() {
+        emit_status_with(&app, &session_id, "stopped", None, exit_code, stderr_tail);
+        dispatch_webhook(&app, &session_id, WebhookEvent::SessionFinished, None);
+    } else if auth_required {
+        // Restarting can't help until the user logs in again
+        log::warn!("CLI for session {} exited because it is not logged in", session_id);
+        dispatch_webhook(&app, &session_id, WebhookEvent::ClaudeError, Some("Claude CLI needs you to log in again"));
+        emit_status_with(
+            &app,
+            &session_id,
+            "error",
+            Some("Claude CLI needs you to log in again".to_string()),
+            exit_code,
+            stderr_tail,
+        );
+    } else {
+        log::warn!("CLI process for session {} exited with {:?}", session_id, exit_code);
+        let error = format!("Claude CLI exited unexpectedly ({})", exit_status);
+        dispatch_webhook(&app, &session_id, WebhookEvent::ClaudeError, Some(&error));
+        emit_status_with(
+            &app,
+            &session_id,
+            "error",
+            Some(error),
+            exit_code,
+            stderr_tail,
+        );
+
+        if (process.restarts as usize) < manager.max_restarts() {
+            let delay = (RESTART_BACKOFF_BASE_MS << process.restarts.min(16)).min(RESTART_BACKOFF_MAX_MS);
+            log::info!(
+                "Restarting CLI for session {} in {}ms (attempt {})",
+                session_id,
+                delay,
+                process.restarts + 1
+            );
+            let cancelled = Arc::new(AtomicBool::new(false));
+            manager
+                .pending_restarts
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(session_id.clone(), cancelled.clone());
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            {
+                let mut pending = manager.pending_restarts.lock().unwrap_or_else(|e| e.into_inner());
+                // A later crash of the same session may have replaced the entry
+                if pending.get(&session_id).is_some_and(|flag| Arc::ptr_eq(flag, &cancelled)) {
+                    pending.remove(&session_id);
+                }
+            }
+
+            // Skip the restart if the session was stopped during the delay.
+            // One started again meanwhile is left alone by `admit`.
+            if !cancelled.load(Ordering::Relaxed) {
+                let pending = PendingStart {
+                    session_id: session_id.clone(),
+                    working_dir: process.working_dir,
+                    resume_context: process.resume_context,
+                    options: process.options,
+                    restarts: process.restarts + 1,
+                };
+                if let Err(e) = manager.admit(&app, pending).await {
+                    log::error!("Failed to restart CLI for session {}: {}", session_id, e);
+                    emit_status_with(&app, &session_id, "error", Some(e.message), None, None);
+                }
+            }
+        }
     }
 
-    emit_status(&app, &session_id, "stopped");
+    // A slot may have opened up
+    manager.start_queued(&app).await;
+}
+
+/// Write a line to a CLI process's stdin
+async fn write_line(process: &mut CliProcess, line: &str) -> Result<(), AppError> {
+    let stdin = process
+        .child
+        .stdin
+        .as_mut()
+        .ok_or_else(|| AppError::claude_cli_error("CLI stdin not available"))?;
+
+    stdin
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|e| AppError::claude_cli_error(format!("Failed to write: {}", e)))?;
+    stdin
+        .write_all(b"\n")
+        .await
+        .map_err(|e| AppError::claude_cli_error(format!("Failed to write: {}", e)))?;
+    stdin
+        .flush()
+        .await
+        .map_err(|e| AppError::claude_cli_error(format!("Failed to flush: {}", e)))
+}
+
+/// Record that a session's CLI reported it isn't logged in
+async fn mark_auth_required(manager: &CliManager, session_id: &str) {
+    let mut procs = manager.processes.write().await;
+    if let Some(process) = procs.get_mut(session_id) {
+        process.auth_required = true;
+    }
+}
+
+/// Emit a `claude_error` event, and send it to webhooks
+fn emit_cli_error(app: &AppHandle, session_id: &str, error: &str, code: ErrorCode, recoverable: bool) {
+    dispatch_webhook(app, session_id, WebhookEvent::ClaudeError, Some(error));
+    let _ = emit_event(
+        app,
+        event_names::CLAUDE_ERROR,
+        serde_json::json!({
+            "sessionId": session_id,
+            "error": error,
+            "code": code,
+            "recoverable": recoverable,
+        }),
+    );
+}
+
+/// Send a session event to webhooks
+fn dispatch_webhook(app: &AppHandle, session_id: &str, event: WebhookEvent, error: Option<&str>) {
+    webhooks::dispatch(
+        app,
+        event,
+        WebhookContext {
+            session_id: Some(session_id.to_string()),
+            error: error.map(str::to_string),
+            ..Default::default()
+        },
+    );
 }
 
 /// Emit a status event
 fn emit_status(app: &AppHandle, session_id: &str, status: &str) {
+    emit_status_with(app, session_id, status, None, None, None);
+}
+
+/// Emit a status event with error and exit details
+fn emit_status_with(
+    app: &AppHandle,
+    session_id: &str,
+    status: &str,
+    error: Option<String>,
+    exit_code: Option<i32>,
+    stderr_tail: Option<String>,
+) {
     let _ = emit_event(
         app,
         event_names::CLAUDE_STATUS,
         ClaudeStatusPayload {
             session_id: session_id.to_string(),
             status: status.to_string(),
-            error: None,
+            error,
+            exit_code,
+            stderr_tail,
         },
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompts_for_permissions() {
+        assert!(CliOptions::default().prompts_for_permissions());
+
+        let plan = CliOptions {
+            permission_mode: Some("plan".to_string()),
+            ..Default::default()
+        };
+        assert!(plan.prompts_for_permissions());
+
+        let bypass = CliOptions {
+            permission_mode: Some("bypassPermissions".to_string()),
+            ..Default::default()
+        };
+        assert!(!bypass.prompts_for_permissions());
+
+        let skip = CliOptions {
+            extra_args: vec!["--dangerously-skip-permissions".to_string()],
+            ..Default::default()
+        };
+        assert!(!skip.prompts_for_permissions());
+    }
+
+    fn pending(session_id: &str) -> PendingStart {
+        PendingStart {
+            session_id: session_id.to_string(),
+            working_dir: std::env::temp_dir(),
+            resume_context: None,
+            options: CliOptions::default(),
+            restarts: 0,
+        }
+    }
+
+    fn process(child: Child) -> CliProcess {
+        CliProcess {
+            child,
+            status: ClaudeStatus::Ready,
+            instance_id: uuid::Uuid::new_v4().to_string(),
+            working_dir: std::env::temp_dir(),
+            resume_context: None,
+            options: CliOptions::default(),
+            restarts: 0,
+            stderr_tail: Arc::new(Mutex::new(VecDeque::new())),
+            resume_failed: false,
+            auth_required: false,
+            pending_permissions: HashMap::new(),
+            last_prompt: None,
+            started_at: chrono::Utc::now(),
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stopped_process_frees_its_slot_for_the_queue() {
+        let manager = CliManager::new();
+        manager.max_concurrent.store(1, Ordering::Relaxed);
+        let child = Command::new("sleep").arg("60").kill_on_drop(true).spawn().unwrap();
+        manager.processes.write().await.insert("running".to_string(), process(child));
+        let mut queue = VecDeque::from([pending("queued")]);
+
+        // At the cap, the queued session waits
+        assert!(manager.next_queued(&mut queue).await.is_none());
+        assert_eq!(queue.len(), 1);
+
+        assert!(manager.remove_process("running").await);
+        assert!(!manager.remove_process("running").await);
+
+        let next = manager.next_queued(&mut queue).await.unwrap();
+        assert_eq!(next.session_id, "queued");
+        assert!(queue.is_empty());
+        assert!(manager.next_queued(&mut queue).await.is_none());
+    }
+}
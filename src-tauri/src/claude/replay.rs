@@ -0,0 +1,164 @@
+//! Output Replay Buffer
+//!
+//! Keeps the most recent streamed output chunks per session in memory, so a
+//! window opened while a response is streaming can catch up on what was
+//! already sent. Buffers are pruned by chunk count and age, and dropped when
+//! the session's CLI process stops.
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Chunks kept per session when no limit is configured
+pub const DEFAULT_REPLAY_CHUNKS: usize = 2000;
+
+/// Chunks older than this are pruned
+const MAX_CHUNK_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// A streamed output chunk
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayChunk {
+    /// Increases by one per chunk within a session, for deduplicating
+    /// against live `claude_output` events
+    pub seq: u64,
+    pub message_id: String,
+    pub chunk: String,
+    pub is_complete: bool,
+    #[serde(skip)]
+    at: Instant,
+}
+
+#[derive(Default)]
+struct SessionBuffer {
+    next_seq: u64,
+    chunks: VecDeque<ReplayChunk>,
+}
+
+/// Per-session ring buffers of streamed output
+#[derive(Clone)]
+pub struct OutputReplay {
+    buffers: Arc<Mutex<HashMap<String, SessionBuffer>>>,
+    /// Chunks kept per session (0 = disabled)
+    max_chunks: Arc<AtomicUsize>,
+}
+
+impl OutputReplay {
+    pub fn new() -> Self {
+        Self {
+            buffers: Arc::new(Mutex::new(HashMap::new())),
+            max_chunks: Arc::new(AtomicUsize::new(DEFAULT_REPLAY_CHUNKS)),
+        }
+    }
+
+    /// Get the number of chunks kept per session
+    pub fn max_chunks(&self) -> usize {
+        self.max_chunks.load(Ordering::Relaxed)
+    }
+
+    /// Set the number of chunks kept per session (0 = disabled)
+    ///
+    /// Existing buffers are trimmed to the new limit.
+    pub fn set_max_chunks(&self, max_chunks: usize) {
+        self.max_chunks.store(max_chunks, Ordering::Relaxed);
+        let mut buffers = self.buffers.lock().unwrap_or_else(|e| e.into_inner());
+        if max_chunks == 0 {
+            buffers.clear();
+            return;
+        }
+        for buffer in buffers.values_mut() {
+            while buffer.chunks.len() > max_chunks {
+                buffer.chunks.pop_front();
+            }
+        }
+    }
+
+    /// Record a chunk streamed for a session
+    pub fn record(&self, session_id: &str, message_id: &str, chunk: &str, is_complete: bool) {
+        let max_chunks = self.max_chunks();
+        if max_chunks == 0 {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut buffers = self.buffers.lock().unwrap_or_else(|e| e.into_inner());
+        let buffer = buffers.entry(session_id.to_string()).or_default();
+
+        prune(&mut buffer.chunks, now, max_chunks.saturating_sub(1));
+        buffer.chunks.push_back(ReplayChunk {
+            seq: buffer.next_seq,
+            message_id: message_id.to_string(),
+            chunk: chunk.to_string(),
+            is_complete,
+            at: now,
+        });
+        buffer.next_seq += 1;
+    }
+
+    /// Get a session's buffered chunks, oldest first
+    pub fn recent(&self, session_id: &str) -> Vec<ReplayChunk> {
+        let mut buffers = self.buffers.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(buffer) = buffers.get_mut(session_id) else {
+            return Vec::new();
+        };
+
+        prune(&mut buffer.chunks, Instant::now(), self.max_chunks());
+        buffer.chunks.iter().cloned().collect()
+    }
+
+    /// Drop a session's buffer
+    pub fn clear(&self, session_id: &str) {
+        self.buffers.lock().unwrap_or_else(|e| e.into_inner()).remove(session_id);
+    }
+}
+
+impl Default for OutputReplay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Drop chunks past the age limit, then the oldest until at most `keep` remain
+fn prune(chunks: &mut VecDeque<ReplayChunk>, now: Instant, keep: usize) {
+    while chunks
+        .front()
+        .is_some_and(|c| now.duration_since(c.at) > MAX_CHUNK_AGE)
+    {
+        chunks.pop_front();
+    }
+    while chunks.len() > keep {
+        chunks.pop_front();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_ring_buffer() {
+        let replay = OutputReplay::new();
+        replay.set_max_chunks(3);
+        for i in 0..5 {
+            replay.record("s1", "m1", &i.to_string(), false);
+        }
+        replay.record("s2", "m2", "other", true);
+
+        let chunks = replay.recent("s1");
+        assert_eq!(chunks.iter().map(|c| c.seq).collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(chunks[2].chunk, "4");
+        assert_eq!(replay.recent("s2").len(), 1);
+
+        replay.set_max_chunks(1);
+        assert_eq!(replay.recent("s1")[0].seq, 4);
+
+        replay.clear("s1");
+        assert!(replay.recent("s1").is_empty());
+
+        replay.set_max_chunks(0);
+        replay.record("s1", "m1", "ignored", false);
+        assert!(replay.recent("s1").is_empty());
+    }
+}
@@ -2,14 +2,26 @@
 //!
 //! Commands for file watching and activity feed management.
 
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tauri::{AppHandle, State};
 use serde::Serialize;
-use sqlx::Row;
+use sqlx::{Row, SqlitePool};
 
+use crate::db::settings::{
+    self, ACTIVITY_MAX_ROWS_PER_SESSION, ACTIVITY_RETENTION_DAYS, FILE_DIFF_MAX_BYTES, WATCHER_CHANNEL_CAPACITY,
+};
 use crate::error::AppError;
+use crate::paths::PathNormalizer;
+use crate::state::file_watcher::{WatcherStatus, DEFAULT_MAX_WATCHED_DIRS, DOTFILES_PATTERN};
+use crate::state::watcher_benchmark::{self, WatcherBenchmarkReport};
 use crate::state::AppState;
 
+use super::policy::session_policy;
+use super::session::fetch_session;
+use super::settings::{get_value, SettingKey, MAX_DEBOUNCE_MS};
+
 /// Activity entry from database
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -20,22 +32,100 @@ pub struct ActivityEntry {
     pub operation: String,
     pub source: String,
     pub timestamp: String,
+    /// Where a renamed file was before
+    pub old_path: Option<String>,
+}
+
+/// File watcher event channel statistics
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileWatcherStatsResponse {
+    pub channel_capacity: usize,
+    /// Events dropped because the channel was full since the app started
+    pub dropped_events: u64,
+}
+
+/// A project's file watcher settings
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectWatchSettings {
+    pub project_id: String,
+    /// Added to the global ignore patterns
+    pub ignore_patterns: Vec<String>,
+    /// Overrides the global debounce; None uses it
+    pub debounce_ms: Option<u64>,
+    pub include_dotfiles: bool,
+    /// None while the project uses the defaults
+    pub updated_at: Option<String>,
 }
 
+/// Largest accepted event channel capacity
+const MAX_CHANNEL_CAPACITY: usize = 1_000_000;
+
+/// Largest accepted per-project watched directory limit
+const MAX_WATCHED_DIRS_LIMIT: usize = 1_000_000;
+
+/// Days activity entries are kept by default
+pub const DEFAULT_ACTIVITY_RETENTION_DAYS: u32 = 90;
+
+/// Activity entries kept per session by default
+pub const DEFAULT_ACTIVITY_MAX_ROWS: u32 = 10_000;
+
+/// How often old activity is pruned while the app runs
+const ACTIVITY_PRUNE_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
 /// Start watching a directory for file changes
+///
+/// Watches the session's working directory when no path is given. The
+/// session's project watch settings are applied on top of the global ones.
 #[tauri::command]
 pub async fn file_watcher_start(
     app: AppHandle,
     state: State<'_, AppState>,
     session_id: String,
-    path: String,
+    path: Option<String>,
     ignore_patterns: Option<Vec<String>>,
 ) -> Result<(), AppError> {
-    let path = PathBuf::from(&path);
+    state.command_metrics.measure("file_watcher_start", async {
+        if state.safe_mode {
+            return Err(AppError::invalid_input("File watching is disabled in safe mode"));
+        }
 
-    state.file_watcher
-        .start_watching(app, session_id, path, ignore_patterns)
-        .await
+        let path = match path {
+            Some(path) => PathBuf::from(path),
+            None => PathBuf::from(fetch_session(&state.db, &session_id).await?.working_directory),
+        };
+        let policy = session_policy(&state.db, &session_id).await?;
+        let project = session_watch_settings(&state.db, &session_id).await?;
+
+        // Patterns from settings apply to every watcher, then the project's
+        let configured: Vec<String> = serde_json::from_value(
+            get_value(&state.db, SettingKey::IgnorePatterns).await?,
+        )
+        .unwrap_or_default();
+        let mut patterns: Vec<String> = configured
+            .into_iter()
+            .chain(project.iter().flat_map(|p| p.ignore_patterns.iter().cloned()))
+            .chain(ignore_patterns.unwrap_or_default())
+            .collect();
+        if project.as_ref().is_some_and(|p| !p.include_dotfiles) {
+            patterns.push(DOTFILES_PATTERN.to_string());
+        }
+        let ignore_patterns = (!patterns.is_empty()).then_some(patterns);
+
+        let max_dirs = session_watcher_limit(&state.db, &session_id).await?;
+        let debounce_ms = project.and_then(|p| p.debounce_ms);
+
+        state.file_watcher
+            .start_watching(app, session_id.clone(), path, ignore_patterns, max_dirs, debounce_ms)
+            .await?;
+        state.file_watcher
+            .set_delete_guard(&session_id, !policy.allow_file_deletes)
+            .await;
+
+        Ok(())
+    })
+    .await
 }
 
 /// Stop watching for a session
@@ -44,9 +134,376 @@ pub async fn file_watcher_stop(
     state: State<'_, AppState>,
     session_id: String,
 ) -> Result<(), AppError> {
-    state.file_watcher
-        .stop_watching(&session_id)
+    state.command_metrics.measure("file_watcher_stop", async {
+        state.file_watcher
+            .stop_watching(&session_id)
+            .await
+    })
+    .await
+}
+
+/// Watch another directory for a session, such as a sibling package in a monorepo
+///
+/// The session's watcher must already be running; the directory shares its
+/// ignore patterns and directory limit.
+#[tauri::command]
+pub async fn file_watcher_add_path(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    session_id: String,
+    path: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("file_watcher_add_path", async {
+        if state.safe_mode {
+            return Err(AppError::invalid_input("File watching is disabled in safe mode"));
+        }
+
+        state.file_watcher
+            .add_path(app, &session_id, PathBuf::from(&path))
+            .await
+    })
+    .await
+}
+
+/// Stop watching a directory added with `file_watcher_add_path`
+#[tauri::command]
+pub async fn file_watcher_remove_path(
+    state: State<'_, AppState>,
+    session_id: String,
+    path: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("file_watcher_remove_path", async {
+        state.file_watcher
+            .remove_path(&session_id, Path::new(&path))
+            .await
+    })
+    .await
+}
+
+/// Get the largest file whose changes are diffed in file_changed events (0 = off)
+#[tauri::command]
+pub async fn file_watcher_get_diff_limit(
+    state: State<'_, AppState>,
+) -> Result<usize, AppError> {
+    state.command_metrics.measure("file_watcher_get_diff_limit", async {
+        Ok(state.file_watcher.diff_max_bytes())
+    })
+    .await
+}
+
+/// Set the largest file whose changes are diffed in file_changed events (0 = off)
+#[tauri::command]
+pub async fn file_watcher_set_diff_limit(
+    state: State<'_, AppState>,
+    max_bytes: usize,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("file_watcher_set_diff_limit", async {
+        settings::set_setting(&state.db, FILE_DIFF_MAX_BYTES, &max_bytes.to_string()).await?;
+        state.file_watcher.set_diff_max_bytes(max_bytes).await;
+
+        Ok(())
+    })
+    .await
+}
+
+/// Get the file watcher's channel capacity and dropped event count
+#[tauri::command]
+pub async fn file_watcher_get_stats(
+    state: State<'_, AppState>,
+) -> Result<FileWatcherStatsResponse, AppError> {
+    state.command_metrics.measure("file_watcher_get_stats", async {
+        Ok(FileWatcherStatsResponse {
+            channel_capacity: state.file_watcher.channel_capacity(),
+            dropped_events: state.file_watcher.dropped_events(),
+        })
+    })
+    .await
+}
+
+/// Get each watcher's directory usage and budget, or one session's
+#[tauri::command]
+pub async fn file_watcher_status(
+    state: State<'_, AppState>,
+    session_id: Option<String>,
+) -> Result<Vec<WatcherStatus>, AppError> {
+    state.command_metrics.measure("file_watcher_status", async {
+        Ok(state.file_watcher.status(session_id.as_deref()).await)
+    })
+    .await
+}
+
+/// Get the most directories a watcher may watch in a project
+#[tauri::command]
+pub async fn file_watcher_get_project_limit(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<usize, AppError> {
+    state.command_metrics.measure("file_watcher_get_project_limit", async {
+        let limit: Option<i64> = sqlx::query_scalar("SELECT watcher_max_dirs FROM projects WHERE id = ?")
+            .bind(&project_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or_else(|| AppError::database_not_found("Project", &project_id))?;
+
+        Ok(limit.map(|l| l as usize).unwrap_or(DEFAULT_MAX_WATCHED_DIRS))
+    })
+    .await
+}
+
+/// Set the most directories a watcher may watch in a project (None = default)
+///
+/// Applies to watchers started afterwards.
+#[tauri::command]
+pub async fn file_watcher_set_project_limit(
+    state: State<'_, AppState>,
+    project_id: String,
+    max_dirs: Option<usize>,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("file_watcher_set_project_limit", async {
+        if let Some(max_dirs) = max_dirs {
+            if !(1..=MAX_WATCHED_DIRS_LIMIT).contains(&max_dirs) {
+                return Err(AppError::invalid_input(format!(
+                    "Watched directory limit must be between 1 and {}",
+                    MAX_WATCHED_DIRS_LIMIT
+                )));
+            }
+        }
+
+        let result = sqlx::query("UPDATE projects SET watcher_max_dirs = ?, updated_at = ? WHERE id = ?")
+            .bind(max_dirs.map(|m| m as i64))
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(&project_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Project", &project_id));
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// The directory limit for a session's watcher, from its project
+async fn session_watcher_limit(db: &sqlx::SqlitePool, session_id: &str) -> Result<usize, AppError> {
+    let limit: Option<i64> = sqlx::query_scalar(
+        r#"
+        SELECT p.watcher_max_dirs FROM sessions s
+        JOIN projects p ON p.id = s.project_id
+        WHERE s.id = ?
+        "#,
+    )
+    .bind(session_id)
+    .fetch_optional(db)
+    .await?
+    .flatten();
+
+    Ok(limit.map(|l| l as usize).unwrap_or(DEFAULT_MAX_WATCHED_DIRS))
+}
+
+/// Get a project's file watcher settings, or the defaults if none are stored
+#[tauri::command]
+pub async fn project_watch_settings_get(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<ProjectWatchSettings, AppError> {
+    state.command_metrics.measure("project_watch_settings_get", async {
+        let exists: Option<i64> = sqlx::query_scalar("SELECT 1 FROM projects WHERE id = ?")
+            .bind(&project_id)
+            .fetch_optional(&state.db)
+            .await?;
+        if exists.is_none() {
+            return Err(AppError::database_not_found("Project", &project_id));
+        }
+
+        Ok(load_watch_settings(&state.db, "project_id = ?", &project_id)
+            .await?
+            .unwrap_or(ProjectWatchSettings {
+                project_id,
+                ignore_patterns: Vec::new(),
+                debounce_ms: None,
+                include_dotfiles: true,
+                updated_at: None,
+            }))
+    })
+    .await
+}
+
+/// Store a project's file watcher settings, replacing any stored before
+///
+/// Applies to watchers started afterwards.
+#[tauri::command]
+pub async fn project_watch_settings_set(
+    state: State<'_, AppState>,
+    project_id: String,
+    ignore_patterns: Option<Vec<String>>,
+    debounce_ms: Option<u64>,
+    include_dotfiles: Option<bool>,
+) -> Result<ProjectWatchSettings, AppError> {
+    state.command_metrics.measure("project_watch_settings_set", async {
+        let ignore_patterns: Vec<String> = ignore_patterns
+            .unwrap_or_default()
+            .into_iter()
+            .map(|p| p.trim().to_string())
+            .collect();
+        if ignore_patterns.iter().any(|p| p.is_empty()) {
+            return Err(AppError::invalid_input("Ignore patterns cannot be empty"));
+        }
+        if debounce_ms.is_some_and(|ms| ms > MAX_DEBOUNCE_MS) {
+            return Err(AppError::invalid_input(format!(
+                "Debounce must be at most {} ms",
+                MAX_DEBOUNCE_MS
+            )));
+        }
+
+        let exists: Option<i64> = sqlx::query_scalar("SELECT 1 FROM projects WHERE id = ?")
+            .bind(&project_id)
+            .fetch_optional(&state.db)
+            .await?;
+        if exists.is_none() {
+            return Err(AppError::database_not_found("Project", &project_id));
+        }
+
+        let include_dotfiles = include_dotfiles.unwrap_or(true);
+        let now = chrono::Utc::now().to_rfc3339();
+        sqlx::query(
+            r#"
+            INSERT INTO project_watch_settings (project_id, ignore_patterns, debounce_ms, include_dotfiles, updated_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(project_id) DO UPDATE SET
+                ignore_patterns = excluded.ignore_patterns,
+                debounce_ms = excluded.debounce_ms,
+                include_dotfiles = excluded.include_dotfiles,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&project_id)
+        .bind(serde_json::to_string(&ignore_patterns)?)
+        .bind(debounce_ms.map(|ms| ms as i64))
+        .bind(include_dotfiles)
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
+
+        Ok(ProjectWatchSettings {
+            project_id,
+            ignore_patterns,
+            debounce_ms,
+            include_dotfiles,
+            updated_at: Some(now),
+        })
+    })
+    .await
+}
+
+/// Remove a project's file watcher settings so it uses the defaults
+#[tauri::command]
+pub async fn project_watch_settings_delete(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("project_watch_settings_delete", async {
+        sqlx::query("DELETE FROM project_watch_settings WHERE project_id = ?")
+            .bind(&project_id)
+            .execute(&state.db)
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
+
+/// The watch settings of a session's project, if it has any stored
+async fn session_watch_settings(
+    db: &SqlitePool,
+    session_id: &str,
+) -> Result<Option<ProjectWatchSettings>, AppError> {
+    load_watch_settings(
+        db,
+        "project_id = (SELECT project_id FROM sessions WHERE id = ?)",
+        session_id,
+    )
+    .await
+}
+
+/// Load stored watch settings matching a filter on `project_watch_settings`
+async fn load_watch_settings(
+    db: &SqlitePool,
+    filter: &str,
+    value: &str,
+) -> Result<Option<ProjectWatchSettings>, AppError> {
+    let row = sqlx::query_as::<_, (String, String, Option<i64>, bool, String)>(&format!(
+        "SELECT project_id, ignore_patterns, debounce_ms, include_dotfiles, updated_at FROM project_watch_settings WHERE {}",
+        filter
+    ))
+    .bind(value)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row.map(|(project_id, ignore_patterns, debounce_ms, include_dotfiles, updated_at)| {
+        ProjectWatchSettings {
+            project_id,
+            ignore_patterns: serde_json::from_str(&ignore_patterns).unwrap_or_default(),
+            debounce_ms: debounce_ms.map(|ms| ms.max(0) as u64),
+            include_dotfiles,
+            updated_at: Some(updated_at),
+        }
+    }))
+}
+
+/// Set how many file events are buffered before further events are dropped
+///
+/// Returns `false` when watching has already started, in which case the new
+/// capacity takes effect on the next launch.
+#[tauri::command]
+pub async fn file_watcher_set_channel_capacity(
+    state: State<'_, AppState>,
+    capacity: usize,
+) -> Result<bool, AppError> {
+    state.command_metrics.measure("file_watcher_set_channel_capacity", async {
+        if !(1..=MAX_CHANNEL_CAPACITY).contains(&capacity) {
+            return Err(AppError::invalid_input(format!(
+                "Channel capacity must be between 1 and {}",
+                MAX_CHANNEL_CAPACITY
+            )));
+        }
+
+        settings::set_setting(&state.db, WATCHER_CHANNEL_CAPACITY, &capacity.to_string()).await?;
+        Ok(state.file_watcher.set_channel_capacity(capacity).await)
+    })
+    .await
+}
+
+/// Measure file watcher latency, debouncing, and dropped events
+///
+/// Writes `file_count` files (default 200) `writes_per_file` times each
+/// (default 5) in a scratch directory under `path`, which is removed afterwards.
+#[tauri::command]
+pub async fn file_watcher_benchmark(
+    state: State<'_, AppState>,
+    path: String,
+    file_count: Option<usize>,
+    writes_per_file: Option<usize>,
+) -> Result<WatcherBenchmarkReport, AppError> {
+    state.command_metrics.measure("file_watcher_benchmark", async {
+        let file_count = file_count.unwrap_or(200);
+        let writes_per_file = writes_per_file.unwrap_or(5);
+        if !(1..=5000).contains(&file_count) {
+            return Err(AppError::invalid_input("File count must be between 1 and 5000"));
+        }
+        if !(1..=50).contains(&writes_per_file) {
+            return Err(AppError::invalid_input("Writes per file must be between 1 and 50"));
+        }
+
+        let path = PathBuf::from(path);
+        tokio::task::spawn_blocking(move || {
+            watcher_benchmark::run_benchmark(&path, file_count, writes_per_file)
+        })
         .await
+        .map_err(|e| AppError::new(crate::error::ErrorCode::Unknown, format!("Benchmark task failed: {}", e)))?
+    })
+    .await
 }
 
 /// Get activity entries for a session
@@ -58,74 +515,144 @@ pub async fn activity_get(
     limit: Option<i64>,
     offset: Option<i64>,
 ) -> Result<Vec<ActivityEntry>, AppError> {
-    let limit = limit.unwrap_or(100);
-    let offset = offset.unwrap_or(0);
+    state.command_metrics.measure("activity_get", async {
+        let limit = limit.unwrap_or(100);
+        let offset = offset.unwrap_or(0);
 
-    // Build query based on filter
-    let rows = if let Some(ref op_filter) = filter {
-        if op_filter == "all" {
-            sqlx::query(
-                r#"
-                SELECT id, session_id, path, operation, source, timestamp
-                FROM activity_log
-                WHERE session_id = ?
-                ORDER BY timestamp DESC
-                LIMIT ? OFFSET ?
-                "#
-            )
-            .bind(&session_id)
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(&state.db)
-            .await?
+        // Build query based on filter
+        let rows = if let Some(ref op_filter) = filter {
+            if op_filter == "all" {
+                sqlx::query(
+                    r#"
+                    SELECT id, session_id, path, operation, source, timestamp, old_path
+                    FROM activity_log
+                    WHERE session_id = ?
+                    ORDER BY timestamp DESC
+                    LIMIT ? OFFSET ?
+                    "#
+                )
+                .bind(&session_id)
+                .bind(limit)
+                .bind(offset)
+                .fetch_all(&state.db)
+                .await?
+            } else {
+                sqlx::query(
+                    r#"
+                    SELECT id, session_id, path, operation, source, timestamp, old_path
+                    FROM activity_log
+                    WHERE session_id = ? AND operation = ?
+                    ORDER BY timestamp DESC
+                    LIMIT ? OFFSET ?
+                    "#
+                )
+                .bind(&session_id)
+                .bind(op_filter)
+                .bind(limit)
+                .bind(offset)
+                .fetch_all(&state.db)
+                .await?
+            }
         } else {
             sqlx::query(
                 r#"
-                SELECT id, session_id, path, operation, source, timestamp
+                SELECT id, session_id, path, operation, source, timestamp, old_path
                 FROM activity_log
-                WHERE session_id = ? AND operation = ?
+                WHERE session_id = ?
                 ORDER BY timestamp DESC
                 LIMIT ? OFFSET ?
                 "#
             )
             .bind(&session_id)
-            .bind(op_filter)
             .bind(limit)
             .bind(offset)
             .fetch_all(&state.db)
             .await?
+        };
+
+        // Map rows to ActivityEntry
+        let entries: Vec<ActivityEntry> = rows
+            .iter()
+            .map(|row| ActivityEntry {
+                id: row.get("id"),
+                session_id: row.get("session_id"),
+                path: row.get("path"),
+                operation: row.get("operation"),
+                source: row.get("source"),
+                timestamp: row.get("timestamp"),
+                old_path: row.get("old_path"),
+            })
+            .collect();
+
+        Ok(entries)
+    })
+    .await
+}
+
+/// Get the file changes Claude made during an assistant message's turn
+///
+/// The turn runs from the user message that prompted it to the assistant
+/// message's last save.
+#[tauri::command]
+pub async fn message_related_activity(
+    state: State<'_, AppState>,
+    message_id: String,
+) -> Result<Vec<ActivityEntry>, AppError> {
+    state.command_metrics.measure("message_related_activity", async {
+        let (session_id, role, created_at, completed_at) = sqlx::query_as::<_, (String, String, String, Option<String>)>(
+            "SELECT session_id, role, created_at, completed_at FROM messages WHERE id = ?",
+        )
+        .bind(&message_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Message", &message_id))?;
+
+        if role != "assistant" {
+            return Err(AppError::invalid_input("Only assistant messages have related activity"));
         }
-    } else {
-        sqlx::query(
+
+        // The turn starts with the prompt, which is saved before the reply
+        let started_at: Option<String> = sqlx::query_scalar(
+            r#"
+            SELECT MAX(created_at) FROM messages
+            WHERE session_id = ? AND role = 'user' AND created_at <= ?
+            "#,
+        )
+        .bind(&session_id)
+        .bind(&created_at)
+        .fetch_one(&state.db)
+        .await?;
+        let started_at = started_at.unwrap_or_else(|| created_at.clone());
+        let completed_at = completed_at.unwrap_or(created_at);
+
+        let rows = sqlx::query(
             r#"
-            SELECT id, session_id, path, operation, source, timestamp
+            SELECT id, session_id, path, operation, source, timestamp, old_path
             FROM activity_log
-            WHERE session_id = ?
-            ORDER BY timestamp DESC
-            LIMIT ? OFFSET ?
-            "#
+            WHERE session_id = ? AND source = 'claude' AND timestamp >= ? AND timestamp <= ?
+            ORDER BY timestamp ASC
+            "#,
         )
         .bind(&session_id)
-        .bind(limit)
-        .bind(offset)
+        .bind(&started_at)
+        .bind(&completed_at)
         .fetch_all(&state.db)
-        .await?
-    };
-
-    // Map rows to ActivityEntry
-    let entries: Vec<ActivityEntry> = rows
-        .iter()
-        .map(|row| ActivityEntry {
-            id: row.get("id"),
-            session_id: row.get("session_id"),
-            path: row.get("path"),
-            operation: row.get("operation"),
-            source: row.get("source"),
-            timestamp: row.get("timestamp"),
-        })
-        .collect();
+        .await?;
 
-    Ok(entries)
+        Ok(rows
+            .iter()
+            .map(|row| ActivityEntry {
+                id: row.get("id"),
+                session_id: row.get("session_id"),
+                path: row.get("path"),
+                operation: row.get("operation"),
+                source: row.get("source"),
+                timestamp: row.get("timestamp"),
+                old_path: row.get("old_path"),
+            })
+            .collect())
+    })
+    .await
 }
 
 /// Clear activity for a session
@@ -134,15 +661,239 @@ pub async fn activity_clear(
     state: State<'_, AppState>,
     session_id: String,
 ) -> Result<(), AppError> {
-    sqlx::query("DELETE FROM activity_log WHERE session_id = ?")
-        .bind(&session_id)
-        .execute(&state.db)
-        .await?;
+    state.command_metrics.measure("activity_clear", async {
+        sqlx::query("DELETE FROM activity_log WHERE session_id = ?")
+            .bind(&session_id)
+            .execute(&state.db)
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
+
+/// A value and how many activity entries have it
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityCount {
+    pub key: String,
+    pub count: i64,
+}
 
-    Ok(())
+/// Activity totals for a session or project
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityStats {
+    pub total: i64,
+    /// By `created`, `modified`, and `deleted`
+    pub by_operation: Vec<ActivityCount>,
+    /// By `claude` and `external`
+    pub by_source: Vec<ActivityCount>,
+    /// By lowercase file extension, most changed first; files without one are keyed ""
+    pub by_extension: Vec<ActivityCount>,
+    /// By UTC hour, keyed like `2026-01-31T14:00:00+00:00`, oldest first
+    pub by_hour: Vec<ActivityCount>,
+}
+
+/// Count a session's or project's activity by operation, source, extension, and hour
+///
+/// Exactly one of `session_id` and `project_id` is required. `since` limits
+/// the counts to entries at or after an RFC 3339 timestamp.
+#[tauri::command]
+pub async fn activity_stats(
+    state: State<'_, AppState>,
+    session_id: Option<String>,
+    project_id: Option<String>,
+    since: Option<String>,
+) -> Result<ActivityStats, AppError> {
+    state.command_metrics.measure("activity_stats", async {
+        if session_id.is_some() == project_id.is_some() {
+            return Err(AppError::invalid_input("Pass either a session ID or a project ID"));
+        }
+
+        // Every breakdown counts the same rows. The text after a path's last
+        // '.' is its extension, unless it crosses a '/'.
+        let scope = r#"
+            WITH scoped AS (
+                SELECT
+                    a.path,
+                    a.operation,
+                    a.source,
+                    a.timestamp,
+                    substr(a.path, length(rtrim(a.path, replace(a.path, '.', ''))) + 1) AS tail
+                FROM activity_log a
+                JOIN sessions s ON s.id = a.session_id
+                WHERE (?1 IS NULL OR a.session_id = ?1)
+                  AND (?2 IS NULL OR s.project_id = ?2)
+                  AND (?3 IS NULL OR a.timestamp >= ?3)
+            )
+        "#;
+        let count_by = |key: &str, order: &str| {
+            format!(
+                "{} SELECT {} AS key, COUNT(*) AS count FROM scoped GROUP BY key ORDER BY {}",
+                scope, key, order
+            )
+        };
+        let extension = "CASE WHEN instr(path, '.') = 0 OR instr(tail, '/') > 0 THEN '' ELSE lower(tail) END";
+
+        let mut breakdowns = Vec::with_capacity(4);
+        for (key, order) in [
+            ("operation", "key"),
+            ("source", "key"),
+            (extension, "count DESC, key"),
+            ("substr(timestamp, 1, 13) || ':00:00+00:00'", "key"),
+        ] {
+            let rows = sqlx::query_as::<_, (String, i64)>(&count_by(key, order))
+                .bind(&session_id)
+                .bind(&project_id)
+                .bind(&since)
+                .fetch_all(&state.db)
+                .await?;
+            breakdowns.push(
+                rows.into_iter()
+                    .map(|(key, count)| ActivityCount { key, count })
+                    .collect::<Vec<_>>(),
+            );
+        }
+
+        let mut breakdowns = breakdowns.into_iter();
+        let by_operation = breakdowns.next().unwrap_or_default();
+        Ok(ActivityStats {
+            total: by_operation.iter().map(|c| c.count).sum(),
+            by_operation,
+            by_source: breakdowns.next().unwrap_or_default(),
+            by_extension: breakdowns.next().unwrap_or_default(),
+            by_hour: breakdowns.next().unwrap_or_default(),
+        })
+    })
+    .await
+}
+
+/// How much activity is kept
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityRetention {
+    /// Entries older than this are pruned (0 = forever)
+    pub days: u32,
+    /// Older entries past this count are pruned from each session (0 = no limit)
+    pub max_rows_per_session: u32,
+}
+
+/// Rows removed by a prune
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityPruneReport {
+    /// Entries older than the retention window
+    pub expired: u64,
+    /// Entries past a session's row limit
+    pub over_limit: u64,
+    pub total: u64,
+}
+
+/// Get how much activity is kept
+#[tauri::command]
+pub async fn activity_get_retention(state: State<'_, AppState>) -> Result<ActivityRetention, AppError> {
+    state.command_metrics.measure("activity_get_retention", async {
+        activity_retention(&state.db).await
+    })
+    .await
+}
+
+/// Set how much activity is kept; applies from the next prune
+#[tauri::command]
+pub async fn activity_set_retention(
+    state: State<'_, AppState>,
+    days: u32,
+    max_rows_per_session: u32,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("activity_set_retention", async {
+        if days > 3650 {
+            return Err(AppError::invalid_input("Retention must be at most 3650 days"));
+        }
+
+        settings::set_setting(&state.db, ACTIVITY_RETENTION_DAYS, &days.to_string()).await?;
+        settings::set_setting(&state.db, ACTIVITY_MAX_ROWS_PER_SESSION, &max_rows_per_session.to_string()).await
+    })
+    .await
+}
+
+/// Delete activity outside the retention policy now
+#[tauri::command]
+pub async fn activity_prune(state: State<'_, AppState>) -> Result<ActivityPruneReport, AppError> {
+    state.command_metrics.measure("activity_prune", async {
+        prune_activity(&state.db).await
+    })
+    .await
+}
+
+/// Prune activity now and then periodically for the lifetime of the app
+pub fn spawn_activity_prune_job(db: SqlitePool) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = prune_activity(&db).await {
+                log::warn!("Failed to prune activity: {}", e);
+            }
+            tokio::time::sleep(ACTIVITY_PRUNE_INTERVAL).await;
+        }
+    });
+}
+
+/// Delete activity older than the retention window or past a session's row limit
+pub(crate) async fn prune_activity(db: &SqlitePool) -> Result<ActivityPruneReport, AppError> {
+    let retention = activity_retention(db).await?;
+    let mut report = ActivityPruneReport::default();
+
+    if retention.days > 0 {
+        let cutoff = (chrono::Utc::now() - chrono::Duration::days(retention.days.into())).to_rfc3339();
+        report.expired = sqlx::query("DELETE FROM activity_log WHERE timestamp < ?")
+            .bind(&cutoff)
+            .execute(db)
+            .await?
+            .rows_affected();
+    }
+
+    if retention.max_rows_per_session > 0 {
+        report.over_limit = sqlx::query(
+            r#"
+            DELETE FROM activity_log WHERE id IN (
+                SELECT id FROM (
+                    SELECT id, ROW_NUMBER() OVER (PARTITION BY session_id ORDER BY timestamp DESC) AS position
+                    FROM activity_log
+                )
+                WHERE position > ?
+            )
+            "#,
+        )
+        .bind(retention.max_rows_per_session)
+        .execute(db)
+        .await?
+        .rows_affected();
+    }
+
+    report.total = report.expired + report.over_limit;
+    if report.total > 0 {
+        log::info!("Pruned {} activity entries", report.total);
+    }
+    Ok(report)
+}
+
+async fn activity_retention(db: &SqlitePool) -> Result<ActivityRetention, AppError> {
+    Ok(ActivityRetention {
+        days: setting_u32(db, ACTIVITY_RETENTION_DAYS).await?.unwrap_or(DEFAULT_ACTIVITY_RETENTION_DAYS),
+        max_rows_per_session: setting_u32(db, ACTIVITY_MAX_ROWS_PER_SESSION)
+            .await?
+            .unwrap_or(DEFAULT_ACTIVITY_MAX_ROWS),
+    })
+}
+
+async fn setting_u32(db: &SqlitePool, key: &str) -> Result<Option<u32>, AppError> {
+    Ok(settings::get_setting(db, key).await?.and_then(|v| v.parse().ok()))
 }
 
 /// Save an activity entry to the database
+///
+/// Changes seen by a file watcher are logged by the watcher itself; this is
+/// for entries the watcher can't see.
 #[tauri::command]
 pub async fn activity_save(
     state: State<'_, AppState>,
@@ -151,25 +902,39 @@ pub async fn activity_save(
     operation: String,
     source: String,
 ) -> Result<String, AppError> {
-    let id = uuid::Uuid::new_v4().to_string();
-    let timestamp = chrono::Utc::now().to_rfc3339();
+    state.command_metrics.measure("activity_save", async {
+        // Store paths relative to the session's working directory so entries
+        // from different sources line up
+        let working_directory: String = sqlx::query_scalar(
+            "SELECT working_directory FROM sessions WHERE id = ?",
+        )
+        .bind(&session_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Session", &session_id))?;
+        let path = PathNormalizer::new(&working_directory).normalize(&path);
 
-    sqlx::query(
-        r#"
-        INSERT INTO activity_log (id, session_id, path, operation, source, timestamp)
-        VALUES (?, ?, ?, ?, ?, ?)
-        "#
-    )
-    .bind(&id)
-    .bind(&session_id)
-    .bind(&path)
-    .bind(&operation)
-    .bind(&source)
-    .bind(&timestamp)
-    .execute(&state.db)
-    .await?;
+        let id = uuid::Uuid::new_v4().to_string();
+        let timestamp = chrono::Utc::now().to_rfc3339();
 
-    Ok(id)
+        sqlx::query(
+            r#"
+            INSERT INTO activity_log (id, session_id, path, operation, source, timestamp)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(&id)
+        .bind(&session_id)
+        .bind(&path)
+        .bind(&operation)
+        .bind(&source)
+        .bind(&timestamp)
+        .execute(&state.db)
+        .await?;
+
+        Ok(id)
+    })
+    .await
 }
 
 /// Record that Claude modified a file (for source attribution)
@@ -180,9 +945,270 @@ pub async fn file_watcher_record_claude_write(
     session_id: String,
     path: String,
 ) -> Result<(), AppError> {
-    state.file_watcher
-        .record_claude_modification(&session_id, &path)
-        .await;
-    Ok(()
-    )
+    state.command_metrics.measure("file_watcher_record_claude_write", async {
+        state.file_watcher
+            .record_claude_modification(&session_id, &path)
+            .await;
+        Ok(()
+        )
+    })
+    .await
+}
+
+/// Render a session's file activity as a change report and write it to disk
+///
+/// `format` is `markdown` or `html`. Returns the path written.
+#[tauri::command]
+pub async fn activity_export(
+    state: State<'_, AppState>,
+    session_id: String,
+    format: String,
+    path: String,
+) -> Result<String, AppError> {
+    state.command_metrics.measure("activity_export", async {
+        let format = format.to_lowercase();
+        if format != "markdown" && format != "html" {
+            return Err(AppError::invalid_input("Format must be 'markdown' or 'html'"));
+        }
+
+        let output_path = PathBuf::from(&path);
+        if !output_path.is_absolute() {
+            return Err(AppError::invalid_input("Export path must be an absolute path"));
+        }
+
+        let (title, working_directory): (String, String) = sqlx::query_as(
+            "SELECT title, working_directory FROM sessions WHERE id = ?",
+        )
+        .bind(&session_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Session", &session_id))?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, session_id, path, operation, source, timestamp, old_path
+            FROM activity_log
+            WHERE session_id = ?
+            ORDER BY timestamp ASC
+            "#
+        )
+        .bind(&session_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        let entries: Vec<ActivityEntry> = rows
+            .iter()
+            .map(|row| ActivityEntry {
+                id: row.get("id"),
+                session_id: row.get("session_id"),
+                path: row.get("path"),
+                operation: row.get("operation"),
+                source: row.get("source"),
+                timestamp: row.get("timestamp"),
+                old_path: row.get("old_path"),
+            })
+            .collect();
+
+        // The user's prompts explain why the changes were made
+        let prompts: Vec<String> = sqlx::query_scalar(
+            "SELECT content FROM messages WHERE session_id = ? AND role = 'user' ORDER BY created_at ASC",
+        )
+        .bind(&session_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        let report = ChangeReport {
+            title,
+            working_directory,
+            prompts,
+            files: summarize_changes(&entries),
+            entries,
+        };
+
+        let rendered = if format == "html" {
+            render_html(&report)
+        } else {
+            render_markdown(&report)
+        };
+
+        tokio::fs::write(&output_path, rendered).await?;
+
+        Ok(output_path.to_string_lossy().to_string())
+    })
+    .await
+}
+
+/// Data for a rendered change report
+struct ChangeReport {
+    title: String,
+    working_directory: String,
+    prompts: Vec<String>,
+    files: Vec<FileChangeSummary>,
+    entries: Vec<ActivityEntry>,
+}
+
+/// Net change to a single file over a session
+#[derive(Debug, PartialEq)]
+struct FileChangeSummary {
+    path: String,
+    /// Operation describing the net effect (e.g. created then modified = created)
+    operation: String,
+    changes: usize,
+    by_claude: bool,
+}
+
+/// Collapse activity entries into one net change per file
+fn summarize_changes(entries: &[ActivityEntry]) -> Vec<FileChangeSummary> {
+    let mut files: BTreeMap<&str, FileChangeSummary> = BTreeMap::new();
+
+    for entry in entries {
+        let summary = files.entry(entry.path.as_str()).or_insert_with(|| FileChangeSummary {
+            path: entry.path.clone(),
+            operation: entry.operation.clone(),
+            changes: 0,
+            by_claude: false,
+        });
+
+        summary.changes += 1;
+        summary.by_claude |= entry.source == "claude";
+        summary.operation = match (summary.operation.as_str(), entry.operation.as_str()) {
+            // A file created during the session stays "created" until deleted
+            ("created", "modified") => "created".to_string(),
+            ("renamed", "modified") => "renamed".to_string(),
+            (_, op) => op.to_string(),
+        };
+    }
+
+    files.into_values().collect()
+}
+
+/// Render a change report as Markdown
+fn render_markdown(report: &ChangeReport) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("# Change report: {}\n\n", report.title));
+    out.push_str(&format!("Working directory: `{}`\n\n", report.working_directory));
+
+    if let (Some(first), Some(last)) = (report.entries.first(), report.entries.last()) {
+        out.push_str(&format!("Activity from {} to {}\n\n", first.timestamp, last.timestamp));
+    }
+
+    if !report.prompts.is_empty() {
+        out.push_str("## Requests\n\n");
+        for prompt in &report.prompts {
+            let quoted = prompt.lines().collect::<Vec<_>>().join("\n> ");
+            out.push_str(&format!("> {}\n\n", quoted));
+        }
+    }
+
+    out.push_str(&format!("## Files changed ({})\n\n", report.files.len()));
+    if report.files.is_empty() {
+        out.push_str("No file changes were recorded.\n");
+    } else {
+        out.push_str("| File | Change | Events | Source |\n");
+        out.push_str("| --- | --- | --- | --- |\n");
+        for file in &report.files {
+            out.push_str(&format!(
+                "| `{}` | {} | {} | {} |\n",
+                file.path,
+                file.operation,
+                file.changes,
+                if file.by_claude { "claude" } else { "external" },
+            ));
+        }
+    }
+
+    out
+}
+
+/// Render a change report as a standalone HTML page
+fn render_html(report: &ChangeReport) -> String {
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str(&format!("<title>Change report: {}</title>\n", escape_html(&report.title)));
+    out.push_str("</head>\n<body>\n");
+    out.push_str(&format!("<h1>Change report: {}</h1>\n", escape_html(&report.title)));
+    out.push_str(&format!(
+        "<p>Working directory: <code>{}</code></p>\n",
+        escape_html(&report.working_directory)
+    ));
+
+    if let (Some(first), Some(last)) = (report.entries.first(), report.entries.last()) {
+        out.push_str(&format!(
+            "<p>Activity from {} to {}</p>\n",
+            escape_html(&first.timestamp),
+            escape_html(&last.timestamp)
+        ));
+    }
+
+    if !report.prompts.is_empty() {
+        out.push_str("<h2>Requests</h2>\n");
+        for prompt in &report.prompts {
+            out.push_str(&format!("<blockquote><pre>{}</pre></blockquote>\n", escape_html(prompt)));
+        }
+    }
+
+    out.push_str(&format!("<h2>Files changed ({})</h2>\n", report.files.len()));
+    if report.files.is_empty() {
+        out.push_str("<p>No file changes were recorded.</p>\n");
+    } else {
+        out.push_str("<table>\n<tr><th>File</th><th>Change</th><th>Events</th><th>Source</th></tr>\n");
+        for file in &report.files {
+            out.push_str(&format!(
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                escape_html(&file.path),
+                escape_html(&file.operation),
+                file.changes,
+                if file.by_claude { "claude" } else { "external" },
+            ));
+        }
+        out.push_str("</table>\n");
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Escape text for inclusion in HTML
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, operation: &str, source: &str) -> ActivityEntry {
+        ActivityEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: "s".to_string(),
+            path: path.to_string(),
+            operation: operation.to_string(),
+            source: source.to_string(),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            old_path: None,
+        }
+    }
+
+    #[test]
+    fn test_summarize_changes_collapses_per_file() {
+        let entries = vec![
+            entry("src/new.rs", "created", "claude"),
+            entry("src/new.rs", "modified", "claude"),
+            entry("src/lib.rs", "modified", "external"),
+            entry("src/lib.rs", "deleted", "external"),
+        ];
+
+        let files = summarize_changes(&entries);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!(files[0].operation, "deleted");
+        assert!(!files[0].by_claude);
+        assert_eq!(files[1].path, "src/new.rs");
+        assert_eq!(files[1].operation, "created");
+        assert_eq!(files[1].changes, 2);
+        assert!(files[1].by_claude);
+    }
 }
@@ -0,0 +1,387 @@
+//! Dashboard Analytics Commands
+//!
+//! Burndown, velocity, and estimate accuracy computed from task history.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// Default number of completed sprints used for velocity
+const DEFAULT_VELOCITY_SPRINTS: i32 = 5;
+
+/// One day on a sprint burndown chart
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BurndownPoint {
+    pub date: String,
+    pub remaining_tasks: i32,
+    pub remaining_hours: f64,
+    /// Remaining tasks on a straight line from the sprint total to zero
+    pub ideal_tasks: f64,
+}
+
+/// Burndown for a single sprint
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SprintBurndownResponse {
+    pub sprint_id: String,
+    pub sprint_name: String,
+    pub points: Vec<BurndownPoint>,
+}
+
+/// Completed work in a finished sprint
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SprintVelocityResponse {
+    pub sprint_id: String,
+    pub sprint_name: String,
+    pub completed_tasks: i32,
+    pub completed_hours: f64,
+}
+
+/// Estimated vs actual hours for completed tasks
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EstimateAccuracyResponse {
+    /// Completed tasks that had both an estimate and a tracked start
+    pub task_count: i32,
+    pub estimated_hours: f64,
+    /// Elapsed hours from first moving to in progress until done
+    pub actual_hours: f64,
+}
+
+/// Project analytics response
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardAnalyticsResponse {
+    pub burndown: Option<SprintBurndownResponse>,
+    pub velocity: Vec<SprintVelocityResponse>,
+    pub average_velocity: f64,
+    pub estimates: EstimateAccuracyResponse,
+}
+
+/// A task's status over time, for replaying history
+struct TaskTimeline {
+    created_at: DateTime<Utc>,
+    estimated_hours: Option<f64>,
+    /// (changed_at, new status) in chronological order
+    transitions: Vec<(DateTime<Utc>, String)>,
+}
+
+impl TaskTimeline {
+    /// Status at a point in time, or None if the task didn't exist yet
+    fn status_at(&self, at: DateTime<Utc>) -> Option<&str> {
+        if self.created_at > at {
+            return None;
+        }
+        Some(
+            self.transitions
+                .iter()
+                .take_while(|(changed_at, _)| *changed_at <= at)
+                .last()
+                .map(|(_, status)| status.as_str())
+                .unwrap_or("todo"),
+        )
+    }
+}
+
+/// Compute burndown analytics for a project
+///
+/// Uses the given sprint, or the active sprint when none is given.
+#[tauri::command]
+pub async fn dashboard_analytics(
+    state: State<'_, AppState>,
+    project_id: String,
+    sprint_id: Option<String>,
+    velocity_sprints: Option<i32>,
+) -> Result<DashboardAnalyticsResponse, AppError> {
+    state.command_metrics.measure("dashboard_analytics", async {
+        let velocity_sprints = velocity_sprints.unwrap_or(DEFAULT_VELOCITY_SPRINTS).clamp(1, 50);
+
+        // Burndown
+        let sprint = if let Some(sid) = sprint_id {
+            sqlx::query_as::<_, (String, String, Option<String>, Option<String>, String)>(
+                "SELECT id, name, start_date, end_date, created_at FROM sprints WHERE id = ? AND project_id = ? AND deleted_at IS NULL",
+            )
+            .bind(&sid)
+            .bind(&project_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or_else(|| AppError::database_not_found("Sprint", &sid))
+            .map(Some)?
+        } else {
+            sqlx::query_as::<_, (String, String, Option<String>, Option<String>, String)>(
+                "SELECT id, name, start_date, end_date, created_at FROM sprints WHERE project_id = ? AND status = 'active' AND deleted_at IS NULL LIMIT 1",
+            )
+            .bind(&project_id)
+            .fetch_optional(&state.db)
+            .await?
+        };
+
+        let burndown = match sprint {
+            Some((id, name, start_date, end_date, created_at)) => {
+                let timelines = load_timelines(&state.db, "t.sprint_id = ?", &id).await?;
+                let start = start_date
+                    .as_deref()
+                    .and_then(parse_date)
+                    .or_else(|| parse_date(&created_at))
+                    .unwrap_or_else(|| Utc::now().date_naive());
+                let end = end_date
+                    .as_deref()
+                    .and_then(parse_date)
+                    .unwrap_or_else(|| Utc::now().date_naive())
+                    .max(start);
+
+                Some(SprintBurndownResponse {
+                    sprint_id: id,
+                    sprint_name: name,
+                    points: compute_burndown(&timelines, start, end),
+                })
+            }
+            None => None,
+        };
+
+        // Velocity over the most recently completed sprints
+        let velocity_rows = sqlx::query_as::<_, (String, String, i32, f64)>(
+            r#"
+            SELECT
+                s.id,
+                s.name,
+                COALESCE(SUM(CASE WHEN t.status = 'done' THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN t.status = 'done' THEN t.estimated_hours ELSE 0 END), 0.0)
+            FROM sprints s
+            LEFT JOIN tasks t ON t.sprint_id = s.id AND t.deleted_at IS NULL
+            WHERE s.project_id = ? AND s.status = 'completed' AND s.deleted_at IS NULL
+            GROUP BY s.id
+            ORDER BY COALESCE(s.end_date, s.updated_at) DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(&project_id)
+        .bind(velocity_sprints)
+        .fetch_all(&state.db)
+        .await?;
+
+        let velocity: Vec<SprintVelocityResponse> = velocity_rows
+            .into_iter()
+            .rev()
+            .map(|v| SprintVelocityResponse {
+                sprint_id: v.0,
+                sprint_name: v.1,
+                completed_tasks: v.2,
+                completed_hours: v.3,
+            })
+            .collect();
+
+        let average_velocity = if velocity.is_empty() {
+            0.0
+        } else {
+            velocity.iter().map(|v| v.completed_tasks as f64).sum::<f64>() / velocity.len() as f64
+        };
+
+        // Estimated vs actual for completed tasks
+        let timelines = load_timelines(&state.db, "t.project_id = ? AND t.status = 'done'", &project_id).await?;
+        let estimates = compute_estimate_accuracy(&timelines);
+
+        Ok(DashboardAnalyticsResponse {
+            burndown,
+            velocity,
+            average_velocity,
+            estimates,
+        })
+    })
+    .await
+}
+
+/// Load task timelines matching a filter on the `tasks t` table
+async fn load_timelines(
+    db: &sqlx::SqlitePool,
+    filter: &str,
+    value: &str,
+) -> Result<Vec<TaskTimeline>, AppError> {
+    let tasks = sqlx::query_as::<_, (String, String, Option<f64>)>(&format!(
+        "SELECT t.id, t.created_at, t.estimated_hours FROM tasks t WHERE t.deleted_at IS NULL AND {}",
+        filter
+    ))
+    .bind(value)
+    .fetch_all(db)
+    .await?;
+
+    let history = sqlx::query_as::<_, (String, String, String)>(&format!(
+        r#"
+        SELECT h.task_id, h.changed_at, h.new_value
+        FROM task_history h
+        JOIN tasks t ON t.id = h.task_id
+        WHERE h.field = 'status' AND h.new_value IS NOT NULL AND t.deleted_at IS NULL AND {}
+        ORDER BY h.changed_at ASC
+        "#,
+        filter
+    ))
+    .bind(value)
+    .fetch_all(db)
+    .await?;
+
+    let mut transitions: HashMap<String, Vec<(DateTime<Utc>, String)>> = HashMap::new();
+    for (task_id, changed_at, status) in history {
+        if let Some(at) = parse_timestamp(&changed_at) {
+            transitions.entry(task_id).or_default().push((at, status));
+        }
+    }
+
+    Ok(tasks
+        .into_iter()
+        .filter_map(|(id, created_at, estimated_hours)| {
+            Some(TaskTimeline {
+                created_at: parse_timestamp(&created_at)?,
+                estimated_hours,
+                transitions: transitions.remove(&id).unwrap_or_default(),
+            })
+        })
+        .collect())
+}
+
+/// Replay task history to get remaining work at the end of each day
+fn compute_burndown(timelines: &[TaskTimeline], start: NaiveDate, end: NaiveDate) -> Vec<BurndownPoint> {
+    let days: Vec<NaiveDate> = start.iter_days().take_while(|d| *d <= end).collect();
+    let total = timelines.len() as f64;
+    let span = (days.len().max(2) - 1) as f64;
+
+    days.iter()
+        .enumerate()
+        .map(|(index, day)| {
+            let end_of_day = day
+                .succ_opt()
+                .unwrap_or(*day)
+                .and_hms_opt(0, 0, 0)
+                .map(|d| d.and_utc())
+                .unwrap_or_else(Utc::now);
+
+            let remaining: Vec<&TaskTimeline> = timelines
+                .iter()
+                .filter(|t| matches!(t.status_at(end_of_day), Some(status) if status != "done"))
+                .collect();
+
+            BurndownPoint {
+                date: day.to_string(),
+                remaining_tasks: remaining.len() as i32,
+                remaining_hours: remaining.iter().filter_map(|t| t.estimated_hours).sum(),
+                ideal_tasks: total * (1.0 - index as f64 / span),
+            }
+        })
+        .collect()
+}
+
+/// Compare estimates against elapsed in-progress time for completed tasks
+fn compute_estimate_accuracy(timelines: &[TaskTimeline]) -> EstimateAccuracyResponse {
+    let mut result = EstimateAccuracyResponse {
+        task_count: 0,
+        estimated_hours: 0.0,
+        actual_hours: 0.0,
+    };
+
+    for timeline in timelines {
+        let Some(estimate) = timeline.estimated_hours else {
+            continue;
+        };
+        let started = timeline
+            .transitions
+            .iter()
+            .find(|(_, status)| status == "in_progress")
+            .map(|(at, _)| *at);
+        let finished = timeline
+            .transitions
+            .iter()
+            .rev()
+            .find(|(_, status)| status == "done")
+            .map(|(at, _)| *at);
+
+        if let (Some(started), Some(finished)) = (started, finished) {
+            if finished > started {
+                result.task_count += 1;
+                result.estimated_hours += estimate;
+                result.actual_hours += (finished - started).num_seconds() as f64 / 3600.0;
+            }
+        }
+    }
+
+    result
+}
+
+/// Parse an RFC 3339 timestamp
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+/// Parse a date stored either as `YYYY-MM-DD` or as a full timestamp
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .or_else(|| parse_timestamp(value).map(|d| d.date_naive()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        parse_timestamp(value).unwrap()
+    }
+
+    #[test]
+    fn test_burndown_replays_history() {
+        let timelines = vec![
+            TaskTimeline {
+                created_at: at("2024-01-01T09:00:00Z"),
+                estimated_hours: Some(2.0),
+                transitions: vec![
+                    (at("2024-01-01T09:00:00Z"), "todo".to_string()),
+                    (at("2024-01-02T10:00:00Z"), "done".to_string()),
+                ],
+            },
+            TaskTimeline {
+                created_at: at("2024-01-01T09:00:00Z"),
+                estimated_hours: Some(3.0),
+                transitions: vec![
+                    (at("2024-01-02T11:00:00Z"), "done".to_string()),
+                    // Reopened after completion
+                    (at("2024-01-03T08:00:00Z"), "in_progress".to_string()),
+                ],
+            },
+        ];
+
+        let points = compute_burndown(
+            &timelines,
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 1, 3).unwrap(),
+        );
+
+        let remaining: Vec<i32> = points.iter().map(|p| p.remaining_tasks).collect();
+        assert_eq!(remaining, vec![2, 0, 1]);
+        assert_eq!(points[2].remaining_hours, 3.0);
+        assert_eq!(points[0].ideal_tasks, 2.0);
+        assert_eq!(points[2].ideal_tasks, 0.0);
+    }
+
+    #[test]
+    fn test_estimate_accuracy_uses_in_progress_to_done() {
+        let timelines = vec![TaskTimeline {
+            created_at: at("2024-01-01T09:00:00Z"),
+            estimated_hours: Some(4.0),
+            transitions: vec![
+                (at("2024-01-01T10:00:00Z"), "in_progress".to_string()),
+                (at("2024-01-01T16:00:00Z"), "done".to_string()),
+            ],
+        }];
+
+        let result = compute_estimate_accuracy(&timelines);
+        assert_eq!(result.task_count, 1);
+        assert_eq!(result.estimated_hours, 4.0);
+        assert_eq!(result.actual_hours, 6.0);
+    }
+}
@@ -0,0 +1,118 @@
+//! API Server Commands
+//!
+//! Commands for turning the local HTTP API on and off and managing its
+//! token. See [`crate::api`] for the routes it serves.
+
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use crate::api::{self, MIN_PORT};
+use crate::db::settings::{self, API_SERVER_ENABLED, API_SERVER_PORT};
+use crate::error::AppError;
+use crate::state::AppState;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiServerStatus {
+    /// Whether the server is turned on, so it starts with the app
+    pub enabled: bool,
+    /// Whether the server is listening; false while enabled means it failed to start
+    pub running: bool,
+    pub port: u16,
+    /// Base URL of the routes, while running
+    pub url: Option<String>,
+}
+
+async fn status(state: &AppState) -> Result<ApiServerStatus, AppError> {
+    let (enabled, configured_port) = api::configured(&state.db).await?;
+    let running_port = state.api_server.port().await;
+    let port = running_port.unwrap_or(configured_port);
+    Ok(ApiServerStatus {
+        enabled,
+        running: running_port.is_some(),
+        port,
+        url: running_port.map(|port| format!("http://127.0.0.1:{}/api", port)),
+    })
+}
+
+/// Report whether the API server is on and where it listens
+#[tauri::command]
+pub async fn api_server_status(
+    state: State<'_, AppState>,
+) -> Result<ApiServerStatus, AppError> {
+    state.command_metrics.measure("api_server_status", async {
+        status(&state).await
+    })
+    .await
+}
+
+/// Turn the API server on, optionally on a new port, and start it now
+#[tauri::command]
+pub async fn api_server_enable(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    port: Option<u16>,
+) -> Result<ApiServerStatus, AppError> {
+    state.command_metrics.measure("api_server_enable", async {
+        let port = match port {
+            Some(port) if port < MIN_PORT => {
+                return Err(AppError::invalid_input(format!(
+                    "API server port must be at least {}",
+                    MIN_PORT
+                )));
+            }
+            Some(port) => port,
+            None => api::configured(&state.db).await?.1,
+        };
+
+        state.api_server.start(&app, port, api::token().await?).await?;
+        settings::set_setting(&state.db, API_SERVER_PORT, &port.to_string()).await?;
+        settings::set_setting(&state.db, API_SERVER_ENABLED, "true").await?;
+
+        status(&state).await
+    })
+    .await
+}
+
+/// Stop the API server and keep it off on later launches
+#[tauri::command]
+pub async fn api_server_disable(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ApiServerStatus, AppError> {
+    state.command_metrics.measure("api_server_disable", async {
+        settings::set_setting(&state.db, API_SERVER_ENABLED, "false").await?;
+        state.api_server.stop(&app).await;
+
+        status(&state).await
+    })
+    .await
+}
+
+/// Get the token API clients authenticate with, creating one if needed
+#[tauri::command]
+pub async fn api_server_get_token(
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    state.command_metrics.measure("api_server_get_token", async {
+        api::token().await
+    })
+    .await
+}
+
+/// Replace the API token, restarting a running server so the old one stops working
+#[tauri::command]
+pub async fn api_server_rotate_token(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    state.command_metrics.measure("api_server_rotate_token", async {
+        let token = api::rotate_token().await?;
+        if let Some(port) = state.api_server.port().await {
+            state.api_server.start(&app, port, token.clone()).await?;
+        }
+
+        Ok(token)
+    })
+    .await
+}
@@ -0,0 +1,178 @@
+//! Session Auto-Commit Commands
+//!
+//! When enabled for a session, the files Claude wrote during each turn are
+//! committed onto a dedicated branch as the turn completes. The checked-out
+//! branch, the index, and the working tree are left alone.
+
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::checkpoints::RESTORE_TOOL;
+use crate::error::AppError;
+use crate::events::{emit_event, event_names, GitCommitCreatedPayload};
+use crate::git;
+use crate::state::AppState;
+
+/// Prefix of the branches sessions are committed onto
+const BRANCH_PREFIX: &str = "wingman/session-";
+
+/// Longest commit subject taken from the prompt
+const MAX_SUBJECT_CHARS: usize = 72;
+
+/// Enable or disable committing Claude's changes after each turn
+#[tauri::command]
+pub async fn session_set_autocommit(
+    state: State<'_, AppState>,
+    session_id: String,
+    enabled: bool,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("session_set_autocommit", async {
+        // Only changes made from now on are committed
+        let result = sqlx::query("UPDATE sessions SET autocommit = ?, autocommit_at = ? WHERE id = ?")
+            .bind(enabled)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(&session_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Session", &session_id));
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Commit the files Claude wrote since the last auto-commit, if enabled
+///
+/// `prompt` is the message that started the turn and becomes the commit
+/// subject.
+pub(crate) async fn autocommit_turn(
+    app: &AppHandle,
+    session_id: &str,
+    prompt: Option<&str>,
+) -> Result<(), AppError> {
+    let state = app.state::<AppState>();
+
+    let session = sqlx::query_as::<_, (String, bool, Option<String>)>(
+        "SELECT working_directory, autocommit, autocommit_at FROM sessions WHERE id = ?",
+    )
+    .bind(session_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some((working_directory, true, since)) = session else {
+        return Ok(());
+    };
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let files: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT DISTINCT file_path FROM checkpoints
+        WHERE session_id = ? AND tool_name != ? AND created_at > ? AND created_at <= ?
+        ORDER BY file_path
+        "#,
+    )
+    .bind(session_id)
+    .bind(RESTORE_TOOL)
+    .bind(since.unwrap_or_default())
+    .bind(&now)
+    .fetch_all(&state.db)
+    .await?;
+
+    sqlx::query("UPDATE sessions SET autocommit_at = ? WHERE id = ?")
+        .bind(&now)
+        .bind(session_id)
+        .execute(&state.db)
+        .await?;
+
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    let branch = branch_name(session_id);
+    let message = commit_message(prompt, session_id);
+    let paths: Vec<PathBuf> = files.iter().map(PathBuf::from).collect();
+
+    let Some(commit) = git::commit_paths_to_branch(Path::new(&working_directory), &branch, &paths, &message).await? else {
+        return Ok(());
+    };
+
+    log::info!("Auto-committed {} file(s) for session {} as {}", files.len(), session_id, commit);
+
+    let _ = emit_event(
+        app,
+        event_names::GIT_COMMIT_CREATED,
+        GitCommitCreatedPayload {
+            session_id: session_id.to_string(),
+            branch,
+            commit,
+            message,
+            files,
+        },
+    );
+
+    Ok(())
+}
+
+/// Branch a session's changes are committed onto
+fn branch_name(session_id: &str) -> String {
+    let short: String = session_id.chars().filter(|c| c.is_ascii_alphanumeric()).take(8).collect();
+    format!("{}{}", BRANCH_PREFIX, short)
+}
+
+/// Build a commit message from the first line of the prompt
+fn commit_message(prompt: Option<&str>, session_id: &str) -> String {
+    let first_line = prompt
+        .and_then(|p| p.lines().map(str::trim).find(|line| !line.is_empty()))
+        .unwrap_or("Claude changes");
+
+    let mut subject: String = first_line.chars().take(MAX_SUBJECT_CHARS).collect();
+    if first_line.chars().count() > MAX_SUBJECT_CHARS {
+        subject.pop();
+        subject.push('…');
+    }
+
+    format!("{}\n\nWingman session {}", subject, session_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_branch_name() {
+        assert_eq!(
+            branch_name("3f2a9c1e-77d4-4b0a-9e2f-0c1d2e3f4a5b"),
+            "wingman/session-3f2a9c1e"
+        );
+        // Separators and anything unsafe in a ref are dropped before truncating
+        assert_eq!(branch_name("ab-cd_ef/gh..ij~kl"), "wingman/session-abcdefgh");
+        assert_eq!(branch_name("s1"), "wingman/session-s1");
+    }
+
+    #[test]
+    fn test_commit_message_uses_first_non_empty_line() {
+        assert_eq!(
+            commit_message(Some("\n   \n  Fix the login redirect  \nand add a test"), "s1"),
+            "Fix the login redirect\n\nWingman session s1"
+        );
+        assert_eq!(commit_message(None, "s1"), "Claude changes\n\nWingman session s1");
+        assert_eq!(commit_message(Some(" \n\t"), "s1"), "Claude changes\n\nWingman session s1");
+    }
+
+    #[test]
+    fn test_commit_message_truncates_long_subjects() {
+        let exact = "a".repeat(MAX_SUBJECT_CHARS);
+        assert_eq!(commit_message(Some(&exact), "s1"), format!("{}\n\nWingman session s1", exact));
+
+        let long = "é".repeat(MAX_SUBJECT_CHARS + 10);
+        let message = commit_message(Some(&long), "s1");
+        let subject = message.lines().next().unwrap();
+        assert_eq!(subject.chars().count(), MAX_SUBJECT_CHARS);
+        assert!(subject.ends_with('…'));
+        assert_eq!(subject.chars().filter(|c| *c == 'é').count(), MAX_SUBJECT_CHARS - 1);
+    }
+}
@@ -0,0 +1,912 @@
+//! Project Bundle Commands
+//!
+//! Exports a project with its milestones, sprints, tasks, sessions,
+//! messages, and file activity to a single JSON file, and imports such a
+//! file as a new project. Imported rows get fresh IDs, so a bundle can be
+//! imported next to the project it came from. Trashed rows are left out.
+//!
+//! A bundle may come from someone else, so imported sessions never skip
+//! permission prompts, whatever their settings were when exported.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use super::session::validate_cli_settings;
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// Bundle format version written by this build
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Permission mode that runs tools without asking
+const BYPASS_PERMISSION_MODE: &str = "bypassPermissions";
+
+/// CLI argument that runs tools without asking
+const SKIP_PERMISSIONS_ARG: &str = "--dangerously-skip-permissions";
+
+/// A project and everything recorded about it
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectBundle {
+    pub version: u32,
+    pub exported_at: String,
+    pub project: BundleProject,
+    pub task_statuses: Vec<BundleTaskStatus>,
+    pub labels: Vec<BundleLabel>,
+    pub milestones: Vec<BundleMilestone>,
+    pub sprints: Vec<BundleSprint>,
+    pub tasks: Vec<BundleTask>,
+    /// `(task_id, depends_on_task_id)` pairs
+    pub task_dependencies: Vec<(String, String)>,
+    /// `(task_id, label_id)` pairs
+    pub task_labels: Vec<(String, String)>,
+    pub sessions: Vec<BundleSession>,
+    pub messages: Vec<BundleMessage>,
+    pub activity: Vec<BundleActivity>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleProject {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub root_path: String,
+    pub preview_url: Option<String>,
+    pub dod_enforcement: String,
+    pub allow_file_deletes: bool,
+    pub allow_shell: bool,
+    pub allow_network: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleTaskStatus {
+    pub id: String,
+    pub name: String,
+    pub category: String,
+    pub sort_order: i64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleLabel {
+    pub id: String,
+    pub name: String,
+    pub color: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleMilestone {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub target_date: Option<String>,
+    pub status: String,
+    pub sort_order: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleSprint {
+    pub id: String,
+    pub milestone_id: Option<String>,
+    pub name: String,
+    pub description: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub status: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleTask {
+    pub id: String,
+    pub sprint_id: Option<String>,
+    pub parent_task_id: Option<String>,
+    pub status_id: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: String,
+    pub priority: String,
+    pub estimated_hours: Option<f64>,
+    /// Missing from bundles exported before tasks had due dates
+    #[serde(default)]
+    pub due_date: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// A session, without its CLI session ID or worktree, which only exist on
+/// the machine it ran on
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleSession {
+    pub id: String,
+    pub title: String,
+    pub working_directory: String,
+    pub model: Option<String>,
+    pub permission_mode: Option<String>,
+    pub cli_args: Option<String>,
+    pub system_prompt: Option<String>,
+    pub archived_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleMessage {
+    pub id: String,
+    pub session_id: String,
+    pub role: String,
+    pub content: String,
+    pub tool_usage: Option<String>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleActivity {
+    pub id: String,
+    pub session_id: String,
+    pub path: String,
+    pub operation: String,
+    pub source: String,
+    pub timestamp: String,
+}
+
+/// Result of importing a bundle
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleImportResponse {
+    pub project_id: String,
+    pub milestones: usize,
+    pub sprints: usize,
+    pub tasks: usize,
+    pub sessions: usize,
+    pub messages: usize,
+    pub activity: usize,
+}
+
+/// Write a project bundle to `path`
+///
+/// Returns the path written.
+#[tauri::command]
+pub async fn project_export_bundle(
+    state: State<'_, AppState>,
+    project_id: String,
+    path: String,
+) -> Result<String, AppError> {
+    state.command_metrics.measure("project_export_bundle", async {
+        let output_path = PathBuf::from(&path);
+        if !output_path.is_absolute() {
+            return Err(AppError::invalid_input("Export path must be an absolute path"));
+        }
+
+        let bundle = read_bundle(&state.db, &project_id).await?;
+        tokio::fs::write(&output_path, serde_json::to_vec_pretty(&bundle)?).await?;
+
+        Ok(output_path.to_string_lossy().to_string())
+    })
+    .await
+}
+
+/// Import a project bundle from `path` as a new project
+///
+/// `root_path` moves the project to a different directory on this machine;
+/// session working directories and activity paths under the old root are
+/// moved with it.
+#[tauri::command]
+pub async fn project_import_bundle(
+    state: State<'_, AppState>,
+    path: String,
+    root_path: Option<String>,
+) -> Result<BundleImportResponse, AppError> {
+    state.command_metrics.measure("project_import_bundle", async {
+        let input_path = PathBuf::from(&path);
+        if !input_path.is_absolute() {
+            return Err(AppError::invalid_input("Bundle path must be an absolute path"));
+        }
+
+        let content = tokio::fs::read(&input_path).await?;
+        let bundle: ProjectBundle = serde_json::from_slice(&content)?;
+        if bundle.version > BUNDLE_VERSION {
+            return Err(AppError::invalid_input(format!(
+                "Bundle version {} is newer than this app supports",
+                bundle.version
+            )));
+        }
+
+        if let Some(root) = &root_path {
+            if !Path::new(root).is_absolute() {
+                return Err(AppError::invalid_input("Root path must be an absolute path"));
+            }
+        }
+
+        write_bundle(&state.db, &bundle, root_path.as_deref()).await
+    })
+    .await
+}
+
+/// Read a live project and its rows into a bundle
+async fn read_bundle(db: &SqlitePool, project_id: &str) -> Result<ProjectBundle, AppError> {
+    let row = sqlx::query(
+        r#"
+        SELECT id, name, description, root_path, preview_url, dod_enforcement,
+               allow_file_deletes, allow_shell, allow_network, created_at, updated_at
+        FROM projects
+        WHERE id = ? AND deleted_at IS NULL
+        "#,
+    )
+    .bind(project_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| AppError::database_not_found("Project", project_id))?;
+
+    let project = BundleProject {
+        id: row.get("id"),
+        name: row.get("name"),
+        description: row.get("description"),
+        root_path: row.get("root_path"),
+        preview_url: row.get("preview_url"),
+        dod_enforcement: row.get("dod_enforcement"),
+        allow_file_deletes: row.get("allow_file_deletes"),
+        allow_shell: row.get("allow_shell"),
+        allow_network: row.get("allow_network"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    };
+
+    let task_statuses = sqlx::query(
+        "SELECT id, name, category, sort_order, created_at FROM task_statuses WHERE project_id = ? ORDER BY sort_order",
+    )
+    .bind(project_id)
+    .fetch_all(db)
+    .await?
+    .iter()
+    .map(|row| BundleTaskStatus {
+        id: row.get("id"),
+        name: row.get("name"),
+        category: row.get("category"),
+        sort_order: row.get("sort_order"),
+        created_at: row.get("created_at"),
+    })
+    .collect();
+
+    let labels = sqlx::query("SELECT id, name, color, created_at FROM labels WHERE project_id = ? ORDER BY name")
+        .bind(project_id)
+        .fetch_all(db)
+        .await?
+        .iter()
+        .map(|row| BundleLabel {
+            id: row.get("id"),
+            name: row.get("name"),
+            color: row.get("color"),
+            created_at: row.get("created_at"),
+        })
+        .collect();
+
+    let milestones = sqlx::query(
+        r#"
+        SELECT id, name, description, target_date, status, sort_order, created_at, updated_at
+        FROM milestones
+        WHERE project_id = ? AND deleted_at IS NULL
+        ORDER BY sort_order
+        "#,
+    )
+    .bind(project_id)
+    .fetch_all(db)
+    .await?
+    .iter()
+    .map(|row| BundleMilestone {
+        id: row.get("id"),
+        name: row.get("name"),
+        description: row.get("description"),
+        target_date: row.get("target_date"),
+        status: row.get("status"),
+        sort_order: row.get("sort_order"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+    .collect();
+
+    let sprints = sqlx::query(
+        r#"
+        SELECT id, milestone_id, name, description, start_date, end_date, status, created_at, updated_at
+        FROM sprints
+        WHERE project_id = ? AND deleted_at IS NULL
+        ORDER BY created_at
+        "#,
+    )
+    .bind(project_id)
+    .fetch_all(db)
+    .await?
+    .iter()
+    .map(|row| BundleSprint {
+        id: row.get("id"),
+        milestone_id: row.get("milestone_id"),
+        name: row.get("name"),
+        description: row.get("description"),
+        start_date: row.get("start_date"),
+        end_date: row.get("end_date"),
+        status: row.get("status"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+    .collect();
+
+    let tasks = sqlx::query(
+        r#"
+        SELECT id, sprint_id, parent_task_id, status_id, title, description, status, priority,
+               estimated_hours, due_date, created_at, updated_at
+        FROM tasks
+        WHERE project_id = ? AND deleted_at IS NULL
+        ORDER BY created_at
+        "#,
+    )
+    .bind(project_id)
+    .fetch_all(db)
+    .await?
+    .iter()
+    .map(|row| BundleTask {
+        id: row.get("id"),
+        sprint_id: row.get("sprint_id"),
+        parent_task_id: row.get("parent_task_id"),
+        status_id: row.get("status_id"),
+        title: row.get("title"),
+        description: row.get("description"),
+        status: row.get("status"),
+        priority: row.get("priority"),
+        estimated_hours: row.get("estimated_hours"),
+        due_date: row.get("due_date"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+    .collect();
+
+    let task_dependencies = sqlx::query_as::<_, (String, String)>(
+        r#"
+        SELECT d.task_id, d.depends_on_task_id FROM task_dependencies d
+        JOIN tasks t ON t.id = d.task_id
+        JOIN tasks dt ON dt.id = d.depends_on_task_id
+        WHERE t.project_id = ? AND t.deleted_at IS NULL AND dt.deleted_at IS NULL
+        "#,
+    )
+    .bind(project_id)
+    .fetch_all(db)
+    .await?;
+
+    let task_labels = sqlx::query_as::<_, (String, String)>(
+        r#"
+        SELECT tl.task_id, tl.label_id FROM task_labels tl
+        JOIN tasks t ON t.id = tl.task_id
+        WHERE t.project_id = ? AND t.deleted_at IS NULL
+        "#,
+    )
+    .bind(project_id)
+    .fetch_all(db)
+    .await?;
+
+    let sessions = sqlx::query(
+        r#"
+        SELECT id, title, working_directory, model, permission_mode, cli_args, system_prompt, archived_at, created_at, updated_at
+        FROM sessions
+        WHERE project_id = ?
+        ORDER BY created_at
+        "#,
+    )
+    .bind(project_id)
+    .fetch_all(db)
+    .await?
+    .iter()
+    .map(|row| BundleSession {
+        id: row.get("id"),
+        title: row.get("title"),
+        working_directory: row.get("working_directory"),
+        model: row.get("model"),
+        permission_mode: row.get("permission_mode"),
+        cli_args: row.get("cli_args"),
+        system_prompt: row.get("system_prompt"),
+        archived_at: row.get("archived_at"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+    .collect();
+
+    let messages = sqlx::query(
+        r#"
+        SELECT m.id, m.session_id, m.role, m.content, m.tool_usage, m.created_at, m.completed_at
+        FROM messages m
+        JOIN sessions s ON s.id = m.session_id
+        WHERE s.project_id = ?
+        ORDER BY m.created_at
+        "#,
+    )
+    .bind(project_id)
+    .fetch_all(db)
+    .await?
+    .iter()
+    .map(|row| BundleMessage {
+        id: row.get("id"),
+        session_id: row.get("session_id"),
+        role: row.get("role"),
+        content: row.get("content"),
+        tool_usage: row.get("tool_usage"),
+        created_at: row.get("created_at"),
+        completed_at: row.get("completed_at"),
+    })
+    .collect();
+
+    let activity = sqlx::query(
+        r#"
+        SELECT a.id, a.session_id, a.path, a.operation, a.source, a.timestamp
+        FROM activity_log a
+        JOIN sessions s ON s.id = a.session_id
+        WHERE s.project_id = ?
+        ORDER BY a.timestamp
+        "#,
+    )
+    .bind(project_id)
+    .fetch_all(db)
+    .await?
+    .iter()
+    .map(|row| BundleActivity {
+        id: row.get("id"),
+        session_id: row.get("session_id"),
+        path: row.get("path"),
+        operation: row.get("operation"),
+        source: row.get("source"),
+        timestamp: row.get("timestamp"),
+    })
+    .collect();
+
+    Ok(ProjectBundle {
+        version: BUNDLE_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        project,
+        task_statuses,
+        labels,
+        milestones,
+        sprints,
+        tasks,
+        task_dependencies,
+        task_labels,
+        sessions,
+        messages,
+        activity,
+    })
+}
+
+/// Insert a bundle as a new project in one transaction
+async fn write_bundle(
+    db: &SqlitePool,
+    bundle: &ProjectBundle,
+    root_path: Option<&str>,
+) -> Result<BundleImportResponse, AppError> {
+    // New IDs keyed by the bundle's IDs, which are UUIDs across all tables
+    let mut ids: HashMap<&str, String> = HashMap::new();
+    let project_id = assign_ids(&mut ids, [bundle.project.id.as_str()]).remove(0);
+    let status_ids = assign_ids(&mut ids, bundle.task_statuses.iter().map(|s| s.id.as_str()));
+    let label_ids = assign_ids(&mut ids, bundle.labels.iter().map(|l| l.id.as_str()));
+    let milestone_ids = assign_ids(&mut ids, bundle.milestones.iter().map(|m| m.id.as_str()));
+    let sprint_ids = assign_ids(&mut ids, bundle.sprints.iter().map(|s| s.id.as_str()));
+    let task_ids = assign_ids(&mut ids, bundle.tasks.iter().map(|t| t.id.as_str()));
+    let session_ids = assign_ids(&mut ids, bundle.sessions.iter().map(|s| s.id.as_str()));
+
+    let cli_settings = bundle
+        .sessions
+        .iter()
+        .map(import_cli_settings)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // References to rows missing from the bundle are dropped
+    let remap = |old: &Option<String>| old.as_deref().and_then(|old| ids.get(old).cloned());
+
+    let old_root = bundle.project.root_path.as_str();
+    let new_root = root_path.unwrap_or(old_root);
+    let mut tx = db.begin().await?;
+
+    let project = &bundle.project;
+    sqlx::query(
+        r#"
+        INSERT INTO projects (id, name, description, root_path, preview_url, dod_enforcement,
+                              allow_file_deletes, allow_shell, allow_network, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&project_id)
+    .bind(&project.name)
+    .bind(&project.description)
+    .bind(new_root)
+    .bind(&project.preview_url)
+    .bind(&project.dod_enforcement)
+    .bind(project.allow_file_deletes)
+    .bind(project.allow_shell)
+    .bind(project.allow_network)
+    .bind(&project.created_at)
+    .bind(&project.updated_at)
+    .execute(&mut *tx)
+    .await?;
+
+    for (status, id) in bundle.task_statuses.iter().zip(&status_ids) {
+        sqlx::query(
+            "INSERT INTO task_statuses (id, project_id, name, category, sort_order, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id)
+        .bind(&project_id)
+        .bind(&status.name)
+        .bind(&status.category)
+        .bind(status.sort_order)
+        .bind(&status.created_at)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for (label, id) in bundle.labels.iter().zip(&label_ids) {
+        sqlx::query("INSERT INTO labels (id, project_id, name, color, created_at) VALUES (?, ?, ?, ?, ?)")
+            .bind(id)
+            .bind(&project_id)
+            .bind(&label.name)
+            .bind(&label.color)
+            .bind(&label.created_at)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    for (milestone, id) in bundle.milestones.iter().zip(&milestone_ids) {
+        sqlx::query(
+            r#"
+            INSERT INTO milestones (id, project_id, name, description, target_date, status, sort_order, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(&project_id)
+        .bind(&milestone.name)
+        .bind(&milestone.description)
+        .bind(&milestone.target_date)
+        .bind(&milestone.status)
+        .bind(milestone.sort_order)
+        .bind(&milestone.created_at)
+        .bind(&milestone.updated_at)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for (sprint, id) in bundle.sprints.iter().zip(&sprint_ids) {
+        sqlx::query(
+            r#"
+            INSERT INTO sprints (id, project_id, milestone_id, name, description, start_date, end_date, status, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(&project_id)
+        .bind(remap(&sprint.milestone_id))
+        .bind(&sprint.name)
+        .bind(&sprint.description)
+        .bind(&sprint.start_date)
+        .bind(&sprint.end_date)
+        .bind(&sprint.status)
+        .bind(&sprint.created_at)
+        .bind(&sprint.updated_at)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for (task, id) in bundle.tasks.iter().zip(&task_ids) {
+        sqlx::query(
+            r#"
+            INSERT INTO tasks (id, project_id, sprint_id, status_id, title, description, status, priority, estimated_hours, due_date, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(&project_id)
+        .bind(remap(&task.sprint_id))
+        .bind(remap(&task.status_id))
+        .bind(&task.title)
+        .bind(&task.description)
+        .bind(&task.status)
+        .bind(&task.priority)
+        .bind(task.estimated_hours)
+        .bind(&task.due_date)
+        .bind(&task.created_at)
+        .bind(&task.updated_at)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO task_history (id, task_id, project_id, field, old_value, new_value, changed_by, changed_at)
+            VALUES (?, ?, ?, 'status', NULL, ?, 'system', ?)
+            "#,
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(id)
+        .bind(&project_id)
+        .bind(&task.status)
+        .bind(&task.created_at)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // Parents may come later in the bundle, so link once every task exists
+    for (task, id) in bundle.tasks.iter().zip(&task_ids) {
+        if let Some(parent_id) = remap(&task.parent_task_id) {
+            sqlx::query("UPDATE tasks SET parent_task_id = ? WHERE id = ?")
+                .bind(parent_id)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+
+    for (task_id, depends_on) in &bundle.task_dependencies {
+        if let (Some(task_id), Some(depends_on)) = (ids.get(task_id.as_str()), ids.get(depends_on.as_str())) {
+            sqlx::query("INSERT OR IGNORE INTO task_dependencies (task_id, depends_on_task_id) VALUES (?, ?)")
+                .bind(task_id)
+                .bind(depends_on)
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+
+    for (task_id, label_id) in &bundle.task_labels {
+        if let (Some(task_id), Some(label_id)) = (ids.get(task_id.as_str()), ids.get(label_id.as_str())) {
+            sqlx::query("INSERT OR IGNORE INTO task_labels (task_id, label_id) VALUES (?, ?)")
+                .bind(task_id)
+                .bind(label_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+
+    for ((session, id), (permission_mode, cli_args)) in bundle.sessions.iter().zip(&session_ids).zip(&cli_settings) {
+        sqlx::query(
+            r#"
+            INSERT INTO sessions (id, title, working_directory, project_id, model, permission_mode, cli_args, system_prompt, archived_at, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(&session.title)
+        .bind(rebase_path(&session.working_directory, old_root, new_root))
+        .bind(&project_id)
+        .bind(&session.model)
+        .bind(permission_mode)
+        .bind(cli_args)
+        .bind(&session.system_prompt)
+        .bind(&session.archived_at)
+        .bind(&session.created_at)
+        .bind(&session.updated_at)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let mut messages = 0;
+    for message in &bundle.messages {
+        let Some(session_id) = ids.get(message.session_id.as_str()) else {
+            continue;
+        };
+        sqlx::query(
+            r#"
+            INSERT INTO messages (id, session_id, role, content, tool_usage, created_at, completed_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(session_id)
+        .bind(&message.role)
+        .bind(&message.content)
+        .bind(&message.tool_usage)
+        .bind(&message.created_at)
+        .bind(&message.completed_at)
+        .execute(&mut *tx)
+        .await?;
+        messages += 1;
+    }
+
+    let mut activity = 0;
+    for entry in &bundle.activity {
+        let Some(session_id) = ids.get(entry.session_id.as_str()) else {
+            continue;
+        };
+        sqlx::query(
+            r#"
+            INSERT INTO activity_log (id, session_id, path, operation, source, timestamp)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(session_id)
+        .bind(rebase_path(&entry.path, old_root, new_root))
+        .bind(&entry.operation)
+        .bind(&entry.source)
+        .bind(&entry.timestamp)
+        .execute(&mut *tx)
+        .await?;
+        activity += 1;
+    }
+
+    tx.commit().await?;
+
+    Ok(BundleImportResponse {
+        project_id,
+        milestones: milestone_ids.len(),
+        sprints: sprint_ids.len(),
+        tasks: task_ids.len(),
+        sessions: session_ids.len(),
+        messages,
+        activity,
+    })
+}
+
+/// Validate an imported session's permission mode and CLI arguments
+///
+/// Returns the permission mode and the arguments as stored, without any
+/// setting that skips permission prompts. Fails when the settings would be
+/// rejected for a session created here.
+fn import_cli_settings(session: &BundleSession) -> Result<(Option<String>, String), AppError> {
+    let invalid = |e: AppError| {
+        AppError::invalid_input(format!("Session \"{}\" in the bundle: {}", session.title, e.message))
+    };
+
+    let mut args: Vec<String> = match session.cli_args.as_deref() {
+        Some(args) => serde_json::from_str(args)
+            .map_err(|_| invalid(AppError::invalid_input("CLI arguments must be a list of strings")))?,
+        None => Vec::new(),
+    };
+    validate_cli_settings(session.permission_mode.as_deref(), &args).map_err(invalid)?;
+
+    let permission_mode = session
+        .permission_mode
+        .clone()
+        .filter(|mode| mode != BYPASS_PERMISSION_MODE);
+    args.retain(|arg| arg != SKIP_PERMISSIONS_ARG);
+
+    Ok((permission_mode, serde_json::to_string(&args)?))
+}
+
+/// Give each bundle ID a fresh one, returning the new IDs in order
+fn assign_ids<'a>(ids: &mut HashMap<&'a str, String>, old_ids: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    old_ids
+        .into_iter()
+        .map(|old| {
+            let id = uuid::Uuid::new_v4().to_string();
+            ids.insert(old, id.clone());
+            id
+        })
+        .collect()
+}
+
+/// Move a path under `old_root` to the same place under `new_root`
+///
+/// Paths outside `old_root` are returned unchanged.
+fn rebase_path(path: &str, old_root: &str, new_root: &str) -> String {
+    match Path::new(path).strip_prefix(old_root) {
+        Ok(relative) if relative.as_os_str().is_empty() => new_root.to_string(),
+        Ok(relative) => Path::new(new_root).join(relative).to_string_lossy().to_string(),
+        Err(_) => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebase_path() {
+        assert_eq!(rebase_path("/old/app", "/old/app", "/new/app"), "/new/app");
+        assert_eq!(rebase_path("/old/app/src/main.rs", "/old/app", "/new/app"), "/new/app/src/main.rs");
+        assert_eq!(rebase_path("/old/application", "/old/app", "/new/app"), "/old/application");
+        assert_eq!(rebase_path("/tmp/scratch", "/old/app", "/new/app"), "/tmp/scratch");
+    }
+
+    fn bundle_with_session(permission_mode: Option<&str>, cli_args: &[&str]) -> ProjectBundle {
+        let now = "2026-10-15T09:30:00+00:00".to_string();
+        ProjectBundle {
+            version: BUNDLE_VERSION,
+            exported_at: now.clone(),
+            project: BundleProject {
+                id: "p1".to_string(),
+                name: "Imported".to_string(),
+                description: None,
+                root_path: "/old/app".to_string(),
+                preview_url: None,
+                dod_enforcement: "off".to_string(),
+                allow_file_deletes: true,
+                allow_shell: true,
+                allow_network: true,
+                created_at: now.clone(),
+                updated_at: now.clone(),
+            },
+            task_statuses: Vec::new(),
+            labels: Vec::new(),
+            milestones: Vec::new(),
+            sprints: Vec::new(),
+            tasks: Vec::new(),
+            task_dependencies: Vec::new(),
+            task_labels: Vec::new(),
+            sessions: vec![BundleSession {
+                id: "s1".to_string(),
+                title: "Session".to_string(),
+                working_directory: "/old/app".to_string(),
+                model: None,
+                permission_mode: permission_mode.map(str::to_string),
+                cli_args: Some(serde_json::to_string(cli_args).unwrap()),
+                system_prompt: None,
+                archived_at: None,
+                created_at: now.clone(),
+                updated_at: now,
+            }],
+            messages: Vec::new(),
+            activity: Vec::new(),
+        }
+    }
+
+    async fn project_count(db: &SqlitePool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM projects").fetch_one(db).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_invalid_cli_settings() {
+        let db = crate::db::test_pool().await;
+
+        let error = write_bundle(&db, &bundle_with_session(Some("yolo"), &[]), None).await.unwrap_err();
+        assert!(error.message.contains("Invalid permission mode"));
+        let error = write_bundle(&db, &bundle_with_session(None, &["--print"]), None).await.unwrap_err();
+        assert!(error.message.contains("--print"));
+
+        let mut bundle = bundle_with_session(None, &[]);
+        bundle.sessions[0].cli_args = Some("--verbose".to_string());
+        assert!(write_bundle(&db, &bundle, None).await.is_err());
+
+        assert_eq!(project_count(&db).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_import_drops_permission_bypass() {
+        let db = crate::db::test_pool().await;
+        let bundle = bundle_with_session(
+            Some("bypassPermissions"),
+            &["--dangerously-skip-permissions", "--max-turns", "5"],
+        );
+        let imported = write_bundle(&db, &bundle, Some("/new/app")).await.unwrap();
+        assert_eq!(imported.sessions, 1);
+
+        let (permission_mode, cli_args, working_directory): (Option<String>, String, String) =
+            sqlx::query_as("SELECT permission_mode, cli_args, working_directory FROM sessions WHERE project_id = ?")
+                .bind(&imported.project_id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(permission_mode, None);
+        assert_eq!(cli_args, r#"["--max-turns","5"]"#);
+        assert_eq!(working_directory, "/new/app");
+
+        let bundle = bundle_with_session(Some("plan"), &[]);
+        let imported = write_bundle(&db, &bundle, None).await.unwrap();
+        let permission_mode: Option<String> =
+            sqlx::query_scalar("SELECT permission_mode FROM sessions WHERE project_id = ?")
+                .bind(&imported.project_id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(permission_mode.as_deref(), Some("plan"));
+    }
+}
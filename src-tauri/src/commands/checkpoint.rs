@@ -0,0 +1,213 @@
+//! Checkpoint Commands
+//!
+//! Commands for listing and restoring the file snapshots taken before
+//! Claude's writes.
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::checkpoints;
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// Checkpoint data returned to frontend
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointResponse {
+    pub id: String,
+    pub session_id: String,
+    pub file_path: String,
+    /// Whether the file existed when the snapshot was taken
+    pub existed: bool,
+    pub tool_name: String,
+    pub restored_at: Option<String>,
+    pub created_at: String,
+}
+
+/// A captured version of a file, as it was before a write
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileVersionResponse {
+    /// Checkpoint ID, passed to `file_restore_version`
+    pub version_id: String,
+    pub session_id: String,
+    pub file_path: String,
+    /// Whether the file existed in this version
+    pub existed: bool,
+    /// `claude` for versions captured before Claude's writes, `restore` for
+    /// versions captured before a restore
+    pub source: String,
+    pub tool_name: String,
+    pub created_at: String,
+}
+
+/// List a session's checkpoints, newest first
+#[tauri::command]
+pub async fn checkpoint_list(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Vec<CheckpointResponse>, AppError> {
+    state.command_metrics.measure("checkpoint_list", async {
+        let rows = sqlx::query_as::<_, (String, String, String, Option<String>, String, Option<String>, String)>(
+            r#"
+            SELECT id, session_id, file_path, snapshot_path, tool_name, restored_at, created_at
+            FROM checkpoints
+            WHERE session_id = ?
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(&session_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|c| CheckpointResponse {
+                id: c.0,
+                session_id: c.1,
+                file_path: c.2,
+                existed: c.3.is_some(),
+                tool_name: c.4,
+                restored_at: c.5,
+                created_at: c.6,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Restore one file to a checkpoint. Returns the restored path.
+#[tauri::command]
+pub async fn checkpoint_restore_file(
+    state: State<'_, AppState>,
+    checkpoint_id: String,
+) -> Result<String, AppError> {
+    state.command_metrics.measure("checkpoint_restore_file", async {
+        checkpoints::restore(&state.db, &state.data_dir, &checkpoint_id).await
+    })
+    .await
+}
+
+/// Undo Claude's writes in a session
+///
+/// Every file written since `since_checkpoint_id` (or since the session
+/// started) goes back to its content before the first of those writes.
+/// Returns the restored paths.
+#[tauri::command]
+pub async fn checkpoint_restore_all(
+    state: State<'_, AppState>,
+    session_id: String,
+    since_checkpoint_id: Option<String>,
+) -> Result<Vec<String>, AppError> {
+    state.command_metrics.measure("checkpoint_restore_all", async {
+        let since = match &since_checkpoint_id {
+            Some(id) => sqlx::query_scalar::<_, String>(
+                "SELECT created_at FROM checkpoints WHERE id = ? AND session_id = ?",
+            )
+            .bind(id)
+            .bind(&session_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or_else(|| AppError::database_not_found("Checkpoint", id))?,
+            None => String::new(),
+        };
+
+        // Oldest first, so the first checkpoint seen for a file is the one to restore
+        let candidates = sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT id, file_path FROM checkpoints
+            WHERE session_id = ? AND created_at >= ? AND tool_name != ?
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(&session_id)
+        .bind(&since)
+        .bind(checkpoints::RESTORE_TOOL)
+        .fetch_all(&state.db)
+        .await?;
+
+        let mut seen = HashSet::new();
+        let mut restored = Vec::new();
+        for (id, file_path) in candidates {
+            if seen.insert(file_path) {
+                restored.push(checkpoints::restore(&state.db, &state.data_dir, &id).await?);
+            }
+        }
+
+        Ok(restored)
+    })
+    .await
+}
+
+/// List the captured versions of a file, newest first
+///
+/// Relative paths are resolved against the session's working directory and
+/// need `session_id`; without it, versions from every session are listed.
+#[tauri::command]
+pub async fn file_history(
+    state: State<'_, AppState>,
+    path: String,
+    session_id: Option<String>,
+) -> Result<Vec<FileVersionResponse>, AppError> {
+    state.command_metrics.measure("file_history", async {
+        let path = resolve_path(&state.db, &path, session_id.as_deref()).await?;
+        let versions = checkpoints::file_versions(&state.db, &path, session_id.as_deref()).await?;
+
+        Ok(versions
+            .into_iter()
+            .map(|v| FileVersionResponse {
+                version_id: v.checkpoint_id,
+                session_id: v.session_id,
+                file_path: v.file_path,
+                existed: v.snapshot_path.is_some(),
+                source: if v.tool_name == checkpoints::RESTORE_TOOL { "restore" } else { "claude" }.to_string(),
+                tool_name: v.tool_name,
+                created_at: v.created_at,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Restore a file to one of its captured versions, leaving other files alone
+///
+/// `path` must name the file the version belongs to. Returns the restored path.
+#[tauri::command]
+pub async fn file_restore_version(
+    state: State<'_, AppState>,
+    path: String,
+    version_id: String,
+    session_id: Option<String>,
+) -> Result<String, AppError> {
+    state.command_metrics.measure("file_restore_version", async {
+        let path = resolve_path(&state.db, &path, session_id.as_deref()).await?;
+        let versions = checkpoints::file_versions(&state.db, &path, session_id.as_deref()).await?;
+        if !versions.iter().any(|v| v.checkpoint_id == version_id) {
+            return Err(AppError::database_not_found("File version", &version_id));
+        }
+
+        checkpoints::restore(&state.db, &state.data_dir, &version_id).await
+    })
+    .await
+}
+
+/// Make a path absolute, resolving relative paths against a session's working directory
+async fn resolve_path(db: &sqlx::SqlitePool, path: &str, session_id: Option<&str>) -> Result<PathBuf, AppError> {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+
+    let Some(session_id) = session_id else {
+        return Err(AppError::invalid_input("Relative paths need a session to resolve against"));
+    };
+    let working_directory: String = sqlx::query_scalar("SELECT working_directory FROM sessions WHERE id = ?")
+        .bind(session_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Session", session_id))?;
+
+    Ok(Path::new(&working_directory).join(path))
+}
@@ -0,0 +1,271 @@
+//! Claude Todo Sync Commands
+//!
+//! Mirrors the CLI's TodoWrite todo list into tasks in the session project's
+//! active sprint. Automatic syncing from CLI output is opt-in.
+
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager, State};
+
+use crate::claude::ClaudeTodo;
+use crate::db::settings::{self, CLAUDE_TODO_SYNC};
+use crate::error::AppError;
+use crate::events::{emit_event, event_names, ClaudeTodoChange, ClaudeTodosSyncedPayload};
+use crate::state::AppState;
+
+use super::project::record_task_change;
+use super::session_task::link_session_task;
+use super::task_status::default_status_for;
+
+/// Longest task title created from a todo
+const MAX_TITLE_CHARS: usize = 200;
+
+/// Check whether CLI todos are mirrored into tasks automatically
+#[tauri::command]
+pub async fn claude_todo_sync_get(state: State<'_, AppState>) -> Result<bool, AppError> {
+    state.command_metrics.measure("claude_todo_sync_get", async {
+        todo_sync_enabled(&state.db).await
+    })
+    .await
+}
+
+/// Enable or disable mirroring CLI todos into tasks automatically
+#[tauri::command]
+pub async fn claude_todo_sync_set(state: State<'_, AppState>, enabled: bool) -> Result<(), AppError> {
+    state.command_metrics.measure("claude_todo_sync_set", async {
+        settings::set_setting(&state.db, CLAUDE_TODO_SYNC, &enabled.to_string()).await
+    })
+    .await
+}
+
+/// Mirror a session's todo list into tasks in its project's active sprint
+///
+/// Works regardless of the automatic sync setting.
+#[tauri::command]
+pub async fn task_sync_from_claude(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    session_id: String,
+    todos: Vec<ClaudeTodo>,
+) -> Result<Vec<ClaudeTodoChange>, AppError> {
+    state.command_metrics.measure("task_sync_from_claude", async {
+        sync_todos(&app, &state.db, &session_id, todos).await
+    })
+    .await
+}
+
+/// Mirror a TodoWrite from CLI output if automatic sync is enabled
+pub(crate) async fn sync_claude_todos(
+    app: &AppHandle,
+    session_id: &str,
+    todos: Vec<ClaudeTodo>,
+) -> Result<(), AppError> {
+    let state = app.state::<AppState>();
+    if !todo_sync_enabled(&state.db).await? {
+        return Ok(());
+    }
+
+    sync_todos(app, &state.db, session_id, todos).await?;
+    Ok(())
+}
+
+async fn todo_sync_enabled(db: &SqlitePool) -> Result<bool, AppError> {
+    Ok(settings::get_setting(db, CLAUDE_TODO_SYNC).await?.as_deref() == Some("true"))
+}
+
+/// Create or move tasks for todos that are new or changed state
+async fn sync_todos(
+    app: &AppHandle,
+    db: &SqlitePool,
+    session_id: &str,
+    todos: Vec<ClaudeTodo>,
+) -> Result<Vec<ClaudeTodoChange>, AppError> {
+    let project_id: Option<String> = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT project_id FROM sessions WHERE id = ?",
+    )
+    .bind(session_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| AppError::database_not_found("Session", session_id))?;
+
+    let Some(project_id) = project_id else {
+        log::debug!("Session {} has no project; skipping todo sync", session_id);
+        return Ok(Vec::new());
+    };
+
+    let sprint_id: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT id FROM sprints
+        WHERE project_id = ? AND status = 'active' AND deleted_at IS NULL
+        ORDER BY start_date DESC, created_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(&project_id)
+    .fetch_optional(db)
+    .await?;
+
+    let Some(sprint_id) = sprint_id else {
+        log::debug!("Project {} has no active sprint; skipping todo sync", project_id);
+        return Ok(Vec::new());
+    };
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut changes = Vec::new();
+
+    for todo in todos {
+        let Some(category) = todo_category(&todo.status) else {
+            log::debug!("Ignoring todo with unknown status {:?}", todo.status);
+            continue;
+        };
+        let content = todo.content.trim().to_string();
+
+        let existing = sqlx::query_as::<_, (Option<String>, String)>(
+            "SELECT task_id, status FROM claude_todos WHERE session_id = ? AND content = ?",
+        )
+        .bind(session_id)
+        .bind(&content)
+        .fetch_optional(db)
+        .await?;
+
+        match existing {
+            None => {
+                let task_id = create_task(db, &project_id, &sprint_id, &content, category, &now).await?;
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO claude_todos (session_id, content, task_id, status, updated_at)
+                    VALUES (?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(session_id)
+                .bind(&content)
+                .bind(&task_id)
+                .bind(category)
+                .bind(&now)
+                .execute(db)
+                .await?;
+
+                link_session_task(db, session_id, &task_id).await?;
+
+                changes.push(ClaudeTodoChange {
+                    task_id,
+                    content,
+                    status: category.to_string(),
+                    created: true,
+                });
+            }
+            // The mirrored task was deleted by the user; don't bring it back
+            Some((None, _)) => {}
+            Some((Some(task_id), previous)) if previous != category => {
+                move_task(db, &task_id, category, &now).await?;
+
+                sqlx::query(
+                    "UPDATE claude_todos SET status = ?, updated_at = ? WHERE session_id = ? AND content = ?",
+                )
+                .bind(category)
+                .bind(&now)
+                .bind(session_id)
+                .bind(&content)
+                .execute(db)
+                .await?;
+
+                changes.push(ClaudeTodoChange {
+                    task_id,
+                    content,
+                    status: category.to_string(),
+                    created: false,
+                });
+            }
+            Some(_) => {}
+        }
+    }
+
+    if !changes.is_empty() {
+        let _ = emit_event(
+            app,
+            event_names::CLAUDE_TODOS_SYNCED,
+            ClaudeTodosSyncedPayload {
+                session_id: session_id.to_string(),
+                project_id,
+                sprint_id,
+                changes: changes.clone(),
+            },
+        );
+    }
+
+    Ok(changes)
+}
+
+/// Create a sprint task for a new todo
+async fn create_task(
+    db: &SqlitePool,
+    project_id: &str,
+    sprint_id: &str,
+    content: &str,
+    category: &str,
+    now: &str,
+) -> Result<String, AppError> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+    let title: String = content.chars().take(MAX_TITLE_CHARS).collect();
+    let status_id = default_status_for(db, project_id, category).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO tasks (id, project_id, sprint_id, title, description, status, status_id, priority, estimated_hours, created_at, updated_at)
+        VALUES (?, ?, ?, ?, NULL, ?, ?, 'medium', NULL, ?, ?)
+        "#,
+    )
+    .bind(&task_id)
+    .bind(project_id)
+    .bind(sprint_id)
+    .bind(&title)
+    .bind(category)
+    .bind(&status_id)
+    .bind(now)
+    .bind(now)
+    .execute(db)
+    .await?;
+
+    record_task_change(db, &task_id, project_id, "status", None, Some(category), "claude", now).await?;
+
+    Ok(task_id)
+}
+
+/// Move a mirrored task to the first column of a new status category
+async fn move_task(db: &SqlitePool, task_id: &str, category: &str, now: &str) -> Result<(), AppError> {
+    let Some((project_id, old_status)) = sqlx::query_as::<_, (String, String)>(
+        "SELECT project_id, status FROM tasks WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(task_id)
+    .fetch_optional(db)
+    .await?
+    else {
+        return Ok(());
+    };
+
+    if old_status == category {
+        return Ok(());
+    }
+
+    let status_id = default_status_for(db, &project_id, category).await?;
+
+    sqlx::query("UPDATE tasks SET status = ?, status_id = ?, updated_at = ? WHERE id = ?")
+        .bind(category)
+        .bind(&status_id)
+        .bind(now)
+        .bind(task_id)
+        .execute(db)
+        .await?;
+
+    record_task_change(db, task_id, &project_id, "status", Some(&old_status), Some(category), "claude", now).await
+}
+
+/// Map a TodoWrite status to a task status category
+fn todo_category(status: &str) -> Option<&'static str> {
+    match status {
+        "pending" => Some("todo"),
+        "in_progress" => Some("in_progress"),
+        "completed" => Some("done"),
+        _ => None,
+    }
+}
@@ -0,0 +1,228 @@
+//! Session Compaction Commands
+//!
+//! Summarizes the older part of a session's history with a one-shot CLI
+//! prompt. When the session is resumed from its transcript, the latest
+//! summary stands in for everything it covers, followed by the messages
+//! sent since.
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::path::Path;
+use tauri::State;
+
+use super::session::RESUME_MESSAGE_LIMIT;
+use crate::claude::oneshot::run_oneshot;
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// Maximum characters of a single message included in the prompt
+const COMPACT_MESSAGE_CHARS: usize = 2000;
+
+/// Summary of a session's earlier history
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummaryResponse {
+    pub id: String,
+    pub session_id: String,
+    pub summary: String,
+    /// Last message covered by the summary
+    pub through_message_id: String,
+    /// Messages covered, including those covered by earlier summaries
+    pub message_count: i64,
+    pub created_at: String,
+}
+
+/// Summarize all but a session's most recent messages
+///
+/// `keep_recent` messages (20 by default, the number replayed on resume)
+/// are left out of the summary. A previous summary is folded into the new
+/// one, so only messages since then are sent to the CLI.
+#[tauri::command]
+pub async fn session_compact(
+    state: State<'_, AppState>,
+    session_id: String,
+    keep_recent: Option<i64>,
+) -> Result<SessionSummaryResponse, AppError> {
+    state.command_metrics.measure("session_compact", async {
+        let keep_recent = keep_recent.unwrap_or(RESUME_MESSAGE_LIMIT).max(0);
+
+        let (working_directory, model): (String, Option<String>) = sqlx::query_as(
+            "SELECT working_directory, model FROM sessions WHERE id = ?",
+        )
+        .bind(&session_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Session", &session_id))?;
+
+        let previous = latest_summary(&state.db, &session_id).await?;
+        let (since, covered) = previous
+            .as_ref()
+            .map_or((String::new(), 0), |p| (p.through_created_at.clone(), p.message_count));
+
+        // Messages since the previous summary, minus the recent ones kept verbatim
+        let messages = sqlx::query_as::<_, (String, String, String, String)>(
+            r#"
+            SELECT id, role, content, created_at
+            FROM messages
+            WHERE session_id = ? AND created_at > ?
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(&session_id)
+        .bind(&since)
+        .fetch_all(&state.db)
+        .await?;
+
+        let older = messages.len().saturating_sub(keep_recent as usize);
+        if older == 0 {
+            return Err(AppError::invalid_input("Nothing to compact; the session has no older messages"));
+        }
+        let messages = &messages[..older];
+
+        let transcript: Vec<(String, String)> = messages
+            .iter()
+            .map(|(_, role, content, _)| (role.clone(), content.clone()))
+            .collect();
+        let prompt = build_compact_prompt(previous.as_ref().map(|p| p.summary.as_str()), &transcript);
+
+        let claude_path = state.cli_manager.resolve_binary()?;
+        let summary = run_oneshot(&claude_path, Path::new(&working_directory), &prompt, model.as_deref()).await?;
+        if summary.is_empty() {
+            return Err(AppError::claude_cli_error("Claude CLI returned an empty summary"));
+        }
+
+        let (through_message_id, _, _, through_created_at) = &messages[older - 1];
+        let message_count = covered + older as i64;
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+
+        sqlx::query(
+            r#"
+            INSERT INTO session_summaries (id, session_id, summary, through_message_id, through_created_at, message_count, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&session_id)
+        .bind(&summary)
+        .bind(through_message_id)
+        .bind(through_created_at)
+        .bind(message_count)
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
+
+        Ok(SessionSummaryResponse {
+            id,
+            session_id,
+            summary,
+            through_message_id: through_message_id.clone(),
+            message_count,
+            created_at: now,
+        })
+    })
+    .await
+}
+
+/// Get a session's latest summary, if it has been compacted
+#[tauri::command]
+pub async fn session_get_summary(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Option<SessionSummaryResponse>, AppError> {
+    state.command_metrics.measure("session_get_summary", async {
+        Ok(latest_summary(&state.db, &session_id).await?.map(|s| SessionSummaryResponse {
+            id: s.id,
+            session_id: s.session_id,
+            summary: s.summary,
+            through_message_id: s.through_message_id,
+            message_count: s.message_count,
+            created_at: s.created_at,
+        }))
+    })
+    .await
+}
+
+/// A stored summary, with the timestamp resume uses to find later messages
+pub(crate) struct StoredSummary {
+    pub id: String,
+    pub session_id: String,
+    pub summary: String,
+    pub through_message_id: String,
+    /// `created_at` of the last message covered
+    pub through_created_at: String,
+    pub message_count: i64,
+    pub created_at: String,
+}
+
+/// Load a session's most recent summary
+pub(crate) async fn latest_summary(db: &SqlitePool, session_id: &str) -> Result<Option<StoredSummary>, AppError> {
+    let row = sqlx::query_as::<_, (String, String, String, String, String, i64, String)>(
+        r#"
+        SELECT id, session_id, summary, through_message_id, through_created_at, message_count, created_at
+        FROM session_summaries
+        WHERE session_id = ?
+        ORDER BY created_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(session_id)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row.map(|r| StoredSummary {
+        id: r.0,
+        session_id: r.1,
+        summary: r.2,
+        through_message_id: r.3,
+        through_created_at: r.4,
+        message_count: r.5,
+        created_at: r.6,
+    }))
+}
+
+/// Build the summarization prompt from the previous summary and new messages
+fn build_compact_prompt(previous: Option<&str>, messages: &[(String, String)]) -> String {
+    let mut prompt = String::from(
+        "Summarize the following part of a coding conversation so it can be continued later \
+         without the full transcript. Keep the goal, decisions made and why, files and \
+         commands involved, open problems, and anything the user asked to remember. \
+         Respond with only the summary in plain prose or bullet points.\n\n",
+    );
+
+    if let Some(previous) = previous {
+        prompt.push_str("Summary of the conversation before this part:\n\n");
+        prompt.push_str(previous);
+        prompt.push_str("\n\n");
+    }
+
+    prompt.push_str("Transcript:\n\n");
+    for (role, content) in messages {
+        let label = if role == "user" { "User" } else { "Assistant" };
+        let truncated: String = content.chars().take(COMPACT_MESSAGE_CHARS).collect();
+        prompt.push_str(&format!("{}: {}\n\n", label, truncated));
+    }
+
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_compact_prompt() {
+        let messages = vec![
+            ("user".to_string(), "Fix the login bug".to_string()),
+            ("assistant".to_string(), "x".repeat(COMPACT_MESSAGE_CHARS + 10)),
+        ];
+
+        let prompt = build_compact_prompt(Some("Earlier: set up the repo."), &messages);
+        assert!(prompt.contains("Summary of the conversation before this part:\n\nEarlier: set up the repo."));
+        assert!(prompt.contains("User: Fix the login bug\n\n"));
+        assert!(prompt.contains(&format!("Assistant: {}\n\n", "x".repeat(COMPACT_MESSAGE_CHARS))));
+        assert!(!prompt.contains(&"x".repeat(COMPACT_MESSAGE_CHARS + 1)));
+
+        assert!(!build_compact_prompt(None, &messages).contains("Summary of the conversation before"));
+    }
+}
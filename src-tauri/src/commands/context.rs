@@ -0,0 +1,72 @@
+//! Context Commands
+//!
+//! Commands for looking up project files relevant to a prompt.
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::state::project_index::{ContextMatch, ProjectIndex};
+use crate::state::AppState;
+
+use super::project::project_root;
+
+/// Default number of suggestions returned by a lookup
+const DEFAULT_LOOKUP_LIMIT: usize = 10;
+
+/// Summary of a project's index
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextIndexStatus {
+    pub file_count: usize,
+    pub symbol_count: usize,
+    pub indexed_at: String,
+    /// Whether the project has more files than the index holds
+    pub truncated: bool,
+}
+
+impl From<&ProjectIndex> for ContextIndexStatus {
+    fn from(index: &ProjectIndex) -> Self {
+        Self {
+            file_count: index.file_count(),
+            symbol_count: index.symbol_count(),
+            indexed_at: index.indexed_at.clone(),
+            truncated: index.truncated,
+        }
+    }
+}
+
+/// Suggest project files relevant to a free-text query
+///
+/// Builds the project's index on first use and refreshes it in the
+/// background once it goes stale.
+#[tauri::command]
+pub async fn context_lookup(
+    state: State<'_, AppState>,
+    project_id: String,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<ContextMatch>, AppError> {
+    state.command_metrics.measure("context_lookup", async {
+        let root = project_root(&state.db, &project_id).await?;
+        let index = state.project_index.get(&project_id, root).await?;
+
+        Ok(index.lookup(&query, limit.unwrap_or(DEFAULT_LOOKUP_LIMIT)))
+    })
+    .await
+}
+
+/// Rebuild a project's index now
+#[tauri::command]
+pub async fn context_index_refresh(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<ContextIndexStatus, AppError> {
+    state.command_metrics.measure("context_index_refresh", async {
+        let root = project_root(&state.db, &project_id).await?;
+        let index = state.project_index.rebuild(&project_id, root).await?;
+
+        Ok(ContextIndexStatus::from(index.as_ref()))
+    })
+    .await
+}
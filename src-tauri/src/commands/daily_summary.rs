@@ -0,0 +1,341 @@
+//! Daily Summary Commands
+//!
+//! A background job recaps each day's work per project once the configured
+//! end-of-day hour passes: tasks completed, sessions and messages, CLI cost,
+//! and files changed. Days are local calendar days. If the app wasn't open
+//! at the end of a day, that day is recapped the next time it starts.
+
+use chrono::{Local, NaiveDate, NaiveDateTime, Timelike, Utc};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::db::settings::{self, DAILY_SUMMARY_HOUR, DAILY_SUMMARY_LAST_DATE};
+use crate::error::AppError;
+use crate::events::{emit_event, event_names, DailySummaryPayload};
+use crate::state::AppState;
+
+/// Local hour the summary is compiled at when none is configured
+pub const DEFAULT_SUMMARY_HOUR: u32 = 18;
+
+/// How often the job checks whether a summary is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Summaries returned when no limit is given
+const DEFAULT_LIST_LIMIT: i64 = 30;
+
+/// One project's recap of a day
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailySummaryResponse {
+    pub id: String,
+    pub project_id: String,
+    pub project_name: String,
+    /// Local calendar day, `YYYY-MM-DD`
+    pub date: String,
+    /// Titles of tasks moved to done, in the order they were completed
+    pub completed_tasks: Vec<String>,
+    pub session_count: i64,
+    pub message_count: i64,
+    pub cost_usd: f64,
+    pub files_changed: i64,
+    pub created_at: String,
+}
+
+/// List stored daily summaries, newest first, optionally for one project
+#[tauri::command]
+pub async fn daily_summary_list(
+    state: State<'_, AppState>,
+    project_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<DailySummaryResponse>, AppError> {
+    state.command_metrics.measure("daily_summary_list", async {
+        let limit = limit.unwrap_or(DEFAULT_LIST_LIMIT).clamp(1, 365);
+
+        let rows = sqlx::query_as::<_, (String, String, String, String, String, i64, i64, f64, i64, String)>(
+            r#"
+            SELECT d.id, d.project_id, p.name, d.date, d.completed_tasks, d.session_count,
+                   d.message_count, d.cost_usd, d.files_changed, d.created_at
+            FROM daily_summaries d
+            JOIN projects p ON p.id = d.project_id
+            WHERE p.deleted_at IS NULL AND (?1 IS NULL OR d.project_id = ?1)
+            ORDER BY d.date DESC, p.name ASC
+            LIMIT ?2
+            "#,
+        )
+        .bind(&project_id)
+        .bind(limit)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DailySummaryResponse {
+                id: row.0,
+                project_id: row.1,
+                project_name: row.2,
+                date: row.3,
+                completed_tasks: serde_json::from_str(&row.4).unwrap_or_default(),
+                session_count: row.5,
+                message_count: row.6,
+                cost_usd: row.7,
+                files_changed: row.8,
+                created_at: row.9,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Compile (or recompile) the summaries for a day without waiting for the job
+///
+/// `date` is a local `YYYY-MM-DD` day and defaults to today.
+#[tauri::command]
+pub async fn daily_summary_generate(
+    state: State<'_, AppState>,
+    date: Option<String>,
+) -> Result<Vec<DailySummaryResponse>, AppError> {
+    state.command_metrics.measure("daily_summary_generate", async {
+        let date = match date {
+            Some(date) => NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .map_err(|_| AppError::invalid_input("Date must be formatted as YYYY-MM-DD"))?,
+            None => Local::now().date_naive(),
+        };
+
+        compile_daily_summaries(&state.db, date).await
+    })
+    .await
+}
+
+/// Run the end-of-day job for the lifetime of the app
+pub fn spawn_daily_summary_job(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = run_if_due(&app).await {
+                log::warn!("Failed to compile daily summary: {}", e);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Compile and announce the summary for the most recent finished day, if
+/// it hasn't been compiled yet
+async fn run_if_due(app: &AppHandle) -> Result<(), AppError> {
+    let state = app.state::<AppState>();
+
+    let hour = settings::get_setting(&state.db, DAILY_SUMMARY_HOUR)
+        .await?
+        .and_then(|v| v.parse::<u32>().ok())
+        .filter(|h| *h < 24)
+        .unwrap_or(DEFAULT_SUMMARY_HOUR);
+    let last = settings::get_setting(&state.db, DAILY_SUMMARY_LAST_DATE)
+        .await?
+        .and_then(|v| NaiveDate::parse_from_str(&v, "%Y-%m-%d").ok());
+
+    let Some(date) = due_date(Local::now().naive_local(), hour, last) else {
+        return Ok(());
+    };
+
+    let summaries = compile_daily_summaries(&state.db, date).await?;
+    settings::set_setting(&state.db, DAILY_SUMMARY_LAST_DATE, &date.format("%Y-%m-%d").to_string()).await?;
+
+    if !summaries.is_empty() {
+        let _ = emit_event(
+            app,
+            event_names::DAILY_SUMMARY,
+            DailySummaryPayload {
+                date: date.format("%Y-%m-%d").to_string(),
+                project_ids: summaries.iter().map(|s| s.project_id.clone()).collect(),
+                completed_tasks: summaries.iter().map(|s| s.completed_tasks.len()).sum(),
+                session_count: summaries.iter().map(|s| s.session_count).sum(),
+                cost_usd: summaries.iter().map(|s| s.cost_usd).sum(),
+                files_changed: summaries.iter().map(|s| s.files_changed).sum(),
+            },
+        );
+    }
+
+    Ok(())
+}
+
+/// The day whose summary is due at `now`, or `None` if it was already compiled
+///
+/// Before the end-of-day hour the most recent finished day is yesterday.
+fn due_date(now: NaiveDateTime, hour: u32, last: Option<NaiveDate>) -> Option<NaiveDate> {
+    let today = now.date();
+    let due = if now.hour() >= hour {
+        today
+    } else {
+        today.pred_opt()?
+    };
+
+    match last {
+        Some(last) if last >= due => None,
+        _ => Some(due),
+    }
+}
+
+/// UTC timestamps bounding a local calendar day, for comparing against stored
+/// RFC 3339 timestamps
+fn day_bounds(date: NaiveDate) -> (String, String) {
+    let to_utc = |date: NaiveDate| {
+        let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+        midnight
+            .and_local_timezone(Local)
+            .earliest()
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|| midnight.and_utc())
+            .to_rfc3339()
+    };
+
+    let next = date.succ_opt().unwrap_or(date);
+    (to_utc(date), to_utc(next))
+}
+
+/// Build and store each live project's summary for a day
+///
+/// Projects with nothing to report are skipped.
+pub(crate) async fn compile_daily_summaries(
+    db: &SqlitePool,
+    date: NaiveDate,
+) -> Result<Vec<DailySummaryResponse>, AppError> {
+    let (start, end) = day_bounds(date);
+    let date = date.format("%Y-%m-%d").to_string();
+    let now = Utc::now().to_rfc3339();
+
+    let projects = sqlx::query_as::<_, (String, String)>(
+        "SELECT id, name FROM projects WHERE deleted_at IS NULL ORDER BY name",
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut summaries = Vec::new();
+
+    for (project_id, project_name) in projects {
+        let completed_tasks: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT t.title FROM task_history h
+            JOIN tasks t ON t.id = h.task_id
+            WHERE h.project_id = ? AND h.field = 'status' AND h.new_value = 'done'
+              AND h.changed_at >= ? AND h.changed_at < ?
+              AND t.status = 'done' AND t.deleted_at IS NULL
+            GROUP BY t.id
+            ORDER BY MIN(h.changed_at)
+            "#,
+        )
+        .bind(&project_id)
+        .bind(&start)
+        .bind(&end)
+        .fetch_all(db)
+        .await?;
+
+        let (session_count, message_count): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COUNT(DISTINCT m.session_id), COUNT(*) FROM messages m
+            JOIN sessions s ON s.id = m.session_id
+            WHERE s.project_id = ? AND m.created_at >= ? AND m.created_at < ?
+            "#,
+        )
+        .bind(&project_id)
+        .bind(&start)
+        .bind(&end)
+        .fetch_one(db)
+        .await?;
+
+        let cost_usd: f64 = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(c.cost_usd), 0.0) FROM session_costs c
+            JOIN sessions s ON s.id = c.session_id
+            WHERE s.project_id = ? AND c.recorded_at >= ? AND c.recorded_at < ?
+            "#,
+        )
+        .bind(&project_id)
+        .bind(&start)
+        .bind(&end)
+        .fetch_one(db)
+        .await?;
+
+        let files_changed: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(DISTINCT a.path) FROM activity_log a
+            JOIN sessions s ON s.id = a.session_id
+            WHERE s.project_id = ? AND a.timestamp >= ? AND a.timestamp < ?
+            "#,
+        )
+        .bind(&project_id)
+        .bind(&start)
+        .bind(&end)
+        .fetch_one(db)
+        .await?;
+
+        if completed_tasks.is_empty() && message_count == 0 && cost_usd == 0.0 && files_changed == 0 {
+            continue;
+        }
+
+        let id: String = sqlx::query_scalar(
+            r#"
+            INSERT INTO daily_summaries (id, project_id, date, completed_tasks, session_count, message_count, cost_usd, files_changed, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(project_id, date) DO UPDATE SET
+                completed_tasks = excluded.completed_tasks,
+                session_count = excluded.session_count,
+                message_count = excluded.message_count,
+                cost_usd = excluded.cost_usd,
+                files_changed = excluded.files_changed,
+                created_at = excluded.created_at
+            RETURNING id
+            "#,
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(&project_id)
+        .bind(&date)
+        .bind(serde_json::to_string(&completed_tasks)?)
+        .bind(session_count)
+        .bind(message_count)
+        .bind(cost_usd)
+        .bind(files_changed)
+        .bind(&now)
+        .fetch_one(db)
+        .await?;
+
+        summaries.push(DailySummaryResponse {
+            id,
+            project_id,
+            project_name,
+            date: date.clone(),
+            completed_tasks,
+            session_count,
+            message_count,
+            cost_usd,
+            files_changed,
+            created_at: now.clone(),
+        });
+    }
+
+    Ok(summaries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str, hour: u32) -> NaiveDateTime {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap().and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    fn day(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_due_date() {
+        // Before the hour, yesterday is due unless already compiled
+        assert_eq!(due_date(at("2026-03-10", 9), 18, None), Some(day("2026-03-09")));
+        assert_eq!(due_date(at("2026-03-10", 9), 18, Some(day("2026-03-09"))), None);
+
+        // From the hour on, today is due
+        assert_eq!(due_date(at("2026-03-10", 18), 18, Some(day("2026-03-09"))), Some(day("2026-03-10")));
+        assert_eq!(due_date(at("2026-03-10", 23), 18, Some(day("2026-03-10"))), None);
+    }
+}
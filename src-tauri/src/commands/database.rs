@@ -0,0 +1,68 @@
+//! Database Commands
+//!
+//! Commands for encrypting the database at rest. See
+//! [`crate::db::encryption`] for how the key is kept.
+
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use crate::db::{self, encryption};
+use crate::error::AppError;
+use crate::state::AppState;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbEncryptionStatus {
+    /// Whether this build can encrypt the database
+    pub supported: bool,
+    /// Whether the open database is encrypted
+    pub encrypted: bool,
+    /// Whether an encrypted copy will replace the database on the next start
+    pub pending: bool,
+}
+
+/// Report whether the database is encrypted at rest
+#[tauri::command]
+pub async fn db_encryption_status(
+    state: State<'_, AppState>,
+) -> Result<DbEncryptionStatus, AppError> {
+    state.command_metrics.measure("db_encryption_status", async {
+        let db_path = state.data_dir.join(db::DB_FILE_NAME);
+        Ok(DbEncryptionStatus {
+            supported: encryption::SUPPORTED,
+            encrypted: encryption::is_encrypted(&db_path)?,
+            pending: encryption::pending_path(&db_path).exists(),
+        })
+    })
+    .await
+}
+
+/// Convert the plaintext database to an encrypted one
+///
+/// The database is closed for the conversion, so the app restarts once it
+/// has started, whether or not it succeeds, and doesn't return then.
+#[tauri::command]
+pub async fn db_encrypt_existing(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<DbEncryptionStatus, AppError> {
+    state.command_metrics.measure("db_encrypt_existing", async {
+        let db_path = state.data_dir.join(db::DB_FILE_NAME);
+        let result = encryption::encrypt_existing(&state.db, &db_path).await;
+        if state.db.is_closed() {
+            match &result {
+                Ok(()) => log::info!("Encrypted the database; restarting to reopen it"),
+                Err(e) => log::error!("Failed to encrypt the database; restarting to reopen it: {}", e),
+            }
+            app.restart();
+        }
+        result?;
+
+        Ok(DbEncryptionStatus {
+            supported: encryption::SUPPORTED,
+            encrypted: true,
+            pending: false,
+        })
+    })
+    .await
+}
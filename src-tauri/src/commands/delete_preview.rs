@@ -0,0 +1,174 @@
+//! Delete Preview Commands
+//!
+//! Reports exactly what a destructive delete will remove or detach, so the
+//! confirmation dialog can show real numbers.
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// Rows affected by deleting an entity, keyed by table
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletePreviewResponse {
+    pub entity: String,
+    pub id: String,
+    pub name: String,
+    /// Rows deleted along with the entity
+    pub removed: BTreeMap<String, i64>,
+    /// Rows kept but detached from the entity (their reference is cleared or moved)
+    pub orphaned: BTreeMap<String, i64>,
+}
+
+/// Preview the effect of deleting a project, milestone, sprint, or task
+///
+/// For tasks, `keep_subtasks` matches the option of the same name on
+/// `task_delete`.
+#[tauri::command]
+pub async fn delete_preview(
+    state: State<'_, AppState>,
+    entity: String,
+    id: String,
+    keep_subtasks: Option<bool>,
+) -> Result<DeletePreviewResponse, AppError> {
+    state.command_metrics.measure("delete_preview", async {
+        let db = &state.db;
+        let mut removed = BTreeMap::new();
+        let mut orphaned = BTreeMap::new();
+
+        let name = match entity.as_str() {
+            "project" => {
+                let name = entity_name(db, "SELECT name FROM projects WHERE id = ?1", "Project", &id).await?;
+                removed.insert("projects".to_string(), 1);
+
+                for table in ["milestones", "sprints", "dod_items", "labels", "task_statuses"] {
+                    let sql = format!("SELECT COUNT(*) FROM {} WHERE project_id = ?1", table);
+                    removed.insert(table.to_string(), count(db, &sql, &id).await?);
+                }
+
+                let doomed = "WITH doomed(id) AS (SELECT id FROM tasks WHERE project_id = ?1)";
+                count_task_rows(db, doomed, &id, &mut removed, &mut orphaned).await?;
+
+                orphaned.insert(
+                    "sessions".to_string(),
+                    count(db, "SELECT COUNT(*) FROM sessions WHERE project_id = ?1", &id).await?,
+                );
+                name
+            }
+            "milestone" => {
+                let name = entity_name(db, "SELECT name FROM milestones WHERE id = ?1", "Milestone", &id).await?;
+                removed.insert("milestones".to_string(), 1);
+                orphaned.insert(
+                    "sprints".to_string(),
+                    count(db, "SELECT COUNT(*) FROM sprints WHERE milestone_id = ?1", &id).await?,
+                );
+                name
+            }
+            "sprint" => {
+                let name = entity_name(db, "SELECT name FROM sprints WHERE id = ?1", "Sprint", &id).await?;
+                removed.insert("sprints".to_string(), 1);
+                orphaned.insert(
+                    "tasks".to_string(),
+                    count(db, "SELECT COUNT(*) FROM tasks WHERE sprint_id = ?1", &id).await?,
+                );
+                name
+            }
+            "task" => {
+                let name = entity_name(db, "SELECT title FROM tasks WHERE id = ?1", "Task", &id).await?;
+
+                let doomed = if keep_subtasks.unwrap_or(false) {
+                    orphaned.insert(
+                        "tasks".to_string(),
+                        count(db, "SELECT COUNT(*) FROM tasks WHERE parent_task_id = ?1", &id).await?,
+                    );
+                    "WITH doomed(id) AS (SELECT ?1)"
+                } else {
+                    r#"
+                    WITH RECURSIVE doomed(id) AS (
+                        SELECT ?1
+                        UNION ALL
+                        SELECT t.id FROM tasks t JOIN doomed d ON t.parent_task_id = d.id
+                    )
+                    "#
+                };
+                count_task_rows(db, doomed, &id, &mut removed, &mut orphaned).await?;
+                name
+            }
+            _ => {
+                return Err(AppError::invalid_input(format!(
+                    "Cannot preview deleting \"{}\"; expected project, milestone, sprint, or task",
+                    entity
+                )))
+            }
+        };
+
+        removed.retain(|_, n| *n > 0);
+        orphaned.retain(|_, n| *n > 0);
+
+        Ok(DeletePreviewResponse {
+            entity,
+            id,
+            name,
+            removed,
+            orphaned,
+        })
+    })
+    .await
+}
+
+/// Count the deleted tasks and the rows that go with them
+///
+/// `doomed` is a `WITH` clause defining `doomed(id)`, the tasks being deleted.
+async fn count_task_rows(
+    db: &SqlitePool,
+    doomed: &str,
+    id: &str,
+    removed: &mut BTreeMap<String, i64>,
+    orphaned: &mut BTreeMap<String, i64>,
+) -> Result<(), AppError> {
+    let in_doomed = "IN (SELECT id FROM doomed)";
+
+    removed.insert(
+        "tasks".to_string(),
+        count(db, &format!("{} SELECT COUNT(*) FROM doomed", doomed), id).await?,
+    );
+    removed.insert(
+        "task_dependencies".to_string(),
+        count(
+            db,
+            &format!(
+                "{} SELECT COUNT(*) FROM task_dependencies WHERE task_id {1} OR depends_on_task_id {1}",
+                doomed, in_doomed
+            ),
+            id,
+        )
+        .await?,
+    );
+
+    for table in ["task_history", "task_labels", "task_dod_checks", "session_tasks"] {
+        let sql = format!("{} SELECT COUNT(*) FROM {} WHERE task_id {}", doomed, table, in_doomed);
+        removed.insert(table.to_string(), count(db, &sql, id).await?);
+    }
+
+    // Mirrored CLI todos keep their entry but lose the task
+    let sql = format!("{} SELECT COUNT(*) FROM claude_todos WHERE task_id {}", doomed, in_doomed);
+    orphaned.insert("claude_todos".to_string(), count(db, &sql, id).await?);
+
+    Ok(())
+}
+
+async fn entity_name(db: &SqlitePool, sql: &str, entity: &str, id: &str) -> Result<String, AppError> {
+    sqlx::query_scalar(sql)
+        .bind(id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found(entity, id))
+}
+
+async fn count(db: &SqlitePool, sql: &str, id: &str) -> Result<i64, AppError> {
+    Ok(sqlx::query_scalar(sql).bind(id).fetch_one(db).await?)
+}
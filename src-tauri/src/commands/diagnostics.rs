@@ -0,0 +1,189 @@
+//! Diagnostics Commands
+//!
+//! Collects what support needs to look into a problem into a zip file:
+//! versions, the CLI setup, the database schema version and row counts,
+//! file watcher state, and recent warnings and errors from the log. Only
+//! counts are read from the database, never message bodies, and no file
+//! contents are included.
+
+use std::collections::BTreeMap;
+use std::io::{Cursor, Write};
+use std::path::PathBuf;
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, State};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use super::system::{app_info, check_resolved_cli, AppInfo, CliStatus};
+use crate::claude::CliProcessInfo;
+use crate::db::{self, encryption};
+use crate::error::{AppError, ErrorCode};
+use crate::logging::{self, LogEntry};
+use crate::state::file_watcher::WatcherStatus;
+use crate::state::AppState;
+
+/// Recent log entries included, at warning level and above
+const LOG_LINES: usize = 500;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Diagnostics {
+    generated_at: String,
+    app: AppInfo,
+    os: OsInfo,
+    cli: CliStatus,
+    cli_processes: Vec<CliProcessInfo>,
+    database: DatabaseDiagnostics,
+    watchers: WatcherDiagnostics,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OsInfo {
+    os: &'static str,
+    family: &'static str,
+    arch: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DatabaseDiagnostics {
+    /// Migrations applied
+    schema_version: i64,
+    encrypted: bool,
+    /// Rows per table, by table name
+    table_rows: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WatcherDiagnostics {
+    paused: bool,
+    dropped_events: u64,
+    channel_capacity: usize,
+    watchers: Vec<WatcherStatus>,
+}
+
+/// Write a diagnostics zip for a support request to `path`
+///
+/// The zip holds `diagnostics.json` and `recent-errors.log`. Returns the
+/// path written.
+#[tauri::command]
+pub async fn system_generate_diagnostics(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<String, AppError> {
+    state.command_metrics.measure("system_generate_diagnostics", async {
+        let output_path = PathBuf::from(&path);
+        if !output_path.is_absolute() {
+            return Err(AppError::invalid_input("Diagnostics path must be an absolute path"));
+        }
+
+        let diagnostics = Diagnostics {
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            app: app_info(&app),
+            os: OsInfo {
+                os: std::env::consts::OS,
+                family: std::env::consts::FAMILY,
+                arch: std::env::consts::ARCH,
+            },
+            cli: check_resolved_cli(&state).await,
+            cli_processes: state.cli_manager.list_processes().await,
+            database: DatabaseDiagnostics {
+                schema_version: sqlx::query_scalar("PRAGMA user_version").fetch_one(&state.db).await?,
+                encrypted: encryption::is_encrypted(&state.data_dir.join(db::DB_FILE_NAME))?,
+                table_rows: table_rows(&state.db).await?,
+            },
+            watchers: WatcherDiagnostics {
+                paused: state.file_watcher.is_paused(),
+                dropped_events: state.file_watcher.dropped_events(),
+                channel_capacity: state.file_watcher.channel_capacity(),
+                watchers: state.file_watcher.status(None).await,
+            },
+        };
+
+        // A missing or unreadable log shouldn't stop the rest being collected
+        let log_dir = logging::log_dir(&state.data_dir);
+        let log = match logging::tail(&log_dir, logging::APP_LOG_PREFIX, LOG_LINES, Some("WARN")) {
+            Ok(entries) => entries.iter().map(format_log_entry).collect::<Vec<_>>().join("\n"),
+            Err(e) => format!("Failed to read the log: {}", e),
+        };
+
+        let zip = write_zip(&[
+            ("diagnostics.json", serde_json::to_vec_pretty(&diagnostics)?),
+            ("recent-errors.log", log.into_bytes()),
+        ])?;
+        tokio::fs::write(&output_path, zip).await?;
+
+        Ok(output_path.to_string_lossy().to_string())
+    })
+    .await
+}
+
+/// Count the rows of every table
+async fn table_rows(db: &SqlitePool) -> Result<BTreeMap<String, i64>, AppError> {
+    let tables: Vec<String> = sqlx::query_scalar(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut rows = BTreeMap::new();
+    for table in tables {
+        let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM \"{}\"", table.replace('"', "\"\"")))
+            .fetch_one(db)
+            .await?;
+        rows.insert(table, count);
+    }
+    Ok(rows)
+}
+
+fn format_log_entry(entry: &LogEntry) -> String {
+    format!("{} {:5} {}: {}", entry.timestamp, entry.level, entry.target, entry.message)
+}
+
+/// Build a zip holding `files`, as `(name, contents)` pairs
+fn write_zip(files: &[(&str, Vec<u8>)]) -> Result<Vec<u8>, AppError> {
+    let zip_error = |e: zip::result::ZipError| {
+        AppError::with_details(ErrorCode::Unknown, "Failed to write the diagnostics zip", e.to_string())
+    };
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, contents) in files {
+        zip.start_file(*name, options).map_err(zip_error)?;
+        zip.write_all(contents)?;
+    }
+    Ok(zip.finish().map_err(zip_error)?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_write_zip() {
+        let bytes = write_zip(&[("a.json", b"{}".to_vec()), ("b.log", b"line".to_vec())]).unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.len(), 2);
+
+        let mut contents = String::new();
+        archive.by_name("b.log").unwrap().read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "line");
+    }
+
+    #[test]
+    fn test_format_log_entry() {
+        let entry = LogEntry {
+            timestamp: "2026-10-15T09:30:00Z".to_string(),
+            level: "WARN".to_string(),
+            target: "wingman_lib::db".to_string(),
+            message: "Disk is slow".to_string(),
+        };
+        assert_eq!(format_log_entry(&entry), "2026-10-15T09:30:00Z WARN  wingman_lib::db: Disk is slow");
+    }
+}
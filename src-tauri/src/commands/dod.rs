@@ -0,0 +1,295 @@
+//! Definition of Done Commands
+//!
+//! Commands for managing per-project "definition of done" checklists and
+//! checking them off on individual tasks.
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// Definition of done checklist item
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DodItemResponse {
+    pub id: String,
+    pub project_id: String,
+    pub text: String,
+    pub sort_order: i32,
+    pub created_at: String,
+}
+
+/// A project's checklist and how strictly it is enforced
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectDodResponse {
+    pub enforcement: String,
+    pub items: Vec<DodItemResponse>,
+}
+
+/// Checklist item with its state for a specific task
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskDodItemResponse {
+    #[serde(flatten)]
+    pub item: DodItemResponse,
+    pub checked: bool,
+    pub checked_at: Option<String>,
+}
+
+/// Get a project's definition of done
+#[tauri::command]
+pub async fn dod_get(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<ProjectDodResponse, AppError> {
+    state.command_metrics.measure("dod_get", async {
+        let enforcement = dod_enforcement(&state.db, &project_id).await?;
+
+        let items = sqlx::query_as::<_, (String, String, String, i32, String)>(
+            r#"
+            SELECT id, project_id, text, sort_order, created_at
+            FROM dod_items
+            WHERE project_id = ?
+            ORDER BY sort_order ASC
+            "#,
+        )
+        .bind(&project_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(ProjectDodResponse {
+            enforcement,
+            items: items
+                .into_iter()
+                .map(|i| DodItemResponse {
+                    id: i.0,
+                    project_id: i.1,
+                    text: i.2,
+                    sort_order: i.3,
+                    created_at: i.4,
+                })
+                .collect(),
+        })
+    })
+    .await
+}
+
+/// Add an item to a project's definition of done
+#[tauri::command]
+pub async fn dod_item_create(
+    state: State<'_, AppState>,
+    project_id: String,
+    text: String,
+) -> Result<DodItemResponse, AppError> {
+    state.command_metrics.measure("dod_item_create", async {
+        let text = text.trim().to_string();
+        if text.is_empty() {
+            return Err(AppError::invalid_input("Checklist item cannot be empty"));
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+
+        let max_order: Option<i32> = sqlx::query_scalar(
+            "SELECT MAX(sort_order) FROM dod_items WHERE project_id = ?",
+        )
+        .bind(&project_id)
+        .fetch_one(&state.db)
+        .await?;
+
+        let sort_order = max_order.unwrap_or(0) + 1;
+
+        sqlx::query(
+            r#"
+            INSERT INTO dod_items (id, project_id, text, sort_order, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&project_id)
+        .bind(&text)
+        .bind(sort_order)
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
+
+        Ok(DodItemResponse {
+            id,
+            project_id,
+            text,
+            sort_order,
+            created_at: now,
+        })
+    })
+    .await
+}
+
+/// Remove an item from a project's definition of done
+#[tauri::command]
+pub async fn dod_item_delete(
+    state: State<'_, AppState>,
+    item_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("dod_item_delete", async {
+        let result = sqlx::query("DELETE FROM dod_items WHERE id = ?")
+            .bind(&item_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Checklist item", &item_id));
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Set how a project's definition of done is enforced when tasks are completed
+///
+/// `off` ignores the checklist, `warn` reports unchecked items, and `block`
+/// refuses to mark a task done until every item is checked.
+#[tauri::command]
+pub async fn dod_set_enforcement(
+    state: State<'_, AppState>,
+    project_id: String,
+    enforcement: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("dod_set_enforcement", async {
+        if !["off", "warn", "block"].contains(&enforcement.as_str()) {
+            return Err(AppError::invalid_input("Invalid enforcement mode"));
+        }
+
+        let result = sqlx::query("UPDATE projects SET dod_enforcement = ? WHERE id = ?")
+            .bind(&enforcement)
+            .bind(&project_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Project", &project_id));
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Get a task's checklist with checked state
+#[tauri::command]
+pub async fn task_dod_get(
+    state: State<'_, AppState>,
+    task_id: String,
+) -> Result<Vec<TaskDodItemResponse>, AppError> {
+    state.command_metrics.measure("task_dod_get", async {
+        let items = sqlx::query_as::<_, (String, String, String, i32, String, Option<String>)>(
+            r#"
+            SELECT d.id, d.project_id, d.text, d.sort_order, d.created_at, c.checked_at
+            FROM tasks t
+            JOIN dod_items d ON d.project_id = t.project_id
+            LEFT JOIN task_dod_checks c ON c.dod_item_id = d.id AND c.task_id = t.id
+            WHERE t.id = ?
+            ORDER BY d.sort_order ASC
+            "#,
+        )
+        .bind(&task_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(items
+            .into_iter()
+            .map(|i| TaskDodItemResponse {
+                item: DodItemResponse {
+                    id: i.0,
+                    project_id: i.1,
+                    text: i.2,
+                    sort_order: i.3,
+                    created_at: i.4,
+                },
+                checked: i.5.is_some(),
+                checked_at: i.5,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Check or uncheck a checklist item for a task
+#[tauri::command]
+pub async fn task_dod_check(
+    state: State<'_, AppState>,
+    task_id: String,
+    item_id: String,
+    checked: bool,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("task_dod_check", async {
+        if checked {
+            let now = chrono::Utc::now().to_rfc3339();
+            sqlx::query(
+                "INSERT OR IGNORE INTO task_dod_checks (task_id, dod_item_id, checked_at) VALUES (?, ?, ?)",
+            )
+            .bind(&task_id)
+            .bind(&item_id)
+            .bind(&now)
+            .execute(&state.db)
+            .await?;
+        } else {
+            sqlx::query("DELETE FROM task_dod_checks WHERE task_id = ? AND dod_item_id = ?")
+                .bind(&task_id)
+                .bind(&item_id)
+                .execute(&state.db)
+                .await?;
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Get a project's enforcement mode
+pub(crate) async fn dod_enforcement<'e>(
+    db: impl sqlx::Executor<'e, Database = sqlx::Sqlite>,
+    project_id: &str,
+) -> Result<String, AppError> {
+    sqlx::query_scalar("SELECT dod_enforcement FROM projects WHERE id = ?")
+        .bind(project_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Project", project_id))
+}
+
+/// Get the checklist items a task has not checked off yet
+pub(crate) async fn unchecked_dod_items<'e>(
+    db: impl sqlx::Executor<'e, Database = sqlx::Sqlite>,
+    task_id: &str,
+) -> Result<Vec<DodItemResponse>, AppError> {
+    let items = sqlx::query_as::<_, (String, String, String, i32, String)>(
+        r#"
+        SELECT d.id, d.project_id, d.text, d.sort_order, d.created_at
+        FROM tasks t
+        JOIN dod_items d ON d.project_id = t.project_id
+        WHERE t.id = ?
+          AND NOT EXISTS (
+              SELECT 1 FROM task_dod_checks c
+              WHERE c.task_id = t.id AND c.dod_item_id = d.id
+          )
+        ORDER BY d.sort_order ASC
+        "#,
+    )
+    .bind(task_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(items
+        .into_iter()
+        .map(|i| DodItemResponse {
+            id: i.0,
+            project_id: i.1,
+            text: i.2,
+            sort_order: i.3,
+            created_at: i.4,
+        })
+        .collect())
+}
@@ -0,0 +1,209 @@
+//! Event Buffer Commands
+//!
+//! While background mode is on, `claude_output` and `file_changed` events are
+//! also written to the `event_buffer` table, so a window that was closed or
+//! reloaded can replay what it missed with `events_replay_since`. Buffered
+//! events are kept for a day.
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Listener, Manager, State};
+use tokio::sync::mpsc;
+
+use crate::error::AppError;
+use crate::events::event_names;
+use crate::state::AppState;
+
+/// Events written to the buffer
+const BUFFERED_EVENTS: &[&str] = &[event_names::CLAUDE_OUTPUT, event_names::FILE_CHANGED];
+
+/// Events waiting to be written before new ones are dropped
+const CHANNEL_CAPACITY: usize = 4096;
+
+/// Most events written in one transaction
+const MAX_BATCH: usize = 500;
+
+/// How long buffered events are kept
+const RETENTION_HOURS: i64 = 24;
+
+/// Most events kept, however recent
+const MAX_ROWS: i64 = 100_000;
+
+/// Time between prunes of the buffer
+const PRUNE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Events returned when no limit is given
+const DEFAULT_REPLAY_LIMIT: i64 = 1000;
+
+/// Largest accepted replay limit
+const MAX_REPLAY_LIMIT: i64 = 10_000;
+
+/// An event as it was emitted
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BufferedEvent {
+    pub id: i64,
+    /// App event name, e.g. `claude_output`
+    pub event: String,
+    pub session_id: Option<String>,
+    /// The event's payload, as the frontend receives it
+    pub payload: Value,
+    pub created_at: String,
+}
+
+/// An emitted event waiting to be written
+struct PendingEvent {
+    event: &'static str,
+    payload: String,
+    created_at: String,
+}
+
+/// Buffer session events for the lifetime of the app
+pub fn spawn_event_buffer(app: AppHandle, db: SqlitePool) {
+    let (sender, mut receiver) = mpsc::channel::<PendingEvent>(CHANNEL_CAPACITY);
+
+    for name in BUFFERED_EVENTS {
+        let sender = sender.clone();
+        let handle = app.clone();
+        app.listen_any(*name, move |emitted| {
+            let enabled = handle
+                .try_state::<AppState>()
+                .is_some_and(|state| state.background_mode());
+            if !enabled {
+                return;
+            }
+            let pending = PendingEvent {
+                event: *name,
+                payload: emitted.payload().to_string(),
+                created_at: timestamp(Utc::now()),
+            };
+            if sender.try_send(pending).is_err() {
+                log::debug!("Event buffer is full, dropping a {} event", name);
+            }
+        });
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let mut last_prune: Option<Instant> = None;
+        while let Some(first) = receiver.recv().await {
+            let mut batch = vec![first];
+            while batch.len() < MAX_BATCH {
+                match receiver.try_recv() {
+                    Ok(pending) => batch.push(pending),
+                    Err(_) => break,
+                }
+            }
+            if let Err(e) = write_batch(&db, &batch).await {
+                log::warn!("Failed to buffer {} events: {}", batch.len(), e);
+            }
+
+            if last_prune.is_none_or(|at| at.elapsed() >= PRUNE_INTERVAL) {
+                last_prune = Some(Instant::now());
+                if let Err(e) = prune_event_buffer(&db).await {
+                    log::warn!("Failed to prune the event buffer: {}", e);
+                }
+            }
+        }
+    });
+}
+
+async fn write_batch(db: &SqlitePool, batch: &[PendingEvent]) -> Result<(), AppError> {
+    let mut tx = db.begin().await?;
+    for pending in batch {
+        let session_id = serde_json::from_str::<Value>(&pending.payload)
+            .ok()
+            .and_then(|payload| payload.get("sessionId").and_then(Value::as_str).map(str::to_string));
+        sqlx::query("INSERT INTO event_buffer (event, session_id, payload, created_at) VALUES (?, ?, ?, ?)")
+            .bind(pending.event)
+            .bind(session_id)
+            .bind(&pending.payload)
+            .bind(&pending.created_at)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Delete buffered events past the retention window or the row limit
+async fn prune_event_buffer(db: &SqlitePool) -> Result<(), AppError> {
+    let cutoff = timestamp(Utc::now() - chrono::Duration::hours(RETENTION_HOURS));
+    sqlx::query("DELETE FROM event_buffer WHERE created_at < ?")
+        .bind(&cutoff)
+        .execute(db)
+        .await?;
+    sqlx::query("DELETE FROM event_buffer WHERE id <= (SELECT MAX(id) FROM event_buffer) - ?")
+        .bind(MAX_ROWS)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Buffer timestamps, fixed-width so they compare as strings
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+/// Get the buffered events emitted after `since`, oldest first
+///
+/// `since` is an RFC 3339 timestamp, usually when the window was last
+/// visible. With `session_id`, only that session's events are returned.
+#[tauri::command]
+pub async fn events_replay_since(
+    state: State<'_, AppState>,
+    since: String,
+    session_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<BufferedEvent>, AppError> {
+    state.command_metrics.measure("events_replay_since", async {
+        let since = DateTime::parse_from_rfc3339(&since)
+            .map_err(|_| AppError::invalid_input(format!("Invalid timestamp: {}", since)))?;
+        let limit = limit.unwrap_or(DEFAULT_REPLAY_LIMIT).clamp(1, MAX_REPLAY_LIMIT);
+
+        let rows = sqlx::query_as::<_, (i64, String, Option<String>, String, String)>(
+            r#"
+            SELECT id, event, session_id, payload, created_at
+            FROM event_buffer
+            WHERE created_at > ? AND (? IS NULL OR session_id = ?)
+            ORDER BY id ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(timestamp(since.with_timezone(&Utc)))
+        .bind(&session_id)
+        .bind(&session_id)
+        .bind(limit)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, event, session_id, payload, created_at)| BufferedEvent {
+                id,
+                event,
+                session_id,
+                payload: serde_json::from_str(&payload).unwrap_or(Value::Null),
+                created_at,
+            })
+            .collect())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamp_orders_as_string() {
+        let earlier = DateTime::parse_from_rfc3339("2026-01-02T03:04:05Z").unwrap().with_timezone(&Utc);
+        let later = earlier + chrono::Duration::microseconds(1);
+
+        assert_eq!(timestamp(earlier), "2026-01-02T03:04:05.000000Z");
+        assert!(timestamp(earlier) < timestamp(later));
+    }
+}
@@ -0,0 +1,171 @@
+//! File Activity Dashboard Commands
+//!
+//! Ranks a project's files by how often they change, from `activity_log`,
+//! with the share of changes Claude made and the write tool calls that
+//! targeted each file. Files Claude keeps rewriting stand out here.
+
+use std::collections::HashMap;
+
+use chrono::{Duration, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::State;
+
+use crate::checkpoints;
+use crate::error::AppError;
+use crate::paths::PathNormalizer;
+use crate::state::AppState;
+
+/// Default number of files in each ranking
+const DEFAULT_FILE_LIMIT: i64 = 20;
+
+/// Default window for hotspots
+const DEFAULT_HOTSPOT_HOURS: i64 = 24;
+
+/// How often one file changed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileActivity {
+    /// Relative to the session's working directory
+    pub path: String,
+    pub changes: i64,
+    pub claude_changes: i64,
+    pub external_changes: i64,
+    /// Share of changes made by Claude, from 0 to 1
+    pub claude_ratio: f64,
+    /// Write, Edit, MultiEdit, and NotebookEdit calls targeting the file
+    pub tool_edits: i64,
+    pub last_changed_at: String,
+}
+
+/// Most-changed files in a project, overall and recently
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileActivityResponse {
+    pub most_edited: Vec<FileActivity>,
+    /// Most-changed files since `hotspot_since`
+    pub hotspots: Vec<FileActivity>,
+    pub hotspot_since: String,
+}
+
+/// Rank a project's files by how often they changed
+///
+/// Hotspots cover the last `hotspot_hours`, 24 by default.
+#[tauri::command]
+pub async fn dashboard_file_activity(
+    state: State<'_, AppState>,
+    project_id: String,
+    limit: Option<i64>,
+    hotspot_hours: Option<i64>,
+) -> Result<FileActivityResponse, AppError> {
+    state.command_metrics.measure("dashboard_file_activity", async {
+        let limit = limit.unwrap_or(DEFAULT_FILE_LIMIT).clamp(1, 500);
+        let hotspot_hours = hotspot_hours.unwrap_or(DEFAULT_HOTSPOT_HOURS).clamp(1, 24 * 365);
+        let hotspot_since = (Utc::now() - Duration::hours(hotspot_hours)).to_rfc3339();
+
+        let most_edited = rank_files(&state.db, &project_id, None, limit).await?;
+        let hotspots = rank_files(&state.db, &project_id, Some(&hotspot_since), limit).await?;
+
+        Ok(FileActivityResponse {
+            most_edited,
+            hotspots,
+            hotspot_since,
+        })
+    })
+    .await
+}
+
+/// The project's most-changed files, optionally only since a timestamp
+async fn rank_files(
+    db: &sqlx::SqlitePool,
+    project_id: &str,
+    since: Option<&str>,
+    limit: i64,
+) -> Result<Vec<FileActivity>, AppError> {
+    let rows = sqlx::query_as::<_, (String, i64, i64, String)>(
+        r#"
+        SELECT a.path, COUNT(*), SUM(a.source = 'claude'), MAX(a.timestamp)
+        FROM activity_log a
+        JOIN sessions s ON s.id = a.session_id
+        WHERE s.project_id = ?1 AND (?2 IS NULL OR a.timestamp >= ?2)
+        GROUP BY a.path
+        ORDER BY COUNT(*) DESC, MAX(a.timestamp) DESC
+        LIMIT ?3
+        "#,
+    )
+    .bind(project_id)
+    .bind(since)
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+
+    let calls = sqlx::query_as::<_, (String, String, String)>(
+        r#"
+        SELECT s.working_directory, t.tool_name, t.input
+        FROM tool_calls t
+        JOIN sessions s ON s.id = t.session_id
+        WHERE s.project_id = ?1 AND (?2 IS NULL OR t.started_at >= ?2)
+        "#,
+    )
+    .bind(project_id)
+    .bind(since)
+    .fetch_all(db)
+    .await?;
+
+    // Tool inputs name files as Claude wrote them, so normalize them the way
+    // the watcher logs paths before matching
+    let mut normalizers: HashMap<String, PathNormalizer> = HashMap::new();
+    let mut tool_edits: HashMap<String, i64> = HashMap::new();
+    for (working_directory, tool_name, input) in calls {
+        let input: Value = serde_json::from_str(&input).unwrap_or(Value::Null);
+        let Some(file_path) = checkpoints::target_path(&tool_name, &input) else {
+            continue;
+        };
+        let normalizer = normalizers
+            .entry(working_directory)
+            .or_insert_with_key(|dir| PathNormalizer::new(dir));
+        *tool_edits.entry(normalizer.normalize(&file_path)).or_default() += 1;
+    }
+
+    Ok(file_activity(rows, &tool_edits))
+}
+
+/// Build file rankings from `(path, changes, claude changes, last change)` rows
+fn file_activity(rows: Vec<(String, i64, i64, String)>, tool_edits: &HashMap<String, i64>) -> Vec<FileActivity> {
+    rows.into_iter()
+        .map(|(path, changes, claude_changes, last_changed_at)| FileActivity {
+            tool_edits: tool_edits.get(&path).copied().unwrap_or(0),
+            claude_ratio: if changes > 0 {
+                claude_changes as f64 / changes as f64
+            } else {
+                0.0
+            },
+            external_changes: changes - claude_changes,
+            path,
+            changes,
+            claude_changes,
+            last_changed_at,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_activity() {
+        let rows = vec![
+            ("src/main.rs".to_string(), 4, 3, "2024-01-02T00:00:00Z".to_string()),
+            ("README.md".to_string(), 2, 0, "2024-01-01T00:00:00Z".to_string()),
+        ];
+        let tool_edits = HashMap::from([("src/main.rs".to_string(), 5)]);
+
+        let files = file_activity(rows, &tool_edits);
+        assert_eq!(files[0].external_changes, 1);
+        assert_eq!(files[0].claude_ratio, 0.75);
+        assert_eq!(files[0].tool_edits, 5);
+        assert_eq!(files[1].claude_ratio, 0.0);
+        assert_eq!(files[1].tool_edits, 0);
+    }
+}
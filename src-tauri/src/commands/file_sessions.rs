@@ -0,0 +1,161 @@
+//! File Sessions Commands
+//!
+//! Finds every session that touched a file, from the activity log and from
+//! the tool calls recorded on assistant messages, to answer when Claude last
+//! changed a file and what it was asked to do at the time.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::paths::PathNormalizer;
+use crate::state::AppState;
+
+/// Characters of the prompting user message included per message
+const PROMPT_PREVIEW_CHARS: usize = 500;
+
+/// An assistant message whose tool calls referenced the file
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSessionMessage {
+    pub message_id: String,
+    pub created_at: String,
+    /// The user message that prompted the turn, truncated
+    pub prompt: Option<String>,
+}
+
+/// A session that touched a file
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSessionResponse {
+    pub session_id: String,
+    pub title: String,
+    pub project_id: Option<String>,
+    pub archived: bool,
+    /// Path as recorded for this session, relative to its working directory
+    pub relative_path: String,
+    /// Activity log entries for the file
+    pub change_count: i64,
+    pub last_changed_at: Option<String>,
+    pub last_claude_change_at: Option<String>,
+    /// Newest first
+    pub messages: Vec<FileSessionMessage>,
+}
+
+/// Find the sessions that changed or referenced a file, most recent first
+#[tauri::command]
+pub async fn file_sessions(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<Vec<FileSessionResponse>, AppError> {
+    state.command_metrics.measure("file_sessions", async {
+        if !Path::new(&path).is_absolute() {
+            return Err(AppError::invalid_input("Path must be an absolute path"));
+        }
+
+        let sessions = sqlx::query_as::<_, (String, String, Option<String>, String, Option<String>)>(
+            "SELECT id, title, project_id, working_directory, archived_at FROM sessions",
+        )
+        .fetch_all(&state.db)
+        .await?;
+
+        // Sessions sharing a working directory record the file the same way
+        let mut by_directory: HashMap<String, Vec<(String, String, Option<String>, bool)>> = HashMap::new();
+        for (id, title, project_id, working_directory, archived_at) in sessions {
+            by_directory
+                .entry(working_directory)
+                .or_default()
+                .push((id, title, project_id, archived_at.is_some()));
+        }
+
+        let absolute_json = serde_json::to_string(&path)?;
+        let mut results = Vec::new();
+
+        for (working_directory, sessions) in by_directory {
+            let normalizer = PathNormalizer::new(&working_directory);
+            let relative_path = normalizer.normalize(&path);
+            let relative_json = serde_json::to_string(&relative_path)?;
+
+            let changes: HashMap<String, (i64, Option<String>, Option<String>)> =
+                sqlx::query_as::<_, (String, i64, Option<String>, Option<String>)>(
+                    r#"
+                    SELECT a.session_id, COUNT(*), MAX(a.timestamp),
+                           MAX(CASE WHEN a.source = 'claude' THEN a.timestamp END)
+                    FROM activity_log a
+                    JOIN sessions s ON s.id = a.session_id
+                    WHERE s.working_directory = ? AND a.path = ?
+                    GROUP BY a.session_id
+                    "#,
+                )
+                .bind(&working_directory)
+                .bind(&relative_path)
+                .fetch_all(&state.db)
+                .await?
+                .into_iter()
+                .map(|(session_id, count, last, last_claude)| (session_id, (count, last, last_claude)))
+                .collect();
+
+            // Tool inputs are stored as JSON, so match the quoted path
+            let mut messages: HashMap<String, Vec<FileSessionMessage>> = HashMap::new();
+            let rows = sqlx::query_as::<_, (String, String, String, Option<String>)>(
+                r#"
+                SELECT m.id, m.session_id, m.created_at,
+                       (SELECT u.content FROM messages u
+                        WHERE u.session_id = m.session_id AND u.role = 'user' AND u.created_at <= m.created_at
+                        ORDER BY u.created_at DESC LIMIT 1)
+                FROM messages m
+                JOIN sessions s ON s.id = m.session_id
+                WHERE s.working_directory = ? AND m.role = 'assistant' AND m.tool_usage IS NOT NULL
+                  AND (instr(m.tool_usage, ?) > 0 OR instr(m.tool_usage, ?) > 0)
+                ORDER BY m.created_at DESC
+                "#,
+            )
+            .bind(&working_directory)
+            .bind(&absolute_json)
+            .bind(&relative_json)
+            .fetch_all(&state.db)
+            .await?;
+            for (message_id, session_id, created_at, prompt) in rows {
+                messages.entry(session_id).or_default().push(FileSessionMessage {
+                    message_id,
+                    created_at,
+                    prompt: prompt.map(|p| p.chars().take(PROMPT_PREVIEW_CHARS).collect()),
+                });
+            }
+
+            for (session_id, title, project_id, archived) in sessions {
+                let session_messages = messages.remove(&session_id).unwrap_or_default();
+                let (change_count, last_changed_at, last_claude_change_at) =
+                    changes.get(&session_id).cloned().unwrap_or((0, None, None));
+                if change_count == 0 && session_messages.is_empty() {
+                    continue;
+                }
+
+                results.push(FileSessionResponse {
+                    session_id,
+                    title,
+                    project_id,
+                    archived,
+                    relative_path: relative_path.clone(),
+                    change_count,
+                    last_changed_at,
+                    last_claude_change_at,
+                    messages: session_messages,
+                });
+            }
+        }
+
+        results.sort_by(|a, b| last_touched(b).cmp(&last_touched(a)));
+
+        Ok(results)
+    })
+    .await
+}
+
+/// When a session last changed or referenced the file
+fn last_touched(session: &FileSessionResponse) -> Option<&str> {
+    let last_message = session.messages.first().map(|m| m.created_at.as_str());
+    session.last_changed_at.as_deref().max(last_message)
+}
@@ -0,0 +1,73 @@
+//! Git Commands
+//!
+//! Commands for inspecting the git repository at a project's root.
+
+use tauri::State;
+
+use crate::error::AppError;
+use crate::git::{self, GitCommit, GitStatus};
+use crate::state::AppState;
+
+use super::project::project_root;
+
+/// Default number of commits returned by `git_log_recent`
+const DEFAULT_LOG_LIMIT: usize = 20;
+
+/// Most commits returned by `git_log_recent`
+const MAX_LOG_LIMIT: usize = 500;
+
+/// Get the branch and changed files of a project's working tree
+#[tauri::command]
+pub async fn git_status(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<GitStatus, AppError> {
+    state.command_metrics.measure("git_status", async {
+        let root = project_root(&state.db, &project_id).await?;
+        git::status(&root).await
+    })
+    .await
+}
+
+/// Get a project's checked-out branch (`None` when HEAD is detached)
+#[tauri::command]
+pub async fn git_current_branch(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<Option<String>, AppError> {
+    state.command_metrics.measure("git_current_branch", async {
+        let root = project_root(&state.db, &project_id).await?;
+        git::current_branch(&root).await
+    })
+    .await
+}
+
+/// Get the unified diff of one file in a project
+#[tauri::command]
+pub async fn git_diff_file(
+    state: State<'_, AppState>,
+    project_id: String,
+    path: String,
+    staged: Option<bool>,
+) -> Result<String, AppError> {
+    state.command_metrics.measure("git_diff_file", async {
+        let root = project_root(&state.db, &project_id).await?;
+        git::diff_file(&root, &path, staged.unwrap_or(false)).await
+    })
+    .await
+}
+
+/// Get a project's most recent commits
+#[tauri::command]
+pub async fn git_log_recent(
+    state: State<'_, AppState>,
+    project_id: String,
+    limit: Option<usize>,
+) -> Result<Vec<GitCommit>, AppError> {
+    state.command_metrics.measure("git_log_recent", async {
+        let root = project_root(&state.db, &project_id).await?;
+        let limit = limit.unwrap_or(DEFAULT_LOG_LIMIT).clamp(1, MAX_LOG_LIMIT);
+        git::log_recent(&root, limit).await
+    })
+    .await
+}
@@ -0,0 +1,229 @@
+//! GitHub Integration Commands
+//!
+//! Links a project to a GitHub repository and imports the repository's
+//! issues as tasks. Issue labels are renamed through the link's label map
+//! before they become task labels. With `close_issues_on_done` set, moving
+//! an imported task to done closes its issue.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, State};
+
+use super::import::{apply_batch, ImportApplyResponse};
+use crate::error::AppError;
+use crate::import::{GithubImporter, Importer};
+use crate::integrations::github::{self, GithubClient};
+use crate::state::AppState;
+
+/// A project's linked GitHub repository
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GithubIntegrationResponse {
+    pub project_id: String,
+    /// `owner/name`
+    pub repo: String,
+    /// GitHub label names to task label names; an empty name drops the label
+    pub label_map: BTreeMap<String, String>,
+    pub close_issues_on_done: bool,
+    pub last_imported_at: Option<String>,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GithubIntegrationRequest {
+    /// `owner/name` or a clone URL
+    pub repo: String,
+    pub label_map: Option<BTreeMap<String, String>>,
+    pub close_issues_on_done: Option<bool>,
+}
+
+/// Get a project's linked GitHub repository, if any
+#[tauri::command]
+pub async fn github_integration_get(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<Option<GithubIntegrationResponse>, AppError> {
+    state.command_metrics.measure("github_integration_get", async {
+        fetch_integration(&state.db, &project_id).await
+    })
+    .await
+}
+
+/// Link a project to a GitHub repository, or change its link
+#[tauri::command]
+pub async fn github_integration_set(
+    state: State<'_, AppState>,
+    project_id: String,
+    request: GithubIntegrationRequest,
+) -> Result<GithubIntegrationResponse, AppError> {
+    state.command_metrics.measure("github_integration_set", async {
+        let repo = github::normalize_repo(&request.repo)
+            .ok_or_else(|| AppError::invalid_input("Repository must be owner/name or a GitHub URL"))?;
+
+        let exists: Option<String> = sqlx::query_scalar("SELECT id FROM projects WHERE id = ? AND deleted_at IS NULL")
+            .bind(&project_id)
+            .fetch_optional(&state.db)
+            .await?;
+        if exists.is_none() {
+            return Err(AppError::database_not_found("Project", &project_id));
+        }
+
+        let label_map = request
+            .label_map
+            .map(|label_map| serde_json::to_string(&label_map).unwrap_or_else(|_| "{}".to_string()));
+
+        sqlx::query(
+            r#"
+            INSERT INTO github_integrations (project_id, repo, label_map, close_issues_on_done, updated_at)
+            VALUES (?1, ?2, COALESCE(?3, '{}'), COALESCE(?4, 0), ?5)
+            ON CONFLICT(project_id) DO UPDATE SET
+                repo = excluded.repo,
+                label_map = COALESCE(?3, label_map),
+                close_issues_on_done = COALESCE(?4, close_issues_on_done),
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&project_id)
+        .bind(&repo)
+        .bind(&label_map)
+        .bind(request.close_issues_on_done)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&state.db)
+        .await?;
+
+        fetch_integration(&state.db, &project_id)
+            .await?
+            .ok_or_else(|| AppError::database_not_found("GitHub integration", &project_id))
+    })
+    .await
+}
+
+/// Unlink a project from its GitHub repository; imported tasks are kept
+#[tauri::command]
+pub async fn github_integration_delete(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("github_integration_delete", async {
+        sqlx::query("DELETE FROM github_integrations WHERE project_id = ?")
+            .bind(&project_id)
+            .execute(&state.db)
+            .await?;
+        Ok(())
+    })
+    .await
+}
+
+/// Import the linked repository's issues into the project's backlog
+///
+/// Issues imported before are skipped. Closed issues are only imported with
+/// `include_closed`, as done tasks. `token` defaults to the one saved in the
+/// keychain, then `GITHUB_TOKEN` or `GH_TOKEN`. Emits `import_progress` as
+/// tasks are created.
+#[tauri::command]
+pub async fn github_import_issues(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    project_id: String,
+    include_closed: Option<bool>,
+    token: Option<String>,
+) -> Result<ImportApplyResponse, AppError> {
+    state.command_metrics.measure("github_import_issues", async {
+        let integration = fetch_integration(&state.db, &project_id)
+            .await?
+            .ok_or_else(|| AppError::invalid_input("Link the project to a GitHub repository first"))?;
+
+        let client = GithubClient::new(github::resolve_token(token).await)?;
+        let issues = client
+            .list_issues(&integration.repo, include_closed.unwrap_or(false))
+            .await?;
+
+        let importer = GithubImporter;
+        let mut batch = importer.parse(&Value::Array(issues).to_string())?;
+        for item in &mut batch.items {
+            // Issue numbers only identify an issue within its repository
+            item.external_id = item
+                .external_id
+                .as_deref()
+                .map(|number| github::external_id(&integration.repo, number));
+            item.labels = map_labels(&item.labels, &integration.label_map);
+        }
+
+        let response = apply_batch(&app, &state.db, &project_id, importer.source(), &batch, None).await?;
+
+        sqlx::query("UPDATE github_integrations SET last_imported_at = ? WHERE project_id = ?")
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(&project_id)
+            .execute(&state.db)
+            .await?;
+
+        Ok(response)
+    })
+    .await
+}
+
+async fn fetch_integration(db: &SqlitePool, project_id: &str) -> Result<Option<GithubIntegrationResponse>, AppError> {
+    let row = sqlx::query_as::<_, (String, String, String, bool, Option<String>, String)>(
+        r#"
+        SELECT project_id, repo, label_map, close_issues_on_done, last_imported_at, updated_at
+        FROM github_integrations
+        WHERE project_id = ?
+        "#,
+    )
+    .bind(project_id)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row.map(
+        |(project_id, repo, label_map, close_issues_on_done, last_imported_at, updated_at)| {
+            GithubIntegrationResponse {
+                project_id,
+                repo,
+                label_map: serde_json::from_str(&label_map).unwrap_or_default(),
+                close_issues_on_done,
+                last_imported_at,
+                updated_at,
+            }
+        },
+    ))
+}
+
+/// Rename issue labels through a label map, dropping those mapped to nothing
+///
+/// Labels are matched case-insensitively; unmapped labels keep their name.
+fn map_labels(labels: &[String], label_map: &BTreeMap<String, String>) -> Vec<String> {
+    let mut mapped: Vec<String> = Vec::new();
+    for label in labels {
+        let name = label_map
+            .iter()
+            .find(|(from, _)| from.eq_ignore_ascii_case(label))
+            .map_or(label.as_str(), |(_, to)| to.as_str())
+            .trim();
+        if !name.is_empty() && !mapped.iter().any(|existing| existing == name) {
+            mapped.push(name.to_string());
+        }
+    }
+    mapped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_labels() {
+        let label_map = BTreeMap::from([
+            ("Bug".to_string(), "bug".to_string()),
+            ("defect".to_string(), "bug".to_string()),
+            ("wontfix".to_string(), String::new()),
+        ]);
+        let labels = ["bug", "Defect", "wontfix", "docs"].map(str::to_string);
+
+        assert_eq!(map_labels(&labels, &label_map), vec!["bug", "docs"]);
+        assert!(map_labels(&[], &label_map).is_empty());
+    }
+}
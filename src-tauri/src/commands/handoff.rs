@@ -0,0 +1,222 @@
+//! Session Hand-off Commands
+//!
+//! Summarizes a session into a hand-off document that can seed a new session
+//! or be passed to a teammate.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::State;
+
+use crate::claude::oneshot::{extract_json_object, run_oneshot};
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// Number of recent messages included in the summarization prompt
+const HANDOFF_MESSAGE_LIMIT: i64 = 40;
+
+/// Maximum characters of a single message included in the prompt
+const HANDOFF_MESSAGE_CHARS: usize = 2000;
+
+/// Hand-off document returned to the frontend
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HandoffResponse {
+    pub id: String,
+    pub session_id: String,
+    pub goal: String,
+    pub decisions: Vec<String>,
+    pub files_in_flight: Vec<String>,
+    pub next_steps: Vec<String>,
+    /// Rendered Markdown, used as resume context for seeded sessions
+    pub content: String,
+    pub created_at: String,
+}
+
+/// Fields the summarization prompt asks Claude to produce
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct HandoffSummary {
+    goal: String,
+    decisions: Vec<String>,
+    next_steps: Vec<String>,
+}
+
+/// Generate and store a hand-off document for a session
+#[tauri::command]
+pub async fn session_handoff(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<HandoffResponse, AppError> {
+    state.command_metrics.measure("session_handoff", async {
+        let (title, working_directory, model): (String, String, Option<String>) = sqlx::query_as(
+            "SELECT title, working_directory, model FROM sessions WHERE id = ?",
+        )
+        .bind(&session_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Session", &session_id))?;
+
+        let mut messages = sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT role, content
+            FROM messages
+            WHERE session_id = ?
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(&session_id)
+        .bind(HANDOFF_MESSAGE_LIMIT)
+        .fetch_all(&state.db)
+        .await?;
+        messages.reverse();
+
+        if messages.is_empty() {
+            return Err(AppError::invalid_input("Session has no messages to hand off"));
+        }
+
+        // Files touched in the session are known exactly, so don't ask the model
+        let files_in_flight: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT path
+            FROM activity_log
+            WHERE session_id = ?
+            GROUP BY path
+            ORDER BY MAX(timestamp) DESC
+            "#,
+        )
+        .bind(&session_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        let prompt = build_handoff_prompt(&messages, &files_in_flight);
+        let claude_path = state.cli_manager.resolve_binary()?;
+        let output = run_oneshot(&claude_path, Path::new(&working_directory), &prompt, model.as_deref()).await?;
+
+        let summary = extract_json_object(&output)
+            .and_then(|json| serde_json::from_str::<HandoffSummary>(json).ok())
+            .unwrap_or_else(|| HandoffSummary {
+                goal: output.clone(),
+                ..Default::default()
+            });
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+        let content = render_handoff(&title, &summary, &files_in_flight);
+
+        sqlx::query(
+            r#"
+            INSERT INTO session_handoffs (id, session_id, goal, decisions, files_in_flight, next_steps, content, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&session_id)
+        .bind(&summary.goal)
+        .bind(serde_json::to_string(&summary.decisions)?)
+        .bind(serde_json::to_string(&files_in_flight)?)
+        .bind(serde_json::to_string(&summary.next_steps)?)
+        .bind(&content)
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
+
+        Ok(HandoffResponse {
+            id,
+            session_id,
+            goal: summary.goal,
+            decisions: summary.decisions,
+            files_in_flight,
+            next_steps: summary.next_steps,
+            content,
+            created_at: now,
+        })
+    })
+    .await
+}
+
+/// List a session's hand-off documents, newest first
+#[tauri::command]
+pub async fn session_handoff_list(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Vec<HandoffResponse>, AppError> {
+    state.command_metrics.measure("session_handoff_list", async {
+        let rows = sqlx::query_as::<_, (String, String, String, String, String, String, String, String)>(
+            r#"
+            SELECT id, session_id, goal, decisions, files_in_flight, next_steps, content, created_at
+            FROM session_handoffs
+            WHERE session_id = ?
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(&session_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|h| HandoffResponse {
+                id: h.0,
+                session_id: h.1,
+                goal: h.2,
+                decisions: serde_json::from_str(&h.3).unwrap_or_default(),
+                files_in_flight: serde_json::from_str(&h.4).unwrap_or_default(),
+                next_steps: serde_json::from_str(&h.5).unwrap_or_default(),
+                content: h.6,
+                created_at: h.7,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Build the summarization prompt from a transcript
+fn build_handoff_prompt(messages: &[(String, String)], files: &[String]) -> String {
+    let mut prompt = String::from(
+        "Summarize the following coding session as a hand-off for another engineer. \
+         Respond with only a JSON object of the form \
+         {\"goal\": string, \"decisions\": [string], \"nextSteps\": [string]} where goal is the \
+         current objective, decisions are choices already made and why, and nextSteps are the \
+         concrete remaining actions.\n\n",
+    );
+
+    if !files.is_empty() {
+        prompt.push_str("Files changed during the session:\n");
+        for file in files {
+            prompt.push_str(&format!("- {}\n", file));
+        }
+        prompt.push('\n');
+    }
+
+    prompt.push_str("Transcript:\n\n");
+    for (role, content) in messages {
+        let label = if role == "user" { "User" } else { "Assistant" };
+        let truncated: String = content.chars().take(HANDOFF_MESSAGE_CHARS).collect();
+        prompt.push_str(&format!("{}: {}\n\n", label, truncated));
+    }
+
+    prompt
+}
+
+/// Render a hand-off as Markdown
+fn render_handoff(title: &str, summary: &HandoffSummary, files: &[String]) -> String {
+    let mut out = format!("# Session hand-off: {}\n\n## Current goal\n\n{}\n", title, summary.goal);
+
+    let sections = [
+        ("Decisions made", &summary.decisions),
+        ("Files in flight", &files.to_vec()),
+        ("Next steps", &summary.next_steps),
+    ];
+    for (heading, items) in sections {
+        out.push_str(&format!("\n## {}\n\n", heading));
+        if items.is_empty() {
+            out.push_str("_None recorded._\n");
+        }
+        for item in items {
+            out.push_str(&format!("- {}\n", item));
+        }
+    }
+
+    out
+}
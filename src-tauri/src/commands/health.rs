@@ -0,0 +1,226 @@
+//! Health Commands
+//!
+//! A snapshot of each subsystem's state for the status page: the database
+//! pool, the CLI processes, the file watchers, and free disk space where the
+//! app keeps its data. Each part reports `ok`, `warning`, or `error`, and the
+//! overall status is the worst of them.
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::claude::CliProcessInfo;
+use crate::db;
+use crate::error::AppError;
+use crate::state::{AppState, ClaudeStatus};
+
+/// WAL size above which the database is reported as a warning
+const WAL_WARNING_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Free space below which the disk is reported as a warning
+const DISK_WARNING_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Free space below which the disk is reported as an error
+const DISK_ERROR_BYTES: u64 = 100 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemHealth {
+    /// The worst status of any subsystem
+    pub status: HealthStatus,
+    pub database: DatabaseHealth,
+    pub cli: CliHealth,
+    pub watchers: WatcherHealth,
+    pub disk: DiskHealth,
+    pub checked_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseHealth {
+    pub status: HealthStatus,
+    /// Open connections, idle or in use
+    pub connections: u32,
+    pub idle_connections: usize,
+    pub max_connections: u32,
+    /// Size of the write-ahead log not yet checkpointed into the database
+    pub wal_bytes: u64,
+    /// Set when the database didn't answer a query
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliHealth {
+    pub status: HealthStatus,
+    pub running_sessions: usize,
+    pub busy_sessions: usize,
+    /// Processes that exited but haven't been reaped yet
+    pub zombie_processes: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatcherHealth {
+    pub status: HealthStatus,
+    pub active_watchers: usize,
+    /// Watchers that stopped adding directories at their limit
+    pub limited_watchers: usize,
+    /// File events dropped since the app started
+    pub dropped_events: u64,
+    pub paused: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskHealth {
+    pub status: HealthStatus,
+    pub data_dir: String,
+    /// None when the space couldn't be read
+    pub available_bytes: Option<u64>,
+    pub total_bytes: Option<u64>,
+}
+
+/// Report the health of the database, CLI processes, file watchers, and disk
+#[tauri::command]
+pub async fn system_health(state: State<'_, AppState>) -> Result<SystemHealth, AppError> {
+    state.command_metrics.measure("system_health", async {
+        let database = database_health(&state).await;
+        let cli = cli_health(&state.cli_manager.list_processes().await);
+        let watchers = watcher_health(&state).await;
+        let disk = disk_health(&state);
+
+        Ok(SystemHealth {
+            status: [database.status, cli.status, watchers.status, disk.status]
+                .into_iter()
+                .max()
+                .unwrap_or(HealthStatus::Ok),
+            database,
+            cli,
+            watchers,
+            disk,
+            checked_at: chrono::Utc::now().to_rfc3339(),
+        })
+    })
+    .await
+}
+
+async fn database_health(state: &AppState) -> DatabaseHealth {
+    let wal_path = state.data_dir.join(format!("{}-wal", db::DB_FILE_NAME));
+    let wal_bytes = std::fs::metadata(wal_path).map(|m| m.len()).unwrap_or(0);
+    let error = sqlx::query("SELECT 1").execute(&state.db).await.err().map(|e| e.to_string());
+
+    let status = if error.is_some() {
+        HealthStatus::Error
+    } else if wal_bytes > WAL_WARNING_BYTES {
+        HealthStatus::Warning
+    } else {
+        HealthStatus::Ok
+    };
+
+    DatabaseHealth {
+        status,
+        connections: state.db.size(),
+        idle_connections: state.db.num_idle(),
+        max_connections: state.db.options().get_max_connections(),
+        wal_bytes,
+        error,
+    }
+}
+
+fn cli_health(processes: &[CliProcessInfo]) -> CliHealth {
+    let zombie_processes = processes.iter().filter(|p| p.pid.is_none()).count();
+
+    CliHealth {
+        status: if zombie_processes > 0 { HealthStatus::Warning } else { HealthStatus::Ok },
+        running_sessions: processes.len() - zombie_processes,
+        busy_sessions: processes.iter().filter(|p| p.status == ClaudeStatus::Busy).count(),
+        zombie_processes,
+    }
+}
+
+async fn watcher_health(state: &AppState) -> WatcherHealth {
+    let watchers = state.file_watcher.status(None).await;
+    let limited_watchers = watchers.iter().filter(|w| w.limit_reached).count();
+    let dropped_events = state.file_watcher.dropped_events();
+    let paused = state.file_watcher.is_paused();
+
+    WatcherHealth {
+        status: if limited_watchers > 0 || dropped_events > 0 || paused {
+            HealthStatus::Warning
+        } else {
+            HealthStatus::Ok
+        },
+        active_watchers: watchers.len(),
+        limited_watchers,
+        dropped_events,
+        paused,
+    }
+}
+
+fn disk_health(state: &AppState) -> DiskHealth {
+    let available_bytes = fs2::available_space(&state.data_dir).ok();
+
+    DiskHealth {
+        status: available_bytes.map_or(HealthStatus::Warning, disk_status),
+        data_dir: state.data_dir.to_string_lossy().to_string(),
+        available_bytes,
+        total_bytes: fs2::total_space(&state.data_dir).ok(),
+    }
+}
+
+fn disk_status(available_bytes: u64) -> HealthStatus {
+    if available_bytes < DISK_ERROR_BYTES {
+        HealthStatus::Error
+    } else if available_bytes < DISK_WARNING_BYTES {
+        HealthStatus::Warning
+    } else {
+        HealthStatus::Ok
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: Option<u32>, status: ClaudeStatus) -> CliProcessInfo {
+        CliProcessInfo {
+            session_id: "s1".to_string(),
+            pid,
+            working_dir: "/tmp".to_string(),
+            status,
+            started_at: "2026-10-15T09:30:00Z".to_string(),
+            uptime_secs: 0,
+        }
+    }
+
+    #[test]
+    fn test_cli_health() {
+        let health = cli_health(&[
+            process(Some(1), ClaudeStatus::Busy),
+            process(Some(2), ClaudeStatus::Ready),
+            process(None, ClaudeStatus::Ready),
+        ]);
+        assert_eq!(health.status, HealthStatus::Warning);
+        assert_eq!(health.running_sessions, 2);
+        assert_eq!(health.busy_sessions, 1);
+        assert_eq!(health.zombie_processes, 1);
+
+        assert_eq!(cli_health(&[]).status, HealthStatus::Ok);
+    }
+
+    #[test]
+    fn test_disk_status() {
+        assert_eq!(disk_status(10 * DISK_WARNING_BYTES), HealthStatus::Ok);
+        assert_eq!(disk_status(DISK_WARNING_BYTES - 1), HealthStatus::Warning);
+        assert_eq!(disk_status(DISK_ERROR_BYTES - 1), HealthStatus::Error);
+        assert!(HealthStatus::Error > HealthStatus::Warning);
+    }
+}
@@ -0,0 +1,157 @@
+//! Hook Commands
+//!
+//! Commands for managing the shell commands a project runs on session,
+//! message, file, and task events. See [`crate::hooks`] for how they run.
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::hooks::HookEvent;
+use crate::state::AppState;
+
+/// A command run on a project event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookResponse {
+    pub id: String,
+    pub project_id: String,
+    pub event: String,
+    pub command: String,
+    pub enabled: bool,
+    pub created_at: String,
+}
+
+/// Add a hook to a project
+#[tauri::command]
+pub async fn hook_create(
+    state: State<'_, AppState>,
+    project_id: String,
+    event: String,
+    command: String,
+) -> Result<HookResponse, AppError> {
+    state.command_metrics.measure("hook_create", async {
+        if HookEvent::parse(&event).is_none() {
+            let events: Vec<&str> = HookEvent::ALL.iter().map(HookEvent::as_str).collect();
+            return Err(AppError::invalid_input(format!(
+                "Hook event must be one of: {}",
+                events.join(", ")
+            )));
+        }
+        let command = command.trim().to_string();
+        if command.is_empty() {
+            return Err(AppError::invalid_input("Hook command cannot be empty"));
+        }
+
+        let project_exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ? AND deleted_at IS NULL)",
+        )
+        .bind(&project_id)
+        .fetch_one(&state.db)
+        .await?;
+        if !project_exists {
+            return Err(AppError::database_not_found("Project", &project_id));
+        }
+
+        let hook = HookResponse {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_id,
+            event,
+            command,
+            enabled: true,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        sqlx::query(
+            "INSERT INTO hooks (id, project_id, event, command, enabled, created_at) VALUES (?, ?, ?, ?, 1, ?)",
+        )
+        .bind(&hook.id)
+        .bind(&hook.project_id)
+        .bind(&hook.event)
+        .bind(&hook.command)
+        .bind(&hook.created_at)
+        .execute(&state.db)
+        .await?;
+
+        Ok(hook)
+    })
+    .await
+}
+
+/// List a project's hooks in the order they run
+#[tauri::command]
+pub async fn hook_list(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<Vec<HookResponse>, AppError> {
+    state.command_metrics.measure("hook_list", async {
+        let rows = sqlx::query_as::<_, (String, String, String, String, bool, String)>(
+            r#"
+            SELECT id, project_id, event, command, enabled, created_at
+            FROM hooks
+            WHERE project_id = ?
+            ORDER BY event, created_at ASC
+            "#,
+        )
+        .bind(&project_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| HookResponse {
+                id: row.0,
+                project_id: row.1,
+                event: row.2,
+                command: row.3,
+                enabled: row.4,
+                created_at: row.5,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Turn a hook on or off without deleting it
+#[tauri::command]
+pub async fn hook_set_enabled(
+    state: State<'_, AppState>,
+    hook_id: String,
+    enabled: bool,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("hook_set_enabled", async {
+        let result = sqlx::query("UPDATE hooks SET enabled = ? WHERE id = ?")
+            .bind(enabled)
+            .bind(&hook_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Hook", &hook_id));
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Delete a hook
+#[tauri::command]
+pub async fn hook_delete(
+    state: State<'_, AppState>,
+    hook_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("hook_delete", async {
+        let result = sqlx::query("DELETE FROM hooks WHERE id = ?")
+            .bind(&hook_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Hook", &hook_id));
+        }
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,302 @@
+//! Import Commands
+//!
+//! Dry-runs and applies task imports from other trackers. Both commands
+//! parse the same export with the source's importer, so the preview shows
+//! exactly what applying would create. Imported tasks land in the backlog,
+//! and their external IDs are recorded so re-importing skips them.
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, State};
+
+use crate::error::AppError;
+use crate::events::{emit_event, event_names, ImportProgressPayload};
+use crate::import::{self, FieldMapping, ImportItem, ImportPlan, ImportWarning};
+use crate::state::AppState;
+
+use super::label::DEFAULT_LABEL_COLOR;
+use super::task_status::default_status_for;
+
+/// Items included in a preview's sample
+const PREVIEW_SAMPLE_SIZE: usize = 20;
+
+/// Items created between progress events
+const PROGRESS_INTERVAL: usize = 25;
+
+/// What an import would do, without doing it
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreviewResponse {
+    pub source: String,
+    /// Items read from the export
+    pub total: usize,
+    pub to_create: usize,
+    /// External IDs that would be skipped as already imported or repeated
+    pub duplicates: Vec<String>,
+    pub new_labels: Vec<String>,
+    pub mapping: Vec<FieldMapping>,
+    /// Source fields that won't be imported
+    pub unmapped: Vec<String>,
+    pub warnings: Vec<ImportWarning>,
+    /// The first items that would be created
+    pub sample: Vec<ImportItem>,
+}
+
+/// Result of an import
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportApplyResponse {
+    pub source: String,
+    pub task_ids: Vec<String>,
+    pub skipped: usize,
+    pub labels_created: usize,
+    pub warnings: Vec<ImportWarning>,
+}
+
+/// Show what importing an export into a project would do
+///
+/// `source` is one of `csv`, `jira`, `linear`, `jsonl`, or `github`, and
+/// `content` is the export's text.
+#[tauri::command]
+pub async fn import_preview(
+    state: State<'_, AppState>,
+    project_id: String,
+    source: String,
+    content: String,
+) -> Result<ImportPreviewResponse, AppError> {
+    state.command_metrics.measure("import_preview", async {
+        let importer = import::importer_for(&source)?;
+        let batch = importer.parse(&content)?;
+        let (plan, _) = plan_import(&state.db, &project_id, importer.source(), &batch).await?;
+
+        Ok(ImportPreviewResponse {
+            source: importer.source().to_string(),
+            total: batch.items.len(),
+            to_create: plan.to_create.len(),
+            duplicates: plan.duplicates,
+            new_labels: plan.new_labels,
+            mapping: batch.mapping,
+            unmapped: batch.unmapped,
+            warnings: plan.warnings,
+            sample: plan.to_create.into_iter().take(PREVIEW_SAMPLE_SIZE).collect(),
+        })
+    })
+    .await
+}
+
+/// Import an export's new items into a project's backlog atomically
+///
+/// Emits `import_progress` as tasks are created.
+#[tauri::command]
+pub async fn import_apply(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    project_id: String,
+    source: String,
+    content: String,
+) -> Result<ImportApplyResponse, AppError> {
+    state.command_metrics.measure("import_apply", async {
+        let importer = import::importer_for(&source)?;
+        let batch = importer.parse(&content)?;
+        apply_batch(&app, &state.db, &project_id, importer.source(), &batch, None).await
+    })
+    .await
+}
+
+/// Create a batch's new items in one transaction, in a sprint or else the backlog
+///
+/// Shared by file imports and imports pulled from a tracker's API.
+pub(crate) async fn apply_batch(
+    app: &AppHandle,
+    db: &SqlitePool,
+    project_id: &str,
+    source: &str,
+    batch: &import::ImportBatch,
+    sprint_id: Option<&str>,
+) -> Result<ImportApplyResponse, AppError> {
+    let (plan, imported) = plan_import(db, project_id, source, batch).await?;
+
+    let mut status_ids = HashMap::new();
+    for category in ["todo", "in_progress", "done"] {
+        status_ids.insert(category, default_status_for(db, project_id, category).await?);
+    }
+    let mut label_ids: HashMap<String, String> =
+        sqlx::query_as::<_, (String, String)>("SELECT name, id FROM labels WHERE project_id = ?")
+            .bind(project_id)
+            .fetch_all(db)
+            .await?
+            .into_iter()
+            .collect();
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let total = plan.to_create.len();
+    let mut tx = db.begin().await?;
+
+    for name in &plan.new_labels {
+        let label_id = uuid::Uuid::new_v4().to_string();
+        sqlx::query("INSERT INTO labels (id, project_id, name, color, created_at) VALUES (?, ?, ?, ?, ?)")
+            .bind(&label_id)
+            .bind(project_id)
+            .bind(name)
+            .bind(DEFAULT_LABEL_COLOR)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+        label_ids.insert(name.clone(), label_id);
+    }
+
+    // Task IDs by external ID, for resolving parents
+    let mut task_ids_by_external = imported;
+    let mut task_ids = Vec::with_capacity(total);
+
+    for (index, item) in plan.to_create.iter().enumerate() {
+        let task_id = uuid::Uuid::new_v4().to_string();
+
+        sqlx::query(
+            r#"
+            INSERT INTO tasks (id, project_id, sprint_id, title, description, status, status_id, priority, estimated_hours, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&task_id)
+        .bind(project_id)
+        .bind(sprint_id)
+        .bind(&item.title)
+        .bind(&item.description)
+        .bind(&item.status)
+        .bind(status_ids.get(item.status.as_str()).cloned().flatten())
+        .bind(&item.priority)
+        .bind(item.estimated_hours)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO task_history (id, task_id, project_id, field, old_value, new_value, changed_by, changed_at)
+            VALUES (?, ?, ?, 'status', NULL, ?, 'user', ?)
+            "#,
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(&task_id)
+        .bind(project_id)
+        .bind(&item.status)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await?;
+
+        for label in &item.labels {
+            if let Some(label_id) = label_ids.get(label) {
+                sqlx::query("INSERT OR IGNORE INTO task_labels (task_id, label_id) VALUES (?, ?)")
+                    .bind(&task_id)
+                    .bind(label_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        if let Some(external_id) = &item.external_id {
+            // Replaces the record of a trashed task imported earlier
+            sqlx::query(
+                r#"
+                INSERT OR REPLACE INTO import_records (project_id, source, external_id, task_id, imported_at)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(project_id)
+            .bind(source)
+            .bind(external_id)
+            .bind(&task_id)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+            task_ids_by_external.insert(external_id.clone(), task_id.clone());
+        }
+
+        task_ids.push(task_id);
+
+        let processed = index + 1;
+        if processed % PROGRESS_INTERVAL == 0 || processed == total {
+            let _ = emit_event(
+                app,
+                event_names::IMPORT_PROGRESS,
+                ImportProgressPayload {
+                    project_id: project_id.to_string(),
+                    source: source.to_string(),
+                    processed,
+                    total,
+                },
+            );
+        }
+    }
+
+    // Parents may come later in the export, so link once every task exists
+    for (item, task_id) in plan.to_create.iter().zip(&task_ids) {
+        let parent_id = item
+            .parent_external_id
+            .as_ref()
+            .and_then(|parent| task_ids_by_external.get(parent));
+        if let Some(parent_id) = parent_id.filter(|parent_id| *parent_id != task_id) {
+            sqlx::query("UPDATE tasks SET parent_task_id = ? WHERE id = ?")
+                .bind(parent_id)
+                .bind(task_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(ImportApplyResponse {
+        source: source.to_string(),
+        task_ids,
+        skipped: plan.duplicates.len(),
+        labels_created: plan.new_labels.len(),
+        warnings: plan.warnings,
+    })
+}
+
+/// Dry-run an import against a project
+///
+/// Also returns the task IDs of live tasks previously imported from the
+/// source, keyed by external ID.
+async fn plan_import(
+    db: &SqlitePool,
+    project_id: &str,
+    source: &str,
+    batch: &import::ImportBatch,
+) -> Result<(ImportPlan, HashMap<String, String>), AppError> {
+    let exists: Option<String> = sqlx::query_scalar("SELECT id FROM projects WHERE id = ? AND deleted_at IS NULL")
+        .bind(project_id)
+        .fetch_optional(db)
+        .await?;
+    if exists.is_none() {
+        return Err(AppError::database_not_found("Project", project_id));
+    }
+
+    let imported: HashMap<String, String> = sqlx::query_as::<_, (String, String)>(
+        r#"
+        SELECT r.external_id, r.task_id FROM import_records r
+        JOIN tasks t ON t.id = r.task_id
+        WHERE r.project_id = ? AND r.source = ? AND t.deleted_at IS NULL
+        "#,
+    )
+    .bind(project_id)
+    .bind(source)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .collect();
+
+    let labels: HashSet<String> = sqlx::query_scalar("SELECT name FROM labels WHERE project_id = ?")
+        .bind(project_id)
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .collect();
+
+    let imported_ids = imported.keys().cloned().collect();
+    Ok((import::preview(batch, &imported_ids, &labels), imported))
+}
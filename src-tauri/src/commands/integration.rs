@@ -0,0 +1,642 @@
+//! Tracker Integration Commands
+//!
+//! Links a project to a Linear team or Jira project, imports its issues as
+//! tasks, and syncs status both ways. Each import record remembers the status
+//! the tracker had at the last sync, which tells which side changed since: a
+//! change on one side is copied to the other, while changes on both sides are
+//! logged as conflicts and left for the user to settle. Every sync writes to
+//! `integration_sync_log`.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, State};
+
+use super::import::apply_batch;
+use super::project::record_task_change;
+use super::task_status::default_status_for;
+use crate::error::AppError;
+use crate::import::{ImportBatch, ImportItem};
+use crate::integrations::{self, RemoteIssue, Tracker, TrackerConfig};
+use crate::state::AppState;
+
+/// Most log entries returned when no limit is given
+const DEFAULT_LOG_LIMIT: i64 = 200;
+
+/// A project's link to a tracker
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackerIntegrationResponse {
+    pub project_id: String,
+    /// Includes `tracker`
+    #[serde(flatten)]
+    pub config: TrackerConfig,
+    pub last_synced_at: Option<String>,
+    pub updated_at: String,
+}
+
+/// One thing a sync did, or couldn't do
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncLogEntry {
+    pub id: String,
+    pub tracker: String,
+    pub task_id: Option<String>,
+    pub external_id: Option<String>,
+    /// created, updated, pushed, conflict, or error
+    pub action: String,
+    pub message: String,
+    pub created_at: String,
+}
+
+/// Result of an import or push
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrationSyncResponse {
+    pub tracker: String,
+    /// Tasks created from new issues
+    pub created: usize,
+    /// Tasks whose status was taken from the tracker
+    pub updated: usize,
+    /// Issues whose status was taken from the task
+    pub pushed: usize,
+    pub conflicts: usize,
+    pub errors: usize,
+    /// What this sync logged
+    pub log: Vec<SyncLogEntry>,
+}
+
+/// What to do about a linked task whose status may differ from its issue's
+#[derive(Debug, PartialEq)]
+enum Reconcile {
+    InSync,
+    /// Only the issue changed
+    Pull,
+    /// Only the task changed
+    Push,
+    Conflict,
+}
+
+/// Compare a task's status with its issue's, given the issue's status at the last sync
+fn reconcile(local: &str, remote: &str, synced: Option<&str>) -> Reconcile {
+    if local == remote {
+        Reconcile::InSync
+    } else if synced == Some(local) {
+        Reconcile::Pull
+    } else if synced == Some(remote) {
+        Reconcile::Push
+    } else {
+        // Never synced, so there's no telling which side moved
+        Reconcile::Conflict
+    }
+}
+
+/// A task imported from a tracker
+struct Link {
+    task_id: String,
+    local_status: String,
+    remote_status: Option<String>,
+}
+
+/// Collects log entries for one sync
+struct SyncLog<'a> {
+    db: &'a SqlitePool,
+    project_id: &'a str,
+    tracker: &'a str,
+    entries: Vec<SyncLogEntry>,
+}
+
+impl SyncLog<'_> {
+    async fn add(
+        &mut self,
+        task_id: Option<&str>,
+        external_id: Option<&str>,
+        action: &str,
+        message: String,
+    ) -> Result<(), AppError> {
+        let entry = SyncLogEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            tracker: self.tracker.to_string(),
+            task_id: task_id.map(str::to_string),
+            external_id: external_id.map(str::to_string),
+            action: action.to_string(),
+            message,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO integration_sync_log (id, project_id, tracker, task_id, external_id, action, message, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&entry.id)
+        .bind(self.project_id)
+        .bind(&entry.tracker)
+        .bind(&entry.task_id)
+        .bind(&entry.external_id)
+        .bind(&entry.action)
+        .bind(&entry.message)
+        .bind(&entry.created_at)
+        .execute(self.db)
+        .await?;
+
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    fn count(&self, action: &str) -> usize {
+        self.entries.iter().filter(|entry| entry.action == action).count()
+    }
+
+    fn into_response(self, created: usize) -> IntegrationSyncResponse {
+        IntegrationSyncResponse {
+            tracker: self.tracker.to_string(),
+            created,
+            updated: self.count("updated"),
+            pushed: self.count("pushed"),
+            conflicts: self.count("conflict"),
+            errors: self.count("error"),
+            log: self.entries,
+        }
+    }
+}
+
+/// Get a project's tracker links
+#[tauri::command]
+pub async fn integration_get_all(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<Vec<TrackerIntegrationResponse>, AppError> {
+    state.command_metrics.measure("integration_get_all", async {
+        let rows = sqlx::query_as::<_, (String, String, Option<String>, String)>(
+            r#"
+            SELECT project_id, config, last_synced_at, updated_at
+            FROM tracker_integrations
+            WHERE project_id = ?
+            ORDER BY tracker
+            "#,
+        )
+        .bind(&project_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(project_id, config, last_synced_at, updated_at)| {
+                Some(TrackerIntegrationResponse {
+                    project_id,
+                    config: serde_json::from_str(&config).ok()?,
+                    last_synced_at,
+                    updated_at,
+                })
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Link a project to a tracker, or change its link to that tracker
+#[tauri::command]
+pub async fn integration_set(
+    state: State<'_, AppState>,
+    project_id: String,
+    config: TrackerConfig,
+) -> Result<TrackerIntegrationResponse, AppError> {
+    state.command_metrics.measure("integration_set", async {
+        config.validate()?;
+
+        let exists: Option<String> = sqlx::query_scalar("SELECT id FROM projects WHERE id = ? AND deleted_at IS NULL")
+            .bind(&project_id)
+            .fetch_optional(&state.db)
+            .await?;
+        if exists.is_none() {
+            return Err(AppError::database_not_found("Project", &project_id));
+        }
+
+        let now = chrono::Utc::now().to_rfc3339();
+        sqlx::query(
+            r#"
+            INSERT INTO tracker_integrations (project_id, tracker, config, updated_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(project_id, tracker) DO UPDATE SET
+                config = excluded.config,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&project_id)
+        .bind(config.tracker())
+        .bind(serde_json::to_string(&config).unwrap_or_else(|_| "{}".to_string()))
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
+
+        let last_synced_at: Option<String> = sqlx::query_scalar(
+            "SELECT last_synced_at FROM tracker_integrations WHERE project_id = ? AND tracker = ?",
+        )
+        .bind(&project_id)
+        .bind(config.tracker())
+        .fetch_one(&state.db)
+        .await?;
+
+        Ok(TrackerIntegrationResponse {
+            project_id,
+            config,
+            last_synced_at,
+            updated_at: now,
+        })
+    })
+    .await
+}
+
+/// Unlink a project from a tracker; imported tasks and the sync log are kept
+#[tauri::command]
+pub async fn integration_delete(
+    state: State<'_, AppState>,
+    project_id: String,
+    tracker: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("integration_delete", async {
+        sqlx::query("DELETE FROM tracker_integrations WHERE project_id = ? AND tracker = ?")
+            .bind(&project_id)
+            .bind(&tracker)
+            .execute(&state.db)
+            .await?;
+        Ok(())
+    })
+    .await
+}
+
+/// Import a linked tracker's issues and take status changes made there
+///
+/// New issues become tasks in `sprint_id`, or the backlog without one.
+/// Tasks imported before take their issue's status if only the issue
+/// changed since the last sync. `token` defaults to the tracker's saved
+/// secret, then its environment variable. Emits `import_progress` as tasks
+/// are created.
+#[tauri::command]
+pub async fn integration_import(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    project_id: String,
+    tracker: String,
+    sprint_id: Option<String>,
+    token: Option<String>,
+) -> Result<IntegrationSyncResponse, AppError> {
+    state.command_metrics.measure("integration_import", async {
+        let config = fetch_config(&state.db, &project_id, &tracker).await?;
+        if let Some(sprint_id) = &sprint_id {
+            let sprint_project: Option<String> =
+                sqlx::query_scalar("SELECT project_id FROM sprints WHERE id = ? AND deleted_at IS NULL")
+                    .bind(sprint_id)
+                    .fetch_optional(&state.db)
+                    .await?;
+            match sprint_project {
+                None => return Err(AppError::database_not_found("Sprint", sprint_id)),
+                Some(sprint_project) if sprint_project != project_id => {
+                    return Err(AppError::invalid_input("Sprint belongs to a different project"));
+                }
+                Some(_) => {}
+            }
+        }
+
+        let client = integrations::tracker_for(&config, token).await?;
+        let source = client.source();
+        let issues = client.list_issues().await?;
+        let links = fetch_links(&state.db, &project_id, source, false).await?;
+        let mut log = SyncLog {
+            db: &state.db,
+            project_id: &project_id,
+            tracker: source,
+            entries: Vec::new(),
+        };
+
+        for issue in &issues {
+            let Some(link) = links.get(&issue.external_id) else {
+                continue;
+            };
+            match reconcile(&link.local_status, &issue.status, link.remote_status.as_deref()) {
+                Reconcile::InSync => {
+                    set_remote_status(&state.db, &project_id, source, &issue.external_id, &issue.status).await?;
+                }
+                Reconcile::Pull => {
+                    pull_status(&state.db, &project_id, source, link, issue).await?;
+                    log.add(
+                        Some(&link.task_id),
+                        Some(&issue.external_id),
+                        "updated",
+                        format!("Status changed from {} to {} in {}", link.local_status, issue.status, source),
+                    )
+                    .await?;
+                }
+                // Left for `integration_push`
+                Reconcile::Push => {}
+                Reconcile::Conflict => {
+                    log_conflict(&mut log, link, &issue.external_id, &issue.status).await?;
+                }
+            }
+        }
+
+        let batch = ImportBatch {
+            items: issues.iter().enumerate().map(|(index, issue)| import_item(index, issue)).collect(),
+            ..Default::default()
+        };
+        let applied = apply_batch(&app, &state.db, &project_id, source, &batch, sprint_id.as_deref()).await?;
+
+        // Issues just imported start out in sync
+        let remote_statuses: HashMap<&str, &str> = issues
+            .iter()
+            .map(|issue| (issue.external_id.as_str(), issue.status.as_str()))
+            .collect();
+        for (external_id, link) in fetch_links(&state.db, &project_id, source, false).await? {
+            if links.contains_key(&external_id) {
+                continue;
+            }
+            if let Some(status) = remote_statuses.get(external_id.as_str()) {
+                set_remote_status(&state.db, &project_id, source, &external_id, status).await?;
+                log.add(
+                    Some(&link.task_id),
+                    Some(&external_id),
+                    "created",
+                    format!("Imported from {}", source),
+                )
+                .await?;
+            }
+        }
+
+        mark_synced(&state.db, &project_id, source).await?;
+        Ok(log.into_response(applied.task_ids.len()))
+    })
+    .await
+}
+
+/// Push task status changes to a linked tracker
+///
+/// Only tasks whose status changed since the last sync are pushed, and only
+/// if their issue didn't change too. `token` defaults to the tracker's saved
+/// secret, then its environment variable.
+#[tauri::command]
+pub async fn integration_push(
+    state: State<'_, AppState>,
+    project_id: String,
+    tracker: String,
+    token: Option<String>,
+) -> Result<IntegrationSyncResponse, AppError> {
+    state.command_metrics.measure("integration_push", async {
+        let config = fetch_config(&state.db, &project_id, &tracker).await?;
+        let client = integrations::tracker_for(&config, token).await?;
+        let source = client.source();
+        let links = fetch_links(&state.db, &project_id, source, true).await?;
+        let mut log = SyncLog {
+            db: &state.db,
+            project_id: &project_id,
+            tracker: source,
+            entries: Vec::new(),
+        };
+
+        for (external_id, link) in &links {
+            if let Err(e) = push_link(client.as_ref(), &mut log, external_id, link).await {
+                log.add(Some(&link.task_id), Some(external_id), "error", e.to_string())
+                    .await?;
+            }
+        }
+
+        mark_synced(&state.db, &project_id, source).await?;
+        Ok(log.into_response(0))
+    })
+    .await
+}
+
+/// Get a project's sync log, newest first
+#[tauri::command]
+pub async fn integration_sync_log_get(
+    state: State<'_, AppState>,
+    project_id: String,
+    limit: Option<i64>,
+) -> Result<Vec<SyncLogEntry>, AppError> {
+    state.command_metrics.measure("integration_sync_log_get", async {
+        let rows = sqlx::query_as::<_, (String, String, Option<String>, Option<String>, String, String, String)>(
+            r#"
+            SELECT id, tracker, task_id, external_id, action, message, created_at
+            FROM integration_sync_log
+            WHERE project_id = ?
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(&project_id)
+        .bind(limit.unwrap_or(DEFAULT_LOG_LIMIT).max(1))
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, tracker, task_id, external_id, action, message, created_at)| SyncLogEntry {
+                id,
+                tracker,
+                task_id,
+                external_id,
+                action,
+                message,
+                created_at,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Push one task's status, unless its issue changed too
+async fn push_link(
+    client: &dyn Tracker,
+    log: &mut SyncLog<'_>,
+    external_id: &str,
+    link: &Link,
+) -> Result<(), AppError> {
+    let issue = client.get_issue(external_id).await?;
+
+    match reconcile(&link.local_status, &issue.status, link.remote_status.as_deref()) {
+        Reconcile::InSync => {
+            set_remote_status(log.db, log.project_id, log.tracker, external_id, &issue.status).await?;
+        }
+        // Left for `integration_import`
+        Reconcile::Pull => {}
+        Reconcile::Push => {
+            client.set_status(external_id, &link.local_status).await?;
+            set_remote_status(log.db, log.project_id, log.tracker, external_id, &link.local_status).await?;
+            log.add(
+                Some(&link.task_id),
+                Some(external_id),
+                "pushed",
+                format!("Status changed from {} to {}", issue.status, link.local_status),
+            )
+            .await?;
+        }
+        Reconcile::Conflict => log_conflict(log, link, external_id, &issue.status).await?,
+    }
+    Ok(())
+}
+
+async fn log_conflict(log: &mut SyncLog<'_>, link: &Link, external_id: &str, remote_status: &str) -> Result<(), AppError> {
+    let tracker = log.tracker;
+    log.add(
+        Some(&link.task_id),
+        Some(external_id),
+        "conflict",
+        format!(
+            "Status is {} here but {} in {}; change either to match",
+            link.local_status, remote_status, tracker
+        ),
+    )
+    .await
+}
+
+/// Take an issue's status onto its task
+async fn pull_status(
+    db: &SqlitePool,
+    project_id: &str,
+    source: &str,
+    link: &Link,
+    issue: &RemoteIssue,
+) -> Result<(), AppError> {
+    let status_id = default_status_for(db, project_id, &issue.status).await?;
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut tx = db.begin().await?;
+
+    sqlx::query("UPDATE tasks SET status = ?, status_id = ?, updated_at = ? WHERE id = ?")
+        .bind(&issue.status)
+        .bind(&status_id)
+        .bind(&now)
+        .bind(&link.task_id)
+        .execute(&mut *tx)
+        .await?;
+    record_task_change(
+        &mut *tx,
+        &link.task_id,
+        project_id,
+        "status",
+        Some(&link.local_status),
+        Some(&issue.status),
+        source,
+        &now,
+    )
+    .await?;
+    set_remote_status(&mut *tx, project_id, source, &issue.external_id, &issue.status).await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn set_remote_status<'e>(
+    db: impl sqlx::Executor<'e, Database = sqlx::Sqlite>,
+    project_id: &str,
+    source: &str,
+    external_id: &str,
+    status: &str,
+) -> Result<(), AppError> {
+    sqlx::query("UPDATE import_records SET remote_status = ? WHERE project_id = ? AND source = ? AND external_id = ?")
+        .bind(status)
+        .bind(project_id)
+        .bind(source)
+        .bind(external_id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+async fn mark_synced(db: &SqlitePool, project_id: &str, tracker: &str) -> Result<(), AppError> {
+    sqlx::query("UPDATE tracker_integrations SET last_synced_at = ? WHERE project_id = ? AND tracker = ?")
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(project_id)
+        .bind(tracker)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+async fn fetch_config(db: &SqlitePool, project_id: &str, tracker: &str) -> Result<TrackerConfig, AppError> {
+    if !integrations::TRACKERS.contains(&tracker) {
+        return Err(AppError::invalid_input(format!(
+            "Unknown tracker: {} (expected one of {})",
+            tracker,
+            integrations::TRACKERS.join(", ")
+        )));
+    }
+
+    let config: Option<String> =
+        sqlx::query_scalar("SELECT config FROM tracker_integrations WHERE project_id = ? AND tracker = ?")
+            .bind(project_id)
+            .bind(tracker)
+            .fetch_optional(db)
+            .await?;
+    let config = config.ok_or_else(|| AppError::invalid_input(format!("Link the project to {} first", tracker)))?;
+    serde_json::from_str(&config)
+        .map_err(|e| AppError::invalid_input(format!("The {} link is invalid: {}", tracker, e)))
+}
+
+/// Live tasks imported from a tracker, by external ID
+///
+/// With `changed_only`, just those whose status differs from the tracker's
+/// at the last sync, or that were never synced.
+async fn fetch_links(
+    db: &SqlitePool,
+    project_id: &str,
+    source: &str,
+    changed_only: bool,
+) -> Result<HashMap<String, Link>, AppError> {
+    let rows = sqlx::query_as::<_, (String, String, String, Option<String>)>(
+        r#"
+        SELECT r.external_id, r.task_id, t.status, r.remote_status
+        FROM import_records r
+        JOIN tasks t ON t.id = r.task_id
+        WHERE r.project_id = ?1 AND r.source = ?2 AND t.deleted_at IS NULL
+          AND (?3 = 0 OR r.remote_status IS NULL OR r.remote_status != t.status)
+        "#,
+    )
+    .bind(project_id)
+    .bind(source)
+    .bind(changed_only)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(external_id, task_id, local_status, remote_status)| {
+            let link = Link {
+                task_id,
+                local_status,
+                remote_status,
+            };
+            (external_id, link)
+        })
+        .collect())
+}
+
+fn import_item(index: usize, issue: &RemoteIssue) -> ImportItem {
+    ImportItem {
+        external_id: Some(issue.external_id.clone()),
+        title: issue.title.clone(),
+        description: issue.description.clone(),
+        status: issue.status.clone(),
+        priority: issue.priority.clone(),
+        estimated_hours: None,
+        labels: issue.labels.clone(),
+        parent_external_id: None,
+        line: index + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconcile() {
+        assert_eq!(reconcile("done", "done", Some("todo")), Reconcile::InSync);
+        assert_eq!(reconcile("done", "done", None), Reconcile::InSync);
+        assert_eq!(reconcile("todo", "done", Some("todo")), Reconcile::Pull);
+        assert_eq!(reconcile("done", "todo", Some("todo")), Reconcile::Push);
+        assert_eq!(reconcile("done", "in_progress", Some("todo")), Reconcile::Conflict);
+        assert_eq!(reconcile("done", "todo", None), Reconcile::Conflict);
+    }
+}
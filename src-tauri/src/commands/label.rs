@@ -0,0 +1,262 @@
+//! Label Commands
+//!
+//! Commands for managing per-project task labels and assigning them to tasks.
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// Color given to labels created without one
+pub(crate) const DEFAULT_LABEL_COLOR: &str = "#6b7280";
+
+/// Label response
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelResponse {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub color: String,
+    pub created_at: String,
+}
+
+/// Create a label in a project
+#[tauri::command]
+pub async fn label_create(
+    state: State<'_, AppState>,
+    project_id: String,
+    name: String,
+    color: Option<String>,
+) -> Result<LabelResponse, AppError> {
+    state.command_metrics.measure("label_create", async {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(AppError::invalid_input("Label name cannot be empty"));
+        }
+
+        let color = match color {
+            Some(color) => normalize_color(&color)?,
+            None => DEFAULT_LABEL_COLOR.to_string(),
+        };
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+
+        sqlx::query(
+            r#"
+            INSERT INTO labels (id, project_id, name, color, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&project_id)
+        .bind(&name)
+        .bind(&color)
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
+
+        Ok(LabelResponse {
+            id,
+            project_id,
+            name,
+            color,
+            created_at: now,
+        })
+    })
+    .await
+}
+
+/// Rename or recolor a label
+#[tauri::command]
+pub async fn label_update(
+    state: State<'_, AppState>,
+    label_id: String,
+    name: Option<String>,
+    color: Option<String>,
+) -> Result<LabelResponse, AppError> {
+    state.command_metrics.measure("label_update", async {
+        let current = sqlx::query_as::<_, (String, String, String, String, String)>(
+            "SELECT id, project_id, name, color, created_at FROM labels WHERE id = ?",
+        )
+        .bind(&label_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Label", &label_id))?;
+
+        let name = match name {
+            Some(name) if name.trim().is_empty() => {
+                return Err(AppError::invalid_input("Label name cannot be empty"));
+            }
+            Some(name) => name.trim().to_string(),
+            None => current.2,
+        };
+        let color = match color {
+            Some(color) => normalize_color(&color)?,
+            None => current.3,
+        };
+
+        sqlx::query("UPDATE labels SET name = ?, color = ? WHERE id = ?")
+            .bind(&name)
+            .bind(&color)
+            .bind(&label_id)
+            .execute(&state.db)
+            .await?;
+
+        Ok(LabelResponse {
+            id: current.0,
+            project_id: current.1,
+            name,
+            color,
+            created_at: current.4,
+        })
+    })
+    .await
+}
+
+/// Delete a label and remove it from all tasks
+#[tauri::command]
+pub async fn label_delete(
+    state: State<'_, AppState>,
+    label_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("label_delete", async {
+        let result = sqlx::query("DELETE FROM labels WHERE id = ?")
+            .bind(&label_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Label", &label_id));
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Get a project's labels, or only those assigned to a task
+#[tauri::command]
+pub async fn label_get_all(
+    state: State<'_, AppState>,
+    project_id: String,
+    task_id: Option<String>,
+) -> Result<Vec<LabelResponse>, AppError> {
+    state.command_metrics.measure("label_get_all", async {
+        let labels = if let Some(tid) = task_id {
+            sqlx::query_as::<_, (String, String, String, String, String)>(
+                r#"
+                SELECT l.id, l.project_id, l.name, l.color, l.created_at
+                FROM labels l
+                JOIN task_labels tl ON tl.label_id = l.id
+                WHERE l.project_id = ? AND tl.task_id = ?
+                ORDER BY l.name ASC
+                "#,
+            )
+            .bind(&project_id)
+            .bind(&tid)
+            .fetch_all(&state.db)
+            .await?
+        } else {
+            sqlx::query_as::<_, (String, String, String, String, String)>(
+                r#"
+                SELECT id, project_id, name, color, created_at
+                FROM labels
+                WHERE project_id = ?
+                ORDER BY name ASC
+                "#,
+            )
+            .bind(&project_id)
+            .fetch_all(&state.db)
+            .await?
+        };
+
+        Ok(labels
+            .into_iter()
+            .map(|l| LabelResponse {
+                id: l.0,
+                project_id: l.1,
+                name: l.2,
+                color: l.3,
+                created_at: l.4,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Assign a label to a task
+#[tauri::command]
+pub async fn label_assign(
+    state: State<'_, AppState>,
+    task_id: String,
+    label_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("label_assign", async {
+        // Labels can only be used within their own project
+        let same_project: Option<bool> = sqlx::query_scalar(
+            r#"
+            SELECT t.project_id = l.project_id
+            FROM tasks t, labels l
+            WHERE t.id = ? AND l.id = ?
+            "#,
+        )
+        .bind(&task_id)
+        .bind(&label_id)
+        .fetch_optional(&state.db)
+        .await?;
+
+        match same_project {
+            None => return Err(AppError::not_found("Task or label not found")),
+            Some(false) => {
+                return Err(AppError::invalid_input("Label belongs to a different project"));
+            }
+            Some(true) => {}
+        }
+
+        sqlx::query("INSERT OR IGNORE INTO task_labels (task_id, label_id) VALUES (?, ?)")
+            .bind(&task_id)
+            .bind(&label_id)
+            .execute(&state.db)
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
+
+/// Remove a label from a task
+#[tauri::command]
+pub async fn label_remove(
+    state: State<'_, AppState>,
+    task_id: String,
+    label_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("label_remove", async {
+        sqlx::query("DELETE FROM task_labels WHERE task_id = ? AND label_id = ?")
+            .bind(&task_id)
+            .bind(&label_id)
+            .execute(&state.db)
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
+
+/// Validate a `#rgb` or `#rrggbb` color and lowercase it
+fn normalize_color(color: &str) -> Result<String, AppError> {
+    let color = color.trim();
+    let valid = color
+        .strip_prefix('#')
+        .map(|hex| (hex.len() == 3 || hex.len() == 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .unwrap_or(false);
+
+    if !valid {
+        return Err(AppError::invalid_input(format!("Invalid label color: {}", color)));
+    }
+
+    Ok(color.to_ascii_lowercase())
+}
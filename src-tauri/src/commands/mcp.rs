@@ -0,0 +1,154 @@
+//! MCP Server Commands
+//!
+//! Commands for managing the MCP servers made available to a project's
+//! sessions. Changes apply to CLI processes started afterwards.
+
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::mcp::{self, McpServer};
+use crate::state::AppState;
+use crate::{MCP_PROJECT_FLAG, MCP_SERVER_FLAG};
+
+/// Name Wingman's own MCP server is registered under
+const WINGMAN_SERVER_NAME: &str = "wingman";
+
+/// Add an MCP server to a project
+#[tauri::command]
+pub async fn project_mcp_add(
+    state: State<'_, AppState>,
+    project_id: String,
+    name: String,
+    command: String,
+    args: Option<Vec<String>>,
+    env: Option<HashMap<String, String>>,
+) -> Result<McpServer, AppError> {
+    state.command_metrics.measure("project_mcp_add", async {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(AppError::invalid_input("MCP server name cannot be empty"));
+        }
+        let command = command.trim().to_string();
+        if command.is_empty() {
+            return Err(AppError::invalid_input("MCP server command cannot be empty"));
+        }
+        let env = env.unwrap_or_default();
+        if env.keys().any(|key| key.trim().is_empty() || key.contains('=')) {
+            return Err(AppError::invalid_input("Environment variable names cannot be empty or contain '='"));
+        }
+
+        ensure_can_add(&state.db, &project_id, &name).await?;
+
+        let server = McpServer {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_id,
+            name,
+            command,
+            args: args.unwrap_or_default(),
+            env,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        mcp::insert(&state.db, &server).await?;
+
+        Ok(server)
+    })
+    .await
+}
+
+/// Make Wingman's own task tools available to a project's sessions
+///
+/// Registers this executable, run with `--mcp-server`, as the project's
+/// `wingman` MCP server, so Claude can create and update board tasks.
+#[tauri::command]
+pub async fn project_mcp_add_wingman(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<McpServer, AppError> {
+    state.command_metrics.measure("project_mcp_add_wingman", async {
+        ensure_can_add(&state.db, &project_id, WINGMAN_SERVER_NAME).await?;
+
+        let executable = std::env::current_exe()?;
+        let server = McpServer {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: WINGMAN_SERVER_NAME.to_string(),
+            command: executable.to_string_lossy().to_string(),
+            args: vec![
+                MCP_SERVER_FLAG.to_string(),
+                MCP_PROJECT_FLAG.to_string(),
+                project_id.clone(),
+            ],
+            env: HashMap::new(),
+            project_id,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+        mcp::insert(&state.db, &server).await?;
+
+        Ok(server)
+    })
+    .await
+}
+
+/// Remove an MCP server from its project
+#[tauri::command]
+pub async fn project_mcp_remove(
+    state: State<'_, AppState>,
+    server_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("project_mcp_remove", async {
+        let result = sqlx::query("DELETE FROM project_mcp_servers WHERE id = ?")
+            .bind(&server_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("MCP server", &server_id));
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// List a project's MCP servers by name
+#[tauri::command]
+pub async fn project_mcp_list(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<Vec<McpServer>, AppError> {
+    state.command_metrics.measure("project_mcp_list", async {
+        mcp::for_project(&state.db, &project_id).await
+    })
+    .await
+}
+
+/// Check the project exists and doesn't already have a server with the name
+async fn ensure_can_add(db: &SqlitePool, project_id: &str, name: &str) -> Result<(), AppError> {
+    let project_exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ? AND deleted_at IS NULL)",
+    )
+    .bind(project_id)
+    .fetch_one(db)
+    .await?;
+    if !project_exists {
+        return Err(AppError::database_not_found("Project", project_id));
+    }
+
+    let name_taken: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM project_mcp_servers WHERE project_id = ? AND name = ?)",
+    )
+    .bind(project_id)
+    .bind(name)
+    .fetch_one(db)
+    .await?;
+    if name_taken {
+        return Err(AppError::invalid_input(format!(
+            "The project already has an MCP server named '{}'",
+            name
+        )));
+    }
+
+    Ok(())
+}
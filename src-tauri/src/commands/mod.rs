@@ -3,11 +3,102 @@
 //! All Tauri commands are defined here and organized by domain.
 
 pub mod activity;
+pub mod analytics;
+pub mod api_server;
+pub mod autocommit;
+pub mod bundle;
+pub mod checkpoint;
+pub mod claude_sync;
+pub mod compact;
+pub mod context;
+pub mod daily_summary;
+pub mod database;
+pub mod delete_preview;
+pub mod diagnostics;
+pub mod dod;
+pub mod event_buffer;
+pub mod file_activity;
+pub mod file_sessions;
+pub mod git;
+pub mod github;
+pub mod handoff;
+pub mod health;
+pub mod hook;
+pub mod import;
+pub mod integration;
+pub mod label;
+pub mod mcp;
+pub mod plan;
+pub mod policy;
 pub mod project;
+pub mod prompt;
+pub mod recurring_task;
+pub mod run;
+pub mod script;
+pub mod search;
+pub mod secret;
 pub mod session;
+pub mod session_task;
+pub mod session_template;
+pub mod session_title;
+pub mod settings;
 pub mod system;
+pub mod task_status;
+pub mod terminal;
+pub mod time_entry;
+pub mod tool_call;
+pub mod trash;
+pub mod verification;
+pub mod webhook;
+pub mod window;
+pub mod worktree;
 
 pub use activity::*;
+pub use analytics::*;
+pub use api_server::*;
+pub use autocommit::*;
+pub use bundle::*;
+pub use checkpoint::*;
+pub use claude_sync::*;
+pub use compact::*;
+pub use context::*;
+pub use daily_summary::*;
+pub use database::*;
+pub use delete_preview::*;
+pub use diagnostics::*;
+pub use dod::*;
+pub use event_buffer::*;
+pub use file_activity::*;
+pub use file_sessions::*;
+pub use git::*;
+pub use github::*;
+pub use handoff::*;
+pub use health::*;
+pub use hook::*;
+pub use import::*;
+pub use integration::*;
+pub use label::*;
+pub use mcp::*;
+pub use plan::*;
+pub use policy::*;
 pub use project::*;
+pub use prompt::*;
+pub use recurring_task::*;
+pub use run::*;
+pub use script::*;
+pub use search::*;
+pub use secret::*;
 pub use session::*;
+pub use session_task::*;
+pub use session_template::*;
+pub use settings::*;
 pub use system::*;
+pub use task_status::*;
+pub use terminal::*;
+pub use time_entry::*;
+pub use tool_call::*;
+pub use trash::*;
+pub use verification::*;
+pub use webhook::*;
+pub use window::*;
+pub use worktree::*;
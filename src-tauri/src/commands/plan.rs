@@ -0,0 +1,395 @@
+//! Project Plan Commands
+//!
+//! Applies a complete nested plan (milestones → sprints → tasks with
+//! dependencies) to a project in a single transaction, so generated or
+//! imported plans are never half-applied.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tauri::State;
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+use super::task_status::default_status_for;
+
+/// A complete plan to apply to a project
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProjectPlan {
+    pub milestones: Vec<PlanMilestone>,
+    /// Tasks not assigned to any sprint
+    pub backlog: Vec<PlanTask>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanMilestone {
+    pub name: String,
+    pub description: Option<String>,
+    pub target_date: Option<String>,
+    #[serde(default)]
+    pub sprints: Vec<PlanSprint>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanSprint {
+    pub name: String,
+    pub description: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    #[serde(default)]
+    pub tasks: Vec<PlanTask>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanTask {
+    /// Plan-local reference used by `depends_on`
+    pub key: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    pub priority: Option<String>,
+    pub estimated_hours: Option<f64>,
+    /// Keys of other plan tasks, or IDs of existing tasks in the project
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+/// Result of applying a plan
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanApplyResponse {
+    pub milestone_ids: Vec<String>,
+    pub sprint_ids: Vec<String>,
+    /// Created task IDs keyed by plan task key
+    pub task_ids: HashMap<String, String>,
+    pub tasks_created: usize,
+}
+
+/// Apply a nested plan to a project atomically
+#[tauri::command]
+pub async fn project_plan_apply(
+    state: State<'_, AppState>,
+    project_id: String,
+    plan: ProjectPlan,
+) -> Result<PlanApplyResponse, AppError> {
+    state.command_metrics.measure("project_plan_apply", async {
+        let exists: Option<String> = sqlx::query_scalar("SELECT id FROM projects WHERE id = ? AND deleted_at IS NULL")
+            .bind(&project_id)
+            .fetch_optional(&state.db)
+            .await?;
+        if exists.is_none() {
+            return Err(AppError::database_not_found("Project", &project_id));
+        }
+
+        let existing_task_ids: HashSet<String> =
+            sqlx::query_scalar::<_, String>("SELECT id FROM tasks WHERE project_id = ? AND deleted_at IS NULL")
+                .bind(&project_id)
+                .fetch_all(&state.db)
+                .await?
+                .into_iter()
+                .collect();
+
+        validate_plan(&plan, &existing_task_ids)?;
+
+        let todo_status_id = default_status_for(&state.db, &project_id, "todo").await?;
+        let now = chrono::Utc::now().to_rfc3339();
+
+        let max_order: Option<i32> = sqlx::query_scalar(
+            "SELECT MAX(sort_order) FROM milestones WHERE project_id = ?",
+        )
+        .bind(&project_id)
+        .fetch_one(&state.db)
+        .await?;
+        let mut sort_order = max_order.unwrap_or(0);
+
+        let mut tx = state.db.begin().await?;
+        let mut milestone_ids = Vec::new();
+        let mut sprint_ids = Vec::new();
+        let mut task_ids = HashMap::new();
+        // (task ID, dependency keys or IDs) resolved once every task exists
+        let mut pending_dependencies = Vec::new();
+
+        // Sprint ID (None for the backlog) and the tasks to create in it
+        let mut task_groups: Vec<(Option<String>, &[PlanTask])> = Vec::new();
+
+        for milestone in &plan.milestones {
+            let milestone_id = uuid::Uuid::new_v4().to_string();
+            sort_order += 1;
+
+            sqlx::query(
+                r#"
+                INSERT INTO milestones (id, project_id, name, description, target_date, status, sort_order, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, 'planned', ?, ?, ?)
+                "#,
+            )
+            .bind(&milestone_id)
+            .bind(&project_id)
+            .bind(milestone.name.trim())
+            .bind(&milestone.description)
+            .bind(&milestone.target_date)
+            .bind(sort_order)
+            .bind(&now)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+
+            for sprint in &milestone.sprints {
+                let sprint_id = uuid::Uuid::new_v4().to_string();
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO sprints (id, project_id, milestone_id, name, description, start_date, end_date, status, created_at, updated_at)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, 'planned', ?, ?)
+                    "#,
+                )
+                .bind(&sprint_id)
+                .bind(&project_id)
+                .bind(&milestone_id)
+                .bind(sprint.name.trim())
+                .bind(&sprint.description)
+                .bind(&sprint.start_date)
+                .bind(&sprint.end_date)
+                .bind(&now)
+                .bind(&now)
+                .execute(&mut *tx)
+                .await?;
+
+                task_groups.push((Some(sprint_id.clone()), sprint.tasks.as_slice()));
+                sprint_ids.push(sprint_id);
+            }
+
+            milestone_ids.push(milestone_id);
+        }
+        task_groups.push((None, plan.backlog.as_slice()));
+
+        let mut tasks_created = 0;
+        for (sprint_id, tasks) in task_groups {
+            for task in tasks {
+                let task_id = uuid::Uuid::new_v4().to_string();
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO tasks (id, project_id, sprint_id, title, description, status, status_id, priority, estimated_hours, created_at, updated_at)
+                    VALUES (?, ?, ?, ?, ?, 'todo', ?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(&task_id)
+                .bind(&project_id)
+                .bind(&sprint_id)
+                .bind(task.title.trim())
+                .bind(&task.description)
+                .bind(&todo_status_id)
+                .bind(task.priority.as_deref().unwrap_or("medium"))
+                .bind(task.estimated_hours)
+                .bind(&now)
+                .bind(&now)
+                .execute(&mut *tx)
+                .await?;
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO task_history (id, task_id, project_id, field, old_value, new_value, changed_by, changed_at)
+                    VALUES (?, ?, ?, 'status', NULL, 'todo', 'user', ?)
+                    "#,
+                )
+                .bind(uuid::Uuid::new_v4().to_string())
+                .bind(&task_id)
+                .bind(&project_id)
+                .bind(&now)
+                .execute(&mut *tx)
+                .await?;
+
+                if let Some(key) = &task.key {
+                    task_ids.insert(key.clone(), task_id.clone());
+                }
+                if !task.depends_on.is_empty() {
+                    pending_dependencies.push((task_id, &task.depends_on));
+                }
+                tasks_created += 1;
+            }
+        }
+
+        for (task_id, depends_on) in pending_dependencies {
+            for reference in depends_on {
+                // Validation guarantees every reference is a plan key or an existing task
+                let depends_on_task_id = task_ids.get(reference).unwrap_or(reference);
+
+                sqlx::query(
+                    "INSERT OR IGNORE INTO task_dependencies (task_id, depends_on_task_id) VALUES (?, ?)",
+                )
+                .bind(&task_id)
+                .bind(depends_on_task_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(PlanApplyResponse {
+            milestone_ids,
+            sprint_ids,
+            task_ids,
+            tasks_created,
+        })
+    })
+    .await
+}
+
+/// Check a plan for missing fields, bad references, and dependency cycles
+fn validate_plan(plan: &ProjectPlan, existing_task_ids: &HashSet<String>) -> Result<(), AppError> {
+    let mut tasks: Vec<&PlanTask> = Vec::new();
+
+    for milestone in &plan.milestones {
+        if milestone.name.trim().is_empty() {
+            return Err(AppError::invalid_input("Milestone name cannot be empty"));
+        }
+        for sprint in &milestone.sprints {
+            if sprint.name.trim().is_empty() {
+                return Err(AppError::invalid_input(format!(
+                    "Sprint name cannot be empty (milestone \"{}\")",
+                    milestone.name
+                )));
+            }
+            tasks.extend(&sprint.tasks);
+        }
+    }
+    tasks.extend(&plan.backlog);
+
+    let mut keys = HashSet::new();
+    for task in &tasks {
+        if task.title.trim().is_empty() {
+            return Err(AppError::invalid_input("Task title cannot be empty"));
+        }
+        if let Some(priority) = &task.priority {
+            if !["low", "medium", "high"].contains(&priority.as_str()) {
+                return Err(AppError::invalid_input(format!(
+                    "Invalid priority \"{}\" on task \"{}\"",
+                    priority, task.title
+                )));
+            }
+        }
+        if let Some(key) = &task.key {
+            if !keys.insert(key.as_str()) {
+                return Err(AppError::invalid_input(format!("Duplicate task key \"{}\"", key)));
+            }
+        }
+    }
+
+    for task in &tasks {
+        for reference in &task.depends_on {
+            if task.key.as_deref() == Some(reference.as_str()) {
+                return Err(AppError::invalid_input(format!(
+                    "Task \"{}\" cannot depend on itself",
+                    task.title
+                )));
+            }
+            if !keys.contains(reference.as_str()) && !existing_task_ids.contains(reference) {
+                return Err(AppError::invalid_input(format!(
+                    "Task \"{}\" depends on unknown task \"{}\"",
+                    task.title, reference
+                )));
+            }
+        }
+    }
+
+    // Existing tasks can't depend on new ones, so cycles can only form within the plan
+    let edges: HashMap<&str, Vec<&str>> = tasks
+        .iter()
+        .filter_map(|task| {
+            let key = task.key.as_deref()?;
+            let deps = task
+                .depends_on
+                .iter()
+                .map(String::as_str)
+                .filter(|d| keys.contains(d))
+                .collect();
+            Some((key, deps))
+        })
+        .collect();
+
+    if let Some(key) = find_cycle(&edges) {
+        return Err(AppError::invalid_input(format!(
+            "Dependency cycle involving task \"{}\"",
+            key
+        )));
+    }
+
+    Ok(())
+}
+
+/// Find a node on a dependency cycle, if any
+fn find_cycle<'a>(edges: &HashMap<&'a str, Vec<&'a str>>) -> Option<&'a str> {
+    fn visit<'a>(
+        node: &'a str,
+        edges: &HashMap<&'a str, Vec<&'a str>>,
+        visiting: &mut HashSet<&'a str>,
+        done: &mut HashSet<&'a str>,
+    ) -> Option<&'a str> {
+        if done.contains(node) {
+            return None;
+        }
+        if !visiting.insert(node) {
+            return Some(node);
+        }
+        for next in edges.get(node).into_iter().flatten() {
+            if let Some(found) = visit(next, edges, visiting, done) {
+                return Some(found);
+            }
+        }
+        visiting.remove(node);
+        done.insert(node);
+        None
+    }
+
+    let mut visiting = HashSet::new();
+    let mut done = HashSet::new();
+    let mut nodes: Vec<&str> = edges.keys().copied().collect();
+    nodes.sort();
+
+    nodes
+        .into_iter()
+        .find_map(|node| visit(node, edges, &mut visiting, &mut done))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(key: &str, depends_on: &[&str]) -> PlanTask {
+        PlanTask {
+            key: Some(key.to_string()),
+            title: key.to_string(),
+            description: None,
+            priority: None,
+            estimated_hours: None,
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_validate_plan_references() {
+        let existing: HashSet<String> = ["existing".to_string()].into_iter().collect();
+
+        let valid = ProjectPlan {
+            milestones: vec![],
+            backlog: vec![task("a", &[]), task("b", &["a", "existing"])],
+        };
+        assert!(validate_plan(&valid, &existing).is_ok());
+
+        let unknown = ProjectPlan {
+            milestones: vec![],
+            backlog: vec![task("a", &["missing"])],
+        };
+        assert!(validate_plan(&unknown, &existing).is_err());
+
+        let cyclic = ProjectPlan {
+            milestones: vec![],
+            backlog: vec![task("a", &["c"]), task("b", &["a"]), task("c", &["b"])],
+        };
+        assert!(validate_plan(&cyclic, &existing).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+//! Project Policy Commands
+//!
+//! Per-project limits on what Claude may do. Disallowed actions are passed to
+//! the CLI as `--disallowedTools`, and Claude's file deletions in projects that
+//! forbid them are reported by the file watcher.
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// CLI tools that reach the network
+const NETWORK_TOOLS: &[&str] = &["WebFetch", "WebSearch"];
+
+/// Shell commands that delete files
+const DELETE_COMMANDS: &[&str] = &["Bash(rm:*)", "Bash(rmdir:*)", "Bash(git rm:*)", "Bash(git clean:*)"];
+
+/// What Claude is allowed to do in a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectPolicy {
+    pub allow_file_deletes: bool,
+    pub allow_shell: bool,
+    pub allow_network: bool,
+}
+
+impl Default for ProjectPolicy {
+    fn default() -> Self {
+        Self {
+            allow_file_deletes: true,
+            allow_shell: true,
+            allow_network: true,
+        }
+    }
+}
+
+impl ProjectPolicy {
+    /// Tool rules to pass to the CLI's `--disallowedTools`
+    pub fn disallowed_tools(&self) -> Vec<String> {
+        let mut tools = Vec::new();
+        if !self.allow_shell {
+            tools.push("Bash".to_string());
+        } else if !self.allow_file_deletes {
+            // With the shell off there's no need to single out delete commands
+            tools.extend(DELETE_COMMANDS.iter().map(|t| t.to_string()));
+        }
+        if !self.allow_network {
+            tools.extend(NETWORK_TOOLS.iter().map(|t| t.to_string()));
+        }
+        tools
+    }
+}
+
+/// Get a project's policy
+#[tauri::command]
+pub async fn project_policy_get(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<ProjectPolicy, AppError> {
+    state.command_metrics.measure("project_policy_get", async {
+        load_project_policy(&state.db, &project_id).await
+    })
+    .await
+}
+
+/// Replace a project's policy
+///
+/// Running CLI processes keep the tools they were started with; the file
+/// watcher's delete guard applies immediately.
+#[tauri::command]
+pub async fn project_policy_set(
+    state: State<'_, AppState>,
+    project_id: String,
+    policy: ProjectPolicy,
+) -> Result<ProjectPolicy, AppError> {
+    state.command_metrics.measure("project_policy_set", async {
+        let now = chrono::Utc::now().to_rfc3339();
+        let result = sqlx::query(
+            r#"
+            UPDATE projects
+            SET allow_file_deletes = ?, allow_shell = ?, allow_network = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(policy.allow_file_deletes)
+        .bind(policy.allow_shell)
+        .bind(policy.allow_network)
+        .bind(&now)
+        .bind(&project_id)
+        .execute(&state.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Project", &project_id));
+        }
+
+        let session_ids: Vec<String> = sqlx::query_scalar("SELECT id FROM sessions WHERE project_id = ?")
+            .bind(&project_id)
+            .fetch_all(&state.db)
+            .await?;
+        for session_id in session_ids {
+            state.file_watcher
+                .set_delete_guard(&session_id, !policy.allow_file_deletes)
+                .await;
+        }
+
+        Ok(policy)
+    })
+    .await
+}
+
+/// Load a project's policy
+pub(crate) async fn load_project_policy(db: &SqlitePool, project_id: &str) -> Result<ProjectPolicy, AppError> {
+    let (allow_file_deletes, allow_shell, allow_network) = sqlx::query_as::<_, (bool, bool, bool)>(
+        "SELECT allow_file_deletes, allow_shell, allow_network FROM projects WHERE id = ?",
+    )
+    .bind(project_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| AppError::database_not_found("Project", project_id))?;
+
+    Ok(ProjectPolicy {
+        allow_file_deletes,
+        allow_shell,
+        allow_network,
+    })
+}
+
+/// Load the policy of a session's project, or the permissive default
+pub(crate) async fn session_policy(db: &SqlitePool, session_id: &str) -> Result<ProjectPolicy, AppError> {
+    let project_id: Option<String> = sqlx::query_scalar("SELECT project_id FROM sessions WHERE id = ?")
+        .bind(session_id)
+        .fetch_optional(db)
+        .await?
+        .flatten();
+
+    match project_id {
+        Some(project_id) => load_project_policy(db, &project_id).await,
+        None => Ok(ProjectPolicy::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disallowed_tools() {
+        assert!(ProjectPolicy::default().disallowed_tools().is_empty());
+
+        let no_deletes = ProjectPolicy {
+            allow_file_deletes: false,
+            ..ProjectPolicy::default()
+        };
+        assert!(no_deletes.disallowed_tools().contains(&"Bash(rm:*)".to_string()));
+
+        let locked_down = ProjectPolicy {
+            allow_file_deletes: false,
+            allow_shell: false,
+            allow_network: false,
+        };
+        assert_eq!(locked_down.disallowed_tools(), vec!["Bash", "WebFetch", "WebSearch"]);
+    }
+}
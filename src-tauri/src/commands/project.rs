@@ -3,18 +3,30 @@
 //! Commands for managing projects, milestones, sprints, and tasks.
 
 use serde::{Deserialize, Serialize};
-use std::path::Path;
-use tauri::State;
-
-use crate::error::AppError;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, State};
+
+use crate::error::{AppError, ErrorCode};
+use crate::events::{emit_event, event_names, PreviewStatusPayload};
+use crate::hooks::{self, HookContext, HookEvent};
+use crate::integrations::github;
+use crate::notifications::parse_due_date;
 use crate::state::AppState;
+use crate::webhooks::{self, WebhookContext, WebhookEvent};
+
+use super::dod::{dod_enforcement, unchecked_dod_items, DodItemResponse};
+use super::task_status::{default_status_for, seed_default_statuses, status_category};
+use super::time_entry::task_time_map;
+use super::trash::{trash_milestone, trash_project, trash_sprint, trash_task};
 
 // ============================================================================
 // Request/Response Types
 // ============================================================================
 
 /// Project response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectResponse {
     pub id: String,
@@ -58,17 +70,29 @@ pub struct SprintResponse {
 }
 
 /// Task response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TaskResponse {
     pub id: String,
     pub project_id: String,
     pub sprint_id: Option<String>,
+    pub parent_task_id: Option<String>,
     pub title: String,
     pub description: Option<String>,
+    /// Status category (`todo`, `in_progress`, or `done`)
     pub status: String,
+    /// Kanban column the task sits in
+    pub status_id: Option<String>,
     pub priority: String,
     pub estimated_hours: Option<f64>,
+    /// RFC 3339 timestamp, or `YYYY-MM-DD` due by the end of that day
+    pub due_date: Option<String>,
+    /// Hours recorded by the task's timers, including a running one
+    pub actual_hours: f64,
+    /// When the task's running timer started
+    pub timer_started_at: Option<String>,
+    /// IDs of dependencies that are not done yet
+    pub blocked_by: Vec<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -84,6 +108,56 @@ pub struct SprintWithProgressResponse {
     pub progress: f64,
 }
 
+/// Task update result with any definition of done items left unchecked
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskUpdateResponse {
+    #[serde(flatten)]
+    pub task: TaskResponse,
+    /// Set when the task was just marked done without meeting the checklist
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unchecked_dod_items: Vec<DodItemResponse>,
+}
+
+/// A completed task that did not meet the definition of done
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskDodGapResponse {
+    pub task_id: String,
+    pub title: String,
+    pub unchecked_items: Vec<String>,
+}
+
+/// Definition of done compliance for a sprint's completed tasks
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DodComplianceResponse {
+    pub done_tasks: i32,
+    pub compliant_tasks: i32,
+    pub non_compliant: Vec<TaskDodGapResponse>,
+}
+
+/// Where a completed sprint's unfinished tasks went
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SprintRolloverResponse {
+    pub completed_tasks: i32,
+    pub incomplete_tasks: i32,
+    /// Unfinished tasks moved to `next_sprint`
+    pub moved_tasks: i32,
+    pub next_sprint: Option<SprintResponse>,
+}
+
+/// Sprint completion result
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SprintCompleteResponse {
+    #[serde(flatten)]
+    pub sprint: SprintResponse,
+    pub dod_compliance: DodComplianceResponse,
+    pub rollover: SprintRolloverResponse,
+}
+
 /// Dashboard stats response
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -114,48 +188,53 @@ pub async fn project_create(
     state: State<'_, AppState>,
     request: ProjectCreateRequest,
 ) -> Result<ProjectResponse, AppError> {
-    // Validate name
-    if request.name.trim().is_empty() {
-        return Err(AppError::invalid_input("Project name cannot be empty"));
-    }
-
-    // Validate root path
-    let dir_path = Path::new(&request.root_path);
-    if !dir_path.is_absolute() {
-        return Err(AppError::invalid_input("Root path must be an absolute path"));
-    }
-    if !dir_path.exists() {
-        return Err(AppError::directory_not_found(&request.root_path));
-    }
-
-    let id = uuid::Uuid::new_v4().to_string();
-    let now = chrono::Utc::now().to_rfc3339();
+    state.command_metrics.measure("project_create", async {
+        // Validate name
+        if request.name.trim().is_empty() {
+            return Err(AppError::invalid_input("Project name cannot be empty"));
+        }
+
+        // Validate root path
+        let dir_path = Path::new(&request.root_path);
+        if !dir_path.is_absolute() {
+            return Err(AppError::invalid_input("Root path must be an absolute path"));
+        }
+        if !dir_path.exists() {
+            return Err(AppError::directory_not_found(&request.root_path));
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+
+        sqlx::query(
+            r#"
+            INSERT INTO projects (id, name, description, root_path, preview_url, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&request.name)
+        .bind(&request.description)
+        .bind(&request.root_path)
+        .bind(&request.preview_url)
+        .bind(&now)
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
 
-    sqlx::query(
-        r#"
-        INSERT INTO projects (id, name, description, root_path, preview_url, created_at, updated_at)
-        VALUES (?, ?, ?, ?, ?, ?, ?)
-        "#,
-    )
-    .bind(&id)
-    .bind(&request.name)
-    .bind(&request.description)
-    .bind(&request.root_path)
-    .bind(&request.preview_url)
-    .bind(&now)
-    .bind(&now)
-    .execute(&state.db)
-    .await?;
+        seed_default_statuses(&state.db, &id, &now).await?;
 
-    Ok(ProjectResponse {
-        id,
-        name: request.name,
-        description: request.description,
-        root_path: request.root_path,
-        preview_url: request.preview_url,
-        created_at: now.clone(),
-        updated_at: now,
+        Ok(ProjectResponse {
+            id,
+            name: request.name,
+            description: request.description,
+            root_path: request.root_path,
+            preview_url: request.preview_url,
+            created_at: now.clone(),
+            updated_at: now,
+        })
     })
+    .await
 }
 
 /// Get all projects
@@ -163,28 +242,32 @@ pub async fn project_create(
 pub async fn project_get_all(
     state: State<'_, AppState>,
 ) -> Result<Vec<ProjectResponse>, AppError> {
-    let projects = sqlx::query_as::<_, (String, String, Option<String>, String, Option<String>, String, String)>(
-        r#"
-        SELECT id, name, description, root_path, preview_url, created_at, updated_at
-        FROM projects
-        ORDER BY updated_at DESC
-        "#,
-    )
-    .fetch_all(&state.db)
-    .await?;
+    state.command_metrics.measure("project_get_all", async {
+        let projects = sqlx::query_as::<_, (String, String, Option<String>, String, Option<String>, String, String)>(
+            r#"
+            SELECT id, name, description, root_path, preview_url, created_at, updated_at
+            FROM projects
+            WHERE deleted_at IS NULL
+            ORDER BY updated_at DESC
+            "#,
+        )
+        .fetch_all(&state.db)
+        .await?;
 
-    Ok(projects
-        .into_iter()
-        .map(|p| ProjectResponse {
-            id: p.0,
-            name: p.1,
-            description: p.2,
-            root_path: p.3,
-            preview_url: p.4,
-            created_at: p.5,
-            updated_at: p.6,
-        })
-        .collect())
+        Ok(projects
+            .into_iter()
+            .map(|p| ProjectResponse {
+                id: p.0,
+                name: p.1,
+                description: p.2,
+                root_path: p.3,
+                preview_url: p.4,
+                created_at: p.5,
+                updated_at: p.6,
+            })
+            .collect())
+    })
+    .await
 }
 
 /// Get a single project
@@ -193,27 +276,30 @@ pub async fn project_get(
     state: State<'_, AppState>,
     project_id: String,
 ) -> Result<ProjectResponse, AppError> {
-    let project = sqlx::query_as::<_, (String, String, Option<String>, String, Option<String>, String, String)>(
-        r#"
-        SELECT id, name, description, root_path, preview_url, created_at, updated_at
-        FROM projects
-        WHERE id = ?
-        "#,
-    )
-    .bind(&project_id)
-    .fetch_optional(&state.db)
-    .await?
-    .ok_or_else(|| AppError::database_not_found("Project", &project_id))?;
-
-    Ok(ProjectResponse {
-        id: project.0,
-        name: project.1,
-        description: project.2,
-        root_path: project.3,
-        preview_url: project.4,
-        created_at: project.5,
-        updated_at: project.6,
+    state.command_metrics.measure("project_get", async {
+        let project = sqlx::query_as::<_, (String, String, Option<String>, String, Option<String>, String, String)>(
+            r#"
+            SELECT id, name, description, root_path, preview_url, created_at, updated_at
+            FROM projects
+            WHERE id = ? AND deleted_at IS NULL
+            "#,
+        )
+        .bind(&project_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Project", &project_id))?;
+
+        Ok(ProjectResponse {
+            id: project.0,
+            name: project.1,
+            description: project.2,
+            root_path: project.3,
+            preview_url: project.4,
+            created_at: project.5,
+            updated_at: project.6,
+        })
     })
+    .await
 }
 
 /// Update a project
@@ -232,71 +318,209 @@ pub async fn project_update(
     project_id: String,
     request: ProjectUpdateRequest,
 ) -> Result<ProjectResponse, AppError> {
-    // Build update query dynamically
-    let now = chrono::Utc::now().to_rfc3339();
+    state.command_metrics.measure("project_update", async {
+        // Build update query dynamically
+        let now = chrono::Utc::now().to_rfc3339();
 
-    // Fetch current values first
-    let current = sqlx::query_as::<_, (String, String, Option<String>, String, Option<String>, String, String)>(
-        "SELECT id, name, description, root_path, preview_url, created_at, updated_at FROM projects WHERE id = ?",
-    )
-    .bind(&project_id)
-    .fetch_optional(&state.db)
-    .await?
-    .ok_or_else(|| AppError::database_not_found("Project", &project_id))?;
+        // Fetch current values first
+        let current = sqlx::query_as::<_, (String, String, Option<String>, String, Option<String>, String, String)>(
+            "SELECT id, name, description, root_path, preview_url, created_at, updated_at FROM projects WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(&project_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Project", &project_id))?;
 
-    let name = request.name.unwrap_or(current.1);
-    let description = request.description.or(current.2);
-    let root_path = request.root_path.unwrap_or(current.3);
-    let preview_url = request.preview_url.or(current.4);
+        let name = request.name.unwrap_or(current.1);
+        let description = request.description.or(current.2);
+        let root_path = request.root_path.unwrap_or(current.3);
+        let preview_url = request.preview_url.or(current.4);
 
-    // Validate
-    if name.trim().is_empty() {
-        return Err(AppError::invalid_input("Project name cannot be empty"));
-    }
+        // Validate
+        if name.trim().is_empty() {
+            return Err(AppError::invalid_input("Project name cannot be empty"));
+        }
 
-    sqlx::query(
-        r#"
-        UPDATE projects
-        SET name = ?, description = ?, root_path = ?, preview_url = ?, updated_at = ?
-        WHERE id = ?
-        "#,
-    )
-    .bind(&name)
-    .bind(&description)
-    .bind(&root_path)
-    .bind(&preview_url)
-    .bind(&now)
-    .bind(&project_id)
-    .execute(&state.db)
-    .await?;
+        sqlx::query(
+            r#"
+            UPDATE projects
+            SET name = ?, description = ?, root_path = ?, preview_url = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&name)
+        .bind(&description)
+        .bind(&root_path)
+        .bind(&preview_url)
+        .bind(&now)
+        .bind(&project_id)
+        .execute(&state.db)
+        .await?;
 
-    Ok(ProjectResponse {
-        id: project_id,
-        name,
-        description,
-        root_path,
-        preview_url,
-        created_at: current.5,
-        updated_at: now,
+        Ok(ProjectResponse {
+            id: project_id,
+            name,
+            description,
+            root_path,
+            preview_url,
+            created_at: current.5,
+            updated_at: now,
+        })
     })
+    .await
 }
 
 /// Delete a project
+///
+/// The project and everything in it go to the trash unless `permanent` is set.
 #[tauri::command]
 pub async fn project_delete(
     state: State<'_, AppState>,
     project_id: String,
+    permanent: Option<bool>,
 ) -> Result<(), AppError> {
-    let result = sqlx::query("DELETE FROM projects WHERE id = ?")
+    state.command_metrics.measure("project_delete", async {
+        if permanent.unwrap_or(false) {
+            let result = sqlx::query("DELETE FROM projects WHERE id = ?")
+                .bind(&project_id)
+                .execute(&state.db)
+                .await?;
+
+            if result.rows_affected() == 0 {
+                return Err(AppError::database_not_found("Project", &project_id));
+            }
+        } else {
+            trash_project(&state.db, &project_id).await?;
+        }
+
+        state.project_index.remove(&project_id).await;
+
+        Ok(())
+    })
+    .await
+}
+
+// ============================================================================
+// Preview Commands
+// ============================================================================
+
+/// Timeout for preview health probes
+const PREVIEW_PROBE_TIMEOUT_MS: u64 = 3000;
+
+/// Preview server health check result
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewHealthResponse {
+    pub url: String,
+    pub reachable: bool,
+    pub status_code: Option<u16>,
+    pub content_type: Option<String>,
+    pub is_html: bool,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+    pub checked_at: String,
+}
+
+/// Probe a project's preview URL and report whether the dev server is up
+#[tauri::command]
+pub async fn project_check_preview(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<PreviewHealthResponse, AppError> {
+    state.command_metrics.measure("project_check_preview", async {
+        let preview_url: Option<String> = sqlx::query_scalar(
+            "SELECT preview_url FROM projects WHERE id = ?",
+        )
         .bind(&project_id)
-        .execute(&state.db)
-        .await?;
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Project", &project_id))?;
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::database_not_found("Project", &project_id));
-    }
+        let url = preview_url
+            .filter(|u| !u.trim().is_empty())
+            .ok_or_else(|| AppError::invalid_input("Project has no preview URL configured"))?;
 
-    Ok(())
+        let health = probe_preview_url(&url).await;
+
+        // Only notify the frontend when reachability changes
+        let previous = {
+            let mut known = state.preview_health.write().await;
+            known.insert(project_id.clone(), health.reachable)
+        };
+
+        if previous != Some(health.reachable) {
+            let _ = emit_event(
+                &app,
+                event_names::PREVIEW_STATUS,
+                PreviewStatusPayload {
+                    project_id,
+                    url: health.url.clone(),
+                    reachable: health.reachable,
+                    status_code: health.status_code,
+                    error: health.error.clone(),
+                },
+            );
+        }
+
+        Ok(health)
+    })
+    .await
+}
+
+/// Send a HEAD request to the preview URL and measure the response
+async fn probe_preview_url(url: &str) -> PreviewHealthResponse {
+    let checked_at = chrono::Utc::now().to_rfc3339();
+    let unreachable = |error: String| PreviewHealthResponse {
+        url: url.to_string(),
+        reachable: false,
+        status_code: None,
+        content_type: None,
+        is_html: false,
+        latency_ms: None,
+        error: Some(error),
+        checked_at: checked_at.clone(),
+    };
+
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_millis(PREVIEW_PROBE_TIMEOUT_MS))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => return unreachable(e.to_string()),
+    };
+
+    let started = Instant::now();
+    match client.head(url).send().await {
+        Ok(response) => {
+            let latency_ms = started.elapsed().as_millis() as u64;
+            let status = response.status();
+            let content_type = response
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string());
+            let is_html = content_type
+                .as_deref()
+                .map(|ct| ct.starts_with("text/html") || ct.starts_with("application/xhtml"))
+                .unwrap_or(false);
+
+            // Anything below 500 means a server is answering on that port
+            PreviewHealthResponse {
+                url: url.to_string(),
+                reachable: !status.is_server_error(),
+                status_code: Some(status.as_u16()),
+                content_type,
+                is_html,
+                latency_ms: Some(latency_ms),
+                error: None,
+                checked_at,
+            }
+        }
+        Err(e) if e.is_timeout() => unreachable("Preview server timed out".to_string()),
+        Err(e) if e.is_connect() => unreachable("Preview server is not running".to_string()),
+        Err(e) => unreachable(e.to_string()),
+    }
 }
 
 // ============================================================================
@@ -317,51 +541,54 @@ pub async fn milestone_create(
     state: State<'_, AppState>,
     request: MilestoneCreateRequest,
 ) -> Result<MilestoneResponse, AppError> {
-    if request.name.trim().is_empty() {
-        return Err(AppError::invalid_input("Milestone name cannot be empty"));
-    }
+    state.command_metrics.measure("milestone_create", async {
+        if request.name.trim().is_empty() {
+            return Err(AppError::invalid_input("Milestone name cannot be empty"));
+        }
 
-    let id = uuid::Uuid::new_v4().to_string();
-    let now = chrono::Utc::now().to_rfc3339();
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
 
-    // Get max sort_order for this project
-    let max_order: Option<i32> = sqlx::query_scalar(
-        "SELECT MAX(sort_order) FROM milestones WHERE project_id = ?",
-    )
-    .bind(&request.project_id)
-    .fetch_one(&state.db)
-    .await?;
+        // Get max sort_order for this project
+        let max_order: Option<i32> = sqlx::query_scalar(
+            "SELECT MAX(sort_order) FROM milestones WHERE project_id = ?",
+        )
+        .bind(&request.project_id)
+        .fetch_one(&state.db)
+        .await?;
 
-    let sort_order = max_order.unwrap_or(0) + 1;
+        let sort_order = max_order.unwrap_or(0) + 1;
 
-    sqlx::query(
-        r#"
-        INSERT INTO milestones (id, project_id, name, description, target_date, status, sort_order, created_at, updated_at)
-        VALUES (?, ?, ?, ?, ?, 'planned', ?, ?, ?)
-        "#,
-    )
-    .bind(&id)
-    .bind(&request.project_id)
-    .bind(&request.name)
-    .bind(&request.description)
-    .bind(&request.target_date)
-    .bind(sort_order)
-    .bind(&now)
-    .bind(&now)
-    .execute(&state.db)
-    .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO milestones (id, project_id, name, description, target_date, status, sort_order, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, 'planned', ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&request.project_id)
+        .bind(&request.name)
+        .bind(&request.description)
+        .bind(&request.target_date)
+        .bind(sort_order)
+        .bind(&now)
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
 
-    Ok(MilestoneResponse {
-        id,
-        project_id: request.project_id,
-        name: request.name,
-        description: request.description,
-        target_date: request.target_date,
-        status: "planned".to_string(),
-        sort_order,
-        created_at: now.clone(),
-        updated_at: now,
+        Ok(MilestoneResponse {
+            id,
+            project_id: request.project_id,
+            name: request.name,
+            description: request.description,
+            target_date: request.target_date,
+            status: "planned".to_string(),
+            sort_order,
+            created_at: now.clone(),
+            updated_at: now,
+        })
     })
+    .await
 }
 
 /// Get all milestones for a project
@@ -370,32 +597,35 @@ pub async fn milestone_get_all(
     state: State<'_, AppState>,
     project_id: String,
 ) -> Result<Vec<MilestoneResponse>, AppError> {
-    let milestones = sqlx::query_as::<_, (String, String, String, Option<String>, Option<String>, String, i32, String, String)>(
-        r#"
-        SELECT id, project_id, name, description, target_date, status, sort_order, created_at, updated_at
-        FROM milestones
-        WHERE project_id = ?
-        ORDER BY sort_order ASC
-        "#,
-    )
-    .bind(&project_id)
-    .fetch_all(&state.db)
-    .await?;
+    state.command_metrics.measure("milestone_get_all", async {
+        let milestones = sqlx::query_as::<_, (String, String, String, Option<String>, Option<String>, String, i32, String, String)>(
+            r#"
+            SELECT id, project_id, name, description, target_date, status, sort_order, created_at, updated_at
+            FROM milestones
+            WHERE project_id = ? AND deleted_at IS NULL
+            ORDER BY sort_order ASC
+            "#,
+        )
+        .bind(&project_id)
+        .fetch_all(&state.db)
+        .await?;
 
-    Ok(milestones
-        .into_iter()
-        .map(|m| MilestoneResponse {
-            id: m.0,
-            project_id: m.1,
-            name: m.2,
-            description: m.3,
-            target_date: m.4,
-            status: m.5,
-            sort_order: m.6,
-            created_at: m.7,
-            updated_at: m.8,
-        })
-        .collect())
+        Ok(milestones
+            .into_iter()
+            .map(|m| MilestoneResponse {
+                id: m.0,
+                project_id: m.1,
+                name: m.2,
+                description: m.3,
+                target_date: m.4,
+                status: m.5,
+                sort_order: m.6,
+                created_at: m.7,
+                updated_at: m.8,
+            })
+            .collect())
+    })
+    .await
 }
 
 #[derive(Debug, Deserialize)]
@@ -413,70 +643,84 @@ pub async fn milestone_update(
     milestone_id: String,
     request: MilestoneUpdateRequest,
 ) -> Result<MilestoneResponse, AppError> {
-    let now = chrono::Utc::now().to_rfc3339();
+    state.command_metrics.measure("milestone_update", async {
+        let now = chrono::Utc::now().to_rfc3339();
 
-    let current = sqlx::query_as::<_, (String, String, String, Option<String>, Option<String>, String, i32, String, String)>(
-        "SELECT id, project_id, name, description, target_date, status, sort_order, created_at, updated_at FROM milestones WHERE id = ?",
-    )
-    .bind(&milestone_id)
-    .fetch_optional(&state.db)
-    .await?
-    .ok_or_else(|| AppError::database_not_found("Milestone", &milestone_id))?;
+        let current = sqlx::query_as::<_, (String, String, String, Option<String>, Option<String>, String, i32, String, String)>(
+            "SELECT id, project_id, name, description, target_date, status, sort_order, created_at, updated_at FROM milestones WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(&milestone_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Milestone", &milestone_id))?;
 
-    let name = request.name.unwrap_or(current.2);
-    let description = request.description.or(current.3);
-    let target_date = request.target_date.or(current.4);
-    let status = request.status.unwrap_or(current.5);
+        let name = request.name.unwrap_or(current.2);
+        let description = request.description.or(current.3);
+        let target_date = request.target_date.or(current.4);
+        let status = request.status.unwrap_or(current.5);
 
-    // Validate status
-    if !["planned", "in_progress", "completed"].contains(&status.as_str()) {
-        return Err(AppError::invalid_input("Invalid milestone status"));
-    }
+        // Validate status
+        if !["planned", "in_progress", "completed"].contains(&status.as_str()) {
+            return Err(AppError::invalid_input("Invalid milestone status"));
+        }
 
-    sqlx::query(
-        r#"
-        UPDATE milestones
-        SET name = ?, description = ?, target_date = ?, status = ?, updated_at = ?
-        WHERE id = ?
-        "#,
-    )
-    .bind(&name)
-    .bind(&description)
-    .bind(&target_date)
-    .bind(&status)
-    .bind(&now)
-    .bind(&milestone_id)
-    .execute(&state.db)
-    .await?;
+        sqlx::query(
+            r#"
+            UPDATE milestones
+            SET name = ?, description = ?, target_date = ?, status = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&name)
+        .bind(&description)
+        .bind(&target_date)
+        .bind(&status)
+        .bind(&now)
+        .bind(&milestone_id)
+        .execute(&state.db)
+        .await?;
 
-    Ok(MilestoneResponse {
-        id: milestone_id,
-        project_id: current.1,
-        name,
-        description,
-        target_date,
-        status,
-        sort_order: current.6,
-        created_at: current.7,
-        updated_at: now,
+        Ok(MilestoneResponse {
+            id: milestone_id,
+            project_id: current.1,
+            name,
+            description,
+            target_date,
+            status,
+            sort_order: current.6,
+            created_at: current.7,
+            updated_at: now,
+        })
     })
+    .await
 }
 
+/// Delete a milestone, detaching its sprints
+///
+/// The milestone goes to the trash unless `permanent` is set.
 #[tauri::command]
 pub async fn milestone_delete(
     state: State<'_, AppState>,
     milestone_id: String,
+    permanent: Option<bool>,
 ) -> Result<(), AppError> {
-    let result = sqlx::query("DELETE FROM milestones WHERE id = ?")
-        .bind(&milestone_id)
-        .execute(&state.db)
-        .await?;
+    state.command_metrics.measure("milestone_delete", async {
+        if !permanent.unwrap_or(false) {
+            return trash_milestone(&state.db, &milestone_id).await;
+        }
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::database_not_found("Milestone", &milestone_id));
-    }
+        let result = sqlx::query("DELETE FROM milestones WHERE id = ?")
+            .bind(&milestone_id)
+            .execute(&state.db)
+            .await?;
 
-    Ok(())
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Milestone", &milestone_id));
+        }
+
+        Ok(())
+    })
+    .await
 }
 
 #[tauri::command]
@@ -484,15 +728,18 @@ pub async fn milestone_reorder(
     state: State<'_, AppState>,
     milestone_ids: Vec<String>,
 ) -> Result<(), AppError> {
-    for (index, id) in milestone_ids.iter().enumerate() {
-        sqlx::query("UPDATE milestones SET sort_order = ? WHERE id = ?")
-            .bind(index as i32)
-            .bind(id)
-            .execute(&state.db)
-            .await?;
-    }
-
-    Ok(())
+    state.command_metrics.measure("milestone_reorder", async {
+        for (index, id) in milestone_ids.iter().enumerate() {
+            sqlx::query("UPDATE milestones SET sort_order = ? WHERE id = ?")
+                .bind(index as i32)
+                .bind(id)
+                .execute(&state.db)
+                .await?;
+        }
+
+        Ok(())
+    })
+    .await
 }
 
 // ============================================================================
@@ -515,43 +762,46 @@ pub async fn sprint_create(
     state: State<'_, AppState>,
     request: SprintCreateRequest,
 ) -> Result<SprintResponse, AppError> {
-    if request.name.trim().is_empty() {
-        return Err(AppError::invalid_input("Sprint name cannot be empty"));
-    }
+    state.command_metrics.measure("sprint_create", async {
+        if request.name.trim().is_empty() {
+            return Err(AppError::invalid_input("Sprint name cannot be empty"));
+        }
 
-    let id = uuid::Uuid::new_v4().to_string();
-    let now = chrono::Utc::now().to_rfc3339();
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
 
-    sqlx::query(
-        r#"
-        INSERT INTO sprints (id, project_id, milestone_id, name, description, start_date, end_date, status, created_at, updated_at)
-        VALUES (?, ?, ?, ?, ?, ?, ?, 'planned', ?, ?)
-        "#,
-    )
-    .bind(&id)
-    .bind(&request.project_id)
-    .bind(&request.milestone_id)
-    .bind(&request.name)
-    .bind(&request.description)
-    .bind(&request.start_date)
-    .bind(&request.end_date)
-    .bind(&now)
-    .bind(&now)
-    .execute(&state.db)
-    .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO sprints (id, project_id, milestone_id, name, description, start_date, end_date, status, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, 'planned', ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&request.project_id)
+        .bind(&request.milestone_id)
+        .bind(&request.name)
+        .bind(&request.description)
+        .bind(&request.start_date)
+        .bind(&request.end_date)
+        .bind(&now)
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
 
-    Ok(SprintResponse {
-        id,
-        project_id: request.project_id,
-        milestone_id: request.milestone_id,
-        name: request.name,
-        description: request.description,
-        start_date: request.start_date,
-        end_date: request.end_date,
-        status: "planned".to_string(),
-        created_at: now.clone(),
-        updated_at: now,
+        Ok(SprintResponse {
+            id,
+            project_id: request.project_id,
+            milestone_id: request.milestone_id,
+            name: request.name,
+            description: request.description,
+            start_date: request.start_date,
+            end_date: request.end_date,
+            status: "planned".to_string(),
+            created_at: now.clone(),
+            updated_at: now,
+        })
     })
+    .await
 }
 
 /// Get all sprints for a project
@@ -560,60 +810,63 @@ pub async fn sprint_get_all(
     state: State<'_, AppState>,
     project_id: String,
 ) -> Result<Vec<SprintWithProgressResponse>, AppError> {
-    let sprints = sqlx::query_as::<_, (String, String, Option<String>, String, Option<String>, Option<String>, Option<String>, String, String, String)>(
-        r#"
-        SELECT id, project_id, milestone_id, name, description, start_date, end_date, status, created_at, updated_at
-        FROM sprints
-        WHERE project_id = ?
-        ORDER BY created_at ASC
-        "#,
-    )
-    .bind(&project_id)
-    .fetch_all(&state.db)
-    .await?;
-
-    let mut result = Vec::new();
-    for s in sprints {
-        // Get task counts
-        let (task_count, completed_count): (i32, i32) = sqlx::query_as(
+    state.command_metrics.measure("sprint_get_all", async {
+        let sprints = sqlx::query_as::<_, (String, String, Option<String>, String, Option<String>, Option<String>, Option<String>, String, String, String)>(
             r#"
-            SELECT
-                COUNT(*) as total,
-                COALESCE(SUM(CASE WHEN status = 'done' THEN 1 ELSE 0 END), 0) as completed
-            FROM tasks
-            WHERE sprint_id = ?
+            SELECT id, project_id, milestone_id, name, description, start_date, end_date, status, created_at, updated_at
+            FROM sprints
+            WHERE project_id = ? AND deleted_at IS NULL
+            ORDER BY created_at ASC
             "#,
         )
-        .bind(&s.0)
-        .fetch_one(&state.db)
+        .bind(&project_id)
+        .fetch_all(&state.db)
         .await?;
 
-        let progress = if task_count > 0 {
-            (completed_count as f64 / task_count as f64) * 100.0
-        } else {
-            0.0
-        };
-
-        result.push(SprintWithProgressResponse {
-            sprint: SprintResponse {
-                id: s.0,
-                project_id: s.1,
-                milestone_id: s.2,
-                name: s.3,
-                description: s.4,
-                start_date: s.5,
-                end_date: s.6,
-                status: s.7,
-                created_at: s.8,
-                updated_at: s.9,
-            },
-            task_count,
-            completed_count,
-            progress,
-        });
-    }
+        let mut result = Vec::new();
+        for s in sprints {
+            // Get task counts
+            let (task_count, completed_count): (i32, i32) = sqlx::query_as(
+                r#"
+                SELECT
+                    COUNT(*) as total,
+                    COALESCE(SUM(CASE WHEN status = 'done' THEN 1 ELSE 0 END), 0) as completed
+                FROM tasks
+                WHERE sprint_id = ? AND deleted_at IS NULL
+                "#,
+            )
+            .bind(&s.0)
+            .fetch_one(&state.db)
+            .await?;
 
-    Ok(result)
+            let progress = if task_count > 0 {
+                (completed_count as f64 / task_count as f64) * 100.0
+            } else {
+                0.0
+            };
+
+            result.push(SprintWithProgressResponse {
+                sprint: SprintResponse {
+                    id: s.0,
+                    project_id: s.1,
+                    milestone_id: s.2,
+                    name: s.3,
+                    description: s.4,
+                    start_date: s.5,
+                    end_date: s.6,
+                    status: s.7,
+                    created_at: s.8,
+                    updated_at: s.9,
+                },
+                task_count,
+                completed_count,
+                progress,
+            });
+        }
+
+        Ok(result)
+    })
+    .await
 }
 
 #[derive(Debug, Deserialize)]
@@ -629,94 +882,357 @@ pub struct SprintUpdateRequest {
 
 #[tauri::command]
 pub async fn sprint_update(
+    app: AppHandle,
     state: State<'_, AppState>,
     sprint_id: String,
     request: SprintUpdateRequest,
 ) -> Result<SprintResponse, AppError> {
-    let now = chrono::Utc::now().to_rfc3339();
-
-    let current = sqlx::query_as::<_, (String, String, Option<String>, String, Option<String>, Option<String>, Option<String>, String, String, String)>(
-        "SELECT id, project_id, milestone_id, name, description, start_date, end_date, status, created_at, updated_at FROM sprints WHERE id = ?",
-    )
-    .bind(&sprint_id)
-    .fetch_optional(&state.db)
-    .await?
-    .ok_or_else(|| AppError::database_not_found("Sprint", &sprint_id))?;
-
-    let milestone_id = request.milestone_id.or(current.2);
-    let name = request.name.unwrap_or(current.3);
-    let description = request.description.or(current.4);
-    let start_date = request.start_date.or(current.5);
-    let end_date = request.end_date.or(current.6);
-    let status = request.status.unwrap_or(current.7);
-
-    // Validate status
-    if !["planned", "active", "completed"].contains(&status.as_str()) {
-        return Err(AppError::invalid_input("Invalid sprint status"));
-    }
+    state.command_metrics.measure("sprint_update", async {
+        let now = chrono::Utc::now().to_rfc3339();
 
-    sqlx::query(
-        r#"
-        UPDATE sprints
-        SET milestone_id = ?, name = ?, description = ?, start_date = ?, end_date = ?, status = ?, updated_at = ?
-        WHERE id = ?
-        "#,
-    )
-    .bind(&milestone_id)
-    .bind(&name)
-    .bind(&description)
-    .bind(&start_date)
-    .bind(&end_date)
-    .bind(&status)
-    .bind(&now)
-    .bind(&sprint_id)
-    .execute(&state.db)
-    .await?;
+        let current = sqlx::query_as::<_, (String, String, Option<String>, String, Option<String>, Option<String>, Option<String>, String, String, String)>(
+            "SELECT id, project_id, milestone_id, name, description, start_date, end_date, status, created_at, updated_at FROM sprints WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(&sprint_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Sprint", &sprint_id))?;
+
+        let milestone_id = request.milestone_id.or(current.2);
+        let name = request.name.unwrap_or(current.3);
+        let description = request.description.or(current.4);
+        let start_date = request.start_date.or(current.5);
+        let end_date = request.end_date.or(current.6);
+        let was_completed = current.7 == "completed";
+        let status = request.status.unwrap_or(current.7);
+
+        // Validate status
+        if !["planned", "active", "completed"].contains(&status.as_str()) {
+            return Err(AppError::invalid_input("Invalid sprint status"));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE sprints
+            SET milestone_id = ?, name = ?, description = ?, start_date = ?, end_date = ?, status = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&milestone_id)
+        .bind(&name)
+        .bind(&description)
+        .bind(&start_date)
+        .bind(&end_date)
+        .bind(&status)
+        .bind(&now)
+        .bind(&sprint_id)
+        .execute(&state.db)
+        .await?;
 
-    Ok(SprintResponse {
-        id: sprint_id,
-        project_id: current.1,
-        milestone_id,
-        name,
-        description,
-        start_date,
-        end_date,
-        status,
-        created_at: current.8,
-        updated_at: now,
+        if status == "completed" && !was_completed {
+            webhooks::dispatch(
+                &app,
+                WebhookEvent::SprintCompleted,
+                WebhookContext {
+                    project_id: Some(current.1.clone()),
+                    sprint_id: Some(sprint_id.clone()),
+                    ..Default::default()
+                },
+            );
+        }
+
+        Ok(SprintResponse {
+            id: sprint_id,
+            project_id: current.1,
+            milestone_id,
+            name,
+            description,
+            start_date,
+            end_date,
+            status,
+            created_at: current.8,
+            updated_at: now,
+        })
     })
+    .await
 }
 
-#[tauri::command]
-pub async fn sprint_delete(
-    state: State<'_, AppState>,
+/// Sprint created to take a completed sprint's unfinished tasks
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NextSprintRequest {
+    pub milestone_id: Option<String>,
+    pub name: String,
+    pub description: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+/// Mark a sprint completed and report definition of done compliance
+///
+/// Unfinished tasks can be carried over to an existing sprint with
+/// `carry_over_to`, or to a new one created from `next_sprint`. Completing
+/// the sprint, creating the next one, and moving the tasks happen in one
+/// transaction.
+#[tauri::command]
+pub async fn sprint_complete(
+    app: AppHandle,
+    state: State<'_, AppState>,
     sprint_id: String,
-) -> Result<(), AppError> {
-    let result = sqlx::query("DELETE FROM sprints WHERE id = ?")
+    carry_over_to: Option<String>,
+    next_sprint: Option<NextSprintRequest>,
+) -> Result<SprintCompleteResponse, AppError> {
+    state.command_metrics.measure("sprint_complete", async {
+        let completed = complete_sprint(&state.db, &sprint_id, carry_over_to, next_sprint).await?;
+
+        webhooks::dispatch(
+            &app,
+            WebhookEvent::SprintCompleted,
+            WebhookContext {
+                project_id: Some(completed.project_id.clone()),
+                sprint_id: Some(sprint_id.clone()),
+                ..Default::default()
+            },
+        );
+
+        let next_sprint = match &completed.next_sprint_id {
+            Some(next_sprint_id) => Some(fetch_sprint(&state.db, next_sprint_id).await?),
+            None => None,
+        };
+
+        let sprint = fetch_sprint(&state.db, &sprint_id).await?;
+
+        let done_tasks = sqlx::query_as::<_, (String, String)>(
+            "SELECT id, title FROM tasks WHERE sprint_id = ? AND status = 'done' AND deleted_at IS NULL ORDER BY created_at ASC",
+        )
         .bind(&sprint_id)
-        .execute(&state.db)
+        .fetch_all(&state.db)
         .await?;
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::database_not_found("Sprint", &sprint_id));
+        let enforcement = dod_enforcement(&state.db, &sprint.project_id).await?;
+        let mut non_compliant = Vec::new();
+        if enforcement != "off" {
+            for (task_id, title) in &done_tasks {
+                let unchecked = unchecked_dod_items(&state.db, task_id).await?;
+                if !unchecked.is_empty() {
+                    non_compliant.push(TaskDodGapResponse {
+                        task_id: task_id.clone(),
+                        title: title.clone(),
+                        unchecked_items: unchecked.into_iter().map(|i| i.text).collect(),
+                    });
+                }
+            }
+        }
+
+        let done_count = done_tasks.len() as i32;
+        Ok(SprintCompleteResponse {
+            sprint,
+            dod_compliance: DodComplianceResponse {
+                done_tasks: done_count,
+                compliant_tasks: done_count - non_compliant.len() as i32,
+                non_compliant,
+            },
+            rollover: SprintRolloverResponse {
+                completed_tasks: done_count,
+                incomplete_tasks: completed.incomplete_tasks,
+                moved_tasks: completed.moved_tasks,
+                next_sprint,
+            },
+        })
+    })
+    .await
+}
+
+/// What completing a sprint changed
+struct CompletedSprint {
+    project_id: String,
+    /// Sprint the unfinished tasks were carried over to
+    next_sprint_id: Option<String>,
+    incomplete_tasks: i32,
+    moved_tasks: i32,
+}
+
+/// Mark a sprint completed and carry its unfinished tasks over, in one transaction
+async fn complete_sprint(
+    db: &sqlx::SqlitePool,
+    sprint_id: &str,
+    carry_over_to: Option<String>,
+    next_sprint: Option<NextSprintRequest>,
+) -> Result<CompletedSprint, AppError> {
+    if carry_over_to.is_some() && next_sprint.is_some() {
+        return Err(AppError::invalid_input("Carry tasks over to an existing sprint or a new one, not both"));
+    }
+    if next_sprint.as_ref().is_some_and(|n| n.name.trim().is_empty()) {
+        return Err(AppError::invalid_input("Sprint name cannot be empty"));
+    }
+    if carry_over_to.as_deref() == Some(sprint_id) {
+        return Err(AppError::invalid_input("Tasks can't be carried over to the sprint being completed"));
     }
 
-    Ok(())
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut tx = db.begin().await?;
+
+    let project_id: String = sqlx::query_scalar("SELECT project_id FROM sprints WHERE id = ? AND deleted_at IS NULL")
+        .bind(sprint_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Sprint", sprint_id))?;
+
+    sqlx::query("UPDATE sprints SET status = 'completed', updated_at = ? WHERE id = ?")
+        .bind(&now)
+        .bind(sprint_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let target = if let Some(next) = next_sprint {
+        let id = uuid::Uuid::new_v4().to_string();
+        sqlx::query(
+            r#"
+            INSERT INTO sprints (id, project_id, milestone_id, name, description, start_date, end_date, status, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, 'planned', ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&project_id)
+        .bind(&next.milestone_id)
+        .bind(&next.name)
+        .bind(&next.description)
+        .bind(&next.start_date)
+        .bind(&next.end_date)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await?;
+        Some(id)
+    } else if let Some(target_id) = carry_over_to {
+        let target = sqlx::query_as::<_, (String, String)>(
+            "SELECT project_id, status FROM sprints WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(&target_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Sprint", &target_id))?;
+        if target.0 != project_id {
+            return Err(AppError::invalid_input("Sprint belongs to a different project"));
+        }
+        if target.1 == "completed" {
+            return Err(AppError::invalid_input("Tasks can't be carried over to a completed sprint"));
+        }
+        Some(target_id)
+    } else {
+        None
+    };
+
+    let incomplete: Vec<String> = sqlx::query_scalar(
+        "SELECT id FROM tasks WHERE sprint_id = ? AND status != 'done' AND deleted_at IS NULL",
+    )
+    .bind(sprint_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut moved_tasks = 0;
+    if let Some(target_id) = &target {
+        for task_id in &incomplete {
+            sqlx::query("UPDATE tasks SET sprint_id = ?, updated_at = ? WHERE id = ?")
+                .bind(target_id)
+                .bind(&now)
+                .bind(task_id)
+                .execute(&mut *tx)
+                .await?;
+            record_task_change(
+                &mut *tx,
+                task_id,
+                &project_id,
+                "sprint_id",
+                Some(sprint_id),
+                Some(target_id.as_str()),
+                "user",
+                &now,
+            )
+            .await?;
+            moved_tasks += 1;
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(CompletedSprint {
+        project_id,
+        next_sprint_id: target,
+        incomplete_tasks: incomplete.len() as i32,
+        moved_tasks,
+    })
+}
+
+/// Load a sprint by ID
+async fn fetch_sprint(db: &sqlx::SqlitePool, sprint_id: &str) -> Result<SprintResponse, AppError> {
+    let s = sqlx::query_as::<_, (String, String, Option<String>, String, Option<String>, Option<String>, Option<String>, String, String, String)>(
+        "SELECT id, project_id, milestone_id, name, description, start_date, end_date, status, created_at, updated_at FROM sprints WHERE id = ?",
+    )
+    .bind(sprint_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| AppError::database_not_found("Sprint", sprint_id))?;
+
+    Ok(SprintResponse {
+        id: s.0,
+        project_id: s.1,
+        milestone_id: s.2,
+        name: s.3,
+        description: s.4,
+        start_date: s.5,
+        end_date: s.6,
+        status: s.7,
+        created_at: s.8,
+        updated_at: s.9,
+    })
+}
+
+/// Delete a sprint, moving its tasks to the backlog
+///
+/// The sprint goes to the trash unless `permanent` is set.
+#[tauri::command]
+pub async fn sprint_delete(
+    state: State<'_, AppState>,
+    sprint_id: String,
+    permanent: Option<bool>,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("sprint_delete", async {
+        if !permanent.unwrap_or(false) {
+            return trash_sprint(&state.db, &sprint_id).await;
+        }
+
+        let result = sqlx::query("DELETE FROM sprints WHERE id = ?")
+            .bind(&sprint_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Sprint", &sprint_id));
+        }
+
+        Ok(())
+    })
+    .await
 }
 
 // ============================================================================
 // Task Commands
 // ============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TaskCreateRequest {
     pub project_id: String,
     pub sprint_id: Option<String>,
+    pub parent_task_id: Option<String>,
+    /// Kanban column to create the task in (defaults to the first to-do column)
+    pub status_id: Option<String>,
     pub title: String,
     pub description: Option<String>,
     pub priority: Option<String>,
     pub estimated_hours: Option<f64>,
+    pub due_date: Option<String>,
 }
 
 #[tauri::command]
@@ -724,99 +1240,147 @@ pub async fn task_create(
     state: State<'_, AppState>,
     request: TaskCreateRequest,
 ) -> Result<TaskResponse, AppError> {
-    if request.title.trim().is_empty() {
-        return Err(AppError::invalid_input("Task title cannot be empty"));
-    }
-
-    let priority = request.priority.unwrap_or_else(|| "medium".to_string());
-    if !["low", "medium", "high"].contains(&priority.as_str()) {
-        return Err(AppError::invalid_input("Invalid task priority"));
-    }
+    state.command_metrics.measure("task_create", async {
+        if request.title.trim().is_empty() {
+            return Err(AppError::invalid_input("Task title cannot be empty"));
+        }
+
+        let priority = request.priority.unwrap_or_else(|| "medium".to_string());
+        if !["low", "medium", "high"].contains(&priority.as_str()) {
+            return Err(AppError::invalid_input("Invalid task priority"));
+        }
+
+        if let Some(due_date) = &request.due_date {
+            validate_due_date(due_date)?;
+        }
+
+        if let Some(parent_id) = &request.parent_task_id {
+            ensure_parent_in_project(&state.db, parent_id, &request.project_id).await?;
+        }
+
+        // Tasks can't skip the definition of done by being created as done
+        let (status_id, status) = match request.status_id {
+            Some(status_id) => {
+                let category = status_category(&state.db, &request.project_id, &status_id).await?;
+                if category == "done" {
+                    return Err(AppError::invalid_input("Tasks cannot be created in a done column"));
+                }
+                (Some(status_id), category)
+            }
+            None => (
+                default_status_for(&state.db, &request.project_id, "todo").await?,
+                "todo".to_string(),
+            ),
+        };
 
-    let id = uuid::Uuid::new_v4().to_string();
-    let now = chrono::Utc::now().to_rfc3339();
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
 
-    sqlx::query(
-        r#"
-        INSERT INTO tasks (id, project_id, sprint_id, title, description, status, priority, estimated_hours, created_at, updated_at)
-        VALUES (?, ?, ?, ?, ?, 'todo', ?, ?, ?, ?)
-        "#,
-    )
-    .bind(&id)
-    .bind(&request.project_id)
-    .bind(&request.sprint_id)
-    .bind(&request.title)
-    .bind(&request.description)
-    .bind(&priority)
-    .bind(&request.estimated_hours)
-    .bind(&now)
-    .bind(&now)
-    .execute(&state.db)
-    .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO tasks (id, project_id, sprint_id, parent_task_id, title, description, status, status_id, priority, estimated_hours, due_date, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&request.project_id)
+        .bind(&request.sprint_id)
+        .bind(&request.parent_task_id)
+        .bind(&request.title)
+        .bind(&request.description)
+        .bind(&status)
+        .bind(&status_id)
+        .bind(&priority)
+        .bind(&request.estimated_hours)
+        .bind(&request.due_date)
+        .bind(&now)
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
 
-    Ok(TaskResponse {
-        id,
-        project_id: request.project_id,
-        sprint_id: request.sprint_id,
-        title: request.title,
-        description: request.description,
-        status: "todo".to_string(),
-        priority,
-        estimated_hours: request.estimated_hours,
-        created_at: now.clone(),
-        updated_at: now,
+        record_task_change(&state.db, &id, &request.project_id, "status", None, Some(&status), "user", &now).await?;
+
+        Ok(TaskResponse {
+            id,
+            project_id: request.project_id,
+            sprint_id: request.sprint_id,
+            parent_task_id: request.parent_task_id,
+            title: request.title,
+            description: request.description,
+            status,
+            status_id,
+            priority,
+            estimated_hours: request.estimated_hours,
+            due_date: request.due_date,
+            actual_hours: 0.0,
+            timer_started_at: None,
+            blocked_by: Vec::new(),
+            created_at: now.clone(),
+            updated_at: now,
+        })
     })
+    .await
 }
 
-/// Get all tasks for a project
+/// Get all tasks for a project, optionally filtered by sprint and label
 #[tauri::command]
 pub async fn task_get_all(
     state: State<'_, AppState>,
     project_id: String,
     sprint_id: Option<String>,
+    label_id: Option<String>,
 ) -> Result<Vec<TaskResponse>, AppError> {
-    let tasks = if let Some(sid) = sprint_id {
-        sqlx::query_as::<_, (String, String, Option<String>, String, Option<String>, String, String, Option<f64>, String, String)>(
+    state.command_metrics.measure("task_get_all", async {
+        let mut sql = String::from(
             r#"
-            SELECT id, project_id, sprint_id, title, description, status, priority, estimated_hours, created_at, updated_at
+            SELECT id, project_id, sprint_id, parent_task_id, title, description, status, status_id, priority, estimated_hours, due_date, created_at, updated_at
             FROM tasks
-            WHERE project_id = ? AND sprint_id = ?
-            ORDER BY created_at ASC
+            WHERE project_id = ? AND deleted_at IS NULL
             "#,
-        )
-        .bind(&project_id)
-        .bind(&sid)
-        .fetch_all(&state.db)
-        .await?
-    } else {
-        sqlx::query_as::<_, (String, String, Option<String>, String, Option<String>, String, String, Option<f64>, String, String)>(
-            r#"
-            SELECT id, project_id, sprint_id, title, description, status, priority, estimated_hours, created_at, updated_at
-            FROM tasks
-            WHERE project_id = ?
-            ORDER BY created_at ASC
-            "#,
-        )
-        .bind(&project_id)
-        .fetch_all(&state.db)
-        .await?
-    };
-
-    Ok(tasks
-        .into_iter()
-        .map(|t| TaskResponse {
-            id: t.0,
-            project_id: t.1,
-            sprint_id: t.2,
-            title: t.3,
-            description: t.4,
-            status: t.5,
-            priority: t.6,
-            estimated_hours: t.7,
-            created_at: t.8,
-            updated_at: t.9,
-        })
-        .collect())
+        );
+        if sprint_id.is_some() {
+            sql.push_str(" AND sprint_id = ?");
+        }
+        if label_id.is_some() {
+            sql.push_str(" AND id IN (SELECT task_id FROM task_labels WHERE label_id = ?)");
+        }
+        sql.push_str(" ORDER BY created_at ASC");
+
+        let mut query = sqlx::query_as::<_, (String, String, Option<String>, Option<String>, String, Option<String>, String, Option<String>, String, Option<f64>, Option<String>, String, String)>(&sql)
+            .bind(&project_id);
+        if let Some(sid) = &sprint_id {
+            query = query.bind(sid);
+        }
+        if let Some(lid) = &label_id {
+            query = query.bind(lid);
+        }
+        let tasks = query.fetch_all(&state.db).await?;
+        let mut blocked = blocked_by_map(&state.db, &project_id).await?;
+        let mut times = task_time_map(&state.db, &project_id).await?;
+
+        Ok(tasks
+            .into_iter()
+            .map(|t| TaskResponse {
+                blocked_by: blocked.remove(&t.0).unwrap_or_default(),
+                actual_hours: times.get(&t.0).map_or(0.0, |time| time.actual_hours),
+                timer_started_at: times.remove(&t.0).and_then(|time| time.timer_started_at),
+                id: t.0,
+                project_id: t.1,
+                sprint_id: t.2,
+                parent_task_id: t.3,
+                title: t.4,
+                description: t.5,
+                status: t.6,
+                status_id: t.7,
+                priority: t.8,
+                estimated_hours: t.9,
+                due_date: t.10,
+                created_at: t.11,
+                updated_at: t.12,
+            })
+            .collect())
+    })
+    .await
 }
 
 #[derive(Debug, Deserialize)]
@@ -825,72 +1389,238 @@ pub struct TaskUpdateRequest {
     pub sprint_id: Option<String>,
     pub title: Option<String>,
     pub description: Option<String>,
+    /// Status category; moves the task to the first column of that category
     pub status: Option<String>,
+    /// Kanban column; takes precedence over `status`
+    pub status_id: Option<String>,
     pub priority: Option<String>,
     pub estimated_hours: Option<f64>,
+    /// An empty string clears the due date
+    pub due_date: Option<String>,
 }
 
 #[tauri::command]
 pub async fn task_update(
+    app: AppHandle,
     state: State<'_, AppState>,
     task_id: String,
     request: TaskUpdateRequest,
-) -> Result<TaskResponse, AppError> {
-    let now = chrono::Utc::now().to_rfc3339();
+) -> Result<TaskUpdateResponse, AppError> {
+    state.command_metrics.measure("task_update", async {
+        let now = chrono::Utc::now().to_rfc3339();
 
-    let current = sqlx::query_as::<_, (String, String, Option<String>, String, Option<String>, String, String, Option<f64>, String, String)>(
-        "SELECT id, project_id, sprint_id, title, description, status, priority, estimated_hours, created_at, updated_at FROM tasks WHERE id = ?",
-    )
-    .bind(&task_id)
-    .fetch_optional(&state.db)
-    .await?
-    .ok_or_else(|| AppError::database_not_found("Task", &task_id))?;
-
-    let sprint_id = request.sprint_id.or(current.2);
-    let title = request.title.unwrap_or(current.3);
-    let description = request.description.or(current.4);
-    let status = request.status.unwrap_or(current.5);
-    let priority = request.priority.unwrap_or(current.6);
-    let estimated_hours = request.estimated_hours.or(current.7);
-
-    // Validate
-    if !["todo", "in_progress", "done"].contains(&status.as_str()) {
-        return Err(AppError::invalid_input("Invalid task status"));
-    }
-    if !["low", "medium", "high"].contains(&priority.as_str()) {
-        return Err(AppError::invalid_input("Invalid task priority"));
-    }
+        let current = sqlx::query_as::<_, (String, String, Option<String>, Option<String>, String, Option<String>, String, Option<String>, String, Option<f64>, Option<String>, String, String)>(
+            "SELECT id, project_id, sprint_id, parent_task_id, title, description, status, status_id, priority, estimated_hours, due_date, created_at, updated_at FROM tasks WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(&task_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Task", &task_id))?;
+
+        // Snapshot the fields tracked in task history
+        let before = [
+            ("sprint_id", current.2.clone()),
+            ("title", Some(current.4.clone())),
+            ("description", current.5.clone()),
+            ("status", Some(current.6.clone())),
+            ("status_id", current.7.clone()),
+            ("priority", Some(current.8.clone())),
+            ("estimated_hours", current.9.map(|h| h.to_string())),
+            ("due_date", current.10.clone()),
+        ];
+
+        let was_done = current.6 == "done";
+        let sprint_id = request.sprint_id.or(current.2);
+        let title = request.title.unwrap_or(current.4);
+        let description = request.description.or(current.5);
+        let priority = request.priority.unwrap_or(current.8);
+        let estimated_hours = request.estimated_hours.or(current.9);
+        let due_date = match request.due_date {
+            Some(due_date) if due_date.is_empty() => None,
+            Some(due_date) => {
+                validate_due_date(&due_date)?;
+                Some(due_date)
+            }
+            None => current.10,
+        };
+
+        // Keep the column and its category in sync
+        let (status, status_id) = match (request.status_id, request.status) {
+            (Some(status_id), _) => (status_category(&state.db, &current.1, &status_id).await?, Some(status_id)),
+            (None, Some(status)) if status != current.6 => {
+                if !["todo", "in_progress", "done"].contains(&status.as_str()) {
+                    return Err(AppError::invalid_input("Invalid task status"));
+                }
+                let status_id = default_status_for(&state.db, &current.1, &status).await?;
+                (status, status_id)
+            }
+            _ => (current.6, current.7),
+        };
+
+        // Validate
+        if !["low", "medium", "high"].contains(&priority.as_str()) {
+            return Err(AppError::invalid_input("Invalid task priority"));
+        }
+
+        // Check the definition of done when the task is being completed
+        let mut unchecked = Vec::new();
+        if status == "done" && !was_done {
+            let enforcement = dod_enforcement(&state.db, &current.1).await?;
+            if enforcement != "off" {
+                unchecked = unchecked_dod_items(&state.db, &task_id).await?;
+                if enforcement == "block" && !unchecked.is_empty() {
+                    return Err(AppError::with_details(
+                        ErrorCode::InvalidInput,
+                        "Task does not meet the definition of done",
+                        unchecked.iter().map(|i| i.text.as_str()).collect::<Vec<_>>().join("\n"),
+                    ));
+                }
+            }
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE tasks
+            SET sprint_id = ?, title = ?, description = ?, status = ?, status_id = ?, priority = ?, estimated_hours = ?, due_date = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&sprint_id)
+        .bind(&title)
+        .bind(&description)
+        .bind(&status)
+        .bind(&status_id)
+        .bind(&priority)
+        .bind(&estimated_hours)
+        .bind(&due_date)
+        .bind(&now)
+        .bind(&task_id)
+        .execute(&state.db)
+        .await?;
 
+        let after = [
+            ("sprint_id", sprint_id.clone()),
+            ("title", Some(title.clone())),
+            ("description", description.clone()),
+            ("status", Some(status.clone())),
+            ("status_id", status_id.clone()),
+            ("priority", Some(priority.clone())),
+            ("estimated_hours", estimated_hours.map(|h| h.to_string())),
+            ("due_date", due_date.clone()),
+        ];
+        for ((field, old_value), (_, new_value)) in before.iter().zip(after.iter()) {
+            if old_value != new_value {
+                record_task_change(
+                    &state.db,
+                    &task_id,
+                    &current.1,
+                    field,
+                    old_value.as_deref(),
+                    new_value.as_deref(),
+                    "user",
+                    &now,
+                )
+                .await?;
+            }
+        }
+
+        if status == "done" && !was_done {
+            hooks::fire(
+                &app,
+                HookEvent::TaskCompleted,
+                HookContext {
+                    project_id: Some(current.1.clone()),
+                    task_id: Some(task_id.clone()),
+                    ..Default::default()
+                },
+            );
+            github::close_issue_for_task(&app, &task_id);
+            webhooks::dispatch(
+                &app,
+                WebhookEvent::TaskCompleted,
+                WebhookContext {
+                    project_id: Some(current.1.clone()),
+                    task_id: Some(task_id.clone()),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let blocked_by = blocked_by_map(&state.db, &current.1)
+            .await?
+            .remove(&task_id)
+            .unwrap_or_default();
+        let time = task_time_map(&state.db, &current.1)
+            .await?
+            .remove(&task_id)
+            .unwrap_or_default();
+
+        Ok(TaskUpdateResponse {
+            task: TaskResponse {
+                id: task_id,
+                project_id: current.1,
+                sprint_id,
+                parent_task_id: current.3,
+                title,
+                description,
+                status,
+                status_id,
+                priority,
+                estimated_hours,
+                due_date,
+                actual_hours: time.actual_hours,
+                timer_started_at: time.timer_started_at,
+                blocked_by,
+                created_at: current.11,
+                updated_at: now,
+            },
+            unchecked_dod_items: unchecked,
+        })
+    })
+    .await
+}
+
+/// Get a project's root directory
+pub(crate) async fn project_root(db: &sqlx::SqlitePool, project_id: &str) -> Result<PathBuf, AppError> {
+    let root_path: String = sqlx::query_scalar("SELECT root_path FROM projects WHERE id = ?")
+        .bind(project_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Project", project_id))?;
+
+    Ok(PathBuf::from(root_path))
+}
+
+/// Append an entry to a task's change history
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn record_task_change<'e>(
+    db: impl sqlx::Executor<'e, Database = sqlx::Sqlite>,
+    task_id: &str,
+    project_id: &str,
+    field: &str,
+    old_value: Option<&str>,
+    new_value: Option<&str>,
+    changed_by: &str,
+    changed_at: &str,
+) -> Result<(), AppError> {
     sqlx::query(
         r#"
-        UPDATE tasks
-        SET sprint_id = ?, title = ?, description = ?, status = ?, priority = ?, estimated_hours = ?, updated_at = ?
-        WHERE id = ?
+        INSERT INTO task_history (id, task_id, project_id, field, old_value, new_value, changed_by, changed_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
-    .bind(&sprint_id)
-    .bind(&title)
-    .bind(&description)
-    .bind(&status)
-    .bind(&priority)
-    .bind(&estimated_hours)
-    .bind(&now)
-    .bind(&task_id)
-    .execute(&state.db)
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(task_id)
+    .bind(project_id)
+    .bind(field)
+    .bind(old_value)
+    .bind(new_value)
+    .bind(changed_by)
+    .bind(changed_at)
+    .execute(db)
     .await?;
 
-    Ok(TaskResponse {
-        id: task_id,
-        project_id: current.1,
-        sprint_id,
-        title,
-        description,
-        status,
-        priority,
-        estimated_hours,
-        created_at: current.8,
-        updated_at: now,
-    })
+    Ok(())
 }
 
 /// Move a task to a different sprint
@@ -900,41 +1630,639 @@ pub async fn task_move(
     task_id: String,
     sprint_id: Option<String>,
 ) -> Result<(), AppError> {
+    state.command_metrics.measure("task_move", async {
+        let now = chrono::Utc::now().to_rfc3339();
+
+        let (project_id, previous_sprint_id): (String, Option<String>) = sqlx::query_as(
+            "SELECT project_id, sprint_id FROM tasks WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(&task_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Task", &task_id))?;
+
+        sqlx::query(
+            "UPDATE tasks SET sprint_id = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(&sprint_id)
+        .bind(&now)
+        .bind(&task_id)
+        .execute(&state.db)
+        .await?;
+
+        if previous_sprint_id != sprint_id {
+            record_task_change(
+                &state.db,
+                &task_id,
+                &project_id,
+                "sprint_id",
+                previous_sprint_id.as_deref(),
+                sprint_id.as_deref(),
+                "user",
+                &now,
+            )
+            .await?;
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Changes applied to every task in a bulk update; unset fields are left alone
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskBulkUpdateRequest {
+    pub sprint_id: Option<String>,
+    /// Status category; moves each task to the first column of that category
+    pub status: Option<String>,
+    /// Kanban column; takes precedence over `status`
+    pub status_id: Option<String>,
+    pub priority: Option<String>,
+}
+
+/// Outcome for one task in a bulk operation
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskBulkResult {
+    pub task_id: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<AppError>,
+}
+
+impl TaskBulkResult {
+    fn from_result(task_id: String, result: Result<(), AppError>) -> Self {
+        Self {
+            task_id,
+            success: result.is_ok(),
+            error: result.err(),
+        }
+    }
+}
+
+/// Update the sprint, status, or priority of several tasks in one transaction
+///
+/// Tasks that can't be updated, such as missing ones or ones blocked by the
+/// definition of done, are reported in their result and the rest are still
+/// updated.
+#[tauri::command]
+pub async fn task_bulk_update(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    task_ids: Vec<String>,
+    request: TaskBulkUpdateRequest,
+) -> Result<Vec<TaskBulkResult>, AppError> {
+    state.command_metrics.measure("task_bulk_update", async {
+        if let Some(priority) = &request.priority {
+            if !["low", "medium", "high"].contains(&priority.as_str()) {
+                return Err(AppError::invalid_input("Invalid task priority"));
+            }
+        }
+        if let Some(status) = &request.status {
+            if !["todo", "in_progress", "done"].contains(&status.as_str()) {
+                return Err(AppError::invalid_input("Invalid task status"));
+            }
+        }
+
+        let (results, completed) = bulk_update(&state.db, task_ids, &request).await?;
+
+        for (project_id, task_id) in completed {
+            github::close_issue_for_task(&app, &task_id);
+            webhooks::dispatch(
+                &app,
+                WebhookEvent::TaskCompleted,
+                WebhookContext {
+                    project_id: Some(project_id.clone()),
+                    task_id: Some(task_id.clone()),
+                    ..Default::default()
+                },
+            );
+            hooks::fire(
+                &app,
+                HookEvent::TaskCompleted,
+                HookContext {
+                    project_id: Some(project_id),
+                    task_id: Some(task_id),
+                    ..Default::default()
+                },
+            );
+        }
+
+        Ok(results)
+    })
+    .await
+}
+
+/// Apply a bulk update in one transaction
+///
+/// Returns each task's result, and `(project, task)` for the tasks the
+/// update completed.
+async fn bulk_update(
+    db: &sqlx::SqlitePool,
+    task_ids: Vec<String>,
+    request: &TaskBulkUpdateRequest,
+) -> Result<(Vec<TaskBulkResult>, Vec<(String, String)>), AppError> {
     let now = chrono::Utc::now().to_rfc3339();
+    let mut results = Vec::with_capacity(task_ids.len());
+    let mut completed = Vec::new();
+
+    let mut tx = db.begin().await?;
+    for task_id in unique_ids(task_ids) {
+        let result = bulk_update_task(&mut tx, &task_id, request, &now).await;
+        let result = match result {
+            Ok(Some(project_id)) => {
+                completed.push((project_id, task_id.clone()));
+                Ok(())
+            }
+            Ok(None) => Ok(()),
+            Err(e) => Err(e),
+        };
+        results.push(TaskBulkResult::from_result(task_id, result));
+    }
+    tx.commit().await?;
 
-    let result = sqlx::query(
-        "UPDATE tasks SET sprint_id = ?, updated_at = ? WHERE id = ?",
-    )
-    .bind(&sprint_id)
-    .bind(&now)
-    .bind(&task_id)
-    .execute(&state.db)
-    .await?;
+    Ok((results, completed))
+}
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::database_not_found("Task", &task_id));
+/// Apply a bulk update to one task inside the bulk transaction
+///
+/// Returns the task's project when the update marked it done.
+async fn bulk_update_task(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    task_id: &str,
+    request: &TaskBulkUpdateRequest,
+    now: &str,
+) -> Result<Option<String>, AppError> {
+    let (project_id, sprint_id, status, status_id, priority): (String, Option<String>, String, Option<String>, String) =
+        sqlx::query_as(
+            "SELECT project_id, sprint_id, status, status_id, priority FROM tasks WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(task_id)
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Task", task_id))?;
+
+    // Keep the column and its category in sync
+    let (new_status, new_status_id) = match (&request.status_id, &request.status) {
+        (Some(new_status_id), _) => (
+            status_category(&mut **tx, &project_id, new_status_id).await?,
+            Some(new_status_id.clone()),
+        ),
+        (None, Some(new_status)) if *new_status != status => (
+            new_status.clone(),
+            default_status_for(&mut **tx, &project_id, new_status).await?,
+        ),
+        _ => (status.clone(), status_id.clone()),
+    };
+    let new_sprint_id = request.sprint_id.clone().or(sprint_id.clone());
+    let new_priority = request.priority.clone().unwrap_or(priority.clone());
+
+    let completing = new_status == "done" && status != "done";
+    if completing && dod_enforcement(&mut **tx, &project_id).await? == "block" {
+        let unchecked = unchecked_dod_items(&mut **tx, task_id).await?;
+        if !unchecked.is_empty() {
+            return Err(AppError::with_details(
+                ErrorCode::InvalidInput,
+                "Task does not meet the definition of done",
+                unchecked.iter().map(|i| i.text.as_str()).collect::<Vec<_>>().join("\n"),
+            ));
+        }
     }
 
-    Ok(())
+    sqlx::query("UPDATE tasks SET sprint_id = ?, status = ?, status_id = ?, priority = ?, updated_at = ? WHERE id = ?")
+        .bind(&new_sprint_id)
+        .bind(&new_status)
+        .bind(&new_status_id)
+        .bind(&new_priority)
+        .bind(now)
+        .bind(task_id)
+        .execute(&mut **tx)
+        .await?;
+
+    let changes = [
+        ("sprint_id", sprint_id, new_sprint_id),
+        ("status", Some(status), Some(new_status)),
+        ("status_id", status_id, new_status_id),
+        ("priority", Some(priority), Some(new_priority)),
+    ];
+    for (field, old_value, new_value) in changes {
+        if old_value != new_value {
+            record_task_change(
+                &mut **tx,
+                task_id,
+                &project_id,
+                field,
+                old_value.as_deref(),
+                new_value.as_deref(),
+                "user",
+                now,
+            )
+            .await?;
+        }
+    }
+
+    Ok(completing.then_some(project_id))
 }
 
+/// Move several tasks to a sprint, or to the backlog, in one transaction
+///
+/// Missing tasks are reported in their result and the rest are still moved.
 #[tauri::command]
-pub async fn task_delete(
+pub async fn task_bulk_move(
+    state: State<'_, AppState>,
+    task_ids: Vec<String>,
+    sprint_id: Option<String>,
+) -> Result<Vec<TaskBulkResult>, AppError> {
+    state.command_metrics.measure("task_bulk_move", async {
+        bulk_move(&state.db, task_ids, sprint_id.as_deref()).await
+    })
+    .await
+}
+
+/// Move tasks to a sprint, or to the backlog, in one transaction
+async fn bulk_move(
+    db: &sqlx::SqlitePool,
+    task_ids: Vec<String>,
+    sprint_id: Option<&str>,
+) -> Result<Vec<TaskBulkResult>, AppError> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut results = Vec::with_capacity(task_ids.len());
+
+    let mut tx = db.begin().await?;
+    for task_id in unique_ids(task_ids) {
+        let previous: Option<(String, Option<String>)> = sqlx::query_as(
+            "SELECT project_id, sprint_id FROM tasks WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(&task_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some((project_id, previous_sprint_id)) = previous else {
+            let error = AppError::database_not_found("Task", &task_id);
+            results.push(TaskBulkResult::from_result(task_id, Err(error)));
+            continue;
+        };
+
+        if previous_sprint_id.as_deref() != sprint_id {
+            sqlx::query("UPDATE tasks SET sprint_id = ?, updated_at = ? WHERE id = ?")
+                .bind(sprint_id)
+                .bind(&now)
+                .bind(&task_id)
+                .execute(&mut *tx)
+                .await?;
+            record_task_change(
+                &mut *tx,
+                &task_id,
+                &project_id,
+                "sprint_id",
+                previous_sprint_id.as_deref(),
+                sprint_id,
+                "user",
+                &now,
+            )
+            .await?;
+        }
+        results.push(TaskBulkResult::from_result(task_id, Ok(())));
+    }
+    tx.commit().await?;
+
+    Ok(results)
+}
+
+/// IDs in their first-seen order, without repeats
+fn unique_ids(ids: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    ids.into_iter().filter(|id| seen.insert(id.clone())).collect()
+}
+
+/// Move a task under another task, or back to the top level
+#[tauri::command]
+pub async fn task_set_parent(
     state: State<'_, AppState>,
     task_id: String,
+    parent_task_id: Option<String>,
 ) -> Result<(), AppError> {
-    let result = sqlx::query("DELETE FROM tasks WHERE id = ?")
+    state.command_metrics.measure("task_set_parent", async {
+        let now = chrono::Utc::now().to_rfc3339();
+
+        let (project_id, previous_parent_id): (String, Option<String>) = sqlx::query_as(
+            "SELECT project_id, parent_task_id FROM tasks WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(&task_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Task", &task_id))?;
+
+        if let Some(parent_id) = &parent_task_id {
+            ensure_parent_in_project(&state.db, parent_id, &project_id).await?;
+
+            // The new parent must not be the task itself or one of its subtasks
+            let would_cycle: bool = sqlx::query_scalar(
+                r#"
+                WITH RECURSIVE subtree(id) AS (
+                    SELECT ?
+                    UNION ALL
+                    SELECT t.id FROM tasks t JOIN subtree s ON t.parent_task_id = s.id
+                )
+                SELECT EXISTS(SELECT 1 FROM subtree WHERE id = ?)
+                "#,
+            )
+            .bind(&task_id)
+            .bind(parent_id)
+            .fetch_one(&state.db)
+            .await?;
+
+            if would_cycle {
+                return Err(AppError::invalid_input("A task cannot be nested under itself or its subtasks"));
+            }
+        }
+
+        sqlx::query(
+            "UPDATE tasks SET parent_task_id = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(&parent_task_id)
+        .bind(&now)
         .bind(&task_id)
         .execute(&state.db)
         .await?;
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::database_not_found("Task", &task_id));
+        if previous_parent_id != parent_task_id {
+            record_task_change(
+                &state.db,
+                &task_id,
+                &project_id,
+                "parent_task_id",
+                previous_parent_id.as_deref(),
+                parent_task_id.as_deref(),
+                "user",
+                &now,
+            )
+            .await?;
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Task with its subtasks and rolled-up completion
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskTreeNode {
+    #[serde(flatten)]
+    pub task: TaskResponse,
+    /// Percentage of the task's leaf subtasks that are done (0-100)
+    pub completion_percent: f64,
+    pub children: Vec<TaskTreeNode>,
+}
+
+/// Get a task hierarchy
+///
+/// Returns the subtree rooted at `task_id`, or every top-level task in the
+/// project when no task is given.
+#[tauri::command]
+pub async fn task_get_tree(
+    state: State<'_, AppState>,
+    project_id: String,
+    task_id: Option<String>,
+) -> Result<Vec<TaskTreeNode>, AppError> {
+    state.command_metrics.measure("task_get_tree", async {
+        let tasks = if let Some(tid) = &task_id {
+            sqlx::query_as::<_, (String, String, Option<String>, Option<String>, String, Option<String>, String, Option<String>, String, Option<f64>, Option<String>, String, String)>(
+                r#"
+                WITH RECURSIVE subtree(id) AS (
+                    SELECT id FROM tasks WHERE id = ? AND project_id = ? AND deleted_at IS NULL
+                    UNION ALL
+                    SELECT t.id FROM tasks t JOIN subtree s ON t.parent_task_id = s.id
+                    WHERE t.deleted_at IS NULL
+                )
+                SELECT id, project_id, sprint_id, parent_task_id, title, description, status, status_id, priority, estimated_hours, due_date, created_at, updated_at
+                FROM tasks
+                WHERE id IN (SELECT id FROM subtree)
+                ORDER BY created_at ASC
+                "#,
+            )
+            .bind(tid)
+            .bind(&project_id)
+            .fetch_all(&state.db)
+            .await?
+        } else {
+            sqlx::query_as::<_, (String, String, Option<String>, Option<String>, String, Option<String>, String, Option<String>, String, Option<f64>, Option<String>, String, String)>(
+                r#"
+                SELECT id, project_id, sprint_id, parent_task_id, title, description, status, status_id, priority, estimated_hours, due_date, created_at, updated_at
+                FROM tasks
+                WHERE project_id = ? AND deleted_at IS NULL
+                ORDER BY created_at ASC
+                "#,
+            )
+            .bind(&project_id)
+            .fetch_all(&state.db)
+            .await?
+        };
+
+        if tasks.is_empty() {
+            if let Some(tid) = task_id {
+                return Err(AppError::database_not_found("Task", &tid));
+            }
+        }
+
+        let mut blocked = blocked_by_map(&state.db, &project_id).await?;
+        let mut times = task_time_map(&state.db, &project_id).await?;
+
+        Ok(build_task_tree(
+            tasks
+                .into_iter()
+                .map(|t| TaskResponse {
+                    blocked_by: blocked.remove(&t.0).unwrap_or_default(),
+                    actual_hours: times.get(&t.0).map_or(0.0, |time| time.actual_hours),
+                    timer_started_at: times.remove(&t.0).and_then(|time| time.timer_started_at),
+                    id: t.0,
+                    project_id: t.1,
+                    sprint_id: t.2,
+                    parent_task_id: t.3,
+                    title: t.4,
+                    description: t.5,
+                    status: t.6,
+                    status_id: t.7,
+                    priority: t.8,
+                    estimated_hours: t.9,
+                    due_date: t.10,
+                    created_at: t.11,
+                    updated_at: t.12,
+                })
+                .collect(),
+        ))
+    })
+    .await
+}
+
+/// Assemble tasks into trees, treating tasks whose parent is not in the
+/// list as roots
+fn build_task_tree(tasks: Vec<TaskResponse>) -> Vec<TaskTreeNode> {
+    let ids: HashSet<String> = tasks.iter().map(|t| t.id.clone()).collect();
+
+    let mut by_parent: HashMap<Option<String>, Vec<TaskResponse>> = HashMap::new();
+    for task in tasks {
+        let parent = task.parent_task_id.clone().filter(|p| ids.contains(p));
+        by_parent.entry(parent).or_default().push(task);
+    }
+
+    fn build(
+        parent: Option<String>,
+        by_parent: &mut HashMap<Option<String>, Vec<TaskResponse>>,
+    ) -> Vec<TaskTreeNode> {
+        by_parent
+            .remove(&parent)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|task| {
+                let children = build(Some(task.id.clone()), by_parent);
+                let completion_percent = if children.is_empty() {
+                    if task.status == "done" { 100.0 } else { 0.0 }
+                } else {
+                    children.iter().map(|c| c.completion_percent).sum::<f64>() / children.len() as f64
+                };
+                TaskTreeNode {
+                    task,
+                    completion_percent,
+                    children,
+                }
+            })
+            .collect()
+    }
+
+    build(None, &mut by_parent)
+}
+
+/// Check that a prospective parent task exists in the given project
+async fn ensure_parent_in_project(
+    db: &sqlx::SqlitePool,
+    parent_task_id: &str,
+    project_id: &str,
+) -> Result<(), AppError> {
+    let parent_project: String = sqlx::query_scalar("SELECT project_id FROM tasks WHERE id = ? AND deleted_at IS NULL")
+        .bind(parent_task_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Task", parent_task_id))?;
+
+    if parent_project != project_id {
+        return Err(AppError::invalid_input("Parent task belongs to a different project"));
     }
 
     Ok(())
 }
 
+/// Check a due date is a timestamp or a `YYYY-MM-DD` date
+fn validate_due_date(due_date: &str) -> Result<(), AppError> {
+    if parse_due_date(due_date).is_none() {
+        return Err(AppError::invalid_input("Due date must be an RFC 3339 timestamp or a YYYY-MM-DD date"));
+    }
+    Ok(())
+}
+
+/// Task history entry
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskHistoryEntry {
+    pub id: String,
+    pub task_id: String,
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_by: String,
+    pub changed_at: String,
+}
+
+/// Get a task's change history, oldest first
+#[tauri::command]
+pub async fn task_get_history(
+    state: State<'_, AppState>,
+    task_id: String,
+    field: Option<String>,
+) -> Result<Vec<TaskHistoryEntry>, AppError> {
+    state.command_metrics.measure("task_get_history", async {
+        let entries = sqlx::query_as::<_, (String, String, String, Option<String>, Option<String>, String, String)>(
+            r#"
+            SELECT id, task_id, field, old_value, new_value, changed_by, changed_at
+            FROM task_history
+            WHERE task_id = ? AND (? IS NULL OR field = ?)
+            ORDER BY changed_at ASC
+            "#,
+        )
+        .bind(&task_id)
+        .bind(&field)
+        .bind(&field)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(entries
+            .into_iter()
+            .map(|e| TaskHistoryEntry {
+                id: e.0,
+                task_id: e.1,
+                field: e.2,
+                old_value: e.3,
+                new_value: e.4,
+                changed_by: e.5,
+                changed_at: e.6,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Delete a task
+///
+/// Subtasks are deleted with it unless `keep_subtasks` is set, in which case
+/// they move up to the deleted task's parent. The task goes to the trash
+/// unless `permanent` is set.
+#[tauri::command]
+pub async fn task_delete(
+    state: State<'_, AppState>,
+    task_id: String,
+    keep_subtasks: Option<bool>,
+    permanent: Option<bool>,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("task_delete", async {
+        if !permanent.unwrap_or(false) {
+            return trash_task(&state.db, &task_id, keep_subtasks.unwrap_or(false)).await;
+        }
+
+        let mut tx = state.db.begin().await?;
+
+        if keep_subtasks.unwrap_or(false) {
+            sqlx::query(
+                r#"
+                UPDATE tasks
+                SET parent_task_id = (SELECT parent_task_id FROM tasks WHERE id = ?), updated_at = ?
+                WHERE parent_task_id = ?
+                "#,
+            )
+            .bind(&task_id)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(&task_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let result = sqlx::query("DELETE FROM tasks WHERE id = ?")
+            .bind(&task_id)
+            .execute(&mut *tx)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Task", &task_id));
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    })
+    .await
+}
+
 // ============================================================================
 // Task Dependencies Commands
 // ============================================================================
@@ -945,20 +2273,23 @@ pub async fn task_add_dependency(
     task_id: String,
     depends_on_task_id: String,
 ) -> Result<(), AppError> {
-    // Prevent self-dependency
-    if task_id == depends_on_task_id {
-        return Err(AppError::invalid_input("A task cannot depend on itself"));
-    }
-
-    sqlx::query(
-        "INSERT OR IGNORE INTO task_dependencies (task_id, depends_on_task_id) VALUES (?, ?)",
-    )
-    .bind(&task_id)
-    .bind(&depends_on_task_id)
-    .execute(&state.db)
-    .await?;
+    state.command_metrics.measure("task_add_dependency", async {
+        // Prevent self-dependency
+        if task_id == depends_on_task_id {
+            return Err(AppError::invalid_input("A task cannot depend on itself"));
+        }
+
+        sqlx::query(
+            "INSERT OR IGNORE INTO task_dependencies (task_id, depends_on_task_id) VALUES (?, ?)",
+        )
+        .bind(&task_id)
+        .bind(&depends_on_task_id)
+        .execute(&state.db)
+        .await?;
 
-    Ok(())
+        Ok(())
+    })
+    .await
 }
 
 #[tauri::command]
@@ -967,15 +2298,18 @@ pub async fn task_remove_dependency(
     task_id: String,
     depends_on_task_id: String,
 ) -> Result<(), AppError> {
-    sqlx::query(
-        "DELETE FROM task_dependencies WHERE task_id = ? AND depends_on_task_id = ?",
-    )
-    .bind(&task_id)
-    .bind(&depends_on_task_id)
-    .execute(&state.db)
-    .await?;
+    state.command_metrics.measure("task_remove_dependency", async {
+        sqlx::query(
+            "DELETE FROM task_dependencies WHERE task_id = ? AND depends_on_task_id = ?",
+        )
+        .bind(&task_id)
+        .bind(&depends_on_task_id)
+        .execute(&state.db)
+        .await?;
 
-    Ok(())
+        Ok(())
+    })
+    .await
 }
 
 #[tauri::command]
@@ -983,14 +2317,104 @@ pub async fn task_get_dependencies(
     state: State<'_, AppState>,
     task_id: String,
 ) -> Result<Vec<String>, AppError> {
-    let deps: Vec<(String,)> = sqlx::query_as(
-        "SELECT depends_on_task_id FROM task_dependencies WHERE task_id = ?",
+    state.command_metrics.measure("task_get_dependencies", async {
+        let deps: Vec<(String,)> = sqlx::query_as(
+            "SELECT depends_on_task_id FROM task_dependencies WHERE task_id = ?",
+        )
+        .bind(&task_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(deps.into_iter().map(|d| d.0).collect())
+    })
+    .await
+}
+
+/// Get tasks that are not done and whose dependencies are all done
+#[tauri::command]
+pub async fn task_get_ready(
+    state: State<'_, AppState>,
+    project_id: String,
+    sprint_id: Option<String>,
+) -> Result<Vec<TaskResponse>, AppError> {
+    state.command_metrics.measure("task_get_ready", async {
+        let mut sql = String::from(
+            r#"
+            SELECT id, project_id, sprint_id, parent_task_id, title, description, status, status_id, priority, estimated_hours, due_date, created_at, updated_at
+            FROM tasks
+            WHERE project_id = ?
+              AND deleted_at IS NULL
+              AND status != 'done'
+              AND NOT EXISTS (
+                  SELECT 1 FROM task_dependencies d
+                  JOIN tasks dep ON dep.id = d.depends_on_task_id
+                  WHERE d.task_id = tasks.id AND dep.status != 'done' AND dep.deleted_at IS NULL
+              )
+            "#,
+        );
+        if sprint_id.is_some() {
+            sql.push_str(" AND sprint_id = ?");
+        }
+        sql.push_str(" ORDER BY created_at ASC");
+
+        let mut query = sqlx::query_as::<_, (String, String, Option<String>, Option<String>, String, Option<String>, String, Option<String>, String, Option<f64>, Option<String>, String, String)>(&sql)
+            .bind(&project_id);
+        if let Some(sid) = &sprint_id {
+            query = query.bind(sid);
+        }
+        let tasks = query.fetch_all(&state.db).await?;
+        let mut times = task_time_map(&state.db, &project_id).await?;
+
+        Ok(tasks
+            .into_iter()
+            .map(|t| TaskResponse {
+                actual_hours: times.get(&t.0).map_or(0.0, |time| time.actual_hours),
+                timer_started_at: times.remove(&t.0).and_then(|time| time.timer_started_at),
+                id: t.0,
+                project_id: t.1,
+                sprint_id: t.2,
+                parent_task_id: t.3,
+                title: t.4,
+                description: t.5,
+                status: t.6,
+                status_id: t.7,
+                priority: t.8,
+                estimated_hours: t.9,
+                due_date: t.10,
+                blocked_by: Vec::new(),
+                created_at: t.11,
+                updated_at: t.12,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Map each task in a project to its dependencies that are not done yet
+async fn blocked_by_map(
+    db: &sqlx::SqlitePool,
+    project_id: &str,
+) -> Result<HashMap<String, Vec<String>>, AppError> {
+    let rows = sqlx::query_as::<_, (String, String)>(
+        r#"
+        SELECT d.task_id, d.depends_on_task_id
+        FROM task_dependencies d
+        JOIN tasks t ON t.id = d.task_id
+        JOIN tasks dep ON dep.id = d.depends_on_task_id
+        WHERE t.project_id = ? AND dep.status != 'done' AND t.deleted_at IS NULL AND dep.deleted_at IS NULL
+        ORDER BY dep.created_at ASC
+        "#,
     )
-    .bind(&task_id)
-    .fetch_all(&state.db)
+    .bind(project_id)
+    .fetch_all(db)
     .await?;
 
-    Ok(deps.into_iter().map(|d| d.0).collect())
+    let mut blocked: HashMap<String, Vec<String>> = HashMap::new();
+    for (task_id, depends_on_task_id) in rows {
+        blocked.entry(task_id).or_default().push(depends_on_task_id);
+    }
+
+    Ok(blocked)
 }
 
 // ============================================================================
@@ -1002,124 +2426,444 @@ pub async fn dashboard_stats(
     state: State<'_, AppState>,
     project_id: String,
 ) -> Result<DashboardStatsResponse, AppError> {
-    // Get active sprint
-    let active_sprint = sqlx::query_as::<_, (String, String, Option<String>, String, Option<String>, Option<String>, Option<String>, String, String, String)>(
-        r#"
-        SELECT id, project_id, milestone_id, name, description, start_date, end_date, status, created_at, updated_at
-        FROM sprints
-        WHERE project_id = ? AND status = 'active'
-        LIMIT 1
-        "#,
-    )
-    .bind(&project_id)
-    .fetch_optional(&state.db)
-    .await?;
+    state.command_metrics.measure("dashboard_stats", async {
+        // Get active sprint
+        let active_sprint = sqlx::query_as::<_, (String, String, Option<String>, String, Option<String>, Option<String>, Option<String>, String, String, String)>(
+            r#"
+            SELECT id, project_id, milestone_id, name, description, start_date, end_date, status, created_at, updated_at
+            FROM sprints
+            WHERE project_id = ? AND status = 'active' AND deleted_at IS NULL
+            LIMIT 1
+            "#,
+        )
+        .bind(&project_id)
+        .fetch_optional(&state.db)
+        .await?;
+
+        let active_sprint_response = if let Some(s) = active_sprint {
+            let (task_count, completed_count): (i32, i32) = sqlx::query_as(
+                r#"
+                SELECT
+                    COUNT(*) as total,
+                    COALESCE(SUM(CASE WHEN status = 'done' THEN 1 ELSE 0 END), 0) as completed
+                FROM tasks
+                WHERE sprint_id = ? AND deleted_at IS NULL
+                "#,
+            )
+            .bind(&s.0)
+            .fetch_one(&state.db)
+            .await?;
+
+            let progress = if task_count > 0 {
+                (completed_count as f64 / task_count as f64) * 100.0
+            } else {
+                0.0
+            };
+
+            Some(SprintWithProgressResponse {
+                sprint: SprintResponse {
+                    id: s.0,
+                    project_id: s.1,
+                    milestone_id: s.2,
+                    name: s.3,
+                    description: s.4,
+                    start_date: s.5,
+                    end_date: s.6,
+                    status: s.7,
+                    created_at: s.8,
+                    updated_at: s.9,
+                },
+                task_count,
+                completed_count,
+                progress,
+            })
+        } else {
+            None
+        };
+
+        // Get tasks completed today
+        let today_start = chrono::Utc::now()
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc()
+            .to_rfc3339();
 
-    let active_sprint_response = if let Some(s) = active_sprint {
-        let (task_count, completed_count): (i32, i32) = sqlx::query_as(
+        // Use the history so later edits to a done task don't count it again
+        let tasks_completed_today: (i32,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(DISTINCT h.task_id)
+            FROM task_history h
+            JOIN tasks t ON t.id = h.task_id
+            WHERE h.project_id = ? AND h.field = 'status' AND h.new_value = 'done'
+              AND h.changed_at >= ? AND t.status = 'done' AND t.deleted_at IS NULL
+            "#,
+        )
+        .bind(&project_id)
+        .bind(&today_start)
+        .fetch_one(&state.db)
+        .await?;
+
+        // Get total task counts
+        let (total_tasks, completed_tasks): (i32, i32) = sqlx::query_as(
             r#"
             SELECT
                 COUNT(*) as total,
                 COALESCE(SUM(CASE WHEN status = 'done' THEN 1 ELSE 0 END), 0) as completed
             FROM tasks
-            WHERE sprint_id = ?
+            WHERE project_id = ? AND deleted_at IS NULL
             "#,
         )
-        .bind(&s.0)
+        .bind(&project_id)
         .fetch_one(&state.db)
         .await?;
 
-        let progress = if task_count > 0 {
-            (completed_count as f64 / task_count as f64) * 100.0
-        } else {
-            0.0
-        };
+        // Get next milestone
+        let next_milestone = sqlx::query_as::<_, (String, String, String, Option<String>, Option<String>, String, i32, String, String)>(
+            r#"
+            SELECT id, project_id, name, description, target_date, status, sort_order, created_at, updated_at
+            FROM milestones
+            WHERE project_id = ? AND status != 'completed' AND deleted_at IS NULL
+            ORDER BY sort_order ASC
+            LIMIT 1
+            "#,
+        )
+        .bind(&project_id)
+        .fetch_optional(&state.db)
+        .await?;
 
-        Some(SprintWithProgressResponse {
-            sprint: SprintResponse {
-                id: s.0,
-                project_id: s.1,
-                milestone_id: s.2,
-                name: s.3,
-                description: s.4,
-                start_date: s.5,
-                end_date: s.6,
-                status: s.7,
-                created_at: s.8,
-                updated_at: s.9,
-            },
-            task_count,
-            completed_count,
-            progress,
+        let next_milestone_response = next_milestone.map(|m| MilestoneResponse {
+            id: m.0,
+            project_id: m.1,
+            name: m.2,
+            description: m.3,
+            target_date: m.4,
+            status: m.5,
+            sort_order: m.6,
+            created_at: m.7,
+            updated_at: m.8,
+        });
+
+        Ok(DashboardStatsResponse {
+            active_sprint: active_sprint_response,
+            tasks_completed_today: tasks_completed_today.0,
+            total_tasks,
+            completed_tasks,
+            next_milestone: next_milestone_response,
         })
-    } else {
-        None
-    };
+    })
+    .await
+}
 
-    // Get tasks completed today
-    let today_start = chrono::Utc::now()
-        .date_naive()
-        .and_hms_opt(0, 0, 0)
-        .unwrap()
-        .and_utc()
-        .to_rfc3339();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, parent: Option<&str>, status: &str) -> TaskResponse {
+        TaskResponse {
+            id: id.to_string(),
+            project_id: "p".to_string(),
+            sprint_id: None,
+            parent_task_id: parent.map(str::to_string),
+            title: id.to_string(),
+            description: None,
+            status: status.to_string(),
+            status_id: None,
+            priority: "medium".to_string(),
+            estimated_hours: None,
+            due_date: None,
+            actual_hours: 0.0,
+            timer_started_at: None,
+            blocked_by: Vec::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
 
-    let tasks_completed_today: (i32,) = sqlx::query_as(
-        r#"
-        SELECT COUNT(*) FROM tasks
-        WHERE project_id = ? AND status = 'done' AND updated_at >= ?
-        "#,
-    )
-    .bind(&project_id)
-    .bind(&today_start)
-    .fetch_one(&state.db)
-    .await?;
+    #[test]
+    fn test_task_tree_rolls_up_completion() {
+        let tree = build_task_tree(vec![
+            task("epic", None, "in_progress"),
+            task("a", Some("epic"), "done"),
+            task("b", Some("epic"), "todo"),
+            task("b1", Some("b"), "done"),
+            task("b2", Some("b"), "todo"),
+            task("b3", Some("b"), "todo"),
+            task("b4", Some("b"), "todo"),
+        ]);
+
+        assert_eq!(tree.len(), 1);
+        let epic = &tree[0];
+        assert_eq!(epic.children.len(), 2);
+        assert_eq!(epic.children[1].completion_percent, 25.0);
+        assert_eq!(epic.completion_percent, 62.5);
+    }
 
-    // Get total task counts
-    let (total_tasks, completed_tasks): (i32, i32) = sqlx::query_as(
-        r#"
-        SELECT
-            COUNT(*) as total,
-            COALESCE(SUM(CASE WHEN status = 'done' THEN 1 ELSE 0 END), 0) as completed
-        FROM tasks
-        WHERE project_id = ?
-        "#,
-    )
-    .bind(&project_id)
-    .fetch_one(&state.db)
-    .await?;
+    #[test]
+    fn test_unique_ids_keeps_first_order() {
+        let ids = vec!["b".to_string(), "a".to_string(), "b".to_string()];
+        assert_eq!(unique_ids(ids), vec!["b".to_string(), "a".to_string()]);
+    }
 
-    // Get next milestone
-    let next_milestone = sqlx::query_as::<_, (String, String, String, Option<String>, Option<String>, String, i32, String, String)>(
-        r#"
-        SELECT id, project_id, name, description, target_date, status, sort_order, created_at, updated_at
-        FROM milestones
-        WHERE project_id = ? AND status != 'completed'
-        ORDER BY sort_order ASC
-        LIMIT 1
-        "#,
-    )
-    .bind(&project_id)
-    .fetch_optional(&state.db)
-    .await?;
+    /// Sprint `s1` holding `todo` and `done` tasks and a trashed `gone` task,
+    /// a planned sprint `s2`, a completed sprint `old`, and a `backlog` task
+    async fn seeded_pool() -> sqlx::SqlitePool {
+        let db = crate::db::test_pool().await;
+        let now = "2026-10-15T09:30:00+00:00";
+        sqlx::query(
+            "INSERT INTO projects (id, name, root_path, created_at, updated_at) VALUES ('p1', 'P', '/tmp', ?1, ?1)",
+        )
+        .bind(now)
+        .execute(&db)
+        .await
+        .unwrap();
+        for (id, status) in [("s1", "active"), ("s2", "planned"), ("old", "completed")] {
+            sqlx::query(
+                r#"
+                INSERT INTO sprints (id, project_id, name, status, created_at, updated_at)
+                VALUES (?1, 'p1', ?1, ?2, ?3, ?3)
+                "#,
+            )
+            .bind(id)
+            .bind(status)
+            .bind(now)
+            .execute(&db)
+            .await
+            .unwrap();
+        }
+        let tasks = [
+            ("todo", Some("s1"), "todo", None),
+            ("done", Some("s1"), "done", None),
+            ("gone", Some("s1"), "todo", Some(now)),
+            ("backlog", None, "todo", None),
+        ];
+        for (id, sprint_id, status, deleted_at) in tasks {
+            sqlx::query(
+                r#"
+                INSERT INTO tasks (id, project_id, sprint_id, title, status, deleted_at, created_at, updated_at)
+                VALUES (?1, 'p1', ?2, ?1, ?3, ?4, ?5, ?5)
+                "#,
+            )
+            .bind(id)
+            .bind(sprint_id)
+            .bind(status)
+            .bind(deleted_at)
+            .bind(now)
+            .execute(&db)
+            .await
+            .unwrap();
+        }
+        db
+    }
 
-    let next_milestone_response = next_milestone.map(|m| MilestoneResponse {
-        id: m.0,
-        project_id: m.1,
-        name: m.2,
-        description: m.3,
-        target_date: m.4,
-        status: m.5,
-        sort_order: m.6,
-        created_at: m.7,
-        updated_at: m.8,
-    });
-
-    Ok(DashboardStatsResponse {
-        active_sprint: active_sprint_response,
-        tasks_completed_today: tasks_completed_today.0,
-        total_tasks,
-        completed_tasks,
-        next_milestone: next_milestone_response,
-    })
+    async fn task_column(db: &sqlx::SqlitePool, column: &str, task_id: &str) -> Option<String> {
+        sqlx::query_scalar(&format!("SELECT {} FROM tasks WHERE id = ?", column))
+            .bind(task_id)
+            .fetch_one(db)
+            .await
+            .unwrap()
+    }
+
+    async fn history_count(db: &sqlx::SqlitePool, task_id: &str, field: &str) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM task_history WHERE task_id = ? AND field = ?")
+            .bind(task_id)
+            .bind(field)
+            .fetch_one(db)
+            .await
+            .unwrap()
+    }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    fn outcomes(results: &[TaskBulkResult]) -> Vec<(&str, bool)> {
+        results.iter().map(|r| (r.task_id.as_str(), r.success)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_complete_sprint_carries_over_to_existing_sprint() {
+        let db = seeded_pool().await;
+        let completed = complete_sprint(&db, "s1", Some("s2".to_string()), None).await.unwrap();
+
+        assert_eq!(completed.project_id, "p1");
+        assert_eq!(completed.next_sprint_id.as_deref(), Some("s2"));
+        assert_eq!(completed.incomplete_tasks, 1);
+        assert_eq!(completed.moved_tasks, 1);
+
+        assert_eq!(task_column(&db, "sprint_id", "todo").await.as_deref(), Some("s2"));
+        assert_eq!(task_column(&db, "sprint_id", "done").await.as_deref(), Some("s1"));
+        assert_eq!(task_column(&db, "sprint_id", "gone").await.as_deref(), Some("s1"));
+        assert_eq!(history_count(&db, "todo", "sprint_id").await, 1);
+        assert_eq!(history_count(&db, "gone", "sprint_id").await, 0);
+
+        let status: String = sqlx::query_scalar("SELECT status FROM sprints WHERE id = 's1'")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(status, "completed");
+    }
+
+    #[tokio::test]
+    async fn test_complete_sprint_carries_over_to_new_sprint() {
+        let db = seeded_pool().await;
+        let next = NextSprintRequest {
+            milestone_id: None,
+            name: "Next".to_string(),
+            description: None,
+            start_date: None,
+            end_date: None,
+        };
+        let completed = complete_sprint(&db, "s1", None, Some(next)).await.unwrap();
+
+        let next_sprint_id = completed.next_sprint_id.unwrap();
+        let next_sprint = fetch_sprint(&db, &next_sprint_id).await.unwrap();
+        assert_eq!(next_sprint.name, "Next");
+        assert_eq!(next_sprint.status, "planned");
+        assert_eq!(task_column(&db, "sprint_id", "todo").await, Some(next_sprint_id));
+        assert_eq!(completed.moved_tasks, 1);
+    }
+
+    #[tokio::test]
+    async fn test_complete_sprint_without_carry_over() {
+        let db = seeded_pool().await;
+        let completed = complete_sprint(&db, "s1", None, None).await.unwrap();
+
+        assert_eq!(completed.next_sprint_id, None);
+        assert_eq!(completed.incomplete_tasks, 1);
+        assert_eq!(completed.moved_tasks, 0);
+        assert_eq!(task_column(&db, "sprint_id", "todo").await.as_deref(), Some("s1"));
+    }
+
+    #[tokio::test]
+    async fn test_complete_sprint_rejects_bad_targets() {
+        let db = seeded_pool().await;
+        assert!(complete_sprint(&db, "s1", Some("s1".to_string()), None).await.is_err());
+        assert!(complete_sprint(&db, "s1", Some("old".to_string()), None).await.is_err());
+        assert!(complete_sprint(&db, "s1", Some("missing".to_string()), None).await.is_err());
+
+        // Nothing was changed by the failed attempts
+        assert_eq!(task_column(&db, "sprint_id", "todo").await.as_deref(), Some("s1"));
+        let status: String = sqlx::query_scalar("SELECT status FROM sprints WHERE id = 's1'")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(status, "active");
+    }
+
+    #[tokio::test]
+    async fn test_bulk_move() {
+        let db = seeded_pool().await;
+        let results = bulk_move(&db, ids(&["todo", "gone", "todo", "missing", "backlog"]), Some("s2"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            outcomes(&results),
+            [("todo", true), ("gone", false), ("missing", false), ("backlog", true)]
+        );
+        assert_eq!(task_column(&db, "sprint_id", "todo").await.as_deref(), Some("s2"));
+        assert_eq!(task_column(&db, "sprint_id", "backlog").await.as_deref(), Some("s2"));
+        assert_eq!(task_column(&db, "sprint_id", "gone").await.as_deref(), Some("s1"));
+        assert_eq!(history_count(&db, "todo", "sprint_id").await, 1);
+
+        bulk_move(&db, ids(&["todo"]), None).await.unwrap();
+        assert_eq!(task_column(&db, "sprint_id", "todo").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_update() {
+        let db = seeded_pool().await;
+        let request = TaskBulkUpdateRequest {
+            sprint_id: Some("s2".to_string()),
+            status: None,
+            status_id: None,
+            priority: Some("high".to_string()),
+        };
+        let (results, completed) = bulk_update(&db, ids(&["todo", "gone", "backlog"]), &request).await.unwrap();
+
+        assert_eq!(outcomes(&results), [("todo", true), ("gone", false), ("backlog", true)]);
+        assert!(completed.is_empty());
+        for task_id in ["todo", "backlog"] {
+            assert_eq!(task_column(&db, "sprint_id", task_id).await.as_deref(), Some("s2"));
+            assert_eq!(task_column(&db, "priority", task_id).await.as_deref(), Some("high"));
+            assert_eq!(history_count(&db, task_id, "priority").await, 1);
+        }
+        assert_eq!(task_column(&db, "priority", "gone").await.as_deref(), Some("medium"));
+        assert_eq!(task_column(&db, "sprint_id", "gone").await.as_deref(), Some("s1"));
+        assert_eq!(history_count(&db, "gone", "priority").await, 0);
+    }
+
+    async fn add_dod_item(db: &sqlx::SqlitePool, enforcement: &str, checked_by: &[&str]) {
+        let now = "2026-10-15T09:30:00+00:00";
+        sqlx::query("UPDATE projects SET dod_enforcement = ? WHERE id = 'p1'")
+            .bind(enforcement)
+            .execute(db)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO dod_items (id, project_id, text, created_at) VALUES ('d1', 'p1', 'Tested', ?)")
+            .bind(now)
+            .execute(db)
+            .await
+            .unwrap();
+        for task_id in checked_by {
+            sqlx::query("INSERT INTO task_dod_checks (task_id, dod_item_id, checked_at) VALUES (?, 'd1', ?)")
+                .bind(task_id)
+                .bind(now)
+                .execute(db)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bulk_update_completes_tasks() {
+        let db = seeded_pool().await;
+        add_dod_item(&db, "warn", &[]).await;
+        let request = TaskBulkUpdateRequest {
+            sprint_id: None,
+            status: Some("done".to_string()),
+            status_id: None,
+            priority: None,
+        };
+        let (results, completed) = bulk_update(&db, ids(&["todo", "done", "backlog"]), &request).await.unwrap();
+
+        assert_eq!(outcomes(&results), [("todo", true), ("done", true), ("backlog", true)]);
+        // Tasks that were already done are not completed again
+        assert_eq!(
+            completed,
+            [("p1".to_string(), "todo".to_string()), ("p1".to_string(), "backlog".to_string())]
+        );
+        assert_eq!(task_column(&db, "status", "todo").await.as_deref(), Some("done"));
+        assert_eq!(history_count(&db, "todo", "status").await, 1);
+        assert_eq!(history_count(&db, "done", "status").await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_update_blocked_by_definition_of_done() {
+        let db = seeded_pool().await;
+        add_dod_item(&db, "block", &["backlog"]).await;
+        sqlx::query(
+            r#"
+            INSERT INTO task_statuses (id, project_id, name, category, sort_order, created_at)
+            VALUES ('col-done', 'p1', 'Shipped', 'done', 0, '2026-10-15T09:30:00+00:00')
+            "#,
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        let request = TaskBulkUpdateRequest {
+            sprint_id: None,
+            status: None,
+            status_id: Some("col-done".to_string()),
+            priority: None,
+        };
+        let (results, completed) = bulk_update(&db, ids(&["todo", "backlog"]), &request).await.unwrap();
+
+        assert_eq!(outcomes(&results), [("todo", false), ("backlog", true)]);
+        assert_eq!(completed, [("p1".to_string(), "backlog".to_string())]);
+        assert_eq!(task_column(&db, "status", "todo").await.as_deref(), Some("todo"));
+        assert_eq!(task_column(&db, "status", "backlog").await.as_deref(), Some("done"));
+        assert_eq!(task_column(&db, "status_id", "backlog").await.as_deref(), Some("col-done"));
+    }
 }
@@ -0,0 +1,408 @@
+//! Prompt Library Commands
+//!
+//! Reusable prompts stored with the app. A prompt body may contain
+//! `{{variable}}` placeholders, which `prompt_render` fills in before the
+//! text is sent with `session_send_message`.
+//!
+//! Given a project, `{{git_branch}}`, `{{open_tasks}}`, and
+//! `{{failing_tests}}` are filled in from the project's current state unless
+//! the caller supplies them.
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::git;
+use crate::state::AppState;
+
+use super::project::project_root;
+
+/// The project's checked-out branch
+const GIT_BRANCH: &str = "git_branch";
+
+/// The project's tasks that aren't done
+const OPEN_TASKS: &str = "open_tasks";
+
+/// Project scripts whose latest run failed, with the end of their output
+const FAILING_TESTS: &str = "failing_tests";
+
+/// Most tasks listed by `{{open_tasks}}`
+const MAX_OPEN_TASKS: i64 = 50;
+
+/// Output lines kept per script in `{{failing_tests}}`
+const FAILING_OUTPUT_LINES: usize = 40;
+
+/// Prompt returned to frontend
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptResponse {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub body: String,
+    /// Placeholder names in the body, in order of first use
+    pub variables: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Create a prompt
+#[tauri::command]
+pub async fn prompt_create(
+    state: State<'_, AppState>,
+    name: String,
+    body: String,
+    description: Option<String>,
+) -> Result<PromptResponse, AppError> {
+    state.command_metrics.measure("prompt_create", async {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(AppError::invalid_input("Prompt name cannot be empty"));
+        }
+        if body.trim().is_empty() {
+            return Err(AppError::invalid_input("Prompt body cannot be empty"));
+        }
+        let description = description
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty());
+
+        let name_taken: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM prompts WHERE name = ?)")
+            .bind(&name)
+            .fetch_one(&state.db)
+            .await?;
+        if name_taken {
+            return Err(AppError::invalid_input(format!("A prompt named '{}' already exists", name)));
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+
+        sqlx::query(
+            r#"
+            INSERT INTO prompts (id, name, description, body, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&name)
+        .bind(&description)
+        .bind(&body)
+        .bind(&now)
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
+
+        Ok(PromptResponse {
+            id,
+            name,
+            description,
+            variables: template_variables(&body),
+            body,
+            created_at: now.clone(),
+            updated_at: now,
+        })
+    })
+    .await
+}
+
+/// List prompts by name
+#[tauri::command]
+pub async fn prompt_list(
+    state: State<'_, AppState>,
+) -> Result<Vec<PromptResponse>, AppError> {
+    state.command_metrics.measure("prompt_list", async {
+        let rows = sqlx::query_as::<_, (String, String, Option<String>, String, String, String)>(
+            r#"
+            SELECT id, name, description, body, created_at, updated_at
+            FROM prompts
+            ORDER BY name COLLATE NOCASE
+            "#,
+        )
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| PromptResponse {
+                id: row.0,
+                name: row.1,
+                description: row.2,
+                variables: template_variables(&row.3),
+                body: row.3,
+                created_at: row.4,
+                updated_at: row.5,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Delete a prompt
+#[tauri::command]
+pub async fn prompt_delete(
+    state: State<'_, AppState>,
+    prompt_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("prompt_delete", async {
+        let result = sqlx::query("DELETE FROM prompts WHERE id = ?")
+            .bind(&prompt_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Prompt", &prompt_id));
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Fill in a prompt's placeholders, ready to send as a message
+///
+/// Every placeholder in the body must be given a value. With `project_id`,
+/// the live variables are read from that project when not given.
+#[tauri::command]
+pub async fn prompt_render(
+    state: State<'_, AppState>,
+    prompt_id: String,
+    variables: Option<HashMap<String, String>>,
+    project_id: Option<String>,
+) -> Result<String, AppError> {
+    state.command_metrics.measure("prompt_render", async {
+        let body: String = sqlx::query_scalar("SELECT body FROM prompts WHERE id = ?")
+            .bind(&prompt_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or_else(|| AppError::database_not_found("Prompt", &prompt_id))?;
+
+        let mut variables = variables.unwrap_or_default();
+        if let Some(project_id) = project_id {
+            resolve_live_variables(&state.db, &project_id, &body, &mut variables).await?;
+        }
+        render_template(&body, &variables)
+    })
+    .await
+}
+
+/// Fill in the live variables `body` uses that have no value yet
+async fn resolve_live_variables(
+    db: &SqlitePool,
+    project_id: &str,
+    body: &str,
+    variables: &mut HashMap<String, String>,
+) -> Result<(), AppError> {
+    for name in template_variables(body) {
+        if variables.contains_key(&name) {
+            continue;
+        }
+        let value = match name.as_str() {
+            GIT_BRANCH => {
+                let root = project_root(db, project_id).await?;
+                git::current_branch(&root)
+                    .await?
+                    .unwrap_or_else(|| "(detached HEAD)".to_string())
+            }
+            OPEN_TASKS => {
+                let tasks = sqlx::query_as::<_, (String, String, String)>(
+                    r#"
+                    SELECT title, status, priority
+                    FROM tasks
+                    WHERE project_id = ? AND status != 'done' AND deleted_at IS NULL
+                    ORDER BY
+                        CASE status WHEN 'in_progress' THEN 0 ELSE 1 END,
+                        CASE priority WHEN 'high' THEN 0 WHEN 'medium' THEN 1 ELSE 2 END,
+                        created_at ASC
+                    LIMIT ?
+                    "#,
+                )
+                .bind(project_id)
+                .bind(MAX_OPEN_TASKS)
+                .fetch_all(db)
+                .await?;
+                format_open_tasks(&tasks)
+            }
+            FAILING_TESTS => {
+                // The latest finished run of each script, where it failed
+                let failures = sqlx::query_as::<_, (String, Option<i64>, String)>(
+                    r#"
+                    SELECT s.name, r.exit_code, r.output
+                    FROM script_runs r
+                    JOIN project_scripts s ON s.id = r.script_id
+                    WHERE r.project_id = ? AND r.status = 'failed'
+                      AND r.started_at = (
+                          SELECT MAX(started_at) FROM script_runs
+                          WHERE script_id = r.script_id AND status != 'running'
+                      )
+                    ORDER BY s.name COLLATE NOCASE
+                    "#,
+                )
+                .bind(project_id)
+                .fetch_all(db)
+                .await?;
+                format_failing_tests(&failures)
+            }
+            _ => continue,
+        };
+        variables.insert(name, value);
+    }
+    Ok(())
+}
+
+/// `{{open_tasks}}` as a list of (title, status, priority)
+fn format_open_tasks(tasks: &[(String, String, String)]) -> String {
+    if tasks.is_empty() {
+        return "No open tasks".to_string();
+    }
+    tasks
+        .iter()
+        .map(|(title, status, priority)| format!("- [{}] {} ({} priority)", status, title, priority))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `{{failing_tests}}` from (script name, exit code, output) of failed runs
+fn format_failing_tests(failures: &[(String, Option<i64>, String)]) -> String {
+    if failures.is_empty() {
+        return "No failing scripts".to_string();
+    }
+    failures
+        .iter()
+        .map(|(name, exit_code, output)| {
+            let exit = exit_code.map_or("no exit code".to_string(), |code| format!("exit code {}", code));
+            let lines: Vec<&str> = output.lines().collect();
+            let tail = lines[lines.len().saturating_sub(FAILING_OUTPUT_LINES)..].join("\n");
+            format!("{} ({}):\n```\n{}\n```", name, exit, tail)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Find each `{{name}}` placeholder, as (start, end, name)
+///
+/// Names are letters, digits, `_`, `-`, and `.`, optionally padded with
+/// spaces inside the braces. Anything else between braces is left as text.
+fn placeholders(body: &str) -> Vec<(usize, usize, &str)> {
+    let mut found = Vec::new();
+    let mut rest = 0;
+
+    while let Some(open) = body[rest..].find("{{").map(|i| rest + i) {
+        let Some(close) = body[open + 2..].find("}}").map(|i| open + 2 + i) else {
+            break;
+        };
+        let name = body[open + 2..close].trim();
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'));
+        if valid {
+            found.push((open, close + 2, name));
+            rest = close + 2;
+        } else {
+            rest = open + 1;
+        }
+    }
+
+    found
+}
+
+/// Placeholder names in a prompt body, in order of first use
+fn template_variables(body: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for (_, _, name) in placeholders(body) {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Substitute every placeholder, failing if any has no value
+fn render_template(body: &str, variables: &HashMap<String, String>) -> Result<String, AppError> {
+    let found = placeholders(body);
+
+    let missing: Vec<String> = template_variables(body)
+        .into_iter()
+        .filter(|name| !variables.contains_key(name))
+        .collect();
+    if !missing.is_empty() {
+        return Err(AppError::invalid_input(format!(
+            "Missing values for prompt variables: {}",
+            missing.join(", ")
+        )));
+    }
+
+    let mut rendered = String::with_capacity(body.len());
+    let mut last = 0;
+    for (start, end, name) in found {
+        rendered.push_str(&body[last..start]);
+        rendered.push_str(&variables[name]);
+        last = end;
+    }
+    rendered.push_str(&body[last..]);
+
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_template_variables() {
+        assert_eq!(
+            template_variables("Fix {{ file }} in {{module}}, then test {{file}}"),
+            vec!["file", "module"]
+        );
+        assert!(template_variables("No {{}} or { {x} } or {{two words}} here").is_empty());
+    }
+
+    #[test]
+    fn test_render_template() {
+        let rendered = render_template(
+            "Review {{file}} for {{ concern }}. {{file}} again.",
+            &vars(&[("file", "main.rs"), ("concern", "{{file}}")]),
+        )
+        .unwrap();
+        // Values are inserted as-is, not expanded again
+        assert_eq!(rendered, "Review main.rs for {{file}}. main.rs again.");
+
+        let err = render_template("{{a}} {{b}}", &vars(&[("a", "1")])).unwrap_err();
+        assert!(err.message.contains('b'));
+
+        assert_eq!(render_template("{{ unclosed", &vars(&[])).unwrap(), "{{ unclosed");
+    }
+
+    #[test]
+    fn test_format_open_tasks() {
+        assert_eq!(format_open_tasks(&[]), "No open tasks");
+        let tasks = vec![
+            ("Fix login".to_string(), "in_progress".to_string(), "high".to_string()),
+            ("Write docs".to_string(), "todo".to_string(), "low".to_string()),
+        ];
+        assert_eq!(
+            format_open_tasks(&tasks),
+            "- [in_progress] Fix login (high priority)\n- [todo] Write docs (low priority)"
+        );
+    }
+
+    #[test]
+    fn test_format_failing_tests() {
+        assert_eq!(format_failing_tests(&[]), "No failing scripts");
+
+        let output = (1..=50).map(|n| format!("line {}", n)).collect::<Vec<_>>().join("\n");
+        let rendered = format_failing_tests(&[
+            ("lint".to_string(), None, "bad".to_string()),
+            ("test".to_string(), Some(101), output),
+        ]);
+        assert!(rendered.starts_with("lint (no exit code):\n```\nbad\n```\n\ntest (exit code 101):\n```\nline 11\n"));
+        assert!(rendered.ends_with("line 50\n```"));
+        assert!(!rendered.contains("line 10\n"));
+    }
+}
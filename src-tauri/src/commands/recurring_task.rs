@@ -0,0 +1,686 @@
+//! Recurring Task Commands
+//!
+//! A recurring task is a task template with a schedule. A background job
+//! creates a task from the template each time the schedule comes due, so
+//! chores like "update dependencies" don't have to be recreated by hand.
+//!
+//! Schedules are either an interval, `every 2 weeks`, or a five-field cron
+//! expression, `0 9 * * 1`, read in local time. `@hourly`, `@daily`,
+//! `@weekly`, and `@monthly` are shorthands for the usual cron expressions.
+//! If the app was closed through several due times, one task is created and
+//! the schedule skips ahead to the next time after now.
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDateTime, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, State};
+
+use super::project::record_task_change;
+use super::task_status::default_status_for;
+use crate::error::AppError;
+use crate::events::{emit_event, event_names, RecurringTaskRunPayload};
+use crate::state::AppState;
+
+/// How often the job checks for due recurring tasks
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// How far ahead a cron expression is searched for its next match
+const CRON_SEARCH_DAYS: i64 = 366 * 5;
+
+/// A recurring task template and its schedule
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringTaskResponse {
+    pub id: String,
+    pub project_id: String,
+    /// `every <n> <minutes|hours|days|weeks>` or a cron expression
+    pub rule: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub priority: String,
+    pub estimated_hours: Option<f64>,
+    /// Put created tasks in the project's active sprint instead of the backlog
+    pub add_to_active_sprint: bool,
+    pub enabled: bool,
+    pub next_run_at: String,
+    pub last_run_at: Option<String>,
+    /// The task created most recently
+    pub last_task_id: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringTaskCreateRequest {
+    pub project_id: String,
+    pub rule: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub priority: Option<String>,
+    pub estimated_hours: Option<f64>,
+    pub add_to_active_sprint: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringTaskUpdateRequest {
+    pub rule: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub priority: Option<String>,
+    pub estimated_hours: Option<f64>,
+    pub add_to_active_sprint: Option<bool>,
+    pub enabled: Option<bool>,
+}
+
+/// Create a recurring task
+///
+/// The first task is created at the rule's first due time after now.
+#[tauri::command]
+pub async fn recurring_task_create(
+    state: State<'_, AppState>,
+    request: RecurringTaskCreateRequest,
+) -> Result<RecurringTaskResponse, AppError> {
+    state.command_metrics.measure("recurring_task_create", async {
+        if request.title.trim().is_empty() {
+            return Err(AppError::invalid_input("Task title cannot be empty"));
+        }
+        let priority = request.priority.unwrap_or_else(|| "medium".to_string());
+        validate_priority(&priority)?;
+
+        let project_exists: Option<String> =
+            sqlx::query_scalar("SELECT id FROM projects WHERE id = ? AND deleted_at IS NULL")
+                .bind(&request.project_id)
+                .fetch_optional(&state.db)
+                .await?;
+        if project_exists.is_none() {
+            return Err(AppError::database_not_found("Project", &request.project_id));
+        }
+
+        let rule = request.rule.trim().to_string();
+        let now = Utc::now();
+        let next_run_at = first_run(&rule, now)?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = now.to_rfc3339();
+        sqlx::query(
+            r#"
+            INSERT INTO recurring_tasks (id, project_id, rule, title, description, priority, estimated_hours,
+                                         add_to_active_sprint, enabled, next_run_at, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, 1, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&request.project_id)
+        .bind(&rule)
+        .bind(&request.title)
+        .bind(&request.description)
+        .bind(&priority)
+        .bind(request.estimated_hours)
+        .bind(request.add_to_active_sprint.unwrap_or(false))
+        .bind(&next_run_at)
+        .bind(&now)
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
+
+        fetch_recurring_task(&state.db, &id).await
+    })
+    .await
+}
+
+/// List a project's recurring tasks, next due first
+#[tauri::command]
+pub async fn recurring_task_list(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<Vec<RecurringTaskResponse>, AppError> {
+    state.command_metrics.measure("recurring_task_list", async {
+        let rows = sqlx::query_as::<_, RecurringTaskRow>(&format!(
+            "{} WHERE project_id = ? ORDER BY enabled DESC, next_run_at ASC",
+            SELECT_RECURRING_TASK
+        ))
+        .bind(&project_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(rows.into_iter().map(recurring_task_response).collect())
+    })
+    .await
+}
+
+/// Update a recurring task's template or schedule
+///
+/// Changing the rule, or enabling a disabled recurring task, reschedules it
+/// from now.
+#[tauri::command]
+pub async fn recurring_task_update(
+    state: State<'_, AppState>,
+    recurring_task_id: String,
+    request: RecurringTaskUpdateRequest,
+) -> Result<RecurringTaskResponse, AppError> {
+    state.command_metrics.measure("recurring_task_update", async {
+        let current = fetch_recurring_task(&state.db, &recurring_task_id).await?;
+
+        if let Some(title) = &request.title {
+            if title.trim().is_empty() {
+                return Err(AppError::invalid_input("Task title cannot be empty"));
+            }
+        }
+        if let Some(priority) = &request.priority {
+            validate_priority(priority)?;
+        }
+
+        let rule = request.rule.map(|rule| rule.trim().to_string());
+        let enabled = request.enabled.unwrap_or(current.enabled);
+        let reschedule = rule.is_some() || (enabled && !current.enabled);
+        let next_run_at = if reschedule {
+            first_run(rule.as_deref().unwrap_or(&current.rule), Utc::now())?
+        } else {
+            current.next_run_at
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE recurring_tasks SET
+                rule = COALESCE(?, rule),
+                title = COALESCE(?, title),
+                description = COALESCE(?, description),
+                priority = COALESCE(?, priority),
+                estimated_hours = COALESCE(?, estimated_hours),
+                add_to_active_sprint = COALESCE(?, add_to_active_sprint),
+                enabled = ?,
+                next_run_at = ?,
+                updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&rule)
+        .bind(&request.title)
+        .bind(&request.description)
+        .bind(&request.priority)
+        .bind(request.estimated_hours)
+        .bind(request.add_to_active_sprint)
+        .bind(enabled)
+        .bind(&next_run_at)
+        .bind(Utc::now().to_rfc3339())
+        .bind(&recurring_task_id)
+        .execute(&state.db)
+        .await?;
+
+        fetch_recurring_task(&state.db, &recurring_task_id).await
+    })
+    .await
+}
+
+/// Delete a recurring task; tasks it already created are kept
+#[tauri::command]
+pub async fn recurring_task_delete(
+    state: State<'_, AppState>,
+    recurring_task_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("recurring_task_delete", async {
+        let result = sqlx::query("DELETE FROM recurring_tasks WHERE id = ?")
+            .bind(&recurring_task_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Recurring task", &recurring_task_id));
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Create tasks for due recurring tasks now and then for the lifetime of the app
+pub fn spawn_recurring_task_job(app: AppHandle, db: SqlitePool) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = run_due_recurring_tasks(&app, &db).await {
+                log::warn!("Failed to create recurring tasks: {}", e);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Create one task for each enabled recurring task that is due
+async fn run_due_recurring_tasks(app: &AppHandle, db: &SqlitePool) -> Result<(), AppError> {
+    let now = Utc::now();
+    let due = sqlx::query_as::<_, RecurringTaskRow>(&format!(
+        r#"
+        {} WHERE enabled = 1 AND next_run_at <= ?
+          AND project_id IN (SELECT id FROM projects WHERE deleted_at IS NULL)
+        ORDER BY next_run_at ASC
+        "#,
+        SELECT_RECURRING_TASK
+    ))
+    .bind(now.to_rfc3339())
+    .fetch_all(db)
+    .await?;
+
+    for row in due {
+        let recurring = recurring_task_response(row);
+        match materialize(db, &recurring, now).await {
+            Ok(task_id) => {
+                log::info!("Created task {} from recurring task {}", task_id, recurring.id);
+                let _ = emit_event(
+                    app,
+                    event_names::RECURRING_TASK_RUN,
+                    RecurringTaskRunPayload {
+                        recurring_task_id: recurring.id,
+                        task_id,
+                        project_id: recurring.project_id,
+                        title: recurring.title,
+                    },
+                );
+            }
+            Err(e) => log::warn!("Failed to run recurring task {}: {}", recurring.id, e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Create a recurring task's next task and schedule the one after it
+async fn materialize(
+    db: &SqlitePool,
+    recurring: &RecurringTaskResponse,
+    now: DateTime<Utc>,
+) -> Result<String, AppError> {
+    let rule = parse_rule(&recurring.rule)?;
+    let scheduled = DateTime::parse_from_rfc3339(&recurring.next_run_at)
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or(now);
+    // A rule that can't run again is disabled after this run
+    let next_run_at = rule.following_run(scheduled, now).map(|t| t.to_rfc3339());
+
+    let sprint_id: Option<String> = if recurring.add_to_active_sprint {
+        sqlx::query_scalar(
+            "SELECT id FROM sprints WHERE project_id = ? AND status = 'active' AND deleted_at IS NULL LIMIT 1",
+        )
+        .bind(&recurring.project_id)
+        .fetch_optional(db)
+        .await?
+    } else {
+        None
+    };
+    let status_id = default_status_for(db, &recurring.project_id, "todo").await?;
+
+    let task_id = uuid::Uuid::new_v4().to_string();
+    let now = now.to_rfc3339();
+    let mut tx = db.begin().await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO tasks (id, project_id, sprint_id, title, description, status, status_id, priority, estimated_hours, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, 'todo', ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&task_id)
+    .bind(&recurring.project_id)
+    .bind(&sprint_id)
+    .bind(&recurring.title)
+    .bind(&recurring.description)
+    .bind(&status_id)
+    .bind(&recurring.priority)
+    .bind(recurring.estimated_hours)
+    .bind(&now)
+    .bind(&now)
+    .execute(&mut *tx)
+    .await?;
+
+    record_task_change(&mut *tx, &task_id, &recurring.project_id, "status", None, Some("todo"), "recurring", &now)
+        .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE recurring_tasks SET
+            next_run_at = COALESCE(?, next_run_at),
+            enabled = ?,
+            last_run_at = ?,
+            last_task_id = ?,
+            updated_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(&next_run_at)
+    .bind(next_run_at.is_some())
+    .bind(&now)
+    .bind(&task_id)
+    .bind(&now)
+    .bind(&recurring.id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(task_id)
+}
+
+const SELECT_RECURRING_TASK: &str = r#"
+    SELECT id, project_id, rule, title, description, priority, estimated_hours, add_to_active_sprint,
+           enabled, next_run_at, last_run_at, last_task_id, created_at, updated_at
+    FROM recurring_tasks
+"#;
+
+type RecurringTaskRow = (
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+    String,
+    Option<f64>,
+    bool,
+    bool,
+    String,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+);
+
+fn recurring_task_response(row: RecurringTaskRow) -> RecurringTaskResponse {
+    RecurringTaskResponse {
+        id: row.0,
+        project_id: row.1,
+        rule: row.2,
+        title: row.3,
+        description: row.4,
+        priority: row.5,
+        estimated_hours: row.6,
+        add_to_active_sprint: row.7,
+        enabled: row.8,
+        next_run_at: row.9,
+        last_run_at: row.10,
+        last_task_id: row.11,
+        created_at: row.12,
+        updated_at: row.13,
+    }
+}
+
+async fn fetch_recurring_task(db: &SqlitePool, id: &str) -> Result<RecurringTaskResponse, AppError> {
+    sqlx::query_as::<_, RecurringTaskRow>(&format!("{} WHERE id = ?", SELECT_RECURRING_TASK))
+        .bind(id)
+        .fetch_optional(db)
+        .await?
+        .map(recurring_task_response)
+        .ok_or_else(|| AppError::database_not_found("Recurring task", id))
+}
+
+fn validate_priority(priority: &str) -> Result<(), AppError> {
+    if !["low", "medium", "high"].contains(&priority) {
+        return Err(AppError::invalid_input("Invalid task priority"));
+    }
+    Ok(())
+}
+
+/// The first due time of a rule after `now`, as stored in `next_run_at`
+fn first_run(rule: &str, now: DateTime<Utc>) -> Result<String, AppError> {
+    parse_rule(rule)?
+        .following_run(now, now)
+        .map(|t| t.to_rfc3339())
+        .ok_or_else(|| AppError::invalid_input(format!("Recurrence rule never matches: {}", rule)))
+}
+
+/// A parsed recurrence rule
+#[derive(Debug, Clone, PartialEq)]
+enum RecurrenceRule {
+    Every(Duration),
+    Cron(CronSchedule),
+}
+
+impl RecurrenceRule {
+    /// The first due time after `now`, counting from the run scheduled at `scheduled`
+    ///
+    /// Intervals stay anchored to their schedule rather than drifting to
+    /// whenever the job happened to run.
+    fn following_run(&self, scheduled: DateTime<Utc>, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            RecurrenceRule::Every(interval) => {
+                let step = interval.num_seconds();
+                let behind = (now - scheduled).num_seconds().max(0);
+                Some(scheduled + Duration::seconds((behind / step + 1) * step))
+            }
+            RecurrenceRule::Cron(cron) => {
+                let mut after = now.with_timezone(&Local).naive_local();
+                loop {
+                    after = cron.next_after(after)?;
+                    // Times skipped by a daylight saving change don't exist
+                    if let Some(time) = Local.from_local_datetime(&after).earliest() {
+                        let time = time.with_timezone(&Utc);
+                        if time > now {
+                            return Some(time);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Parse `every <n> <unit>`, a cron shorthand, or a five-field cron expression
+fn parse_rule(rule: &str) -> Result<RecurrenceRule, AppError> {
+    let invalid = || {
+        AppError::invalid_input(format!(
+            "Invalid recurrence rule '{}': use 'every <n> <minutes|hours|days|weeks>' or a cron expression",
+            rule
+        ))
+    };
+
+    let rule = rule.trim().to_lowercase();
+    let expression = match rule.as_str() {
+        "@hourly" => "0 * * * *",
+        "@daily" => "0 0 * * *",
+        "@weekly" => "0 0 * * 0",
+        "@monthly" => "0 0 1 * *",
+        other => other,
+    };
+
+    let words: Vec<&str> = expression.split_whitespace().collect();
+    if words.first() == Some(&"every") {
+        let (count, unit) = match words.as_slice() {
+            [_, unit] => (1, *unit),
+            [_, count, unit] => (count.parse::<i64>().map_err(|_| invalid())?, *unit),
+            _ => return Err(invalid()),
+        };
+        if !(1..=10_000).contains(&count) {
+            return Err(invalid());
+        }
+        let interval = match unit.trim_end_matches('s') {
+            "minute" => Duration::minutes(count),
+            "hour" => Duration::hours(count),
+            "day" => Duration::days(count),
+            "week" => Duration::weeks(count),
+            _ => return Err(invalid()),
+        };
+        return Ok(RecurrenceRule::Every(interval));
+    }
+
+    CronSchedule::parse(&words).map(RecurrenceRule::Cron).ok_or_else(invalid)
+}
+
+/// A five-field cron expression: minute, hour, day of month, month, day of week
+///
+/// Each field is a bit set of the values it matches.
+#[derive(Debug, Clone, PartialEq)]
+struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Day of month was `*`
+    any_day: bool,
+    /// Day of week was `*`
+    any_weekday: bool,
+}
+
+impl CronSchedule {
+    fn parse(fields: &[&str]) -> Option<Self> {
+        let [minute, hour, day, month, weekday] = fields else {
+            return None;
+        };
+        let mut weekdays = parse_cron_field(weekday, 0, 7)?;
+        // Both 0 and 7 are Sunday
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+
+        Some(Self {
+            minutes: parse_cron_field(minute, 0, 59)?,
+            hours: parse_cron_field(hour, 0, 23)?,
+            days: parse_cron_field(day, 1, 31)?,
+            months: parse_cron_field(month, 1, 12)?,
+            weekdays,
+            any_day: *day == "*",
+            any_weekday: *weekday == "*",
+        })
+    }
+
+    /// The first matching minute after `after`
+    fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let matches = |bits: u64, value: u32| bits & (1 << value) != 0;
+        let mut time = after.date().and_hms_opt(after.hour(), after.minute(), 0)? + Duration::minutes(1);
+        let limit = time + Duration::days(CRON_SEARCH_DAYS);
+
+        while time < limit {
+            let date = time.date();
+            if !matches(self.months, date.month()) {
+                let (year, month) = if date.month() == 12 {
+                    (date.year() + 1, 1)
+                } else {
+                    (date.year(), date.month() + 1)
+                };
+                time = chrono::NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.day_matches(date) {
+                time = date.succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !matches(self.hours, time.hour()) {
+                time = date.and_hms_opt(time.hour(), 0, 0)? + Duration::hours(1);
+            } else if !matches(self.minutes, time.minute()) {
+                time += Duration::minutes(1);
+            } else {
+                return Some(time);
+            }
+        }
+        None
+    }
+
+    /// Cron matches either day field when both are restricted
+    fn day_matches(&self, date: chrono::NaiveDate) -> bool {
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => weekday,
+            (false, true) => day,
+            (false, false) => day || weekday,
+        }
+    }
+}
+
+/// Parse one cron field of `*`, values, `a-b` ranges, and `/n` steps, comma separated
+fn parse_cron_field(field: &str, min: u32, max: u32) -> Option<u64> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|step| *step > 0)?),
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (start.parse().ok()?, end.parse().ok()?)
+        } else {
+            let start = range.parse().ok()?;
+            // `5/15` means from 5 to the end in steps of 15
+            (start, if step > 1 { max } else { start })
+        };
+        if start < min || end > max || start > end {
+            return None;
+        }
+        bits |= (start..=end).step_by(step as usize).fold(0u64, |bits, value| bits | 1 << value);
+    }
+    Some(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn cron(expression: &str) -> CronSchedule {
+        match parse_rule(expression).unwrap() {
+            RecurrenceRule::Cron(cron) => cron,
+            other => panic!("expected a cron rule, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_interval_rule() {
+        assert_eq!(parse_rule("every 2 weeks").unwrap(), RecurrenceRule::Every(Duration::weeks(2)));
+        assert_eq!(parse_rule("Every day").unwrap(), RecurrenceRule::Every(Duration::days(1)));
+        assert_eq!(parse_rule("every 30 minutes").unwrap(), RecurrenceRule::Every(Duration::minutes(30)));
+        assert!(parse_rule("every 0 days").is_err());
+        assert!(parse_rule("every 3 fortnights").is_err());
+        assert!(parse_rule("sometimes").is_err());
+    }
+
+    #[test]
+    fn test_parse_cron_field() {
+        assert_eq!(parse_cron_field("*", 0, 3), Some(0b1111));
+        assert_eq!(parse_cron_field("1,3", 0, 5), Some(0b1010));
+        assert_eq!(parse_cron_field("1-3", 0, 5), Some(0b1110));
+        assert_eq!(parse_cron_field("*/2", 0, 5), Some(0b10101));
+        assert_eq!(parse_cron_field("1/2", 0, 5), Some(0b101010));
+        assert_eq!(parse_cron_field("6", 0, 5), None);
+        assert_eq!(parse_cron_field("3-1", 0, 5), None);
+        assert_eq!(parse_cron_field("*/0", 0, 5), None);
+        assert!(parse_rule("0 9 * *").is_err());
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        // Mondays at 09:00; 2024-01-01 was a Monday
+        let weekly = cron("0 9 * * 1");
+        assert_eq!(weekly.next_after(at("2024-01-01 08:59")), Some(at("2024-01-01 09:00")));
+        assert_eq!(weekly.next_after(at("2024-01-01 09:00")), Some(at("2024-01-08 09:00")));
+
+        // Sunday written as 7
+        assert_eq!(cron("30 8 * * 7").next_after(at("2024-01-01 00:00")), Some(at("2024-01-07 08:30")));
+
+        let monthly = cron("@monthly");
+        assert_eq!(monthly.next_after(at("2024-01-15 12:00")), Some(at("2024-02-01 00:00")));
+        assert_eq!(monthly.next_after(at("2024-12-31 23:59")), Some(at("2025-01-01 00:00")));
+
+        assert_eq!(cron("*/15 * * * *").next_after(at("2024-01-01 10:07")), Some(at("2024-01-01 10:15")));
+
+        // Either day field matches when both are set: the 15th, or a Friday
+        let either = cron("0 0 15 * 5");
+        assert_eq!(either.next_after(at("2024-01-01 00:00")), Some(at("2024-01-05 00:00")));
+        assert_eq!(either.next_after(at("2024-01-13 00:00")), Some(at("2024-01-15 00:00")));
+
+        assert_eq!(cron("0 0 30 2 *").next_after(at("2024-01-01 00:00")), None);
+    }
+
+    #[test]
+    fn test_interval_following_run() {
+        let rule = parse_rule("every 1 day").unwrap();
+        let scheduled = Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap();
+
+        // On time: the next run is one interval later
+        assert_eq!(rule.following_run(scheduled, scheduled), Some(scheduled + Duration::days(1)));
+
+        // Closed for a few days: skips ahead, keeping the time of day
+        let now = Utc.with_ymd_and_hms(2024, 1, 4, 12, 0, 0).unwrap();
+        assert_eq!(
+            rule.following_run(scheduled, now),
+            Some(Utc.with_ymd_and_hms(2024, 1, 5, 9, 0, 0).unwrap())
+        );
+    }
+}
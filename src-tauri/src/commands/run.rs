@@ -0,0 +1,244 @@
+//! Agent Run Commands
+//!
+//! A run is one attempt at a task: a session started for the task, and the
+//! messages, tool calls, file changes, and cost recorded while the run was
+//! open, plus how it turned out. Numbered attempts make it easy to compare
+//! one approach to a task with another.
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::State;
+
+use super::session::{create_session, SessionCreateRequest, SessionResponse};
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// Outcomes a run can finish with
+const RUN_OUTCOMES: &[&str] = &["succeeded", "failed", "abandoned"];
+
+/// An attempt at a task
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunResponse {
+    pub id: String,
+    pub task_id: String,
+    pub session_id: String,
+    /// 1 for the first attempt at the task, 2 for the second, ...
+    pub attempt: i64,
+    /// `in_progress`, `succeeded`, `failed`, or `abandoned`
+    pub outcome: String,
+    pub notes: Option<String>,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub message_count: i64,
+    pub tool_call_count: i64,
+    pub files_changed: i64,
+    pub cost_usd: f64,
+}
+
+/// A run and the session it runs in
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskSessionResponse {
+    pub session: SessionResponse,
+    pub run: RunResponse,
+}
+
+/// Start a new attempt at a task in a fresh session linked to it
+///
+/// The working directory defaults to the task's project root.
+#[tauri::command]
+pub async fn session_create_for_task(
+    state: State<'_, AppState>,
+    task_id: String,
+    working_directory: Option<String>,
+    title: Option<String>,
+) -> Result<TaskSessionResponse, AppError> {
+    state.command_metrics.measure("session_create_for_task", async {
+        let (project_id, task_title, root_path) = sqlx::query_as::<_, (String, String, String)>(
+            r#"
+            SELECT t.project_id, t.title, p.root_path
+            FROM tasks t
+            JOIN projects p ON p.id = t.project_id
+            WHERE t.id = ? AND t.deleted_at IS NULL
+            "#,
+        )
+        .bind(&task_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Task", &task_id))?;
+
+        let attempt: i64 = sqlx::query_scalar("SELECT COALESCE(MAX(attempt), 0) + 1 FROM runs WHERE task_id = ?")
+            .bind(&task_id)
+            .fetch_one(&state.db)
+            .await?;
+
+        let session = create_session(
+            &state.db,
+            SessionCreateRequest {
+                working_directory: working_directory.unwrap_or(root_path),
+                project_id: Some(project_id),
+                title: Some(title.unwrap_or_else(|| format!("{} (attempt {})", task_title, attempt))),
+                model: None,
+                permission_mode: None,
+                extra_args: None,
+                system_prompt: None,
+                handoff_id: None,
+                task_id: Some(task_id.clone()),
+            },
+        )
+        .await?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        sqlx::query(
+            r#"
+            INSERT INTO runs (id, task_id, session_id, attempt, outcome, started_at)
+            VALUES (?, ?, ?, ?, 'in_progress', ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&task_id)
+        .bind(&session.id)
+        .bind(attempt)
+        .bind(&session.created_at)
+        .execute(&state.db)
+        .await?;
+
+        let run = load_run(&state.db, &id).await?;
+        Ok(TaskSessionResponse { session, run })
+    })
+    .await
+}
+
+/// List a task's runs, first attempt first
+#[tauri::command]
+pub async fn run_list(
+    state: State<'_, AppState>,
+    task_id: String,
+) -> Result<Vec<RunResponse>, AppError> {
+    state.command_metrics.measure("run_list", async {
+        let ids: Vec<String> = sqlx::query_scalar("SELECT id FROM runs WHERE task_id = ? ORDER BY attempt ASC")
+            .bind(&task_id)
+            .fetch_all(&state.db)
+            .await?;
+
+        let mut runs = Vec::with_capacity(ids.len());
+        for id in ids {
+            runs.push(load_run(&state.db, &id).await?);
+        }
+        Ok(runs)
+    })
+    .await
+}
+
+/// Get a run with its totals
+#[tauri::command]
+pub async fn run_get(
+    state: State<'_, AppState>,
+    run_id: String,
+) -> Result<RunResponse, AppError> {
+    state.command_metrics.measure("run_get", async {
+        load_run(&state.db, &run_id).await
+    })
+    .await
+}
+
+/// Record how a run turned out and stop counting activity towards it
+///
+/// `outcome` is `succeeded`, `failed`, or `abandoned`.
+#[tauri::command]
+pub async fn run_finish(
+    state: State<'_, AppState>,
+    run_id: String,
+    outcome: String,
+    notes: Option<String>,
+) -> Result<RunResponse, AppError> {
+    state.command_metrics.measure("run_finish", async {
+        if !RUN_OUTCOMES.contains(&outcome.as_str()) {
+            return Err(AppError::invalid_input(format!(
+                "Outcome must be one of {}",
+                RUN_OUTCOMES.join(", ")
+            )));
+        }
+        let notes = notes.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+
+        let result = sqlx::query(
+            "UPDATE runs SET outcome = ?, notes = ?, ended_at = COALESCE(ended_at, ?) WHERE id = ?",
+        )
+        .bind(&outcome)
+        .bind(&notes)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(&run_id)
+        .execute(&state.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Run", &run_id));
+        }
+
+        load_run(&state.db, &run_id).await
+    })
+    .await
+}
+
+/// Load a run and total the session activity within its time window
+async fn load_run(db: &SqlitePool, run_id: &str) -> Result<RunResponse, AppError> {
+    let (id, task_id, session_id, attempt, outcome, notes, started_at, ended_at) =
+        sqlx::query_as::<_, (String, String, String, i64, String, Option<String>, String, Option<String>)>(
+            "SELECT id, task_id, session_id, attempt, outcome, notes, started_at, ended_at FROM runs WHERE id = ?",
+        )
+        .bind(run_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Run", run_id))?;
+
+    // An open run counts everything up to now
+    let end = ended_at.clone().unwrap_or_else(|| chrono::Utc::now().to_rfc3339());
+
+    let (message_count, tool_call_count): (i64, i64) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*),
+               COALESCE(SUM(CASE WHEN json_valid(tool_usage) THEN json_array_length(tool_usage) ELSE 0 END), 0)
+        FROM messages
+        WHERE session_id = ? AND created_at >= ? AND created_at <= ?
+        "#,
+    )
+    .bind(&session_id)
+    .bind(&started_at)
+    .bind(&end)
+    .fetch_one(db)
+    .await?;
+
+    let files_changed: i64 = sqlx::query_scalar(
+        "SELECT COUNT(DISTINCT path) FROM activity_log WHERE session_id = ? AND timestamp >= ? AND timestamp <= ?",
+    )
+    .bind(&session_id)
+    .bind(&started_at)
+    .bind(&end)
+    .fetch_one(db)
+    .await?;
+
+    let cost_usd: f64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(cost_usd), 0.0) FROM session_costs WHERE session_id = ? AND recorded_at >= ? AND recorded_at <= ?",
+    )
+    .bind(&session_id)
+    .bind(&started_at)
+    .bind(&end)
+    .fetch_one(db)
+    .await?;
+
+    Ok(RunResponse {
+        id,
+        task_id,
+        session_id,
+        attempt,
+        outcome,
+        notes,
+        started_at,
+        ended_at,
+        message_count,
+        tool_call_count,
+        files_changed,
+        cost_usd,
+    })
+}
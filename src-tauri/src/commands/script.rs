@@ -0,0 +1,605 @@
+//! Project Script Commands
+//!
+//! Named commands a project defines, such as `build`, `test`, or `lint`,
+//! that can be launched from the UI. Output is streamed as `script_output`
+//! events and each run's exit code and output are kept in `script_runs`.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+use crate::error::{AppError, ErrorCode};
+use crate::events::{emit_event, event_names, ScriptOutputPayload};
+use crate::hooks::shell_command;
+use crate::state::script_runs::RunningScript;
+use crate::state::AppState;
+use crate::system;
+
+use super::project::project_root;
+use super::verification::{output_tail, MAX_OUTPUT_BYTES};
+
+/// Default number of runs listed
+const DEFAULT_RUN_LIST_LIMIT: i64 = 20;
+
+/// A command saved for a project
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectScriptResponse {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub command: String,
+    /// Directory relative to the project root; None is the root
+    pub cwd: Option<String>,
+    pub env: HashMap<String, String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// One run of a project script
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptRunResponse {
+    pub id: String,
+    pub script_id: String,
+    pub project_id: String,
+    pub command: String,
+    pub cwd: String,
+    /// `running`, `succeeded`, `failed`, or `cancelled`
+    pub status: String,
+    pub exit_code: Option<i32>,
+    pub output: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+/// Save a command for a project
+#[tauri::command]
+pub async fn script_create(
+    state: State<'_, AppState>,
+    project_id: String,
+    name: String,
+    command: String,
+    cwd: Option<String>,
+    env: Option<HashMap<String, String>>,
+) -> Result<ProjectScriptResponse, AppError> {
+    state.command_metrics.measure("script_create", async {
+        let (name, command, cwd, env) = validate_script(&name, &command, cwd, env)?;
+
+        let project_exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ? AND deleted_at IS NULL)",
+        )
+        .bind(&project_id)
+        .fetch_one(&state.db)
+        .await?;
+        if !project_exists {
+            return Err(AppError::database_not_found("Project", &project_id));
+        }
+        ensure_name_free(&state.db, &project_id, &name, None).await?;
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let script = ProjectScriptResponse {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_id,
+            name,
+            command,
+            cwd,
+            env,
+            created_at: now.clone(),
+            updated_at: now,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO project_scripts (id, project_id, name, command, cwd, env, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&script.id)
+        .bind(&script.project_id)
+        .bind(&script.name)
+        .bind(&script.command)
+        .bind(&script.cwd)
+        .bind(serde_json::to_string(&script.env)?)
+        .bind(&script.created_at)
+        .bind(&script.updated_at)
+        .execute(&state.db)
+        .await?;
+
+        Ok(script)
+    })
+    .await
+}
+
+/// Replace a saved command's name, command line, directory, and environment
+#[tauri::command]
+pub async fn script_update(
+    state: State<'_, AppState>,
+    script_id: String,
+    name: String,
+    command: String,
+    cwd: Option<String>,
+    env: Option<HashMap<String, String>>,
+) -> Result<ProjectScriptResponse, AppError> {
+    state.command_metrics.measure("script_update", async {
+        let (name, command, cwd, env) = validate_script(&name, &command, cwd, env)?;
+        let existing = fetch_script(&state.db, &script_id).await?;
+        ensure_name_free(&state.db, &existing.project_id, &name, Some(&script_id)).await?;
+
+        let now = chrono::Utc::now().to_rfc3339();
+        sqlx::query(
+            "UPDATE project_scripts SET name = ?, command = ?, cwd = ?, env = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(&name)
+        .bind(&command)
+        .bind(&cwd)
+        .bind(serde_json::to_string(&env)?)
+        .bind(&now)
+        .bind(&script_id)
+        .execute(&state.db)
+        .await?;
+
+        Ok(ProjectScriptResponse {
+            name,
+            command,
+            cwd,
+            env,
+            updated_at: now,
+            ..existing
+        })
+    })
+    .await
+}
+
+/// Delete a saved command and its run history
+#[tauri::command]
+pub async fn script_delete(
+    state: State<'_, AppState>,
+    script_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("script_delete", async {
+        let result = sqlx::query("DELETE FROM project_scripts WHERE id = ?")
+            .bind(&script_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Script", &script_id));
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// List a project's saved commands by name
+#[tauri::command]
+pub async fn script_list(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<Vec<ProjectScriptResponse>, AppError> {
+    state.command_metrics.measure("script_list", async {
+        let rows = sqlx::query_as::<_, ScriptRow>(
+            r#"
+            SELECT id, project_id, name, command, cwd, env, created_at, updated_at
+            FROM project_scripts
+            WHERE project_id = ?
+            ORDER BY name
+            "#,
+        )
+        .bind(&project_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(rows.into_iter().map(script_response).collect())
+    })
+    .await
+}
+
+/// Start a saved command
+///
+/// Returns as soon as the process has started; output and the final status
+/// arrive as `script_output` events.
+#[tauri::command]
+pub async fn script_run(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    script_id: String,
+) -> Result<ScriptRunResponse, AppError> {
+    state.command_metrics.measure("script_run", async {
+        let script = fetch_script(&state.db, &script_id).await?;
+        let root = project_root(&state.db, &script.project_id).await?;
+        let cwd = match &script.cwd {
+            Some(cwd) => root.join(cwd),
+            None => root,
+        };
+        if !cwd.is_dir() {
+            return Err(AppError::new(
+                ErrorCode::DirectoryNotFound,
+                format!("Directory not found: {}", cwd.display()),
+            ));
+        }
+
+        let mut cmd = shell_command(&script.command);
+        system::env::apply(&mut cmd);
+        cmd.envs(&script.env)
+            .current_dir(&cwd)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        let child = cmd.spawn().map_err(|e| {
+            AppError::with_details(ErrorCode::Unknown, "Failed to start script", e.to_string())
+        })?;
+        let started_at = chrono::Utc::now();
+
+        let run = ScriptRunResponse {
+            id: uuid::Uuid::new_v4().to_string(),
+            script_id: script.id,
+            project_id: script.project_id,
+            command: script.command,
+            cwd: cwd.to_string_lossy().to_string(),
+            status: "running".to_string(),
+            exit_code: None,
+            output: String::new(),
+            started_at: started_at.to_rfc3339(),
+            finished_at: None,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO script_runs (id, script_id, project_id, command, cwd, status, started_at)
+            VALUES (?, ?, ?, ?, ?, 'running', ?)
+            "#,
+        )
+        .bind(&run.id)
+        .bind(&run.script_id)
+        .bind(&run.project_id)
+        .bind(&run.command)
+        .bind(&run.cwd)
+        .bind(&run.started_at)
+        .execute(&state.db)
+        .await?;
+
+        let cancelled = state.script_runs.start(RunningScript {
+            run_id: run.id.clone(),
+            project_id: run.project_id.clone(),
+            pid: child.id(),
+            cwd: run.cwd.clone(),
+            started_at,
+        });
+        let app = app.clone();
+        let run_id = run.id.clone();
+        let script_id = run.script_id.clone();
+        tokio::spawn(async move {
+            if let Err(e) = supervise_run(&app, &run_id, &script_id, child, cancelled).await {
+                log::warn!("Failed to record script run {}: {}", run_id, e);
+            }
+        });
+
+        Ok(run)
+    })
+    .await
+}
+
+/// Stop a running script
+#[tauri::command]
+pub async fn script_cancel(
+    state: State<'_, AppState>,
+    run_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("script_cancel", async {
+        if !state.script_runs.cancel(&run_id) {
+            return Err(AppError::invalid_input("Script run is not running"));
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// List a script's runs, newest first
+#[tauri::command]
+pub async fn script_run_list(
+    state: State<'_, AppState>,
+    script_id: String,
+    limit: Option<i64>,
+) -> Result<Vec<ScriptRunResponse>, AppError> {
+    state.command_metrics.measure("script_run_list", async {
+        let rows = sqlx::query_as::<_, ScriptRunRow>(
+            r#"
+            SELECT id, script_id, project_id, command, cwd, status, exit_code, output, started_at, finished_at
+            FROM script_runs
+            WHERE script_id = ?
+            ORDER BY started_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(&script_id)
+        .bind(limit.unwrap_or(DEFAULT_RUN_LIST_LIMIT))
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(rows.into_iter().map(run_response).collect())
+    })
+    .await
+}
+
+/// Get a script run with its output
+#[tauri::command]
+pub async fn script_run_get(
+    state: State<'_, AppState>,
+    run_id: String,
+) -> Result<ScriptRunResponse, AppError> {
+    state.command_metrics.measure("script_run_get", async {
+        let row = sqlx::query_as::<_, ScriptRunRow>(
+            r#"
+            SELECT id, script_id, project_id, command, cwd, status, exit_code, output, started_at, finished_at
+            FROM script_runs
+            WHERE id = ?
+            "#,
+        )
+        .bind(&run_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Script run", &run_id))?;
+
+        Ok(run_response(row))
+    })
+    .await
+}
+
+/// Stream a run's output until it exits or is cancelled, then record it
+async fn supervise_run(
+    app: &AppHandle,
+    run_id: &str,
+    script_id: &str,
+    mut child: tokio::process::Child,
+    cancelled: tokio::sync::oneshot::Receiver<()>,
+) -> Result<(), AppError> {
+    let output = Arc::new(Mutex::new(String::new()));
+    let emit = |stream: Option<&str>, line: Option<String>, status: &str, exit_code: Option<i32>| {
+        let _ = emit_event(
+            app,
+            event_names::SCRIPT_OUTPUT,
+            ScriptOutputPayload {
+                run_id: run_id.to_string(),
+                script_id: script_id.to_string(),
+                stream: stream.map(str::to_string),
+                line,
+                status: status.to_string(),
+                exit_code,
+            },
+        );
+    };
+    let capture = |stream: &'static str| {
+        let output = output.clone();
+        move |line: String| {
+            append_output(&mut output.lock().unwrap_or_else(|e| e.into_inner()), &line);
+            emit(Some(stream), Some(line), "running", None);
+        }
+    };
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let run = async {
+        tokio::join!(
+            stream_lines(stdout, capture("stdout")),
+            stream_lines(stderr, capture("stderr")),
+        );
+        child.wait().await
+    };
+
+    // None when the run was cancelled
+    let result = tokio::select! {
+        result = run => Some(result),
+        _ = cancelled => None,
+    };
+    let (status, exit_code) = match result {
+        Some(Ok(exit)) if exit.success() => ("succeeded", exit.code()),
+        Some(Ok(exit)) => ("failed", exit.code()),
+        Some(Err(e)) => {
+            append_output(&mut output.lock().unwrap_or_else(|e| e.into_inner()), &format!("Script failed: {}", e));
+            ("failed", None)
+        }
+        None => {
+            let _ = child.kill().await;
+            ("cancelled", None)
+        }
+    };
+
+    let state = app.state::<AppState>();
+    state.script_runs.finish(run_id);
+
+    let output = output.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    sqlx::query(
+        "UPDATE script_runs SET status = ?, exit_code = ?, output = ?, finished_at = ? WHERE id = ?",
+    )
+    .bind(status)
+    .bind(exit_code)
+    .bind(output_tail(&output, MAX_OUTPUT_BYTES))
+    .bind(chrono::Utc::now().to_rfc3339())
+    .bind(run_id)
+    .execute(&state.db)
+    .await?;
+
+    emit(None, None, status, exit_code);
+
+    Ok(())
+}
+
+/// Add a line to captured output, dropping the oldest output past the limit
+fn append_output(output: &mut String, line: &str) {
+    output.push_str(line);
+    output.push('\n');
+    // Trimmed in batches so long runs don't shift the buffer on every line
+    if output.len() > MAX_OUTPUT_BYTES * 2 {
+        let keep = output_tail(output, MAX_OUTPUT_BYTES).len();
+        output.drain(..output.len() - keep);
+    }
+}
+
+/// Forward each line of a pipe
+async fn stream_lines(pipe: Option<impl AsyncRead + Unpin>, mut on_line: impl FnMut(String)) {
+    let Some(pipe) = pipe else {
+        return;
+    };
+    let mut lines = BufReader::new(pipe).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        on_line(line);
+    }
+}
+
+/// Trim and check a script's fields
+fn validate_script(
+    name: &str,
+    command: &str,
+    cwd: Option<String>,
+    env: Option<HashMap<String, String>>,
+) -> Result<(String, String, Option<String>, HashMap<String, String>), AppError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::invalid_input("Script name cannot be empty"));
+    }
+    let command = command.trim().to_string();
+    if command.is_empty() {
+        return Err(AppError::invalid_input("Script command cannot be empty"));
+    }
+
+    let cwd = cwd.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+    if let Some(cwd) = &cwd {
+        if !is_within_root(Path::new(cwd)) {
+            return Err(AppError::invalid_input("Script directory must be inside the project"));
+        }
+    }
+
+    let env = env.unwrap_or_default();
+    if env.keys().any(|key| key.trim().is_empty() || key.contains('=')) {
+        return Err(AppError::invalid_input("Environment variable names cannot be empty or contain '='"));
+    }
+
+    Ok((name, command, cwd, env))
+}
+
+/// Whether a relative path stays inside the directory it is joined to
+fn is_within_root(path: &Path) -> bool {
+    let mut depth = 0usize;
+    for component in path.components() {
+        match component {
+            std::path::Component::Normal(_) => depth += 1,
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => match depth.checked_sub(1) {
+                Some(d) => depth = d,
+                None => return false,
+            },
+            std::path::Component::RootDir | std::path::Component::Prefix(_) => return false,
+        }
+    }
+    true
+}
+
+async fn ensure_name_free(
+    db: &sqlx::SqlitePool,
+    project_id: &str,
+    name: &str,
+    except_id: Option<&str>,
+) -> Result<(), AppError> {
+    let taken: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM project_scripts WHERE project_id = ? AND name = ? AND id != COALESCE(?, ''))",
+    )
+    .bind(project_id)
+    .bind(name)
+    .bind(except_id)
+    .fetch_one(db)
+    .await?;
+    if taken {
+        return Err(AppError::invalid_input(format!(
+            "The project already has a script named '{}'",
+            name
+        )));
+    }
+    Ok(())
+}
+
+async fn fetch_script(db: &sqlx::SqlitePool, script_id: &str) -> Result<ProjectScriptResponse, AppError> {
+    let row = sqlx::query_as::<_, ScriptRow>(
+        "SELECT id, project_id, name, command, cwd, env, created_at, updated_at FROM project_scripts WHERE id = ?",
+    )
+    .bind(script_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| AppError::database_not_found("Script", script_id))?;
+
+    Ok(script_response(row))
+}
+
+type ScriptRow = (String, String, String, String, Option<String>, String, String, String);
+
+fn script_response(row: ScriptRow) -> ProjectScriptResponse {
+    ProjectScriptResponse {
+        id: row.0,
+        project_id: row.1,
+        name: row.2,
+        command: row.3,
+        cwd: row.4,
+        env: serde_json::from_str(&row.5).unwrap_or_default(),
+        created_at: row.6,
+        updated_at: row.7,
+    }
+}
+
+type ScriptRunRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    Option<i32>,
+    String,
+    String,
+    Option<String>,
+);
+
+fn run_response(row: ScriptRunRow) -> ScriptRunResponse {
+    ScriptRunResponse {
+        id: row.0,
+        script_id: row.1,
+        project_id: row.2,
+        command: row.3,
+        cwd: row.4,
+        status: row.5,
+        exit_code: row.6,
+        output: row.7,
+        started_at: row.8,
+        finished_at: row.9,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_within_root() {
+        assert!(is_within_root(Path::new("packages/web")));
+        assert!(is_within_root(Path::new("./packages/../apps")));
+        assert!(!is_within_root(Path::new("../other")));
+        assert!(!is_within_root(Path::new("packages/../../other")));
+        assert!(!is_within_root(Path::new("/usr/bin")));
+    }
+
+    #[test]
+    fn test_append_output_keeps_tail() {
+        let mut output = String::new();
+        let line = "x".repeat(1024);
+        for _ in 0..(MAX_OUTPUT_BYTES * 3 / 1024) {
+            append_output(&mut output, &line);
+        }
+        assert!(output.len() <= MAX_OUTPUT_BYTES * 2);
+        assert!(output.ends_with(&format!("{}\n", line)));
+    }
+}
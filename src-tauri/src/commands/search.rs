@@ -0,0 +1,367 @@
+//! Global Search Command
+//!
+//! Searches sessions, messages, tasks, milestones, and changed file paths
+//! with one query so a command palette needs a single round trip. Matches
+//! are case-insensitive substrings, ranked by where the query matches.
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// Default number of results returned
+const DEFAULT_SEARCH_LIMIT: i64 = 20;
+
+/// Characters of context kept on each side of a match in a snippet
+const SNIPPET_CONTEXT_CHARS: usize = 60;
+
+/// A search hit
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    /// `session`, `message`, `task`, `milestone`, or `file`
+    pub kind: String,
+    /// Entity ID, or the relative path for files
+    pub id: String,
+    pub title: String,
+    /// Text around the match when it isn't in the title
+    pub snippet: Option<String>,
+    /// Session holding the entity; for files, the last session that changed it
+    pub session_id: Option<String>,
+    pub project_id: Option<String>,
+    /// Higher is better
+    pub score: f64,
+    pub updated_at: String,
+}
+
+/// Search everything for a query, best matches first
+///
+/// Pass `project_id` to search only a project's sessions, tasks, and
+/// milestones. Archived sessions, deleted tasks, and deleted milestones are
+/// left out.
+#[tauri::command]
+pub async fn search_global(
+    state: State<'_, AppState>,
+    query: String,
+    project_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<SearchResult>, AppError> {
+    state.command_metrics.measure("search_global", async {
+        let query = query.trim().to_string();
+        if query.is_empty() {
+            return Err(AppError::invalid_input("Search query cannot be empty"));
+        }
+        let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, 200);
+
+        let mut results = Vec::new();
+        search_sessions(&state.db, &query, project_id.as_deref(), limit, &mut results).await?;
+        search_messages(&state.db, &query, project_id.as_deref(), limit, &mut results).await?;
+        search_tasks(&state.db, &query, project_id.as_deref(), limit, &mut results).await?;
+        search_milestones(&state.db, &query, project_id.as_deref(), limit, &mut results).await?;
+        search_files(&state.db, &query, project_id.as_deref(), limit, &mut results).await?;
+
+        results.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| b.updated_at.cmp(&a.updated_at))
+        });
+        results.truncate(limit as usize);
+
+        Ok(results)
+    })
+    .await
+}
+
+async fn search_sessions(
+    db: &SqlitePool,
+    query: &str,
+    project_id: Option<&str>,
+    limit: i64,
+    results: &mut Vec<SearchResult>,
+) -> Result<(), AppError> {
+    let rows = sqlx::query_as::<_, (String, String, Option<String>, String)>(
+        r#"
+        SELECT id, title, project_id, updated_at
+        FROM sessions
+        WHERE instr(lower(title), lower(?1)) > 0
+          AND archived_at IS NULL
+          AND (?2 IS NULL OR project_id = ?2)
+        ORDER BY updated_at DESC
+        LIMIT ?3
+        "#,
+    )
+    .bind(query)
+    .bind(project_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+
+    results.extend(rows.into_iter().map(|(id, title, project_id, updated_at)| SearchResult {
+        kind: "session".to_string(),
+        score: match_score(&title, query, true),
+        session_id: Some(id.clone()),
+        id,
+        title,
+        snippet: None,
+        project_id,
+        updated_at,
+    }));
+    Ok(())
+}
+
+async fn search_messages(
+    db: &SqlitePool,
+    query: &str,
+    project_id: Option<&str>,
+    limit: i64,
+    results: &mut Vec<SearchResult>,
+) -> Result<(), AppError> {
+    let rows = sqlx::query_as::<_, (String, String, String, String, Option<String>, String)>(
+        r#"
+        SELECT m.id, m.content, m.session_id, s.title, s.project_id, m.created_at
+        FROM messages m
+        JOIN sessions s ON s.id = m.session_id
+        WHERE instr(lower(m.content), lower(?1)) > 0
+          AND s.archived_at IS NULL
+          AND (?2 IS NULL OR s.project_id = ?2)
+        ORDER BY m.created_at DESC
+        LIMIT ?3
+        "#,
+    )
+    .bind(query)
+    .bind(project_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+
+    results.extend(rows.into_iter().map(
+        |(id, content, session_id, session_title, project_id, created_at)| SearchResult {
+            kind: "message".to_string(),
+            id,
+            title: session_title,
+            score: match_score(&content, query, false),
+            snippet: snippet(&content, query),
+            session_id: Some(session_id),
+            project_id,
+            updated_at: created_at,
+        },
+    ));
+    Ok(())
+}
+
+async fn search_tasks(
+    db: &SqlitePool,
+    query: &str,
+    project_id: Option<&str>,
+    limit: i64,
+    results: &mut Vec<SearchResult>,
+) -> Result<(), AppError> {
+    let rows = sqlx::query_as::<_, (String, String, Option<String>, String, String)>(
+        r#"
+        SELECT id, title, description, project_id, updated_at
+        FROM tasks
+        WHERE (instr(lower(title), lower(?1)) > 0 OR instr(lower(COALESCE(description, '')), lower(?1)) > 0)
+          AND deleted_at IS NULL
+          AND (?2 IS NULL OR project_id = ?2)
+        ORDER BY updated_at DESC
+        LIMIT ?3
+        "#,
+    )
+    .bind(query)
+    .bind(project_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+
+    results.extend(rows.into_iter().map(|(id, title, description, project_id, updated_at)| {
+        titled_result("task", id, title, description, query, Some(project_id), updated_at)
+    }));
+    Ok(())
+}
+
+async fn search_milestones(
+    db: &SqlitePool,
+    query: &str,
+    project_id: Option<&str>,
+    limit: i64,
+    results: &mut Vec<SearchResult>,
+) -> Result<(), AppError> {
+    let rows = sqlx::query_as::<_, (String, String, Option<String>, String, String)>(
+        r#"
+        SELECT id, name, description, project_id, updated_at
+        FROM milestones
+        WHERE (instr(lower(name), lower(?1)) > 0 OR instr(lower(COALESCE(description, '')), lower(?1)) > 0)
+          AND deleted_at IS NULL
+          AND (?2 IS NULL OR project_id = ?2)
+        ORDER BY updated_at DESC
+        LIMIT ?3
+        "#,
+    )
+    .bind(query)
+    .bind(project_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+
+    results.extend(rows.into_iter().map(|(id, name, description, project_id, updated_at)| {
+        titled_result("milestone", id, name, description, query, Some(project_id), updated_at)
+    }));
+    Ok(())
+}
+
+async fn search_files(
+    db: &SqlitePool,
+    query: &str,
+    project_id: Option<&str>,
+    limit: i64,
+    results: &mut Vec<SearchResult>,
+) -> Result<(), AppError> {
+    // SQLite returns the bare columns from the row holding the MAX()
+    let rows = sqlx::query_as::<_, (String, String, Option<String>, String)>(
+        r#"
+        SELECT a.path, a.session_id, s.project_id, MAX(a.timestamp)
+        FROM activity_log a
+        JOIN sessions s ON s.id = a.session_id
+        WHERE instr(lower(a.path), lower(?1)) > 0
+          AND (?2 IS NULL OR s.project_id = ?2)
+        GROUP BY a.path
+        ORDER BY MAX(a.timestamp) DESC
+        LIMIT ?3
+        "#,
+    )
+    .bind(query)
+    .bind(project_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+
+    results.extend(rows.into_iter().map(|(path, session_id, project_id, timestamp)| {
+        // Rank by the file name so "main" prefers main.rs over domain/x.rs
+        let file_name = path.rsplit('/').next().unwrap_or(&path);
+        let score = match_score(file_name, query, true).max(match_score(&path, query, false));
+        SearchResult {
+            kind: "file".to_string(),
+            id: path.clone(),
+            title: path,
+            snippet: None,
+            session_id: Some(session_id),
+            project_id,
+            score,
+            updated_at: timestamp,
+        }
+    }));
+    Ok(())
+}
+
+/// Build a result for an entity with a title and optional description
+fn titled_result(
+    kind: &str,
+    id: String,
+    title: String,
+    description: Option<String>,
+    query: &str,
+    project_id: Option<String>,
+    updated_at: String,
+) -> SearchResult {
+    let title_score = match_score(&title, query, true);
+    let (score, snippet) = if title_score > 0.0 {
+        (title_score, None)
+    } else {
+        let description = description.unwrap_or_default();
+        (match_score(&description, query, false), snippet(&description, query))
+    };
+
+    SearchResult {
+        kind: kind.to_string(),
+        id,
+        title,
+        snippet,
+        session_id: None,
+        project_id,
+        score,
+        updated_at,
+    }
+}
+
+/// Score how well `text` matches a query
+///
+/// An exact match beats a prefix, which beats the start of a word, which
+/// beats a match elsewhere. Matches in titles outrank matches in body text.
+fn match_score(text: &str, query: &str, is_title: bool) -> f64 {
+    let text = text.to_lowercase();
+    let query = query.to_lowercase();
+    let Some(position) = text.find(&query) else {
+        return 0.0;
+    };
+
+    let base = if text == query {
+        100.0
+    } else if position == 0 {
+        80.0
+    } else if !text[..position].ends_with(|c: char| c.is_alphanumeric()) {
+        60.0
+    } else {
+        40.0
+    };
+
+    if is_title {
+        base
+    } else {
+        base / 2.0
+    }
+}
+
+/// Text around the first match, with ellipses where it was cut
+fn snippet(text: &str, query: &str) -> Option<String> {
+    let lower = text.to_lowercase();
+    let position = lower.find(&query.to_lowercase())?;
+    // Lowercasing can change byte lengths, so fall back to the start
+    let position = if text.is_char_boundary(position) { position } else { 0 };
+
+    let before: Vec<char> = text[..position].chars().rev().take(SNIPPET_CONTEXT_CHARS).collect();
+    let start = position - before.iter().map(|c| c.len_utf8()).sum::<usize>();
+    let after_len: usize = text[position..]
+        .chars()
+        .take(query.chars().count() + SNIPPET_CONTEXT_CHARS)
+        .map(|c| c.len_utf8())
+        .sum();
+    let end = position + after_len;
+
+    let mut snippet = text[start..end].split_whitespace().collect::<Vec<_>>().join(" ");
+    if start > 0 {
+        snippet.insert_str(0, "...");
+    }
+    if end < text.len() {
+        snippet.push_str("...");
+    }
+    Some(snippet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_score() {
+        assert_eq!(match_score("Login", "login", true), 100.0);
+        assert_eq!(match_score("Login page", "login", true), 80.0);
+        assert_eq!(match_score("Fix login", "login", true), 60.0);
+        assert_eq!(match_score("Relogin", "login", true), 40.0);
+        assert_eq!(match_score("Fix login", "login", false), 30.0);
+        assert_eq!(match_score("Signup", "login", true), 0.0);
+    }
+
+    #[test]
+    fn test_snippet() {
+        assert_eq!(snippet("short text", "text").as_deref(), Some("short text"));
+        assert_eq!(snippet("no match", "zzz"), None);
+
+        let text = format!("{}needle{}", "a".repeat(100), "b".repeat(100));
+        let snippet = snippet(&text, "NEEDLE").unwrap();
+        assert!(snippet.starts_with("...") && snippet.ends_with("..."));
+        assert!(snippet.contains("needle"));
+        assert_eq!(snippet.len(), 3 + SNIPPET_CONTEXT_CHARS * 2 + "needle".len() + 3);
+    }
+}
@@ -0,0 +1,50 @@
+//! Secret Commands
+//!
+//! Commands for the API tokens kept in the OS keychain. See
+//! [`crate::secrets`] for the names accepted.
+
+use tauri::State;
+
+use crate::error::AppError;
+use crate::secrets;
+use crate::state::AppState;
+
+/// Store a secret in the OS keychain, replacing any earlier value
+#[tauri::command]
+pub async fn secret_set(
+    state: State<'_, AppState>,
+    name: String,
+    value: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("secret_set", async {
+        secrets::validate_name(&name)?;
+        secrets::set(&name, value.trim()).await
+    })
+    .await
+}
+
+/// Read a secret from the OS keychain, or null if it isn't set
+#[tauri::command]
+pub async fn secret_get(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<Option<String>, AppError> {
+    state.command_metrics.measure("secret_get", async {
+        secrets::validate_name(&name)?;
+        secrets::get(&name).await
+    })
+    .await
+}
+
+/// Remove a secret from the OS keychain
+#[tauri::command]
+pub async fn secret_delete(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("secret_delete", async {
+        secrets::validate_name(&name)?;
+        secrets::delete(&name).await
+    })
+    .await
+}
@@ -3,23 +3,69 @@
 //! Commands for managing chat sessions and messages.
 
 use serde::{Deserialize, Serialize};
+use sqlx::Row;
 use std::path::Path;
 use tauri::{AppHandle, State};
 
+use crate::claude::logs::CliLogLine;
+use crate::claude::replay::ReplayChunk;
+use crate::claude::CliOptions;
+use crate::db::settings::{self, CLI_MAX_RESTARTS, MAX_CONCURRENT_SESSIONS, OUTPUT_REPLAY_MAX_CHUNKS};
 use crate::error::AppError;
+use crate::attachments::{self, Attachment};
+use crate::checkpoints;
+use crate::hooks::{self, HookContext, HookEvent};
+use crate::webhooks::{self, WebhookContext, WebhookEvent};
+use crate::mcp;
 use crate::state::AppState;
 
+use super::compact::latest_summary;
+use super::policy::session_policy;
+use super::session_task::link_session_task;
+use super::settings::{get_value, SettingKey};
+use super::worktree::remove_session_worktree;
+
+/// Title of a session until it is renamed
+pub(crate) const DEFAULT_SESSION_TITLE: &str = "New Session";
+
 /// Request to create a new session
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionCreateRequest {
     pub working_directory: String,
     pub project_id: Option<String>,
     pub title: Option<String>,
+    pub model: Option<String>,
+    pub permission_mode: Option<String>,
+    pub extra_args: Option<Vec<String>>,
+    /// Instructions appended to the CLI's system prompt
+    pub system_prompt: Option<String>,
+    /// Hand-off document to seed the new session with
+    pub handoff_id: Option<String>,
+    /// Task the session is started from; the two are linked automatically
+    pub task_id: Option<String>,
+}
+
+/// Request to update a session's CLI settings
+///
+/// Omitted fields are left unchanged; an empty model or permission mode
+/// resets it to the CLI default.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSettingsUpdateRequest {
+    pub model: Option<String>,
+    pub permission_mode: Option<String>,
+    pub extra_args: Option<Vec<String>>,
 }
 
+/// Permission modes accepted by the Claude CLI
+const PERMISSION_MODES: &[&str] = &["default", "acceptEdits", "bypassPermissions", "plan"];
+
+/// Number of recent messages replayed when resuming from the transcript
+pub(crate) const RESUME_MESSAGE_LIMIT: i64 = 20;
+
 /// Session data returned to frontend
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionResponse {
     pub id: String,
@@ -27,6 +73,17 @@ pub struct SessionResponse {
     pub working_directory: String,
     pub project_id: Option<String>,
     pub claude_status: String,
+    pub model: Option<String>,
+    pub permission_mode: Option<String>,
+    pub extra_args: Vec<String>,
+    /// How the CLI was last resumed: `native` or `transcript`
+    pub resume_mode: Option<String>,
+    /// Whether Claude's changes are committed to a side branch after each turn
+    pub autocommit: bool,
+    /// Session this one was forked from
+    pub parent_session_id: Option<String>,
+    /// Last message of the parent session copied into this one
+    pub forked_from_message_id: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -43,17 +100,46 @@ pub struct MessageResponse {
     pub created_at: String,
 }
 
+/// A byte range of a message's text
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageRangeResponse {
+    pub message_id: String,
+    /// Byte offset the chunk starts at, moved back to a character boundary
+    pub offset: usize,
+    /// Bytes in the chunk; the next chunk starts at `offset + len`
+    pub len: usize,
+    /// Bytes in the whole text
+    pub total_len: usize,
+    pub content: String,
+    pub has_more: bool,
+}
+
 /// Session with messages response
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionWithMessagesResponse {
     pub session: SessionResponse,
     pub messages: Vec<MessageResponse>,
+    /// Streamed responses that were never saved, e.g. because the app crashed
+    pub drafts: Vec<MessageDraftResponse>,
 }
 
-/// Session summary for listing
+/// Assistant text saved while it was streaming
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
+pub struct MessageDraftResponse {
+    pub message_id: String,
+    pub content: String,
+    /// Whether the CLI finished the response before it was lost
+    pub is_complete: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Session summary for listing
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SessionSummaryResponse {
     pub id: String,
     pub title: String,
@@ -62,6 +148,11 @@ pub struct SessionSummaryResponse {
     pub project_name: Option<String>,
     pub message_count: i32,
     pub last_message: Option<String>,
+    /// Set while the session is archived
+    pub archived_at: Option<String>,
+    /// Pinned sessions are listed first
+    pub is_pinned: bool,
+    pub sort_order: Option<i64>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -71,6 +162,17 @@ pub struct SessionSummaryResponse {
 pub async fn session_create(
     state: State<'_, AppState>,
     request: SessionCreateRequest,
+) -> Result<SessionResponse, AppError> {
+    state.command_metrics.measure("session_create", async {
+        create_session(&state.db, request).await
+    })
+    .await
+}
+
+/// Validate and insert a new session
+pub(crate) async fn create_session(
+    db: &sqlx::SqlitePool,
+    request: SessionCreateRequest,
 ) -> Result<SessionResponse, AppError> {
     // Validate working directory
     let dir_path = Path::new(&request.working_directory);
@@ -81,33 +183,61 @@ pub async fn session_create(
         return Err(AppError::directory_not_found(&request.working_directory));
     }
 
+    let model = match normalize_cli_setting(request.model) {
+        Some(model) => Some(model),
+        None => get_value(db, SettingKey::DefaultModel)
+            .await?
+            .as_str()
+            .map(|m| m.to_string()),
+    };
+    let permission_mode = normalize_cli_setting(request.permission_mode);
+    let system_prompt = normalize_cli_setting(request.system_prompt);
+    let extra_args = request.extra_args.unwrap_or_default();
+    validate_cli_settings(permission_mode.as_deref(), &extra_args)?;
+
     // Generate ID and timestamps
     let id = uuid::Uuid::new_v4().to_string();
     let now = chrono::Utc::now().to_rfc3339();
-    let title = request.title.unwrap_or_else(|| "New Session".to_string());
+    let title = request.title.unwrap_or_else(|| DEFAULT_SESSION_TITLE.to_string());
 
     // Insert into database
     sqlx::query(
         r#"
-        INSERT INTO sessions (id, title, working_directory, project_id, created_at, updated_at)
-        VALUES (?, ?, ?, ?, ?, ?)
+        INSERT INTO sessions (id, title, working_directory, project_id, model, permission_mode, cli_args, system_prompt, handoff_id, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(&id)
     .bind(&title)
     .bind(&request.working_directory)
     .bind(&request.project_id)
+    .bind(&model)
+    .bind(&permission_mode)
+    .bind(serde_json::to_string(&extra_args)?)
+    .bind(&system_prompt)
+    .bind(&request.handoff_id)
     .bind(&now)
     .bind(&now)
-    .execute(&state.db)
+    .execute(db)
     .await?;
 
+    if let Some(task_id) = &request.task_id {
+        link_session_task(db, &id, task_id).await?;
+    }
+
     Ok(SessionResponse {
         id,
         title,
         working_directory: request.working_directory,
         project_id: request.project_id,
         claude_status: "stopped".to_string(),
+        model,
+        permission_mode,
+        extra_args,
+        resume_mode: None,
+        autocommit: false,
+        parent_session_id: None,
+        forked_from_message_id: None,
         created_at: now.clone(),
         updated_at: now,
     })
@@ -119,57 +249,146 @@ pub async fn session_load(
     state: State<'_, AppState>,
     session_id: String,
 ) -> Result<SessionWithMessagesResponse, AppError> {
-    // Load session
-    let session = sqlx::query_as::<_, (String, String, String, Option<String>, String, String)>(
-        r#"
-        SELECT id, title, working_directory, project_id, created_at, updated_at
-        FROM sessions
-        WHERE id = ?
-        "#,
-    )
-    .bind(&session_id)
-    .fetch_optional(&state.db)
-    .await?
-    .ok_or_else(|| AppError::database_not_found("Session", &session_id))?;
+    state.command_metrics.measure("session_load", async {
+        // Load session
+        let session = fetch_session(&state.db, &session_id).await?;
 
-    // Load messages
-    let messages = sqlx::query_as::<_, (String, String, String, String, Option<String>, String)>(
-        r#"
-        SELECT id, session_id, role, content, tool_usage, created_at
-        FROM messages
-        WHERE session_id = ?
-        ORDER BY created_at ASC
-        "#,
-    )
-    .bind(&session_id)
-    .fetch_all(&state.db)
-    .await?;
+        // Load messages
+        let messages = sqlx::query_as::<_, (String, String, String, String, Option<String>, String)>(
+            r#"
+            SELECT id, session_id, role, content, tool_usage, created_at
+            FROM messages
+            WHERE session_id = ?
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(&session_id)
+        .fetch_all(&state.db)
+        .await?;
 
-    // Get current CLI status
-    let status = state.get_cli_status(&session_id).await;
+        // Drafts whose message was saved are stale
+        let drafts = sqlx::query_as::<_, (String, String, bool, String, String)>(
+            r#"
+            SELECT d.message_id, d.content, d.is_complete, d.created_at, d.updated_at
+            FROM message_drafts d
+            WHERE d.session_id = ? AND NOT EXISTS (SELECT 1 FROM messages m WHERE m.id = d.message_id)
+            ORDER BY d.created_at ASC
+            "#,
+        )
+        .bind(&session_id)
+        .fetch_all(&state.db)
+        .await?;
 
-    Ok(SessionWithMessagesResponse {
-        session: SessionResponse {
-            id: session.0,
-            title: session.1,
-            working_directory: session.2,
-            project_id: session.3,
-            claude_status: format!("{:?}", status).to_lowercase(),
-            created_at: session.4,
-            updated_at: session.5,
-        },
-        messages: messages
-            .into_iter()
-            .map(|m| MessageResponse {
-                id: m.0,
-                session_id: m.1,
-                role: m.2,
-                content: m.3,
-                tool_usage: m.4.and_then(|s| serde_json::from_str(&s).ok()),
-                created_at: m.5,
-            })
-            .collect(),
+        // Get current CLI status
+        let status = state.get_cli_status(&session_id).await;
+
+        Ok(SessionWithMessagesResponse {
+            session: SessionResponse {
+                claude_status: format!("{:?}", status).to_lowercase(),
+                ..session
+            },
+            messages: messages
+                .into_iter()
+                .map(|m| MessageResponse {
+                    id: m.0,
+                    session_id: m.1,
+                    role: m.2,
+                    content: m.3,
+                    tool_usage: m.4.and_then(|s| serde_json::from_str(&s).ok()),
+                    created_at: m.5,
+                })
+                .collect(),
+            drafts: drafts
+                .into_iter()
+                .map(|d| MessageDraftResponse {
+                    message_id: d.0,
+                    content: d.1,
+                    is_complete: d.2,
+                    created_at: d.3,
+                    updated_at: d.4,
+                })
+                .collect(),
+        })
+    })
+    .await
+}
+
+/// Get the files attached to a message
+#[tauri::command]
+pub async fn message_get_attachments(
+    state: State<'_, AppState>,
+    message_id: String,
+) -> Result<Vec<Attachment>, AppError> {
+    state.command_metrics.measure("message_get_attachments", async {
+        attachments::for_message(&state.db, &message_id).await
+    })
+    .await
+}
+
+/// Largest chunk message_get_range returns
+const MAX_MESSAGE_RANGE_BYTES: usize = 1024 * 1024;
+
+/// Get a byte range of a message's content or tool usage JSON
+///
+/// Lets the frontend page through very long messages instead of receiving
+/// them whole. `field` is `content` (the default) or `tool_usage`. Ranges
+/// are adjusted to UTF-8 character boundaries and capped at 1 MiB.
+#[tauri::command]
+pub async fn message_get_range(
+    state: State<'_, AppState>,
+    message_id: String,
+    offset: usize,
+    len: usize,
+    field: Option<String>,
+) -> Result<MessageRangeResponse, AppError> {
+    state.command_metrics.measure("message_get_range", async {
+        let column = match field.as_deref().unwrap_or("content") {
+            "content" => "content",
+            "tool_usage" => "tool_usage",
+            other => return Err(AppError::invalid_input(format!("Unknown message field: {}", other))),
+        };
+
+        let text: Option<String> = sqlx::query_scalar(&format!("SELECT {} FROM messages WHERE id = ?", column))
+            .bind(&message_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or_else(|| AppError::database_not_found("Message", &message_id))?;
+        let text = text.unwrap_or_default();
+
+        let (start, end) = char_range(&text, offset, len.min(MAX_MESSAGE_RANGE_BYTES));
+
+        Ok(MessageRangeResponse {
+            message_id,
+            offset: start,
+            len: end - start,
+            total_len: text.len(),
+            content: text[start..end].to_string(),
+            has_more: end < text.len(),
+        })
     })
+    .await
+}
+
+/// Byte range of `len` bytes from `offset`, with both ends moved back to
+/// character boundaries
+///
+/// A non-empty request always yields at least one character, so paging by
+/// `offset + len` makes progress even with a `len` shorter than a character.
+fn char_range(text: &str, offset: usize, len: usize) -> (usize, usize) {
+    let floor = |mut i: usize| {
+        i = i.min(text.len());
+        while !text.is_char_boundary(i) {
+            i -= 1;
+        }
+        i
+    };
+
+    let start = floor(offset);
+    let mut end = floor(start.saturating_add(len));
+    if end == start && len > 0 {
+        end = text[start..].chars().next().map_or(start, |c| start + c.len_utf8());
+    }
+    (start, end)
 }
 
 /// Start the Claude CLI for a session
@@ -180,71 +399,201 @@ pub async fn session_start_cli(
     session_id: String,
     resume: Option<bool>,
 ) -> Result<(), AppError> {
-    // Get session working directory
-    let session = sqlx::query_as::<_, (String, String, String, Option<String>, String, String)>(
-        r#"
-        SELECT id, title, working_directory, project_id, created_at, updated_at
-        FROM sessions
-        WHERE id = ?
-        "#,
-    )
-    .bind(&session_id)
-    .fetch_optional(&state.db)
-    .await?
-    .ok_or_else(|| AppError::database_not_found("Session", &session_id))?;
+    state.command_metrics.measure("session_start_cli", async {
+        // Get session working directory and CLI settings
+        let session = fetch_session(&state.db, &session_id).await?;
+
+        let working_dir = Path::new(&session.working_directory);
+        let resume = resume.unwrap_or(false);
+
+        // Prefer the CLI's own session history, keeping the transcript as a fallback
+        let (cli_session_id, system_prompt): (Option<String>, Option<String>) =
+            sqlx::query_as("SELECT cli_session_id, system_prompt FROM sessions WHERE id = ?")
+                .bind(&session_id)
+                .fetch_one(&state.db)
+                .await?;
+        let resume_id = if resume { cli_session_id } else { None };
 
-    let working_dir = Path::new(&session.2);
+        let policy = session_policy(&state.db, &session_id).await?;
+        let mcp_config = match session.project_id.as_deref() {
+            Some(project_id) => mcp::write_config(&state.db, &state.data_dir, project_id).await?,
+            None => None,
+        };
+        let options = CliOptions {
+            model: session.model.clone(),
+            permission_mode: session.permission_mode.clone(),
+            disallowed_tools: policy.disallowed_tools(),
+            extra_args: session.extra_args.clone(),
+            system_prompt,
+            mcp_config,
+            resume_id,
+        };
+
+        // Build resume context if requested
+        let resume_context = if resume {
+            // A compacted session replays its summary plus the messages since
+            let summary = latest_summary(&state.db, &session_id).await?;
+            let since = summary.as_ref().map_or(String::new(), |s| s.through_created_at.clone());
+
+            // Load recent messages for context
+            let mut messages = sqlx::query_as::<_, (String, String)>(
+                r#"
+                SELECT role, content
+                FROM messages
+                WHERE session_id = ? AND created_at > ?
+                ORDER BY created_at DESC
+                LIMIT ?
+                "#,
+            )
+            .bind(&session_id)
+            .bind(&since)
+            .bind(RESUME_MESSAGE_LIMIT)
+            .fetch_all(&state.db)
+            .await?;
+            messages.reverse();
 
-    // Build resume context if requested
-    let resume_context = if resume.unwrap_or(false) {
-        // Load recent messages for context
-        let messages = sqlx::query_as::<_, (String, String, String)>(
+            build_resume_context(summary.as_ref().map(|s| s.summary.as_str()), &messages)
+        } else {
+            None
+        };
+
+        // Sessions seeded from a hand-off start with that document as context
+        let handoff: Option<String> = sqlx::query_scalar(
             r#"
-            SELECT role, content, created_at
-            FROM messages
-            WHERE session_id = ?
-            ORDER BY created_at DESC
-            LIMIT 20
+            SELECT h.content
+            FROM sessions s
+            JOIN session_handoffs h ON h.id = s.handoff_id
+            WHERE s.id = ?
             "#,
         )
         .bind(&session_id)
-        .fetch_all(&state.db)
+        .fetch_optional(&state.db)
         .await?;
 
-        if !messages.is_empty() {
-            let mut context = String::from("You are resuming a previous conversation. Here is the context:\n\n");
-            for (role, content, _) in messages.iter().rev() {
-                let label = if role == "user" { "User" } else { "Assistant" };
-                let truncated = if content.len() > 500 {
-                    format!("{}... [truncated]", &content[..500])
-                } else {
-                    content.clone()
-                };
-                context.push_str(&format!("{}: {}\n\n", label, truncated));
-            }
-            context.push_str("Continue the conversation from where it left off.\n");
-            Some(context)
-        } else {
-            None
+        let resume_context = match (handoff, resume_context) {
+            (Some(handoff), Some(context)) => Some(format!("{}\n\n{}", handoff, context)),
+            (Some(handoff), None) => Some(format!(
+                "You are picking up work handed off from a previous session:\n\n{}\n\nContinue from the next steps above.\n",
+                handoff
+            )),
+            (None, context) => context,
+        };
+
+        if resume {
+            let mode = if options.resume_id.is_some() { "native" } else { "transcript" };
+            sqlx::query("UPDATE sessions SET resume_mode = ? WHERE id = ?")
+                .bind(mode)
+                .bind(&session_id)
+                .execute(&state.db)
+                .await?;
         }
-    } else {
-        None
-    };
 
-    // Start CLI
-    state
-        .cli_manager
-        .start(app, session_id, working_dir, resume_context)
-        .await
+        // Start CLI
+        state
+            .cli_manager
+            .start(app.clone(), session_id.clone(), working_dir, resume_context, options)
+            .await?;
+
+        hooks::fire(
+            &app,
+            HookEvent::SessionStart,
+            HookContext {
+                session_id: Some(session_id),
+                ..Default::default()
+            },
+        );
+
+        Ok(())
+    })
+    .await
+}
+
+/// Build the transcript replayed to a CLI that can't resume natively
+///
+/// Messages are in chronological order. Returns None when there is nothing
+/// to replay.
+fn build_resume_context(summary: Option<&str>, messages: &[(String, String)]) -> Option<String> {
+    if summary.is_none() && messages.is_empty() {
+        return None;
+    }
+
+    let mut context = String::from("You are resuming a previous conversation. Here is the context:\n\n");
+    if let Some(summary) = summary {
+        context.push_str(&format!("Summary of the earlier conversation:\n\n{}\n\n", summary));
+        if !messages.is_empty() {
+            context.push_str("Most recent messages:\n\n");
+        }
+    }
+    for (role, content) in messages {
+        let label = if role == "user" { "User" } else { "Assistant" };
+        let truncated = if content.len() > 500 {
+            let (_, end) = char_range(content, 0, 500);
+            format!("{}... [truncated]", &content[..end])
+        } else {
+            content.clone()
+        };
+        context.push_str(&format!("{}: {}\n\n", label, truncated));
+    }
+    context.push_str("Continue the conversation from where it left off.\n");
+    Some(context)
 }
 
 /// Stop the Claude CLI for a session
+///
+/// The session finished webhook and stop hook only fire when the CLI was
+/// running, queued, or waiting to restart.
 #[tauri::command]
 pub async fn session_stop_cli(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("session_stop_cli", async {
+        if !state.cli_manager.stop(&app, &session_id).await? {
+            return Ok(());
+        }
+
+        webhooks::dispatch(
+            &app,
+            WebhookEvent::SessionFinished,
+            WebhookContext {
+                session_id: Some(session_id.clone()),
+                ..Default::default()
+            },
+        );
+        hooks::fire(
+            &app,
+            HookEvent::SessionStop,
+            HookContext {
+                session_id: Some(session_id),
+                ..Default::default()
+            },
+        );
+
+        Ok(())
+    })
+    .await
+}
+
+/// Answer a `claude_permission_request` event, allowing or denying the tool
+///
+/// A denial can carry a message that is passed on to Claude.
+#[tauri::command]
+pub async fn session_respond_permission(
     state: State<'_, AppState>,
     session_id: String,
+    request_id: String,
+    allow: bool,
+    message: Option<String>,
 ) -> Result<(), AppError> {
-    state.cli_manager.stop(&session_id).await
+    state.command_metrics.measure("session_respond_permission", async {
+        let message = message.map(|m| m.trim().to_string()).filter(|m| !m.is_empty());
+        state
+            .cli_manager
+            .respond_permission(&session_id, &request_id, allow, message.as_deref())
+            .await
+    })
+    .await
 }
 
 /// Send a message to Claude
@@ -253,50 +602,62 @@ pub async fn session_send_message(
     state: State<'_, AppState>,
     session_id: String,
     content: String,
+    attachments: Option<Vec<String>>,
 ) -> Result<String, AppError> {
-    // Validate content
-    if content.trim().is_empty() {
-        return Err(AppError::invalid_input("Message content cannot be empty"));
-    }
+    state.command_metrics.measure("session_send_message", async {
+        // Validate content
+        if content.trim().is_empty() {
+            return Err(AppError::invalid_input("Message content cannot be empty"));
+        }
+        let attachment_paths = attachments.unwrap_or_default();
+        attachments::validate(&attachment_paths).await?;
 
-    // Check if CLI is running
-    if !state.cli_manager.is_running(&session_id).await {
-        return Err(AppError::claude_cli_error("CLI is not running for this session"));
-    }
+        // Check if CLI is running
+        if !state.cli_manager.is_running(&session_id).await {
+            return Err(AppError::claude_cli_error("CLI is not running for this session"));
+        }
 
-    // Generate message ID
-    let message_id = uuid::Uuid::new_v4().to_string();
-    let now = chrono::Utc::now().to_rfc3339();
+        // Generate message ID
+        let message_id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
 
-    // Store user message in database
-    sqlx::query(
-        r#"
-        INSERT INTO messages (id, session_id, role, content, created_at)
-        VALUES (?, ?, 'user', ?, ?)
-        "#,
-    )
-    .bind(&message_id)
-    .bind(&session_id)
-    .bind(&content)
-    .bind(&now)
-    .execute(&state.db)
-    .await?;
+        // Store user message in database
+        sqlx::query(
+            r#"
+            INSERT INTO messages (id, session_id, role, content, created_at)
+            VALUES (?, ?, 'user', ?, ?)
+            "#,
+        )
+        .bind(&message_id)
+        .bind(&session_id)
+        .bind(&content)
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
 
-    // Update session updated_at
-    sqlx::query(
-        r#"
-        UPDATE sessions SET updated_at = ? WHERE id = ?
-        "#,
-    )
-    .bind(&now)
-    .bind(&session_id)
-    .execute(&state.db)
-    .await?;
+        // Update session updated_at
+        sqlx::query(
+            r#"
+            UPDATE sessions SET updated_at = ? WHERE id = ?
+            "#,
+        )
+        .bind(&now)
+        .bind(&session_id)
+        .execute(&state.db)
+        .await?;
+
+        // Copy attachments and reference them so the CLI reads the copies
+        let stored = attachments::store(&state.db, &state.data_dir, &session_id, &message_id, &attachment_paths).await?;
 
-    // Send to CLI
-    state.cli_manager.send_message(&session_id, &content).await?;
+        // Send to CLI
+        state
+            .cli_manager
+            .send_message(&session_id, &attachments::with_references(&content, &stored))
+            .await?;
 
-    Ok(message_id)
+        Ok(message_id)
+    })
+    .await
 }
 
 /// Cancel the current Claude response
@@ -305,29 +666,185 @@ pub async fn session_cancel_response(
     state: State<'_, AppState>,
     session_id: String,
 ) -> Result<(), AppError> {
-    state.cli_manager.cancel(&session_id).await
+    state.command_metrics.measure("session_cancel_response", async {
+        state.cli_manager.cancel(&session_id).await
+    })
+    .await
 }
 
 /// Delete a session
+///
+/// Sessions running in a worktree need `worktree_action`, `merge` or
+/// `discard`, to say what happens to its changes.
 #[tauri::command]
 pub async fn session_delete(
+    app: AppHandle,
     state: State<'_, AppState>,
     session_id: String,
+    worktree_action: Option<String>,
 ) -> Result<(), AppError> {
-    // Stop CLI if running
-    let _ = state.cli_manager.stop(&session_id).await;
+    state.command_metrics.measure("session_delete", async {
+        let title: String = sqlx::query_scalar("SELECT title FROM sessions WHERE id = ?")
+            .bind(&session_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or_else(|| AppError::database_not_found("Session", &session_id))?;
 
-    // Delete from database (messages will cascade)
-    let result = sqlx::query("DELETE FROM sessions WHERE id = ?")
-        .bind(&session_id)
-        .execute(&state.db)
-        .await?;
+        // Stop CLI if running
+        let _ = state.cli_manager.stop(&app, &session_id).await;
+        state.cli_manager.logs.clear(&session_id);
+        state.terminals.kill_session(&session_id);
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::database_not_found("Session", &session_id));
-    }
+        // A failed merge keeps the session so nothing is lost
+        remove_session_worktree(&state.db, &session_id, &title, worktree_action.as_deref()).await?;
 
-    Ok(())
+        // Delete from database (messages will cascade)
+        let result = sqlx::query("DELETE FROM sessions WHERE id = ?")
+            .bind(&session_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Session", &session_id));
+        }
+
+        // Checkpoint and attachment rows cascade; their files don't
+        let snapshots = checkpoints::session_dir(&state.data_dir, &session_id);
+        if let Err(e) = tokio::fs::remove_dir_all(&snapshots).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!("Failed to remove checkpoints for session {}: {}", session_id, e);
+            }
+        }
+        let attachment_dir = attachments::session_dir(&state.data_dir, &session_id);
+        if let Err(e) = tokio::fs::remove_dir_all(&attachment_dir).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!("Failed to remove attachments for session {}: {}", session_id, e);
+            }
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Archive a session, hiding it from the session list
+///
+/// Unlike deleting, the conversation and its messages are kept and the
+/// session can be unarchived later. A running CLI is stopped.
+#[tauri::command]
+pub async fn session_archive(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("session_archive", async {
+        let now = chrono::Utc::now().to_rfc3339();
+        let result = sqlx::query("UPDATE sessions SET archived_at = COALESCE(archived_at, ?) WHERE id = ?")
+            .bind(&now)
+            .bind(&session_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Session", &session_id));
+        }
+
+        let _ = state.cli_manager.stop(&app, &session_id).await;
+
+        Ok(())
+    })
+    .await
+}
+
+/// Restore an archived session to the session list
+#[tauri::command]
+pub async fn session_unarchive(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("session_unarchive", async {
+        let result = sqlx::query("UPDATE sessions SET archived_at = NULL WHERE id = ?")
+            .bind(&session_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Session", &session_id));
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Pin a session to the top of the session list, or unpin it
+///
+/// A newly pinned session goes after the sessions already pinned.
+/// Unpinning clears its custom order.
+#[tauri::command]
+pub async fn session_pin(
+    state: State<'_, AppState>,
+    session_id: String,
+    pinned: bool,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("session_pin", async {
+        let result = if pinned {
+            sqlx::query(
+                r#"
+                UPDATE sessions
+                SET is_pinned = 1,
+                    sort_order = (SELECT COALESCE(MAX(sort_order), -1) + 1 FROM sessions WHERE is_pinned = 1)
+                WHERE id = ? AND is_pinned = 0
+                "#,
+            )
+            .bind(&session_id)
+            .execute(&state.db)
+            .await?
+        } else {
+            sqlx::query("UPDATE sessions SET is_pinned = 0, sort_order = NULL WHERE id = ?")
+                .bind(&session_id)
+                .execute(&state.db)
+                .await?
+        };
+
+        // Pinning an already pinned session keeps its place
+        if result.rows_affected() == 0 {
+            let exists: Option<i64> = sqlx::query_scalar("SELECT 1 FROM sessions WHERE id = ?")
+                .bind(&session_id)
+                .fetch_optional(&state.db)
+                .await?;
+            if exists.is_none() {
+                return Err(AppError::database_not_found("Session", &session_id));
+            }
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Set the custom order of sessions in the session list
+///
+/// Sessions are listed in the order given, pinned ones still first.
+#[tauri::command]
+pub async fn session_reorder(
+    state: State<'_, AppState>,
+    session_ids: Vec<String>,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("session_reorder", async {
+        let mut tx = state.db.begin().await?;
+        for (index, id) in session_ids.iter().enumerate() {
+            sqlx::query("UPDATE sessions SET sort_order = ? WHERE id = ?")
+                .bind(index as i64)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    })
+    .await
 }
 
 /// Rename a session
@@ -337,115 +854,414 @@ pub async fn session_rename(
     session_id: String,
     title: String,
 ) -> Result<(), AppError> {
-    // Validate title
-    if title.trim().is_empty() {
-        return Err(AppError::invalid_input("Title cannot be empty"));
-    }
-    if title.len() > 100 {
-        return Err(AppError::invalid_input("Title must be 100 characters or less"));
-    }
+    state.command_metrics.measure("session_rename", async {
+        // Validate title
+        if title.trim().is_empty() {
+            return Err(AppError::invalid_input("Title cannot be empty"));
+        }
+        if title.len() > 100 {
+            return Err(AppError::invalid_input("Title must be 100 characters or less"));
+        }
 
-    let now = chrono::Utc::now().to_rfc3339();
+        let now = chrono::Utc::now().to_rfc3339();
+
+        let result = sqlx::query("UPDATE sessions SET title = ?, updated_at = ? WHERE id = ?")
+            .bind(&title)
+            .bind(&now)
+            .bind(&session_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Session", &session_id));
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Fork a session into a new one holding its conversation up to a message
+///
+/// The fork keeps the original's directory, project, and CLI settings, and
+/// starts without a native CLI session, so resuming it replays the copied
+/// messages as context. Attachments are not copied.
+#[tauri::command]
+pub async fn session_fork(
+    state: State<'_, AppState>,
+    session_id: String,
+    message_id: String,
+    title: Option<String>,
+) -> Result<SessionResponse, AppError> {
+    state.command_metrics.measure("session_fork", async {
+        let parent = fetch_session(&state.db, &session_id).await?;
+
+        let fork_point: String = sqlx::query_scalar("SELECT created_at FROM messages WHERE id = ? AND session_id = ?")
+            .bind(&message_id)
+            .bind(&session_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or_else(|| AppError::database_not_found("Message", &message_id))?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+        let title = normalize_cli_setting(title).unwrap_or_else(|| format!("{} (fork)", parent.title));
 
-    let result = sqlx::query("UPDATE sessions SET title = ?, updated_at = ? WHERE id = ?")
+        let mut tx = state.db.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO sessions (id, title, working_directory, project_id, model, permission_mode, cli_args, system_prompt,
+                                  parent_session_id, forked_from_message_id, created_at, updated_at)
+            SELECT ?, ?, working_directory, project_id, model, permission_mode, cli_args, system_prompt, id, ?, ?, ?
+            FROM sessions WHERE id = ?
+            "#,
+        )
+        .bind(&id)
         .bind(&title)
+        .bind(&message_id)
+        .bind(&now)
+        .bind(&now)
+        .bind(&session_id)
+        .execute(&mut *tx)
+        .await?;
+
+        // Ties on the fork point's timestamp are broken by ID
+        let messages = sqlx::query_as::<_, (String, String, Option<String>, String, Option<String>)>(
+            r#"
+            SELECT role, content, tool_usage, created_at, completed_at
+            FROM messages
+            WHERE session_id = ?1 AND (created_at < ?2 OR (created_at = ?2 AND id <= ?3))
+            ORDER BY created_at ASC, id ASC
+            "#,
+        )
+        .bind(&session_id)
+        .bind(&fork_point)
+        .bind(&message_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for (role, content, tool_usage, created_at, completed_at) in messages {
+            sqlx::query(
+                r#"
+                INSERT INTO messages (id, session_id, role, content, tool_usage, created_at, completed_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&id)
+            .bind(&role)
+            .bind(&content)
+            .bind(&tool_usage)
+            .bind(&created_at)
+            .bind(&completed_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(SessionResponse {
+            id,
+            title,
+            claude_status: "stopped".to_string(),
+            resume_mode: None,
+            autocommit: false,
+            parent_session_id: Some(session_id),
+            forked_from_message_id: Some(message_id),
+            created_at: now.clone(),
+            updated_at: now,
+            ..parent
+        })
+    })
+    .await
+}
+
+/// Update a session's model, permission mode, and extra CLI arguments
+///
+/// Changes apply the next time the CLI is started for the session.
+#[tauri::command]
+pub async fn session_update_settings(
+    state: State<'_, AppState>,
+    session_id: String,
+    request: SessionSettingsUpdateRequest,
+) -> Result<SessionResponse, AppError> {
+    state.command_metrics.measure("session_update_settings", async {
+        let current = fetch_session(&state.db, &session_id).await?;
+
+        let model = match request.model {
+            Some(model) => normalize_cli_setting(Some(model)),
+            None => current.model,
+        };
+        let permission_mode = match request.permission_mode {
+            Some(mode) => normalize_cli_setting(Some(mode)),
+            None => current.permission_mode,
+        };
+        let extra_args = request.extra_args.unwrap_or(current.extra_args);
+        validate_cli_settings(permission_mode.as_deref(), &extra_args)?;
+
+        let now = chrono::Utc::now().to_rfc3339();
+
+        sqlx::query(
+            "UPDATE sessions SET model = ?, permission_mode = ?, cli_args = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(&model)
+        .bind(&permission_mode)
+        .bind(serde_json::to_string(&extra_args)?)
         .bind(&now)
         .bind(&session_id)
         .execute(&state.db)
         .await?;
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::database_not_found("Session", &session_id));
+        let status = state.get_cli_status(&session_id).await;
+
+        Ok(SessionResponse {
+            claude_status: format!("{:?}", status).to_lowercase(),
+            model,
+            permission_mode,
+            extra_args,
+            updated_at: now,
+            ..current
+        })
+    })
+    .await
+}
+
+/// Load a session row, reporting its CLI status as stopped
+pub(crate) async fn fetch_session(db: &sqlx::SqlitePool, session_id: &str) -> Result<SessionResponse, AppError> {
+    let session = sqlx::query(
+        r#"
+        SELECT id, title, working_directory, project_id, model, permission_mode, cli_args, resume_mode, autocommit,
+               parent_session_id, forked_from_message_id, created_at, updated_at
+        FROM sessions
+        WHERE id = ?
+        "#,
+    )
+    .bind(session_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| AppError::database_not_found("Session", session_id))?;
+
+    Ok(SessionResponse {
+        id: session.get("id"),
+        title: session.get("title"),
+        working_directory: session.get("working_directory"),
+        project_id: session.get("project_id"),
+        claude_status: "stopped".to_string(),
+        model: session.get("model"),
+        permission_mode: session.get("permission_mode"),
+        extra_args: session
+            .get::<Option<String>, _>("cli_args")
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default(),
+        resume_mode: session.get("resume_mode"),
+        autocommit: session.get("autocommit"),
+        parent_session_id: session.get("parent_session_id"),
+        forked_from_message_id: session.get("forked_from_message_id"),
+        created_at: session.get("created_at"),
+        updated_at: session.get("updated_at"),
+    })
+}
+
+/// Store the CLI's own session ID for native `--resume`
+pub(crate) async fn record_cli_session_id(
+    db: &sqlx::SqlitePool,
+    session_id: &str,
+    cli_session_id: &str,
+) -> Result<(), AppError> {
+    sqlx::query("UPDATE sessions SET cli_session_id = ? WHERE id = ?")
+        .bind(cli_session_id)
+        .bind(session_id)
+        .execute(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Record what a CLI turn cost
+pub(crate) async fn record_turn_cost(db: &sqlx::SqlitePool, session_id: &str, cost_usd: f64) -> Result<(), AppError> {
+    sqlx::query("INSERT INTO session_costs (id, session_id, cost_usd, recorded_at) VALUES (?, ?, ?, ?)")
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(session_id)
+        .bind(cost_usd)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Save the text streamed so far for an assistant message
+pub(crate) async fn save_message_draft(
+    db: &sqlx::SqlitePool,
+    session_id: &str,
+    message_id: &str,
+    content: &str,
+    is_complete: bool,
+) -> Result<(), AppError> {
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        r#"
+        INSERT INTO message_drafts (message_id, session_id, content, is_complete, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT(message_id) DO UPDATE SET
+            content = excluded.content,
+            is_complete = excluded.is_complete,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(message_id)
+    .bind(session_id)
+    .bind(content)
+    .bind(is_complete)
+    .bind(&now)
+    .bind(&now)
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Record that a native resume failed and the transcript was used instead
+///
+/// The rejected ID is cleared so later resumes don't retry it.
+pub(crate) async fn record_resume_fallback(db: &sqlx::SqlitePool, session_id: &str) -> Result<(), AppError> {
+    sqlx::query("UPDATE sessions SET cli_session_id = NULL, resume_mode = 'transcript' WHERE id = ?")
+        .bind(session_id)
+        .execute(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Treat blank CLI settings as unset
+pub(crate) fn normalize_cli_setting(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Validate per-session CLI settings
+pub(crate) fn validate_cli_settings(permission_mode: Option<&str>, extra_args: &[String]) -> Result<(), AppError> {
+    if let Some(mode) = permission_mode {
+        if !PERMISSION_MODES.contains(&mode) {
+            return Err(AppError::invalid_input(format!("Invalid permission mode: {}", mode)));
+        }
+    }
+
+    // The backend relies on print mode for its stdin/stdout protocol
+    if extra_args.iter().any(|arg| arg == "--print" || arg == "-p") {
+        return Err(AppError::invalid_input("Extra CLI arguments cannot include --print"));
     }
 
     Ok(())
 }
 
 /// List all sessions with message counts and last message preview
+///
+/// Pinned sessions come first, then sessions in their custom order, then
+/// the rest by most recently updated.
 #[tauri::command]
 pub async fn session_list(
     state: State<'_, AppState>,
     project_id: Option<String>,
     limit: Option<i32>,
     offset: Option<i32>,
+    include_archived: Option<bool>,
 ) -> Result<Vec<SessionSummaryResponse>, AppError> {
-    let limit = limit.unwrap_or(50).min(200);
-    let offset = offset.unwrap_or(0);
+    state.command_metrics.measure("session_list", async {
+        let limit = limit.unwrap_or(50).min(200);
+        let offset = offset.unwrap_or(0);
+        let include_archived = include_archived.unwrap_or(false);
 
-    // Query sessions with message count and last message using subqueries
-    let query = if project_id.is_some() {
-        r#"
-        SELECT
-            s.id,
-            s.title,
-            s.working_directory,
-            s.project_id,
-            s.created_at,
-            s.updated_at,
-            COALESCE((SELECT COUNT(*) FROM messages WHERE session_id = s.id), 0) as message_count,
-            (SELECT content FROM messages WHERE session_id = s.id ORDER BY created_at DESC LIMIT 1) as last_message
-        FROM sessions s
-        WHERE s.project_id = ?
-        ORDER BY s.updated_at DESC
-        LIMIT ? OFFSET ?
-        "#
-    } else {
-        r#"
-        SELECT
-            s.id,
-            s.title,
-            s.working_directory,
-            s.project_id,
-            s.created_at,
-            s.updated_at,
-            COALESCE((SELECT COUNT(*) FROM messages WHERE session_id = s.id), 0) as message_count,
-            (SELECT content FROM messages WHERE session_id = s.id ORDER BY created_at DESC LIMIT 1) as last_message
-        FROM sessions s
-        ORDER BY s.updated_at DESC
-        LIMIT ? OFFSET ?
-        "#
-    };
+        // Query sessions with message count and last message using subqueries
+        let query = if project_id.is_some() {
+            r#"
+            SELECT
+                s.id,
+                s.title,
+                s.working_directory,
+                s.project_id,
+                s.created_at,
+                s.updated_at,
+                COALESCE((SELECT COUNT(*) FROM messages WHERE session_id = s.id), 0) as message_count,
+                (SELECT content FROM messages WHERE session_id = s.id ORDER BY created_at DESC LIMIT 1) as last_message,
+                s.archived_at,
+                s.is_pinned,
+                s.sort_order
+            FROM sessions s
+            WHERE s.project_id = ? AND (? OR s.archived_at IS NULL)
+            ORDER BY s.is_pinned DESC, s.sort_order IS NULL, s.sort_order ASC, s.updated_at DESC
+            LIMIT ? OFFSET ?
+            "#
+        } else {
+            r#"
+            SELECT
+                s.id,
+                s.title,
+                s.working_directory,
+                s.project_id,
+                s.created_at,
+                s.updated_at,
+                COALESCE((SELECT COUNT(*) FROM messages WHERE session_id = s.id), 0) as message_count,
+                (SELECT content FROM messages WHERE session_id = s.id ORDER BY created_at DESC LIMIT 1) as last_message,
+                s.archived_at,
+                s.is_pinned,
+                s.sort_order
+            FROM sessions s
+            WHERE ? OR s.archived_at IS NULL
+            ORDER BY s.is_pinned DESC, s.sort_order IS NULL, s.sort_order ASC, s.updated_at DESC
+            LIMIT ? OFFSET ?
+            "#
+        };
 
-    let sessions = if let Some(proj_id) = project_id {
-        sqlx::query_as::<_, (String, String, String, Option<String>, String, String, i32, Option<String>)>(query)
-            .bind(&proj_id)
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(&state.db)
-            .await?
-    } else {
-        sqlx::query_as::<_, (String, String, String, Option<String>, String, String, i32, Option<String>)>(query)
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(&state.db)
-            .await?
-    };
+        let sessions = if let Some(proj_id) = project_id {
+            sqlx::query_as::<_, (String, String, String, Option<String>, String, String, i32, Option<String>, Option<String>, bool, Option<i64>)>(query)
+                .bind(&proj_id)
+                .bind(include_archived)
+                .bind(limit)
+                .bind(offset)
+                .fetch_all(&state.db)
+                .await?
+        } else {
+            sqlx::query_as::<_, (String, String, String, Option<String>, String, String, i32, Option<String>, Option<String>, bool, Option<i64>)>(query)
+                .bind(include_archived)
+                .bind(limit)
+                .bind(offset)
+                .fetch_all(&state.db)
+                .await?
+        };
 
-    Ok(sessions
-        .into_iter()
-        .map(|s| {
-            // Truncate last message to 100 chars for preview
-            let last_message = s.7.map(|msg| {
-                if msg.len() > 100 {
-                    format!("{}...", &msg[..100])
-                } else {
-                    msg
+        Ok(sessions
+            .into_iter()
+            .map(|s| {
+                // Truncate last message to 100 chars for preview
+                let last_message = s.7.map(|msg| {
+                    if msg.len() > 100 {
+                        format!("{}...", &msg[..100])
+                    } else {
+                        msg
+                    }
+                });
+
+                SessionSummaryResponse {
+                    id: s.0,
+                    title: s.1,
+                    working_directory: s.2,
+                    project_id: s.3.clone(),
+                    project_name: None, // TODO: Join with projects table when implemented
+                    message_count: s.6,
+                    last_message,
+                    archived_at: s.8,
+                    is_pinned: s.9,
+                    sort_order: s.10,
+                    created_at: s.4,
+                    updated_at: s.5,
                 }
-            });
-
-            SessionSummaryResponse {
-                id: s.0,
-                title: s.1,
-                working_directory: s.2,
-                project_id: s.3.clone(),
-                project_name: None, // TODO: Join with projects table when implemented
-                message_count: s.6,
-                last_message,
-                created_at: s.4,
-                updated_at: s.5,
-            }
-        })
-        .collect())
+            })
+            .collect())
+    })
+    .await
 }
 
 /// Save a message to the database
@@ -458,39 +1274,222 @@ pub async fn session_save_message(
     content: String,
     tool_usage: Option<serde_json::Value>,
 ) -> Result<(), AppError> {
-    // Validate role
-    if role != "user" && role != "assistant" {
-        return Err(AppError::invalid_input("Role must be 'user' or 'assistant'"));
-    }
-
-    let now = chrono::Utc::now().to_rfc3339();
-    let tool_usage_str = tool_usage.map(|t| t.to_string());
+    state.command_metrics.measure("session_save_message", async {
+        // Validate role
+        if role != "user" && role != "assistant" {
+            return Err(AppError::invalid_input("Role must be 'user' or 'assistant'"));
+        }
 
-    // Insert or update message (upsert)
-    sqlx::query(
-        r#"
-        INSERT INTO messages (id, session_id, role, content, tool_usage, created_at)
-        VALUES (?, ?, ?, ?, ?, ?)
-        ON CONFLICT(id) DO UPDATE SET
-            content = excluded.content,
-            tool_usage = excluded.tool_usage
-        "#,
-    )
-    .bind(&message_id)
-    .bind(&session_id)
-    .bind(&role)
-    .bind(&content)
-    .bind(&tool_usage_str)
-    .bind(&now)
-    .execute(&state.db)
-    .await?;
+        let now = chrono::Utc::now().to_rfc3339();
+        let tool_usage_str = tool_usage.map(|t| t.to_string());
 
-    // Update session updated_at
-    sqlx::query("UPDATE sessions SET updated_at = ? WHERE id = ?")
-        .bind(&now)
+        // Insert or update message (upsert)
+        sqlx::query(
+            r#"
+            INSERT INTO messages (id, session_id, role, content, tool_usage, created_at, completed_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                content = excluded.content,
+                tool_usage = excluded.tool_usage,
+                completed_at = excluded.completed_at
+            "#,
+        )
+        .bind(&message_id)
         .bind(&session_id)
+        .bind(&role)
+        .bind(&content)
+        .bind(&tool_usage_str)
+        .bind(&now)
+        .bind(&now)
         .execute(&state.db)
         .await?;
 
-    Ok(())
+        // Update session updated_at
+        sqlx::query("UPDATE sessions SET updated_at = ? WHERE id = ?")
+            .bind(&now)
+            .bind(&session_id)
+            .execute(&state.db)
+            .await?;
+
+        // The saved message supersedes its streaming draft
+        sqlx::query("DELETE FROM message_drafts WHERE message_id = ?")
+            .bind(&message_id)
+            .execute(&state.db)
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
+
+/// Discard the unsaved streaming drafts of a session
+#[tauri::command]
+pub async fn session_discard_drafts(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("session_discard_drafts", async {
+        sqlx::query("DELETE FROM message_drafts WHERE session_id = ?")
+            .bind(&session_id)
+            .execute(&state.db)
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
+
+/// Get the maximum number of concurrent CLI processes (None = unlimited)
+#[tauri::command]
+pub async fn session_get_concurrency_limit(
+    state: State<'_, AppState>,
+) -> Result<Option<usize>, AppError> {
+    state.command_metrics.measure("session_get_concurrency_limit", async {
+        Ok(state.cli_manager.max_concurrent())
+    })
+    .await
+}
+
+/// Set the maximum number of concurrent CLI processes
+///
+/// Sessions started beyond the limit are queued until a slot frees up.
+#[tauri::command]
+pub async fn session_set_concurrency_limit(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    limit: Option<usize>,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("session_set_concurrency_limit", async {
+        let limit = limit.filter(|n| *n > 0);
+
+        match limit {
+            Some(n) => settings::set_setting(&state.db, MAX_CONCURRENT_SESSIONS, &n.to_string()).await?,
+            None => settings::delete_setting(&state.db, MAX_CONCURRENT_SESSIONS).await?,
+        }
+
+        state.cli_manager.set_max_concurrent(Some(app), limit).await;
+
+        Ok(())
+    })
+    .await
+}
+
+/// Get how many times a crashed CLI process is restarted automatically
+#[tauri::command]
+pub async fn session_get_restart_limit(
+    state: State<'_, AppState>,
+) -> Result<usize, AppError> {
+    state.command_metrics.measure("session_get_restart_limit", async {
+        Ok(state.cli_manager.max_restarts())
+    })
+    .await
+}
+
+/// Set how many times a crashed CLI process is restarted automatically (0 = never)
+#[tauri::command]
+pub async fn session_set_restart_limit(
+    state: State<'_, AppState>,
+    retries: usize,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("session_set_restart_limit", async {
+        if retries > 10 {
+            return Err(AppError::invalid_input("Restart limit must be 10 or less"));
+        }
+
+        settings::set_setting(&state.db, CLI_MAX_RESTARTS, &retries.to_string()).await?;
+        state.cli_manager.set_max_restarts(retries);
+
+        Ok(())
+    })
+    .await
+}
+
+/// Get the output streamed recently for a session, oldest first
+///
+/// Lets a window opened mid-response catch up before following the live
+/// `claude_output` events; chunks carry a sequence number for deduplication.
+#[tauri::command]
+pub async fn session_replay_recent(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Vec<ReplayChunk>, AppError> {
+    state.command_metrics.measure("session_replay_recent", async {
+        Ok(state.cli_manager.replay.recent(&session_id))
+    })
+    .await
+}
+
+/// Get the recent stderr output of a session's CLI process
+///
+/// Kept across restarts and after the process exits, so the output of a
+/// crash can still be read.
+#[tauri::command]
+pub async fn session_get_cli_logs(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Vec<CliLogLine>, AppError> {
+    state.command_metrics.measure("session_get_cli_logs", async {
+        Ok(state.cli_manager.logs.lines(&session_id))
+    })
+    .await
+}
+
+/// Get how many streamed chunks are kept per session for replay
+#[tauri::command]
+pub async fn session_get_replay_limit(
+    state: State<'_, AppState>,
+) -> Result<usize, AppError> {
+    state.command_metrics.measure("session_get_replay_limit", async {
+        Ok(state.cli_manager.replay.max_chunks())
+    })
+    .await
+}
+
+/// Set how many streamed chunks are kept per session for replay (0 = off)
+#[tauri::command]
+pub async fn session_set_replay_limit(
+    state: State<'_, AppState>,
+    max_chunks: usize,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("session_set_replay_limit", async {
+        if max_chunks > 100_000 {
+            return Err(AppError::invalid_input("Replay limit must be 100000 chunks or less"));
+        }
+
+        settings::set_setting(&state.db, OUTPUT_REPLAY_MAX_CHUNKS, &max_chunks.to_string()).await?;
+        state.cli_manager.replay.set_max_chunks(max_chunks);
+
+        Ok(())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_char_range() {
+        let text = "ab\u{e9}cd"; // é is two bytes, at 2..4
+        assert_eq!(char_range(text, 0, 2), (0, 2));
+        assert_eq!(char_range(text, 0, 3), (0, 2));
+        assert_eq!(char_range(text, 3, 2), (2, 4));
+        assert_eq!(char_range(text, 2, 1), (2, 4));
+        assert_eq!(char_range(text, 4, 100), (4, 6));
+        assert_eq!(char_range(text, 100, 5), (6, 6));
+        assert_eq!(char_range(text, 0, 0), (0, 0));
+    }
+
+    #[test]
+    fn test_build_resume_context() {
+        assert_eq!(build_resume_context(None, &[]), None);
+
+        let messages = vec![("user".to_string(), "\u{e9}".repeat(300))];
+        let context = build_resume_context(Some("Set up the repo."), &messages).unwrap();
+        assert!(context.contains("Summary of the earlier conversation:\n\nSet up the repo.\n\nMost recent messages:"));
+        assert!(context.contains(&format!("User: {}... [truncated]", "\u{e9}".repeat(250))));
+
+        let context = build_resume_context(Some("Set up the repo."), &[]).unwrap();
+        assert!(!context.contains("Most recent messages"));
+    }
 }
@@ -0,0 +1,163 @@
+//! Session/Task Link Commands
+//!
+//! Commands for linking chat sessions to the tasks they worked on, so
+//! conversations can be traced to the work they produced.
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// Task linked to a session
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkedTaskResponse {
+    pub task_id: String,
+    pub project_id: String,
+    pub title: String,
+    pub status: String,
+    pub linked_at: String,
+}
+
+/// Session linked to a task
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkedSessionResponse {
+    pub session_id: String,
+    pub title: String,
+    pub working_directory: String,
+    pub linked_at: String,
+}
+
+/// Link a session to a task
+#[tauri::command]
+pub async fn session_link_task(
+    state: State<'_, AppState>,
+    session_id: String,
+    task_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("session_link_task", async {
+        link_session_task(&state.db, &session_id, &task_id).await
+    })
+    .await
+}
+
+/// Remove a link between a session and a task
+#[tauri::command]
+pub async fn session_unlink_task(
+    state: State<'_, AppState>,
+    session_id: String,
+    task_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("session_unlink_task", async {
+        sqlx::query("DELETE FROM session_tasks WHERE session_id = ? AND task_id = ?")
+            .bind(&session_id)
+            .bind(&task_id)
+            .execute(&state.db)
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
+
+/// Get the tasks a session is linked to
+#[tauri::command]
+pub async fn session_get_tasks(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Vec<LinkedTaskResponse>, AppError> {
+    state.command_metrics.measure("session_get_tasks", async {
+        let tasks = sqlx::query_as::<_, (String, String, String, String, String)>(
+            r#"
+            SELECT t.id, t.project_id, t.title, t.status, st.linked_at
+            FROM session_tasks st
+            JOIN tasks t ON t.id = st.task_id
+            WHERE st.session_id = ? AND t.deleted_at IS NULL
+            ORDER BY st.linked_at ASC
+            "#,
+        )
+        .bind(&session_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(tasks
+            .into_iter()
+            .map(|t| LinkedTaskResponse {
+                task_id: t.0,
+                project_id: t.1,
+                title: t.2,
+                status: t.3,
+                linked_at: t.4,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Get the sessions that worked on a task
+#[tauri::command]
+pub async fn task_get_sessions(
+    state: State<'_, AppState>,
+    task_id: String,
+) -> Result<Vec<LinkedSessionResponse>, AppError> {
+    state.command_metrics.measure("task_get_sessions", async {
+        let sessions = sqlx::query_as::<_, (String, String, String, String)>(
+            r#"
+            SELECT s.id, s.title, s.working_directory, st.linked_at
+            FROM session_tasks st
+            JOIN sessions s ON s.id = st.session_id
+            WHERE st.task_id = ?
+            ORDER BY st.linked_at DESC
+            "#,
+        )
+        .bind(&task_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(sessions
+            .into_iter()
+            .map(|s| LinkedSessionResponse {
+                session_id: s.0,
+                title: s.1,
+                working_directory: s.2,
+                linked_at: s.3,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Link a session to a task, ignoring existing links
+pub(crate) async fn link_session_task(
+    db: &SqlitePool,
+    session_id: &str,
+    task_id: &str,
+) -> Result<(), AppError> {
+    let task_exists: Option<String> = sqlx::query_scalar("SELECT id FROM tasks WHERE id = ? AND deleted_at IS NULL")
+        .bind(task_id)
+        .fetch_optional(db)
+        .await?;
+    if task_exists.is_none() {
+        return Err(AppError::database_not_found("Task", task_id));
+    }
+
+    let session_exists: Option<String> = sqlx::query_scalar("SELECT id FROM sessions WHERE id = ?")
+        .bind(session_id)
+        .fetch_optional(db)
+        .await?;
+    if session_exists.is_none() {
+        return Err(AppError::database_not_found("Session", session_id));
+    }
+
+    sqlx::query("INSERT OR IGNORE INTO session_tasks (session_id, task_id, linked_at) VALUES (?, ?, ?)")
+        .bind(session_id)
+        .bind(task_id)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(db)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,398 @@
+//! Session Template Commands
+//!
+//! Named presets for starting sessions: a title pattern, project, working
+//! directory, system prompt, and CLI settings. `{date}` and `{project}` in a
+//! title pattern are filled in when a session is created from the template.
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::path::Path;
+use tauri::State;
+
+use super::session::{
+    create_session, normalize_cli_setting, validate_cli_settings, SessionCreateRequest, SessionResponse,
+};
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// Title used when a template has no pattern
+const DEFAULT_TITLE_PATTERN: &str = "New Session";
+
+/// Session template returned to frontend
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTemplateResponse {
+    pub id: String,
+    pub name: String,
+    pub title_pattern: Option<String>,
+    pub project_id: Option<String>,
+    /// Falls back to the project's root path when unset
+    pub working_directory: Option<String>,
+    pub system_prompt: Option<String>,
+    pub model: Option<String>,
+    pub permission_mode: Option<String>,
+    pub cli_args: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Request to create a session template
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTemplateCreateRequest {
+    pub name: String,
+    pub title_pattern: Option<String>,
+    pub project_id: Option<String>,
+    pub working_directory: Option<String>,
+    pub system_prompt: Option<String>,
+    pub model: Option<String>,
+    pub permission_mode: Option<String>,
+    pub cli_args: Option<Vec<String>>,
+}
+
+/// Request to update a session template
+///
+/// Omitted fields are left unchanged; an empty string clears a field.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTemplateUpdateRequest {
+    pub name: Option<String>,
+    pub title_pattern: Option<String>,
+    pub project_id: Option<String>,
+    pub working_directory: Option<String>,
+    pub system_prompt: Option<String>,
+    pub model: Option<String>,
+    pub permission_mode: Option<String>,
+    pub cli_args: Option<Vec<String>>,
+}
+
+/// Overrides applied when creating a session from a template
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionFromTemplateRequest {
+    pub title: Option<String>,
+    pub working_directory: Option<String>,
+    pub task_id: Option<String>,
+}
+
+type TemplateRow = (
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+);
+
+const TEMPLATE_COLUMNS: &str = "id, name, title_pattern, project_id, working_directory, system_prompt, \
+     model, permission_mode, cli_args, created_at, updated_at";
+
+impl From<TemplateRow> for SessionTemplateResponse {
+    fn from(row: TemplateRow) -> Self {
+        Self {
+            id: row.0,
+            name: row.1,
+            title_pattern: row.2,
+            project_id: row.3,
+            working_directory: row.4,
+            system_prompt: row.5,
+            model: row.6,
+            permission_mode: row.7,
+            cli_args: row.8.and_then(|a| serde_json::from_str(&a).ok()).unwrap_or_default(),
+            created_at: row.9,
+            updated_at: row.10,
+        }
+    }
+}
+
+/// Create a session template
+#[tauri::command]
+pub async fn session_template_create(
+    state: State<'_, AppState>,
+    request: SessionTemplateCreateRequest,
+) -> Result<SessionTemplateResponse, AppError> {
+    state.command_metrics.measure("session_template_create", async {
+        let now = chrono::Utc::now().to_rfc3339();
+        let template = SessionTemplateResponse {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: request.name.trim().to_string(),
+            title_pattern: normalize_cli_setting(request.title_pattern),
+            project_id: normalize_cli_setting(request.project_id),
+            working_directory: normalize_cli_setting(request.working_directory),
+            system_prompt: normalize_cli_setting(request.system_prompt),
+            model: normalize_cli_setting(request.model),
+            permission_mode: normalize_cli_setting(request.permission_mode),
+            cli_args: request.cli_args.unwrap_or_default(),
+            created_at: now.clone(),
+            updated_at: now,
+        };
+        validate_template(&state.db, &template).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO session_templates (id, name, title_pattern, project_id, working_directory, system_prompt, model, permission_mode, cli_args, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&template.id)
+        .bind(&template.name)
+        .bind(&template.title_pattern)
+        .bind(&template.project_id)
+        .bind(&template.working_directory)
+        .bind(&template.system_prompt)
+        .bind(&template.model)
+        .bind(&template.permission_mode)
+        .bind(serde_json::to_string(&template.cli_args)?)
+        .bind(&template.created_at)
+        .bind(&template.updated_at)
+        .execute(&state.db)
+        .await?;
+
+        Ok(template)
+    })
+    .await
+}
+
+/// List session templates by name
+#[tauri::command]
+pub async fn session_template_list(
+    state: State<'_, AppState>,
+) -> Result<Vec<SessionTemplateResponse>, AppError> {
+    state.command_metrics.measure("session_template_list", async {
+        let rows = sqlx::query_as::<_, TemplateRow>(&format!(
+            "SELECT {} FROM session_templates ORDER BY name COLLATE NOCASE",
+            TEMPLATE_COLUMNS
+        ))
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(rows.into_iter().map(SessionTemplateResponse::from).collect())
+    })
+    .await
+}
+
+/// Update a session template
+#[tauri::command]
+pub async fn session_template_update(
+    state: State<'_, AppState>,
+    template_id: String,
+    request: SessionTemplateUpdateRequest,
+) -> Result<SessionTemplateResponse, AppError> {
+    state.command_metrics.measure("session_template_update", async {
+        let mut template = get_template(&state.db, &template_id).await?;
+
+        if let Some(name) = request.name {
+            template.name = name.trim().to_string();
+        }
+        if request.title_pattern.is_some() {
+            template.title_pattern = normalize_cli_setting(request.title_pattern);
+        }
+        if request.project_id.is_some() {
+            template.project_id = normalize_cli_setting(request.project_id);
+        }
+        if request.working_directory.is_some() {
+            template.working_directory = normalize_cli_setting(request.working_directory);
+        }
+        if request.system_prompt.is_some() {
+            template.system_prompt = normalize_cli_setting(request.system_prompt);
+        }
+        if request.model.is_some() {
+            template.model = normalize_cli_setting(request.model);
+        }
+        if request.permission_mode.is_some() {
+            template.permission_mode = normalize_cli_setting(request.permission_mode);
+        }
+        if let Some(cli_args) = request.cli_args {
+            template.cli_args = cli_args;
+        }
+        validate_template(&state.db, &template).await?;
+        template.updated_at = chrono::Utc::now().to_rfc3339();
+
+        sqlx::query(
+            r#"
+            UPDATE session_templates
+            SET name = ?, title_pattern = ?, project_id = ?, working_directory = ?, system_prompt = ?,
+                model = ?, permission_mode = ?, cli_args = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&template.name)
+        .bind(&template.title_pattern)
+        .bind(&template.project_id)
+        .bind(&template.working_directory)
+        .bind(&template.system_prompt)
+        .bind(&template.model)
+        .bind(&template.permission_mode)
+        .bind(serde_json::to_string(&template.cli_args)?)
+        .bind(&template.updated_at)
+        .bind(&template.id)
+        .execute(&state.db)
+        .await?;
+
+        Ok(template)
+    })
+    .await
+}
+
+/// Delete a session template
+///
+/// Sessions already created from it are unaffected.
+#[tauri::command]
+pub async fn session_template_delete(
+    state: State<'_, AppState>,
+    template_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("session_template_delete", async {
+        let result = sqlx::query("DELETE FROM session_templates WHERE id = ?")
+            .bind(&template_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Session template", &template_id));
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Create a session from a template
+///
+/// The working directory falls back to the template's project root when the
+/// template has none and no override is given.
+#[tauri::command]
+pub async fn session_create_from_template(
+    state: State<'_, AppState>,
+    template_id: String,
+    request: Option<SessionFromTemplateRequest>,
+) -> Result<SessionResponse, AppError> {
+    state.command_metrics.measure("session_create_from_template", async {
+        let template = get_template(&state.db, &template_id).await?;
+        let request = request.unwrap_or_default();
+
+        let project = match &template.project_id {
+            Some(project_id) => sqlx::query_as::<_, (String, String)>(
+                "SELECT name, root_path FROM projects WHERE id = ? AND deleted_at IS NULL",
+            )
+            .bind(project_id)
+            .fetch_optional(&state.db)
+            .await?,
+            None => None,
+        };
+
+        let working_directory = normalize_cli_setting(request.working_directory)
+            .or(template.working_directory)
+            .or_else(|| project.as_ref().map(|(_, root_path)| root_path.clone()))
+            .ok_or_else(|| AppError::invalid_input("Template has no working directory or project"))?;
+
+        let title = normalize_cli_setting(request.title).unwrap_or_else(|| {
+            expand_title_pattern(
+                template.title_pattern.as_deref().unwrap_or(DEFAULT_TITLE_PATTERN),
+                &chrono::Local::now().format("%Y-%m-%d").to_string(),
+                project.as_ref().map(|(name, _)| name.as_str()),
+            )
+        });
+
+        create_session(
+            &state.db,
+            SessionCreateRequest {
+                working_directory,
+                project_id: project.and(template.project_id),
+                title: Some(title),
+                model: template.model,
+                permission_mode: template.permission_mode,
+                extra_args: Some(template.cli_args),
+                system_prompt: template.system_prompt,
+                handoff_id: None,
+                task_id: request.task_id,
+            },
+        )
+        .await
+    })
+    .await
+}
+
+async fn get_template(db: &SqlitePool, template_id: &str) -> Result<SessionTemplateResponse, AppError> {
+    sqlx::query_as::<_, TemplateRow>(&format!(
+        "SELECT {} FROM session_templates WHERE id = ?",
+        TEMPLATE_COLUMNS
+    ))
+    .bind(template_id)
+    .fetch_optional(db)
+    .await?
+    .map(SessionTemplateResponse::from)
+    .ok_or_else(|| AppError::database_not_found("Session template", template_id))
+}
+
+/// Check a template's name, project, directory, and CLI settings
+async fn validate_template(db: &SqlitePool, template: &SessionTemplateResponse) -> Result<(), AppError> {
+    if template.name.is_empty() {
+        return Err(AppError::invalid_input("Template name cannot be empty"));
+    }
+
+    let name_taken: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM session_templates WHERE name = ? AND id != ?)",
+    )
+    .bind(&template.name)
+    .bind(&template.id)
+    .fetch_one(db)
+    .await?;
+    if name_taken {
+        return Err(AppError::invalid_input(format!(
+            "A template named '{}' already exists",
+            template.name
+        )));
+    }
+
+    if let Some(project_id) = &template.project_id {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ? AND deleted_at IS NULL)",
+        )
+        .bind(project_id)
+        .fetch_one(db)
+        .await?;
+        if !exists {
+            return Err(AppError::database_not_found("Project", project_id));
+        }
+    }
+
+    if let Some(dir) = &template.working_directory {
+        if !Path::new(dir).is_absolute() {
+            return Err(AppError::invalid_input("Working directory must be an absolute path"));
+        }
+    }
+
+    validate_cli_settings(template.permission_mode.as_deref(), &template.cli_args)
+}
+
+/// Fill in `{date}` and `{project}` in a title pattern
+///
+/// `{project}` is left as-is when the template has no project.
+fn expand_title_pattern(pattern: &str, date: &str, project: Option<&str>) -> String {
+    let title = pattern.replace("{date}", date);
+    match project {
+        Some(project) => title.replace("{project}", project),
+        None => title,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_title_pattern() {
+        assert_eq!(
+            expand_title_pattern("Debug {project} {date}", "2026-03-10", Some("wingman")),
+            "Debug wingman 2026-03-10"
+        );
+        assert_eq!(expand_title_pattern("{date}: {project}", "2026-03-10", None), "2026-03-10: {project}");
+        assert_eq!(expand_title_pattern("Standup", "2026-03-10", Some("wingman")), "Standup");
+    }
+}
@@ -0,0 +1,144 @@
+//! Automatic Session Titles
+//!
+//! When the `auto_title_sessions` setting is on, a session still named
+//! "New Session" is titled from its first exchange with a one-shot CLI
+//! prompt once Claude's first response completes.
+
+use std::path::Path;
+
+use tauri::{AppHandle, Manager};
+
+use super::session::DEFAULT_SESSION_TITLE;
+use super::settings::{get_value, SettingKey};
+use crate::claude::oneshot::run_oneshot;
+use crate::error::AppError;
+use crate::events::{emit_event, event_names, SessionSavedPayload};
+use crate::state::AppState;
+
+/// Maximum characters of each side of the exchange included in the prompt
+const TITLE_EXCHANGE_CHARS: usize = 2000;
+
+/// Longest title kept from the CLI's answer
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Title a session from its first exchange, if enabled and still untitled
+///
+/// Only the first turn counts: a session with more than one user message or
+/// a title other than the default is left alone. Emits `session_saved` with
+/// the new title.
+pub(crate) async fn title_session(app: &AppHandle, session_id: &str, response: &str) -> Result<(), AppError> {
+    let state = app.state::<AppState>();
+
+    if get_value(&state.db, SettingKey::AutoTitleSessions).await?.as_bool() != Some(true) {
+        return Ok(());
+    }
+
+    let session: Option<(String, String, Option<String>)> =
+        sqlx::query_as("SELECT title, working_directory, model FROM sessions WHERE id = ?")
+            .bind(session_id)
+            .fetch_optional(&state.db)
+            .await?;
+    let Some((title, working_directory, model)) = session else {
+        return Ok(());
+    };
+    if title != DEFAULT_SESSION_TITLE {
+        return Ok(());
+    }
+
+    let prompts: Vec<String> = sqlx::query_scalar(
+        "SELECT content FROM messages WHERE session_id = ? AND role = 'user' ORDER BY created_at ASC LIMIT 2",
+    )
+    .bind(session_id)
+    .fetch_all(&state.db)
+    .await?;
+    let [prompt] = prompts.as_slice() else {
+        return Ok(());
+    };
+
+    let claude_path = state.cli_manager.resolve_binary()?;
+    let answer = run_oneshot(
+        &claude_path,
+        Path::new(&working_directory),
+        &build_title_prompt(prompt, response),
+        model.as_deref(),
+    )
+    .await?;
+    let Some(title) = clean_title(&answer) else {
+        return Err(AppError::claude_cli_error("Claude CLI returned an empty title"));
+    };
+
+    // A rename while the title was being generated wins
+    let now = chrono::Utc::now().to_rfc3339();
+    let result = sqlx::query("UPDATE sessions SET title = ?, updated_at = ? WHERE id = ? AND title = ?")
+        .bind(&title)
+        .bind(&now)
+        .bind(session_id)
+        .bind(DEFAULT_SESSION_TITLE)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() > 0 {
+        log::info!("Titled session {}: {}", session_id, title);
+        let _ = emit_event(
+            app,
+            event_names::SESSION_SAVED,
+            SessionSavedPayload {
+                session_id: session_id.to_string(),
+                title: Some(title),
+                timestamp: now,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+/// Build the prompt asking for a title for an exchange
+fn build_title_prompt(prompt: &str, response: &str) -> String {
+    let excerpt = |text: &str| text.chars().take(TITLE_EXCHANGE_CHARS).collect::<String>();
+    format!(
+        "Write a short title, at most six words, for a conversation that starts with the exchange below. \
+         Reply with only the title, without quotes or punctuation at the end.\n\n\
+         USER: {}\n\nASSISTANT: {}",
+        excerpt(prompt),
+        excerpt(response)
+    )
+}
+
+/// Take a title from the CLI's answer, dropping labels, quotes, and markdown
+fn clean_title(answer: &str) -> Option<String> {
+    let line = answer.lines().map(str::trim).find(|line| !line.is_empty())?;
+    let line = line.trim_start_matches('#').trim();
+    let line = line
+        .strip_prefix("Title:")
+        .or_else(|| line.strip_prefix("title:"))
+        .unwrap_or(line);
+    let title = line
+        .trim()
+        .trim_matches(|c| matches!(c, '"' | '\'' | '*' | '`'))
+        .trim_end_matches('.')
+        .trim();
+
+    (!title.is_empty()).then(|| title.chars().take(MAX_TITLE_CHARS).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_title() {
+        assert_eq!(clean_title("Fix login redirect bug"), Some("Fix login redirect bug".to_string()));
+        assert_eq!(clean_title("\n\"Refactor the parser.\"\n"), Some("Refactor the parser".to_string()));
+        assert_eq!(clean_title("# Title: **Add dark mode**"), Some("Add dark mode".to_string()));
+        assert_eq!(clean_title("  \n  "), None);
+        assert_eq!(clean_title(&"a".repeat(200)).map(|t| t.len()), Some(MAX_TITLE_CHARS));
+    }
+
+    #[test]
+    fn test_build_title_prompt_truncates() {
+        let prompt = build_title_prompt(&"x".repeat(5000), "ok");
+        assert!(prompt.contains(&"x".repeat(TITLE_EXCHANGE_CHARS)));
+        assert!(!prompt.contains(&"x".repeat(TITLE_EXCHANGE_CHARS + 1)));
+    }
+}
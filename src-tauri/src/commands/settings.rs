@@ -0,0 +1,315 @@
+//! Settings Commands
+//!
+//! Typed user preferences stored as JSON values in the `settings` table.
+//! Each key has a default that applies until it is set, and values are
+//! validated against the key's type before they are stored.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+use tauri::{AppHandle, State};
+
+use crate::db::settings;
+use crate::error::AppError;
+use crate::events::{emit_event, event_names, SettingsChangedPayload};
+use crate::notifications::{NotificationSettings, MAX_DUE_SOON_HOURS};
+use crate::state::file_watcher::PollingMode;
+use crate::state::AppState;
+
+/// Largest accepted file watcher debounce
+pub(crate) const MAX_DEBOUNCE_MS: u64 = 10_000;
+
+/// Accepted range for the time between scans of polled directories
+const MIN_POLL_INTERVAL_MS: u64 = 100;
+const MAX_POLL_INTERVAL_MS: u64 = 60_000;
+
+/// Themes the frontend can render
+const THEMES: &[&str] = &["dark", "light", "system"];
+
+/// A user preference
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SettingKey {
+    /// `dark`, `light`, or `system`
+    Theme,
+    /// Model new sessions use when none is chosen, or null for the CLI default
+    DefaultModel,
+    /// Patterns ignored by every file watcher, on top of the built-in ones
+    IgnorePatterns,
+    /// Quiet period before a file change is reported
+    DebounceMs,
+    /// Whether anonymous usage data may be sent
+    TelemetryOptIn,
+    /// When watched directories are polled: `auto`, `always`, or `never`
+    WatcherPolling,
+    /// Time between scans of polled directories
+    PollIntervalMs,
+    /// Whether new sessions are titled from their first exchange
+    AutoTitleSessions,
+    /// Which due date notifications are shown, as a `NotificationSettings` object
+    NotificationSettings,
+    /// Whether closing the window leaves sessions and watchers running in the tray
+    BackgroundMode,
+}
+
+impl SettingKey {
+    pub const ALL: [SettingKey; 10] = [
+        SettingKey::Theme,
+        SettingKey::DefaultModel,
+        SettingKey::IgnorePatterns,
+        SettingKey::DebounceMs,
+        SettingKey::TelemetryOptIn,
+        SettingKey::WatcherPolling,
+        SettingKey::PollIntervalMs,
+        SettingKey::AutoTitleSessions,
+        SettingKey::NotificationSettings,
+        SettingKey::BackgroundMode,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SettingKey::Theme => "theme",
+            SettingKey::DefaultModel => "default_model",
+            SettingKey::IgnorePatterns => "ignore_patterns",
+            SettingKey::DebounceMs => "debounce_ms",
+            SettingKey::TelemetryOptIn => "telemetry_opt_in",
+            SettingKey::WatcherPolling => "watcher_polling",
+            SettingKey::PollIntervalMs => "poll_interval_ms",
+            SettingKey::AutoTitleSessions => "auto_title_sessions",
+            SettingKey::NotificationSettings => "notification_settings",
+            SettingKey::BackgroundMode => "background_mode",
+        }
+    }
+
+    pub fn parse(key: &str) -> Result<Self, AppError> {
+        Self::ALL
+            .into_iter()
+            .find(|k| k.as_str() == key)
+            .ok_or_else(|| AppError::invalid_input(format!("Unknown setting '{}'", key)))
+    }
+
+    /// Value used while the setting is unset
+    pub fn default_value(&self) -> Value {
+        match self {
+            SettingKey::Theme => json!("dark"),
+            SettingKey::DefaultModel => Value::Null,
+            SettingKey::IgnorePatterns => json!([]),
+            SettingKey::DebounceMs => json!(crate::state::file_watcher::DEBOUNCE_MS),
+            SettingKey::TelemetryOptIn => json!(false),
+            SettingKey::WatcherPolling => json!(PollingMode::Auto.as_str()),
+            SettingKey::PollIntervalMs => json!(crate::state::file_watcher::DEFAULT_POLL_INTERVAL_MS),
+            SettingKey::AutoTitleSessions => json!(false),
+            SettingKey::NotificationSettings => json!(NotificationSettings::default()),
+            SettingKey::BackgroundMode => json!(true),
+        }
+    }
+
+    /// Check a value has the key's type and range
+    pub fn validate(&self, value: &Value) -> Result<(), AppError> {
+        let valid = match self {
+            SettingKey::Theme => value.as_str().is_some_and(|t| THEMES.contains(&t)),
+            SettingKey::DefaultModel => {
+                value.is_null() || value.as_str().is_some_and(|m| !m.trim().is_empty())
+            }
+            SettingKey::IgnorePatterns => value.as_array().is_some_and(|patterns| {
+                patterns.iter().all(|p| p.as_str().is_some_and(|p| !p.trim().is_empty()))
+            }),
+            SettingKey::DebounceMs => value.as_u64().is_some_and(|ms| ms <= MAX_DEBOUNCE_MS),
+            SettingKey::TelemetryOptIn | SettingKey::AutoTitleSessions | SettingKey::BackgroundMode => {
+                value.is_boolean()
+            }
+            SettingKey::WatcherPolling => value.as_str().and_then(PollingMode::parse).is_some(),
+            SettingKey::PollIntervalMs => value
+                .as_u64()
+                .is_some_and(|ms| (MIN_POLL_INTERVAL_MS..=MAX_POLL_INTERVAL_MS).contains(&ms)),
+            SettingKey::NotificationSettings => NotificationSettings::from_value(value).is_some(),
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(AppError::invalid_input(format!(
+                "Invalid value for '{}': expected {}",
+                self.as_str(),
+                self.expected()
+            )))
+        }
+    }
+
+    /// Description of accepted values, for error messages
+    fn expected(&self) -> String {
+        match self {
+            SettingKey::Theme => format!("one of {}", THEMES.join(", ")),
+            SettingKey::DefaultModel => "a model name or null".to_string(),
+            SettingKey::IgnorePatterns => "an array of non-empty strings".to_string(),
+            SettingKey::DebounceMs => format!("an integer from 0 to {}", MAX_DEBOUNCE_MS),
+            SettingKey::TelemetryOptIn | SettingKey::AutoTitleSessions | SettingKey::BackgroundMode => {
+                "true or false".to_string()
+            }
+            SettingKey::WatcherPolling => {
+                let modes: Vec<&str> = PollingMode::ALL.iter().map(|m| m.as_str()).collect();
+                format!("one of {}", modes.join(", "))
+            }
+            SettingKey::PollIntervalMs => {
+                format!("an integer from {} to {}", MIN_POLL_INTERVAL_MS, MAX_POLL_INTERVAL_MS)
+            }
+            SettingKey::NotificationSettings => format!(
+                "an object with enabled, tasksDueSoon, and milestonesOverdue booleans and dueSoonHours from 1 to {}",
+                MAX_DUE_SOON_HOURS
+            ),
+        }
+    }
+}
+
+/// A setting's current value
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingResponse {
+    pub key: String,
+    pub value: Value,
+    /// Whether the value is the default because the setting is unset
+    pub is_default: bool,
+}
+
+/// Get a setting's value
+#[tauri::command]
+pub async fn settings_get(
+    state: State<'_, AppState>,
+    key: String,
+) -> Result<SettingResponse, AppError> {
+    state.command_metrics.measure("settings_get", async {
+        let key = SettingKey::parse(&key)?;
+        read_setting(&state.db, key).await
+    })
+    .await
+}
+
+/// Get every setting's value, keyed by setting
+#[tauri::command]
+pub async fn settings_get_all(
+    state: State<'_, AppState>,
+) -> Result<BTreeMap<String, Value>, AppError> {
+    state.command_metrics.measure("settings_get_all", async {
+        let mut values = BTreeMap::new();
+        for key in SettingKey::ALL {
+            values.insert(key.as_str().to_string(), get_value(&state.db, key).await?);
+        }
+        Ok(values)
+    })
+    .await
+}
+
+/// Set a setting, or reset it to its default with a null value
+///
+/// Emits `settings_changed` with the new value.
+#[tauri::command]
+pub async fn settings_set(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    key: String,
+    value: Value,
+) -> Result<SettingResponse, AppError> {
+    state.command_metrics.measure("settings_set", async {
+        let key = SettingKey::parse(&key)?;
+
+        if value.is_null() {
+            settings::delete_setting(&state.db, key.as_str()).await?;
+        } else {
+            key.validate(&value)?;
+            settings::set_setting(&state.db, key.as_str(), &value.to_string()).await?;
+        }
+
+        let setting = read_setting(&state.db, key).await?;
+
+        if key == SettingKey::DebounceMs {
+            if let Some(ms) = setting.value.as_u64() {
+                state.file_watcher.set_debounce_ms(ms);
+            }
+        }
+        if key == SettingKey::WatcherPolling {
+            if let Some(mode) = setting.value.as_str().and_then(PollingMode::parse) {
+                state.file_watcher.set_polling_mode(mode).await;
+            }
+        }
+        if key == SettingKey::PollIntervalMs {
+            if let Some(ms) = setting.value.as_u64() {
+                state.file_watcher.set_poll_interval_ms(ms);
+            }
+        }
+        if key == SettingKey::BackgroundMode {
+            if let Some(enabled) = setting.value.as_bool() {
+                state.set_background_mode(enabled);
+            }
+        }
+
+        let _ = emit_event(
+            &app,
+            event_names::SETTINGS_CHANGED,
+            SettingsChangedPayload {
+                key: setting.key.clone(),
+                value: setting.value.clone(),
+            },
+        );
+        if key == SettingKey::Theme {
+            let _ = emit_event(&app, event_names::THEME_CHANGED, json!({ "theme": setting.value }));
+        }
+
+        Ok(setting)
+    })
+    .await
+}
+
+/// Read a setting's value, falling back to the default when it is unset or
+/// the stored value no longer validates
+pub(crate) async fn get_value(db: &SqlitePool, key: SettingKey) -> Result<Value, AppError> {
+    Ok(read_setting(db, key).await?.value)
+}
+
+async fn read_setting(db: &SqlitePool, key: SettingKey) -> Result<SettingResponse, AppError> {
+    let stored = settings::get_setting(db, key.as_str())
+        .await?
+        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+        .filter(|value| key.validate(value).is_ok());
+
+    Ok(SettingResponse {
+        key: key.as_str().to_string(),
+        is_default: stored.is_none(),
+        value: stored.unwrap_or_else(|| key.default_value()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setting_key_parse() {
+        assert_eq!(SettingKey::parse("debounce_ms").unwrap(), SettingKey::DebounceMs);
+        assert!(SettingKey::parse("font_size").is_err());
+    }
+
+    #[test]
+    fn test_setting_validation() {
+        assert!(SettingKey::Theme.validate(&json!("light")).is_ok());
+        assert!(SettingKey::Theme.validate(&json!("neon")).is_err());
+        assert!(SettingKey::DefaultModel.validate(&json!("sonnet")).is_ok());
+        assert!(SettingKey::DefaultModel.validate(&json!(" ")).is_err());
+        assert!(SettingKey::IgnorePatterns.validate(&json!(["*.log", "tmp"])).is_ok());
+        assert!(SettingKey::IgnorePatterns.validate(&json!(["*.log", 3])).is_err());
+        assert!(SettingKey::DebounceMs.validate(&json!(250)).is_ok());
+        assert!(SettingKey::DebounceMs.validate(&json!(-1)).is_err());
+        assert!(SettingKey::DebounceMs.validate(&json!(MAX_DEBOUNCE_MS + 1)).is_err());
+        assert!(SettingKey::TelemetryOptIn.validate(&json!("yes")).is_err());
+        assert!(SettingKey::AutoTitleSessions.validate(&json!(true)).is_ok());
+        assert!(SettingKey::WatcherPolling.validate(&json!("always")).is_ok());
+        assert!(SettingKey::WatcherPolling.validate(&json!("sometimes")).is_err());
+        assert!(SettingKey::PollIntervalMs.validate(&json!(500)).is_ok());
+        assert!(SettingKey::PollIntervalMs.validate(&json!(MIN_POLL_INTERVAL_MS - 1)).is_err());
+        assert!(SettingKey::NotificationSettings.validate(&json!({ "dueSoonHours": 4 })).is_ok());
+        assert!(SettingKey::NotificationSettings.validate(&json!({ "dueSoonHours": "4" })).is_err());
+
+        for key in SettingKey::ALL {
+            assert!(key.validate(&key.default_value()).is_ok(), "default for {}", key.as_str());
+        }
+    }
+}
@@ -3,9 +3,20 @@
 //! Commands for system-level operations.
 
 use serde::Serialize;
-use tauri::AppHandle;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
 
-use crate::error::AppError;
+use crate::db::settings::{self, CLAUDE_BINARY_PATH, IPC_RATE_LIMIT};
+use crate::error::{AppError, ErrorCode};
+use crate::logging::{self, LogEntry};
+use crate::state::command_metrics::CommandMetricSummary;
+use crate::state::startup::StartupStatus;
+use crate::state::{AppState, StartupTracker};
+use crate::system;
+
+/// Longest a CLI `--version` check may run
+const CLI_VERSION_TIMEOUT_SECS: u64 = 10;
 
 /// Application info returned by system_get_app_info
 #[derive(Debug, Serialize)]
@@ -14,18 +25,147 @@ pub struct AppInfo {
     pub version: String,
     pub name: String,
     pub tauri_version: String,
+    pub safe_mode: bool,
 }
 
 /// Get application information
 #[tauri::command]
 pub fn system_get_app_info(app: AppHandle) -> Result<AppInfo, AppError> {
+    measure_early(&app, "system_get_app_info", || Ok(app_info(&app)))
+}
+
+pub(crate) fn app_info(app: &AppHandle) -> AppInfo {
     let config = app.config();
+    let safe_mode = app
+        .try_state::<AppState>()
+        .map(|state| state.safe_mode)
+        .unwrap_or(false);
 
-    Ok(AppInfo {
+    AppInfo {
         version: config.version.clone().unwrap_or_else(|| "0.1.0".to_string()),
         name: config.product_name.clone().unwrap_or_else(|| "Wingman".to_string()),
         tauri_version: tauri::VERSION.to_string(),
+        safe_mode,
+    }
+}
+
+/// Get how far app initialization has got, including migration progress
+///
+/// Meant to be called before the app state exists.
+#[tauri::command]
+pub fn system_get_startup_status(app: AppHandle) -> Result<StartupStatus, AppError> {
+    measure_early(&app, "system_get_startup_status", || Ok(app.state::<StartupTracker>().get()))
+}
+
+/// Most log entries returned by system_get_logs
+const MAX_LOG_LINES: usize = 5000;
+
+/// Get the last `lines` app log entries, oldest first
+///
+/// With `level`, only entries at that level or more severe are returned.
+/// Reads the log files directly, so it works when the app state failed to
+/// load.
+#[tauri::command]
+pub fn system_get_logs(
+    app: AppHandle,
+    lines: Option<usize>,
+    level: Option<String>,
+) -> Result<Vec<LogEntry>, AppError> {
+    measure_early(&app, "system_get_logs", || {
+        if let Some(level) = level.as_deref() {
+            if !logging::is_level(level) {
+                return Err(AppError::invalid_input(format!("Unknown log level: {}", level)));
+            }
+        }
+        let lines = lines.unwrap_or(200).min(MAX_LOG_LINES);
+        let dir = logging::log_dir(&crate::app_data_dir()?);
+        Ok(logging::tail(&dir, logging::APP_LOG_PREFIX, lines, level.as_deref())?)
+    })
+}
+
+/// Open the log directory in the system file manager
+///
+/// Works before the app state exists.
+#[tauri::command]
+pub fn system_open_log_dir(app: AppHandle) -> Result<(), AppError> {
+    measure_early(&app, "system_open_log_dir", || {
+        let dir = logging::log_dir(&crate::app_data_dir()?);
+        std::fs::create_dir_all(&dir)?;
+        open::that(&dir).map_err(|e| {
+            AppError::with_details(ErrorCode::Unknown, "Failed to open the log directory", e.to_string())
+        })
+    })
+}
+
+/// Run a command that may be called before the app state exists, recording
+/// it in the command metrics when the state is there
+fn measure_early<T>(
+    app: &AppHandle,
+    command: &str,
+    body: impl FnOnce() -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let started = Instant::now();
+    let result = body();
+    if let Some(state) = app.try_state::<AppState>() {
+        state.command_metrics.record(command, started.elapsed(), result.is_ok());
+    }
+    result
+}
+
+/// Get the maximum calls per IPC command per second (0 = unlimited)
+#[tauri::command]
+pub async fn system_get_ipc_rate_limit(
+    state: State<'_, AppState>,
+) -> Result<usize, AppError> {
+    state.command_metrics.measure("system_get_ipc_rate_limit", async {
+        Ok(state.ipc_limiter.limit())
     })
+    .await
+}
+
+/// Set the maximum calls per IPC command per second (0 = unlimited)
+#[tauri::command]
+pub async fn system_set_ipc_rate_limit(
+    state: State<'_, AppState>,
+    limit: usize,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("system_set_ipc_rate_limit", async {
+        settings::set_setting(&state.db, IPC_RATE_LIMIT, &limit.to_string()).await?;
+        state.ipc_limiter.set_limit(limit);
+
+        Ok(())
+    })
+    .await
+}
+
+/// Recent command timings returned by system_get_command_metrics
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandMetricsResponse {
+    /// Calls currently recorded
+    pub sample_count: usize,
+    /// Most calls kept before the oldest are dropped
+    pub capacity: usize,
+    pub commands: Vec<CommandMetricSummary>,
+}
+
+/// Get duration and failure statistics for recent command calls
+#[tauri::command]
+pub async fn system_get_command_metrics(
+    state: State<'_, AppState>,
+) -> Result<CommandMetricsResponse, AppError> {
+    Ok(CommandMetricsResponse {
+        sample_count: state.command_metrics.sample_count(),
+        capacity: state.command_metrics.capacity(),
+        commands: state.command_metrics.summary(),
+    })
+}
+
+/// Discard recorded command metrics, e.g. to compare before and after a change
+#[tauri::command]
+pub async fn system_clear_command_metrics(state: State<'_, AppState>) -> Result<(), AppError> {
+    state.command_metrics.clear();
+    Ok(())
 }
 
 /// CLI status check result
@@ -39,139 +179,318 @@ pub struct CliStatus {
 }
 
 /// Check if Claude CLI is installed
+///
+/// Checks the configured CLI executable when one is set, otherwise `claude`
+/// from PATH or the login shell's PATH.
+#[tauri::command]
+pub async fn system_check_cli(
+    state: State<'_, AppState>,
+) -> Result<CliStatus, AppError> {
+    state.command_metrics.measure("system_check_cli", async {
+        Ok(check_resolved_cli(&state).await)
+    })
+    .await
+}
+
+/// Use a specific CLI executable instead of `claude` from PATH
+///
+/// The executable must be an absolute path that answers `--version`. An
+/// empty or missing path goes back to the PATH lookup.
 #[tauri::command]
-pub async fn system_check_cli() -> Result<CliStatus, AppError> {
-    // Try to run `claude --version` to check if CLI is installed
-    let output = tokio::process::Command::new("claude")
-        .arg("--version")
-        .output()
-        .await;
+pub async fn system_set_cli_path(
+    state: State<'_, AppState>,
+    path: Option<String>,
+) -> Result<CliStatus, AppError> {
+    state.command_metrics.measure("system_set_cli_path", async {
+        let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
 
-    match output {
-        Ok(output) => {
-            if output.status.success() {
-                let version = String::from_utf8_lossy(&output.stdout)
-                    .trim()
-                    .to_string();
-
-                // Try to find the path
-                let path = which_claude().await;
-
-                Ok(CliStatus {
-                    installed: true,
-                    version: Some(version),
-                    path,
-                    error: None,
-                })
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-                Ok(CliStatus {
-                    installed: false,
-                    version: None,
-                    path: None,
-                    error: Some(stderr),
-                })
-            }
+        let Some(path) = path else {
+            settings::delete_setting(&state.db, CLAUDE_BINARY_PATH).await?;
+            state.cli_manager.set_binary_path(None);
+            return Ok(check_resolved_cli(&state).await);
+        };
+
+        let binary = PathBuf::from(&path);
+        if !binary.is_absolute() {
+            return Err(AppError::invalid_input("CLI path must be an absolute path"));
         }
-        Err(e) => {
-            if e.kind() == std::io::ErrorKind::NotFound {
-                Ok(CliStatus {
-                    installed: false,
-                    version: None,
-                    path: None,
-                    error: Some("Claude CLI not found in PATH".to_string()),
-                })
-            } else {
-                Ok(CliStatus {
-                    installed: false,
-                    version: None,
-                    path: None,
-                    error: Some(e.to_string()),
-                })
-            }
+        if !binary.is_file() {
+            return Err(AppError::file_not_found(&path));
         }
+
+        let status = check_cli(&binary, Some(path.clone())).await;
+        if !status.installed {
+            return Err(AppError::with_details(
+                ErrorCode::ClaudeCliError,
+                "The selected file is not a working Claude CLI",
+                status.error.unwrap_or_default(),
+            ));
+        }
+
+        settings::set_setting(&state.db, CLAUDE_BINARY_PATH, &path).await?;
+        state.cli_manager.set_binary_path(Some(binary));
+
+        Ok(status)
+    })
+    .await
+}
+
+/// Check the CLI executable sessions would launch
+pub(crate) async fn check_resolved_cli(state: &AppState) -> CliStatus {
+    match state.cli_manager.resolve_binary() {
+        Ok(path) => {
+            let path_string = path.to_string_lossy().to_string();
+            check_cli(&path, Some(path_string)).await
+        }
+        Err(_) => CliStatus {
+            installed: false,
+            version: None,
+            path: None,
+            error: Some("Claude CLI not found in PATH".to_string()),
+        },
     }
 }
 
-/// Try to find the path to the claude executable
-async fn which_claude() -> Option<String> {
-    #[cfg(windows)]
-    let cmd = "where";
-    #[cfg(not(windows))]
-    let cmd = "which";
-
-    let output = tokio::process::Command::new(cmd)
-        .arg("claude")
-        .output()
-        .await
-        .ok()?;
-
-    if output.status.success() {
-        Some(
-            String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .next()?
-                .trim()
-                .to_string(),
-        )
-    } else {
-        None
+/// Open a terminal running `claude login` so the user can sign in again
+///
+/// Returns once the terminal is open. A `cli_login_finished` event reports
+/// whether the login succeeded.
+#[tauri::command]
+pub async fn system_cli_login(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("system_cli_login", async {
+        let claude_path = state.cli_manager.resolve_binary()?;
+        system::login::start(&app, &claude_path, &system::login::work_dir(&state.data_dir)).await
+    })
+    .await
+}
+
+/// Run `--version` against a CLI executable
+async fn check_cli(binary: &Path, path: Option<String>) -> CliStatus {
+    // The CLI's own interpreter (e.g. node) may only be on the login PATH
+    let mut cmd = tokio::process::Command::new(binary);
+    system::env::apply(&mut cmd);
+    cmd.arg("--version").kill_on_drop(true);
+
+    let output = tokio::time::timeout(Duration::from_secs(CLI_VERSION_TIMEOUT_SECS), cmd.output()).await;
+
+    let failed = |error: String| CliStatus {
+        installed: false,
+        version: None,
+        path: None,
+        error: Some(error),
+    };
+
+    match output {
+        Ok(Ok(output)) if output.status.success() => CliStatus {
+            installed: true,
+            version: Some(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+            path,
+            error: None,
+        },
+        Ok(Ok(output)) => failed(String::from_utf8_lossy(&output.stderr).to_string()),
+        Ok(Err(e)) => failed(e.to_string()),
+        Err(_) => failed(format!(
+            "`--version` did not finish within {} seconds",
+            CLI_VERSION_TIMEOUT_SECS
+        )),
     }
 }
 
 /// Open a URL in the default browser
 #[tauri::command]
-pub async fn system_open_external(url: String) -> Result<(), AppError> {
-    open::that(&url).map_err(|e| {
-        AppError::with_details(
-            crate::error::ErrorCode::Unknown,
-            "Failed to open URL",
-            e.to_string(),
-        )
+pub async fn system_open_external(
+    state: State<'_, AppState>,
+    url: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("system_open_external", async {
+        open::that(&url).map_err(|e| {
+            AppError::with_details(
+                crate::error::ErrorCode::Unknown,
+                "Failed to open URL",
+                e.to_string(),
+            )
+        })
     })
+    .await
 }
 
 /// Open a file or folder in the system file manager
 #[tauri::command]
-pub async fn system_open_path(path: String) -> Result<(), AppError> {
-    let path = std::path::Path::new(&path);
+pub async fn system_open_path(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("system_open_path", async {
+        let path = std::path::Path::new(&path);
 
-    if !path.exists() {
-        return Err(AppError::file_not_found(path.display().to_string()));
-    }
+        if !path.exists() {
+            return Err(AppError::file_not_found(path.display().to_string()));
+        }
 
-    // If it's a file, open its parent directory
-    let target = if path.is_file() {
-        path.parent().unwrap_or(path)
-    } else {
-        path
-    };
+        // If it's a file, open its parent directory
+        let target = if path.is_file() {
+            path.parent().unwrap_or(path)
+        } else {
+            path
+        };
 
-    open::that(target).map_err(|e| {
-        AppError::with_details(
-            crate::error::ErrorCode::Unknown,
-            "Failed to open path",
-            e.to_string(),
-        )
+        open::that(target).map_err(|e| {
+            AppError::with_details(
+                crate::error::ErrorCode::Unknown,
+                "Failed to open path",
+                e.to_string(),
+            )
+        })
     })
+    .await
 }
 
 /// Open a directory picker dialog
 #[tauri::command]
 pub async fn system_select_directory(
     app: tauri::AppHandle,
+    state: State<'_, AppState>,
     title: Option<String>,
 ) -> Result<Option<String>, AppError> {
-    use tauri_plugin_dialog::DialogExt;
+    state.command_metrics.measure("system_select_directory", async {
+        use tauri_plugin_dialog::DialogExt;
 
-    let dialog = app.dialog().file();
-    let dialog = if let Some(t) = title {
-        dialog.set_title(t)
-    } else {
-        dialog.set_title("Select Directory")
-    };
+        let dialog = app.dialog().file();
+        let dialog = if let Some(t) = title {
+            dialog.set_title(t)
+        } else {
+            dialog.set_title("Select Directory")
+        };
 
-    let result = dialog.blocking_pick_folder();
+        let result = dialog.blocking_pick_folder();
+
+        Ok(result.map(|p| p.to_string()))
+    })
+    .await
+}
 
-    Ok(result.map(|p| p.to_string()))
+/// A process spawned by the app
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChildProcessResponse {
+    pub pid: Option<u32>,
+    /// What the process is for: `cli_session`, `terminal`, or `script`
+    pub kind: String,
+    /// Terminal ID for `terminal` processes, run ID for `script` ones
+    pub id: Option<String>,
+    pub session_id: Option<String>,
+    pub session_title: Option<String>,
+    pub project_id: Option<String>,
+    pub working_directory: String,
+    /// The CLI status for `cli_session` processes, otherwise `running`
+    pub status: String,
+    pub started_at: String,
+    pub uptime_secs: u64,
+}
+
+/// List the processes the app has spawned and is still running
+#[tauri::command]
+pub async fn system_list_children(
+    state: State<'_, AppState>,
+) -> Result<Vec<ChildProcessResponse>, AppError> {
+    state.command_metrics.measure("system_list_children", async {
+        let mut children = Vec::new();
+        let now = chrono::Utc::now();
+
+        for process in state.cli_manager.list_processes().await {
+            let (session_title, project_id) = session_owner(&state.db, &process.session_id).await?;
+            children.push(ChildProcessResponse {
+                pid: process.pid,
+                kind: "cli_session".to_string(),
+                id: None,
+                session_id: Some(process.session_id),
+                session_title,
+                project_id,
+                working_directory: process.working_dir,
+                status: process.status.as_str().to_string(),
+                started_at: process.started_at,
+                uptime_secs: process.uptime_secs,
+            });
+        }
+
+        for terminal in state.terminals.list(None) {
+            let (session_title, project_id) = session_owner(&state.db, &terminal.session_id).await?;
+            let uptime_secs = chrono::DateTime::parse_from_rfc3339(&terminal.created_at)
+                .map_or(0, |created| (now - created.with_timezone(&chrono::Utc)).num_seconds().max(0) as u64);
+            children.push(ChildProcessResponse {
+                pid: terminal.pid,
+                kind: "terminal".to_string(),
+                id: Some(terminal.id),
+                session_id: Some(terminal.session_id),
+                session_title,
+                project_id,
+                working_directory: terminal.working_directory,
+                status: "running".to_string(),
+                started_at: terminal.created_at,
+                uptime_secs,
+            });
+        }
+
+        for run in state.script_runs.list() {
+            children.push(ChildProcessResponse {
+                pid: run.pid,
+                kind: "script".to_string(),
+                id: Some(run.run_id),
+                session_id: None,
+                session_title: None,
+                project_id: Some(run.project_id),
+                working_directory: run.cwd,
+                status: "running".to_string(),
+                started_at: run.started_at.to_rfc3339(),
+                uptime_secs: (now - run.started_at).num_seconds().max(0) as u64,
+            });
+        }
+
+        Ok(children)
+    })
+    .await
+}
+
+/// Title and project of the session a process belongs to
+async fn session_owner(
+    db: &sqlx::SqlitePool,
+    session_id: &str,
+) -> Result<(Option<String>, Option<String>), AppError> {
+    let owner = sqlx::query_as::<_, (String, Option<String>)>("SELECT title, project_id FROM sessions WHERE id = ?")
+        .bind(session_id)
+        .fetch_optional(db)
+        .await?;
+    Ok(owner.map_or((None, None), |(title, project_id)| (Some(title), project_id)))
+}
+
+/// Kill a process the app spawned
+///
+/// Only pids listed by `system_list_children` are accepted. Killing a CLI
+/// process stops its session the same way `session_stop_cli` does, so it
+/// is not restarted; a terminal is closed and a script run cancelled.
+#[tauri::command]
+pub async fn system_kill_child(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    pid: u32,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("system_kill_child", async {
+        if let Some(session_id) = state.cli_manager.session_for_pid(pid).await {
+            state.cli_manager.stop(&app, &session_id).await?;
+            return Ok(());
+        }
+        if let Some(terminal) = state.terminals.list(None).into_iter().find(|t| t.pid == Some(pid)) {
+            return state.terminals.kill(&terminal.id);
+        }
+        if let Some(run) = state.script_runs.list().into_iter().find(|run| run.pid == Some(pid)) {
+            state.script_runs.cancel(&run.run_id);
+            return Ok(());
+        }
+
+        Err(AppError::invalid_input(format!("Process {} was not started by Wingman", pid)))
+    })
+    .await
 }
@@ -0,0 +1,329 @@
+//! Task Status Commands
+//!
+//! Commands for managing a project's kanban columns. Each column belongs to
+//! one of the fixed `todo` / `in_progress` / `done` categories, which is what
+//! progress, burndown, and definition of done checks are based on.
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+use super::project::record_task_change;
+
+/// Status categories every column maps to
+const STATUS_CATEGORIES: &[&str] = &["todo", "in_progress", "done"];
+
+/// Columns created for new projects
+const DEFAULT_STATUSES: &[(&str, &str)] = &[
+    ("To Do", "todo"),
+    ("In Progress", "in_progress"),
+    ("Done", "done"),
+];
+
+/// Kanban column response
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskStatusResponse {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub category: String,
+    pub sort_order: i32,
+    pub created_at: String,
+}
+
+/// Get a project's columns in board order
+#[tauri::command]
+pub async fn task_status_get_all(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<Vec<TaskStatusResponse>, AppError> {
+    state.command_metrics.measure("task_status_get_all", async {
+        let statuses = sqlx::query_as::<_, (String, String, String, String, i32, String)>(
+            r#"
+            SELECT id, project_id, name, category, sort_order, created_at
+            FROM task_statuses
+            WHERE project_id = ?
+            ORDER BY sort_order ASC
+            "#,
+        )
+        .bind(&project_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(statuses
+            .into_iter()
+            .map(|s| TaskStatusResponse {
+                id: s.0,
+                project_id: s.1,
+                name: s.2,
+                category: s.3,
+                sort_order: s.4,
+                created_at: s.5,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Add a column to the end of a project's board
+#[tauri::command]
+pub async fn task_status_create(
+    state: State<'_, AppState>,
+    project_id: String,
+    name: String,
+    category: String,
+) -> Result<TaskStatusResponse, AppError> {
+    state.command_metrics.measure("task_status_create", async {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(AppError::invalid_input("Column name cannot be empty"));
+        }
+        if !STATUS_CATEGORIES.contains(&category.as_str()) {
+            return Err(AppError::invalid_input("Invalid status category"));
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+
+        let max_order: Option<i32> = sqlx::query_scalar(
+            "SELECT MAX(sort_order) FROM task_statuses WHERE project_id = ?",
+        )
+        .bind(&project_id)
+        .fetch_one(&state.db)
+        .await?;
+
+        let sort_order = max_order.map_or(0, |o| o + 1);
+
+        sqlx::query(
+            r#"
+            INSERT INTO task_statuses (id, project_id, name, category, sort_order, created_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&project_id)
+        .bind(&name)
+        .bind(&category)
+        .bind(sort_order)
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
+
+        Ok(TaskStatusResponse {
+            id,
+            project_id,
+            name,
+            category,
+            sort_order,
+            created_at: now,
+        })
+    })
+    .await
+}
+
+/// Rename a column
+#[tauri::command]
+pub async fn task_status_rename(
+    state: State<'_, AppState>,
+    status_id: String,
+    name: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("task_status_rename", async {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(AppError::invalid_input("Column name cannot be empty"));
+        }
+
+        let result = sqlx::query("UPDATE task_statuses SET name = ? WHERE id = ?")
+            .bind(&name)
+            .bind(&status_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Task status", &status_id));
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Reorder a project's columns
+#[tauri::command]
+pub async fn task_status_reorder(
+    state: State<'_, AppState>,
+    status_ids: Vec<String>,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("task_status_reorder", async {
+        for (index, id) in status_ids.iter().enumerate() {
+            sqlx::query("UPDATE task_statuses SET sort_order = ? WHERE id = ?")
+                .bind(index as i32)
+                .bind(id)
+                .execute(&state.db)
+                .await?;
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Delete a column, moving its tasks to another column of the same project
+///
+/// Each category must keep at least one column so tasks can always be placed.
+#[tauri::command]
+pub async fn task_status_delete(
+    state: State<'_, AppState>,
+    status_id: String,
+    move_to_status_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("task_status_delete", async {
+        if status_id == move_to_status_id {
+            return Err(AppError::invalid_input("Tasks must move to a different column"));
+        }
+
+        let (project_id, category): (String, String) = sqlx::query_as(
+            "SELECT project_id, category FROM task_statuses WHERE id = ?",
+        )
+        .bind(&status_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Task status", &status_id))?;
+
+        let target_category = status_category(&state.db, &project_id, &move_to_status_id).await?;
+
+        let siblings: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM task_statuses WHERE project_id = ? AND category = ?",
+        )
+        .bind(&project_id)
+        .bind(&category)
+        .fetch_one(&state.db)
+        .await?;
+
+        if siblings <= 1 {
+            return Err(AppError::invalid_input(format!(
+                "Cannot delete the last column in the {} category",
+                category
+            )));
+        }
+
+        // Moving across categories changes the tasks' status, which is tracked in history
+        let moved: Vec<String> = if target_category != category {
+            sqlx::query_scalar("SELECT id FROM tasks WHERE status_id = ?")
+                .bind(&status_id)
+                .fetch_all(&state.db)
+                .await?
+        } else {
+            Vec::new()
+        };
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let mut tx = state.db.begin().await?;
+
+        sqlx::query("UPDATE tasks SET status_id = ?, status = ?, updated_at = ? WHERE status_id = ?")
+            .bind(&move_to_status_id)
+            .bind(&target_category)
+            .bind(&now)
+            .bind(&status_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM task_statuses WHERE id = ?")
+            .bind(&status_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        for task_id in moved {
+            record_task_change(
+                &state.db,
+                &task_id,
+                &project_id,
+                "status",
+                Some(&category),
+                Some(&target_category),
+                "user",
+                &now,
+            )
+            .await?;
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Create the default columns for a new project
+pub(crate) async fn seed_default_statuses(
+    db: &SqlitePool,
+    project_id: &str,
+    now: &str,
+) -> Result<(), AppError> {
+    for (index, (name, category)) in DEFAULT_STATUSES.iter().enumerate() {
+        sqlx::query(
+            r#"
+            INSERT INTO task_statuses (id, project_id, name, category, sort_order, created_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(project_id)
+        .bind(name)
+        .bind(category)
+        .bind(index as i32)
+        .bind(now)
+        .execute(db)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Get the category of a column, checking it belongs to the project
+pub(crate) async fn status_category<'e>(
+    db: impl sqlx::Executor<'e, Database = sqlx::Sqlite>,
+    project_id: &str,
+    status_id: &str,
+) -> Result<String, AppError> {
+    let (status_project, category): (String, String) = sqlx::query_as(
+        "SELECT project_id, category FROM task_statuses WHERE id = ?",
+    )
+    .bind(status_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| AppError::database_not_found("Task status", status_id))?;
+
+    if status_project != project_id {
+        return Err(AppError::invalid_input("Column belongs to a different project"));
+    }
+
+    Ok(category)
+}
+
+/// Get the first column of a category in board order
+pub(crate) async fn default_status_for<'e>(
+    db: impl sqlx::Executor<'e, Database = sqlx::Sqlite>,
+    project_id: &str,
+    category: &str,
+) -> Result<Option<String>, AppError> {
+    let status_id = sqlx::query_scalar(
+        r#"
+        SELECT id FROM task_statuses
+        WHERE project_id = ? AND category = ?
+        ORDER BY sort_order ASC
+        LIMIT 1
+        "#,
+    )
+    .bind(project_id)
+    .bind(category)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(status_id)
+}
@@ -0,0 +1,88 @@
+//! Terminal Commands
+//!
+//! Commands for the interactive shells opened in a session's working
+//! directory. Output arrives as `terminal_output` events.
+
+use std::path::Path;
+
+use tauri::{AppHandle, State};
+
+use crate::error::AppError;
+use crate::state::AppState;
+use crate::terminal::TerminalInfo;
+
+use super::session::fetch_session;
+
+/// Open a shell in a session's working directory
+#[tauri::command]
+pub async fn terminal_create(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    session_id: String,
+    cols: Option<u16>,
+    rows: Option<u16>,
+) -> Result<TerminalInfo, AppError> {
+    state.command_metrics.measure("terminal_create", async {
+        let session = fetch_session(&state.db, &session_id).await?;
+
+        state.terminals.create(
+            &app,
+            &session_id,
+            Path::new(&session.working_directory),
+            cols,
+            rows,
+        )
+    })
+    .await
+}
+
+/// Send keystrokes or pasted text to a terminal
+#[tauri::command]
+pub async fn terminal_write(
+    state: State<'_, AppState>,
+    terminal_id: String,
+    data: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("terminal_write", async {
+        state.terminals.write(&terminal_id, &data).await
+    })
+    .await
+}
+
+/// Resize a terminal to the columns and rows its view shows
+#[tauri::command]
+pub async fn terminal_resize(
+    state: State<'_, AppState>,
+    terminal_id: String,
+    cols: u16,
+    rows: u16,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("terminal_resize", async {
+        state.terminals.resize(&terminal_id, cols, rows)
+    })
+    .await
+}
+
+/// Kill a terminal's shell
+#[tauri::command]
+pub async fn terminal_kill(
+    state: State<'_, AppState>,
+    terminal_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("terminal_kill", async {
+        state.terminals.kill(&terminal_id)
+    })
+    .await
+}
+
+/// List open terminals, optionally only a session's
+#[tauri::command]
+pub async fn terminal_list(
+    state: State<'_, AppState>,
+    session_id: Option<String>,
+) -> Result<Vec<TerminalInfo>, AppError> {
+    state.command_metrics.measure("terminal_list", async {
+        Ok(state.terminals.list(session_id.as_deref()))
+    })
+    .await
+}
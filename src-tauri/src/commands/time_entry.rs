@@ -0,0 +1,220 @@
+//! Time Tracking Commands
+//!
+//! Timers record time spent on a task as entries in `time_entries`. A task
+//! has at most one running timer. Recorded time is rolled up into
+//! `actual_hours` on each task so it can be compared with its estimate.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// A span of time spent on a task
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeEntryResponse {
+    pub id: String,
+    pub task_id: String,
+    pub started_at: String,
+    /// Unset while the timer is running
+    pub ended_at: Option<String>,
+    pub duration_seconds: Option<i64>,
+}
+
+/// Time recorded on a task
+#[derive(Debug, Default)]
+pub(crate) struct TaskTime {
+    /// Finished entries plus the running timer so far
+    pub actual_hours: f64,
+    pub timer_started_at: Option<String>,
+}
+
+/// Start a timer on a task
+#[tauri::command]
+pub async fn task_timer_start(
+    state: State<'_, AppState>,
+    task_id: String,
+) -> Result<TimeEntryResponse, AppError> {
+    state.command_metrics.measure("task_timer_start", async {
+        let project_id: String =
+            sqlx::query_scalar("SELECT project_id FROM tasks WHERE id = ? AND deleted_at IS NULL")
+                .bind(&task_id)
+                .fetch_optional(&state.db)
+                .await?
+                .ok_or_else(|| AppError::database_not_found("Task", &task_id))?;
+
+        if running_entry(&state.db, &task_id).await?.is_some() {
+            return Err(AppError::invalid_input("A timer is already running for this task"));
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+        sqlx::query(
+            r#"
+            INSERT INTO time_entries (id, task_id, project_id, started_at, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&task_id)
+        .bind(&project_id)
+        .bind(&now)
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
+
+        Ok(TimeEntryResponse {
+            id,
+            task_id,
+            started_at: now,
+            ended_at: None,
+            duration_seconds: None,
+        })
+    })
+    .await
+}
+
+/// Stop a task's running timer
+#[tauri::command]
+pub async fn task_timer_stop(
+    state: State<'_, AppState>,
+    task_id: String,
+) -> Result<TimeEntryResponse, AppError> {
+    state.command_metrics.measure("task_timer_stop", async {
+        let (id, started_at) = running_entry(&state.db, &task_id)
+            .await?
+            .ok_or_else(|| AppError::invalid_input("No timer is running for this task"))?;
+
+        let now = Utc::now();
+        let duration_seconds = elapsed_seconds(&started_at, now);
+        let ended_at = now.to_rfc3339();
+        sqlx::query("UPDATE time_entries SET ended_at = ?, duration_seconds = ? WHERE id = ?")
+            .bind(&ended_at)
+            .bind(duration_seconds)
+            .bind(&id)
+            .execute(&state.db)
+            .await?;
+
+        Ok(TimeEntryResponse {
+            id,
+            task_id,
+            started_at,
+            ended_at: Some(ended_at),
+            duration_seconds: Some(duration_seconds),
+        })
+    })
+    .await
+}
+
+/// Get a task's time entries, newest first
+#[tauri::command]
+pub async fn task_time_entries_get(
+    state: State<'_, AppState>,
+    task_id: String,
+) -> Result<Vec<TimeEntryResponse>, AppError> {
+    state.command_metrics.measure("task_time_entries_get", async {
+        let rows = sqlx::query_as::<_, (String, String, String, Option<String>, Option<i64>)>(
+            r#"
+            SELECT id, task_id, started_at, ended_at, duration_seconds
+            FROM time_entries
+            WHERE task_id = ?
+            ORDER BY started_at DESC
+            "#,
+        )
+        .bind(&task_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, task_id, started_at, ended_at, duration_seconds)| TimeEntryResponse {
+                id,
+                task_id,
+                started_at,
+                ended_at,
+                duration_seconds,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// The ID and start of a task's running timer
+async fn running_entry(db: &SqlitePool, task_id: &str) -> Result<Option<(String, String)>, AppError> {
+    let entry: Option<(String, String)> =
+        sqlx::query_as("SELECT id, started_at FROM time_entries WHERE task_id = ? AND ended_at IS NULL")
+            .bind(task_id)
+            .fetch_optional(db)
+            .await?;
+    Ok(entry)
+}
+
+/// Time recorded on each of a project's tasks that has any
+pub(crate) async fn task_time_map(db: &SqlitePool, project_id: &str) -> Result<HashMap<String, TaskTime>, AppError> {
+    let rows = sqlx::query_as::<_, (String, i64, Option<String>)>(
+        r#"
+        SELECT task_id, COALESCE(SUM(duration_seconds), 0), MAX(CASE WHEN ended_at IS NULL THEN started_at END)
+        FROM time_entries
+        WHERE project_id = ?
+        GROUP BY task_id
+        "#,
+    )
+    .bind(project_id)
+    .fetch_all(db)
+    .await?;
+
+    let now = Utc::now();
+    Ok(rows
+        .into_iter()
+        .map(|(task_id, recorded_seconds, timer_started_at)| {
+            let running_seconds = timer_started_at
+                .as_deref()
+                .map_or(0, |started_at| elapsed_seconds(started_at, now));
+            let time = TaskTime {
+                actual_hours: seconds_to_hours(recorded_seconds + running_seconds),
+                timer_started_at,
+            };
+            (task_id, time)
+        })
+        .collect())
+}
+
+/// Whole seconds from an RFC 3339 timestamp to `now`, never negative
+fn elapsed_seconds(started_at: &str, now: DateTime<Utc>) -> i64 {
+    DateTime::parse_from_rfc3339(started_at)
+        .map(|started_at| (now - started_at.with_timezone(&Utc)).num_seconds().max(0))
+        .unwrap_or(0)
+}
+
+/// Hours rounded to the hundredth
+fn seconds_to_hours(seconds: i64) -> f64 {
+    (seconds as f64 / 36.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_elapsed_seconds() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        assert_eq!(elapsed_seconds("2024-01-01T11:30:00Z", now), 1800);
+        assert_eq!(elapsed_seconds("2024-01-01T13:30:00+02:00", now), 1800);
+        // Clock changes never make time negative
+        assert_eq!(elapsed_seconds("2024-01-01T12:05:00Z", now), 0);
+        assert_eq!(elapsed_seconds("not a time", now), 0);
+    }
+
+    #[test]
+    fn test_seconds_to_hours() {
+        assert_eq!(seconds_to_hours(0), 0.0);
+        assert_eq!(seconds_to_hours(5400), 1.5);
+        assert_eq!(seconds_to_hours(1000), 0.28);
+    }
+}
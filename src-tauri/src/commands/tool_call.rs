@@ -0,0 +1,230 @@
+//! Tool Call Commands
+//!
+//! Each tool Claude runs is recorded in `tool_calls` as its `tool_use` and
+//! `tool_result` events stream in, so the UI can list and count them
+//! without parsing the JSON kept in `messages.tool_usage`.
+
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// Most of a tool's result kept, from the start
+const MAX_RESULT_BYTES: usize = 16 * 1024;
+
+/// Default number of tool calls listed
+const DEFAULT_LIST_LIMIT: i64 = 200;
+
+/// A tool Claude ran
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCallResponse {
+    pub id: String,
+    pub session_id: String,
+    pub message_id: String,
+    pub tool_name: String,
+    pub input: Value,
+    pub result: Option<String>,
+    /// `running`, `succeeded`, or `failed`
+    pub status: String,
+    pub duration_ms: Option<i64>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+/// How often a session used one tool
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCallStats {
+    pub tool_name: String,
+    pub calls: i64,
+    pub failed: i64,
+    /// Average over the calls that finished
+    pub avg_duration_ms: Option<f64>,
+    pub total_duration_ms: i64,
+}
+
+/// List the tools Claude ran in a session or one message, oldest first
+///
+/// Pass `tool_name` to list only one tool's calls.
+#[tauri::command]
+pub async fn tool_calls_get(
+    state: State<'_, AppState>,
+    session_id: Option<String>,
+    message_id: Option<String>,
+    tool_name: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<ToolCallResponse>, AppError> {
+    state.command_metrics.measure("tool_calls_get", async {
+        if session_id.is_none() && message_id.is_none() {
+            return Err(AppError::invalid_input("A session or message ID is required"));
+        }
+
+        let rows = sqlx::query_as::<_, ToolCallRow>(
+            r#"
+            SELECT id, session_id, message_id, tool_name, input, result, status, duration_ms, started_at, finished_at
+            FROM tool_calls
+            WHERE (?1 IS NULL OR session_id = ?1)
+              AND (?2 IS NULL OR message_id = ?2)
+              AND (?3 IS NULL OR tool_name = ?3)
+            ORDER BY started_at ASC
+            LIMIT ?4
+            "#,
+        )
+        .bind(&session_id)
+        .bind(&message_id)
+        .bind(&tool_name)
+        .bind(limit.unwrap_or(DEFAULT_LIST_LIMIT))
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(rows.into_iter().map(call_response).collect())
+    })
+    .await
+}
+
+/// Count a session's tool calls per tool, most used first
+#[tauri::command]
+pub async fn tool_calls_stats(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Vec<ToolCallStats>, AppError> {
+    state.command_metrics.measure("tool_calls_stats", async {
+        let rows = sqlx::query_as::<_, (String, i64, i64, Option<f64>, i64)>(
+            r#"
+            SELECT tool_name,
+                   COUNT(*),
+                   SUM(status = 'failed'),
+                   AVG(duration_ms),
+                   COALESCE(SUM(duration_ms), 0)
+            FROM tool_calls
+            WHERE session_id = ?
+            GROUP BY tool_name
+            ORDER BY COUNT(*) DESC, tool_name ASC
+            "#,
+        )
+        .bind(&session_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(tool_name, calls, failed, avg_duration_ms, total_duration_ms)| ToolCallStats {
+                tool_name,
+                calls,
+                failed,
+                avg_duration_ms,
+                total_duration_ms,
+            })
+            .collect())
+    })
+    .await
+}
+
+type ToolCallRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+    String,
+    Option<i64>,
+    String,
+    Option<String>,
+);
+
+fn call_response(row: ToolCallRow) -> ToolCallResponse {
+    ToolCallResponse {
+        id: row.0,
+        session_id: row.1,
+        message_id: row.2,
+        tool_name: row.3,
+        input: serde_json::from_str(&row.4).unwrap_or(Value::Null),
+        result: row.5,
+        status: row.6,
+        duration_ms: row.7,
+        started_at: row.8,
+        finished_at: row.9,
+    }
+}
+
+/// Record a tool Claude started
+pub(crate) async fn record_tool_use(
+    db: &SqlitePool,
+    session_id: &str,
+    message_id: &str,
+    tool_use_id: Option<&str>,
+    tool_name: &str,
+    input: &Value,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        INSERT INTO tool_calls (id, session_id, message_id, tool_use_id, tool_name, input, status, started_at)
+        VALUES (?, ?, ?, ?, ?, ?, 'running', ?)
+        "#,
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(session_id)
+    .bind(message_id)
+    .bind(tool_use_id)
+    .bind(tool_name)
+    .bind(input.to_string())
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Record the result of a tool call started with `record_tool_use`
+pub(crate) async fn record_tool_result(
+    db: &SqlitePool,
+    session_id: &str,
+    tool_use_id: &str,
+    result: &str,
+    is_error: bool,
+    duration_ms: Option<i64>,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        UPDATE tool_calls SET result = ?, status = ?, duration_ms = ?, finished_at = ?
+        WHERE session_id = ? AND tool_use_id = ? AND status = 'running'
+        "#,
+    )
+    .bind(result_head(result, MAX_RESULT_BYTES))
+    .bind(if is_error { "failed" } else { "succeeded" })
+    .bind(duration_ms)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .bind(session_id)
+    .bind(tool_use_id)
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// The first `max_bytes` of a result, ending on a character boundary
+fn result_head(result: &str, max_bytes: usize) -> &str {
+    let mut end = result.len().min(max_bytes);
+    while !result.is_char_boundary(end) {
+        end -= 1;
+    }
+    &result[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_result_head() {
+        assert_eq!(result_head("short", 64), "short");
+        assert_eq!(result_head("abcdef", 3), "abc");
+        // Never splits a multi-byte character
+        assert_eq!(result_head("aé", 2), "a");
+    }
+}
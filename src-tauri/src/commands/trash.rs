@@ -0,0 +1,622 @@
+//! Trash Commands
+//!
+//! Deleting a project, milestone, sprint, or task moves it to the trash by
+//! setting `deleted_at`. Rows deleted along with it share the same
+//! timestamp, which is how a restore finds them again. Trashed items are
+//! purged for good once they are older than the retention window.
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::db::settings::{self, TRASH_RETENTION_DAYS};
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// Days trashed items are kept when no retention is configured
+pub const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+/// A trashed item
+///
+/// Rows trashed along with a parent aren't listed separately.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashItemResponse {
+    /// `project`, `milestone`, `sprint`, or `task`
+    pub entity: String,
+    pub id: String,
+    pub name: String,
+    pub project_id: String,
+    pub deleted_at: String,
+    /// When the item is purged, or `None` if the trash is kept forever
+    pub expires_at: Option<String>,
+}
+
+/// List trashed items, newest first, optionally for one project
+#[tauri::command]
+pub async fn trash_list(
+    state: State<'_, AppState>,
+    project_id: Option<String>,
+) -> Result<Vec<TrashItemResponse>, AppError> {
+    state.command_metrics.measure("trash_list", async {
+        let rows = sqlx::query_as::<_, (String, String, String, String, String)>(
+            r#"
+            SELECT 'project', id, name, id, deleted_at FROM projects
+            WHERE deleted_at IS NOT NULL
+            UNION ALL
+            SELECT 'milestone', m.id, m.name, m.project_id, m.deleted_at FROM milestones m
+            WHERE m.deleted_at IS NOT NULL
+              AND NOT EXISTS (SELECT 1 FROM projects p WHERE p.id = m.project_id AND p.deleted_at = m.deleted_at)
+            UNION ALL
+            SELECT 'sprint', s.id, s.name, s.project_id, s.deleted_at FROM sprints s
+            WHERE s.deleted_at IS NOT NULL
+              AND NOT EXISTS (SELECT 1 FROM projects p WHERE p.id = s.project_id AND p.deleted_at = s.deleted_at)
+            UNION ALL
+            SELECT 'task', t.id, t.title, t.project_id, t.deleted_at FROM tasks t
+            WHERE t.deleted_at IS NOT NULL
+              AND NOT EXISTS (SELECT 1 FROM projects p WHERE p.id = t.project_id AND p.deleted_at = t.deleted_at)
+              AND NOT EXISTS (SELECT 1 FROM tasks pt WHERE pt.id = t.parent_task_id AND pt.deleted_at = t.deleted_at)
+            ORDER BY 5 DESC
+            "#,
+        )
+        .fetch_all(&state.db)
+        .await?;
+
+        let retention_days = retention_days(&state.db).await?;
+
+        Ok(rows
+            .into_iter()
+            .filter(|row| project_id.as_ref().is_none_or(|p| *p == row.3))
+            .map(|row| TrashItemResponse {
+                expires_at: expires_at(&row.4, retention_days),
+                entity: row.0,
+                id: row.1,
+                name: row.2,
+                project_id: row.3,
+                deleted_at: row.4,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Restore a trashed item and everything trashed along with it
+///
+/// Milestones, sprints, and tasks can only be restored while their project
+/// is live, and subtasks while their parent task is. Sprints and tasks that
+/// were detached when their milestone or sprint was trashed stay detached.
+#[tauri::command]
+pub async fn trash_restore(
+    state: State<'_, AppState>,
+    entity: String,
+    id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("trash_restore", async {
+        restore(&state.db, &entity, &id).await
+    })
+    .await
+}
+
+/// Permanently delete trashed items
+///
+/// With `entity` and `id`, purges that item and everything trashed along
+/// with it. Without them, purges every item older than the retention window.
+/// Returns the number of items purged.
+#[tauri::command]
+pub async fn trash_purge(
+    state: State<'_, AppState>,
+    entity: Option<String>,
+    id: Option<String>,
+) -> Result<u64, AppError> {
+    state.command_metrics.measure("trash_purge", async {
+        match (entity, id) {
+            (Some(entity), Some(id)) => purge_item(&state.db, &entity, &id).await,
+            (None, None) => purge_expired(&state.db).await,
+            _ => Err(AppError::invalid_input("Pass both entity and id, or neither")),
+        }
+    })
+    .await
+}
+
+/// Get how many days trashed items are kept (0 = forever)
+#[tauri::command]
+pub async fn trash_get_retention_days(state: State<'_, AppState>) -> Result<u32, AppError> {
+    state.command_metrics.measure("trash_get_retention_days", async {
+        retention_days(&state.db).await
+    })
+    .await
+}
+
+/// Set how many days trashed items are kept (0 = forever)
+#[tauri::command]
+pub async fn trash_set_retention_days(state: State<'_, AppState>, days: u32) -> Result<(), AppError> {
+    state.command_metrics.measure("trash_set_retention_days", async {
+        if days > 3650 {
+            return Err(AppError::invalid_input("Retention must be at most 3650 days"));
+        }
+
+        settings::set_setting(&state.db, TRASH_RETENTION_DAYS, &days.to_string()).await
+    })
+    .await
+}
+
+/// Restore a trashed item and everything trashed along with it
+async fn restore(db: &SqlitePool, entity: &str, id: &str) -> Result<(), AppError> {
+    let table = entity_table(entity)?;
+    let deleted_at = trashed_at(db, table, entity, id).await?;
+
+    if entity != "project" {
+        let project_live: bool = sqlx::query_scalar(&format!(
+            "SELECT p.deleted_at IS NULL FROM {} x JOIN projects p ON p.id = x.project_id WHERE x.id = ?",
+            table
+        ))
+        .bind(id)
+        .fetch_one(db)
+        .await?;
+        if !project_live {
+            return Err(AppError::invalid_input("Restore the project first"));
+        }
+    }
+
+    let mut tx = db.begin().await?;
+
+    match entity {
+        "project" => {
+            for table in ["projects", "milestones", "sprints", "tasks"] {
+                let column = if table == "projects" { "id" } else { "project_id" };
+                sqlx::query(&format!(
+                    "UPDATE {} SET deleted_at = NULL WHERE {} = ? AND deleted_at = ?",
+                    table, column
+                ))
+                .bind(id)
+                .bind(&deleted_at)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+        "task" => {
+            let parent_trashed: bool = sqlx::query_scalar(
+                r#"
+                SELECT EXISTS(
+                    SELECT 1 FROM tasks t JOIN tasks pt ON pt.id = t.parent_task_id
+                    WHERE t.id = ? AND pt.deleted_at IS NOT NULL
+                )
+                "#,
+            )
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+            if parent_trashed {
+                return Err(AppError::invalid_input("Restore the parent task first"));
+            }
+
+            sqlx::query(
+                r#"
+                WITH RECURSIVE subtree(id) AS (
+                    SELECT ?1
+                    UNION ALL
+                    SELECT t.id FROM tasks t JOIN subtree s ON t.parent_task_id = s.id
+                    WHERE t.deleted_at = ?2
+                )
+                UPDATE tasks SET deleted_at = NULL WHERE id IN (SELECT id FROM subtree)
+                "#,
+            )
+            .bind(id)
+            .bind(&deleted_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        _ => {
+            sqlx::query(&format!("UPDATE {} SET deleted_at = NULL WHERE id = ?", table))
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Permanently delete a trashed item and everything trashed along with it
+async fn purge_item(db: &SqlitePool, entity: &str, id: &str) -> Result<u64, AppError> {
+    let table = entity_table(entity)?;
+    trashed_at(db, table, entity, id).await?;
+
+    // Children trashed with the item go through foreign key cascades
+    sqlx::query(&format!("DELETE FROM {} WHERE id = ?", table))
+        .bind(id)
+        .execute(db)
+        .await?;
+    Ok(1)
+}
+
+/// Move a project and everything in it to the trash
+pub(crate) async fn trash_project(db: &SqlitePool, project_id: &str) -> Result<(), AppError> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut tx = db.begin().await?;
+
+    let result = sqlx::query("UPDATE projects SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL")
+        .bind(&now)
+        .bind(project_id)
+        .execute(&mut *tx)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::database_not_found("Project", project_id));
+    }
+
+    for table in ["milestones", "sprints", "tasks"] {
+        sqlx::query(&format!(
+            "UPDATE {} SET deleted_at = ? WHERE project_id = ? AND deleted_at IS NULL",
+            table
+        ))
+        .bind(&now)
+        .bind(project_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Move a milestone to the trash, detaching its sprints as a delete would
+pub(crate) async fn trash_milestone(db: &SqlitePool, milestone_id: &str) -> Result<(), AppError> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut tx = db.begin().await?;
+
+    let result = sqlx::query("UPDATE milestones SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL")
+        .bind(&now)
+        .bind(milestone_id)
+        .execute(&mut *tx)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::database_not_found("Milestone", milestone_id));
+    }
+
+    sqlx::query("UPDATE sprints SET milestone_id = NULL, updated_at = ? WHERE milestone_id = ?")
+        .bind(&now)
+        .bind(milestone_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Move a sprint to the trash, moving its tasks to the backlog as a delete would
+pub(crate) async fn trash_sprint(db: &SqlitePool, sprint_id: &str) -> Result<(), AppError> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut tx = db.begin().await?;
+
+    let result = sqlx::query("UPDATE sprints SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL")
+        .bind(&now)
+        .bind(sprint_id)
+        .execute(&mut *tx)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::database_not_found("Sprint", sprint_id));
+    }
+
+    sqlx::query("UPDATE tasks SET sprint_id = NULL, updated_at = ? WHERE sprint_id = ?")
+        .bind(&now)
+        .bind(sprint_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Move a task and its subtasks to the trash
+///
+/// With `keep_subtasks`, the subtasks move up to the task's parent instead.
+pub(crate) async fn trash_task(db: &SqlitePool, task_id: &str, keep_subtasks: bool) -> Result<(), AppError> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut tx = db.begin().await?;
+
+    let exists: Option<String> = sqlx::query_scalar("SELECT id FROM tasks WHERE id = ? AND deleted_at IS NULL")
+        .bind(task_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+    if exists.is_none() {
+        return Err(AppError::database_not_found("Task", task_id));
+    }
+
+    if keep_subtasks {
+        sqlx::query(
+            r#"
+            UPDATE tasks
+            SET parent_task_id = (SELECT parent_task_id FROM tasks WHERE id = ?1), updated_at = ?2
+            WHERE parent_task_id = ?1 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(task_id)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query(
+        r#"
+        WITH RECURSIVE subtree(id) AS (
+            SELECT ?1
+            UNION ALL
+            SELECT t.id FROM tasks t JOIN subtree s ON t.parent_task_id = s.id
+            WHERE t.deleted_at IS NULL
+        )
+        UPDATE tasks SET deleted_at = ?2 WHERE id IN (SELECT id FROM subtree)
+        "#,
+    )
+    .bind(task_id)
+    .bind(&now)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Permanently delete trashed items older than the retention window
+pub(crate) async fn purge_expired(db: &SqlitePool) -> Result<u64, AppError> {
+    let days = retention_days(db).await?;
+    if days == 0 {
+        return Ok(0);
+    }
+
+    let cutoff = (chrono::Utc::now() - chrono::Duration::days(days.into())).to_rfc3339();
+    let mut purged = 0;
+    for table in ["projects", "milestones", "sprints", "tasks"] {
+        purged += sqlx::query(&format!("DELETE FROM {} WHERE deleted_at < ?", table))
+            .bind(&cutoff)
+            .execute(db)
+            .await?
+            .rows_affected();
+    }
+
+    if purged > 0 {
+        log::info!("Purged {} expired item(s) from the trash", purged);
+    }
+    Ok(purged)
+}
+
+async fn retention_days(db: &SqlitePool) -> Result<u32, AppError> {
+    Ok(settings::get_setting(db, TRASH_RETENTION_DAYS)
+        .await?
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_RETENTION_DAYS))
+}
+
+fn expires_at(deleted_at: &str, retention_days: u32) -> Option<String> {
+    if retention_days == 0 {
+        return None;
+    }
+    let deleted_at = chrono::DateTime::parse_from_rfc3339(deleted_at).ok()?;
+    Some((deleted_at + chrono::Duration::days(retention_days.into())).to_rfc3339())
+}
+
+/// Map an entity name to its table
+fn entity_table(entity: &str) -> Result<&'static str, AppError> {
+    match entity {
+        "project" => Ok("projects"),
+        "milestone" => Ok("milestones"),
+        "sprint" => Ok("sprints"),
+        "task" => Ok("tasks"),
+        _ => Err(AppError::invalid_input(format!(
+            "Unknown entity \"{}\"; expected project, milestone, sprint, or task",
+            entity
+        ))),
+    }
+}
+
+/// Get when an item was trashed, failing if it isn't in the trash
+async fn trashed_at(db: &SqlitePool, table: &str, entity: &str, id: &str) -> Result<String, AppError> {
+    sqlx::query_scalar::<_, Option<String>>(&format!("SELECT deleted_at FROM {} WHERE id = ?", table))
+        .bind(id)
+        .fetch_optional(db)
+        .await?
+        .flatten()
+        .ok_or_else(|| AppError::not_found(format!("No {} {} in the trash", entity, id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    const LONG_AGO: &str = "2000-01-01T00:00:00+00:00";
+
+    async fn insert_project(db: &SqlitePool, id: &str) {
+        sqlx::query(
+            "INSERT INTO projects (id, name, root_path, created_at, updated_at) VALUES (?1, ?1, '/tmp', ?2, ?2)",
+        )
+        .bind(id)
+        .bind(LONG_AGO)
+        .execute(db)
+        .await
+        .unwrap();
+    }
+
+    async fn insert_task(db: &SqlitePool, id: &str, sprint_id: Option<&str>, parent_task_id: Option<&str>) {
+        sqlx::query(
+            r#"
+            INSERT INTO tasks (id, project_id, sprint_id, parent_task_id, title, created_at, updated_at)
+            VALUES (?1, 'p1', ?2, ?3, ?1, ?4, ?4)
+            "#,
+        )
+        .bind(id)
+        .bind(sprint_id)
+        .bind(parent_task_id)
+        .bind(LONG_AGO)
+        .execute(db)
+        .await
+        .unwrap();
+    }
+
+    /// A project with a milestone, a sprint in it, a task in the sprint with
+    /// a subtask, and a second task
+    async fn seeded_pool() -> SqlitePool {
+        let db = test_pool().await;
+        insert_project(&db, "p1").await;
+        sqlx::query(
+            r#"
+            INSERT INTO milestones (id, project_id, name, created_at, updated_at)
+            VALUES ('m1', 'p1', 'M', ?1, ?1)
+            "#,
+        )
+        .bind(LONG_AGO)
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO sprints (id, project_id, milestone_id, name, created_at, updated_at)
+            VALUES ('s1', 'p1', 'm1', 'S', ?1, ?1)
+            "#,
+        )
+        .bind(LONG_AGO)
+        .execute(&db)
+        .await
+        .unwrap();
+        insert_task(&db, "t1", Some("s1"), None).await;
+        insert_task(&db, "t1a", None, Some("t1")).await;
+        insert_task(&db, "t2", None, None).await;
+        db
+    }
+
+    async fn is_trashed(db: &SqlitePool, table: &str, id: &str) -> bool {
+        sqlx::query_scalar::<_, bool>(&format!("SELECT deleted_at IS NOT NULL FROM {} WHERE id = ?", table))
+            .bind(id)
+            .fetch_one(db)
+            .await
+            .unwrap()
+    }
+
+    async fn count(db: &SqlitePool, table: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(db)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_restore_project() {
+        let db = seeded_pool().await;
+        // Trashed on its own before the project was
+        sqlx::query("UPDATE tasks SET deleted_at = ? WHERE id = 't2'")
+            .bind(LONG_AGO)
+            .execute(&db)
+            .await
+            .unwrap();
+        trash_project(&db, "p1").await.unwrap();
+        assert!(is_trashed(&db, "milestones", "m1").await);
+        assert!(is_trashed(&db, "tasks", "t1a").await);
+
+        restore(&db, "project", "p1").await.unwrap();
+        let restored = [("projects", "p1"), ("milestones", "m1"), ("sprints", "s1"), ("tasks", "t1"), ("tasks", "t1a")];
+        for (table, id) in restored {
+            assert!(!is_trashed(&db, table, id).await, "{} {}", table, id);
+        }
+        assert!(is_trashed(&db, "tasks", "t2").await);
+
+        let error = restore(&db, "project", "p1").await.unwrap_err();
+        assert!(error.message.contains("in the trash"));
+    }
+
+    #[tokio::test]
+    async fn test_restore_needs_a_live_project_and_parent() {
+        let db = seeded_pool().await;
+        trash_project(&db, "p1").await.unwrap();
+        let error = restore(&db, "task", "t1").await.unwrap_err();
+        assert!(error.message.contains("Restore the project first"));
+        restore(&db, "project", "p1").await.unwrap();
+
+        trash_task(&db, "t1", false).await.unwrap();
+        let error = restore(&db, "task", "t1a").await.unwrap_err();
+        assert!(error.message.contains("Restore the parent task first"));
+    }
+
+    #[tokio::test]
+    async fn test_restore_task_with_subtasks() {
+        let db = seeded_pool().await;
+        trash_task(&db, "t1", false).await.unwrap();
+        assert!(is_trashed(&db, "tasks", "t1a").await);
+
+        restore(&db, "task", "t1").await.unwrap();
+        assert!(!is_trashed(&db, "tasks", "t1").await);
+        assert!(!is_trashed(&db, "tasks", "t1a").await);
+        assert!(!is_trashed(&db, "tasks", "t2").await);
+    }
+
+    #[tokio::test]
+    async fn test_restore_sprint_leaves_tasks_detached() {
+        let db = seeded_pool().await;
+        trash_sprint(&db, "s1").await.unwrap();
+        restore(&db, "sprint", "s1").await.unwrap();
+
+        assert!(!is_trashed(&db, "sprints", "s1").await);
+        let sprint_id: Option<String> = sqlx::query_scalar("SELECT sprint_id FROM tasks WHERE id = 't1'")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(sprint_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_purge_project_cascades() {
+        let db = seeded_pool().await;
+        assert!(purge_item(&db, "project", "p1").await.is_err());
+
+        trash_project(&db, "p1").await.unwrap();
+        assert_eq!(purge_item(&db, "project", "p1").await.unwrap(), 1);
+        for table in ["projects", "milestones", "sprints", "tasks"] {
+            assert_eq!(count(&db, table).await, 0, "{}", table);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_purge_task_cascades_to_subtasks() {
+        let db = seeded_pool().await;
+        trash_task(&db, "t1", false).await.unwrap();
+        purge_item(&db, "task", "t1").await.unwrap();
+
+        let remaining: Vec<String> = sqlx::query_scalar("SELECT id FROM tasks ORDER BY id")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert_eq!(remaining, ["t2"]);
+        assert_eq!(count(&db, "sprints").await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_purge_expired() {
+        let db = seeded_pool().await;
+        trash_task(&db, "t2", false).await.unwrap();
+        sqlx::query("UPDATE tasks SET deleted_at = ? WHERE id = 't1a'")
+            .bind(LONG_AGO)
+            .execute(&db)
+            .await
+            .unwrap();
+
+        assert_eq!(purge_expired(&db).await.unwrap(), 1);
+        assert_eq!(count(&db, "tasks").await, 2);
+
+        settings::set_setting(&db, TRASH_RETENTION_DAYS, "0").await.unwrap();
+        sqlx::query("UPDATE tasks SET deleted_at = ? WHERE id = 't2'")
+            .bind(LONG_AGO)
+            .execute(&db)
+            .await
+            .unwrap();
+        assert_eq!(purge_expired(&db).await.unwrap(), 0);
+    }
+
+    #[test]
+    fn test_expires_at() {
+        assert_eq!(
+            expires_at("2026-10-15T09:30:00+00:00", 30).as_deref(),
+            Some("2026-11-14T09:30:00+00:00")
+        );
+        assert_eq!(expires_at("2026-10-15T09:30:00+00:00", 0), None);
+    }
+}
@@ -0,0 +1,299 @@
+//! Verification Commands
+//!
+//! A project can name a verification command, such as `cargo test` or
+//! `npm test`, that is run in the session's directory whenever one of
+//! Claude's turns writes files. Each run is recorded in `verification_runs`
+//! and reported with `verification_started` and `verification_finished`
+//! events, so a broken change is caught as soon as it is made.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::error::AppError;
+use crate::events::{emit_event, event_names, VerificationPayload};
+use crate::hooks::shell_command;
+use crate::state::AppState;
+use crate::system;
+
+/// Longest a verification command may run before it counts as failed
+const VERIFICATION_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// Most output kept per run, from the end where failures are reported
+pub(crate) const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// Default number of runs listed
+const DEFAULT_LIST_LIMIT: i64 = 50;
+
+/// A run of a project's verification command
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationRunResponse {
+    pub id: String,
+    pub session_id: String,
+    pub project_id: String,
+    pub message_id: String,
+    pub command: String,
+    /// `running`, `passed`, or `failed`
+    pub status: String,
+    pub exit_code: Option<i32>,
+    pub output: String,
+    pub files: Vec<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+/// Get a project's verification command, if one is set
+#[tauri::command]
+pub async fn verification_get_command(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<Option<String>, AppError> {
+    state.command_metrics.measure("verification_get_command", async {
+        let command: Option<Option<String>> =
+            sqlx::query_scalar("SELECT verification_command FROM projects WHERE id = ?")
+                .bind(&project_id)
+                .fetch_optional(&state.db)
+                .await?;
+
+        command.ok_or_else(|| AppError::database_not_found("Project", &project_id))
+    })
+    .await
+}
+
+/// Set the command run after Claude's turns that write files
+///
+/// An empty or missing command turns verification off for the project.
+#[tauri::command]
+pub async fn verification_set_command(
+    state: State<'_, AppState>,
+    project_id: String,
+    command: Option<String>,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("verification_set_command", async {
+        let command = command.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+
+        let result = sqlx::query("UPDATE projects SET verification_command = ?, updated_at = ? WHERE id = ?")
+            .bind(&command)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(&project_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Project", &project_id));
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// List a project's verification runs, newest first
+///
+/// Pass `session_id` to list only the runs for one session.
+#[tauri::command]
+pub async fn verification_list(
+    state: State<'_, AppState>,
+    project_id: String,
+    session_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<VerificationRunResponse>, AppError> {
+    state.command_metrics.measure("verification_list", async {
+        let rows = sqlx::query_as::<_, VerificationRow>(
+            r#"
+            SELECT id, session_id, project_id, message_id, command, status, exit_code, output, files, started_at, finished_at
+            FROM verification_runs
+            WHERE project_id = ? AND (? IS NULL OR session_id = ?)
+            ORDER BY started_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(&project_id)
+        .bind(&session_id)
+        .bind(&session_id)
+        .bind(limit.unwrap_or(DEFAULT_LIST_LIMIT))
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(rows.into_iter().map(run_response).collect())
+    })
+    .await
+}
+
+/// Get a verification run with its output
+#[tauri::command]
+pub async fn verification_get(
+    state: State<'_, AppState>,
+    run_id: String,
+) -> Result<VerificationRunResponse, AppError> {
+    state.command_metrics.measure("verification_get", async {
+        let row = sqlx::query_as::<_, VerificationRow>(
+            r#"
+            SELECT id, session_id, project_id, message_id, command, status, exit_code, output, files, started_at, finished_at
+            FROM verification_runs
+            WHERE id = ?
+            "#,
+        )
+        .bind(&run_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Verification run", &run_id))?;
+
+        Ok(run_response(row))
+    })
+    .await
+}
+
+type VerificationRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    Option<i32>,
+    String,
+    String,
+    String,
+    Option<String>,
+);
+
+fn run_response(row: VerificationRow) -> VerificationRunResponse {
+    VerificationRunResponse {
+        id: row.0,
+        session_id: row.1,
+        project_id: row.2,
+        message_id: row.3,
+        command: row.4,
+        status: row.5,
+        exit_code: row.6,
+        output: row.7,
+        files: serde_json::from_str(&row.8).unwrap_or_default(),
+        started_at: row.9,
+        finished_at: row.10,
+    }
+}
+
+/// Run the project's verification command after a turn that wrote files
+///
+/// Does nothing when the session has no project or the project has no
+/// verification command.
+pub(crate) async fn verify_turn(
+    app: &AppHandle,
+    session_id: &str,
+    message_id: &str,
+    files: Vec<String>,
+) -> Result<(), AppError> {
+    let state = app.state::<AppState>();
+
+    let target = sqlx::query_as::<_, (String, String, Option<String>)>(
+        r#"
+        SELECT p.id, s.working_directory, p.verification_command
+        FROM sessions s
+        JOIN projects p ON p.id = s.project_id
+        WHERE s.id = ?
+        "#,
+    )
+    .bind(session_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some((project_id, working_directory, Some(command))) = target else {
+        return Ok(());
+    };
+
+    let run_id = uuid::Uuid::new_v4().to_string();
+    sqlx::query(
+        r#"
+        INSERT INTO verification_runs (id, session_id, project_id, message_id, command, status, files, started_at)
+        VALUES (?, ?, ?, ?, ?, 'running', ?, ?)
+        "#,
+    )
+    .bind(&run_id)
+    .bind(session_id)
+    .bind(&project_id)
+    .bind(message_id)
+    .bind(&command)
+    .bind(serde_json::to_string(&files)?)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&state.db)
+    .await?;
+
+    let mut payload = VerificationPayload {
+        run_id: run_id.clone(),
+        session_id: session_id.to_string(),
+        project_id,
+        command: command.clone(),
+        status: "running".to_string(),
+        exit_code: None,
+        files,
+    };
+    let _ = emit_event(app, event_names::VERIFICATION_STARTED, payload.clone());
+
+    let mut cmd = shell_command(&command);
+    system::env::apply(&mut cmd);
+    cmd.current_dir(&working_directory)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let (exit_code, output) = match tokio::time::timeout(VERIFICATION_TIMEOUT, cmd.output()).await {
+        Ok(Ok(output)) => {
+            let mut combined = String::from_utf8_lossy(&output.stdout).to_string();
+            combined.push_str(&String::from_utf8_lossy(&output.stderr));
+            (output.status.code(), combined)
+        }
+        Ok(Err(e)) => (None, format!("Failed to run verification command: {}", e)),
+        Err(_) => (
+            None,
+            format!("Verification command timed out after {} seconds", VERIFICATION_TIMEOUT.as_secs()),
+        ),
+    };
+    let status = if exit_code == Some(0) { "passed" } else { "failed" };
+
+    sqlx::query(
+        "UPDATE verification_runs SET status = ?, exit_code = ?, output = ?, finished_at = ? WHERE id = ?",
+    )
+    .bind(status)
+    .bind(exit_code)
+    .bind(output_tail(&output, MAX_OUTPUT_BYTES))
+    .bind(chrono::Utc::now().to_rfc3339())
+    .bind(&run_id)
+    .execute(&state.db)
+    .await?;
+
+    log::info!("Verification for session {} {}: {}", session_id, status, command);
+
+    payload.status = status.to_string();
+    payload.exit_code = exit_code;
+    let _ = emit_event(app, event_names::VERIFICATION_FINISHED, payload);
+
+    Ok(())
+}
+
+/// The last `max_bytes` of output, starting on a character boundary
+pub(crate) fn output_tail(output: &str, max_bytes: usize) -> &str {
+    let mut start = output.len().saturating_sub(max_bytes);
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    &output[start..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_tail() {
+        assert_eq!(output_tail("short", 64), "short");
+        assert_eq!(output_tail("abcdef", 3), "def");
+        // Never splits a multi-byte character
+        assert_eq!(output_tail("aé", 1), "");
+        assert_eq!(output_tail("aéb", 2), "b");
+    }
+}
@@ -0,0 +1,348 @@
+//! Webhook Commands
+//!
+//! Commands for managing the URLs project events are POSTed to. See
+//! [`crate::webhooks`] for how deliveries are made.
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::error::AppError;
+use crate::secrets;
+use crate::state::AppState;
+use crate::webhooks::{self, Delivery, DeliveryAttempt, WebhookContext, WebhookEvent};
+
+/// Delivery attempts returned when no limit is given
+const DEFAULT_DELIVERY_LIMIT: i64 = 50;
+
+/// Largest accepted delivery attempt limit
+const MAX_DELIVERY_LIMIT: i64 = 200;
+
+/// A URL project events are POSTed to
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookResponse {
+    pub id: String,
+    /// Unset for webhooks receiving events from every project
+    pub project_id: Option<String>,
+    pub name: String,
+    pub url: String,
+    /// Whether deliveries are signed; the secret itself is never returned
+    pub has_secret: bool,
+    pub events: Vec<String>,
+    pub enabled: bool,
+    pub last_delivery_at: Option<String>,
+    pub last_status_code: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookCreateRequest {
+    /// Unset to receive events from every project
+    pub project_id: Option<String>,
+    pub name: String,
+    pub url: String,
+    /// Stored in the OS keychain
+    pub secret: Option<String>,
+    pub events: Vec<String>,
+}
+
+/// Add a webhook
+#[tauri::command]
+pub async fn webhook_create(
+    state: State<'_, AppState>,
+    request: WebhookCreateRequest,
+) -> Result<WebhookResponse, AppError> {
+    state.command_metrics.measure("webhook_create", async {
+        let name = request.name.trim().to_string();
+        if name.is_empty() {
+            return Err(AppError::invalid_input("Webhook name cannot be empty"));
+        }
+        let url = request.url.trim().to_string();
+        webhooks::validate_url(&url)?;
+
+        let mut events: Vec<String> = Vec::new();
+        for event in &request.events {
+            if WebhookEvent::parse(event).is_none() {
+                let names: Vec<&str> = WebhookEvent::ALL.iter().map(WebhookEvent::as_str).collect();
+                return Err(AppError::invalid_input(format!(
+                    "Webhook events must be among: {}",
+                    names.join(", ")
+                )));
+            }
+            if !events.contains(event) {
+                events.push(event.clone());
+            }
+        }
+        if events.is_empty() {
+            return Err(AppError::invalid_input("Choose at least one event for the webhook"));
+        }
+
+        if let Some(project_id) = &request.project_id {
+            let project_exists: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ? AND deleted_at IS NULL)",
+            )
+            .bind(project_id)
+            .fetch_one(&state.db)
+            .await?;
+            if !project_exists {
+                return Err(AppError::database_not_found("Project", project_id));
+            }
+        }
+
+        let secret = request.secret.filter(|secret| !secret.is_empty());
+        let now = chrono::Utc::now().to_rfc3339();
+        let webhook = WebhookResponse {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_id: request.project_id,
+            name,
+            url,
+            has_secret: secret.is_some(),
+            events,
+            enabled: true,
+            last_delivery_at: None,
+            last_status_code: None,
+            last_error: None,
+            created_at: now.clone(),
+        };
+
+        if let Some(secret) = &secret {
+            secrets::set(&secrets::webhook_secret_name(&webhook.id), secret).await?;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO webhooks (id, project_id, name, url, has_secret, events, enabled, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, 1, ?, ?)
+            "#,
+        )
+        .bind(&webhook.id)
+        .bind(&webhook.project_id)
+        .bind(&webhook.name)
+        .bind(&webhook.url)
+        .bind(webhook.has_secret)
+        .bind(serde_json::to_string(&webhook.events).unwrap_or_else(|_| "[]".to_string()))
+        .bind(&now)
+        .bind(&now)
+        .execute(&state.db)
+        .await?;
+
+        Ok(webhook)
+    })
+    .await
+}
+
+/// List webhooks: a project's and those for every project, or all of them without a project
+#[tauri::command]
+pub async fn webhook_list(
+    state: State<'_, AppState>,
+    project_id: Option<String>,
+) -> Result<Vec<WebhookResponse>, AppError> {
+    state.command_metrics.measure("webhook_list", async {
+        let rows = sqlx::query_as::<
+            _,
+            (String, Option<String>, String, String, bool, String, bool, Option<String>, Option<i64>, Option<String>, String),
+        >(
+            r#"
+            SELECT id, project_id, name, url, has_secret, events, enabled,
+                   last_delivery_at, last_status_code, last_error, created_at
+            FROM webhooks
+            WHERE ?1 IS NULL OR project_id IS NULL OR project_id = ?1
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(&project_id)
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| WebhookResponse {
+                id: row.0,
+                project_id: row.1,
+                name: row.2,
+                url: row.3,
+                has_secret: row.4,
+                events: serde_json::from_str(&row.5).unwrap_or_default(),
+                enabled: row.6,
+                last_delivery_at: row.7,
+                last_status_code: row.8,
+                last_error: row.9,
+                created_at: row.10,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Turn a webhook on or off without deleting it
+#[tauri::command]
+pub async fn webhook_set_enabled(
+    state: State<'_, AppState>,
+    webhook_id: String,
+    enabled: bool,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("webhook_set_enabled", async {
+        let result = sqlx::query("UPDATE webhooks SET enabled = ?, updated_at = ? WHERE id = ?")
+            .bind(enabled)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(&webhook_id)
+            .execute(&state.db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::database_not_found("Webhook", &webhook_id));
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Delete a webhook
+#[tauri::command]
+pub async fn webhook_delete(
+    state: State<'_, AppState>,
+    webhook_id: String,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("webhook_delete", async {
+        let has_secret: bool = sqlx::query_scalar("SELECT has_secret FROM webhooks WHERE id = ?")
+            .bind(&webhook_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or_else(|| AppError::database_not_found("Webhook", &webhook_id))?;
+
+        sqlx::query("DELETE FROM webhooks WHERE id = ?")
+            .bind(&webhook_id)
+            .execute(&state.db)
+            .await?;
+        if has_secret {
+            secrets::delete(&secrets::webhook_secret_name(&webhook_id)).await?;
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Send a `ping` event to a webhook once, without retrying, and report how it went
+///
+/// Works on disabled webhooks too, so one can be checked before turning it on.
+#[tauri::command]
+pub async fn webhook_test(
+    state: State<'_, AppState>,
+    webhook_id: String,
+) -> Result<Delivery, AppError> {
+    state.command_metrics.measure("webhook_test", async {
+        let (project_id, url, has_secret) = sqlx::query_as::<_, (Option<String>, String, bool)>(
+            "SELECT project_id, url, has_secret FROM webhooks WHERE id = ?",
+        )
+        .bind(&webhook_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Webhook", &webhook_id))?;
+
+        let context = WebhookContext {
+            project_id,
+            ..Default::default()
+        };
+        let secret = webhooks::signing_secret(&webhook_id, has_secret).await?;
+        let payload = webhooks::build_payload(&state.db, WebhookEvent::Ping, context).await?;
+        let delivery = webhooks::deliver(
+            &state.db,
+            &webhook_id,
+            &url,
+            secret.as_deref(),
+            WebhookEvent::Ping,
+            &payload,
+            &[],
+        )
+        .await;
+        webhooks::record_delivery(&state.db, &webhook_id, &delivery).await?;
+
+        Ok(delivery)
+    })
+    .await
+}
+
+/// List a webhook's delivery attempts, newest first
+#[tauri::command]
+pub async fn webhook_list_deliveries(
+    state: State<'_, AppState>,
+    webhook_id: String,
+    limit: Option<i64>,
+) -> Result<Vec<DeliveryAttempt>, AppError> {
+    state.command_metrics.measure("webhook_list_deliveries", async {
+        let rows = sqlx::query_as::<
+            _,
+            (String, String, String, String, i64, bool, Option<i64>, Option<String>, Option<String>, String),
+        >(
+            r#"
+            SELECT delivery_id, webhook_id, event, payload, attempt, success,
+                   status_code, response_body, error, created_at
+            FROM webhook_deliveries
+            WHERE webhook_id = ?
+            ORDER BY id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(&webhook_id)
+        .bind(limit.unwrap_or(DEFAULT_DELIVERY_LIMIT).clamp(1, MAX_DELIVERY_LIMIT))
+        .fetch_all(&state.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DeliveryAttempt {
+                delivery_id: row.0,
+                webhook_id: row.1,
+                event: row.2,
+                payload: serde_json::from_str(&row.3).unwrap_or(serde_json::Value::Null),
+                attempt: row.4,
+                success: row.5,
+                status_code: row.6,
+                response_body: row.7,
+                error: row.8,
+                created_at: row.9,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Send an earlier delivery's payload to its webhook again, once, as a new delivery
+///
+/// Works on disabled webhooks too, like `webhook_test`.
+#[tauri::command]
+pub async fn webhook_redeliver(
+    state: State<'_, AppState>,
+    delivery_id: String,
+) -> Result<Delivery, AppError> {
+    state.command_metrics.measure("webhook_redeliver", async {
+        let (webhook_id, event, payload) = sqlx::query_as::<_, (String, String, String)>(
+            "SELECT webhook_id, event, payload FROM webhook_deliveries WHERE delivery_id = ? LIMIT 1",
+        )
+        .bind(&delivery_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Webhook delivery", &delivery_id))?;
+
+        let (url, has_secret) = sqlx::query_as::<_, (String, bool)>("SELECT url, has_secret FROM webhooks WHERE id = ?")
+            .bind(&webhook_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or_else(|| AppError::database_not_found("Webhook", &webhook_id))?;
+
+        let event = WebhookEvent::parse_sent(&event)
+            .ok_or_else(|| AppError::invalid_input(format!("Unknown webhook event: {}", event)))?;
+        let payload: serde_json::Value = serde_json::from_str(&payload)?;
+        let secret = webhooks::signing_secret(&webhook_id, has_secret).await?;
+        let delivery =
+            webhooks::deliver(&state.db, &webhook_id, &url, secret.as_deref(), event, &payload, &[]).await;
+        webhooks::record_delivery(&state.db, &webhook_id, &delivery).await?;
+
+        Ok(delivery)
+    })
+    .await
+}
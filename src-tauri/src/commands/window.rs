@@ -0,0 +1,82 @@
+//! Window Commands
+//!
+//! Commands for opening read-only observer windows that follow a session
+//! without being able to change anything.
+
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+use crate::error::{AppError, ErrorCode};
+use crate::state::AppState;
+
+/// Open a read-only observer window for a session
+///
+/// Returns the new window's label. The window loads the app with an
+/// `observe` query parameter naming the session to follow.
+#[tauri::command]
+pub async fn window_open_observer(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<String, AppError> {
+    state.command_metrics.measure("window_open_observer", async {
+        let title: String = sqlx::query_scalar("SELECT title FROM sessions WHERE id = ?")
+            .bind(&session_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or_else(|| AppError::database_not_found("Session", &session_id))?;
+
+        let label = format!("observer-{}", uuid::Uuid::new_v4());
+
+        // Mark the window before it loads so its first command is already guarded
+        state.set_observer(&label, true);
+
+        let url = WebviewUrl::App(format!("index.html?observe={}", session_id).into());
+        let window = WebviewWindowBuilder::new(&app, &label, url)
+            .title(format!("{} (observing)", title))
+            .inner_size(1000.0, 700.0)
+            .build()
+            .map_err(|e| {
+                state.set_observer(&label, false);
+                AppError::new(ErrorCode::Unknown, format!("Failed to open observer window: {}", e))
+            })?;
+
+        let handle = app.clone();
+        let closed_label = label.clone();
+        window.on_window_event(move |event| {
+            if let WindowEvent::Destroyed = event {
+                if let Some(state) = handle.try_state::<AppState>() {
+                    state.set_observer(&closed_label, false);
+                }
+            }
+        });
+
+        Ok(label)
+    })
+    .await
+}
+
+/// Mark an existing window as a read-only observer, or restore it
+#[tauri::command]
+pub async fn window_set_observer(
+    state: State<'_, AppState>,
+    label: String,
+    observer: bool,
+) -> Result<(), AppError> {
+    state.command_metrics.measure("window_set_observer", async {
+        state.set_observer(&label, observer);
+        Ok(())
+    })
+    .await
+}
+
+/// Check whether the calling window is a read-only observer
+#[tauri::command]
+pub async fn window_is_observer(
+    window: tauri::Window,
+    state: State<'_, AppState>,
+) -> Result<bool, AppError> {
+    state.command_metrics.measure("window_is_observer", async {
+        Ok(state.is_observer(window.label()))
+    })
+    .await
+}
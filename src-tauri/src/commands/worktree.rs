@@ -0,0 +1,143 @@
+//! Session Worktree Commands
+//!
+//! Isolated sessions run in their own git worktree on their own branch, so
+//! several sessions can change the same repository without clobbering each
+//! other. The worktree is merged back or discarded when the session is
+//! deleted.
+
+use std::path::{Path, PathBuf};
+
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::git;
+use crate::state::{AppState, ClaudeStatus};
+
+use super::project::project_root;
+use super::session::{fetch_session, SessionResponse};
+
+/// Directory under the project root holding session worktrees
+const WORKTREE_DIR: &str = ".wingman/worktrees";
+
+/// Prefix of the branches session worktrees are created on
+const BRANCH_PREFIX: &str = "wingman/worktree-";
+
+/// Move a session into a new git worktree of its project
+///
+/// The worktree is created at `.wingman/worktrees/<session>` under the
+/// project root on a new branch from HEAD, and becomes the session's working
+/// directory. The CLI must be stopped first.
+#[tauri::command]
+pub async fn session_create_worktree(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<SessionResponse, AppError> {
+    state.command_metrics.measure("session_create_worktree", async {
+        let (project_id, worktree_path) = sqlx::query_as::<_, (Option<String>, Option<String>)>(
+            "SELECT project_id, worktree_path FROM sessions WHERE id = ?",
+        )
+        .bind(&session_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| AppError::database_not_found("Session", &session_id))?;
+
+        if worktree_path.is_some() {
+            return Err(AppError::invalid_input("Session already has a worktree"));
+        }
+        let project_id = project_id
+            .ok_or_else(|| AppError::invalid_input("Session must belong to a project to use a worktree"))?;
+        if state.get_cli_status(&session_id).await != ClaudeStatus::Stopped {
+            return Err(AppError::invalid_input("Stop the CLI before moving the session into a worktree"));
+        }
+
+        let root = project_root(&state.db, &project_id).await?;
+        let path = root.join(WORKTREE_DIR).join(&session_id);
+        let branch = format!("{}{}", BRANCH_PREFIX, session_id);
+
+        // Keep worktrees out of the main checkout's status
+        git::exclude_locally(&root, "/.wingman/").await?;
+        git::add_worktree(&root, &path, &branch).await?;
+
+        let path = path.to_string_lossy().to_string();
+        sqlx::query(
+            r#"
+            UPDATE sessions
+            SET working_directory = ?, worktree_path = ?, worktree_branch = ?, worktree_base = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&path)
+        .bind(&path)
+        .bind(&branch)
+        .bind(root.to_string_lossy().to_string())
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(&session_id)
+        .execute(&state.db)
+        .await?;
+
+        fetch_session(&state.db, &session_id).await
+    })
+    .await
+}
+
+/// Merge or discard a session's worktree before the session is deleted
+///
+/// `action` is `merge` or `discard`. Merging commits outstanding changes in
+/// the worktree, then merges its branch into the project root's checked-out
+/// branch; a conflicting merge is aborted and the worktree kept. Sessions
+/// without a worktree need no action.
+pub(crate) async fn remove_session_worktree(
+    db: &SqlitePool,
+    session_id: &str,
+    title: &str,
+    action: Option<&str>,
+) -> Result<(), AppError> {
+    let worktree = sqlx::query_as::<_, (Option<String>, Option<String>, Option<String>)>(
+        "SELECT worktree_path, worktree_branch, worktree_base FROM sessions WHERE id = ?",
+    )
+    .bind(session_id)
+    .fetch_optional(db)
+    .await?;
+
+    let Some((Some(path), Some(branch), Some(base))) = worktree else {
+        return Ok(());
+    };
+    let (path, base) = (PathBuf::from(path), PathBuf::from(base));
+
+    match action {
+        Some("merge") => {
+            if path.is_dir() {
+                git::commit_all(&path, &format!("Session changes: {}", title)).await?;
+            }
+            git::merge_branch(&base, &branch, &format!("Merge session: {}", title)).await?;
+        }
+        Some("discard") => {}
+        Some(other) => {
+            return Err(AppError::invalid_input(format!(
+                "Invalid worktree action: {} (expected merge or discard)",
+                other
+            )))
+        }
+        None => {
+            return Err(AppError::invalid_input(
+                "Session has a worktree; choose whether to merge or discard it",
+            ))
+        }
+    }
+
+    remove_worktree_and_branch(&base, &path, &branch).await;
+    Ok(())
+}
+
+/// Best-effort removal once the worktree's changes are merged or abandoned
+async fn remove_worktree_and_branch(base: &Path, path: &Path, branch: &str) {
+    if path.is_dir() {
+        if let Err(e) = git::remove_worktree(base, path).await {
+            log::warn!("Failed to remove worktree {}: {}", path.display(), e);
+        }
+    }
+    if let Err(e) = git::delete_branch(base, branch).await {
+        log::warn!("Failed to delete worktree branch {}: {}", branch, e);
+    }
+}
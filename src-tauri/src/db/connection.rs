@@ -2,28 +2,59 @@
 //!
 //! Sets up SQLite connection pool with proper configuration.
 
+use serde::Serialize;
 use sqlx::{
     sqlite::{SqliteConnectOptions, SqlitePoolOptions, SqliteJournalMode, SqliteSynchronous},
     SqlitePool,
 };
 use std::path::Path;
 
+use super::encryption;
 use crate::error::AppError;
 
+/// Name of the database file in the app data directory
+pub const DB_FILE_NAME: &str = "wingman.db";
+
+/// Progress through the pending schema migrations
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationProgress {
+    /// Schema version being applied, or the final version once finished
+    pub version: usize,
+    /// Pending migrations applied so far
+    pub completed: usize,
+    /// Migrations pending at startup
+    pub total: usize,
+}
+
 /// Create a SQLite connection pool with proper settings
-pub async fn create_pool(db_path: &Path) -> Result<SqlitePool, AppError> {
+///
+/// `on_progress` is called before each pending migration and once more
+/// after the last, so slow upgrades can be reported.
+pub async fn create_pool(
+    db_path: &Path,
+    on_progress: impl Fn(MigrationProgress),
+) -> Result<SqlitePool, AppError> {
     // Ensure parent directory exists
     if let Some(parent) = db_path.parent() {
         std::fs::create_dir_all(parent)?;
     }
 
-    let options = SqliteConnectOptions::new()
+    // Finish an encryption started by `db_encrypt_existing` before opening
+    encryption::finish_pending(db_path)?;
+    let key = encryption::key_for(db_path).await?;
+
+    let mut options = SqliteConnectOptions::new()
         .filename(db_path)
         .create_if_missing(true)
         .journal_mode(SqliteJournalMode::Wal)
         .synchronous(SqliteSynchronous::Normal)
         // Enable foreign keys for each connection
         .foreign_keys(true);
+    if let Some(key) = key {
+        // Must be the first statement on each connection
+        options = options.pragma("key", key);
+    }
 
     let pool = SqlitePoolOptions::new()
         .max_connections(5)
@@ -32,21 +63,130 @@ pub async fn create_pool(db_path: &Path) -> Result<SqlitePool, AppError> {
         .map_err(|e| AppError::database(format!("Failed to create database pool: {}", e)))?;
 
     // Run migrations
-    run_migrations(&pool).await?;
+    run_migrations(&pool, on_progress).await?;
 
     Ok(pool)
 }
 
+/// Schema migrations in order; a database at version N has applied the first N
+const MIGRATIONS: &[&str] = &[
+    MIGRATION_001_INITIAL,
+    MIGRATION_002_SESSION_CLI_SETTINGS,
+    MIGRATION_003_DEFINITION_OF_DONE,
+    MIGRATION_004_TASK_HISTORY,
+    MIGRATION_005_SESSION_HANDOFFS,
+    MIGRATION_006_SUBTASKS,
+    MIGRATION_007_LABELS,
+    MIGRATION_008_TASK_STATUSES,
+    MIGRATION_009_SESSION_TASKS,
+    MIGRATION_010_CLAUDE_TODOS,
+    MIGRATION_011_SESSION_RESUME,
+    MIGRATION_012_CHECKPOINTS,
+    MIGRATION_013_SESSION_AUTOCOMMIT,
+    MIGRATION_014_MESSAGE_COMPLETED_AT,
+    MIGRATION_015_SESSION_WORKTREES,
+    MIGRATION_016_SESSION_ARCHIVE,
+    MIGRATION_017_PROJECT_POLICY,
+    MIGRATION_018_SOFT_DELETE,
+    MIGRATION_019_IMPORT_RECORDS,
+    MIGRATION_020_DAILY_SUMMARIES,
+    MIGRATION_021_SESSION_TEMPLATES,
+    MIGRATION_022_PROMPTS,
+    MIGRATION_023_MESSAGE_ATTACHMENTS,
+    MIGRATION_024_WATCHER_BUDGET,
+    MIGRATION_025_MESSAGE_DRAFTS,
+    MIGRATION_026_SESSION_FORKS,
+    MIGRATION_027_RUNS,
+    MIGRATION_028_SESSION_SUMMARIES,
+    MIGRATION_029_PROJECT_MCP_SERVERS,
+    MIGRATION_030_HOOKS,
+    MIGRATION_031_VERIFICATION_RUNS,
+    MIGRATION_032_PROJECT_SCRIPTS,
+    MIGRATION_033_ACTIVITY_RENAMES,
+    MIGRATION_034_TOOL_CALLS,
+    MIGRATION_035_SESSION_PINNING,
+    MIGRATION_036_PROJECT_WATCH_SETTINGS,
+    MIGRATION_037_RECURRING_TASKS,
+    MIGRATION_038_DUE_DATES,
+    MIGRATION_039_TIME_ENTRIES,
+    MIGRATION_040_GITHUB_INTEGRATIONS,
+    MIGRATION_041_TRACKER_INTEGRATIONS,
+    MIGRATION_042_WEBHOOKS,
+    MIGRATION_043_WEBHOOK_KEYCHAIN_SECRETS,
+    MIGRATION_044_EVENT_BUFFER,
+    MIGRATION_045_WEBHOOK_DELIVERIES,
+];
+
 /// Run database migrations
-async fn run_migrations(pool: &SqlitePool) -> Result<(), AppError> {
-    sqlx::query(MIGRATION_001_INITIAL)
-        .execute(pool)
+///
+/// The applied schema version is tracked in SQLite's `user_version` pragma.
+async fn run_migrations(pool: &SqlitePool, on_progress: impl Fn(MigrationProgress)) -> Result<(), AppError> {
+    let current: i64 = sqlx::query_scalar("PRAGMA user_version")
+        .fetch_one(pool)
         .await
-        .map_err(|e| AppError::database(format!("Migration failed: {}", e)))?;
+        .map_err(|e| AppError::database(format!("Failed to read schema version: {}", e)))?;
+
+    let start = (current.max(0) as usize).min(MIGRATIONS.len());
+    let total = MIGRATIONS.len() - start;
+    if total == 0 {
+        return Ok(());
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(start) {
+        let version = index + 1;
+        on_progress(MigrationProgress {
+            version,
+            completed: index - start,
+            total,
+        });
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(migration)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::database(format!("Migration {} failed: {}", version, e)))?;
+
+        // PRAGMA doesn't accept bound parameters
+        sqlx::query(&format!("PRAGMA user_version = {}", version))
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        log::info!("Applied database migration {}", version);
+    }
+
+    on_progress(MigrationProgress {
+        version: MIGRATIONS.len(),
+        completed: total,
+        total,
+    });
 
     Ok(())
 }
 
+/// Open an in-memory database with every migration applied, for tests
+///
+/// The pool holds a single connection, so code that reads through the pool
+/// while it has a transaction open waits until the acquire times out.
+#[cfg(test)]
+pub async fn test_pool() -> SqlitePool {
+    let options = "sqlite::memory:"
+        .parse::<SqliteConnectOptions>()
+        .unwrap()
+        .foreign_keys(true);
+
+    // Each connection to `:memory:` gets its own database, so keep just one
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect_with(options)
+        .await
+        .unwrap();
+    run_migrations(&pool, |_| {}).await.unwrap();
+    pool
+}
+
 /// Initial database schema
 const MIGRATION_001_INITIAL: &str = r#"
 -- Sessions table
@@ -164,3 +304,709 @@ CREATE INDEX IF NOT EXISTS idx_tasks_sprint_id ON tasks(sprint_id);
 CREATE INDEX IF NOT EXISTS idx_activity_session_id ON activity_log(session_id);
 CREATE INDEX IF NOT EXISTS idx_activity_timestamp ON activity_log(timestamp);
 "#;
+
+/// Per-session CLI model, permission mode, and extra arguments
+const MIGRATION_002_SESSION_CLI_SETTINGS: &str = r#"
+ALTER TABLE sessions ADD COLUMN model TEXT;
+ALTER TABLE sessions ADD COLUMN permission_mode TEXT;
+ALTER TABLE sessions ADD COLUMN cli_args TEXT; -- JSON array of extra arguments
+"#;
+
+/// Per-project definition of done checklists
+const MIGRATION_003_DEFINITION_OF_DONE: &str = r#"
+ALTER TABLE projects ADD COLUMN dod_enforcement TEXT NOT NULL DEFAULT 'warn'
+    CHECK (dod_enforcement IN ('off', 'warn', 'block'));
+
+CREATE TABLE IF NOT EXISTS dod_items (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    text TEXT NOT NULL,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS task_dod_checks (
+    task_id TEXT NOT NULL,
+    dod_item_id TEXT NOT NULL,
+    checked_at TEXT NOT NULL,
+    PRIMARY KEY (task_id, dod_item_id),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (dod_item_id) REFERENCES dod_items(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_dod_items_project_id ON dod_items(project_id);
+"#;
+
+/// Task change history, backfilled from current task state
+const MIGRATION_004_TASK_HISTORY: &str = r#"
+CREATE TABLE IF NOT EXISTS task_history (
+    id TEXT PRIMARY KEY,
+    task_id TEXT NOT NULL,
+    project_id TEXT NOT NULL,
+    field TEXT NOT NULL,
+    old_value TEXT,
+    new_value TEXT,
+    changed_by TEXT NOT NULL DEFAULT 'user',
+    changed_at TEXT NOT NULL,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_history_task_id ON task_history(task_id);
+CREATE INDEX IF NOT EXISTS idx_task_history_project_changed ON task_history(project_id, changed_at);
+
+INSERT INTO task_history (id, task_id, project_id, field, old_value, new_value, changed_by, changed_at)
+SELECT lower(hex(randomblob(16))), id, project_id, 'status', NULL, 'todo', 'system', created_at
+FROM tasks;
+
+INSERT INTO task_history (id, task_id, project_id, field, old_value, new_value, changed_by, changed_at)
+SELECT lower(hex(randomblob(16))), id, project_id, 'status', 'todo', status, 'system', updated_at
+FROM tasks
+WHERE status != 'todo';
+"#;
+
+/// Session hand-off documents
+const MIGRATION_005_SESSION_HANDOFFS: &str = r#"
+CREATE TABLE IF NOT EXISTS session_handoffs (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    goal TEXT NOT NULL,
+    decisions TEXT NOT NULL, -- JSON array of strings
+    files_in_flight TEXT NOT NULL, -- JSON array of strings
+    next_steps TEXT NOT NULL, -- JSON array of strings
+    content TEXT NOT NULL, -- Rendered Markdown document
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_handoffs_session_id ON session_handoffs(session_id);
+
+-- Hand-off a new session was seeded from
+ALTER TABLE sessions ADD COLUMN handoff_id TEXT REFERENCES session_handoffs(id) ON DELETE SET NULL;
+"#;
+
+/// Task hierarchy; deleting a task deletes its subtasks
+const MIGRATION_006_SUBTASKS: &str = r#"
+ALTER TABLE tasks ADD COLUMN parent_task_id TEXT REFERENCES tasks(id) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_parent_task_id ON tasks(parent_task_id);
+"#;
+
+/// Per-project task labels
+const MIGRATION_007_LABELS: &str = r#"
+CREATE TABLE IF NOT EXISTS labels (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    color TEXT NOT NULL, -- Hex color, e.g. #3b82f6
+    created_at TEXT NOT NULL,
+    UNIQUE (project_id, name),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS task_labels (
+    task_id TEXT NOT NULL,
+    label_id TEXT NOT NULL,
+    PRIMARY KEY (task_id, label_id),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (label_id) REFERENCES labels(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_labels_label_id ON task_labels(label_id);
+"#;
+
+/// Per-project kanban columns, each mapped to a status category
+const MIGRATION_008_TASK_STATUSES: &str = r#"
+CREATE TABLE IF NOT EXISTS task_statuses (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    category TEXT NOT NULL CHECK (category IN ('todo', 'in_progress', 'done')),
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_statuses_project_id ON task_statuses(project_id);
+
+INSERT INTO task_statuses (id, project_id, name, category, sort_order, created_at)
+SELECT lower(hex(randomblob(16))), p.id, d.name, d.category, d.sort_order, p.created_at
+FROM projects p
+CROSS JOIN (
+    SELECT 'To Do' AS name, 'todo' AS category, 0 AS sort_order
+    UNION ALL SELECT 'In Progress', 'in_progress', 1
+    UNION ALL SELECT 'Done', 'done', 2
+) d;
+
+-- Column a task sits in; tasks.status keeps the column's category
+ALTER TABLE tasks ADD COLUMN status_id TEXT REFERENCES task_statuses(id) ON DELETE SET NULL;
+
+UPDATE tasks
+SET status_id = (
+    SELECT s.id FROM task_statuses s
+    WHERE s.project_id = tasks.project_id AND s.category = tasks.status
+);
+"#;
+
+/// Links between sessions and the tasks they worked on
+const MIGRATION_009_SESSION_TASKS: &str = r#"
+CREATE TABLE IF NOT EXISTS session_tasks (
+    session_id TEXT NOT NULL,
+    task_id TEXT NOT NULL,
+    linked_at TEXT NOT NULL,
+    PRIMARY KEY (session_id, task_id),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_tasks_task_id ON session_tasks(task_id);
+"#;
+
+/// Tasks mirrored from the CLI's todo list, keyed by todo text per session
+const MIGRATION_010_CLAUDE_TODOS: &str = r#"
+CREATE TABLE IF NOT EXISTS claude_todos (
+    session_id TEXT NOT NULL,
+    content TEXT NOT NULL,
+    task_id TEXT,
+    status TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (session_id, content),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE SET NULL
+);
+"#;
+
+/// Native CLI session IDs and how each session was last resumed
+const MIGRATION_011_SESSION_RESUME: &str = r#"
+ALTER TABLE sessions ADD COLUMN cli_session_id TEXT;
+ALTER TABLE sessions ADD COLUMN resume_mode TEXT; -- 'native' or 'transcript'
+"#;
+
+/// File snapshots taken before Claude's writes
+const MIGRATION_012_CHECKPOINTS: &str = r#"
+CREATE TABLE IF NOT EXISTS checkpoints (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    snapshot_path TEXT, -- NULL when the file did not exist yet
+    tool_name TEXT NOT NULL,
+    restored_at TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_checkpoints_session ON checkpoints(session_id, created_at);
+"#;
+
+/// Per-session auto-commit of Claude's changes onto a side branch
+const MIGRATION_013_SESSION_AUTOCOMMIT: &str = r#"
+ALTER TABLE sessions ADD COLUMN autocommit INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE sessions ADD COLUMN autocommit_at TEXT; -- when changes were last committed
+"#;
+
+/// When each message was last saved, marking the end of an assistant turn
+const MIGRATION_014_MESSAGE_COMPLETED_AT: &str = r#"
+ALTER TABLE messages ADD COLUMN completed_at TEXT;
+"#;
+
+/// Git worktrees isolated sessions run in
+const MIGRATION_015_SESSION_WORKTREES: &str = r#"
+ALTER TABLE sessions ADD COLUMN worktree_path TEXT;
+ALTER TABLE sessions ADD COLUMN worktree_branch TEXT;
+ALTER TABLE sessions ADD COLUMN worktree_base TEXT; -- repository root the worktree was created from
+"#;
+
+/// Archived sessions are hidden from the session list but kept
+const MIGRATION_016_SESSION_ARCHIVE: &str = r#"
+ALTER TABLE sessions ADD COLUMN archived_at TEXT;
+"#;
+
+/// Per-project limits on Claude's destructive actions
+const MIGRATION_017_PROJECT_POLICY: &str = r#"
+ALTER TABLE projects ADD COLUMN allow_file_deletes INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE projects ADD COLUMN allow_shell INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE projects ADD COLUMN allow_network INTEGER NOT NULL DEFAULT 1;
+"#;
+
+/// Trash for projects, milestones, sprints, and tasks
+const MIGRATION_018_SOFT_DELETE: &str = r#"
+ALTER TABLE projects ADD COLUMN deleted_at TEXT;
+ALTER TABLE milestones ADD COLUMN deleted_at TEXT;
+ALTER TABLE sprints ADD COLUMN deleted_at TEXT;
+ALTER TABLE tasks ADD COLUMN deleted_at TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_projects_deleted_at ON projects(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_milestones_deleted_at ON milestones(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_sprints_deleted_at ON sprints(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_tasks_deleted_at ON tasks(deleted_at);
+"#;
+
+/// External IDs of imported tasks, so re-importing an export skips them
+const MIGRATION_019_IMPORT_RECORDS: &str = r#"
+CREATE TABLE IF NOT EXISTS import_records (
+    project_id TEXT NOT NULL,
+    source TEXT NOT NULL, -- csv, jira, linear, jsonl, or github
+    external_id TEXT NOT NULL,
+    task_id TEXT NOT NULL,
+    imported_at TEXT NOT NULL,
+    PRIMARY KEY (project_id, source, external_id),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_import_records_task_id ON import_records(task_id);
+"#;
+
+/// Per-turn CLI costs and end-of-day recaps built from them
+const MIGRATION_020_DAILY_SUMMARIES: &str = r#"
+CREATE TABLE IF NOT EXISTS session_costs (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    cost_usd REAL NOT NULL,
+    recorded_at TEXT NOT NULL,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_costs_session ON session_costs(session_id, recorded_at);
+
+CREATE TABLE IF NOT EXISTS daily_summaries (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    date TEXT NOT NULL, -- local calendar day, YYYY-MM-DD
+    completed_tasks TEXT NOT NULL, -- JSON array of task titles
+    session_count INTEGER NOT NULL,
+    message_count INTEGER NOT NULL,
+    cost_usd REAL NOT NULL,
+    files_changed INTEGER NOT NULL,
+    created_at TEXT NOT NULL,
+    UNIQUE (project_id, date),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+"#;
+
+/// Reusable session presets and per-session system prompts
+const MIGRATION_021_SESSION_TEMPLATES: &str = r#"
+ALTER TABLE sessions ADD COLUMN system_prompt TEXT;
+
+CREATE TABLE IF NOT EXISTS session_templates (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    title_pattern TEXT, -- may contain {date} and {project}
+    project_id TEXT,
+    working_directory TEXT, -- defaults to the project's root path
+    system_prompt TEXT,
+    model TEXT,
+    permission_mode TEXT,
+    cli_args TEXT, -- JSON array of extra arguments
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE SET NULL
+);
+"#;
+
+/// Reusable prompts with `{{variable}}` placeholders
+const MIGRATION_022_PROMPTS: &str = r#"
+CREATE TABLE IF NOT EXISTS prompts (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    body TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+"#;
+
+/// Files attached to messages, copied under the app data directory
+const MIGRATION_023_MESSAGE_ATTACHMENTS: &str = r#"
+CREATE TABLE IF NOT EXISTS message_attachments (
+    id TEXT PRIMARY KEY,
+    message_id TEXT NOT NULL,
+    session_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    file_name TEXT NOT NULL,
+    original_path TEXT NOT NULL,
+    stored_path TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('image', 'file')),
+    size_bytes INTEGER NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (message_id) REFERENCES messages(id) ON DELETE CASCADE,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_message_attachments_message_id ON message_attachments(message_id);
+"#;
+
+/// Per-project limit on directories watched per session
+const MIGRATION_024_WATCHER_BUDGET: &str = r#"
+ALTER TABLE projects ADD COLUMN watcher_max_dirs INTEGER; -- NULL uses the default
+"#;
+
+/// Streamed assistant text saved as it arrives, for recovery after a crash
+const MIGRATION_025_MESSAGE_DRAFTS: &str = r#"
+CREATE TABLE IF NOT EXISTS message_drafts (
+    message_id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    content TEXT NOT NULL,
+    is_complete INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_message_drafts_session_id ON message_drafts(session_id);
+"#;
+
+/// Sessions forked from another session's conversation
+const MIGRATION_026_SESSION_FORKS: &str = r#"
+ALTER TABLE sessions ADD COLUMN parent_session_id TEXT REFERENCES sessions(id) ON DELETE SET NULL;
+ALTER TABLE sessions ADD COLUMN forked_from_message_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_sessions_parent_session_id ON sessions(parent_session_id);
+"#;
+
+/// Attempts at a task, each in its own session
+const MIGRATION_027_RUNS: &str = r#"
+CREATE TABLE IF NOT EXISTS runs (
+    id TEXT PRIMARY KEY,
+    task_id TEXT NOT NULL,
+    session_id TEXT NOT NULL,
+    attempt INTEGER NOT NULL,
+    outcome TEXT NOT NULL DEFAULT 'in_progress' CHECK (outcome IN ('in_progress', 'succeeded', 'failed', 'abandoned')),
+    notes TEXT,
+    started_at TEXT NOT NULL,
+    ended_at TEXT,
+    UNIQUE (task_id, attempt),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_runs_session_id ON runs(session_id);
+"#;
+
+/// Summaries of older session history, used in place of it on resume
+const MIGRATION_028_SESSION_SUMMARIES: &str = r#"
+CREATE TABLE IF NOT EXISTS session_summaries (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    through_message_id TEXT NOT NULL,
+    through_created_at TEXT NOT NULL,
+    message_count INTEGER NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_summaries_session_id ON session_summaries(session_id, created_at);
+"#;
+
+/// MCP servers passed to the CLI for a project's sessions
+const MIGRATION_029_PROJECT_MCP_SERVERS: &str = r#"
+CREATE TABLE IF NOT EXISTS project_mcp_servers (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    command TEXT NOT NULL,
+    args TEXT NOT NULL DEFAULT '[]', -- JSON array of strings
+    env TEXT NOT NULL DEFAULT '{}', -- JSON object of variable name to value
+    created_at TEXT NOT NULL,
+    UNIQUE (project_id, name),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+"#;
+
+/// Commands run on session, message, file, and task events
+const MIGRATION_030_HOOKS: &str = r#"
+CREATE TABLE IF NOT EXISTS hooks (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    event TEXT NOT NULL CHECK (event IN ('session_start', 'session_stop', 'message_complete', 'file_changed', 'task_completed')),
+    command TEXT NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_hooks_project_event ON hooks(project_id, event);
+"#;
+
+/// Per-project verification command and the results of running it after Claude's turns
+const MIGRATION_031_VERIFICATION_RUNS: &str = r#"
+ALTER TABLE projects ADD COLUMN verification_command TEXT; -- NULL disables verification
+
+CREATE TABLE IF NOT EXISTS verification_runs (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    project_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,
+    command TEXT NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('running', 'passed', 'failed')),
+    exit_code INTEGER,
+    output TEXT NOT NULL DEFAULT '', -- Tail of combined stdout and stderr
+    files TEXT NOT NULL DEFAULT '[]', -- JSON array of files Claude wrote in the turn
+    started_at TEXT NOT NULL,
+    finished_at TEXT,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_verification_runs_session_id ON verification_runs(session_id, started_at);
+CREATE INDEX IF NOT EXISTS idx_verification_runs_project_id ON verification_runs(project_id, started_at);
+"#;
+
+/// Named project commands launched from the UI, and their runs
+const MIGRATION_032_PROJECT_SCRIPTS: &str = r#"
+CREATE TABLE IF NOT EXISTS project_scripts (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    command TEXT NOT NULL,
+    cwd TEXT, -- Relative to the project root; NULL runs in the root
+    env TEXT NOT NULL DEFAULT '{}', -- JSON object of variable name to value
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    UNIQUE (project_id, name),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS script_runs (
+    id TEXT PRIMARY KEY,
+    script_id TEXT NOT NULL,
+    project_id TEXT NOT NULL,
+    command TEXT NOT NULL,
+    cwd TEXT NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('running', 'succeeded', 'failed', 'cancelled')),
+    exit_code INTEGER,
+    output TEXT NOT NULL DEFAULT '', -- Tail of combined stdout and stderr
+    started_at TEXT NOT NULL,
+    finished_at TEXT,
+    FOREIGN KEY (script_id) REFERENCES project_scripts(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_script_runs_script_id ON script_runs(script_id, started_at);
+"#;
+
+/// Renamed files in the activity log, with the name they had before
+///
+/// SQLite can't alter a CHECK constraint, so the table is rebuilt.
+const MIGRATION_033_ACTIVITY_RENAMES: &str = r#"
+CREATE TABLE activity_log_new (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    path TEXT NOT NULL,
+    operation TEXT NOT NULL CHECK (operation IN ('created', 'modified', 'deleted', 'renamed')),
+    source TEXT NOT NULL CHECK (source IN ('claude', 'external')),
+    timestamp TEXT NOT NULL,
+    old_path TEXT, -- Set for renames; relative like path
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+INSERT INTO activity_log_new (id, session_id, path, operation, source, timestamp)
+SELECT id, session_id, path, operation, source, timestamp FROM activity_log;
+
+DROP TABLE activity_log;
+ALTER TABLE activity_log_new RENAME TO activity_log;
+
+CREATE INDEX IF NOT EXISTS idx_activity_session_id ON activity_log(session_id);
+CREATE INDEX IF NOT EXISTS idx_activity_timestamp ON activity_log(timestamp);
+"#;
+
+/// Claude's tool calls, one row per call, so they can be counted and queried
+const MIGRATION_034_TOOL_CALLS: &str = r#"
+CREATE TABLE IF NOT EXISTS tool_calls (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    message_id TEXT NOT NULL, -- The message may only be saved once its turn ends
+    tool_use_id TEXT, -- The CLI's ID, which the result refers back to
+    tool_name TEXT NOT NULL,
+    input TEXT NOT NULL, -- JSON
+    result TEXT,
+    status TEXT NOT NULL CHECK (status IN ('running', 'succeeded', 'failed')),
+    duration_ms INTEGER,
+    started_at TEXT NOT NULL,
+    finished_at TEXT,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_tool_calls_session_id ON tool_calls(session_id, started_at);
+CREATE INDEX IF NOT EXISTS idx_tool_calls_message_id ON tool_calls(message_id);
+"#;
+
+/// Pinned sessions, listed first in their own order
+const MIGRATION_035_SESSION_PINNING: &str = r#"
+ALTER TABLE sessions ADD COLUMN is_pinned INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE sessions ADD COLUMN sort_order INTEGER; -- Set by session_reorder; pinned sessions get one when pinned
+"#;
+
+/// File watcher settings applied to every session in a project
+const MIGRATION_036_PROJECT_WATCH_SETTINGS: &str = r#"
+CREATE TABLE IF NOT EXISTS project_watch_settings (
+    project_id TEXT PRIMARY KEY,
+    ignore_patterns TEXT NOT NULL DEFAULT '[]', -- JSON array, added to the global patterns
+    debounce_ms INTEGER, -- Overrides the global debounce_ms setting
+    include_dotfiles INTEGER NOT NULL DEFAULT 1,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+"#;
+
+/// Task templates created again on a schedule by the recurring task job
+const MIGRATION_037_RECURRING_TASKS: &str = r#"
+CREATE TABLE IF NOT EXISTS recurring_tasks (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    rule TEXT NOT NULL, -- "every <n> <unit>" or a five-field cron expression in local time
+    title TEXT NOT NULL,
+    description TEXT,
+    priority TEXT NOT NULL DEFAULT 'medium' CHECK (priority IN ('low', 'medium', 'high')),
+    estimated_hours REAL,
+    add_to_active_sprint INTEGER NOT NULL DEFAULT 0,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    next_run_at TEXT NOT NULL,
+    last_run_at TEXT,
+    last_task_id TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_recurring_tasks_next_run_at ON recurring_tasks(enabled, next_run_at);
+"#;
+
+/// Task due dates, and the due dates already notified about
+const MIGRATION_038_DUE_DATES: &str = r#"
+ALTER TABLE tasks ADD COLUMN due_date TEXT; -- RFC 3339 timestamp, or YYYY-MM-DD due by the end of that day
+
+CREATE INDEX IF NOT EXISTS idx_tasks_due_date ON tasks(due_date);
+
+CREATE TABLE IF NOT EXISTS sent_notifications (
+    entity_type TEXT NOT NULL CHECK (entity_type IN ('task', 'milestone')),
+    entity_id TEXT NOT NULL,
+    due_date TEXT NOT NULL, -- The date notified about; a new date is notified about again
+    sent_at TEXT NOT NULL,
+    PRIMARY KEY (entity_type, entity_id, due_date)
+);
+"#;
+
+/// Time spent on tasks, recorded by start/stop timers
+const MIGRATION_039_TIME_ENTRIES: &str = r#"
+CREATE TABLE IF NOT EXISTS time_entries (
+    id TEXT PRIMARY KEY,
+    task_id TEXT NOT NULL,
+    project_id TEXT NOT NULL,
+    started_at TEXT NOT NULL,
+    ended_at TEXT, -- Unset while the timer is running
+    duration_seconds INTEGER,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_time_entries_task_id ON time_entries(task_id, started_at);
+CREATE INDEX IF NOT EXISTS idx_time_entries_project_id ON time_entries(project_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_time_entries_running ON time_entries(task_id) WHERE ended_at IS NULL;
+"#;
+
+/// GitHub repositories linked to projects for importing issues
+const MIGRATION_040_GITHUB_INTEGRATIONS: &str = r#"
+CREATE TABLE IF NOT EXISTS github_integrations (
+    project_id TEXT PRIMARY KEY,
+    repo TEXT NOT NULL, -- owner/name
+    label_map TEXT NOT NULL DEFAULT '{}', -- JSON object of GitHub label to task label
+    close_issues_on_done INTEGER NOT NULL DEFAULT 0,
+    last_imported_at TEXT,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+"#;
+
+/// Linear and Jira projects linked to projects, and a log of syncs with them
+const MIGRATION_041_TRACKER_INTEGRATIONS: &str = r#"
+CREATE TABLE IF NOT EXISTS tracker_integrations (
+    project_id TEXT NOT NULL,
+    tracker TEXT NOT NULL CHECK (tracker IN ('linear', 'jira')),
+    config TEXT NOT NULL, -- JSON; see TrackerConfig
+    last_synced_at TEXT,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (project_id, tracker),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+ALTER TABLE import_records ADD COLUMN remote_status TEXT; -- Status category the tracker had at the last sync
+
+CREATE TABLE IF NOT EXISTS integration_sync_log (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    tracker TEXT NOT NULL,
+    task_id TEXT,
+    external_id TEXT,
+    action TEXT NOT NULL CHECK (action IN ('created', 'updated', 'pushed', 'conflict', 'error')),
+    message TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_integration_sync_log_project ON integration_sync_log(project_id, created_at);
+"#;
+
+/// URLs project events are POSTed to
+const MIGRATION_042_WEBHOOKS: &str = r#"
+CREATE TABLE IF NOT EXISTS webhooks (
+    id TEXT PRIMARY KEY,
+    project_id TEXT, -- Unset to receive events from every project
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    secret TEXT, -- Signs deliveries with HMAC-SHA256 when set
+    events TEXT NOT NULL DEFAULT '[]', -- JSON array of event names
+    enabled INTEGER NOT NULL DEFAULT 1,
+    last_delivery_at TEXT,
+    last_status_code INTEGER,
+    last_error TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhooks_project_id ON webhooks(project_id);
+"#;
+
+/// Webhook signing secrets move to the OS keychain; `secret` is emptied once moved
+const MIGRATION_043_WEBHOOK_KEYCHAIN_SECRETS: &str = r#"
+ALTER TABLE webhooks ADD COLUMN has_secret INTEGER NOT NULL DEFAULT 0;
+
+UPDATE webhooks SET has_secret = 1 WHERE secret IS NOT NULL;
+"#;
+
+/// Recent session events, replayed to a window that missed them while closed
+const MIGRATION_044_EVENT_BUFFER: &str = r#"
+CREATE TABLE IF NOT EXISTS event_buffer (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event TEXT NOT NULL,
+    session_id TEXT,
+    payload TEXT NOT NULL, -- JSON, as the frontend receives it
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_buffer_created_at ON event_buffer(created_at);
+CREATE INDEX IF NOT EXISTS idx_event_buffer_session ON event_buffer(session_id, created_at);
+"#;
+
+/// Each attempt at a webhook delivery, kept so failed ones can be inspected and resent
+const MIGRATION_045_WEBHOOK_DELIVERIES: &str = r#"
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    delivery_id TEXT NOT NULL, -- Sent as X-Wingman-Delivery; shared by retries
+    webhook_id TEXT NOT NULL,
+    event TEXT NOT NULL,
+    payload TEXT NOT NULL, -- JSON body sent
+    attempt INTEGER NOT NULL, -- 1 for the first try
+    success INTEGER NOT NULL,
+    status_code INTEGER,
+    response_body TEXT, -- Start of the response body
+    error TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook_id ON webhook_deliveries(webhook_id, id);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_delivery_id ON webhook_deliveries(delivery_id);
+"#;
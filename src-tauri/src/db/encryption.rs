@@ -0,0 +1,240 @@
+//! Database Encryption
+//!
+//! With the `sqlcipher` feature, the database can be encrypted at rest with
+//! SQLCipher. The key is derived from a random secret kept in the OS
+//! keychain, so the database file alone is unreadable. New databases are
+//! created encrypted; an existing plaintext database is converted by
+//! `encrypt_existing`, which closes the pool, writes an encrypted copy, and
+//! swaps it in, so the database has to be reopened afterwards.
+//!
+//! Builds without the feature open plaintext databases only.
+
+use std::path::{Path, PathBuf};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::error::{AppError, ErrorCode};
+
+/// Header every plaintext SQLite database starts with
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Mixed into the keychain secret, so the key can be rotated by changing it
+const KEY_CONTEXT: &[u8] = b"wingman database key v1";
+
+/// Whether this build can encrypt the database
+pub const SUPPORTED: bool = cfg!(feature = "sqlcipher");
+
+/// Whether a database file exists and isn't plaintext SQLite
+///
+/// Encrypted databases have no recognizable header.
+pub fn is_encrypted(db_path: &Path) -> std::io::Result<bool> {
+    use std::io::Read;
+
+    let mut file = match std::fs::File::open(db_path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    let mut header = [0u8; 16];
+    let mut read = 0;
+    while read < header.len() {
+        match file.read(&mut header[read..])? {
+            0 => break,
+            n => read += n,
+        }
+    }
+    // An empty file is a database SQLite hasn't written to yet
+    Ok(read > 0 && header[..read] != SQLITE_HEADER[..read])
+}
+
+/// Where `encrypt_existing` writes the encrypted copy of a database
+pub fn pending_path(db_path: &Path) -> PathBuf {
+    let mut name = db_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".encrypted");
+    db_path.with_file_name(name)
+}
+
+/// Swap in an encrypted copy written by `encrypt_existing`, if there is one
+///
+/// Must run while the database isn't open. `encrypt_existing` swaps the copy
+/// in itself; this finishes one interrupted before the swap.
+pub fn finish_pending(db_path: &Path) -> Result<(), AppError> {
+    let pending = pending_path(db_path);
+    if !pending.exists() {
+        return Ok(());
+    }
+
+    // The plaintext WAL would otherwise be replayed into the encrypted file
+    for suffix in ["-wal", "-shm"] {
+        let mut name = db_path.file_name().unwrap_or_default().to_os_string();
+        name.push(suffix);
+        let sidecar = db_path.with_file_name(name);
+        if sidecar.exists() {
+            std::fs::remove_file(&sidecar)?;
+        }
+    }
+    std::fs::rename(&pending, db_path)?;
+    log::info!("Replaced {} with its encrypted copy", db_path.display());
+    Ok(())
+}
+
+/// SQLCipher raw key for a keychain secret, in `x'…'` form
+fn derive_key(secret: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes any key length");
+    mac.update(KEY_CONTEXT);
+    let hex: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("\"x'{}'\"", hex)
+}
+
+/// The key to open a database with, or `None` to open it as plaintext
+///
+/// A new database gets a new keychain secret. If the keychain can't store
+/// one, the database is created as plaintext rather than not at all.
+#[cfg(feature = "sqlcipher")]
+pub async fn key_for(db_path: &Path) -> Result<Option<String>, AppError> {
+    use crate::secrets;
+
+    if is_encrypted(db_path)? {
+        let secret = secrets::get(secrets::DATABASE_KEY).await?.ok_or_else(|| {
+            AppError::new(
+                ErrorCode::PermissionDenied,
+                "The database is encrypted, but its key is missing from the OS keychain",
+            )
+        })?;
+        return Ok(Some(derive_key(&secret)));
+    }
+    if db_path.exists() {
+        return Ok(None);
+    }
+
+    match get_or_create_secret().await {
+        Ok(secret) => Ok(Some(derive_key(&secret))),
+        Err(e) => {
+            log::warn!("Creating an unencrypted database: {}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// Without SQLCipher, only plaintext databases can be opened
+#[cfg(not(feature = "sqlcipher"))]
+pub async fn key_for(db_path: &Path) -> Result<Option<String>, AppError> {
+    if is_encrypted(db_path)? {
+        return Err(AppError::database(format!(
+            "{} isn't a plaintext SQLite database; if it is encrypted, this build lacks SQLCipher support",
+            db_path.display()
+        )));
+    }
+    Ok(None)
+}
+
+#[cfg(feature = "sqlcipher")]
+async fn get_or_create_secret() -> Result<String, AppError> {
+    use crate::secrets;
+
+    if let Some(secret) = secrets::get(secrets::DATABASE_KEY).await? {
+        return Ok(secret);
+    }
+    let secret = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    secrets::set(secrets::DATABASE_KEY, &secret).await?;
+    Ok(secret)
+}
+
+/// Replace an open plaintext database with an encrypted copy
+///
+/// Closes `pool` first, waiting for queries in flight, so nothing written
+/// through it can be missed by the copy. The pool stays closed even if the
+/// export fails, and the database has to be reopened either way.
+#[cfg(feature = "sqlcipher")]
+pub async fn encrypt_existing(pool: &sqlx::SqlitePool, db_path: &Path) -> Result<(), AppError> {
+    use sqlx::sqlite::SqliteConnectOptions;
+    use sqlx::{ConnectOptions, Connection};
+
+    if is_encrypted(db_path)? {
+        return Err(AppError::invalid_input("The database is already encrypted"));
+    }
+
+    let key = derive_key(&get_or_create_secret().await?);
+    let pending = pending_path(db_path);
+    if pending.exists() {
+        std::fs::remove_file(&pending)?;
+    }
+
+    pool.close().await;
+    let mut conn = SqliteConnectOptions::new().filename(db_path).connect().await?;
+    let version: i64 = sqlx::query_scalar("PRAGMA user_version").fetch_one(&mut conn).await?;
+    sqlx::query(&format!("ATTACH DATABASE ? AS encrypted KEY {}", key))
+        .bind(pending.to_string_lossy().to_string())
+        .execute(&mut conn)
+        .await?;
+    let exported = async {
+        sqlx::query("SELECT sqlcipher_export('encrypted')")
+            .execute(&mut conn)
+            .await?;
+        // sqlcipher_export leaves the schema version behind
+        sqlx::query(&format!("PRAGMA encrypted.user_version = {}", version))
+            .execute(&mut conn)
+            .await?;
+        Ok::<_, AppError>(())
+    }
+    .await;
+    sqlx::query("DETACH DATABASE encrypted").execute(&mut conn).await?;
+    conn.close().await?;
+
+    if let Err(e) = exported {
+        let _ = std::fs::remove_file(&pending);
+        return Err(e);
+    }
+    finish_pending(db_path)
+}
+
+#[cfg(not(feature = "sqlcipher"))]
+pub async fn encrypt_existing(_pool: &sqlx::SqlitePool, _db_path: &Path) -> Result<(), AppError> {
+    Err(AppError::new(
+        ErrorCode::InvalidInput,
+        "This build doesn't support database encryption; rebuild with the sqlcipher feature",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derive_key() {
+        let key = derive_key("secret");
+        assert!(key.starts_with("\"x'") && key.ends_with("'\""));
+        assert_eq!(key.len(), 64 + 5);
+        assert_eq!(key, derive_key("secret"));
+        assert_ne!(key, derive_key("other"));
+    }
+
+    #[test]
+    fn test_is_encrypted() {
+        let dir = std::env::temp_dir().join(format!("wingman-encryption-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("test.db");
+        assert!(!is_encrypted(&path).unwrap());
+        std::fs::write(&path, b"").unwrap();
+        assert!(!is_encrypted(&path).unwrap());
+        std::fs::write(&path, b"SQLite format 3\0rest of the page").unwrap();
+        assert!(!is_encrypted(&path).unwrap());
+        std::fs::write(&path, [0x8f, 0x21, 0x07, 0xc4, 0x55, 0x00, 0x13]).unwrap();
+        assert!(is_encrypted(&path).unwrap());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_pending_path() {
+        let path = Path::new("/data/wingman.db");
+        assert_eq!(pending_path(path), Path::new("/data/wingman.db.encrypted"));
+    }
+}
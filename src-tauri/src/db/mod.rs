@@ -3,5 +3,7 @@
 //! Handles SQLite database connection and queries.
 
 pub mod connection;
+pub mod encryption;
+pub mod settings;
 
 pub use connection::*;
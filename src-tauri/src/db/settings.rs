@@ -0,0 +1,88 @@
+//! Settings Storage
+//!
+//! Key/value access to the `settings` table.
+
+use sqlx::SqlitePool;
+
+use crate::error::AppError;
+
+/// Setting key for the maximum number of concurrent CLI processes
+pub const MAX_CONCURRENT_SESSIONS: &str = "max_concurrent_sessions";
+
+/// Setting key for the CLI executable used instead of `claude` from PATH
+pub const CLAUDE_BINARY_PATH: &str = "claude_binary_path";
+
+/// Setting key for how many times a crashed CLI process is restarted
+pub const CLI_MAX_RESTARTS: &str = "cli_max_restarts";
+
+/// Setting key for how many streamed output chunks are kept per session for replay
+pub const OUTPUT_REPLAY_MAX_CHUNKS: &str = "output_replay_max_chunks";
+
+/// Setting key for the maximum calls per IPC command per second
+pub const IPC_RATE_LIMIT: &str = "ipc_rate_limit";
+
+/// Setting key for the largest file whose changes are diffed (0 = off)
+pub const FILE_DIFF_MAX_BYTES: &str = "file_diff_max_bytes";
+
+/// Setting key for how many file events are buffered before they are dropped
+pub const WATCHER_CHANNEL_CAPACITY: &str = "watcher_channel_capacity";
+
+/// Setting key for how many days trashed items are kept (0 = forever)
+pub const TRASH_RETENTION_DAYS: &str = "trash_retention_days";
+
+/// Setting key for how many days activity entries are kept (0 = forever)
+pub const ACTIVITY_RETENTION_DAYS: &str = "activity_retention_days";
+
+/// Setting key for how many activity entries are kept per session (0 = no limit)
+pub const ACTIVITY_MAX_ROWS_PER_SESSION: &str = "activity_max_rows_per_session";
+
+/// Setting key for mirroring the CLI's todo list into sprint tasks
+pub const CLAUDE_TODO_SYNC: &str = "claude_todo_sync";
+
+/// Setting key for the local hour the end-of-day summary is compiled at
+pub const DAILY_SUMMARY_HOUR: &str = "daily_summary_hour";
+
+/// Setting key for the last local day an end-of-day summary was compiled for
+pub const DAILY_SUMMARY_LAST_DATE: &str = "daily_summary_last_date";
+
+/// Setting key for whether the local HTTP API server runs
+pub const API_SERVER_ENABLED: &str = "api_server_enabled";
+
+/// Setting key for the localhost port the HTTP API server listens on
+pub const API_SERVER_PORT: &str = "api_server_port";
+
+/// Read a raw setting value
+pub async fn get_setting(pool: &SqlitePool, key: &str) -> Result<Option<String>, AppError> {
+    let value: Option<String> = sqlx::query_scalar("SELECT value FROM settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(value)
+}
+
+/// Write a raw setting value, replacing any existing one
+pub async fn set_setting(pool: &SqlitePool, key: &str, value: &str) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value) VALUES (?, ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value
+        "#,
+    )
+    .bind(key)
+    .bind(value)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Remove a setting so its default applies again
+pub async fn delete_setting(pool: &SqlitePool, key: &str) -> Result<(), AppError> {
+    sqlx::query("DELETE FROM settings WHERE key = ?")
+        .bind(key)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
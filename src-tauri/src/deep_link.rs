@@ -0,0 +1,313 @@
+//! Deep Links
+//!
+//! Handles `wingman://` URLs opened from terminals, editors, or webhook
+//! messages. Each link is parsed into a [`DeepLink`], acted on, and
+//! announced to the frontend as a `deep_link` event so it can show what the
+//! link points at; the main window is brought to the front.
+//!
+//! - `wingman://session/<id>` opens a session
+//! - `wingman://project/<id>` opens a project
+//! - `wingman://project/<id>/task/<task_id>` opens a task
+//! - `wingman://project/<id>/task/new?title=...` creates a task, with
+//!   optional `description`, `priority`, and `sprint` parameters
+//!
+//! Links opened while a second copy of the app starts are forwarded to the
+//! running one by the single-instance plugin.
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::commands::{self, TaskCreateRequest};
+use crate::error::{AppError, ErrorCode};
+use crate::events::{emit_event, event_names, DeepLinkPayload};
+use crate::state::startup::StartupPhase;
+use crate::state::{AppState, StartupTracker};
+
+/// URL scheme registered for the app
+pub const SCHEME: &str = "wingman";
+
+/// How often a link opened during startup checks whether the app is ready
+const STARTUP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// What a `wingman://` URL asks for
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLink {
+    OpenSession {
+        session_id: String,
+    },
+    OpenProject {
+        project_id: String,
+    },
+    OpenTask {
+        project_id: String,
+        task_id: String,
+    },
+    NewTask {
+        project_id: String,
+        title: String,
+        description: Option<String>,
+        priority: Option<String>,
+        sprint_id: Option<String>,
+    },
+}
+
+impl DeepLink {
+    /// Parse a `wingman://` URL
+    pub fn parse(url: &Url) -> Result<Self, AppError> {
+        if url.scheme() != SCHEME {
+            return Err(AppError::invalid_input(format!("Not a {}:// link: {}", SCHEME, url)));
+        }
+
+        let mut segments: Vec<&str> = url.host_str().into_iter().collect();
+        segments.extend(url.path_segments().into_iter().flatten().filter(|s| !s.is_empty()));
+        let query = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        match segments.as_slice() {
+            ["session", session_id] => Ok(DeepLink::OpenSession {
+                session_id: session_id.to_string(),
+            }),
+            ["project", project_id] => Ok(DeepLink::OpenProject {
+                project_id: project_id.to_string(),
+            }),
+            ["project", project_id, "task", "new"] => Ok(DeepLink::NewTask {
+                project_id: project_id.to_string(),
+                title: query("title").ok_or_else(|| AppError::invalid_input("New task links need a title"))?,
+                description: query("description"),
+                priority: query("priority"),
+                sprint_id: query("sprint"),
+            }),
+            ["project", project_id, "task", task_id] => Ok(DeepLink::OpenTask {
+                project_id: project_id.to_string(),
+                task_id: task_id.to_string(),
+            }),
+            _ => Err(AppError::invalid_input(format!("Unrecognized link: {}", url))),
+        }
+    }
+
+    /// The `wingman://` URL for this link
+    pub fn to_url(&self) -> Url {
+        let (path, query): (String, Vec<(&str, &str)>) = match self {
+            DeepLink::OpenSession { session_id } => (format!("session/{}", session_id), Vec::new()),
+            DeepLink::OpenProject { project_id } => (format!("project/{}", project_id), Vec::new()),
+            DeepLink::OpenTask { project_id, task_id } => {
+                (format!("project/{}/task/{}", project_id, task_id), Vec::new())
+            }
+            DeepLink::NewTask {
+                project_id,
+                title,
+                description,
+                priority,
+                sprint_id,
+            } => {
+                let mut query = vec![("title", title.as_str())];
+                query.extend(description.as_deref().map(|value| ("description", value)));
+                query.extend(priority.as_deref().map(|value| ("priority", value)));
+                query.extend(sprint_id.as_deref().map(|value| ("sprint", value)));
+                (format!("project/{}/task/new", project_id), query)
+            }
+        };
+
+        let mut url = Url::parse(&format!("{}://{}", SCHEME, path)).expect("IDs are valid URL segments");
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        url
+    }
+}
+
+/// Listen for links opened while the app runs, and handle the one it was launched with
+pub fn init(app: &AppHandle) {
+    // Installed builds register the scheme when bundled; dev builds do it here
+    #[cfg(all(debug_assertions, any(target_os = "linux", windows)))]
+    if let Err(e) = app.deep_link().register_all() {
+        log::warn!("Failed to register the {}:// scheme: {}", SCHEME, e);
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            spawn_handle(&handle, url);
+        }
+    });
+
+    match app.deep_link().get_current() {
+        Ok(urls) => {
+            for url in urls.into_iter().flatten() {
+                spawn_handle(app, url);
+            }
+        }
+        Err(e) => log::warn!("Failed to read the launch link: {}", e),
+    }
+}
+
+/// Open a link in the background, as if it came from outside the app
+pub(crate) fn spawn_handle(app: &AppHandle, url: Url) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let payload = match handle(&app, &url).await {
+            Ok(payload) => payload,
+            Err(e) => {
+                log::warn!("Failed to open {}: {}", url, e);
+                DeepLinkPayload {
+                    url: url.to_string(),
+                    action: "error".to_string(),
+                    error: Some(e.message),
+                    ..Default::default()
+                }
+            }
+        };
+        let _ = emit_event(&app, event_names::DEEP_LINK, payload);
+        focus_main_window(&app);
+    });
+}
+
+/// Act on a link, returning the event describing it
+async fn handle(app: &AppHandle, url: &Url) -> Result<DeepLinkPayload, AppError> {
+    let link = DeepLink::parse(url)?;
+    wait_until_ready(app).await?;
+
+    let mut payload = DeepLinkPayload {
+        url: url.to_string(),
+        ..Default::default()
+    };
+    match link {
+        DeepLink::OpenSession { session_id } => {
+            payload.action = "open_session".to_string();
+            payload.session_id = Some(session_id);
+        }
+        DeepLink::OpenProject { project_id } => {
+            payload.action = "open_project".to_string();
+            payload.project_id = Some(project_id);
+        }
+        DeepLink::OpenTask { project_id, task_id } => {
+            payload.action = "open_task".to_string();
+            payload.project_id = Some(project_id);
+            payload.task_id = Some(task_id);
+        }
+        DeepLink::NewTask {
+            project_id,
+            title,
+            description,
+            priority,
+            sprint_id,
+        } => {
+            let task = commands::task_create(
+                app.state(),
+                TaskCreateRequest {
+                    project_id,
+                    sprint_id,
+                    parent_task_id: None,
+                    status_id: None,
+                    title,
+                    description,
+                    priority,
+                    estimated_hours: None,
+                    due_date: None,
+                },
+            )
+            .await?;
+            payload.action = "task_created".to_string();
+            payload.project_id = Some(task.project_id);
+            payload.task_id = Some(task.id);
+        }
+    }
+
+    Ok(payload)
+}
+
+/// Wait for the app state, for links that launched the app
+async fn wait_until_ready(app: &AppHandle) -> Result<(), AppError> {
+    loop {
+        if app.try_state::<AppState>().is_some() {
+            return Ok(());
+        }
+        let status = app.state::<StartupTracker>().get();
+        if status.phase == StartupPhase::Failed {
+            return Err(AppError::new(
+                ErrorCode::Unknown,
+                status.error.unwrap_or_else(|| "Wingman failed to start".to_string()),
+            ));
+        }
+        tokio::time::sleep(STARTUP_POLL_INTERVAL).await;
+    }
+}
+
+/// Bring the main window to the front
+pub fn focus_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(url: &str) -> Result<DeepLink, AppError> {
+        DeepLink::parse(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("wingman://session/s1").unwrap(),
+            DeepLink::OpenSession { session_id: "s1".to_string() }
+        );
+        assert_eq!(
+            parse("wingman://project/p1/").unwrap(),
+            DeepLink::OpenProject { project_id: "p1".to_string() }
+        );
+        assert_eq!(
+            parse("wingman://project/p1/task/t1").unwrap(),
+            DeepLink::OpenTask {
+                project_id: "p1".to_string(),
+                task_id: "t1".to_string(),
+            }
+        );
+        assert_eq!(
+            parse("wingman://project/p1/task/new?title=Fix%20login&priority=high").unwrap(),
+            DeepLink::NewTask {
+                project_id: "p1".to_string(),
+                title: "Fix login".to_string(),
+                description: None,
+                priority: Some("high".to_string()),
+                sprint_id: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_to_url() {
+        let link = DeepLink::OpenTask {
+            project_id: "p1".to_string(),
+            task_id: "t1".to_string(),
+        };
+        assert_eq!(link.to_url().as_str(), "wingman://project/p1/task/t1");
+
+        let link = DeepLink::NewTask {
+            project_id: "p1".to_string(),
+            title: "Fix login & signup".to_string(),
+            description: None,
+            priority: Some("high".to_string()),
+            sprint_id: None,
+        };
+        assert_eq!(DeepLink::parse(&link.to_url()).unwrap(), link);
+    }
+
+    #[test]
+    fn test_parse_rejects() {
+        assert!(parse("wingman://project/p1/task/new").is_err());
+        assert!(parse("wingman://project/p1/task/new?title=%20").is_err());
+        assert!(parse("wingman://settings").is_err());
+        assert!(parse("https://session/s1").is_err());
+    }
+}
@@ -16,6 +16,7 @@ pub enum ErrorCode {
     NotFound,
     InvalidInput,
     PermissionDenied,
+    RateLimited,
 
     // Claude CLI
     ClaudeCliNotFound,
@@ -23,6 +24,10 @@ pub enum ErrorCode {
     ClaudeCliTimeout,
     ClaudeCliAuthRequired,
 
+    // Git
+    GitNotFound,
+    GitError,
+
     // Database
     DatabaseError,
     DatabaseConstraint,
@@ -33,6 +38,10 @@ pub enum ErrorCode {
     FileAccessDenied,
     FileAlreadyExists,
     DirectoryNotFound,
+    WatcherLimitReached,
+
+    // Terminal
+    TerminalError,
 
     // Network
     NetworkError,
@@ -79,6 +88,13 @@ impl AppError {
         Self::new(ErrorCode::InvalidInput, message)
     }
 
+    pub fn rate_limited(command: &str) -> Self {
+        Self::new(
+            ErrorCode::RateLimited,
+            format!("Too many calls to {}", command),
+        )
+    }
+
     pub fn database(message: impl Into<String>) -> Self {
         Self::new(ErrorCode::DatabaseError, message)
     }
@@ -101,6 +117,14 @@ impl AppError {
         Self::new(ErrorCode::ClaudeCliError, message)
     }
 
+    pub fn git_not_found() -> Self {
+        Self::new(ErrorCode::GitNotFound, "Git is not installed or not in PATH")
+    }
+
+    pub fn git_error(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::GitError, message)
+    }
+
     pub fn file_not_found(path: impl Into<String>) -> Self {
         Self::with_details(
             ErrorCode::FileNotFound,
@@ -157,3 +181,9 @@ impl From<serde_json::Error> for AppError {
         Self::with_details(ErrorCode::InvalidInput, "JSON parsing error", err.to_string())
     }
 }
+
+impl From<tauri::Error> for AppError {
+    fn from(err: tauri::Error) -> Self {
+        Self::with_details(ErrorCode::Unknown, "Window system error", err.to_string())
+    }
+}
@@ -3,19 +3,40 @@
 //! Handles emitting events to the frontend.
 
 use tauri::{AppHandle, Emitter};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// Event names matching the frontend EVENTS constant
 #[allow(dead_code)]
 pub mod event_names {
+    pub const BULK_CHANGE: &str = "bulk_change";
     pub const CLAUDE_OUTPUT: &str = "claude_output";
+    pub const CLAUDE_PERMISSION_REQUEST: &str = "claude_permission_request";
     pub const CLAUDE_STATUS: &str = "claude_status";
     pub const CLAUDE_ERROR: &str = "claude_error";
+    pub const CLAUDE_QUEUE_STATUS: &str = "claude_queue_status";
+    pub const CLAUDE_TODOS_SYNCED: &str = "claude_todos_synced";
+    pub const CLI_LOGIN_FINISHED: &str = "cli_login_finished";
+    pub const DAILY_SUMMARY: &str = "daily_summary";
+    pub const DEEP_LINK: &str = "deep_link";
     pub const FILE_CHANGED: &str = "file_changed";
+    pub const GIT_COMMIT_CREATED: &str = "git_commit_created";
+    pub const HOOK_OUTPUT: &str = "hook_output";
+    pub const IMPORT_PROGRESS: &str = "import_progress";
+    pub const MIGRATION_PROGRESS: &str = "migration_progress";
+    pub const POLICY_VIOLATION: &str = "policy_violation";
+    pub const PREVIEW_STATUS: &str = "preview_status";
+    pub const PROJECT_CONFIG_CHANGED: &str = "project_config_changed";
+    pub const RECURRING_TASK_RUN: &str = "recurring_task_run";
+    pub const SCRIPT_OUTPUT: &str = "script_output";
     pub const SESSION_SAVED: &str = "session_saved";
+    pub const SETTINGS_CHANGED: &str = "settings_changed";
+    pub const TERMINAL_OUTPUT: &str = "terminal_output";
     pub const THEME_CHANGED: &str = "theme_changed";
     pub const UPDATE_AVAILABLE: &str = "update_available";
     pub const UPDATE_PROGRESS: &str = "update_progress";
+    pub const VERIFICATION_FINISHED: &str = "verification_finished";
+    pub const VERIFICATION_STARTED: &str = "verification_started";
+    pub const WATCHER_OVERFLOW: &str = "watcher_overflow";
 }
 
 /// Emit an event to all windows
@@ -24,7 +45,7 @@ pub fn emit_event<T: Serialize + Clone>(app: &AppHandle, event: &str, payload: T
 }
 
 /// Claude output event payload
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ClaudeOutputPayload {
     pub session_id: String,
@@ -34,12 +55,56 @@ pub struct ClaudeOutputPayload {
 }
 
 /// Claude status event payload
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ClaudeStatusPayload {
     pub session_id: String,
     pub status: String,
     pub error: Option<String>,
+    pub exit_code: Option<i32>,
+    pub stderr_tail: Option<String>,
+}
+
+/// Claude permission request event payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudePermissionRequestPayload {
+    pub session_id: String,
+    /// Passed back to `session_respond_permission`
+    pub request_id: String,
+    pub tool_name: String,
+    pub input: serde_json::Value,
+}
+
+/// Claude queue status event payload
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeQueueStatusPayload {
+    pub running: usize,
+    pub max_concurrent: Option<usize>,
+    /// Session IDs waiting for a slot, first in line first
+    pub queued: Vec<String>,
+}
+
+/// A task created or moved from the CLI's todo list
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeTodoChange {
+    pub task_id: String,
+    pub content: String,
+    /// Task status category the todo maps to
+    pub status: String,
+    pub created: bool,
+}
+
+/// Claude todos synced event payload
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeTodosSyncedPayload {
+    pub session_id: String,
+    pub project_id: String,
+    pub sprint_id: String,
+    pub changes: Vec<ClaudeTodoChange>,
 }
 
 /// File changed event payload
@@ -48,7 +113,254 @@ pub struct ClaudeStatusPayload {
 pub struct FileChangedPayload {
     pub session_id: String,
     pub path: String,
+    /// The watched root the file is under, one of the session's roots
+    pub root_path: String,
+    /// Path relative to `root_path`, `/`-separated
+    pub relative_path: String,
     pub operation: String,
     pub source: String,
     pub timestamp: String,
+    /// Set when the file is Claude configuration (see `ConfigKind`)
+    pub config_kind: Option<String>,
+    /// Unified diff of the change, when diff capture is enabled and the file
+    /// is small enough
+    pub diff: Option<String>,
+    /// Where a renamed file was before
+    pub old_path: Option<String>,
+    /// `old_path` relative to `root_path`, `/`-separated
+    pub old_relative_path: Option<String>,
+}
+
+/// Git commit created event payload
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommitCreatedPayload {
+    pub session_id: String,
+    pub branch: String,
+    pub commit: String,
+    pub message: String,
+    /// Files included in the commit, as written by Claude
+    pub files: Vec<String>,
+}
+
+/// Import progress event payload
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportProgressPayload {
+    pub project_id: String,
+    pub source: String,
+    /// Tasks created so far
+    pub processed: usize,
+    pub total: usize,
+}
+
+/// Daily summary event payload
+///
+/// Totals across every project recapped for the day; the per-project
+/// summaries are read with `daily_summary_list`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailySummaryPayload {
+    /// Local calendar day, `YYYY-MM-DD`
+    pub date: String,
+    pub project_ids: Vec<String>,
+    pub completed_tasks: usize,
+    pub session_count: i64,
+    pub cost_usd: f64,
+    pub files_changed: i64,
+}
+
+/// File watcher overflow event payload
+///
+/// Sent when file events were dropped because processing fell behind, so the
+/// session's file feed is incomplete.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatcherOverflowPayload {
+    pub session_id: String,
+    /// Events dropped for the session since the last overflow event
+    pub dropped: u64,
+    /// Events dropped across all sessions since the app started
+    pub total_dropped: u64,
+}
+
+/// Policy violation event payload
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyViolationPayload {
+    pub session_id: String,
+    pub path: String,
+    pub relative_path: String,
+    /// The policy that was broken, e.g. `file_deletes`
+    pub policy: String,
+    pub timestamp: String,
+}
+
+/// Project config changed event payload
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectConfigChangedPayload {
+    pub session_id: String,
+    pub path: String,
+    pub relative_path: String,
+    /// `claude_md`, `claude_settings`, or `mcp_config`
+    pub kind: String,
+    pub operation: String,
+    pub timestamp: String,
+}
+
+/// Settings changed event payload
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsChangedPayload {
+    pub key: String,
+    /// The setting's new value, which is its default after a reset
+    pub value: serde_json::Value,
+}
+
+/// Preview server status event payload
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewStatusPayload {
+    pub project_id: String,
+    pub url: String,
+    pub reachable: bool,
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+}
+
+/// Hook output event payload
+///
+/// Sent for each line a hook prints, then once more with `done` set when the
+/// hook exits.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookOutputPayload {
+    pub hook_id: String,
+    /// The hook event that ran the hook, e.g. `session_start`
+    pub event: String,
+    pub session_id: Option<String>,
+    /// `stdout` or `stderr`
+    pub stream: String,
+    pub line: Option<String>,
+    /// Set on the final event when the hook exited normally
+    pub exit_code: Option<i32>,
+    pub done: bool,
+}
+
+/// Verification run event payload
+///
+/// Sent as `verification_started` when the project's verification command
+/// starts after a turn, and as `verification_finished` with the outcome.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationPayload {
+    pub run_id: String,
+    pub session_id: String,
+    pub project_id: String,
+    pub command: String,
+    /// `running`, `passed`, or `failed`
+    pub status: String,
+    pub exit_code: Option<i32>,
+    /// Files Claude wrote in the turn being verified
+    pub files: Vec<String>,
+}
+
+/// Terminal output event payload
+///
+/// The last event for a terminal has `exited` set, once its shell is gone.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalOutputPayload {
+    pub terminal_id: String,
+    pub session_id: String,
+    /// Raw output, including escape sequences
+    pub data: String,
+    pub exited: bool,
+    /// The shell's exit code, when it exited on its own
+    pub exit_code: Option<u32>,
+}
+
+/// Script output event payload
+///
+/// Sent for each line a project script prints, then once more with its
+/// final status when the run ends.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptOutputPayload {
+    pub run_id: String,
+    pub script_id: String,
+    /// `stdout` or `stderr`; None on the final event
+    pub stream: Option<String>,
+    pub line: Option<String>,
+    /// `running` until the final event, then `succeeded`, `failed`, or `cancelled`
+    pub status: String,
+    pub exit_code: Option<i32>,
+}
+
+/// Bulk change event payload
+///
+/// Sent instead of `file_changed` events when a session's files churn faster
+/// than they can be reported one by one, e.g. during a dependency install.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkChangePayload {
+    pub session_id: String,
+    /// Changes coalesced, counting repeated changes to a file
+    pub events: usize,
+    pub created: usize,
+    pub modified: usize,
+    pub deleted: usize,
+    pub renamed: usize,
+    /// Directories with the most changes, relative to their watched root
+    pub top_directories: Vec<BulkDirectoryCount>,
+    pub started_at: String,
+    pub finished_at: String,
+}
+
+/// Changes under one directory in a bulk change
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkDirectoryCount {
+    pub path: String,
+    pub count: usize,
+}
+
+/// Session saved event payload
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSavedPayload {
+    pub session_id: String,
+    /// Set when the session's title changed
+    pub title: Option<String>,
+    pub timestamp: String,
+}
+
+/// Recurring task run event payload
+///
+/// Sent when the recurring task job creates a task from its template.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringTaskRunPayload {
+    pub recurring_task_id: String,
+    pub task_id: String,
+    pub project_id: String,
+    pub title: String,
+}
+
+/// Deep link event payload
+///
+/// Sent when a `wingman://` link is opened, after any action it asks for
+/// has been taken.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepLinkPayload {
+    pub url: String,
+    /// `open_session`, `open_project`, `open_task`, `task_created`, or `error`
+    pub action: String,
+    pub project_id: Option<String>,
+    pub session_id: Option<String>,
+    pub task_id: Option<String>,
+    /// Why the link couldn't be opened
+    pub error: Option<String>,
 }
@@ -0,0 +1,109 @@
+//! Git Branch Commits
+//!
+//! Commits selected files onto a branch without touching the checked-out
+//! branch, the user's index, or the working tree.
+
+use std::path::{Path, PathBuf};
+
+use crate::error::AppError;
+
+use super::repo::{run_git, run_git_allow_failure, run_git_with_index};
+
+/// Commit the current contents of `paths` onto `branch`
+///
+/// The branch is created from HEAD if it doesn't exist. Staging happens in a
+/// temporary index seeded from the branch tip, so only the listed files
+/// change relative to it. Paths outside the repository are skipped. Returns
+/// the new commit hash, or `None` when the files match the branch already.
+pub async fn commit_paths_to_branch(
+    root: &Path,
+    branch: &str,
+    paths: &[PathBuf],
+    message: &str,
+) -> Result<Option<String>, AppError> {
+    let toplevel = PathBuf::from(run_git(root, &["rev-parse", "--show-toplevel"]).await?.trim());
+    let toplevel = toplevel.canonicalize().unwrap_or(toplevel);
+
+    let branch_ref = format!("refs/heads/{}", branch);
+    if run_git_allow_failure(&toplevel, &["check-ref-format", &branch_ref]).await?.is_none() {
+        return Err(AppError::invalid_input(format!("Invalid branch name: {}", branch)));
+    }
+
+    let parent = match rev_parse(&toplevel, &branch_ref).await? {
+        Some(tip) => Some(tip),
+        None => rev_parse(&toplevel, "HEAD").await?,
+    };
+
+    let index = std::env::temp_dir().join(format!("wingman-index-{}", uuid::Uuid::new_v4()));
+    let result = commit_with_index(&toplevel, &index, parent.as_deref(), &branch_ref, paths, message).await;
+    let _ = std::fs::remove_file(&index);
+
+    result
+}
+
+async fn commit_with_index(
+    toplevel: &Path,
+    index: &Path,
+    parent: Option<&str>,
+    branch_ref: &str,
+    paths: &[PathBuf],
+    message: &str,
+) -> Result<Option<String>, AppError> {
+    match parent {
+        Some(parent) => run_git_with_index(toplevel, Some(index), &["read-tree", parent]).await?,
+        None => run_git_with_index(toplevel, Some(index), &["read-tree", "--empty"]).await?,
+    };
+
+    for path in paths {
+        let Some(relative) = repo_relative(toplevel, path) else {
+            continue;
+        };
+        // Ignored files and paths git has never seen are left out
+        if let Err(e) = run_git_with_index(toplevel, Some(index), &["add", "-A", "--", &relative]).await {
+            log::debug!("Skipping {} in branch commit: {}", relative, e);
+        }
+    }
+
+    let tree = run_git_with_index(toplevel, Some(index), &["write-tree"]).await?;
+    let tree = tree.trim();
+
+    if let Some(parent) = parent {
+        let parent_tree = run_git(toplevel, &["rev-parse", &format!("{}^{{tree}}", parent)]).await?;
+        if parent_tree.trim() == tree {
+            return Ok(None);
+        }
+    }
+
+    let mut args = vec!["commit-tree", tree, "-m", message];
+    if let Some(parent) = parent {
+        args.extend(["-p", parent]);
+    }
+    let commit = run_git(toplevel, &args).await?.trim().to_string();
+
+    run_git(toplevel, &["update-ref", branch_ref, &commit]).await?;
+
+    Ok(Some(commit))
+}
+
+/// Resolve a revision to a commit hash, or `None` if it doesn't exist
+async fn rev_parse(root: &Path, rev: &str) -> Result<Option<String>, AppError> {
+    let spec = format!("{}^{{commit}}", rev);
+    let output = run_git_allow_failure(root, &["rev-parse", "--verify", "-q", &spec]).await?;
+    Ok(output.map(|hash| hash.trim().to_string()).filter(|h| !h.is_empty()))
+}
+
+/// Express a path relative to the repository root with `/` separators
+fn repo_relative(toplevel: &Path, path: &Path) -> Option<String> {
+    // Deleted files can't be canonicalized, so resolve their directory instead
+    let absolute = match path.canonicalize() {
+        Ok(canonical) => canonical,
+        Err(_) => path.parent()?.canonicalize().ok()?.join(path.file_name()?),
+    };
+
+    let relative = absolute.strip_prefix(toplevel).ok()?;
+    if relative.as_os_str().is_empty() {
+        return None;
+    }
+
+    Some(relative.to_string_lossy().replace('\\', "/"))
+}
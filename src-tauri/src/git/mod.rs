@@ -0,0 +1,13 @@
+//! Git Integration Module
+//!
+//! Reads branch, status, diff, and history information from a project's
+//! repository, commits onto side branches, and manages session worktrees,
+//! by running the `git` executable.
+
+mod commit;
+mod repo;
+mod worktree;
+
+pub use commit::commit_paths_to_branch;
+pub use repo::{current_branch, diff_file, log_recent, status, GitCommit, GitStatus};
+pub use worktree::{add_worktree, commit_all, delete_branch, exclude_locally, merge_branch, remove_worktree};
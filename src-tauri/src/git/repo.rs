@@ -0,0 +1,273 @@
+//! Git Repository Queries
+//!
+//! Runs `git` against a working tree and parses its machine-readable output.
+
+use serde::Serialize;
+use std::path::{Component, Path};
+use tokio::process::Command;
+
+use crate::error::AppError;
+
+/// Field and record separators used in `git log` output
+const FIELD_SEP: char = '\u{1f}';
+const RECORD_SEP: char = '\u{1e}';
+
+/// Working tree status
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitStatus {
+    /// Current branch, or `None` when HEAD is detached
+    pub branch: Option<String>,
+    pub upstream: Option<String>,
+    pub ahead: u32,
+    pub behind: u32,
+    pub files: Vec<GitFileStatus>,
+}
+
+/// Status of a single changed file
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFileStatus {
+    pub path: String,
+    /// Previous path for renames and copies
+    pub orig_path: Option<String>,
+    /// Staged change code (`M`, `A`, `D`, `R`, `C`, `?`, or ` `)
+    pub index_status: String,
+    /// Unstaged change code
+    pub worktree_status: String,
+}
+
+/// A commit in the recent history
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommit {
+    pub hash: String,
+    pub short_hash: String,
+    pub author: String,
+    pub date: String,
+    pub subject: String,
+}
+
+/// Get the checked-out branch, or `None` when HEAD is detached
+pub async fn current_branch(root: &Path) -> Result<Option<String>, AppError> {
+    let output = run_git_allow_failure(root, &["symbolic-ref", "--short", "-q", "HEAD"]).await?;
+    Ok(output.map(|branch| branch.trim().to_string()).filter(|b| !b.is_empty()))
+}
+
+/// Get the branch and changed files of the working tree
+pub async fn status(root: &Path) -> Result<GitStatus, AppError> {
+    let output = run_git(root, &["status", "--porcelain=v1", "--branch", "-z", "--untracked-files=all"]).await?;
+    Ok(parse_status(&output))
+}
+
+/// Get the unified diff for one file, relative to the repository root
+///
+/// Unstaged changes are diffed against the index and staged changes against
+/// HEAD. Untracked files are shown as entirely added.
+pub async fn diff_file(root: &Path, path: &str, staged: bool) -> Result<String, AppError> {
+    let relative = Path::new(path);
+    if relative.is_absolute() || relative.components().any(|c| matches!(c, Component::ParentDir)) {
+        return Err(AppError::invalid_input("Path must be relative to the project root"));
+    }
+
+    let args: &[&str] = if staged {
+        &["diff", "--no-color", "--cached", "--", path]
+    } else {
+        &["diff", "--no-color", "--", path]
+    };
+    let diff = run_git(root, args).await?;
+    if !diff.is_empty() || staged {
+        return Ok(diff);
+    }
+
+    // Untracked files have no diff against the index
+    let tracked = run_git_allow_failure(root, &["ls-files", "--error-unmatch", "--", path]).await?;
+    if tracked.is_some() || !root.join(relative).is_file() {
+        return Ok(diff);
+    }
+
+    // `--no-index` exits with 1 when the files differ, which they always do here
+    let diff = run_git_allow_failure(root, &["diff", "--no-color", "--no-index", "--", "/dev/null", path]).await?;
+    Ok(diff.unwrap_or_default())
+}
+
+/// Get the most recent commits on the current branch
+pub async fn log_recent(root: &Path, limit: usize) -> Result<Vec<GitCommit>, AppError> {
+    // A repository without commits has no history rather than an error
+    if run_git_allow_failure(root, &["rev-parse", "--verify", "-q", "HEAD"]).await?.is_none() {
+        return Ok(Vec::new());
+    }
+
+    let format = format!("--format=%H{0}%h{0}%an{0}%aI{0}%s{1}", FIELD_SEP, RECORD_SEP);
+    let count = format!("-n{}", limit);
+    let output = run_git(root, &["log", &count, &format]).await?;
+
+    Ok(parse_log(&output))
+}
+
+/// Run git and return stdout, failing on a non-zero exit
+pub(super) async fn run_git(root: &Path, args: &[&str]) -> Result<String, AppError> {
+    run_git_with_index(root, None, args).await
+}
+
+/// Run git against an alternate index file, failing on a non-zero exit
+pub(super) async fn run_git_with_index(
+    root: &Path,
+    index: Option<&Path>,
+    args: &[&str],
+) -> Result<String, AppError> {
+    let output = git_command(root, index, args).await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(AppError::git_error(stderr.trim().to_string()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Run git and return stdout, or `None` on a non-zero exit
+pub(super) async fn run_git_allow_failure(root: &Path, args: &[&str]) -> Result<Option<String>, AppError> {
+    let output = git_command(root, None, args).await?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+}
+
+async fn git_command(root: &Path, index: Option<&Path>, args: &[&str]) -> Result<std::process::Output, AppError> {
+    if !root.is_dir() {
+        return Err(AppError::directory_not_found(root.to_string_lossy()));
+    }
+
+    let git = which::which("git").map_err(|_| AppError::git_not_found())?;
+
+    let mut command = Command::new(git);
+    command.arg("-C").arg(root).args(args).env("GIT_OPTIONAL_LOCKS", "0");
+    if let Some(index) = index {
+        command.env("GIT_INDEX_FILE", index);
+    }
+
+    command
+        .output()
+        .await
+        .map_err(|e| AppError::git_error(format!("Failed to run git: {}", e)))
+}
+
+/// Parse `git status --porcelain=v1 --branch -z` output
+fn parse_status(output: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+    let mut records = output.split('\0').filter(|r| !r.is_empty());
+
+    while let Some(record) = records.next() {
+        if let Some(header) = record.strip_prefix("## ") {
+            parse_branch_header(header, &mut status);
+            continue;
+        }
+        if record.len() < 4 {
+            continue;
+        }
+
+        let index_status = record[..1].to_string();
+        let worktree_status = record[1..2].to_string();
+        let path = record[3..].to_string();
+
+        // Renames and copies are followed by a record holding the original path
+        let orig_path = if matches!(index_status.as_str(), "R" | "C") {
+            records.next().map(str::to_string)
+        } else {
+            None
+        };
+
+        status.files.push(GitFileStatus {
+            path,
+            orig_path,
+            index_status,
+            worktree_status,
+        });
+    }
+
+    status
+}
+
+/// Parse the `## branch...upstream [ahead N, behind M]` status header
+fn parse_branch_header(header: &str, status: &mut GitStatus) {
+    if let Some(branch) = header.strip_prefix("No commits yet on ") {
+        status.branch = Some(branch.to_string());
+        return;
+    }
+    if header.starts_with("HEAD (no branch)") {
+        return;
+    }
+
+    let (refs, tracking) = match header.split_once(" [") {
+        Some((refs, tracking)) => (refs, Some(tracking.trim_end_matches(']'))),
+        None => (header, None),
+    };
+
+    match refs.split_once("...") {
+        Some((branch, upstream)) => {
+            status.branch = Some(branch.to_string());
+            status.upstream = Some(upstream.to_string());
+        }
+        None => status.branch = Some(refs.to_string()),
+    }
+
+    for part in tracking.into_iter().flat_map(|t| t.split(", ")) {
+        if let Some(n) = part.strip_prefix("ahead ") {
+            status.ahead = n.parse().unwrap_or(0);
+        } else if let Some(n) = part.strip_prefix("behind ") {
+            status.behind = n.parse().unwrap_or(0);
+        }
+    }
+}
+
+/// Parse `git log` output written with the field and record separators
+fn parse_log(output: &str) -> Vec<GitCommit> {
+    output
+        .split(RECORD_SEP)
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(5, FIELD_SEP);
+            Some(GitCommit {
+                hash: fields.next().filter(|h| !h.is_empty())?.to_string(),
+                short_hash: fields.next()?.to_string(),
+                author: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                subject: fields.next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status() {
+        let output = "## main...origin/main [ahead 2, behind 1]\0 M src/lib.rs\0R  new.rs\0old.rs\0?? notes.md\0";
+        let status = parse_status(output);
+
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/main"));
+        assert_eq!((status.ahead, status.behind), (2, 1));
+        assert_eq!(status.files.len(), 3);
+        assert_eq!(status.files[0].path, "src/lib.rs");
+        assert_eq!(status.files[0].worktree_status, "M");
+        assert_eq!(status.files[1].path, "new.rs");
+        assert_eq!(status.files[1].orig_path.as_deref(), Some("old.rs"));
+        assert_eq!(status.files[2].index_status, "?");
+    }
+
+    #[test]
+    fn test_parse_log() {
+        let output = "abc123\u{1f}abc\u{1f}Ada\u{1f}2024-01-01T00:00:00+00:00\u{1f}Fix: a|b\u{1e}\n\
+                      def456\u{1f}def\u{1f}Bob\u{1f}2023-12-31T00:00:00+00:00\u{1f}Initial\u{1e}\n";
+        let commits = parse_log(output);
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].short_hash, "abc");
+        assert_eq!(commits[0].subject, "Fix: a|b");
+        assert_eq!(commits[1].author, "Bob");
+    }
+}
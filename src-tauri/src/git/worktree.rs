@@ -0,0 +1,81 @@
+//! Git Worktrees
+//!
+//! Creates and tears down the linked worktrees isolated sessions run in.
+
+use std::path::Path;
+
+use crate::error::AppError;
+
+use super::repo::{run_git, run_git_allow_failure};
+
+/// Create a worktree at `path` on a new `branch` started from HEAD
+pub async fn add_worktree(root: &Path, path: &Path, branch: &str) -> Result<(), AppError> {
+    let path = path.to_string_lossy();
+    run_git(root, &["worktree", "add", "-b", branch, &path, "HEAD"]).await?;
+    Ok(())
+}
+
+/// Remove a worktree, discarding any uncommitted changes in it
+pub async fn remove_worktree(root: &Path, path: &Path) -> Result<(), AppError> {
+    let path = path.to_string_lossy();
+    run_git(root, &["worktree", "remove", "--force", &path]).await?;
+    Ok(())
+}
+
+/// Commit every change in the working tree, returning `None` if it is clean
+pub async fn commit_all(root: &Path, message: &str) -> Result<Option<String>, AppError> {
+    run_git(root, &["add", "-A"]).await?;
+
+    // `diff --cached --quiet` exits non-zero when something is staged
+    if run_git_allow_failure(root, &["diff", "--cached", "--quiet"]).await?.is_some() {
+        return Ok(None);
+    }
+
+    run_git(root, &["commit", "-q", "-m", message]).await?;
+    let hash = run_git(root, &["rev-parse", "HEAD"]).await?;
+    Ok(Some(hash.trim().to_string()))
+}
+
+/// Merge `branch` into the checked-out branch with a merge commit
+///
+/// A conflicting merge is aborted, leaving the working tree as it was.
+pub async fn merge_branch(root: &Path, branch: &str, message: &str) -> Result<(), AppError> {
+    if let Err(e) = run_git(root, &["merge", "--no-ff", "-m", message, branch]).await {
+        let _ = run_git_allow_failure(root, &["merge", "--abort"]).await?;
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+/// Delete a local branch, merged or not
+pub async fn delete_branch(root: &Path, branch: &str) -> Result<(), AppError> {
+    run_git(root, &["branch", "-D", branch]).await?;
+    Ok(())
+}
+
+/// Add a pattern to the repository's local exclude file if it isn't there
+///
+/// Unlike `.gitignore`, `info/exclude` isn't tracked, so the repository's
+/// files stay untouched.
+pub async fn exclude_locally(root: &Path, pattern: &str) -> Result<(), AppError> {
+    let exclude = run_git(root, &["rev-parse", "--git-path", "info/exclude"]).await?;
+    let exclude = root.join(exclude.trim());
+
+    let existing = match tokio::fs::read_to_string(&exclude).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    if existing.lines().any(|line| line.trim() == pattern) {
+        return Ok(());
+    }
+
+    if let Some(parent) = exclude.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let separator = if existing.is_empty() || existing.ends_with('\n') { "" } else { "\n" };
+    tokio::fs::write(&exclude, format!("{}{}{}\n", existing, separator, pattern)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,291 @@
+//! Lifecycle Hooks
+//!
+//! Runs user-defined shell commands when things happen in a project: a
+//! session starting or stopping, Claude finishing a message or changing a
+//! file, or a task being completed. Details of the event are passed in
+//! `WINGMAN_*` environment variables, and each line of output is streamed to
+//! the frontend as a `hook_output` event.
+//!
+//! A project's hooks for an event run one after another, in the order they
+//! were created, so a formatter can finish before a test suite starts.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+
+use crate::error::AppError;
+use crate::events::{emit_event, event_names, HookOutputPayload};
+use crate::state::AppState;
+use crate::system;
+
+/// Longest a hook may run before it is killed
+const HOOK_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Events hooks can run on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HookEvent {
+    SessionStart,
+    SessionStop,
+    /// Claude finished a response
+    MessageComplete,
+    /// Claude created, modified, or deleted a file
+    FileChanged,
+    TaskCompleted,
+}
+
+impl HookEvent {
+    pub const ALL: [HookEvent; 5] = [
+        HookEvent::SessionStart,
+        HookEvent::SessionStop,
+        HookEvent::MessageComplete,
+        HookEvent::FileChanged,
+        HookEvent::TaskCompleted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookEvent::SessionStart => "session_start",
+            HookEvent::SessionStop => "session_stop",
+            HookEvent::MessageComplete => "message_complete",
+            HookEvent::FileChanged => "file_changed",
+            HookEvent::TaskCompleted => "task_completed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|event| event.as_str() == value)
+    }
+}
+
+/// What a hook was fired for; unset fields don't apply to the event
+#[derive(Debug, Clone, Default)]
+pub struct HookContext {
+    pub project_id: Option<String>,
+    pub session_id: Option<String>,
+    pub message_id: Option<String>,
+    pub task_id: Option<String>,
+    /// Workspace-relative path of a changed file
+    pub file_path: Option<String>,
+    /// `created`, `modified`, or `deleted`, for a changed file
+    pub file_operation: Option<String>,
+}
+
+/// Run a project's hooks for an event in the background
+///
+/// The project is looked up from the session when the context doesn't name
+/// one. Does nothing before the app state is ready or in safe mode.
+pub fn fire(app: &AppHandle, event: HookEvent, context: HookContext) {
+    let app = app.clone();
+    tokio::spawn(async move {
+        if let Err(e) = run_hooks(&app, event, context).await {
+            log::warn!("Failed to run {} hooks: {}", event.as_str(), e);
+        }
+    });
+}
+
+async fn run_hooks(app: &AppHandle, event: HookEvent, mut context: HookContext) -> Result<(), AppError> {
+    let Some(state) = app.try_state::<AppState>() else {
+        return Ok(());
+    };
+    // A broken hook shouldn't be able to get in the way of recovering
+    if state.safe_mode {
+        return Ok(());
+    }
+
+    // Hooks run in the session's directory, or else the project root
+    let mut working_dir = None;
+    if let Some(session_id) = context.session_id.as_deref() {
+        if let Some((project_id, directory)) = sqlx::query_as::<_, (Option<String>, String)>(
+            "SELECT project_id, working_directory FROM sessions WHERE id = ?",
+        )
+        .bind(session_id)
+        .fetch_optional(&state.db)
+        .await?
+        {
+            context.project_id = context.project_id.or(project_id);
+            working_dir = Some(directory);
+        }
+    }
+    let Some(project_id) = context.project_id.clone() else {
+        return Ok(());
+    };
+    if working_dir.is_none() {
+        working_dir = sqlx::query_scalar("SELECT root_path FROM projects WHERE id = ?")
+            .bind(&project_id)
+            .fetch_optional(&state.db)
+            .await?;
+    }
+    let Some(working_dir) = working_dir else {
+        return Ok(());
+    };
+
+    let hooks = sqlx::query_as::<_, (String, String)>(
+        r#"
+        SELECT id, command
+        FROM hooks
+        WHERE project_id = ? AND event = ? AND enabled = 1
+        ORDER BY created_at ASC
+        "#,
+    )
+    .bind(&project_id)
+    .bind(event.as_str())
+    .fetch_all(&state.db)
+    .await?;
+
+    let env = hook_env(event, &context);
+    for (hook_id, command) in hooks {
+        run_hook(app, &hook_id, event, &context, &command, &working_dir, &env).await;
+    }
+
+    Ok(())
+}
+
+/// Run one hook to completion, streaming its output
+async fn run_hook(
+    app: &AppHandle,
+    hook_id: &str,
+    event: HookEvent,
+    context: &HookContext,
+    command: &str,
+    working_dir: &str,
+    env: &[(&'static str, String)],
+) {
+    let emit = |stream: &str, line: Option<String>, exit_code: Option<i32>, done: bool| {
+        let _ = emit_event(
+            app,
+            event_names::HOOK_OUTPUT,
+            HookOutputPayload {
+                hook_id: hook_id.to_string(),
+                event: event.as_str().to_string(),
+                session_id: context.session_id.clone(),
+                stream: stream.to_string(),
+                line,
+                exit_code,
+                done,
+            },
+        );
+    };
+
+    let mut cmd = shell_command(command);
+    system::env::apply(&mut cmd);
+    cmd.envs(env.iter().map(|(k, v)| (*k, v.as_str())))
+        .current_dir(working_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            log::warn!("Failed to start hook {}: {}", hook_id, e);
+            emit("stderr", Some(format!("Failed to start hook: {}", e)), None, true);
+            return;
+        }
+    };
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    // Both pipes are drained together so neither can fill up and stall the hook
+    let run = async {
+        tokio::join!(
+            stream_lines(stdout, |line| emit("stdout", Some(line), None, false)),
+            stream_lines(stderr, |line| emit("stderr", Some(line), None, false)),
+        );
+        child.wait().await
+    };
+
+    match tokio::time::timeout(HOOK_TIMEOUT, run).await {
+        Ok(Ok(status)) => emit("stdout", None, status.code(), true),
+        Ok(Err(e)) => emit("stderr", Some(format!("Hook failed: {}", e)), None, true),
+        Err(_) => {
+            log::warn!("Hook {} timed out", hook_id);
+            emit(
+                "stderr",
+                Some(format!("Hook timed out after {} seconds", HOOK_TIMEOUT.as_secs())),
+                None,
+                true,
+            );
+        }
+    }
+}
+
+/// Forward each line of a pipe
+async fn stream_lines(pipe: Option<impl AsyncRead + Unpin>, mut on_line: impl FnMut(String)) {
+    let Some(pipe) = pipe else {
+        return;
+    };
+    let mut lines = BufReader::new(pipe).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        on_line(line);
+    }
+}
+
+/// Run a command line through the platform shell
+pub(crate) fn shell_command(command: &str) -> Command {
+    #[cfg(windows)]
+    {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    }
+    #[cfg(not(windows))]
+    {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    }
+}
+
+/// Environment variables describing the event to the hook
+fn hook_env(event: HookEvent, context: &HookContext) -> Vec<(&'static str, String)> {
+    let mut env = vec![("WINGMAN_EVENT", event.as_str().to_string())];
+    let fields = [
+        ("WINGMAN_PROJECT_ID", &context.project_id),
+        ("WINGMAN_SESSION_ID", &context.session_id),
+        ("WINGMAN_MESSAGE_ID", &context.message_id),
+        ("WINGMAN_TASK_ID", &context.task_id),
+        ("WINGMAN_FILE_PATH", &context.file_path),
+        ("WINGMAN_FILE_OPERATION", &context.file_operation),
+    ];
+    for (name, value) in fields {
+        if let Some(value) = value {
+            env.push((name, value.clone()));
+        }
+    }
+    env
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_event_names() {
+        for event in HookEvent::ALL {
+            assert_eq!(HookEvent::parse(event.as_str()), Some(event));
+        }
+        assert_eq!(HookEvent::parse("session_crash"), None);
+    }
+
+    #[test]
+    fn test_hook_env() {
+        let context = HookContext {
+            project_id: Some("p1".to_string()),
+            file_path: Some("src/main.rs".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            hook_env(HookEvent::FileChanged, &context),
+            vec![
+                ("WINGMAN_EVENT", "file_changed".to_string()),
+                ("WINGMAN_PROJECT_ID", "p1".to_string()),
+                ("WINGMAN_FILE_PATH", "src/main.rs".to_string()),
+            ]
+        );
+    }
+}
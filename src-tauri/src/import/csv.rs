@@ -0,0 +1,180 @@
+//! CSV Importer
+//!
+//! Reads generic CSV files and the CSV exports of Jira and Linear. The
+//! sources only differ in their column names.
+
+use crate::error::AppError;
+
+use super::{map_records, FieldAliases, ImportBatch, Importer};
+
+/// Columns of a plain CSV file
+const GENERIC: FieldAliases = FieldAliases {
+    external_id: &["id", "key", "external id", "external_id"],
+    title: &["title", "summary", "name"],
+    description: &["description", "body", "details"],
+    status: &["status", "state"],
+    priority: &["priority"],
+    estimate: &["estimate", "estimated hours", "estimated_hours", "hours"],
+    labels: &["labels", "label", "tags"],
+    parent: &["parent", "parent id", "parent_id"],
+    estimate_divisor: 1.0,
+};
+
+/// Columns of a Jira issue export; estimates are in seconds
+const JIRA: FieldAliases = FieldAliases {
+    external_id: &["issue key"],
+    title: &["summary"],
+    description: &["description"],
+    status: &["status"],
+    priority: &["priority"],
+    estimate: &["original estimate"],
+    labels: &["labels"],
+    parent: &["parent", "parent key"],
+    estimate_divisor: 3600.0,
+};
+
+/// Columns of a Linear issue export
+///
+/// Linear estimates are points rather than hours, so they aren't mapped.
+const LINEAR: FieldAliases = FieldAliases {
+    external_id: &["id"],
+    title: &["title"],
+    description: &["description"],
+    status: &["status"],
+    priority: &["priority"],
+    estimate: &[],
+    labels: &["labels"],
+    parent: &["parent issue"],
+    estimate_divisor: 1.0,
+};
+
+/// Imports a CSV file with a header row
+pub struct CsvImporter {
+    source: &'static str,
+    pub(super) aliases: FieldAliases,
+}
+
+impl CsvImporter {
+    /// Plain CSV with common column names
+    pub fn generic() -> Self {
+        Self { source: "csv", aliases: GENERIC }
+    }
+
+    /// Jira's "Export CSV (all fields)"
+    pub fn jira() -> Self {
+        Self { source: "jira", aliases: JIRA }
+    }
+
+    /// Linear's CSV export
+    pub fn linear() -> Self {
+        Self { source: "linear", aliases: LINEAR }
+    }
+}
+
+impl Importer for CsvImporter {
+    fn source(&self) -> &'static str {
+        self.source
+    }
+
+    fn parse(&self, content: &str) -> Result<ImportBatch, AppError> {
+        let mut rows = parse_csv(content)?.into_iter();
+        let (_, header) = rows
+            .next()
+            .ok_or_else(|| AppError::invalid_input("CSV file is empty"))?;
+
+        let records = rows
+            .filter(|(_, row)| row.iter().any(|cell| !cell.trim().is_empty()))
+            .map(|(line, row)| (line, header.iter().cloned().zip(row).collect()))
+            .collect();
+
+        Ok(map_records(&self.aliases, records))
+    }
+}
+
+/// Split CSV text into rows, each paired with the line it starts on
+///
+/// Handles quoted fields containing commas, escaped quotes, and line breaks.
+fn parse_csv(content: &str) -> Result<Vec<(usize, Vec<String>)>, AppError> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut row_line = 1;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push((row_line, std::mem::take(&mut row)));
+                line += 1;
+                row_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(AppError::invalid_input(format!(
+            "Unterminated quoted field starting on line {}",
+            row_line
+        )));
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push((row_line, row));
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv() {
+        let rows = parse_csv("id,title\r\n1,\"Fix \"\"login\"\", again\"\n2,\"Two\nlines\"\n3,Last").unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[1], (2, vec!["1".to_string(), "Fix \"login\", again".to_string()]));
+        assert_eq!(rows[2].1[1], "Two\nlines");
+        assert_eq!(rows[3], (5, vec!["3".to_string(), "Last".to_string()]));
+
+        assert!(parse_csv("id,title\n1,\"open").is_err());
+    }
+
+    #[test]
+    fn test_linear_import() {
+        let batch = CsvImporter::linear()
+            .parse("ID,Title,Status,Priority,Labels,Parent issue\nENG-2,Child,Todo,Urgent,\"Bug, UI\",ENG-1\n")
+            .unwrap();
+
+        let item = &batch.items[0];
+        assert_eq!(item.external_id.as_deref(), Some("ENG-2"));
+        assert_eq!(item.priority, "high");
+        assert_eq!(item.labels, vec!["Bug", "UI"]);
+        assert_eq!(item.parent_external_id.as_deref(), Some("ENG-1"));
+        assert_eq!(item.line, 2);
+    }
+}
@@ -0,0 +1,137 @@
+//! JSON Importers
+//!
+//! Reads JSON Lines files with one task object per line, and GitHub issue
+//! lists as printed by `gh issue list --json number,title,body,state,labels`.
+
+use serde_json::Value;
+
+use crate::error::AppError;
+
+use super::{map_records, FieldAliases, ImportBatch, Importer};
+
+/// Keys of a JSON Lines task object, lowercased
+const JSONL: FieldAliases = FieldAliases {
+    external_id: &["id", "externalid", "external_id", "key"],
+    title: &["title", "summary", "name"],
+    description: &["description", "body"],
+    status: &["status", "state"],
+    priority: &["priority"],
+    estimate: &["estimatedhours", "estimated_hours", "estimate"],
+    labels: &["labels", "tags"],
+    parent: &["parent", "parentid", "parent_id"],
+    estimate_divisor: 1.0,
+};
+
+/// Keys of a `gh issue list --json` issue
+const GITHUB: FieldAliases = FieldAliases {
+    external_id: &["number"],
+    title: &["title"],
+    description: &["body"],
+    status: &["state"],
+    priority: &[],
+    estimate: &[],
+    labels: &["labels"],
+    parent: &[],
+    estimate_divisor: 1.0,
+};
+
+/// Imports one JSON task object per line
+pub struct JsonlImporter;
+
+impl Importer for JsonlImporter {
+    fn source(&self) -> &'static str {
+        "jsonl"
+    }
+
+    fn parse(&self, content: &str) -> Result<ImportBatch, AppError> {
+        let mut records = Vec::new();
+
+        for (index, text) in content.lines().enumerate() {
+            if text.trim().is_empty() {
+                continue;
+            }
+            let value: Value = serde_json::from_str(text)
+                .map_err(|e| AppError::invalid_input(format!("Invalid JSON on line {}: {}", index + 1, e)))?;
+            records.push((index + 1, object_fields(&value, index + 1)?));
+        }
+
+        Ok(map_records(&JSONL, records))
+    }
+}
+
+/// Imports a JSON array of GitHub issues
+pub struct GithubImporter;
+
+impl Importer for GithubImporter {
+    fn source(&self) -> &'static str {
+        "github"
+    }
+
+    fn parse(&self, content: &str) -> Result<ImportBatch, AppError> {
+        let issues: Vec<Value> = serde_json::from_str(content)
+            .map_err(|e| AppError::invalid_input(format!("Expected a JSON array of issues: {}", e)))?;
+
+        let records = issues
+            .iter()
+            .enumerate()
+            .map(|(index, issue)| Ok((index + 1, object_fields(issue, index + 1)?)))
+            .collect::<Result<Vec<_>, AppError>>()?;
+
+        Ok(map_records(&GITHUB, records))
+    }
+}
+
+/// Flatten a JSON object into (key, text) pairs
+fn object_fields(value: &Value, line: usize) -> Result<Vec<(String, String)>, AppError> {
+    let object = value
+        .as_object()
+        .ok_or_else(|| AppError::invalid_input(format!("Expected a JSON object on line {}", line)))?;
+
+    Ok(object.iter().map(|(key, value)| (key.clone(), value_text(value))).collect())
+}
+
+/// Text of a JSON value; arrays become comma-separated lists
+///
+/// Objects stand for their `name`, which covers GitHub's label objects.
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(value_text).collect::<Vec<_>>().join(","),
+        Value::Object(object) => object.get("name").map(value_text).unwrap_or_default(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_github_import() {
+        let batch = GithubImporter
+            .parse(r#"[{"number": 42, "title": "Crash", "body": "", "state": "CLOSED", "labels": [{"name": "bug"}, {"name": "p1"}]}]"#)
+            .unwrap();
+
+        let item = &batch.items[0];
+        assert_eq!(item.external_id.as_deref(), Some("42"));
+        assert_eq!(item.status, "done");
+        assert_eq!(item.description, None);
+        assert_eq!(item.labels, vec!["bug", "p1"]);
+    }
+
+    #[test]
+    fn test_jsonl_import() {
+        let batch = JsonlImporter
+            .parse("{\"id\": \"a\", \"title\": \"One\", \"estimatedHours\": 3, \"tags\": [\"x\"]}\n\n{\"title\": \"Two\", \"parentId\": \"a\"}\n")
+            .unwrap();
+
+        assert_eq!(batch.items.len(), 2);
+        assert_eq!(batch.items[0].estimated_hours, Some(3.0));
+        assert_eq!(batch.items[0].labels, vec!["x"]);
+        assert_eq!(batch.items[1].parent_external_id.as_deref(), Some("a"));
+        assert_eq!(batch.items[1].line, 3);
+
+        assert!(JsonlImporter.parse("not json").is_err());
+    }
+}
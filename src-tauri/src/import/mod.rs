@@ -0,0 +1,369 @@
+//! Import Module
+//!
+//! Brings tasks in from other trackers. Every source has an `Importer` that
+//! turns an export into a normalized `ImportBatch`; the preview (dry run) and
+//! apply phases only ever see that batch, so they behave the same for every
+//! source.
+
+mod csv;
+mod json;
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::error::AppError;
+
+pub use self::csv::CsvImporter;
+pub use self::json::{GithubImporter, JsonlImporter};
+
+/// Sources accepted by `importer_for`
+pub const SOURCES: &[&str] = &["csv", "jira", "linear", "jsonl", "github"];
+
+/// A task as read from an export, before it is written to the database
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportItem {
+    /// ID in the source system, used to skip items imported before
+    pub external_id: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    /// Status category: todo, in_progress, or done
+    pub status: String,
+    /// Priority: low, medium, or high
+    pub priority: String,
+    pub estimated_hours: Option<f64>,
+    pub labels: Vec<String>,
+    pub parent_external_id: Option<String>,
+    /// Line (CSV and JSONL) or array index (GitHub) the item came from
+    pub line: usize,
+}
+
+/// A problem with one item that didn't stop the import
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportWarning {
+    pub line: usize,
+    pub message: String,
+}
+
+/// Which source field fed a task field
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldMapping {
+    pub field: String,
+    pub source_field: String,
+}
+
+/// Everything an importer read from an export
+#[derive(Debug, Default)]
+pub struct ImportBatch {
+    pub items: Vec<ImportItem>,
+    pub warnings: Vec<ImportWarning>,
+    pub mapping: Vec<FieldMapping>,
+    /// Source fields that didn't map to any task field
+    pub unmapped: Vec<String>,
+}
+
+/// Reads one kind of export into the normalized model
+pub trait Importer: Send + Sync {
+    /// Source name recorded against imported tasks
+    fn source(&self) -> &'static str;
+
+    /// Parse an export into items, without touching the database
+    fn parse(&self, content: &str) -> Result<ImportBatch, AppError>;
+}
+
+/// Get the importer for a source name
+pub fn importer_for(source: &str) -> Result<Box<dyn Importer>, AppError> {
+    match source {
+        "csv" => Ok(Box::new(CsvImporter::generic())),
+        "jira" => Ok(Box::new(CsvImporter::jira())),
+        "linear" => Ok(Box::new(CsvImporter::linear())),
+        "jsonl" => Ok(Box::new(JsonlImporter)),
+        "github" => Ok(Box::new(GithubImporter)),
+        other => Err(AppError::invalid_input(format!(
+            "Unknown import source: {} (expected one of {})",
+            other,
+            SOURCES.join(", ")
+        ))),
+    }
+}
+
+/// Source field names recognized for each task field, lowercased
+pub(crate) struct FieldAliases {
+    pub external_id: &'static [&'static str],
+    pub title: &'static [&'static str],
+    pub description: &'static [&'static str],
+    pub status: &'static [&'static str],
+    pub priority: &'static [&'static str],
+    pub estimate: &'static [&'static str],
+    pub labels: &'static [&'static str],
+    pub parent: &'static [&'static str],
+    /// Divisor turning the estimate into hours, e.g. 3600 for seconds
+    pub estimate_divisor: f64,
+}
+
+impl FieldAliases {
+    /// Task field a source field maps to, if any
+    fn field_for(&self, name: &str) -> Option<&'static str> {
+        let name = name.trim().to_lowercase();
+        let fields: [(&'static str, &[&str]); 8] = [
+            ("externalId", self.external_id),
+            ("title", self.title),
+            ("description", self.description),
+            ("status", self.status),
+            ("priority", self.priority),
+            ("estimatedHours", self.estimate),
+            ("labels", self.labels),
+            ("parent", self.parent),
+        ];
+        fields
+            .into_iter()
+            .find(|(_, aliases)| aliases.contains(&name.as_str()))
+            .map(|(field, _)| field)
+    }
+}
+
+/// Build a batch from records of (field name, value) pairs
+///
+/// `records` pairs each record with the line it came from. Field names are
+/// matched against `aliases`; a task field fed by several source fields
+/// (such as Jira's repeated `Labels` columns) collects them all.
+pub(crate) fn map_records(aliases: &FieldAliases, records: Vec<(usize, Vec<(String, String)>)>) -> ImportBatch {
+    let mut batch = ImportBatch::default();
+    let mut mapped: Vec<(String, String)> = Vec::new();
+    let mut unmapped: Vec<String> = Vec::new();
+
+    for (line, fields) in records {
+        let mut values: HashMap<&'static str, Vec<String>> = HashMap::new();
+
+        for (name, value) in fields {
+            match aliases.field_for(&name) {
+                Some(field) => {
+                    if !mapped.iter().any(|(f, s)| f == field && *s == name) {
+                        mapped.push((field.to_string(), name.clone()));
+                    }
+                    let value = value.trim();
+                    if !value.is_empty() {
+                        values.entry(field).or_default().push(value.to_string());
+                    }
+                }
+                None => {
+                    if !unmapped.contains(&name) {
+                        unmapped.push(name);
+                    }
+                }
+            }
+        }
+
+        let first = |field: &str| values.get(field).and_then(|v| v.first()).cloned();
+
+        let Some(title) = first("title") else {
+            batch.warnings.push(ImportWarning {
+                line,
+                message: "Skipped: no title".to_string(),
+            });
+            continue;
+        };
+
+        let status = match first("status") {
+            Some(value) => normalize_status(&value).unwrap_or_else(|| {
+                batch.warnings.push(ImportWarning {
+                    line,
+                    message: format!("Unrecognized status '{}', imported as todo", value),
+                });
+                "todo"
+            }),
+            None => "todo",
+        };
+
+        let priority = match first("priority") {
+            Some(value) => normalize_priority(&value).unwrap_or_else(|| {
+                batch.warnings.push(ImportWarning {
+                    line,
+                    message: format!("Unrecognized priority '{}', imported as medium", value),
+                });
+                "medium"
+            }),
+            None => "medium",
+        };
+
+        let estimated_hours = match first("estimatedHours") {
+            Some(value) => match value.parse::<f64>() {
+                Ok(estimate) if estimate >= 0.0 => Some(estimate / aliases.estimate_divisor),
+                _ => {
+                    batch.warnings.push(ImportWarning {
+                        line,
+                        message: format!("Ignored invalid estimate '{}'", value),
+                    });
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let mut labels: Vec<String> = Vec::new();
+        for label in values.get("labels").into_iter().flatten().flat_map(|v| v.split(',')) {
+            let label = label.trim();
+            if !label.is_empty() && !labels.iter().any(|l| l == label) {
+                labels.push(label.to_string());
+            }
+        }
+
+        batch.items.push(ImportItem {
+            external_id: first("externalId"),
+            title,
+            description: first("description"),
+            status: status.to_string(),
+            priority: priority.to_string(),
+            estimated_hours,
+            labels,
+            parent_external_id: first("parent"),
+            line,
+        });
+    }
+
+    batch.mapping = mapped
+        .into_iter()
+        .map(|(field, source_field)| FieldMapping { field, source_field })
+        .collect();
+    batch.unmapped = unmapped;
+    batch
+}
+
+/// Map a source status onto a status category
+pub(crate) fn normalize_status(value: &str) -> Option<&'static str> {
+    let value = value.trim().to_lowercase().replace(['-', '_'], " ");
+    match value.as_str() {
+        "todo" | "to do" | "open" | "opened" | "new" | "backlog" | "triage" | "unstarted" | "planned"
+        | "selected for development" | "reopened" => Some("todo"),
+        "in progress" | "doing" | "started" | "active" | "in review" | "review" | "code review" | "blocked" => {
+            Some("in_progress")
+        }
+        "done" | "closed" | "completed" | "complete" | "resolved" | "fixed" | "merged" | "canceled"
+        | "cancelled" | "duplicate" | "won't do" | "wont do" => Some("done"),
+        _ => None,
+    }
+}
+
+/// Map a source priority onto low, medium, or high
+pub(crate) fn normalize_priority(value: &str) -> Option<&'static str> {
+    match value.trim().to_lowercase().as_str() {
+        "urgent" | "highest" | "high" | "critical" | "blocker" | "p0" | "p1" | "1" => Some("high"),
+        "medium" | "normal" | "no priority" | "none" | "p2" | "2" | "0" => Some("medium"),
+        "low" | "lowest" | "minor" | "trivial" | "p3" | "p4" | "3" | "4" => Some("low"),
+        _ => None,
+    }
+}
+
+/// Outcome of a dry run
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPlan {
+    /// Items that would be created, in order
+    pub to_create: Vec<ImportItem>,
+    /// External IDs skipped because they were imported before or repeat
+    pub duplicates: Vec<String>,
+    /// Labels that don't exist in the project yet
+    pub new_labels: Vec<String>,
+    pub warnings: Vec<ImportWarning>,
+}
+
+/// Decide what an import would do
+///
+/// `imported` holds external IDs already imported from this source into the
+/// project and `labels` the project's label names.
+pub fn preview(batch: &ImportBatch, imported: &HashSet<String>, labels: &HashSet<String>) -> ImportPlan {
+    let mut seen = HashSet::new();
+    let mut to_create = Vec::new();
+    let mut duplicates = Vec::new();
+    let mut new_labels: Vec<String> = Vec::new();
+    let mut warnings = batch.warnings.clone();
+
+    for item in &batch.items {
+        if let Some(external_id) = &item.external_id {
+            if imported.contains(external_id) || !seen.insert(external_id.clone()) {
+                duplicates.push(external_id.clone());
+                continue;
+            }
+        }
+        for label in &item.labels {
+            if !labels.contains(label) && !new_labels.contains(label) {
+                new_labels.push(label.clone());
+            }
+        }
+        to_create.push(item.clone());
+    }
+
+    for item in &to_create {
+        if let Some(parent) = &item.parent_external_id {
+            if !seen.contains(parent) && !imported.contains(parent) {
+                warnings.push(ImportWarning {
+                    line: item.line,
+                    message: format!("Parent '{}' not found, imported as a top-level task", parent),
+                });
+            }
+        }
+    }
+
+    ImportPlan {
+        to_create,
+        duplicates,
+        new_labels,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(fields: &[(&str, &str)]) -> Vec<(String, String)> {
+        fields.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_map_records() {
+        let batch = map_records(
+            &CsvImporter::jira().aliases,
+            vec![
+                (2, record(&[("Issue key", "ENG-1"), ("Summary", "Login"), ("Status", "In Review"),
+                    ("Priority", "Highest"), ("Original Estimate", "7200"), ("Labels", "auth"),
+                    ("Labels", "web"), ("Reporter", "sam")])),
+                (3, record(&[("Issue key", "ENG-2"), ("Summary", ""), ("Status", "Done")])),
+                (4, record(&[("Issue key", "ENG-3"), ("Summary", "Logout"), ("Status", "Shipped")])),
+            ],
+        );
+
+        assert_eq!(batch.items.len(), 2);
+        let login = &batch.items[0];
+        assert_eq!(login.external_id.as_deref(), Some("ENG-1"));
+        assert_eq!(login.status, "in_progress");
+        assert_eq!(login.priority, "high");
+        assert_eq!(login.estimated_hours, Some(2.0));
+        assert_eq!(login.labels, vec!["auth", "web"]);
+        assert_eq!(batch.items[1].status, "todo");
+        assert_eq!(batch.warnings.len(), 2);
+        assert_eq!(batch.unmapped, vec!["Reporter"]);
+    }
+
+    #[test]
+    fn test_preview_skips_duplicates() {
+        let batch = map_records(
+            &CsvImporter::generic().aliases,
+            vec![
+                (2, record(&[("id", "1"), ("title", "A"), ("labels", "bug")])),
+                (3, record(&[("id", "2"), ("title", "B"), ("labels", "bug, ui"), ("parent", "9")])),
+                (4, record(&[("id", "2"), ("title", "B again")])),
+            ],
+        );
+        let imported = HashSet::from(["1".to_string()]);
+        let labels = HashSet::from(["bug".to_string()]);
+
+        let plan = preview(&batch, &imported, &labels);
+        assert_eq!(plan.to_create.len(), 1);
+        assert_eq!(plan.duplicates, vec!["1", "2"]);
+        assert_eq!(plan.new_labels, vec!["ui"]);
+        assert_eq!(plan.warnings.len(), 1);
+    }
+}
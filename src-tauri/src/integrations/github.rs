@@ -0,0 +1,288 @@
+//! GitHub Issues
+//!
+//! Lists a repository's issues and closes them through the REST API. Tasks
+//! imported from an issue are recorded in `import_records` under the
+//! `github` source with an `owner/name#number` external ID, which is how a
+//! completed task finds the issue to close.
+//!
+//! Requests use the token passed to the command, or else the one saved in
+//! the keychain, or else the `GITHUB_TOKEN` or `GH_TOKEN` environment
+//! variable. Public repositories can be read without one.
+
+use std::time::Duration;
+
+use reqwest::{header, Method, StatusCode};
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::error::{AppError, ErrorCode};
+use crate::secrets;
+use crate::state::AppState;
+
+const API_URL: &str = "https://api.github.com";
+
+/// REST API version requested
+const API_VERSION: &str = "2022-11-28";
+
+/// Issues requested per page, the API's maximum
+const PAGE_SIZE: usize = 100;
+
+/// Most pages of issues fetched in one import
+const MAX_PAGES: usize = 50;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Environment variables a token is read from when none is given
+const TOKEN_VARS: &[&str] = &["GITHUB_TOKEN", "GH_TOKEN"];
+
+/// Issue fields the `github` importer reads
+const ISSUE_FIELDS: &[&str] = &["number", "title", "body", "state", "labels"];
+
+/// A GitHub REST API client
+pub struct GithubClient {
+    http: reqwest::Client,
+    token: Option<String>,
+}
+
+impl GithubClient {
+    pub fn new(token: Option<String>) -> Result<Self, AppError> {
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("Wingman/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(network_error)?;
+        Ok(Self { http, token })
+    }
+
+    fn request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self
+            .http
+            .request(method, format!("{}{}", API_URL, path))
+            .header(header::ACCEPT, "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", API_VERSION);
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// List a repository's issues, oldest first, leaving out pull requests
+    ///
+    /// Issues are trimmed to the fields the `github` importer reads.
+    pub async fn list_issues(&self, repo: &str, include_closed: bool) -> Result<Vec<Value>, AppError> {
+        let state = if include_closed { "all" } else { "open" };
+        let mut issues = Vec::new();
+
+        for page in 1..=MAX_PAGES {
+            let response = self
+                .request(Method::GET, &format!("/repos/{}/issues", repo))
+                .query(&[
+                    ("state", state.to_string()),
+                    ("sort", "created".to_string()),
+                    ("direction", "asc".to_string()),
+                    ("per_page", PAGE_SIZE.to_string()),
+                    ("page", page.to_string()),
+                ])
+                .send()
+                .await
+                .map_err(network_error)?;
+            let batch: Vec<Value> = check(response, repo).await?.json().await.map_err(network_error)?;
+
+            let count = batch.len();
+            // The issues endpoint lists pull requests too
+            issues.extend(
+                batch
+                    .into_iter()
+                    .filter(|issue| issue.get("pull_request").is_none())
+                    .map(issue_fields),
+            );
+            if count < PAGE_SIZE {
+                break;
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// Close an issue as completed
+    pub async fn close_issue(&self, repo: &str, number: u64) -> Result<(), AppError> {
+        let response = self
+            .request(Method::PATCH, &format!("/repos/{}/issues/{}", repo, number))
+            .json(&json!({ "state": "closed", "state_reason": "completed" }))
+            .send()
+            .await
+            .map_err(network_error)?;
+        check(response, repo).await?;
+        Ok(())
+    }
+}
+
+/// Turn an unsuccessful response into an error
+async fn check(response: reqwest::Response, repo: &str) -> Result<reqwest::Response, AppError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let rate_limited = status == StatusCode::TOO_MANY_REQUESTS
+        || response
+            .headers()
+            .get("x-ratelimit-remaining")
+            .is_some_and(|remaining| remaining.as_bytes() == b"0");
+    let body = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<Value>(&body)
+        .ok()
+        .and_then(|body| body.get("message")?.as_str().map(str::to_string))
+        .unwrap_or(body);
+
+    Err(match status {
+        _ if rate_limited => AppError::with_details(ErrorCode::RateLimited, "GitHub API rate limit reached", message),
+        StatusCode::UNAUTHORIZED => {
+            AppError::with_details(ErrorCode::PermissionDenied, "GitHub rejected the token", message)
+        }
+        StatusCode::FORBIDDEN => AppError::with_details(
+            ErrorCode::PermissionDenied,
+            format!("The GitHub token can't do this in {}", repo),
+            message,
+        ),
+        StatusCode::NOT_FOUND => AppError::with_details(
+            ErrorCode::NotFound,
+            format!("Repository {} wasn't found, or the token can't access it", repo),
+            message,
+        ),
+        _ => AppError::with_details(ErrorCode::NetworkError, format!("GitHub returned {}", status), message),
+    })
+}
+
+fn network_error(e: reqwest::Error) -> AppError {
+    AppError::new(ErrorCode::NetworkError, format!("GitHub request failed: {}", e))
+}
+
+/// Keep only the fields the importer reads
+fn issue_fields(issue: Value) -> Value {
+    let Value::Object(mut fields) = issue else {
+        return issue;
+    };
+    let kept: Map<String, Value> = ISSUE_FIELDS
+        .iter()
+        .filter_map(|field| fields.remove(*field).map(|value| (field.to_string(), value)))
+        .collect();
+    Value::Object(kept)
+}
+
+/// Normalize a repository to `owner/name`
+///
+/// Accepts `owner/name` as well as HTTPS and SSH clone URLs.
+pub fn normalize_repo(repo: &str) -> Option<String> {
+    let repo = repo.trim();
+    let repo = ["https://github.com/", "http://github.com/", "git@github.com:", "github.com/"]
+        .iter()
+        .find_map(|prefix| repo.strip_prefix(prefix))
+        .unwrap_or(repo);
+    let repo = repo.trim_end_matches('/');
+    let repo = repo.strip_suffix(".git").unwrap_or(repo);
+
+    let (owner, name) = repo.split_once('/')?;
+    let valid = |part: &str| {
+        !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    (valid(owner) && valid(name)).then(|| format!("{}/{}", owner, name))
+}
+
+/// External ID recorded for a task imported from an issue
+pub fn external_id(repo: &str, number: &str) -> String {
+    format!("{}#{}", repo, number)
+}
+
+/// Split an external ID from `external_id` into its repository and issue number
+pub fn parse_external_id(external_id: &str) -> Option<(&str, u64)> {
+    let (repo, number) = external_id.rsplit_once('#')?;
+    Some((repo, number.parse().ok()?))
+}
+
+/// The token given, or else one from the keychain or the environment
+pub async fn resolve_token(token: Option<String>) -> Option<String> {
+    if let Some(token) = token.map(|token| token.trim().to_string()).filter(|token| !token.is_empty()) {
+        return Some(token);
+    }
+    if let Some(token) = secrets::lookup(secrets::GITHUB_TOKEN).await {
+        return Some(token);
+    }
+    TOKEN_VARS
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|token| !token.trim().is_empty()))
+}
+
+/// Close the issue a task was imported from, if its project closes issues on done
+///
+/// Runs in the background; failures are logged.
+pub fn close_issue_for_task(app: &AppHandle, task_id: &str) {
+    let app = app.clone();
+    let task_id = task_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = close_linked_issue(&app, &task_id).await {
+            log::warn!("Failed to close the GitHub issue for task {}: {}", task_id, e);
+        }
+    });
+}
+
+async fn close_linked_issue(app: &AppHandle, task_id: &str) -> Result<(), AppError> {
+    let Some(state) = app.try_state::<AppState>() else {
+        return Ok(());
+    };
+
+    let link: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT r.external_id
+        FROM import_records r
+        JOIN github_integrations g ON g.project_id = r.project_id
+        WHERE r.task_id = ? AND r.source = 'github' AND g.close_issues_on_done = 1
+        "#,
+    )
+    .bind(task_id)
+    .fetch_optional(&state.db)
+    .await?;
+    // Issues imported from a `gh` export have no repository to close them in
+    let Some((repo, number)) = link.as_deref().and_then(parse_external_id) else {
+        return Ok(());
+    };
+
+    let token = resolve_token(None).await.ok_or_else(|| {
+        AppError::invalid_input("Closing GitHub issues needs a token in the keychain, GITHUB_TOKEN, or GH_TOKEN")
+    })?;
+    GithubClient::new(Some(token))?.close_issue(repo, number).await?;
+    log::info!("Closed GitHub issue {}#{} for task {}", repo, number, task_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_repo() {
+        assert_eq!(normalize_repo("galaxy-co-ai/wingman").as_deref(), Some("galaxy-co-ai/wingman"));
+        assert_eq!(
+            normalize_repo("https://github.com/galaxy-co-ai/wingman.git").as_deref(),
+            Some("galaxy-co-ai/wingman")
+        );
+        assert_eq!(normalize_repo("git@github.com:owner/repo.js").as_deref(), Some("owner/repo.js"));
+        assert_eq!(normalize_repo("owner"), None);
+        assert_eq!(normalize_repo("owner/repo/extra"), None);
+        assert_eq!(normalize_repo("owner/"), None);
+    }
+
+    #[test]
+    fn test_external_id_round_trip() {
+        let id = external_id("owner/repo", "42");
+        assert_eq!(id, "owner/repo#42");
+        assert_eq!(parse_external_id(&id), Some(("owner/repo", 42)));
+        assert_eq!(parse_external_id("42"), None);
+    }
+
+    #[test]
+    fn test_issue_fields() {
+        let issue = json!({ "number": 1, "title": "Bug", "user": { "login": "octocat" }, "labels": [] });
+        assert_eq!(issue_fields(issue), json!({ "number": 1, "title": "Bug", "labels": [] }));
+    }
+}
@@ -0,0 +1,241 @@
+//! Jira
+//!
+//! Reads a project's issues and transitions them through the Jira Cloud REST
+//! API, authenticated with an account email and API token. Issues are
+//! identified by their key, like `OPS-7`, as in Jira's CSV export. Statuses
+//! are matched by status category, so custom workflows work as long as they
+//! have a transition into the wanted category.
+
+use serde_json::{json, Value};
+
+use super::{http_client, read_json, RemoteIssue, Tracker, TrackerFuture};
+use crate::error::AppError;
+use crate::import::normalize_priority;
+
+/// Issues requested per page, the API's maximum
+const PAGE_SIZE: usize = 100;
+
+/// Most pages of issues fetched in one import
+const MAX_PAGES: usize = 50;
+
+const ISSUE_FIELDS: &str = "summary,description,status,priority,labels";
+
+pub struct JiraTracker {
+    http: reqwest::Client,
+    base_url: String,
+    project_key: String,
+    email: String,
+    token: String,
+}
+
+impl JiraTracker {
+    pub fn new(base_url: &str, project_key: &str, email: &str, token: String) -> Result<Self, AppError> {
+        Ok(Self {
+            http: http_client()?,
+            base_url: base_url.trim().trim_end_matches('/').to_string(),
+            project_key: project_key.trim().to_string(),
+            email: email.trim().to_string(),
+            token,
+        })
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        self.http
+            .request(method, format!("{}/rest/api/3{}", self.base_url, path))
+            .basic_auth(&self.email, Some(&self.token))
+            .header(reqwest::header::ACCEPT, "application/json")
+    }
+}
+
+impl Tracker for JiraTracker {
+    fn source(&self) -> &'static str {
+        "jira"
+    }
+
+    fn list_issues(&self) -> TrackerFuture<'_, Vec<RemoteIssue>> {
+        Box::pin(async move {
+            let jql = format!("project = \"{}\" ORDER BY created ASC", self.project_key.replace('"', ""));
+            let mut issues = Vec::new();
+            let mut page_token: Option<String> = None;
+
+            for _ in 0..MAX_PAGES {
+                let mut query = vec![
+                    ("jql", jql.clone()),
+                    ("fields", ISSUE_FIELDS.to_string()),
+                    ("maxResults", PAGE_SIZE.to_string()),
+                ];
+                if let Some(page_token) = &page_token {
+                    query.push(("nextPageToken", page_token.clone()));
+                }
+                let response = self
+                    .request(reqwest::Method::GET, "/search/jql")
+                    .query(&query)
+                    .send()
+                    .await;
+                let page = read_json(response, "Jira").await?;
+
+                if let Some(batch) = page["issues"].as_array() {
+                    issues.extend(batch.iter().filter_map(issue_from_json));
+                }
+                page_token = page["nextPageToken"].as_str().map(str::to_string);
+                if page["isLast"].as_bool() != Some(false) || page_token.is_none() {
+                    break;
+                }
+            }
+
+            Ok(issues)
+        })
+    }
+
+    fn get_issue<'a>(&'a self, external_id: &'a str) -> TrackerFuture<'a, RemoteIssue> {
+        Box::pin(async move {
+            let response = self
+                .request(reqwest::Method::GET, &format!("/issue/{}", external_id))
+                .query(&[("fields", ISSUE_FIELDS)])
+                .send()
+                .await;
+            let issue = read_json(response, "Jira").await?;
+            issue_from_json(&issue).ok_or_else(|| AppError::database_not_found("Jira issue", external_id))
+        })
+    }
+
+    fn set_status<'a>(&'a self, external_id: &'a str, status: &'a str) -> TrackerFuture<'a, ()> {
+        Box::pin(async move {
+            let path = format!("/issue/{}/transitions", external_id);
+            let response = self.request(reqwest::Method::GET, &path).send().await;
+            let transitions = read_json(response, "Jira").await?;
+            let transition_id = pick_transition(&transitions["transitions"], status).ok_or_else(|| {
+                AppError::invalid_input(format!("{} has no transition to a {} status", external_id, status))
+            })?;
+
+            let response = self
+                .request(reqwest::Method::POST, &path)
+                .json(&json!({ "transition": { "id": transition_id } }))
+                .send()
+                .await;
+            read_json(response, "Jira").await?;
+            Ok(())
+        })
+    }
+}
+
+/// Read an issue from the REST API
+fn issue_from_json(issue: &Value) -> Option<RemoteIssue> {
+    let fields = &issue["fields"];
+    let description = adf_text(&fields["description"]);
+    Some(RemoteIssue {
+        external_id: issue["key"].as_str()?.to_string(),
+        title: fields["summary"].as_str()?.to_string(),
+        description: (!description.is_empty()).then_some(description),
+        status: category_status(fields["status"]["statusCategory"]["key"].as_str().unwrap_or_default()).to_string(),
+        priority: fields["priority"]["name"]
+            .as_str()
+            .and_then(normalize_priority)
+            .unwrap_or("medium")
+            .to_string(),
+        labels: fields["labels"]
+            .as_array()
+            .map(|labels| labels.iter().filter_map(|label| label.as_str().map(str::to_string)).collect())
+            .unwrap_or_default(),
+    })
+}
+
+/// Status for a status category key
+fn category_status(category: &str) -> &'static str {
+    match category {
+        "indeterminate" => "in_progress",
+        "done" => "done",
+        // new, and undefined
+        _ => "todo",
+    }
+}
+
+/// The first transition into a status category matching a status
+fn pick_transition(transitions: &Value, status: &str) -> Option<String> {
+    transitions.as_array()?.iter().find_map(|transition| {
+        let category = transition["to"]["statusCategory"]["key"].as_str()?;
+        if category_status(category) == status {
+            transition["id"].as_str().map(str::to_string)
+        } else {
+            None
+        }
+    })
+}
+
+/// Plain text of an Atlassian Document Format value, one line per block
+fn adf_text(node: &Value) -> String {
+    fn walk(node: &Value, out: &mut String) {
+        if let Some(text) = node["text"].as_str() {
+            out.push_str(text);
+        }
+        if node["type"].as_str() == Some("hardBreak") {
+            out.push('\n');
+        }
+        if let Some(content) = node["content"].as_array() {
+            for child in content {
+                walk(child, out);
+            }
+            // Separate paragraphs, headings, list items, and the like
+            if !out.ends_with('\n') && node["type"].as_str() != Some("doc") {
+                out.push('\n');
+            }
+        }
+    }
+
+    // Older sites and some fields return plain text
+    if let Some(text) = node.as_str() {
+        return text.trim().to_string();
+    }
+    let mut out = String::new();
+    walk(node, &mut out);
+    out.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_from_json() {
+        let issue = json!({
+            "key": "OPS-7",
+            "fields": {
+                "summary": "Rotate keys",
+                "description": {
+                    "type": "doc",
+                    "content": [
+                        { "type": "paragraph", "content": [{ "type": "text", "text": "First" }] },
+                        { "type": "paragraph", "content": [{ "type": "text", "text": "Second" }] }
+                    ]
+                },
+                "status": { "statusCategory": { "key": "indeterminate" } },
+                "priority": { "name": "Highest" },
+                "labels": ["infra"]
+            }
+        });
+        let issue = issue_from_json(&issue).unwrap();
+        assert_eq!(issue.external_id, "OPS-7");
+        assert_eq!(issue.description.as_deref(), Some("First\nSecond"));
+        assert_eq!(issue.status, "in_progress");
+        assert_eq!(issue.priority, "high");
+        assert_eq!(issue.labels, vec!["infra"]);
+
+        let bare = json!({ "key": "OPS-8", "fields": { "summary": "Bare", "description": null } });
+        let bare = issue_from_json(&bare).unwrap();
+        assert_eq!(bare.description, None);
+        assert_eq!(bare.status, "todo");
+        assert_eq!(bare.priority, "medium");
+    }
+
+    #[test]
+    fn test_pick_transition() {
+        let transitions = json!([
+            { "id": "11", "to": { "statusCategory": { "key": "new" } } },
+            { "id": "21", "to": { "statusCategory": { "key": "indeterminate" } } },
+            { "id": "31", "to": { "statusCategory": { "key": "done" } } }
+        ]);
+        assert_eq!(pick_transition(&transitions, "done").as_deref(), Some("31"));
+        assert_eq!(pick_transition(&transitions, "todo").as_deref(), Some("11"));
+        assert_eq!(pick_transition(&json!([]), "done"), None);
+    }
+}
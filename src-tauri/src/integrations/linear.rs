@@ -0,0 +1,246 @@
+//! Linear
+//!
+//! Reads a team's issues and moves them between workflow states through
+//! Linear's GraphQL API, authenticated with a personal API key. Issues are
+//! identified by their identifier, like `ENG-42`, as in Linear's CSV export.
+
+use serde_json::{json, Value};
+
+use super::{http_client, read_json, RemoteIssue, Tracker, TrackerFuture};
+use crate::error::{AppError, ErrorCode};
+
+const API_URL: &str = "https://api.linear.app/graphql";
+
+/// Issues requested per page
+const PAGE_SIZE: usize = 100;
+
+/// Most pages of issues fetched in one import
+const MAX_PAGES: usize = 50;
+
+const ISSUE_FIELDS: &str = "identifier title description priority state { type } labels { nodes { name } }";
+
+pub struct LinearTracker {
+    http: reqwest::Client,
+    team_id: String,
+    api_key: String,
+}
+
+impl LinearTracker {
+    pub fn new(team_id: &str, api_key: String) -> Result<Self, AppError> {
+        Ok(Self {
+            http: http_client()?,
+            team_id: team_id.trim().to_string(),
+            api_key,
+        })
+    }
+
+    /// Run a GraphQL query and return its `data`
+    async fn query(&self, query: &str, variables: Value) -> Result<Value, AppError> {
+        let response = self
+            .http
+            .post(API_URL)
+            .header(reqwest::header::AUTHORIZATION, &self.api_key)
+            .json(&json!({ "query": query, "variables": variables }))
+            .send()
+            .await;
+        let mut body = read_json(response, "Linear").await?;
+
+        // GraphQL reports failures in a successful response
+        if let Some(message) = body
+            .get("errors")
+            .and_then(|errors| errors.get(0))
+            .and_then(|error| error.get("message"))
+            .and_then(Value::as_str)
+        {
+            return Err(AppError::with_details(
+                ErrorCode::NetworkError,
+                "Linear rejected the request",
+                message,
+            ));
+        }
+        Ok(body["data"].take())
+    }
+}
+
+impl Tracker for LinearTracker {
+    fn source(&self) -> &'static str {
+        "linear"
+    }
+
+    fn list_issues(&self) -> TrackerFuture<'_, Vec<RemoteIssue>> {
+        Box::pin(async move {
+            let query = format!(
+                r#"query($teamId: String!, $first: Int!, $after: String) {{
+                    team(id: $teamId) {{
+                        issues(first: $first, after: $after) {{
+                            nodes {{ {} }}
+                            pageInfo {{ hasNextPage endCursor }}
+                        }}
+                    }}
+                }}"#,
+                ISSUE_FIELDS
+            );
+
+            let mut issues = Vec::new();
+            let mut after = Value::Null;
+            for _ in 0..MAX_PAGES {
+                let data = self
+                    .query(&query, json!({ "teamId": self.team_id, "first": PAGE_SIZE, "after": after }))
+                    .await?;
+                let page = &data["team"]["issues"];
+                if let Some(nodes) = page["nodes"].as_array() {
+                    issues.extend(nodes.iter().filter_map(issue_from_node));
+                }
+                if page["pageInfo"]["hasNextPage"].as_bool() != Some(true) {
+                    break;
+                }
+                after = page["pageInfo"]["endCursor"].clone();
+            }
+
+            Ok(issues)
+        })
+    }
+
+    fn get_issue<'a>(&'a self, external_id: &'a str) -> TrackerFuture<'a, RemoteIssue> {
+        Box::pin(async move {
+            let query = format!("query($id: String!) {{ issue(id: $id) {{ {} }} }}", ISSUE_FIELDS);
+            let data = self.query(&query, json!({ "id": external_id })).await?;
+            issue_from_node(&data["issue"]).ok_or_else(|| AppError::database_not_found("Linear issue", external_id))
+        })
+    }
+
+    fn set_status<'a>(&'a self, external_id: &'a str, status: &'a str) -> TrackerFuture<'a, ()> {
+        Box::pin(async move {
+            let data = self
+                .query(
+                    "query($id: String!) { issue(id: $id) { team { states { nodes { id type position } } } } }",
+                    json!({ "id": external_id }),
+                )
+                .await?;
+            let states = data["issue"]["team"]["states"]["nodes"].as_array().cloned().unwrap_or_default();
+            let state_id = pick_state(&states, status).ok_or_else(|| {
+                AppError::invalid_input(format!("The team of {} has no workflow state for {}", external_id, status))
+            })?;
+
+            let data = self
+                .query(
+                    "mutation($id: String!, $stateId: String!) { issueUpdate(id: $id, input: { stateId: $stateId }) { success } }",
+                    json!({ "id": external_id, "stateId": state_id }),
+                )
+                .await?;
+            if data["issueUpdate"]["success"].as_bool() != Some(true) {
+                return Err(AppError::new(
+                    ErrorCode::NetworkError,
+                    format!("Linear didn't update {}", external_id),
+                ));
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Read an issue node from a query
+fn issue_from_node(node: &Value) -> Option<RemoteIssue> {
+    Some(RemoteIssue {
+        external_id: node["identifier"].as_str()?.to_string(),
+        title: node["title"].as_str()?.to_string(),
+        description: node["description"]
+            .as_str()
+            .map(str::trim)
+            .filter(|description| !description.is_empty())
+            .map(str::to_string),
+        status: state_status(node["state"]["type"].as_str().unwrap_or_default()).to_string(),
+        priority: priority_name(node["priority"].as_i64().unwrap_or(0)).to_string(),
+        labels: node["labels"]["nodes"]
+            .as_array()
+            .map(|labels| {
+                labels
+                    .iter()
+                    .filter_map(|label| label["name"].as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
+/// Status category for a workflow state type
+fn state_status(state_type: &str) -> &'static str {
+    match state_type {
+        "started" => "in_progress",
+        "completed" | "canceled" => "done",
+        // backlog, unstarted, and triage
+        _ => "todo",
+    }
+}
+
+/// Workflow state types a status category moves an issue to, most preferred first
+fn state_types(status: &str) -> &'static [&'static str] {
+    match status {
+        "in_progress" => &["started"],
+        "done" => &["completed"],
+        _ => &["unstarted", "backlog"],
+    }
+}
+
+/// The first state, by position, of the most preferred type for a status category
+fn pick_state(states: &[Value], status: &str) -> Option<String> {
+    state_types(status).iter().find_map(|state_type| {
+        states
+            .iter()
+            .filter(|state| state["type"].as_str() == Some(state_type))
+            .min_by(|a, b| {
+                let position = |state: &Value| state["position"].as_f64().unwrap_or(f64::MAX);
+                position(a).total_cmp(&position(b))
+            })
+            .and_then(|state| state["id"].as_str().map(str::to_string))
+    })
+}
+
+/// Priority for Linear's 0 (none) to 4 (low) scale
+fn priority_name(priority: i64) -> &'static str {
+    match priority {
+        1 | 2 => "high",
+        4 => "low",
+        _ => "medium",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_from_node() {
+        let node = json!({
+            "identifier": "ENG-42",
+            "title": "Fix login",
+            "description": "  ",
+            "priority": 1,
+            "state": { "type": "started" },
+            "labels": { "nodes": [{ "name": "bug" }] }
+        });
+        let issue = issue_from_node(&node).unwrap();
+        assert_eq!(issue.external_id, "ENG-42");
+        assert_eq!(issue.description, None);
+        assert_eq!(issue.status, "in_progress");
+        assert_eq!(issue.priority, "high");
+        assert_eq!(issue.labels, vec!["bug"]);
+
+        assert!(issue_from_node(&json!({ "title": "No identifier" })).is_none());
+    }
+
+    #[test]
+    fn test_pick_state() {
+        let states = vec![
+            json!({ "id": "b", "type": "backlog", "position": 0.0 }),
+            json!({ "id": "t2", "type": "unstarted", "position": 2.0 }),
+            json!({ "id": "t1", "type": "unstarted", "position": 1.0 }),
+            json!({ "id": "c", "type": "canceled", "position": 5.0 }),
+        ];
+        assert_eq!(pick_state(&states, "todo").as_deref(), Some("t1"));
+        assert_eq!(pick_state(&states, "in_progress"), None);
+        // Canceled counts as done when reading, but isn't a target
+        assert_eq!(pick_state(&states, "done"), None);
+        assert_eq!(pick_state(&states[..1], "todo").as_deref(), Some("b"));
+    }
+}
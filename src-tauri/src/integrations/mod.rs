@@ -0,0 +1,225 @@
+//! Tracker Integrations
+//!
+//! Clients for external issue trackers that Wingman pulls tasks from and
+//! pushes task status back to over their APIs. File exports from the same
+//! trackers are read by the `import` module instead.
+//!
+//! Linear and Jira implement `Tracker`, so importing and two-way status sync
+//! work the same for both; `tracker_for` picks the adapter for a project's
+//! configuration. GitHub predates the trait and has its own client.
+
+pub mod github;
+mod jira;
+mod linear;
+
+use std::future::Future;
+use std::pin::Pin;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{AppError, ErrorCode};
+use crate::secrets;
+
+pub use self::jira::JiraTracker;
+pub use self::linear::LinearTracker;
+
+/// Trackers accepted by `tracker_for`
+pub const TRACKERS: &[&str] = &["linear", "jira"];
+
+/// A future returned by a `Tracker`
+pub type TrackerFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, AppError>> + Send + 'a>>;
+
+/// An issue as read from a tracker
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteIssue {
+    /// Human-readable key, like `ENG-42`, matching the tracker's CSV export
+    pub external_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    /// Status category: todo, in_progress, or done
+    pub status: String,
+    /// Priority: low, medium, or high
+    pub priority: String,
+    pub labels: Vec<String>,
+}
+
+/// Reads issues from and writes status to one external tracker
+pub trait Tracker: Send + Sync {
+    /// Source name recorded against imported tasks, shared with the file importer
+    fn source(&self) -> &'static str;
+
+    /// Every issue in the configured team or project
+    fn list_issues(&self) -> TrackerFuture<'_, Vec<RemoteIssue>>;
+
+    /// One issue, as it is now
+    fn get_issue<'a>(&'a self, external_id: &'a str) -> TrackerFuture<'a, RemoteIssue>;
+
+    /// Move an issue to a state in the given status category
+    fn set_status<'a>(&'a self, external_id: &'a str, status: &'a str) -> TrackerFuture<'a, ()>;
+}
+
+/// Where a project's issues live in a tracker
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "tracker", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum TrackerConfig {
+    Linear {
+        /// Team ID or key
+        team_id: String,
+    },
+    Jira {
+        /// Site URL, like `https://acme.atlassian.net`
+        base_url: String,
+        project_key: String,
+        /// Account the API token belongs to
+        email: String,
+    },
+}
+
+impl TrackerConfig {
+    pub fn tracker(&self) -> &'static str {
+        match self {
+            TrackerConfig::Linear { .. } => "linear",
+            TrackerConfig::Jira { .. } => "jira",
+        }
+    }
+
+    /// Environment variable a token is read from when none is given or saved
+    pub fn token_var(&self) -> &'static str {
+        match self {
+            TrackerConfig::Linear { .. } => "LINEAR_API_KEY",
+            TrackerConfig::Jira { .. } => "JIRA_API_TOKEN",
+        }
+    }
+
+    /// Keychain secret a token is read from when none is given
+    pub fn secret_name(&self) -> &'static str {
+        match self {
+            TrackerConfig::Linear { .. } => secrets::LINEAR_API_KEY,
+            TrackerConfig::Jira { .. } => secrets::JIRA_API_TOKEN,
+        }
+    }
+
+    /// Check required fields are filled in
+    pub fn validate(&self) -> Result<(), AppError> {
+        let filled = match self {
+            TrackerConfig::Linear { team_id } => !team_id.trim().is_empty(),
+            TrackerConfig::Jira {
+                base_url,
+                project_key,
+                email,
+            } => {
+                base_url.starts_with("https://")
+                    && !project_key.trim().is_empty()
+                    && email.contains('@')
+            }
+        };
+        if filled {
+            Ok(())
+        } else {
+            Err(AppError::invalid_input(format!(
+                "Incomplete {} configuration",
+                self.tracker()
+            )))
+        }
+    }
+}
+
+/// Get the adapter for a tracker configuration
+pub async fn tracker_for(config: &TrackerConfig, token: Option<String>) -> Result<Box<dyn Tracker>, AppError> {
+    let token = resolve_token(token, config.secret_name(), config.token_var())
+        .await
+        .ok_or_else(|| {
+            AppError::invalid_input(format!(
+                "A {} token is required; pass one, save one, or set {}",
+                config.tracker(),
+                config.token_var()
+            ))
+        })?;
+
+    match config {
+        TrackerConfig::Linear { team_id } => Ok(Box::new(LinearTracker::new(team_id, token)?)),
+        TrackerConfig::Jira {
+            base_url,
+            project_key,
+            email,
+        } => Ok(Box::new(JiraTracker::new(base_url, project_key, email, token)?)),
+    }
+}
+
+/// The token given, or else one from the keychain or an environment variable
+pub async fn resolve_token(token: Option<String>, secret_name: &str, var: &str) -> Option<String> {
+    if let Some(token) = token.map(|token| token.trim().to_string()).filter(|token| !token.is_empty()) {
+        return Some(token);
+    }
+    if let Some(token) = secrets::lookup(secret_name).await {
+        return Some(token);
+    }
+    std::env::var(var).ok().filter(|token| !token.trim().is_empty())
+}
+
+/// An HTTP client for talking to trackers
+fn http_client() -> Result<reqwest::Client, AppError> {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .user_agent(concat!("Wingman/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| AppError::new(ErrorCode::NetworkError, format!("Failed to create HTTP client: {}", e)))
+}
+
+/// Read a successful JSON response, or turn a failed one into an error
+async fn read_json(response: reqwest::Result<reqwest::Response>, service: &str) -> Result<Value, AppError> {
+    let response = response
+        .map_err(|e| AppError::new(ErrorCode::NetworkError, format!("{} request failed: {}", service, e)))?;
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+
+    if status.is_success() {
+        // Some endpoints answer with no content
+        if body.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        return serde_json::from_str(&body).map_err(|e| {
+            AppError::new(ErrorCode::NetworkError, format!("{} returned invalid JSON: {}", service, e))
+        });
+    }
+
+    let (code, message) = match status.as_u16() {
+        401 => (ErrorCode::PermissionDenied, format!("{} rejected the credentials", service)),
+        403 => (ErrorCode::PermissionDenied, format!("{} denied access", service)),
+        404 => (ErrorCode::NotFound, format!("{} couldn't find the requested item", service)),
+        429 => (ErrorCode::RateLimited, format!("{} rate limit reached", service)),
+        _ => (ErrorCode::NetworkError, format!("{} returned {}", service, status)),
+    };
+    Err(AppError::with_details(code, message, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_tracker_config_serde() {
+        let config: TrackerConfig = serde_json::from_value(json!({ "tracker": "linear", "teamId": "ENG" })).unwrap();
+        assert_eq!(config, TrackerConfig::Linear { team_id: "ENG".to_string() });
+        assert_eq!(config.token_var(), "LINEAR_API_KEY");
+
+        let jira = json!({
+            "tracker": "jira",
+            "baseUrl": "https://acme.atlassian.net",
+            "projectKey": "OPS",
+            "email": "dev@acme.io"
+        });
+        let config: TrackerConfig = serde_json::from_value(jira.clone()).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(serde_json::to_value(&config).unwrap(), jira);
+
+        let insecure = TrackerConfig::Jira {
+            base_url: "http://acme.atlassian.net".to_string(),
+            project_key: "OPS".to_string(),
+            email: "dev@acme.io".to_string(),
+        };
+        assert!(insecure.validate().is_err());
+    }
+}
@@ -2,91 +2,439 @@
 //!
 //! This is the Rust backend for the Wingman application.
 
+mod api;
+mod attachments;
+mod checkpoints;
 mod commands;
 mod db;
+mod deep_link;
 mod error;
 mod events;
+mod git;
+mod hooks;
+mod import;
+mod integrations;
+mod logging;
+mod mcp;
+mod notifications;
+mod paths;
+mod secrets;
 mod state;
+mod system;
+mod terminal;
+mod tray;
+mod webhooks;
 mod claude;
 
-use state::AppState;
+/// Request and response types of the local HTTP API, for `wingman-cli`
+pub use api::types as api_types;
+
+use state::{AppState, StartupTracker};
 use tauri::Manager;
 
-/// Initialize the application
-async fn init_app() -> Result<AppState, error::AppError> {
-    // Get the app data directory
-    let data_dir = dirs::data_local_dir()
+/// Environment variable that enables safe mode when set to `1` or `true`
+const SAFE_MODE_ENV: &str = "WINGMAN_SAFE_MODE";
+
+/// Command-line flag that enables safe mode
+const SAFE_MODE_FLAG: &str = "--safe-mode";
+
+/// Command-line flag that runs the MCP server on stdio instead of the app
+pub(crate) const MCP_SERVER_FLAG: &str = "--mcp-server";
+
+/// Command-line flag naming the project MCP tools act on by default
+pub(crate) const MCP_PROJECT_FLAG: &str = "--project";
+
+/// Check whether the app was launched in safe mode
+///
+/// Safe mode only brings up the database and core commands, so a bad
+/// persisted setting or background job can't crash the app at boot.
+fn safe_mode_requested() -> bool {
+    let from_env = std::env::var(SAFE_MODE_ENV)
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+
+    from_env || std::env::args().any(|arg| arg == SAFE_MODE_FLAG)
+}
+
+/// Get the app data directory
+fn app_data_dir() -> Result<std::path::PathBuf, error::AppError> {
+    Ok(dirs::data_local_dir()
         .ok_or_else(|| error::AppError::new(
             error::ErrorCode::Unknown,
             "Could not determine app data directory",
         ))?
-        .join("com.wingman.app");
+        .join("com.wingman.app"))
+}
+
+/// Initialize the application
+async fn init_app(app: &tauri::AppHandle, safe_mode: bool) -> Result<AppState, error::AppError> {
+    // Get the app data directory
+    let data_dir = app_data_dir()?;
 
     // Create database path
-    let db_path = data_dir.join("wingman.db");
+    let db_path = data_dir.join(db::DB_FILE_NAME);
+
+    // Initialize database, reporting progress while migrations run
+    let pool = db::create_pool(&db_path, |progress| {
+        app.state::<StartupTracker>().set_migration(progress);
+        let _ = events::emit_event(app, events::event_names::MIGRATION_PROGRESS, progress);
+    })
+    .await?;
+
+    let state = AppState::new(pool, data_dir, safe_mode);
+
+    if safe_mode {
+        log::warn!("Starting in safe mode - background services are disabled");
+        return Ok(state);
+    }
+
+    // Pick up the login shell's PATH so the CLI can be found from a GUI launch
+    system::env::init().await;
+
+    // Apply the persisted CLI concurrency limit
+    let max_concurrent = db::settings::get_setting(&state.db, db::settings::MAX_CONCURRENT_SESSIONS)
+        .await?
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|n| *n > 0);
+    state.cli_manager.set_max_concurrent(None, max_concurrent).await;
+
+    // Apply the persisted CLI executable override
+    let binary_path = db::settings::get_setting(&state.db, db::settings::CLAUDE_BINARY_PATH)
+        .await?
+        .filter(|v| !v.is_empty())
+        .map(std::path::PathBuf::from);
+    state.cli_manager.set_binary_path(binary_path);
+
+    // Apply the persisted crash restart policy
+    let max_restarts = db::settings::get_setting(&state.db, db::settings::CLI_MAX_RESTARTS)
+        .await?
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    state.cli_manager.set_max_restarts(max_restarts);
 
-    // Initialize database
-    let pool = db::create_pool(&db_path).await?;
+    // Apply the persisted output replay limit
+    if let Some(max_chunks) = db::settings::get_setting(&state.db, db::settings::OUTPUT_REPLAY_MAX_CHUNKS)
+        .await?
+        .and_then(|v| v.parse::<usize>().ok())
+    {
+        state.cli_manager.replay.set_max_chunks(max_chunks);
+    }
 
-    Ok(AppState::new(pool))
+    // Apply the persisted IPC rate limit
+    if let Some(limit) = db::settings::get_setting(&state.db, db::settings::IPC_RATE_LIMIT)
+        .await?
+        .and_then(|v| v.parse::<usize>().ok())
+    {
+        state.ipc_limiter.set_limit(limit);
+    }
+
+    // Apply the persisted file diff size limit
+    if let Some(max_bytes) = db::settings::get_setting(&state.db, db::settings::FILE_DIFF_MAX_BYTES)
+        .await?
+        .and_then(|v| v.parse::<usize>().ok())
+    {
+        state.file_watcher.set_diff_max_bytes(max_bytes).await;
+    }
+
+    // Apply the persisted file watcher channel capacity
+    if let Some(capacity) = db::settings::get_setting(&state.db, db::settings::WATCHER_CHANNEL_CAPACITY)
+        .await?
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|n| *n > 0)
+    {
+        state.file_watcher.set_channel_capacity(capacity).await;
+    }
+
+    // Apply the persisted file watcher debounce
+    if let Some(debounce_ms) = commands::settings::get_value(&state.db, commands::settings::SettingKey::DebounceMs)
+        .await?
+        .as_u64()
+    {
+        state.file_watcher.set_debounce_ms(debounce_ms);
+    }
+
+    // Apply the persisted close-to-tray preference
+    if let Some(enabled) = commands::settings::get_value(&state.db, commands::settings::SettingKey::BackgroundMode)
+        .await?
+        .as_bool()
+    {
+        state.set_background_mode(enabled);
+    }
+
+    // Apply the persisted file watcher polling preferences
+    if let Some(mode) = commands::settings::get_value(&state.db, commands::settings::SettingKey::WatcherPolling)
+        .await?
+        .as_str()
+        .and_then(state::file_watcher::PollingMode::parse)
+    {
+        state.file_watcher.set_polling_mode(mode).await;
+    }
+    if let Some(interval_ms) = commands::settings::get_value(&state.db, commands::settings::SettingKey::PollIntervalMs)
+        .await?
+        .as_u64()
+    {
+        state.file_watcher.set_poll_interval_ms(interval_ms);
+    }
+
+    // Empty the trash of items past the retention window
+    commands::trash::purge_expired(&state.db).await?;
+
+    // Keep the activity log within its retention policy
+    commands::activity::spawn_activity_prune_job(state.db.clone());
+
+    // Create tasks from recurring task templates as they come due
+    commands::recurring_task::spawn_recurring_task_job(app.clone(), state.db.clone());
+
+    // Keep session events for windows that were closed when they were emitted
+    commands::event_buffer::spawn_event_buffer(app.clone(), state.db.clone());
+
+    // Notify about tasks due soon and milestones past their target date
+    notifications::spawn_notification_job(app.clone(), state.db.clone());
+
+    // Webhook secrets stored before they were kept in the keychain
+    webhooks::spawn_secret_migration(state.db.clone());
+
+    Ok(state)
+}
+
+/// Check whether the binary was launched as an MCP server
+pub fn mcp_server_requested() -> bool {
+    std::env::args().any(|arg| arg == MCP_SERVER_FLAG)
+}
+
+/// Serve the project board over MCP on stdin/stdout, without the app window
+pub fn run_mcp_server() {
+    // Logs go to stderr and the log files, leaving stdout to the protocol
+    logging::init(logging::MCP_LOG_PREFIX);
+
+    let args: Vec<String> = std::env::args().collect();
+    let project_id = args
+        .iter()
+        .position(|arg| arg == MCP_PROJECT_FLAG)
+        .and_then(|i| args.get(i + 1))
+        .cloned();
+
+    let result = tauri::async_runtime::block_on(async {
+        let db_path = app_data_dir()?.join(db::DB_FILE_NAME);
+        let pool = db::create_pool(&db_path, |_| {}).await?;
+        mcp::server::serve(pool, project_id).await
+    });
+
+    if let Err(e) = result {
+        log::error!("MCP server failed: {}", e);
+        std::process::exit(1);
+    }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Initialize logging
-    env_logger::init();
+    logging::init(logging::APP_LOG_PREFIX);
 
     tauri::Builder::default()
+        // Must come first, so a second launch hands its link over before starting anything
+        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            deep_link::focus_main_window(app);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
         .setup(|app| {
             // Initialize app state asynchronously
             let handle = app.handle().clone();
+            let safe_mode = safe_mode_requested();
+            app.manage(StartupTracker::new());
+            deep_link::init(&handle);
+            if let Err(e) = tray::init(&handle) {
+                log::warn!("Failed to add the tray icon: {}", e);
+            }
             tauri::async_runtime::spawn(async move {
-                match init_app().await {
+                match init_app(&handle, safe_mode).await {
                     Ok(state) => {
                         handle.manage(state);
+                        tray::spawn_tray_updates(handle.clone());
+                        if !safe_mode {
+                            commands::daily_summary::spawn_daily_summary_job(handle.clone());
+                            api::spawn_api_server(handle.clone());
+                        }
+                        handle.state::<StartupTracker>().finish(None);
                         log::info!("Wingman initialized successfully");
                     }
                     Err(e) => {
+                        handle.state::<StartupTracker>().finish(Some(e.to_string()));
                         log::error!("Failed to initialize Wingman: {}", e);
                     }
                 }
             });
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .on_window_event(|window, event| {
+            // Closing the main window leaves Wingman running in the tray, unless
+            // background mode is off
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                let app = window.app_handle();
+                let background = app.try_state::<AppState>().is_none_or(|state| state.background_mode());
+                if window.label() == "main" && background && app.tray_by_id(tray::TRAY_ID).is_some() {
+                    api.prevent_close();
+                    let _ = window.hide();
+                }
+            }
+        })
+        .invoke_handler(state::with_command_guards(tauri::generate_handler![
             // System commands
             commands::system_get_app_info,
+            commands::system_get_startup_status,
+            commands::system_get_logs,
+            commands::system_open_log_dir,
+            commands::system_get_ipc_rate_limit,
+            commands::system_set_ipc_rate_limit,
+            commands::system_get_command_metrics,
+            commands::system_clear_command_metrics,
             commands::system_check_cli,
+            commands::system_set_cli_path,
+            commands::system_cli_login,
             commands::system_open_external,
             commands::system_open_path,
             commands::system_select_directory,
+            commands::system_list_children,
+            commands::system_kill_child,
+            commands::system_generate_diagnostics,
+            commands::system_health,
+            // Settings commands
+            commands::settings_get,
+            commands::settings_get_all,
+            commands::settings_set,
+            // Window commands
+            commands::window_open_observer,
+            commands::window_set_observer,
+            commands::window_is_observer,
             // Session commands
             commands::session_create,
             commands::session_load,
             commands::session_start_cli,
             commands::session_stop_cli,
+            commands::session_respond_permission,
             commands::session_send_message,
             commands::session_cancel_response,
             commands::session_delete,
             commands::session_rename,
+            commands::session_fork,
+            commands::session_archive,
+            commands::session_unarchive,
+            commands::session_pin,
+            commands::session_reorder,
+            commands::session_update_settings,
+            commands::session_handoff,
+            commands::session_compact,
+            commands::session_get_summary,
+            commands::session_handoff_list,
+            commands::session_link_task,
+            commands::session_unlink_task,
+            commands::session_get_tasks,
+            commands::checkpoint_list,
+            commands::checkpoint_restore_file,
+            commands::checkpoint_restore_all,
+            commands::file_history,
+            commands::file_restore_version,
+            commands::file_sessions,
+            commands::task_get_sessions,
+            commands::session_create_for_task,
+            commands::run_list,
+            commands::run_get,
+            commands::run_finish,
+            commands::task_sync_from_claude,
+            commands::claude_todo_sync_get,
+            commands::claude_todo_sync_set,
+            commands::git_status,
+            commands::git_current_branch,
+            commands::git_diff_file,
+            commands::git_log_recent,
             commands::session_list,
             commands::session_save_message,
+            commands::session_discard_drafts,
+            commands::message_get_range,
+            commands::message_get_attachments,
+            commands::session_get_concurrency_limit,
+            commands::session_set_concurrency_limit,
+            commands::session_get_restart_limit,
+            commands::session_set_restart_limit,
+            commands::session_replay_recent,
+            commands::session_get_cli_logs,
+            commands::session_get_replay_limit,
+            commands::session_set_replay_limit,
+            commands::session_set_autocommit,
+            commands::session_create_worktree,
+            commands::session_template_create,
+            commands::session_template_list,
+            commands::session_template_update,
+            commands::session_template_delete,
+            commands::session_create_from_template,
+            // Prompt library commands
+            commands::prompt_create,
+            commands::prompt_list,
+            commands::prompt_delete,
+            commands::prompt_render,
             // Activity and file watcher commands
             commands::file_watcher_start,
             commands::file_watcher_stop,
+            commands::file_watcher_add_path,
+            commands::file_watcher_remove_path,
+            commands::file_watcher_get_diff_limit,
+            commands::file_watcher_set_diff_limit,
+            commands::file_watcher_get_stats,
+            commands::file_watcher_status,
+            commands::file_watcher_get_project_limit,
+            commands::file_watcher_set_project_limit,
+            commands::project_watch_settings_get,
+            commands::project_watch_settings_set,
+            commands::project_watch_settings_delete,
+            commands::file_watcher_set_channel_capacity,
+            commands::file_watcher_benchmark,
             commands::file_watcher_record_claude_write,
             commands::activity_get,
+            commands::message_related_activity,
             commands::activity_clear,
+            commands::activity_stats,
+            commands::activity_get_retention,
+            commands::activity_set_retention,
+            commands::activity_prune,
             commands::activity_save,
+            commands::activity_export,
             // Project commands
             commands::project_create,
             commands::project_get_all,
             commands::project_get,
             commands::project_update,
             commands::project_delete,
+            commands::delete_preview,
+            commands::trash_list,
+            commands::trash_restore,
+            commands::trash_purge,
+            commands::trash_get_retention_days,
+            commands::trash_set_retention_days,
+            commands::project_check_preview,
+            commands::project_plan_apply,
+            commands::import_preview,
+            commands::import_apply,
+            // GitHub integration commands
+            commands::github_integration_get,
+            commands::github_integration_set,
+            commands::github_integration_delete,
+            commands::github_import_issues,
+            // Tracker integration commands
+            commands::integration_get_all,
+            commands::integration_set,
+            commands::integration_delete,
+            commands::integration_import,
+            commands::integration_push,
+            commands::integration_sync_log_get,
+            commands::project_export_bundle,
+            commands::project_import_bundle,
+            // Context commands
+            commands::context_lookup,
+            commands::context_index_refresh,
             // Milestone commands
             commands::milestone_create,
             commands::milestone_get_all,
@@ -98,18 +446,117 @@ pub fn run() {
             commands::sprint_get_all,
             commands::sprint_update,
             commands::sprint_delete,
+            commands::sprint_complete,
             // Task commands
             commands::task_create,
             commands::task_get_all,
             commands::task_update,
             commands::task_move,
+            commands::task_bulk_update,
+            commands::task_bulk_move,
+            commands::task_set_parent,
+            commands::task_get_tree,
             commands::task_delete,
             commands::task_add_dependency,
             commands::task_remove_dependency,
             commands::task_get_dependencies,
+            commands::task_get_ready,
+            commands::task_get_history,
+            // Time tracking commands
+            commands::task_timer_start,
+            commands::task_timer_stop,
+            commands::task_time_entries_get,
+            // Recurring task commands
+            commands::recurring_task_create,
+            commands::recurring_task_list,
+            commands::recurring_task_update,
+            commands::recurring_task_delete,
+            // Task status commands
+            commands::task_status_get_all,
+            commands::task_status_create,
+            commands::task_status_rename,
+            commands::task_status_reorder,
+            commands::task_status_delete,
+            // MCP server commands
+            commands::project_mcp_add,
+            commands::project_mcp_add_wingman,
+            commands::project_mcp_remove,
+            commands::project_mcp_list,
+            // Hook commands
+            commands::hook_create,
+            commands::hook_list,
+            commands::hook_set_enabled,
+            commands::hook_delete,
+            // Secret commands
+            commands::secret_set,
+            commands::secret_get,
+            commands::secret_delete,
+            // Database encryption commands
+            commands::db_encryption_status,
+            commands::db_encrypt_existing,
+            // API server commands
+            commands::api_server_status,
+            commands::api_server_enable,
+            commands::api_server_disable,
+            commands::api_server_get_token,
+            commands::api_server_rotate_token,
+            // Webhook commands
+            commands::webhook_create,
+            commands::webhook_list,
+            commands::webhook_set_enabled,
+            commands::webhook_delete,
+            commands::webhook_test,
+            commands::webhook_list_deliveries,
+            commands::webhook_redeliver,
+            // Verification commands
+            commands::verification_get_command,
+            commands::verification_set_command,
+            commands::verification_list,
+            commands::verification_get,
+            // Tool call commands
+            commands::tool_calls_get,
+            commands::tool_calls_stats,
+            // Terminal commands
+            commands::terminal_create,
+            commands::terminal_write,
+            commands::terminal_resize,
+            commands::terminal_kill,
+            commands::terminal_list,
+            // Script commands
+            commands::script_create,
+            commands::script_update,
+            commands::script_delete,
+            commands::script_list,
+            commands::script_run,
+            commands::script_cancel,
+            commands::script_run_list,
+            commands::script_run_get,
+            // Label commands
+            commands::label_create,
+            commands::label_update,
+            commands::label_delete,
+            commands::label_get_all,
+            commands::label_assign,
+            commands::label_remove,
+            // Project policy commands
+            commands::project_policy_get,
+            commands::project_policy_set,
+            // Definition of done commands
+            commands::dod_get,
+            commands::dod_item_create,
+            commands::dod_item_delete,
+            commands::dod_set_enforcement,
+            commands::task_dod_get,
+            commands::task_dod_check,
             // Dashboard commands
             commands::dashboard_stats,
-        ])
+            commands::dashboard_analytics,
+            commands::dashboard_file_activity,
+            commands::search_global,
+            commands::daily_summary_list,
+            commands::daily_summary_generate,
+            commands::events_replay_since,
+        ]))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
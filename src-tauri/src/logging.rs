@@ -0,0 +1,225 @@
+//! Logging
+//!
+//! Log records go to stderr and to JSON-lines files under `<app data>/logs`,
+//! rotated daily with a week kept. Each record is written to the file as it
+//! is logged rather than buffered, so the lines before a crash survive, and
+//! panics are logged before the default panic hook runs. The `log` macros
+//! used across the app are bridged into `tracing`.
+//!
+//! `RUST_LOG` filters both outputs, defaulting to `info`.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer};
+
+use crate::error::{AppError, ErrorCode};
+
+/// Directory under the app data directory holding the log files
+pub const LOG_DIR_NAME: &str = "logs";
+
+/// File name prefix of the app's logs
+pub const APP_LOG_PREFIX: &str = "wingman";
+
+/// File name prefix of the MCP server's logs, kept apart from the app's
+pub const MCP_LOG_PREFIX: &str = "wingman-mcp";
+
+const LOG_FILE_SUFFIX: &str = "log";
+
+/// Daily log files kept per prefix
+const MAX_LOG_FILES: usize = 7;
+
+/// Filter used when `RUST_LOG` is unset or invalid
+const DEFAULT_FILTER: &str = "info";
+
+/// Levels from least to most severe
+const LEVELS: &[&str] = &["TRACE", "DEBUG", "INFO", "WARN", "ERROR"];
+
+/// A line from a log file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    pub timestamp: String,
+    /// `TRACE`, `DEBUG`, `INFO`, `WARN`, or `ERROR`
+    pub level: String,
+    /// Module the record came from
+    pub target: String,
+    pub message: String,
+}
+
+/// Directory the log files are written to
+pub fn log_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(LOG_DIR_NAME)
+}
+
+/// Start logging to stderr and to files named after `prefix`
+///
+/// Logging to stderr still works when the log directory can't be created.
+pub fn init(prefix: &str) {
+    let filter = || EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+
+    let appender = crate::app_data_dir().map(|dir| log_dir(&dir)).and_then(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(prefix)
+            .filename_suffix(LOG_FILE_SUFFIX)
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)
+            .map_err(|e| AppError::with_details(ErrorCode::Unknown, "Failed to open the log file", e.to_string()))
+    });
+    let appender_error = appender.as_ref().err().map(|e| e.to_string());
+
+    let console = fmt::layer().with_writer(io::stderr).with_filter(filter());
+    let file = appender
+        .ok()
+        .map(|appender| fmt::layer().json().with_ansi(false).with_writer(appender).with_filter(filter()));
+
+    if tracing_subscriber::registry().with(console).with(file).try_init().is_err() {
+        return;
+    }
+    if let Some(error) = appender_error {
+        log::warn!("Logging to stderr only: {}", error);
+    }
+
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        log::error!(target: "panic", "{}", info);
+        default_hook(info);
+    }));
+}
+
+/// Read the last `lines` entries at `min_level` or above, oldest first
+///
+/// Reads back through older files until enough entries are found. Lines
+/// that aren't JSON log records are skipped.
+pub fn tail(dir: &Path, prefix: &str, lines: usize, min_level: Option<&str>) -> io::Result<Vec<LogEntry>> {
+    let min_rank = min_level.map_or(0, level_rank);
+    let file_prefix = format!("{}.", prefix);
+
+    let mut files: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name().and_then(|name| name.to_str()).is_some_and(|name| {
+                    name.starts_with(&file_prefix) && name.ends_with(&format!(".{}", LOG_FILE_SUFFIX))
+                })
+            })
+            .collect(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    // Dated names sort oldest first
+    files.sort();
+
+    let mut entries = Vec::new();
+    for file in files.iter().rev() {
+        let contents = fs::read_to_string(file)?;
+        let matching = contents
+            .lines()
+            .rev()
+            .filter_map(parse_line)
+            .filter(|entry| level_rank(&entry.level) >= min_rank);
+        for entry in matching {
+            if entries.len() >= lines {
+                break;
+            }
+            entries.push(entry);
+        }
+        if entries.len() >= lines {
+            break;
+        }
+    }
+
+    entries.reverse();
+    Ok(entries)
+}
+
+/// Whether `level` names a log level
+pub fn is_level(level: &str) -> bool {
+    LEVELS.iter().any(|known| known.eq_ignore_ascii_case(level))
+}
+
+/// Position of a level from least to most severe; unknown levels rank lowest
+fn level_rank(level: &str) -> usize {
+    LEVELS
+        .iter()
+        .position(|known| known.eq_ignore_ascii_case(level))
+        .unwrap_or(0)
+}
+
+/// Parse a JSON log line written by the file layer
+fn parse_line(line: &str) -> Option<LogEntry> {
+    let record: Value = serde_json::from_str(line).ok()?;
+    let field = |value: Option<&Value>| value.and_then(Value::as_str).unwrap_or_default().to_string();
+
+    Some(LogEntry {
+        timestamp: field(record.get("timestamp")),
+        level: record.get("level")?.as_str()?.to_string(),
+        target: field(record.get("target")),
+        message: field(record.get("fields").and_then(|fields| fields.get("message"))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let line = r#"{"timestamp":"2026-10-15T09:30:00.000000Z","level":"WARN","fields":{"message":"Disk is slow"},"target":"wingman_lib::db"}"#;
+        assert_eq!(
+            parse_line(line),
+            Some(LogEntry {
+                timestamp: "2026-10-15T09:30:00.000000Z".to_string(),
+                level: "WARN".to_string(),
+                target: "wingman_lib::db".to_string(),
+                message: "Disk is slow".to_string(),
+            })
+        );
+        assert_eq!(parse_line("thread 'main' panicked"), None);
+        assert_eq!(parse_line(r#"{"fields":{}}"#), None);
+    }
+
+    #[test]
+    fn test_level_rank() {
+        assert!(level_rank("error") > level_rank("WARN"));
+        assert!(level_rank("INFO") > level_rank("debug"));
+        assert!(is_level("Warn"));
+        assert!(!is_level("verbose"));
+    }
+
+    #[test]
+    fn test_tail() {
+        let dir = std::env::temp_dir().join(format!("wingman-logs-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let record = |level: &str, message: &str| {
+            format!(r#"{{"timestamp":"t","level":"{}","fields":{{"message":"{}"}},"target":"x"}}"#, level, message)
+        };
+        fs::write(
+            dir.join("wingman.2026-10-14.log"),
+            [record("INFO", "a"), record("ERROR", "b")].join("\n"),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("wingman.2026-10-15.log"),
+            [record("WARN", "c"), record("DEBUG", "d"), record("INFO", "e")].join("\n"),
+        )
+        .unwrap();
+        fs::write(dir.join("wingman-mcp.2026-10-15.log"), record("ERROR", "mcp")).unwrap();
+
+        let messages = |entries: Vec<LogEntry>| entries.into_iter().map(|e| e.message).collect::<Vec<_>>();
+        assert_eq!(messages(tail(&dir, APP_LOG_PREFIX, 3, None).unwrap()), ["c", "d", "e"]);
+        assert_eq!(messages(tail(&dir, APP_LOG_PREFIX, 10, Some("warn")).unwrap()), ["b", "c"]);
+        assert_eq!(messages(tail(&dir, MCP_LOG_PREFIX, 10, None).unwrap()), ["mcp"]);
+        assert!(tail(&dir.join("missing"), APP_LOG_PREFIX, 10, None).unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
-    wingman_lib::run()
+    if wingman_lib::mcp_server_requested() {
+        wingman_lib::run_mcp_server()
+    } else {
+        wingman_lib::run()
+    }
 }
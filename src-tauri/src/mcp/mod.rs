@@ -0,0 +1,156 @@
+//! MCP Server Configuration
+//!
+//! Per-project MCP servers, stored in the `project_mcp_servers` table. When
+//! a session in the project starts, the servers are written to a config
+//! file in the app data directory and passed to the CLI with `--mcp-config`,
+//! leaving any `.mcp.json` in the project itself untouched.
+//!
+//! The app can also act as an MCP server itself; see [`server`].
+
+pub mod server;
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::error::AppError;
+
+/// An MCP server launched over stdio
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServer {
+    pub id: String,
+    pub project_id: String,
+    /// Key the server is registered under in the CLI config
+    pub name: String,
+    pub command: String,
+    pub args: Vec<String>,
+    pub env: HashMap<String, String>,
+    pub created_at: String,
+}
+
+/// Get a project's MCP servers by name
+pub async fn for_project(db: &SqlitePool, project_id: &str) -> Result<Vec<McpServer>, AppError> {
+    let rows = sqlx::query_as::<_, (String, String, String, String, String, String, String)>(
+        r#"
+        SELECT id, project_id, name, command, args, env, created_at
+        FROM project_mcp_servers
+        WHERE project_id = ?
+        ORDER BY name
+        "#,
+    )
+    .bind(project_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| McpServer {
+            id: row.0,
+            project_id: row.1,
+            name: row.2,
+            command: row.3,
+            args: serde_json::from_str(&row.4).unwrap_or_default(),
+            env: serde_json::from_str(&row.5).unwrap_or_default(),
+            created_at: row.6,
+        })
+        .collect())
+}
+
+/// Store a new MCP server
+pub async fn insert(db: &SqlitePool, server: &McpServer) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        INSERT INTO project_mcp_servers (id, project_id, name, command, args, env, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&server.id)
+    .bind(&server.project_id)
+    .bind(&server.name)
+    .bind(&server.command)
+    .bind(serde_json::to_string(&server.args)?)
+    .bind(serde_json::to_string(&server.env)?)
+    .bind(&server.created_at)
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Write a project's MCP config for the CLI
+///
+/// Returns the file to pass with `--mcp-config`, or None when the project
+/// has no servers.
+pub async fn write_config(
+    db: &SqlitePool,
+    data_dir: &Path,
+    project_id: &str,
+) -> Result<Option<PathBuf>, AppError> {
+    let servers = for_project(db, project_id).await?;
+    let path = data_dir.join("mcp").join(format!("{}.json", project_id));
+
+    if servers.is_empty() {
+        if let Err(e) = tokio::fs::remove_file(&path).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!("Failed to remove MCP config for project {}: {}", project_id, e);
+            }
+        }
+        return Ok(None);
+    }
+
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    tokio::fs::write(&path, serde_json::to_string_pretty(&config_json(&servers))?).await?;
+
+    Ok(Some(path))
+}
+
+/// Render servers in the CLI's `mcpServers` config format
+fn config_json(servers: &[McpServer]) -> serde_json::Value {
+    let entries: serde_json::Map<String, serde_json::Value> = servers
+        .iter()
+        .map(|server| {
+            // Sorted so the file doesn't change between identical writes
+            let env: BTreeMap<&String, &String> = server.env.iter().collect();
+            (
+                server.name.clone(),
+                serde_json::json!({
+                    "type": "stdio",
+                    "command": server.command,
+                    "args": server.args,
+                    "env": env,
+                }),
+            )
+        })
+        .collect();
+
+    serde_json::json!({ "mcpServers": entries })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_json() {
+        let server = McpServer {
+            id: "1".to_string(),
+            project_id: "p".to_string(),
+            name: "postgres".to_string(),
+            command: "npx".to_string(),
+            args: vec!["-y".to_string(), "@modelcontextprotocol/server-postgres".to_string()],
+            env: HashMap::from([("PGHOST".to_string(), "localhost".to_string())]),
+            created_at: String::new(),
+        };
+
+        let config = config_json(&[server]);
+        let entry = &config["mcpServers"]["postgres"];
+        assert_eq!(entry["type"], "stdio");
+        assert_eq!(entry["command"], "npx");
+        assert_eq!(entry["args"][1], "@modelcontextprotocol/server-postgres");
+        assert_eq!(entry["env"]["PGHOST"], "localhost");
+    }
+}
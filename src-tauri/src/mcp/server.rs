@@ -0,0 +1,336 @@
+//! Wingman MCP Server
+//!
+//! Serves the project board to the Claude CLI over MCP, so Claude can create
+//! tasks and move them along while it works. The app binary runs as the
+//! server when started with `--mcp-server`; it speaks JSON-RPC over stdio,
+//! one message per line, against the same SQLite database as the app.
+
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::commands::dod::{dod_enforcement, unchecked_dod_items};
+use crate::commands::project::record_task_change;
+use crate::commands::task_status::default_status_for;
+use crate::error::AppError;
+
+/// Protocol version answered when the client doesn't ask for one
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// JSON-RPC error for an unknown method
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// JSON-RPC error for a message that isn't valid JSON
+const PARSE_ERROR: i64 = -32700;
+
+/// Task statuses the tools accept
+const TASK_STATUSES: &[&str] = &["todo", "in_progress", "done"];
+
+/// Task priorities the tools accept
+const TASK_PRIORITIES: &[&str] = &["low", "medium", "high"];
+
+/// Serve MCP requests on stdin/stdout until stdin closes
+///
+/// `project_id` is the project tools act on when a call doesn't name one.
+pub async fn serve(db: SqlitePool, project_id: Option<String>) -> Result<(), AppError> {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => handle_message(&db, project_id.as_deref(), message).await,
+            Err(e) => Some(rpc_error(Value::Null, PARSE_ERROR, &e.to_string())),
+        };
+
+        if let Some(response) = response {
+            stdout.write_all(response.to_string().as_bytes()).await?;
+            stdout.write_all(b"\n").await?;
+            stdout.flush().await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Answer a JSON-RPC message; notifications get no response
+async fn handle_message(db: &SqlitePool, project_id: Option<&str>, message: Value) -> Option<Value> {
+    let id = message.get("id").cloned()?;
+    let method = message.get("method").and_then(|m| m.as_str()).unwrap_or("");
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => json!({
+            "protocolVersion": params
+                .get("protocolVersion")
+                .and_then(|v| v.as_str())
+                .unwrap_or(PROTOCOL_VERSION),
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "wingman", "version": env!("CARGO_PKG_VERSION") },
+        }),
+        "ping" => json!({}),
+        "tools/list" => json!({ "tools": tool_definitions() }),
+        "tools/call" => {
+            let name = params.get("name").and_then(|n| n.as_str()).unwrap_or("");
+            let arguments = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+            // Tool failures are results Claude can read, not protocol errors
+            match call_tool(db, project_id, name, &arguments).await {
+                Ok(output) => json!({
+                    "content": [{ "type": "text", "text": output.to_string() }],
+                }),
+                Err(e) => json!({
+                    "content": [{ "type": "text", "text": e.message }],
+                    "isError": true,
+                }),
+            }
+        }
+        _ => return Some(rpc_error(id, METHOD_NOT_FOUND, &format!("Unknown method: {}", method))),
+    };
+
+    Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+}
+
+fn rpc_error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// Tools offered to the client, with their input schemas
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "create_task",
+            "description": "Create a task on the Wingman project board.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string" },
+                    "description": { "type": "string" },
+                    "priority": { "type": "string", "enum": TASK_PRIORITIES },
+                    "sprint_id": { "type": "string", "description": "Defaults to the project's active sprint" },
+                    "project_id": { "type": "string", "description": "Defaults to the project the server was registered for" },
+                },
+                "required": ["title"],
+            },
+        },
+        {
+            "name": "update_task_status",
+            "description": "Move a task to todo, in_progress, or done.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "task_id": { "type": "string" },
+                    "status": { "type": "string", "enum": TASK_STATUSES },
+                },
+                "required": ["task_id", "status"],
+            },
+        },
+        {
+            "name": "list_sprint_tasks",
+            "description": "List the tasks in a sprint, or in the project's active sprint.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "sprint_id": { "type": "string" },
+                    "project_id": { "type": "string" },
+                },
+            },
+        },
+    ])
+}
+
+/// Run a tool and return its output
+async fn call_tool(
+    db: &SqlitePool,
+    default_project: Option<&str>,
+    name: &str,
+    arguments: &Value,
+) -> Result<Value, AppError> {
+    let arg = |key: &str| arguments.get(key).and_then(|v| v.as_str()).map(str::trim).filter(|v| !v.is_empty());
+    let project = || {
+        arg("project_id")
+            .or(default_project)
+            .map(str::to_string)
+            .ok_or_else(|| AppError::invalid_input("project_id is required"))
+    };
+
+    match name {
+        "create_task" => {
+            let title = arg("title").ok_or_else(|| AppError::invalid_input("title is required"))?;
+            let priority = arg("priority").unwrap_or("medium");
+            if !TASK_PRIORITIES.contains(&priority) {
+                return Err(AppError::invalid_input("Invalid task priority"));
+            }
+            let project_id = project()?;
+            let sprint_id = match arg("sprint_id") {
+                Some(sprint_id) => Some(sprint_id.to_string()),
+                None => active_sprint(db, &project_id).await?,
+            };
+            create_task(db, &project_id, sprint_id.as_deref(), title, arg("description"), priority).await
+        }
+        "update_task_status" => {
+            let task_id = arg("task_id").ok_or_else(|| AppError::invalid_input("task_id is required"))?;
+            let status = arg("status").ok_or_else(|| AppError::invalid_input("status is required"))?;
+            if !TASK_STATUSES.contains(&status) {
+                return Err(AppError::invalid_input("Status must be todo, in_progress, or done"));
+            }
+            update_task_status(db, task_id, status).await
+        }
+        "list_sprint_tasks" => {
+            let sprint_id = match arg("sprint_id") {
+                Some(sprint_id) => sprint_id.to_string(),
+                None => active_sprint(db, &project()?)
+                    .await?
+                    .ok_or_else(|| AppError::invalid_input("The project has no active sprint"))?,
+            };
+            list_sprint_tasks(db, &sprint_id).await
+        }
+        _ => Err(AppError::invalid_input(format!("Unknown tool: {}", name))),
+    }
+}
+
+/// The project's active sprint, if any
+async fn active_sprint(db: &SqlitePool, project_id: &str) -> Result<Option<String>, AppError> {
+    let sprint_id = sqlx::query_scalar(
+        r#"
+        SELECT id FROM sprints
+        WHERE project_id = ? AND status = 'active' AND deleted_at IS NULL
+        ORDER BY start_date DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(project_id)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(sprint_id)
+}
+
+async fn create_task(
+    db: &SqlitePool,
+    project_id: &str,
+    sprint_id: Option<&str>,
+    title: &str,
+    description: Option<&str>,
+    priority: &str,
+) -> Result<Value, AppError> {
+    let project_exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ? AND deleted_at IS NULL)",
+    )
+    .bind(project_id)
+    .fetch_one(db)
+    .await?;
+    if !project_exists {
+        return Err(AppError::database_not_found("Project", project_id));
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+    let status_id = default_status_for(db, project_id, "todo").await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO tasks (id, project_id, sprint_id, title, description, status, status_id, priority, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, 'todo', ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(project_id)
+    .bind(sprint_id)
+    .bind(title)
+    .bind(description)
+    .bind(&status_id)
+    .bind(priority)
+    .bind(&now)
+    .bind(&now)
+    .execute(db)
+    .await?;
+
+    record_task_change(db, &id, project_id, "status", None, Some("todo"), "claude", &now).await?;
+
+    Ok(json!({ "id": id, "title": title, "status": "todo", "sprintId": sprint_id }))
+}
+
+/// Move a task to the first column of a status category
+///
+/// Completing a task respects the project's definition of done.
+async fn update_task_status(db: &SqlitePool, task_id: &str, status: &str) -> Result<Value, AppError> {
+    let (project_id, old_status): (String, String) = sqlx::query_as(
+        "SELECT project_id, status FROM tasks WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(task_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| AppError::database_not_found("Task", task_id))?;
+
+    if old_status == status {
+        return Ok(json!({ "id": task_id, "status": status }));
+    }
+
+    if status == "done" && dod_enforcement(db, &project_id).await? == "block" {
+        let unchecked = unchecked_dod_items(db, task_id).await?;
+        if !unchecked.is_empty() {
+            let items: Vec<&str> = unchecked.iter().map(|i| i.text.as_str()).collect();
+            return Err(AppError::invalid_input(format!(
+                "Task does not meet the definition of done: {}",
+                items.join("; ")
+            )));
+        }
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let status_id = default_status_for(db, &project_id, status).await?;
+
+    sqlx::query("UPDATE tasks SET status = ?, status_id = ?, updated_at = ? WHERE id = ?")
+        .bind(status)
+        .bind(&status_id)
+        .bind(&now)
+        .bind(task_id)
+        .execute(db)
+        .await?;
+
+    record_task_change(db, task_id, &project_id, "status", Some(&old_status), Some(status), "claude", &now).await?;
+
+    Ok(json!({ "id": task_id, "status": status }))
+}
+
+async fn list_sprint_tasks(db: &SqlitePool, sprint_id: &str) -> Result<Value, AppError> {
+    let tasks = sqlx::query_as::<_, (String, String, Option<String>, String, String)>(
+        r#"
+        SELECT id, title, description, status, priority
+        FROM tasks
+        WHERE sprint_id = ? AND deleted_at IS NULL
+        ORDER BY created_at ASC
+        "#,
+    )
+    .bind(sprint_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(Value::Array(
+        tasks
+            .into_iter()
+            .map(|t| json!({ "id": t.0, "title": t.1, "description": t.2, "status": t.3, "priority": t.4 }))
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_definitions() {
+        let tools = tool_definitions();
+        let names: Vec<&str> = tools
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["create_task", "update_task_status", "list_sprint_tasks"]);
+        assert_eq!(tools[1]["inputSchema"]["properties"]["status"]["enum"][2], "done");
+    }
+}
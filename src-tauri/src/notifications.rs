@@ -0,0 +1,237 @@
+//! Due Date Notifications
+//!
+//! A background job fires native notifications for tasks whose due date is
+//! coming up and milestones past their target date. Each due date is
+//! notified about once; moving it to a new date notifies again. What gets
+//! notified is controlled by the `notification_settings` setting.
+
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::commands::settings::{get_value, SettingKey};
+use crate::error::AppError;
+
+/// How often the job checks for due tasks and overdue milestones
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+/// Largest accepted due-soon window, two weeks
+pub const MAX_DUE_SOON_HOURS: u32 = 14 * 24;
+
+/// The `notification_settings` setting
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct NotificationSettings {
+    /// Turns every due date notification on or off
+    pub enabled: bool,
+    pub tasks_due_soon: bool,
+    /// How far ahead of its due date a task is notified about
+    pub due_soon_hours: u32,
+    pub milestones_overdue: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            tasks_due_soon: true,
+            due_soon_hours: 24,
+            milestones_overdue: true,
+        }
+    }
+}
+
+impl NotificationSettings {
+    /// Read settings from a setting value, or `None` if it isn't valid
+    ///
+    /// Missing fields take their defaults.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        serde_json::from_value::<Self>(value.clone())
+            .ok()
+            .filter(|settings| (1..=MAX_DUE_SOON_HOURS).contains(&settings.due_soon_hours))
+    }
+}
+
+/// Check for due dates now and then for the lifetime of the app
+pub fn spawn_notification_job(app: AppHandle, db: SqlitePool) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = notify_due(&app, &db).await {
+                log::warn!("Failed to check due dates: {}", e);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Notify about due tasks and overdue milestones not notified about yet
+async fn notify_due(app: &AppHandle, db: &SqlitePool) -> Result<(), AppError> {
+    let settings = NotificationSettings::from_value(&get_value(db, SettingKey::NotificationSettings).await?)
+        .unwrap_or_default();
+    if !settings.enabled {
+        return Ok(());
+    }
+    let now = Utc::now();
+
+    if settings.tasks_due_soon {
+        let window = Duration::hours(settings.due_soon_hours.into());
+        let tasks = sqlx::query_as::<_, (String, String, String, String)>(
+            r#"
+            SELECT t.id, t.title, t.due_date, p.name
+            FROM tasks t
+            JOIN projects p ON p.id = t.project_id
+            WHERE t.due_date IS NOT NULL AND t.status != 'done'
+              AND t.deleted_at IS NULL AND p.deleted_at IS NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM sent_notifications n
+                  WHERE n.entity_type = 'task' AND n.entity_id = t.id AND n.due_date = t.due_date
+              )
+            "#,
+        )
+        .fetch_all(db)
+        .await?;
+
+        for (id, title, due_date, project_name) in tasks {
+            let Some(due) = parse_due_date(&due_date) else {
+                continue;
+            };
+            if due - now > window {
+                continue;
+            }
+            show(app, &format!("{}: {}", project_name, title), &describe_due(due, now));
+            mark_sent(db, "task", &id, &due_date).await?;
+        }
+    }
+
+    if settings.milestones_overdue {
+        let milestones = sqlx::query_as::<_, (String, String, String, String)>(
+            r#"
+            SELECT m.id, m.name, m.target_date, p.name
+            FROM milestones m
+            JOIN projects p ON p.id = m.project_id
+            WHERE m.target_date IS NOT NULL AND m.status != 'completed'
+              AND m.deleted_at IS NULL AND p.deleted_at IS NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM sent_notifications n
+                  WHERE n.entity_type = 'milestone' AND n.entity_id = m.id AND n.due_date = m.target_date
+              )
+            "#,
+        )
+        .fetch_all(db)
+        .await?;
+
+        for (id, name, target_date, project_name) in milestones {
+            if parse_due_date(&target_date).is_some_and(|due| due <= now) {
+                show(
+                    app,
+                    &format!("{}: {}", project_name, name),
+                    &format!("Milestone is past its target date of {}", target_date),
+                );
+                mark_sent(db, "milestone", &id, &target_date).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn show(app: &AppHandle, title: &str, body: &str) {
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        log::warn!("Failed to show notification '{}': {}", title, e);
+    }
+}
+
+async fn mark_sent(db: &SqlitePool, entity_type: &str, entity_id: &str, due_date: &str) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO sent_notifications (entity_type, entity_id, due_date, sent_at)
+        VALUES (?, ?, ?, ?)
+        "#,
+    )
+    .bind(entity_type)
+    .bind(entity_id)
+    .bind(due_date)
+    .bind(Utc::now().to_rfc3339())
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Read a due date: an RFC 3339 timestamp, or a `YYYY-MM-DD` date due by the end of that local day
+pub(crate) fn parse_due_date(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    let end_of_day = date.succ_opt()?.and_hms_opt(0, 0, 0)?;
+    Local
+        .from_local_datetime(&end_of_day)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Describe how soon something is due, for a notification body
+fn describe_due(due: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let left = due - now;
+    if left <= Duration::zero() {
+        return "Overdue".to_string();
+    }
+    let plural = |count: i64, unit: &str| {
+        format!("Due in {} {}{}", count, unit, if count == 1 { "" } else { "s" })
+    };
+    // Round up so "due in 0 minutes" never shows
+    let minutes = (left.num_seconds() + 59) / 60;
+    match minutes {
+        0..=59 => plural(minutes, "minute"),
+        60..=2879 => plural((minutes + 59) / 60, "hour"),
+        _ => plural((minutes + 1439) / 1440, "day"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_notification_settings_from_value() {
+        assert_eq!(NotificationSettings::from_value(&json!({})), Some(NotificationSettings::default()));
+
+        let settings = NotificationSettings::from_value(&json!({ "dueSoonHours": 48, "milestonesOverdue": false }))
+            .unwrap();
+        assert_eq!(settings.due_soon_hours, 48);
+        assert!(!settings.milestones_overdue);
+        assert!(settings.enabled);
+
+        assert_eq!(NotificationSettings::from_value(&json!({ "dueSoonHours": 0 })), None);
+        assert_eq!(NotificationSettings::from_value(&json!({ "unknown": true })), None);
+        assert_eq!(NotificationSettings::from_value(&json!(true)), None);
+    }
+
+    #[test]
+    fn test_parse_due_date() {
+        let time = parse_due_date("2024-03-01T09:30:00Z").unwrap();
+        assert_eq!(time, Utc.with_ymd_and_hms(2024, 3, 1, 9, 30, 0).unwrap());
+
+        // A bare date is due at the end of that local day
+        let end = Local.with_ymd_and_hms(2024, 3, 2, 0, 0, 0).unwrap().with_timezone(&Utc);
+        assert_eq!(parse_due_date("2024-03-01"), Some(end));
+
+        assert_eq!(parse_due_date("next week"), None);
+    }
+
+    #[test]
+    fn test_describe_due() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        assert_eq!(describe_due(now - Duration::hours(1), now), "Overdue");
+        assert_eq!(describe_due(now + Duration::seconds(30), now), "Due in 1 minute");
+        assert_eq!(describe_due(now + Duration::minutes(45), now), "Due in 45 minutes");
+        assert_eq!(describe_due(now + Duration::minutes(90), now), "Due in 2 hours");
+        assert_eq!(describe_due(now + Duration::hours(47), now), "Due in 47 hours");
+        assert_eq!(describe_due(now + Duration::days(3), now), "Due in 3 days");
+    }
+}
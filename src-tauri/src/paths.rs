@@ -0,0 +1,161 @@
+//! Path Normalization
+//!
+//! Converts the mix of absolute and relative paths reported by the watcher,
+//! Claude tool calls, and git into one workspace-relative form so they can be
+//! compared and joined reliably.
+
+use std::path::{Component, Path, PathBuf};
+
+/// Normalizes paths against a single workspace root
+#[derive(Debug, Clone)]
+pub struct PathNormalizer {
+    /// Canonical workspace root
+    root: PathBuf,
+}
+
+impl PathNormalizer {
+    /// Create a normalizer for a workspace root
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        let root = root.as_ref();
+        Self {
+            root: canonicalize_lenient(root),
+        }
+    }
+
+    /// Resolve a path to an absolute, canonical path
+    ///
+    /// Relative paths are taken relative to the workspace root. Paths that no
+    /// longer exist (e.g. deleted files) are resolved through their nearest
+    /// existing ancestor.
+    pub fn absolute(&self, path: impl AsRef<Path>) -> PathBuf {
+        let path = path.as_ref();
+        if path.is_absolute() {
+            canonicalize_lenient(path)
+        } else {
+            canonicalize_lenient(&self.root.join(path))
+        }
+    }
+
+    /// Normalize a path to its workspace-relative form
+    ///
+    /// Uses `/` separators and is case-folded on Windows. Paths outside the
+    /// workspace are returned as normalized absolute paths.
+    pub fn normalize(&self, path: impl AsRef<Path>) -> String {
+        let absolute = self.absolute(path);
+        let normalized = match absolute.strip_prefix(&self.root) {
+            Ok(relative) => to_slash(relative),
+            Err(_) => to_slash(&absolute),
+        };
+        fold_case(normalized)
+    }
+}
+
+/// Canonicalize a path, falling back to lexical cleanup for missing paths
+fn canonicalize_lenient(path: &Path) -> PathBuf {
+    if let Ok(canonical) = canonicalize(path) {
+        return canonical;
+    }
+
+    // Canonicalize the deepest existing ancestor and re-append the rest
+    let cleaned = clean(path);
+    let mut existing = cleaned.as_path();
+    let mut rest = Vec::new();
+    while let Some(parent) = existing.parent() {
+        if let Some(name) = existing.file_name() {
+            rest.push(name.to_os_string());
+        }
+        existing = parent;
+        if let Ok(mut canonical) = canonicalize(existing) {
+            for name in rest.iter().rev() {
+                canonical.push(name);
+            }
+            return canonical;
+        }
+    }
+
+    cleaned
+}
+
+/// Canonicalize without the `\\?\` verbatim prefix Windows adds
+fn canonicalize(path: &Path) -> std::io::Result<PathBuf> {
+    let canonical = path.canonicalize()?;
+
+    #[cfg(windows)]
+    {
+        let s = canonical.to_string_lossy();
+        if let Some(stripped) = s.strip_prefix(r"\\?\") {
+            if !stripped.starts_with("UNC") {
+                return Ok(PathBuf::from(stripped));
+            }
+        }
+    }
+
+    Ok(canonical)
+}
+
+/// Lexically resolve `.` and `..` components
+fn clean(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+/// Render a path with `/` separators
+fn to_slash(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Case-fold on case-insensitive platforms
+#[cfg(windows)]
+fn fold_case(path: String) -> String {
+    path.to_lowercase()
+}
+
+/// Case-fold on case-insensitive platforms
+#[cfg(not(windows))]
+fn fold_case(path: String) -> String {
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("wingman-paths-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/main.rs"), "").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_absolute_and_relative_agree() {
+        let root = temp_root();
+        let normalizer = PathNormalizer::new(&root);
+
+        assert_eq!(normalizer.normalize(root.join("src/main.rs")), "src/main.rs");
+        assert_eq!(normalizer.normalize("src/main.rs"), "src/main.rs");
+        assert_eq!(normalizer.normalize("./src/../src/main.rs"), "src/main.rs");
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_missing_file_is_resolved_through_parent() {
+        let root = temp_root();
+        let normalizer = PathNormalizer::new(&root);
+
+        assert_eq!(normalizer.normalize(root.join("src/deleted.rs")), "src/deleted.rs");
+        assert_eq!(normalizer.normalize("new/dir/file.txt"), "new/dir/file.txt");
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,140 @@
+//! Secrets
+//!
+//! API tokens are kept in the OS keychain (Keychain on macOS, Credential
+//! Manager on Windows, the Secret Service on Linux) rather than in the
+//! settings table or anywhere else in the database. Each secret is an entry
+//! under the app's identifier, named by one of the constants below or, for
+//! a webhook's signing secret, by `webhook_secret_name`.
+//!
+//! Keychain calls can block on an unlock prompt, so they run on the blocking
+//! thread pool.
+
+use crate::error::{AppError, ErrorCode};
+
+/// Keychain service the entries are stored under
+pub const SERVICE: &str = "com.wingman.app";
+
+/// GitHub personal access token, for importing and closing issues
+pub const GITHUB_TOKEN: &str = "github_token";
+pub const LINEAR_API_KEY: &str = "linear_api_key";
+pub const JIRA_API_TOKEN: &str = "jira_api_token";
+/// Passed to the Claude CLI as `ANTHROPIC_API_KEY`
+pub const ANTHROPIC_API_KEY: &str = "anthropic_api_key";
+/// Token clients of the local HTTP API authenticate with
+pub const API_SERVER_TOKEN: &str = "api_server_token";
+/// Secret the database encryption key is derived from; never exposed to the frontend
+pub const DATABASE_KEY: &str = "database_key";
+
+/// Secrets the frontend may read and write by name
+pub const NAMES: &[&str] = &[GITHUB_TOKEN, LINEAR_API_KEY, JIRA_API_TOKEN, ANTHROPIC_API_KEY];
+
+/// Longest secret accepted
+const MAX_SECRET_LEN: usize = 8 * 1024;
+
+/// Name a webhook's signing secret is stored under
+pub fn webhook_secret_name(webhook_id: &str) -> String {
+    format!("webhook:{}", webhook_id)
+}
+
+/// Check a name is one the frontend may use
+pub fn validate_name(name: &str) -> Result<(), AppError> {
+    if NAMES.contains(&name) {
+        Ok(())
+    } else {
+        Err(AppError::invalid_input(format!(
+            "Unknown secret: {} (expected one of {})",
+            name,
+            NAMES.join(", ")
+        )))
+    }
+}
+
+/// Read a secret, or `None` if it isn't set
+pub async fn get(name: &str) -> Result<Option<String>, AppError> {
+    let name = name.to_string();
+    blocking(move || match entry(&name)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(keychain_error("read", &name, e)),
+    })
+    .await
+}
+
+/// Read a secret used as a fallback, treating an unavailable keychain as no secret
+pub async fn lookup(name: &str) -> Option<String> {
+    match get(name).await {
+        Ok(secret) => secret,
+        Err(e) => {
+            log::warn!("{}: {}", e, e.details.as_deref().unwrap_or_default());
+            None
+        }
+    }
+}
+
+/// Store a secret, replacing any earlier value
+pub async fn set(name: &str, secret: &str) -> Result<(), AppError> {
+    if secret.is_empty() {
+        return Err(AppError::invalid_input("Secret cannot be empty"));
+    }
+    if secret.len() > MAX_SECRET_LEN {
+        return Err(AppError::invalid_input("Secret is too long"));
+    }
+
+    let name = name.to_string();
+    let secret = secret.to_string();
+    blocking(move || {
+        entry(&name)?
+            .set_password(&secret)
+            .map_err(|e| keychain_error("store", &name, e))
+    })
+    .await
+}
+
+/// Remove a secret; removing one that isn't set is not an error
+pub async fn delete(name: &str) -> Result<(), AppError> {
+    let name = name.to_string();
+    blocking(move || match entry(&name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(keychain_error("remove", &name, e)),
+    })
+    .await
+}
+
+fn entry(name: &str) -> Result<keyring::Entry, AppError> {
+    keyring::Entry::new(SERVICE, name).map_err(|e| keychain_error("open", name, e))
+}
+
+fn keychain_error(action: &str, name: &str, e: keyring::Error) -> AppError {
+    let code = match &e {
+        keyring::Error::NoStorageAccess(_) | keyring::Error::PlatformFailure(_) => ErrorCode::PermissionDenied,
+        _ => ErrorCode::Unknown,
+    };
+    AppError::with_details(
+        code,
+        format!("Failed to {} {} in the OS keychain", action, name),
+        e.to_string(),
+    )
+}
+
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, AppError> + Send + 'static,
+) -> Result<T, AppError> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| AppError::new(ErrorCode::Unknown, format!("Keychain task failed: {}", e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        for name in NAMES {
+            assert!(validate_name(name).is_ok());
+        }
+        // Webhook secrets are managed through their webhook
+        assert!(validate_name(&webhook_secret_name("w1")).is_err());
+        assert!(validate_name("password").is_err());
+    }
+}
@@ -2,16 +2,28 @@
 //!
 //! Centralized application state accessible from all commands.
 
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use sqlx::SqlitePool;
+use tokio::sync::RwLock;
 
+use crate::api::ApiServer;
 use crate::claude::CliManager;
+use crate::terminal::TerminalManager;
+use super::command_metrics::CommandMetrics;
 use super::file_watcher::FileWatcherManager;
+use super::project_index::ProjectIndexer;
+use super::rate_limiter::IpcRateLimiter;
+use super::script_runs::ScriptRuns;
 
 /// Claude CLI process status
 #[derive(Debug, Clone, PartialEq, serde::Serialize)]
 #[serde(rename_all = "lowercase")]
 #[allow(dead_code)]
 pub enum ClaudeStatus {
+    Queued,
     Starting,
     Ready,
     Busy,
@@ -19,26 +31,104 @@ pub enum ClaudeStatus {
     Error,
 }
 
+impl ClaudeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClaudeStatus::Queued => "queued",
+            ClaudeStatus::Starting => "starting",
+            ClaudeStatus::Ready => "ready",
+            ClaudeStatus::Busy => "busy",
+            ClaudeStatus::Stopped => "stopped",
+            ClaudeStatus::Error => "error",
+        }
+    }
+}
+
 /// Application state shared across all commands
 pub struct AppState {
     /// Database connection pool
     pub db: SqlitePool,
+    /// App data directory holding the database and checkpoint snapshots
+    pub data_dir: PathBuf,
     /// CLI process manager
     pub cli_manager: CliManager,
     /// File watcher manager
     pub file_watcher: FileWatcherManager,
+    /// Project file and symbol indexes
+    pub project_index: ProjectIndexer,
+    /// Interactive session terminals
+    pub terminals: TerminalManager,
+    /// Project script runs in progress
+    pub script_runs: ScriptRuns,
+    /// Last known preview server reachability keyed by project ID
+    pub preview_health: RwLock<HashMap<String, bool>>,
+    /// Per-command IPC rate limiter
+    pub ipc_limiter: IpcRateLimiter,
+    /// Recent command durations and outcomes
+    pub command_metrics: CommandMetrics,
+    /// Local HTTP API server, when turned on
+    pub api_server: ApiServer,
+    /// Labels of read-only observer windows
+    ///
+    /// A std lock because it is read from the synchronous command guard.
+    observer_windows: std::sync::RwLock<HashSet<String>>,
+    /// Whether closing the main window hides it rather than quitting
+    background_mode: AtomicBool,
+    /// Whether background services are disabled for this run
+    pub safe_mode: bool,
 }
 
 impl AppState {
     /// Create new application state
-    pub fn new(db: SqlitePool) -> Self {
+    pub fn new(db: SqlitePool, data_dir: PathBuf, safe_mode: bool) -> Self {
         Self {
             db,
+            data_dir,
             cli_manager: CliManager::new(),
             file_watcher: FileWatcherManager::new(),
+            project_index: ProjectIndexer::new(),
+            terminals: TerminalManager::new(),
+            script_runs: ScriptRuns::default(),
+            preview_health: RwLock::new(HashMap::new()),
+            ipc_limiter: IpcRateLimiter::default(),
+            command_metrics: CommandMetrics::default(),
+            api_server: ApiServer::default(),
+            observer_windows: std::sync::RwLock::new(HashSet::new()),
+            background_mode: AtomicBool::new(true),
+            safe_mode,
+        }
+    }
+
+    /// Check whether a window is a read-only observer
+    pub fn is_observer(&self, label: &str) -> bool {
+        self.observer_windows
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(label)
+    }
+
+    /// Mark or unmark a window as a read-only observer
+    pub fn set_observer(&self, label: &str, observer: bool) {
+        let mut windows = self.observer_windows.write().unwrap_or_else(|e| e.into_inner());
+        if observer {
+            windows.insert(label.to_string());
+        } else {
+            windows.remove(label);
         }
     }
 
+    /// Whether closing the main window leaves the app running in the tray
+    ///
+    /// Session events are buffered for replay only while this is on.
+    pub fn background_mode(&self) -> bool {
+        self.background_mode.load(Ordering::Relaxed)
+    }
+
+    /// Turn background mode on or off
+    pub fn set_background_mode(&self, enabled: bool) {
+        self.background_mode.store(enabled, Ordering::Relaxed);
+    }
+
     /// Get the status of a CLI session
     pub async fn get_cli_status(&self, session_id: &str) -> ClaudeStatus {
         self.cli_manager.get_status(session_id).await
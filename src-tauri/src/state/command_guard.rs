@@ -0,0 +1,145 @@
+//! Command Guards
+//!
+//! Checks applied to every IPC command before it reaches its handler:
+//! read-only observer windows and per-command rate limiting. Rejected calls
+//! are recorded as failures in the command metrics.
+
+use std::time::Duration;
+
+use tauri::ipc::Invoke;
+use tauri::{Manager, Runtime};
+
+use crate::error::{AppError, ErrorCode};
+use super::AppState;
+
+/// Commands an observer window may call; everything else is rejected
+const OBSERVER_COMMANDS: &[&str] = &[
+    "system_get_app_info",
+    "system_get_startup_status",
+    "system_get_logs",
+    "system_health",
+    "system_check_cli",
+    "system_get_ipc_rate_limit",
+    "system_get_command_metrics",
+    "system_list_children",
+    "terminal_list",
+    "settings_get",
+    "settings_get_all",
+    "window_is_observer",
+    "session_load",
+    "message_get_range",
+    "message_get_attachments",
+    "session_list",
+    "session_get_concurrency_limit",
+    "session_get_restart_limit",
+    "session_replay_recent",
+    "session_get_cli_logs",
+    "session_get_replay_limit",
+    "session_handoff_list",
+    "session_get_summary",
+    "session_get_tasks",
+    "session_template_list",
+    "prompt_list",
+    "prompt_render",
+    "checkpoint_list",
+    "file_history",
+    "file_sessions",
+    "task_get_sessions",
+    "run_list",
+    "run_get",
+    "claude_todo_sync_get",
+    "activity_get",
+    "activity_get_retention",
+    "activity_stats",
+    "message_related_activity",
+    "file_watcher_get_diff_limit",
+    "file_watcher_get_stats",
+    "file_watcher_status",
+    "file_watcher_get_project_limit",
+    "project_watch_settings_get",
+    "project_get_all",
+    "project_get",
+    "project_check_preview",
+    "delete_preview",
+    "trash_list",
+    "trash_get_retention_days",
+    "import_preview",
+    "github_integration_get",
+    "integration_get_all",
+    "integration_sync_log_get",
+    "context_lookup",
+    "git_status",
+    "git_current_branch",
+    "git_diff_file",
+    "git_log_recent",
+    "milestone_get_all",
+    "sprint_get_all",
+    "task_get_all",
+    "task_get_tree",
+    "task_get_dependencies",
+    "task_get_ready",
+    "task_get_history",
+    "task_time_entries_get",
+    "recurring_task_list",
+    "task_status_get_all",
+    "label_get_all",
+    "project_mcp_list",
+    "hook_list",
+    "webhook_list",
+    "webhook_list_deliveries",
+    "db_encryption_status",
+    "api_server_status",
+    "verification_get_command",
+    "verification_list",
+    "verification_get",
+    "tool_calls_get",
+    "tool_calls_stats",
+    "script_list",
+    "script_run_list",
+    "script_run_get",
+    "project_policy_get",
+    "dod_get",
+    "task_dod_get",
+    "dashboard_stats",
+    "dashboard_analytics",
+    "dashboard_file_activity",
+    "search_global",
+    "daily_summary_list",
+    "events_replay_since",
+];
+
+/// Wrap a command handler with the observer and rate limit guards
+pub fn with_command_guards<R, F>(handler: F) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static
+where
+    R: Runtime,
+    F: Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+{
+    move |invoke| {
+        let command = invoke.message.command().to_string();
+        let webview = invoke.message.webview_ref();
+
+        // Commands that run before the app state is ready are not guarded
+        let rejection = webview.try_state::<AppState>().and_then(|state| {
+            if state.is_observer(webview.label()) && !OBSERVER_COMMANDS.contains(&command.as_str()) {
+                return Some(AppError::new(
+                    ErrorCode::PermissionDenied,
+                    format!("{} is not available in a read-only window", command),
+                ));
+            }
+            if !state.ipc_limiter.check(&command) {
+                return Some(AppError::rate_limited(&command));
+            }
+            None
+        });
+
+        if let Some(error) = rejection {
+            if let Some(state) = webview.try_state::<AppState>() {
+                state.command_metrics.record(&command, Duration::ZERO, false);
+            }
+            invoke.resolver.reject(error);
+            return true;
+        }
+
+        handler(invoke)
+    }
+}
@@ -0,0 +1,177 @@
+//! Command Metrics
+//!
+//! Records the duration and outcome of every IPC command in a fixed-size
+//! ring buffer, so slow or failing commands are visible without a profiler.
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::AppError;
+
+/// Number of most recent calls kept
+const MAX_SAMPLES: usize = 2048;
+
+/// A single recorded command call
+#[derive(Debug, Clone)]
+struct CommandSample {
+    command: String,
+    duration: Duration,
+    ok: bool,
+    at: String,
+}
+
+/// Timing summary for one command over the recorded window
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandMetricSummary {
+    pub command: String,
+    pub calls: usize,
+    pub errors: usize,
+    pub mean_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+    pub last_ms: f64,
+    pub last_called_at: String,
+}
+
+/// Ring buffer of recent command calls
+#[derive(Clone, Default)]
+pub struct CommandMetrics {
+    samples: Arc<Mutex<VecDeque<CommandSample>>>,
+}
+
+impl CommandMetrics {
+    /// Run a command body, recording how long it took and whether it failed
+    pub async fn measure<T, F>(&self, command: &str, body: F) -> Result<T, AppError>
+    where
+        F: Future<Output = Result<T, AppError>>,
+    {
+        let started = Instant::now();
+        let result = body.await;
+        self.record(command, started.elapsed(), result.is_ok());
+        result
+    }
+
+    /// Record a completed call
+    pub fn record(&self, command: &str, duration: Duration, ok: bool) {
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        if samples.len() == MAX_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(CommandSample {
+            command: command.to_string(),
+            duration,
+            ok,
+            at: chrono::Utc::now().to_rfc3339(),
+        });
+    }
+
+    /// Summarize the recorded calls per command, slowest (by p95) first
+    pub fn summary(&self) -> Vec<CommandMetricSummary> {
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        summarize(samples.iter())
+    }
+
+    /// Number of calls currently recorded
+    pub fn sample_count(&self) -> usize {
+        self.samples.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Discard all recorded calls
+    pub fn clear(&self) {
+        self.samples.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// Capacity of the ring buffer
+    pub fn capacity(&self) -> usize {
+        MAX_SAMPLES
+    }
+}
+
+fn summarize<'a>(samples: impl Iterator<Item = &'a CommandSample>) -> Vec<CommandMetricSummary> {
+    let mut by_command: HashMap<&str, Vec<&CommandSample>> = HashMap::new();
+    for sample in samples {
+        by_command.entry(&sample.command).or_default().push(sample);
+    }
+
+    let mut summaries: Vec<CommandMetricSummary> = by_command
+        .into_iter()
+        .map(|(command, calls)| {
+            let mut durations: Vec<f64> = calls
+                .iter()
+                .map(|s| s.duration.as_secs_f64() * 1000.0)
+                .collect();
+            let last = calls[calls.len() - 1];
+            let last_ms = durations[durations.len() - 1];
+            durations.sort_by(|a, b| a.total_cmp(b));
+            let p95_index = ((durations.len() as f64 * 0.95).ceil() as usize).saturating_sub(1);
+
+            CommandMetricSummary {
+                command: command.to_string(),
+                calls: calls.len(),
+                errors: calls.iter().filter(|s| !s.ok).count(),
+                mean_ms: durations.iter().sum::<f64>() / durations.len() as f64,
+                p95_ms: durations[p95_index],
+                max_ms: durations[durations.len() - 1],
+                last_ms,
+                last_called_at: last.at.clone(),
+            }
+        })
+        .collect();
+
+    summaries.sort_by(|a, b| b.p95_ms.total_cmp(&a.p95_ms).then_with(|| a.command.cmp(&b.command)));
+    summaries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer_and_summary() {
+        let metrics = CommandMetrics::default();
+        for _ in 0..MAX_SAMPLES {
+            metrics.record("fast", Duration::from_millis(1), true);
+        }
+        for i in 0..20 {
+            metrics.record("slow", Duration::from_millis(100 + i), i % 2 == 0);
+        }
+
+        assert_eq!(metrics.sample_count(), MAX_SAMPLES);
+
+        let summary = metrics.summary();
+        assert_eq!(summary[0].command, "slow");
+        assert_eq!(summary[0].calls, 20);
+        assert_eq!(summary[0].errors, 10);
+        assert_eq!(summary[0].max_ms, 119.0);
+        assert_eq!(summary[0].last_ms, 119.0);
+        assert_eq!(summary[1].calls, MAX_SAMPLES - 20);
+    }
+
+    #[tokio::test]
+    async fn test_measure_records_awaited_outcome() {
+        let metrics = CommandMetrics::default();
+        let ok = metrics
+            .measure("slow_ok", async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Ok(1)
+            })
+            .await;
+        let failed: Result<(), AppError> = metrics
+            .measure("fails", async { Err(AppError::invalid_input("bad")) })
+            .await;
+
+        assert_eq!(ok.unwrap(), 1);
+        assert!(failed.is_err());
+
+        let summary = metrics.summary();
+        let slow = summary.iter().find(|s| s.command == "slow_ok").unwrap();
+        assert_eq!(slow.errors, 0);
+        assert!(slow.max_ms >= 20.0);
+        let fails = summary.iter().find(|s| s.command == "fails").unwrap();
+        assert_eq!(fails.errors, 1);
+    }
+}
@@ -0,0 +1,203 @@
+//! File Diffs
+//!
+//! Line-based unified diffs (Myers' algorithm) between two versions of a
+//! file, used to show what a watched change actually did.
+
+/// Lines of unchanged context around each hunk
+const CONTEXT_LINES: usize = 3;
+
+/// Largest edit distance searched before giving up on a diff
+const MAX_EDIT_DISTANCE: usize = 1000;
+
+/// A single line-level edit
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Edit {
+    /// Line present in both versions
+    Equal,
+    /// Line only in the old version
+    Delete,
+    /// Line only in the new version
+    Insert,
+}
+
+/// Render a unified diff between two versions of a file
+///
+/// Returns `None` when the contents are identical or differ too much to diff
+/// cheaply.
+pub fn unified_diff(path: &str, old: &str, new: &str) -> Option<String> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    let edits = diff_lines(&a, &b)?;
+    if edits.iter().all(|(edit, _)| *edit == Edit::Equal) {
+        return None;
+    }
+
+    // Old and new line offsets before each edit
+    let mut positions = Vec::with_capacity(edits.len() + 1);
+    let (mut old_pos, mut new_pos) = (0, 0);
+    for (edit, _) in &edits {
+        positions.push((old_pos, new_pos));
+        match edit {
+            Edit::Equal => {
+                old_pos += 1;
+                new_pos += 1;
+            }
+            Edit::Delete => old_pos += 1,
+            Edit::Insert => new_pos += 1,
+        }
+    }
+
+    let mut out = format!("--- a/{0}\n+++ b/{0}\n", path);
+
+    for (start, end) in hunk_ranges(&edits) {
+        let old_count = edits[start..end].iter().filter(|(e, _)| *e != Edit::Insert).count();
+        let new_count = edits[start..end].iter().filter(|(e, _)| *e != Edit::Delete).count();
+        let (old_start, new_start) = positions[start];
+
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_count),
+            hunk_range(new_start, new_count)
+        ));
+
+        for (edit, line) in &edits[start..end] {
+            let prefix = match edit {
+                Edit::Equal => ' ',
+                Edit::Delete => '-',
+                Edit::Insert => '+',
+            };
+            out.push(prefix);
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+
+    Some(out)
+}
+
+/// Format a hunk's `start,count` the way `diff -u` does
+fn hunk_range(offset: usize, count: usize) -> String {
+    match count {
+        0 => format!("{},0", offset),
+        1 => format!("{}", offset + 1),
+        _ => format!("{},{}", offset + 1, count),
+    }
+}
+
+/// Group changed edits with their context into `[start, end)` ranges
+fn hunk_ranges(edits: &[(Edit, &str)]) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+
+    for (i, (edit, _)) in edits.iter().enumerate() {
+        if *edit == Edit::Equal {
+            continue;
+        }
+        let start = i.saturating_sub(CONTEXT_LINES);
+        let end = (i + 1 + CONTEXT_LINES).min(edits.len());
+
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    ranges
+}
+
+/// Compute the shortest line edit script from `a` to `b`
+fn diff_lines<'a>(a: &[&'a str], b: &[&'a str]) -> Option<Vec<(Edit, &'a str)>> {
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    let max = (a.len() + b.len()).min(MAX_EDIT_DISTANCE) as isize;
+    let offset = max + 1;
+
+    // Furthest x reached on each diagonal k, indexed by k + offset
+    let mut v = vec![0isize; 2 * offset as usize + 1];
+    // State of `v` over diagonals -d..=d at the start of each step d
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    let mut found = None;
+    'search: for d in 0..=max {
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[(offset + k - 1) as usize] < v[(offset + k + 1) as usize]) {
+                v[(offset + k + 1) as usize]
+            } else {
+                v[(offset + k - 1) as usize] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[(offset + k) as usize] = x;
+
+            if x >= n && y >= m {
+                found = Some(d);
+                break 'search;
+            }
+        }
+    }
+    let distance = found?;
+
+    // Walk back through the trace to recover the edits
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (0..=distance).rev() {
+        let saved = &trace[d as usize];
+        let at = |k: isize| saved[(k + d) as usize];
+        let k = x - y;
+
+        let (prev_x, prev_y) = if d == 0 {
+            (0, 0)
+        } else {
+            let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) { k + 1 } else { k - 1 };
+            let prev_x = at(prev_k);
+            (prev_x, prev_x - prev_k)
+        };
+
+        while x > prev_x && y > prev_y {
+            edits.push((Edit::Equal, a[(x - 1) as usize]));
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            if x == prev_x {
+                edits.push((Edit::Insert, b[(y - 1) as usize]));
+            } else {
+                edits.push((Edit::Delete, a[(x - 1) as usize]));
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+
+    edits.reverse();
+    Some(edits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nb\nc\nD\ne\nf\ng\nh\ni\nj\nk\n";
+
+        let diff = unified_diff("src/x.txt", old, new).unwrap();
+        assert_eq!(
+            diff,
+            "--- a/src/x.txt\n+++ b/src/x.txt\n\
+             @@ -1,10 +1,11 @@\n a\n b\n c\n-d\n+D\n e\n f\n g\n h\n i\n j\n+k\n"
+        );
+
+        assert_eq!(unified_diff("x", old, old), None);
+        assert_eq!(
+            unified_diff("x", "", "one\n").unwrap(),
+            "--- a/x\n+++ b/x\n@@ -0,0 +1 @@\n+one\n"
+        );
+    }
+}
@@ -2,23 +2,89 @@
 //!
 //! Cross-platform file system watching with debouncing and source attribution.
 
-use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher, Event, EventKind};
-use std::collections::HashMap;
-use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Config, EventHandler, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher, Event, EventKind};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
 use std::time::{Duration, Instant};
+use tokio::sync::mpsc::error::TrySendError;
 use tokio::sync::{mpsc, RwLock, Mutex};
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
 
-use crate::error::AppError;
-use crate::events::{emit_event, event_names, FileChangedPayload};
+use crate::error::{AppError, ErrorCode};
+use crate::events::{
+    emit_event, event_names, BulkChangePayload, BulkDirectoryCount, FileChangedPayload, PolicyViolationPayload, ProjectConfigChangedPayload,
+    WatcherOverflowPayload,
+};
+use crate::git;
+use crate::hooks::{self, HookContext, HookEvent};
+use crate::paths::PathNormalizer;
+use super::file_diff::unified_diff;
+use super::AppState;
 
 /// Default debounce duration in milliseconds
-const DEBOUNCE_MS: u64 = 100;
+pub const DEBOUNCE_MS: u64 = 100;
 
 /// Attribution window - changes within this time of CLI write are attributed to Claude
 const ATTRIBUTION_WINDOW_MS: u64 = 2000;
 
+/// Most file snapshots kept per session for diffing
+const MAX_SNAPSHOTS_PER_SESSION: usize = 1000;
+
+/// Default number of raw events buffered between the watchers and processing
+const DEFAULT_CHANNEL_CAPACITY: usize = 1000;
+
+/// How often dropped events are reported
+const OVERFLOW_REPORT_MS: u64 = 1000;
+
+/// Events a session may send within `BURST_WINDOW_MS` before its changes
+/// are summarized as a bulk change, e.g. during `npm install`
+const BURST_THRESHOLD: usize = 200;
+
+/// Window bursts are counted over
+const BURST_WINDOW_MS: u64 = 1000;
+
+/// Longest quiet period a burst must wait out before it is reported
+const MAX_BURST_DEBOUNCE_MS: u64 = 10_000;
+
+/// Directories listed in a bulk change
+const MAX_BULK_DIRECTORIES: usize = 10;
+
+/// Directories watched per session when the project sets no limit
+pub const DEFAULT_MAX_WATCHED_DIRS: usize = 20_000;
+
+/// Ignored directories listed in a watcher's status
+const MAX_REPORTED_EXCLUSIONS: usize = 50;
+
+/// Subtrees suggested for exclusion when the directory limit is exceeded
+const MAX_SUGGESTED_EXCLUSIONS: usize = 5;
+
+/// Whether each directory gets its own watch, as inotify requires, so
+/// ignored directories can be left out; other platforms watch the root once
+const WATCH_PER_DIRECTORY: bool = cfg!(target_os = "linux");
+
+/// Default time between scans of a polled root
+pub const DEFAULT_POLL_INTERVAL_MS: u64 = 2000;
+
+/// Filesystems whose changes native watchers miss, so they're polled
+const POLLED_FILESYSTEMS: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "9p",
+    "drvfs",
+    "fuse.sshfs",
+    "fuse.rclone",
+    "afs",
+    "ceph",
+    "glusterfs",
+];
+
 /// Default ignore patterns
 const DEFAULT_IGNORE_PATTERNS: &[&str] = &[
     ".git",
@@ -40,12 +106,16 @@ const DEFAULT_IGNORE_PATTERNS: &[&str] = &[
     ".cargo",
 ];
 
+/// Ignore pattern matching every hidden file and directory under a root
+pub const DOTFILES_PATTERN: &str = ".*";
+
 /// File operation types
 #[derive(Debug, Clone, PartialEq)]
 pub enum FileOperation {
     Created,
     Modified,
     Deleted,
+    Renamed,
 }
 
 impl FileOperation {
@@ -54,7 +124,166 @@ impl FileOperation {
             FileOperation::Created => "created",
             FileOperation::Modified => "modified",
             FileOperation::Deleted => "deleted",
+            FileOperation::Renamed => "renamed",
+        }
+    }
+}
+
+/// A change waiting to be written to the activity log
+struct ActivityEntry {
+    session_id: String,
+    /// Relative to the watched root
+    path: String,
+    operation: &'static str,
+    source: &'static str,
+    timestamp: String,
+    /// Where a renamed file was, relative to the watched root
+    old_path: Option<String>,
+}
+
+/// When roots are polled instead of watched natively
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PollingMode {
+    /// Poll network and WSL mounts, and roots native watching fails on
+    Auto,
+    /// Poll every root
+    Always,
+    /// Never poll
+    Never,
+}
+
+impl PollingMode {
+    pub const ALL: [PollingMode; 3] = [PollingMode::Auto, PollingMode::Always, PollingMode::Never];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PollingMode::Auto => "auto",
+            PollingMode::Always => "always",
+            PollingMode::Never => "never",
+        }
+    }
+
+    pub fn parse(mode: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.as_str() == mode)
+    }
+}
+
+/// Counts a session's events to detect bursts of churn
+struct BurstTracker {
+    window_start: Instant,
+    window_events: usize,
+    last_event: Instant,
+    /// Quiet period that ends the burst; doubles while the churn continues
+    debounce: Duration,
+    /// Changes summarized since the burst began
+    bulk: Option<BulkSummary>,
+}
+
+impl BurstTracker {
+    fn new(now: Instant) -> Self {
+        Self {
+            window_start: now,
+            window_events: 0,
+            last_event: now,
+            debounce: Duration::ZERO,
+            bulk: None,
+        }
+    }
+
+    /// Count an event, returning whether the session is bursting
+    fn record(&mut self, now: Instant, base_debounce: Duration) -> bool {
+        self.last_event = now;
+        if now.duration_since(self.window_start) >= Duration::from_millis(BURST_WINDOW_MS) {
+            self.window_start = now;
+            self.window_events = 0;
+        }
+        self.window_events += 1;
+
+        if self.window_events == BURST_THRESHOLD {
+            let max = Duration::from_millis(MAX_BURST_DEBOUNCE_MS);
+            self.debounce = match self.bulk {
+                Some(_) => (self.debounce * 2).min(max),
+                None => (base_debounce * 2).max(Duration::from_millis(BURST_WINDOW_MS)).min(max),
+            };
+            self.bulk.get_or_insert_with(BulkSummary::new);
+        }
+
+        self.bulk.is_some()
+    }
+
+    /// Whether a burst has been quiet long enough to report
+    fn finished(&self, now: Instant) -> bool {
+        self.bulk.is_some() && now.duration_since(self.last_event) >= self.debounce
+    }
+}
+
+/// Changes coalesced during a burst
+struct BulkSummary {
+    started_at: String,
+    events: usize,
+    /// Events per operation
+    operations: HashMap<&'static str, usize>,
+    /// Events per top-level directory, relative to its root
+    directories: HashMap<String, usize>,
+    /// The latest change to each file, for the activity log and hooks
+    changes: HashMap<PathBuf, SettledChange>,
+}
+
+impl BulkSummary {
+    fn new() -> Self {
+        Self {
+            started_at: chrono::Utc::now().to_rfc3339(),
+            events: 0,
+            operations: HashMap::new(),
+            directories: HashMap::new(),
+            changes: HashMap::new(),
+        }
+    }
+
+    fn add(&mut self, path: &Path, root_path: &Path, operation: &FileOperation) {
+        self.events += 1;
+        *self.operations.entry(operation.as_str()).or_insert(0) += 1;
+
+        // Files directly in the root count under "."
+        let relative = path.strip_prefix(root_path).unwrap_or(path);
+        let mut components = relative.components();
+        let directory = match (components.next(), components.next()) {
+            (Some(first), Some(_)) => first.as_os_str().to_string_lossy().to_string(),
+            _ => ".".to_string(),
+        };
+        *self.directories.entry(directory).or_insert(0) += 1;
+    }
+
+    /// Keep a file's latest change, so the burst is logged once per file
+    ///
+    /// A file created during the burst stays created however often it is
+    /// written, and stays Claude's once any of its changes were.
+    fn settle(&mut self, change: SettledChange) {
+        let Some(existing) = self.changes.get_mut(&change.path) else {
+            self.changes.insert(change.path.clone(), change);
+            return;
+        };
+
+        if !(existing.operation == FileOperation::Created && change.operation == FileOperation::Modified) {
+            existing.operation = change.operation;
+            existing.old_path = change.old_path;
         }
+        if change.source == ChangeSource::Claude {
+            existing.source = ChangeSource::Claude;
+        }
+        existing.timestamp = change.timestamp;
+    }
+
+    /// The directories with the most changes, busiest first
+    fn top_directories(&self) -> Vec<BulkDirectoryCount> {
+        let mut directories: Vec<BulkDirectoryCount> = self
+            .directories
+            .iter()
+            .map(|(path, count)| BulkDirectoryCount { path: path.clone(), count: *count })
+            .collect();
+        directories.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.path.cmp(&b.path)));
+        directories.truncate(MAX_BULK_DIRECTORIES);
+        directories
     }
 }
 
@@ -74,29 +303,71 @@ impl ChangeSource {
     }
 }
 
+/// Claude configuration files that affect running sessions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigKind {
+    /// `CLAUDE.md` / `CLAUDE.local.md` memory files
+    ClaudeMd,
+    /// `.claude/settings.json` / `.claude/settings.local.json`
+    ClaudeSettings,
+    /// `.mcp.json` MCP server configuration
+    McpConfig,
+}
+
+impl ConfigKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigKind::ClaudeMd => "claude_md",
+            ConfigKind::ClaudeSettings => "claude_settings",
+            ConfigKind::McpConfig => "mcp_config",
+        }
+    }
+
+    /// Classify a workspace-relative, `/`-separated path
+    pub fn classify(relative_path: &str) -> Option<Self> {
+        let lower = relative_path.to_lowercase();
+        let file_name = lower.rsplit('/').next().unwrap_or(&lower);
+
+        match file_name {
+            // Memory files are picked up from any directory
+            "claude.md" | "claude.local.md" => Some(ConfigKind::ClaudeMd),
+            "settings.json" | "settings.local.json" if lower == format!(".claude/{}", file_name) => {
+                Some(ConfigKind::ClaudeSettings)
+            }
+            ".mcp.json" if lower == ".mcp.json" => Some(ConfigKind::McpConfig),
+            _ => None,
+        }
+    }
+}
+
 /// Tracks files recently modified by Claude for source attribution
 pub struct SourceTracker {
-    /// Map of file path to last modification time by Claude
+    /// Map of workspace-relative file path to last modification time by Claude
     claude_modifications: HashMap<String, Instant>,
     /// Attribution window duration
     window: Duration,
+    /// Normalizes the paths Claude reports and the paths the watcher sees
+    normalizer: PathNormalizer,
 }
 
 impl SourceTracker {
-    fn new() -> Self {
+    fn new(root: &Path) -> Self {
         Self {
             claude_modifications: HashMap::new(),
             window: Duration::from_millis(ATTRIBUTION_WINDOW_MS),
+            normalizer: PathNormalizer::new(root),
         }
     }
 
     /// Record that Claude modified a file
     pub fn record_claude_modification(&mut self, path: &str) {
-        self.claude_modifications.insert(path.to_string(), Instant::now());
+        let path = self.normalizer.normalize(path);
+        self.claude_modifications.insert(path, Instant::now());
     }
 
     /// Determine the source of a file change
     pub fn determine_source(&mut self, path: &str) -> ChangeSource {
+        let path = self.normalizer.normalize(path);
         let now = Instant::now();
 
         // Clean up old entries
@@ -105,7 +376,7 @@ impl SourceTracker {
         });
 
         // Check if Claude recently modified this file
-        if let Some(timestamp) = self.claude_modifications.remove(path) {
+        if let Some(timestamp) = self.claude_modifications.remove(&path) {
             if now.duration_since(timestamp) < self.window {
                 return ChangeSource::Claude;
             }
@@ -117,10 +388,83 @@ impl SourceTracker {
 
 /// Watcher state for a single session
 struct WatcherState {
-    /// The notify watcher
-    _watcher: RecommendedWatcher,
-    /// Root path being watched (kept for potential future use)
-    _root_path: PathBuf,
+    /// Directories being watched; the first is the one the watcher started with
+    roots: Vec<WatchedRoot>,
+    /// Directory budget and usage, shared by every root
+    budget: Arc<WatchBudget>,
+    /// Default and custom ignore patterns, applied to every root
+    patterns: Vec<String>,
+}
+
+impl WatcherState {
+    /// The root that contains, or is inside, a canonical path
+    fn overlapping_root(&self, canonical: &Path) -> Option<&WatchedRoot> {
+        self.roots
+            .iter()
+            .find(|root| canonical.starts_with(&root.canonical_path) || root.canonical_path.starts_with(canonical))
+    }
+}
+
+/// One directory tree watched for a session
+struct WatchedRoot {
+    /// The notify watcher, shared with the thread that watches new directories
+    _watcher: Arc<std::sync::Mutex<Box<dyn Watcher + Send>>>,
+    /// Root path being watched, as given
+    root_path: PathBuf,
+    /// Root path resolved, for comparing roots
+    canonical_path: PathBuf,
+    /// Directories with a watch under this root
+    watched_dirs: Arc<AtomicUsize>,
+    /// Ignored directories left unwatched, relative to the root
+    excluded_dirs: Vec<String>,
+    /// Scanned on an interval rather than watched natively
+    polling: bool,
+}
+
+/// Directories a session may watch, and how many it does
+struct WatchBudget {
+    max_dirs: usize,
+    watched_dirs: AtomicUsize,
+    /// A new directory went unwatched because the budget was used up
+    limit_reached: AtomicBool,
+}
+
+/// A session's watcher resource usage
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatcherStatus {
+    pub session_id: String,
+    /// The directory the watcher was started with
+    pub root_path: String,
+    /// Directories with a watch, or 1 per root watched recursively
+    pub watched_dirs: usize,
+    pub max_dirs: usize,
+    /// Whether directories created since the watcher started went unwatched
+    pub limit_reached: bool,
+    /// Ignored directories left unwatched, relative to the root
+    pub excluded_dirs: Vec<String>,
+    /// Every watched directory, starting with `root_path`
+    pub roots: Vec<WatchedRootStatus>,
+}
+
+/// One of a session's watched directories
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchedRootStatus {
+    pub root_path: String,
+    pub watched_dirs: usize,
+    /// Ignored directories left unwatched, relative to this root
+    pub excluded_dirs: Vec<String>,
+    /// Whether the directory is scanned on an interval rather than watched natively
+    pub polling: bool,
+}
+
+/// Directories to watch under a root, found before any watch is added
+#[derive(Debug)]
+struct WatchPlan {
+    dirs: Vec<PathBuf>,
+    /// Ignored directories skipped, relative to the root
+    excluded: Vec<String>,
 }
 
 /// Internal event for the event loop
@@ -129,20 +473,120 @@ struct FileEvent {
     path: PathBuf,
     operation: FileOperation,
     root_path: PathBuf,
+    /// Set when the event is one half of a rename
+    rename: Option<RenameHalf>,
+}
+
+/// One half of a rename; notify reports the old and new names separately
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RenameHalf {
+    /// The name a file was moved away from
+    From { tracker: Option<usize> },
+    /// The name a file was moved to
+    To { tracker: Option<usize> },
+}
+
+/// The "from" half of a rename, waiting for its "to" half
+struct RenameStart {
+    path: PathBuf,
+    tracker: Option<usize>,
+    /// The file was created under the old name within the debounce period
+    new_file: bool,
+}
+
+/// An attributed change, ready to be reported
+#[derive(Debug, Clone)]
+struct SettledChange {
+    session_id: String,
+    path: PathBuf,
+    root_path: PathBuf,
+    old_path: Option<PathBuf>,
+    operation: FileOperation,
+    source: ChangeSource,
+    timestamp: String,
+}
+
+/// A change waiting out the debounce period
+#[derive(Debug, Clone)]
+struct PendingChange {
+    operation: FileOperation,
+    root_path: PathBuf,
+    old_path: Option<PathBuf>,
+    at: Instant,
 }
 
 /// Shared state that can be accessed across async boundaries
 struct SharedState {
     /// Source attribution tracker per session
     source_trackers: RwLock<HashMap<String, SourceTracker>>,
+    /// Largest file whose changes are diffed (0 = diff capture off)
+    diff_max_bytes: AtomicUsize,
+    /// Last seen content of changed files per session, for diffing
+    snapshots: RwLock<HashMap<String, HashMap<PathBuf, String>>>,
+    /// Events dropped because the channel was full, per session, not yet reported
+    ///
+    /// Written from notify's callback thread, so this can't be an async lock.
+    unreported_drops: std::sync::Mutex<HashMap<String, u64>>,
+    /// Events dropped since the app started
+    dropped_total: AtomicU64,
+    /// Sessions whose project forbids Claude from deleting files
+    delete_guarded: RwLock<HashSet<String>>,
+    /// Quiet period before a change is emitted
+    debounce_ms: AtomicU64,
+    /// Quiet periods of sessions whose project sets its own
+    ///
+    /// Read while filtering pending changes, so this can't be an async lock.
+    session_debounce_ms: std::sync::Mutex<HashMap<String, u64>>,
+    /// When roots are polled, for watchers started afterwards
+    polling_mode: RwLock<PollingMode>,
+    /// Time between scans of polled roots, for watchers started afterwards
+    poll_interval_ms: AtomicU64,
+    /// Changes are ignored rather than reported
+    paused: AtomicBool,
 }
 
 impl SharedState {
     fn new() -> Self {
         Self {
             source_trackers: RwLock::new(HashMap::new()),
+            diff_max_bytes: AtomicUsize::new(0),
+            snapshots: RwLock::new(HashMap::new()),
+            unreported_drops: std::sync::Mutex::new(HashMap::new()),
+            dropped_total: AtomicU64::new(0),
+            delete_guarded: RwLock::new(HashSet::new()),
+            debounce_ms: AtomicU64::new(DEBOUNCE_MS),
+            session_debounce_ms: std::sync::Mutex::new(HashMap::new()),
+            polling_mode: RwLock::new(PollingMode::Auto),
+            poll_interval_ms: AtomicU64::new(DEFAULT_POLL_INTERVAL_MS),
+            paused: AtomicBool::new(false),
         }
     }
+
+    /// Quiet period before a session's changes are emitted
+    fn debounce_for(&self, session_id: &str) -> Duration {
+        let session_ms = self
+            .session_debounce_ms
+            .lock()
+            .ok()
+            .and_then(|debounces| debounces.get(session_id).copied());
+        Duration::from_millis(session_ms.unwrap_or_else(|| self.debounce_ms.load(Ordering::Relaxed)))
+    }
+
+    /// Count an event the processing task had no room for
+    fn record_drop(&self, session_id: &str) {
+        self.dropped_total.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut drops) = self.unreported_drops.lock() {
+            *drops.entry(session_id.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    /// Take the per-session drop counts accumulated since the last call
+    fn take_unreported_drops(&self) -> HashMap<String, u64> {
+        self.unreported_drops
+            .lock()
+            .map(|mut drops| std::mem::take(&mut *drops))
+            .unwrap_or_default()
+    }
 }
 
 /// File watcher manager
@@ -155,45 +599,47 @@ pub struct FileWatcherManager {
     app_handle: Mutex<Option<AppHandle>>,
     /// Processing task handle
     processing_task: Mutex<Option<tokio::task::JoinHandle<()>>>,
-    /// Event sender for processing
-    event_tx: mpsc::Sender<FileEvent>,
-    /// Event receiver (taken when processing starts)
-    event_rx: Mutex<Option<mpsc::Receiver<FileEvent>>>,
+    /// Event sender for processing (created when processing starts)
+    event_tx: Mutex<Option<mpsc::Sender<FileEvent>>>,
+    /// Capacity of the event channel, fixed once processing starts
+    channel_capacity: AtomicUsize,
 }
 
 impl FileWatcherManager {
     /// Create a new file watcher manager
     pub fn new() -> Self {
-        let (tx, rx) = mpsc::channel(1000);
-
         Self {
             watchers: RwLock::new(HashMap::new()),
             shared: Arc::new(SharedState::new()),
             app_handle: Mutex::new(None),
             processing_task: Mutex::new(None),
-            event_tx: tx,
-            event_rx: Mutex::new(Some(rx)),
+            event_tx: Mutex::new(None),
+            channel_capacity: AtomicUsize::new(DEFAULT_CHANNEL_CAPACITY),
         }
     }
 
     /// Initialize with app handle (called on first watcher start)
-    async fn ensure_initialized(&self, app: AppHandle) {
-        let mut handle = self.app_handle.lock().await;
-        if handle.is_none() {
-            *handle = Some(app.clone());
-
-            // Start the processing task
-            let mut rx_guard = self.event_rx.lock().await;
-            if let Some(rx) = rx_guard.take() {
-                // Clone the shared state Arc for the async task
-                let shared = Arc::clone(&self.shared);
-
-                let mut task_guard = self.processing_task.lock().await;
-                *task_guard = Some(tokio::spawn(async move {
-                    Self::process_events(app, rx, shared).await;
-                }));
-            }
+    ///
+    /// Returns the sender watchers feed events into.
+    async fn ensure_initialized(&self, app: AppHandle) -> mpsc::Sender<FileEvent> {
+        let mut tx_guard = self.event_tx.lock().await;
+        if let Some(tx) = tx_guard.as_ref() {
+            return tx.clone();
         }
+
+        *self.app_handle.lock().await = Some(app.clone());
+
+        // Start the processing task
+        let (tx, rx) = mpsc::channel(self.channel_capacity());
+        let shared = Arc::clone(&self.shared);
+
+        let mut task_guard = self.processing_task.lock().await;
+        *task_guard = Some(tokio::spawn(async move {
+            Self::process_events(app, rx, shared).await;
+        }));
+
+        *tx_guard = Some(tx.clone());
+        tx
     }
 
     /// Process file events
@@ -203,17 +649,62 @@ impl FileWatcherManager {
         shared: Arc<SharedState>,
     ) {
         // Simple debouncing: collect events and emit after quiet period
-        let mut pending: HashMap<(String, PathBuf), (FileOperation, PathBuf, Instant)> = HashMap::new();
-        let debounce_duration = Duration::from_millis(DEBOUNCE_MS);
+        let mut pending: HashMap<(String, PathBuf), PendingChange> = HashMap::new();
+        // The last unpaired "from" half of a rename per session
+        let mut renames: HashMap<String, RenameStart> = HashMap::new();
+        // Event rates per session, and the bursts being summarized
+        let mut bursts: HashMap<String, BurstTracker> = HashMap::new();
+        let overflow_interval = Duration::from_millis(OVERFLOW_REPORT_MS);
+        let mut last_overflow_report = Instant::now();
+        // Emits the last batch's file_changed events
+        let mut emit_task: Option<tokio::task::JoinHandle<()>> = None;
 
         loop {
             // Check for new events with timeout
             match tokio::time::timeout(Duration::from_millis(50), rx.recv()).await {
+                // Changes made while paused are never reported
+                Ok(Some(_)) if shared.paused.load(Ordering::Relaxed) => {}
                 Ok(Some(event)) => {
-                    pending.insert(
-                        (event.session_id, event.path),
-                        (event.operation, event.root_path, Instant::now())
-                    );
+                    let now = Instant::now();
+                    let base_debounce = shared.debounce_for(&event.session_id);
+                    let burst = bursts
+                        .entry(event.session_id.clone())
+                        .or_insert_with(|| BurstTracker::new(now));
+                    let was_bursting = burst.bulk.is_some();
+
+                    if burst.record(now, base_debounce) {
+                        let session_id = event.session_id.clone();
+                        let mut joining = Vec::new();
+                        // Changes queued before the burst was noticed join the summary
+                        if !was_bursting {
+                            let queued: Vec<_> = pending.keys().filter(|(id, _)| *id == session_id).cloned().collect();
+                            for key in queued {
+                                if let Some(change) = pending.remove(&key) {
+                                    joining.push((key.1, change.root_path, change.operation, change.old_path));
+                                }
+                            }
+                            renames.remove(&session_id);
+                        }
+                        joining.push((event.path, event.root_path, event.operation, None));
+
+                        // Attributed now, since the burst can outlast the attribution window
+                        let bulk = burst.bulk.get_or_insert_with(BulkSummary::new);
+                        for (path, root_path, operation, old_path) in joining {
+                            bulk.add(&path, &root_path, &operation);
+                            let source = Self::attribute(&shared, &session_id, &root_path, &path).await;
+                            bulk.settle(SettledChange {
+                                session_id: session_id.clone(),
+                                path,
+                                root_path,
+                                old_path,
+                                operation,
+                                source,
+                                timestamp: chrono::Utc::now().to_rfc3339(),
+                            });
+                        }
+                    } else {
+                        queue_change(&mut pending, &mut renames, event, now);
+                    }
                 }
                 Ok(None) => break, // Channel closed
                 Err(_) => {
@@ -221,58 +712,448 @@ impl FileWatcherManager {
                 }
             }
 
-            // Emit events that have been debounced
+            // Let the frontend know its feed is missing events
+            if last_overflow_report.elapsed() >= overflow_interval {
+                last_overflow_report = Instant::now();
+                Self::report_overflow(&app, &shared);
+            }
+
+            // Report bursts that have settled, and forget idle sessions
             let now = Instant::now();
+            for (session_id, burst) in bursts.iter_mut() {
+                if burst.finished(now) {
+                    if let Some(bulk) = burst.bulk.take() {
+                        Self::report_bulk_change(&app, &shared, session_id, bulk).await;
+                    }
+                }
+            }
+            bursts.retain(|_, burst| {
+                burst.bulk.is_some() || now.duration_since(burst.last_event) < Duration::from_millis(BURST_WINDOW_MS)
+            });
+
+            // Emit events that have been debounced
             let ready: Vec<_> = pending
                 .iter()
-                .filter(|(_, (_, _, time))| now.duration_since(*time) >= debounce_duration)
-                .map(|((session_id, path), (op, root, _))| (session_id.clone(), path.clone(), op.clone(), root.clone()))
+                .filter(|((session_id, _), change)| now.duration_since(change.at) >= shared.debounce_for(session_id))
+                .map(|((session_id, path), change)| (session_id.clone(), path.clone(), change.clone()))
                 .collect();
 
-            for (session_id, path, operation, _root_path) in ready {
-                pending.remove(&(session_id.clone(), path.clone()));
+            // Changes are logged together once the batch has been handled
+            let mut activity = Vec::with_capacity(ready.len());
+            let mut settled = Vec::with_capacity(ready.len());
 
-                // Determine source attribution
-                let source = {
-                    let mut trackers = shared.source_trackers.write().await;
-                    let tracker = trackers.entry(session_id.clone()).or_insert_with(SourceTracker::new);
-                    tracker.determine_source(path.to_string_lossy().as_ref())
-                };
+            for (session_id, path, change) in ready {
+                pending.remove(&(session_id.clone(), path.clone()));
+                let PendingChange { operation, root_path, old_path, .. } = change;
 
-                // Emit the file changed event
-                let payload = FileChangedPayload {
-                    session_id: session_id.clone(),
-                    path: path.to_string_lossy().to_string(),
-                    operation: operation.as_str().to_string(),
-                    source: source.as_str().to_string(),
+                let source = Self::attribute(&shared, &session_id, &root_path, &path).await;
+                let change = SettledChange {
+                    session_id,
+                    path,
+                    root_path,
+                    old_path,
+                    operation,
+                    source,
                     timestamp: chrono::Utc::now().to_rfc3339(),
                 };
+                activity.push(Self::handle_change(&app, &shared, &change).await);
+                settled.push(change);
+            }
 
-                if let Err(e) = emit_event(&app, event_names::FILE_CHANGED, payload) {
-                    log::error!("Failed to emit file_changed event: {}", e);
+            if !settled.is_empty() {
+                let previous = emit_task.take();
+                emit_task = Some(tokio::spawn(Self::emit_changes(
+                    app.clone(),
+                    Arc::clone(&shared),
+                    settled,
+                    previous,
+                )));
+            }
+
+            if !activity.is_empty() {
+                if let Err(e) = Self::save_activity(&app, &activity).await {
+                    log::error!("Failed to save {} activity entries: {}", activity.len(), e);
                 }
             }
         }
     }
 
+    /// Attribute a change to Claude or to something else
+    async fn attribute(shared: &SharedState, session_id: &str, root_path: &Path, path: &Path) -> ChangeSource {
+        let mut trackers = shared.source_trackers.write().await;
+        trackers
+            .entry(session_id.to_string())
+            .or_insert_with(|| SourceTracker::new(root_path))
+            .determine_source(path.to_string_lossy().as_ref())
+    }
+
+    /// Fire the hooks for a change and flag policy and config changes
+    ///
+    /// Returns the change's activity log entry. The file_changed event is left
+    /// to the caller, since bursts are reported as one bulk change instead.
+    async fn handle_change(app: &AppHandle, shared: &SharedState, change: &SettledChange) -> ActivityEntry {
+        let SettledChange { session_id, path, root_path, operation, source, timestamp, .. } = change;
+
+        // Logged paths are relative to the session's first root, like Claude's,
+        // and absolute outside it
+        let (logged_path, logged_old_path) = {
+            let mut trackers = shared.source_trackers.write().await;
+            let tracker = trackers
+                .entry(session_id.clone())
+                .or_insert_with(|| SourceTracker::new(root_path));
+            (
+                tracker.normalizer.normalize(path),
+                change.old_path.as_ref().map(|old| tracker.normalizer.normalize(old)),
+            )
+        };
+        let relative_path = PathNormalizer::new(root_path).normalize(path);
+
+        if *source == ChangeSource::Claude {
+            hooks::fire(
+                app,
+                HookEvent::FileChanged,
+                HookContext {
+                    session_id: Some(session_id.clone()),
+                    file_path: Some(logged_path.clone()),
+                    file_operation: Some(operation.as_str().to_string()),
+                    ..Default::default()
+                },
+            );
+        }
+
+        // Deletes the CLI flags didn't stop still get surfaced
+        if *operation == FileOperation::Deleted
+            && *source == ChangeSource::Claude
+            && shared.delete_guarded.read().await.contains(session_id)
+        {
+            log::warn!("Claude deleted {} in session {} despite the project policy", relative_path, session_id);
+            let payload = PolicyViolationPayload {
+                session_id: session_id.clone(),
+                path: path.to_string_lossy().to_string(),
+                relative_path: relative_path.clone(),
+                policy: "file_deletes".to_string(),
+                timestamp: timestamp.clone(),
+            };
+            if let Err(e) = emit_event(app, event_names::POLICY_VIOLATION, payload) {
+                log::error!("Failed to emit policy_violation event: {}", e);
+            }
+        }
+
+        // Sessions started before a config change are running with stale settings
+        if let Some(kind) = ConfigKind::classify(&relative_path) {
+            let payload = ProjectConfigChangedPayload {
+                session_id: session_id.clone(),
+                path: path.to_string_lossy().to_string(),
+                relative_path,
+                kind: kind.as_str().to_string(),
+                operation: operation.as_str().to_string(),
+                timestamp: timestamp.clone(),
+            };
+
+            if let Err(e) = emit_event(app, event_names::PROJECT_CONFIG_CHANGED, payload) {
+                log::error!("Failed to emit project_config_changed event: {}", e);
+            }
+        }
+
+        ActivityEntry {
+            session_id: session_id.clone(),
+            path: logged_path,
+            operation: operation.as_str(),
+            source: source.as_str(),
+            timestamp: timestamp.clone(),
+            old_path: logged_old_path,
+        }
+    }
+
+    /// Emit the file_changed events for a batch of changes, with their diffs
+    ///
+    /// Diffs can run git, so they are captured off the event loop. Each batch
+    /// waits for the one before it, keeping events and snapshots in order.
+    async fn emit_changes(
+        app: AppHandle,
+        shared: Arc<SharedState>,
+        changes: Vec<SettledChange>,
+        previous: Option<tokio::task::JoinHandle<()>>,
+    ) {
+        if let Some(previous) = previous {
+            let _ = previous.await;
+        }
+
+        for change in changes {
+            // Paths in events are relative to the root the change is under
+            let root = PathNormalizer::new(&change.root_path);
+            let relative_path = root.normalize(&change.path);
+            let old_relative_path = change.old_path.as_ref().map(|old| root.normalize(old));
+
+            let diff = Self::capture_diff(
+                &shared,
+                &change.session_id,
+                &change.path,
+                change.old_path.as_deref(),
+                &change.root_path,
+                &relative_path,
+                &change.operation,
+            )
+            .await;
+
+            let payload = FileChangedPayload {
+                session_id: change.session_id,
+                path: change.path.to_string_lossy().to_string(),
+                root_path: change.root_path.to_string_lossy().to_string(),
+                config_kind: ConfigKind::classify(&relative_path).map(|k| k.as_str().to_string()),
+                relative_path,
+                operation: change.operation.as_str().to_string(),
+                source: change.source.as_str().to_string(),
+                timestamp: change.timestamp,
+                diff,
+                old_path: change.old_path.as_ref().map(|old| old.to_string_lossy().to_string()),
+                old_relative_path,
+            };
+
+            if let Err(e) = emit_event(&app, event_names::FILE_CHANGED, payload) {
+                log::error!("Failed to emit file_changed event: {}", e);
+            }
+        }
+    }
+
+    /// Report a burst of changes
+    ///
+    /// The frontend gets one summary instead of a file_changed event per
+    /// change. Each file's latest change is still logged, in one batch, and
+    /// passed to the hooks, but not diffed.
+    async fn report_bulk_change(app: &AppHandle, shared: &SharedState, session_id: &str, mut bulk: BulkSummary) {
+        log::info!("Coalesced {} file events for session {} into a bulk change", bulk.events, session_id);
+
+        let count = |operation: FileOperation| bulk.operations.get(operation.as_str()).copied().unwrap_or(0);
+        let payload = BulkChangePayload {
+            session_id: session_id.to_string(),
+            events: bulk.events,
+            created: count(FileOperation::Created),
+            modified: count(FileOperation::Modified),
+            deleted: count(FileOperation::Deleted),
+            renamed: count(FileOperation::Renamed),
+            top_directories: bulk.top_directories(),
+            started_at: bulk.started_at,
+            finished_at: chrono::Utc::now().to_rfc3339(),
+        };
+        if let Err(e) = emit_event(app, event_names::BULK_CHANGE, payload) {
+            log::error!("Failed to emit bulk_change event: {}", e);
+        }
+
+        let mut changes: Vec<SettledChange> = bulk.changes.drain().map(|(_, change)| change).collect();
+        changes.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        let mut activity = Vec::with_capacity(changes.len());
+        for change in &changes {
+            activity.push(Self::handle_change(app, shared, change).await);
+        }
+        if let Err(e) = Self::save_activity(app, &activity).await {
+            log::error!("Failed to save {} activity entries: {}", activity.len(), e);
+        }
+    }
+
+    /// Write a batch of changes to the activity log
+    ///
+    /// Entries for sessions deleted while their watcher was running are
+    /// skipped rather than failing the batch.
+    async fn save_activity(app: &AppHandle, entries: &[ActivityEntry]) -> Result<(), AppError> {
+        let Some(state) = app.try_state::<AppState>() else {
+            return Ok(());
+        };
+
+        let mut tx = state.db.begin().await?;
+        for entry in entries {
+            sqlx::query(
+                r#"
+                INSERT INTO activity_log (id, session_id, path, operation, source, timestamp, old_path)
+                SELECT ?, ?, ?, ?, ?, ?, ?
+                WHERE EXISTS(SELECT 1 FROM sessions WHERE id = ?)
+                "#,
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&entry.session_id)
+            .bind(&entry.path)
+            .bind(entry.operation)
+            .bind(entry.source)
+            .bind(&entry.timestamp)
+            .bind(&entry.old_path)
+            .bind(&entry.session_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Log and emit the events dropped since the last report
+    fn report_overflow(app: &AppHandle, shared: &SharedState) {
+        let drops = shared.take_unreported_drops();
+        if drops.is_empty() {
+            return;
+        }
+
+        let total_dropped = shared.dropped_total.load(Ordering::Relaxed);
+        for (session_id, dropped) in drops {
+            log::warn!(
+                "File watcher dropped {} event(s) for session {} ({} total); the event channel is full",
+                dropped,
+                session_id,
+                total_dropped
+            );
+
+            let payload = WatcherOverflowPayload {
+                session_id,
+                dropped,
+                total_dropped,
+            };
+            if let Err(e) = emit_event(app, event_names::WATCHER_OVERFLOW, payload) {
+                log::error!("Failed to emit watcher_overflow event: {}", e);
+            }
+        }
+    }
+
+    /// Diff a changed file against its last seen content
+    ///
+    /// Files modified before they were first seen are diffed against the git
+    /// index instead, when the project is a repository. A renamed file is
+    /// diffed against what was last seen under its old name.
+    async fn capture_diff(
+        shared: &SharedState,
+        session_id: &str,
+        path: &Path,
+        old_path: Option<&Path>,
+        root_path: &Path,
+        relative_path: &str,
+        operation: &FileOperation,
+    ) -> Option<String> {
+        let max_bytes = shared.diff_max_bytes.load(Ordering::SeqCst);
+        if max_bytes == 0 {
+            return None;
+        }
+
+        let current = match operation {
+            FileOperation::Deleted => None,
+            _ => read_text(path, max_bytes).await,
+        };
+
+        let previous = {
+            let mut snapshots = shared.snapshots.write().await;
+            let session = snapshots.entry(session_id.to_string()).or_default();
+            if let Some(content) = old_path.and_then(|old| session.remove(old)) {
+                session.insert(path.to_path_buf(), content);
+            }
+            match &current {
+                Some(content) if session.len() < MAX_SNAPSHOTS_PER_SESSION || session.contains_key(path) => {
+                    session.insert(path.to_path_buf(), content.clone())
+                }
+                Some(_) => session.get(path).cloned(),
+                None => session.remove(path),
+            }
+        };
+
+        match (operation, previous, current) {
+            (_, Some(old), Some(new)) => unified_diff(relative_path, &old, &new),
+            (FileOperation::Created, None, Some(new)) => unified_diff(relative_path, "", &new),
+            (FileOperation::Deleted, Some(old), None) => unified_diff(relative_path, &old, ""),
+            (FileOperation::Modified, None, Some(_)) => git::diff_file(root_path, relative_path, false)
+                .await
+                .ok()
+                .filter(|diff| !diff.is_empty()),
+            _ => None,
+        }
+    }
+
+    /// Get the largest file whose changes are diffed (0 = off)
+    pub fn diff_max_bytes(&self) -> usize {
+        self.shared.diff_max_bytes.load(Ordering::SeqCst)
+    }
+
+    /// Set the largest file whose changes are diffed (0 = off)
+    pub async fn set_diff_max_bytes(&self, max_bytes: usize) {
+        self.shared.diff_max_bytes.store(max_bytes, Ordering::SeqCst);
+        if max_bytes == 0 {
+            self.shared.snapshots.write().await.clear();
+        }
+    }
+
+    /// Set how long a file must be quiet before its change is emitted
+    pub fn set_debounce_ms(&self, debounce_ms: u64) {
+        self.shared.debounce_ms.store(debounce_ms, Ordering::Relaxed);
+    }
+
+    /// Set when roots are polled instead of watched natively
+    ///
+    /// Applies to watchers and directories added afterwards.
+    pub async fn set_polling_mode(&self, mode: PollingMode) {
+        *self.shared.polling_mode.write().await = mode;
+    }
+
+    /// Set the time between scans of polled roots
+    ///
+    /// Applies to watchers and directories added afterwards.
+    pub fn set_poll_interval_ms(&self, interval_ms: u64) {
+        self.shared.poll_interval_ms.store(interval_ms, Ordering::Relaxed);
+    }
+
+    /// Report Claude's file deletions in a session as policy violations
+    pub async fn set_delete_guard(&self, session_id: &str, guarded: bool) {
+        let mut sessions = self.shared.delete_guarded.write().await;
+        if guarded {
+            sessions.insert(session_id.to_string());
+        } else {
+            sessions.remove(session_id);
+        }
+    }
+
+    /// Get the event channel capacity
+    pub fn channel_capacity(&self) -> usize {
+        self.channel_capacity.load(Ordering::SeqCst)
+    }
+
+    /// Set the event channel capacity
+    ///
+    /// The channel is created when the first watcher starts, so a change after
+    /// that takes effect on the next launch. Returns whether it applied now.
+    pub async fn set_channel_capacity(&self, capacity: usize) -> bool {
+        let tx_guard = self.event_tx.lock().await;
+        self.channel_capacity.store(capacity, Ordering::SeqCst);
+        tx_guard.is_none()
+    }
+
+    /// Get the number of events dropped because the channel was full
+    pub fn dropped_events(&self) -> u64 {
+        self.shared.dropped_total.load(Ordering::Relaxed)
+    }
+
+    /// Whether file changes are being ignored for every session
+    pub fn is_paused(&self) -> bool {
+        self.shared.paused.load(Ordering::Relaxed)
+    }
+
+    /// Ignore file changes for every session until resumed
+    ///
+    /// Watchers keep running, so changes made while paused are lost rather
+    /// than reported on resume.
+    pub fn set_paused(&self, paused: bool) {
+        self.shared.paused.store(paused, Ordering::Relaxed);
+    }
+
     /// Start watching a directory for a session
+    ///
+    /// `debounce_ms` overrides the global quiet period for this session.
     pub async fn start_watching(
         &self,
         app: AppHandle,
         session_id: String,
         path: PathBuf,
         ignore_patterns: Option<Vec<String>>,
+        max_dirs: usize,
+        debounce_ms: Option<u64>,
     ) -> Result<(), AppError> {
         // Ensure initialized
-        self.ensure_initialized(app).await;
+        let tx = self.ensure_initialized(app).await;
 
-        // Validate path exists and is a directory
-        if !path.exists() {
-            return Err(AppError::directory_not_found(path.to_string_lossy()));
-        }
-        if !path.is_dir() {
-            return Err(AppError::invalid_input("Path must be a directory"));
-        }
+        validate_root(&path)?;
 
         // Combine default and custom ignore patterns
         let patterns: Vec<String> = DEFAULT_IGNORE_PATTERNS
@@ -281,81 +1162,216 @@ impl FileWatcherManager {
             .chain(ignore_patterns.unwrap_or_default())
             .collect();
 
-        // Create the watcher
-        let session_id_clone = session_id.clone();
-        let root_path = path.clone();
-        let patterns_clone = patterns.clone();
-        let tx = self.event_tx.clone();
-
-        let watcher = RecommendedWatcher::new(
-            move |result: Result<Event, notify::Error>| {
-                if let Ok(event) = result {
-                    let operation = match event.kind {
-                        EventKind::Create(_) => Some(FileOperation::Created),
-                        EventKind::Modify(_) => Some(FileOperation::Modified),
-                        EventKind::Remove(_) => Some(FileOperation::Deleted),
-                        _ => None,
-                    };
-
-                    if let Some(op) = operation {
-                        for event_path in event.paths {
-                            // Check ignore patterns
-                            if Self::should_ignore(&event_path, &patterns_clone) {
-                                continue;
-                            }
-
-                            // Only watch files, not directories (for modify/delete)
-                            // For create, we can't always check if it's a dir yet
-                            if matches!(op, FileOperation::Modified | FileOperation::Deleted)
-                                && event_path.is_dir() {
-                                continue;
-                            }
-
-                            // Send to processing task
-                            let _ = tx.blocking_send(FileEvent {
-                                session_id: session_id_clone.clone(),
-                                path: event_path,
-                                operation: op.clone(),
-                                root_path: root_path.clone(),
-                            });
-                        }
-                    }
-                }
-            },
-            Config::default(),
-        ).map_err(|e| AppError::new(crate::error::ErrorCode::Unknown, format!("Failed to create watcher: {}", e)))?;
-
-        // Start watching
-        let mut watcher = watcher;
-        watcher.watch(&path, RecursiveMode::Recursive)
-            .map_err(|e| AppError::new(crate::error::ErrorCode::Unknown, format!("Failed to watch directory: {}", e)))?;
+        let budget = Arc::new(WatchBudget {
+            max_dirs,
+            watched_dirs: AtomicUsize::new(0),
+            limit_reached: AtomicBool::new(false),
+        });
+        let root = self.watch_root(tx, &session_id, &path, &patterns, &budget).await?;
 
         // Store the watcher state
         let state = WatcherState {
-            _watcher: watcher,
-            _root_path: path,
+            roots: vec![root],
+            budget,
+            patterns,
         };
 
         let mut watchers = self.watchers.write().await;
         watchers.insert(session_id.clone(), state);
 
+        if let Ok(mut debounces) = self.shared.session_debounce_ms.lock() {
+            match debounce_ms {
+                Some(ms) => debounces.insert(session_id.clone(), ms),
+                None => debounces.remove(&session_id),
+            };
+        }
+
         // Initialize source tracker for this session
         let mut trackers = self.shared.source_trackers.write().await;
-        trackers.entry(session_id).or_insert_with(SourceTracker::new);
+        trackers.insert(session_id, SourceTracker::new(&path));
 
         log::info!("Started file watcher for session");
 
         Ok(())
     }
 
+    /// Watch another directory for a session that is already being watched
+    ///
+    /// The new root shares the session's ignore patterns and directory
+    /// budget. Roots may not overlap, so no change is reported twice.
+    pub async fn add_path(&self, app: AppHandle, session_id: &str, path: PathBuf) -> Result<(), AppError> {
+        let tx = self.ensure_initialized(app).await;
+
+        validate_root(&path)?;
+        let canonical = std::fs::canonicalize(&path)?;
+
+        let (patterns, budget) = {
+            let watchers = self.watchers.read().await;
+            let state = watchers.get(session_id).ok_or_else(|| not_watching(session_id))?;
+            if let Some(existing) = state.overlapping_root(&canonical) {
+                return Err(AppError::invalid_input(format!(
+                    "{} overlaps the watched directory {}",
+                    path.to_string_lossy(),
+                    existing.root_path.to_string_lossy()
+                )));
+            }
+            (state.patterns.clone(), Arc::clone(&state.budget))
+        };
+
+        let root = self.watch_root(tx, session_id, &path, &patterns, &budget).await?;
+
+        let mut watchers = self.watchers.write().await;
+        let Some(state) = watchers.get_mut(session_id) else {
+            // Stopped while the new root was being scanned
+            return Err(not_watching(session_id));
+        };
+        if state.overlapping_root(&canonical).is_some() {
+            budget.watched_dirs.fetch_sub(root.watched_dirs.load(Ordering::Relaxed), Ordering::Relaxed);
+            return Err(AppError::invalid_input(format!(
+                "{} is already being watched",
+                path.to_string_lossy()
+            )));
+        }
+        state.roots.push(root);
+
+        log::info!("Added a watched directory for session {}", session_id);
+
+        Ok(())
+    }
+
+    /// Stop watching one of a session's extra directories
+    ///
+    /// The directory the watcher was started with can't be removed; stop the
+    /// watcher instead.
+    pub async fn remove_path(&self, session_id: &str, path: &Path) -> Result<(), AppError> {
+        let canonical = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+
+        let root = {
+            let mut watchers = self.watchers.write().await;
+            let state = watchers.get_mut(session_id).ok_or_else(|| not_watching(session_id))?;
+            let index = state
+                .roots
+                .iter()
+                .position(|root| root.canonical_path == canonical)
+                .ok_or_else(|| {
+                    AppError::invalid_input(format!("{} is not being watched", path.to_string_lossy()))
+                })?;
+            if index == 0 {
+                return Err(AppError::invalid_input(
+                    "The session's main directory can't be removed; stop the watcher instead",
+                ));
+            }
+            let root = state.roots.remove(index);
+            state.budget.watched_dirs.fetch_sub(root.watched_dirs.load(Ordering::Relaxed), Ordering::Relaxed);
+            root
+        };
+
+        // Snapshots under the root would never be diffed again
+        if let Some(snapshots) = self.shared.snapshots.write().await.get_mut(session_id) {
+            snapshots.retain(|snapshot, _| !snapshot.starts_with(&root.root_path));
+        }
+
+        log::info!("Removed a watched directory for session {}", session_id);
+
+        Ok(())
+    }
+
+    /// Create a notify watcher for one of a session's roots
+    async fn watch_root(
+        &self,
+        tx: mpsc::Sender<FileEvent>,
+        session_id: &str,
+        path: &Path,
+        patterns: &[String],
+        budget: &Arc<WatchBudget>,
+    ) -> Result<WatchedRoot, AppError> {
+        let path = path.to_path_buf();
+        let max_dirs = budget.max_dirs;
+        let remaining = max_dirs.saturating_sub(budget.watched_dirs.load(Ordering::Relaxed));
+
+        // Find what to watch before adding any watches, so a repository too
+        // large for the budget fails cleanly
+        let plan = {
+            let root = path.clone();
+            let patterns = patterns.to_vec();
+            tokio::task::spawn_blocking(move || plan_watch(&root, &patterns, remaining))
+                .await
+                .map_err(|e| AppError::new(ErrorCode::Unknown, format!("Failed to scan directory: {}", e)))??
+        };
+
+        // Directories created later are watched from a separate thread, since
+        // adding a watch from notify's callback would deadlock it
+        let (dir_tx, dir_rx) = std::sync::mpsc::channel::<PathBuf>();
+
+        let handler = RootEventHandler {
+            session_id: session_id.to_string(),
+            root_path: path.clone(),
+            patterns: patterns.to_vec(),
+            per_directory: WATCH_PER_DIRECTORY,
+            tx,
+            dir_tx,
+            shared: Arc::clone(&self.shared),
+        };
+
+        // Network and WSL mounts often deliver no native events, so they're polled
+        let mode = *self.shared.polling_mode.read().await;
+        let native = match mode {
+            PollingMode::Always => None,
+            PollingMode::Auto if is_network_mount(&path) => None,
+            _ => Some(start_native_watcher(handler.clone(), &path, &plan, max_dirs)),
+        };
+        let (watcher, watched_dirs, polling) = match native {
+            Some(Ok((watcher, dirs))) => (watcher, dirs, false),
+            Some(Err(e)) if mode == PollingMode::Never => return Err(e),
+            native => {
+                if let Some(Err(e)) = native {
+                    log::warn!("Native file watching failed for {}; polling instead: {}", path.display(), e.message);
+                }
+                let interval = Duration::from_millis(self.shared.poll_interval_ms.load(Ordering::Relaxed));
+                let handler = RootEventHandler { per_directory: true, ..handler };
+                let dirs = plan.dirs.clone();
+                let (watcher, dirs) = tokio::task::spawn_blocking(move || start_poll_watcher(handler, &dirs, interval))
+                    .await
+                    .map_err(|e| AppError::new(ErrorCode::Unknown, format!("Failed to start polling: {}", e)))??;
+                (watcher, dirs, true)
+            }
+        };
+        budget.watched_dirs.fetch_add(watched_dirs, Ordering::Relaxed);
+        let watched_dirs = Arc::new(AtomicUsize::new(watched_dirs));
+
+        let watcher = Arc::new(std::sync::Mutex::new(watcher));
+        if WATCH_PER_DIRECTORY || polling {
+            let watcher = Arc::downgrade(&watcher);
+            let budget = Arc::clone(budget);
+            let root_dirs = Arc::clone(&watched_dirs);
+            let patterns = patterns.to_vec();
+            let session_id = session_id.to_string();
+            std::thread::spawn(move || watch_new_dirs(dir_rx, watcher, budget, root_dirs, patterns, session_id));
+        }
+
+        Ok(WatchedRoot {
+            _watcher: watcher,
+            canonical_path: std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone()),
+            root_path: path,
+            watched_dirs,
+            excluded_dirs: plan.excluded,
+            polling,
+        })
+    }
+
     /// Stop watching for a session
     pub async fn stop_watching(&self, session_id: &str) -> Result<(), AppError> {
         let mut watchers = self.watchers.write().await;
         watchers.remove(session_id);
 
-        // Also clean up source tracker
+        // Also clean up source tracker and snapshots
         let mut trackers = self.shared.source_trackers.write().await;
         trackers.remove(session_id);
+        self.shared.snapshots.write().await.remove(session_id);
+        self.shared.delete_guarded.write().await.remove(session_id);
+        if let Ok(mut debounces) = self.shared.session_debounce_ms.lock() {
+            debounces.remove(session_id);
+        }
 
         log::info!("Stopped file watcher for session");
 
@@ -370,13 +1386,22 @@ impl FileWatcherManager {
         }
     }
 
-    /// Check if a path matches ignore patterns
-    fn should_ignore(path: &Path, patterns: &[String]) -> bool {
+    /// Check if a path under a watched root matches ignore patterns
+    fn should_ignore(path: &Path, root: &Path, patterns: &[String]) -> bool {
         let path_str = path.to_string_lossy();
 
         for pattern in patterns {
             // Simple pattern matching
-            if pattern.starts_with('*') {
+            if pattern == DOTFILES_PATTERN {
+                // Hidden entries below the root; the root itself may be hidden
+                let relative = path.strip_prefix(root).unwrap_or(path);
+                if relative
+                    .components()
+                    .any(|c| matches!(c, Component::Normal(name) if name.to_string_lossy().starts_with('.')))
+                {
+                    return true;
+                }
+            } else if pattern.starts_with('*') {
                 // Suffix match (e.g., *.swp)
                 let suffix = &pattern[1..];
                 if path_str.ends_with(suffix) {
@@ -401,6 +1426,38 @@ impl FileWatcherManager {
         false
     }
 
+    /// Get resource usage for one session's watcher, or for all of them
+    pub async fn status(&self, session_id: Option<&str>) -> Vec<WatcherStatus> {
+        let watchers = self.watchers.read().await;
+        let mut statuses: Vec<WatcherStatus> = watchers
+            .iter()
+            .filter(|(id, _)| session_id.is_none_or(|s| s == id.as_str()))
+            .map(|(id, state)| {
+                let roots: Vec<WatchedRootStatus> = state
+                    .roots
+                    .iter()
+                    .map(|root| WatchedRootStatus {
+                        root_path: root.root_path.to_string_lossy().to_string(),
+                        watched_dirs: root.watched_dirs.load(Ordering::Relaxed),
+                        excluded_dirs: root.excluded_dirs.clone(),
+                        polling: root.polling,
+                    })
+                    .collect();
+                WatcherStatus {
+                    session_id: id.clone(),
+                    root_path: roots[0].root_path.clone(),
+                    watched_dirs: state.budget.watched_dirs.load(Ordering::Relaxed),
+                    max_dirs: state.budget.max_dirs,
+                    limit_reached: state.budget.limit_reached.load(Ordering::Relaxed),
+                    excluded_dirs: roots[0].excluded_dirs.clone(),
+                    roots,
+                }
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+        statuses
+    }
+
     /// Check if we're watching a specific session
     #[allow(dead_code)]
     pub async fn is_watching(&self, session_id: &str) -> bool {
@@ -409,8 +1466,606 @@ impl FileWatcherManager {
     }
 }
 
+/// Walk a root for the directories to watch, skipping ignored ones
+///
+/// Fails with `WATCHER_LIMIT_REACHED` when more than `max_dirs` remain, naming
+/// the largest subtrees so they can be added to the ignore patterns.
+fn plan_watch(root: &Path, patterns: &[String], max_dirs: usize) -> Result<WatchPlan, AppError> {
+    let mut dirs = Vec::new();
+    let mut excluded = Vec::new();
+    // Directory counts per top-level entry, for suggesting exclusions
+    let mut subtrees: HashMap<PathBuf, usize> = HashMap::new();
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        if let Ok(relative) = dir.strip_prefix(root) {
+            if let Some(top) = relative.components().next() {
+                *subtrees.entry(PathBuf::from(top.as_os_str())).or_insert(0) += 1;
+            }
+        }
+        dirs.push(dir.clone());
+
+        if dirs.len() > max_dirs {
+            return Err(limit_error(root, max_dirs, subtrees));
+        }
+
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            // Symlinks aren't followed, so a link cycle can't run away
+            if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+                continue;
+            }
+            let path = entry.path();
+            if FileWatcherManager::should_ignore(&path, root, patterns) {
+                if excluded.len() < MAX_REPORTED_EXCLUSIONS {
+                    let relative = path.strip_prefix(root).unwrap_or(&path);
+                    excluded.push(relative.to_string_lossy().replace('\\', "/"));
+                }
+                continue;
+            }
+            stack.push(path);
+        }
+    }
+
+    excluded.sort();
+    Ok(WatchPlan { dirs, excluded })
+}
+
+/// Build the error for a root with more directories than the budget
+fn limit_error(root: &Path, max_dirs: usize, subtrees: HashMap<PathBuf, usize>) -> AppError {
+    let mut largest: Vec<(PathBuf, usize)> = subtrees.into_iter().collect();
+    largest.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    largest.truncate(MAX_SUGGESTED_EXCLUSIONS);
+
+    let suggestions: Vec<String> = largest
+        .iter()
+        .map(|(path, count)| format!("{} ({}+ directories)", path.to_string_lossy(), count))
+        .collect();
+
+    AppError::with_details(
+        ErrorCode::WatcherLimitReached,
+        format!("{} has more than {} directories to watch", root.to_string_lossy(), max_dirs),
+        format!(
+            "Add the largest directories to the ignore patterns or raise the project's watcher limit. Largest: {}",
+            suggestions.join(", ")
+        ),
+    )
+}
+
+/// Map a failure to add a watch, which usually means the OS ran out of them
+fn watch_error(root: &Path, max_dirs: usize, error: notify::Error) -> AppError {
+    match error.kind {
+        notify::ErrorKind::MaxFilesWatch => AppError::with_details(
+            ErrorCode::WatcherLimitReached,
+            format!("The system ran out of file watches for {}", root.to_string_lossy()),
+            format!(
+                "Raise fs.inotify.max_user_watches, add large directories to the ignore patterns, or lower the project's watcher limit (currently {})",
+                max_dirs
+            ),
+        ),
+        _ => AppError::new(ErrorCode::Unknown, format!("Failed to watch directory: {}", error)),
+    }
+}
+
+/// Watch directories created after a watcher started, within its budget
+///
+/// Runs until the watcher is dropped.
+fn watch_new_dirs(
+    rx: std::sync::mpsc::Receiver<PathBuf>,
+    watcher: Weak<std::sync::Mutex<Box<dyn Watcher + Send>>>,
+    budget: Arc<WatchBudget>,
+    root_dirs: Arc<AtomicUsize>,
+    patterns: Vec<String>,
+    session_id: String,
+) {
+    while let Ok(dir) = rx.recv() {
+        let Some(watcher) = watcher.upgrade() else {
+            break;
+        };
+
+        // The directory may already have children, e.g. from `mkdir -p`
+        let remaining = budget.max_dirs.saturating_sub(budget.watched_dirs.load(Ordering::Relaxed));
+        let dirs = match plan_watch(&dir, &patterns, remaining) {
+            Ok(plan) => plan.dirs,
+            Err(_) => {
+                if !budget.limit_reached.swap(true, Ordering::Relaxed) {
+                    log::warn!(
+                        "File watcher for session {} reached its limit of {} directories; new directories are not watched",
+                        session_id,
+                        budget.max_dirs
+                    );
+                }
+                continue;
+            }
+        };
+
+        let mut watcher = watcher.lock().unwrap_or_else(|e| e.into_inner());
+        for dir in dirs {
+            if watcher.watch(&dir, RecursiveMode::NonRecursive).is_ok() {
+                budget.watched_dirs.fetch_add(1, Ordering::Relaxed);
+                root_dirs.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Add a raw event to the changes waiting out the debounce period
+///
+/// The two halves of a rename become one `Renamed` change when the "to" half
+/// arrives while the "from" half is still pending. Unpaired, a "from" half is
+/// a delete and a "to" half a create, as for files moved out of or into the
+/// watched tree.
+fn queue_change(
+    pending: &mut HashMap<(String, PathBuf), PendingChange>,
+    renames: &mut HashMap<String, RenameStart>,
+    event: FileEvent,
+    now: Instant,
+) {
+    let key = (event.session_id.clone(), event.path.clone());
+
+    let (operation, old_path) = match event.rename {
+        Some(RenameHalf::From { tracker }) => {
+            let new_file = pending
+                .get(&key)
+                .is_some_and(|change| change.operation == FileOperation::Created);
+            renames.insert(
+                event.session_id.clone(),
+                RenameStart { path: event.path.clone(), tracker, new_file },
+            );
+            (event.operation, None)
+        }
+        Some(RenameHalf::To { tracker }) => {
+            let start = match renames.remove(&event.session_id) {
+                Some(start) if start.tracker.is_none() || tracker.is_none() || start.tracker == tracker => {
+                    // Once the "from" half has been emitted as a delete, this can only be a create
+                    pending.remove(&(event.session_id.clone(), start.path.clone())).map(|_| start)
+                }
+                _ => None,
+            };
+            match start {
+                // Written under a temporary name and moved into place, as atomic saves do
+                Some(start) if start.new_file => (FileOperation::Modified, None),
+                Some(start) => (FileOperation::Renamed, Some(start.path)),
+                None => (event.operation, None),
+            }
+        }
+        None => (event.operation, None),
+    };
+
+    // Writes to a file just renamed don't hide the rename
+    if operation == FileOperation::Modified {
+        if let Some(change) = pending.get_mut(&key).filter(|c| c.operation == FileOperation::Renamed) {
+            change.at = now;
+            return;
+        }
+    }
+
+    pending.insert(
+        key,
+        PendingChange {
+            operation,
+            root_path: event.root_path,
+            old_path,
+            at: now,
+        },
+    );
+}
+
+/// Check that a path can be watched
+fn validate_root(path: &Path) -> Result<(), AppError> {
+    if !path.exists() {
+        return Err(AppError::directory_not_found(path.to_string_lossy()));
+    }
+    if !path.is_dir() {
+        return Err(AppError::invalid_input("Path must be a directory"));
+    }
+    Ok(())
+}
+
+fn not_watching(session_id: &str) -> AppError {
+    AppError::invalid_input(format!("Session {} has no file watcher running", session_id))
+}
+
+/// Turns one root's notify events into file events for processing
+#[derive(Clone)]
+struct RootEventHandler {
+    session_id: String,
+    root_path: PathBuf,
+    patterns: Vec<String>,
+    /// Each directory has its own watch, so new ones are sent to be watched
+    per_directory: bool,
+    tx: mpsc::Sender<FileEvent>,
+    dir_tx: std::sync::mpsc::Sender<PathBuf>,
+    shared: Arc<SharedState>,
+}
+
+impl EventHandler for RootEventHandler {
+    fn handle_event(&mut self, result: Result<Event, notify::Error>) {
+        if let Ok(event) = result {
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+                return;
+            }
+            let tracker = event.attrs.tracker();
+
+            for event_path in event.paths {
+                // Check ignore patterns
+                if FileWatcherManager::should_ignore(&event_path, &self.root_path, &self.patterns) {
+                    continue;
+                }
+
+                let (op, rename) = match event.kind {
+                    EventKind::Create(_) => (FileOperation::Created, None),
+                    EventKind::Modify(ModifyKind::Name(mode)) => match mode {
+                        RenameMode::From => (FileOperation::Deleted, Some(RenameHalf::From { tracker })),
+                        RenameMode::To => (FileOperation::Created, Some(RenameHalf::To { tracker })),
+                        // macOS doesn't say which side of a rename a path is on
+                        RenameMode::Any if event_path.exists() => {
+                            (FileOperation::Created, Some(RenameHalf::To { tracker: None }))
+                        }
+                        RenameMode::Any => (FileOperation::Deleted, Some(RenameHalf::From { tracker: None })),
+                        // Linux sends this after the two halves it pairs
+                        RenameMode::Both => continue,
+                        RenameMode::Other => (FileOperation::Modified, None),
+                    },
+                    EventKind::Modify(_) => (FileOperation::Modified, None),
+                    _ => (FileOperation::Deleted, None),
+                };
+
+                if self.per_directory && op == FileOperation::Created && event_path.is_dir() {
+                    let _ = self.dir_tx.send(event_path.clone());
+                }
+
+                // Only watch files, not directories (for modify/delete)
+                // For create, we can't always check if it's a dir yet
+                if matches!(op, FileOperation::Modified | FileOperation::Deleted)
+                    && event_path.is_dir() {
+                    continue;
+                }
+
+                // Send to processing task, counting what doesn't fit
+                // rather than stalling notify's thread
+                let event = FileEvent {
+                    session_id: self.session_id.clone(),
+                    path: event_path,
+                    operation: op,
+                    root_path: self.root_path.clone(),
+                    rename,
+                };
+                if let Err(TrySendError::Full(_)) = self.tx.try_send(event) {
+                    self.shared.record_drop(&self.session_id);
+                }
+            }
+        }
+    }
+}
+
+/// Start the platform's watcher on a root
+///
+/// Returns the watcher and how many directories it watches.
+fn start_native_watcher(
+    handler: RootEventHandler,
+    root: &Path,
+    plan: &WatchPlan,
+    max_dirs: usize,
+) -> Result<(Box<dyn Watcher + Send>, usize), AppError> {
+    let mut watcher = RecommendedWatcher::new(handler, Config::default())
+        .map_err(|e| AppError::new(ErrorCode::Unknown, format!("Failed to create watcher: {}", e)))?;
+
+    let watched_dirs = if WATCH_PER_DIRECTORY {
+        for dir in &plan.dirs {
+            watcher.watch(dir, RecursiveMode::NonRecursive)
+                .map_err(|e| watch_error(root, max_dirs, e))?;
+        }
+        plan.dirs.len()
+    } else {
+        watcher.watch(root, RecursiveMode::Recursive)
+            .map_err(|e| watch_error(root, max_dirs, e))?;
+        1
+    };
+
+    Ok((Box::new(watcher), watched_dirs))
+}
+
+/// Start a watcher that rescans a root's directories every `interval`
+///
+/// Directories are scanned one level at a time, like inotify's watches, so
+/// ignored directories are never walked. The first scan happens here, so
+/// this blocks.
+fn start_poll_watcher(
+    handler: RootEventHandler,
+    dirs: &[PathBuf],
+    interval: Duration,
+) -> Result<(Box<dyn Watcher + Send>, usize), AppError> {
+    let mut watcher = PollWatcher::new(handler, Config::default().with_poll_interval(interval))
+        .map_err(|e| AppError::new(ErrorCode::Unknown, format!("Failed to create polling watcher: {}", e)))?;
+
+    for dir in dirs {
+        watcher.watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| AppError::new(ErrorCode::Unknown, format!("Failed to poll directory: {}", e)))?;
+    }
+
+    Ok((Box::new(watcher), dirs.len()))
+}
+
+/// Whether a path is on a filesystem that native watching can't be trusted on
+fn is_network_mount(path: &Path) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        std::fs::read_to_string("/proc/mounts")
+            .ok()
+            .and_then(|mounts| mount_fs_type(&mounts, &path).map(|fs| POLLED_FILESYSTEMS.contains(&fs)))
+            .unwrap_or(false)
+    }
+    #[cfg(windows)]
+    {
+        // UNC paths are network shares, including WSL's \\wsl$ and \\wsl.localhost
+        let path = path.to_string_lossy();
+        path.starts_with(r"\\?\UNC\") || (path.starts_with(r"\\") && !path.starts_with(r"\\?\"))
+    }
+    #[cfg(not(any(target_os = "linux", windows)))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+/// The filesystem type of the mount containing `path`, from `/proc/mounts`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn mount_fs_type<'a>(mounts: &'a str, path: &Path) -> Option<&'a str> {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let mount_point = unescape_mount_path(fields.nth(1)?);
+            let fs_type = fields.next()?;
+            path.starts_with(&mount_point).then_some((mount_point, fs_type))
+        })
+        // The deepest mount point is the one the path is on
+        .max_by_key(|(mount_point, _)| mount_point.components().count())
+        .map(|(_, fs_type)| fs_type)
+}
+
+/// Decode the octal escapes `/proc/mounts` uses for spaces and the like
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn unescape_mount_path(field: &str) -> PathBuf {
+    let bytes = field.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = bytes
+            .get(i + 1..i + 4)
+            .filter(|digits| bytes[i] == b'\\' && digits.iter().all(|d| (b'0'..=b'7').contains(d)));
+        match escape {
+            Some(digits) => {
+                decoded.push(digits.iter().fold(0u8, |n, d| n.wrapping_mul(8) + (d - b'0')));
+                i += 4;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    PathBuf::from(String::from_utf8_lossy(&decoded).to_string())
+}
+
+/// Read a file as text if it is small enough and valid UTF-8
+async fn read_text(path: &Path, max_bytes: usize) -> Option<String> {
+    let metadata = tokio::fs::metadata(path).await.ok()?;
+    if !metadata.is_file() || metadata.len() > max_bytes as u64 {
+        return None;
+    }
+
+    String::from_utf8(tokio::fs::read(path).await.ok()?).ok()
+}
+
 impl Default for FileWatcherManager {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_watch_budget() {
+        let root = std::env::temp_dir().join(format!("wingman-plan-{}", uuid::Uuid::new_v4()));
+        for dir in ["src/a", "src/b", "node_modules/x/y", "docs"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        let patterns: Vec<String> = DEFAULT_IGNORE_PATTERNS.iter().map(|p| p.to_string()).collect();
+
+        let plan = plan_watch(&root, &patterns, 10).unwrap();
+        assert_eq!(plan.dirs.len(), 5);
+        assert_eq!(plan.excluded, vec!["node_modules"]);
+
+        let err = plan_watch(&root, &patterns, 3).unwrap_err();
+        assert!(matches!(err.code, ErrorCode::WatcherLimitReached));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_classify_config_files() {
+        assert_eq!(ConfigKind::classify("CLAUDE.md"), Some(ConfigKind::ClaudeMd));
+        assert_eq!(ConfigKind::classify("packages/api/CLAUDE.md"), Some(ConfigKind::ClaudeMd));
+        assert_eq!(ConfigKind::classify(".claude/settings.json"), Some(ConfigKind::ClaudeSettings));
+        assert_eq!(ConfigKind::classify(".claude/settings.local.json"), Some(ConfigKind::ClaudeSettings));
+        assert_eq!(ConfigKind::classify(".mcp.json"), Some(ConfigKind::McpConfig));
+        assert_eq!(ConfigKind::classify("src/settings.json"), None);
+        assert_eq!(ConfigKind::classify("docs/.mcp.json"), None);
+        assert_eq!(ConfigKind::classify("src/main.rs"), None);
+    }
+
+    fn event(path: &str, operation: FileOperation, rename: Option<RenameHalf>) -> FileEvent {
+        FileEvent {
+            session_id: "s".to_string(),
+            path: PathBuf::from(path),
+            operation,
+            root_path: PathBuf::from("/root"),
+            rename,
+        }
+    }
+
+    #[test]
+    fn test_queue_change_pairs_renames() {
+        let mut pending = HashMap::new();
+        let mut renames = HashMap::new();
+        let now = Instant::now();
+
+        let from = Some(RenameHalf::From { tracker: Some(7) });
+        let to = Some(RenameHalf::To { tracker: Some(7) });
+        queue_change(&mut pending, &mut renames, event("/root/a.rs", FileOperation::Deleted, from), now);
+        queue_change(&mut pending, &mut renames, event("/root/b.rs", FileOperation::Created, to), now);
+        // Writing the new name keeps it a rename
+        queue_change(&mut pending, &mut renames, event("/root/b.rs", FileOperation::Modified, None), now);
+
+        assert_eq!(pending.len(), 1);
+        let change = &pending[&("s".to_string(), PathBuf::from("/root/b.rs"))];
+        assert_eq!(change.operation, FileOperation::Renamed);
+        assert_eq!(change.old_path, Some(PathBuf::from("/root/a.rs")));
+    }
+
+    #[test]
+    fn test_queue_change_unpaired_halves() {
+        let mut pending = HashMap::new();
+        let mut renames = HashMap::new();
+        let now = Instant::now();
+
+        // Moved out of the tree, then a different file moved in
+        let from = Some(RenameHalf::From { tracker: Some(1) });
+        let to = Some(RenameHalf::To { tracker: Some(2) });
+        queue_change(&mut pending, &mut renames, event("/root/a.rs", FileOperation::Deleted, from), now);
+        queue_change(&mut pending, &mut renames, event("/root/b.rs", FileOperation::Created, to), now);
+
+        assert_eq!(pending[&("s".to_string(), PathBuf::from("/root/a.rs"))].operation, FileOperation::Deleted);
+        assert_eq!(pending[&("s".to_string(), PathBuf::from("/root/b.rs"))].operation, FileOperation::Created);
+    }
+
+    #[test]
+    fn test_queue_change_atomic_save() {
+        let mut pending = HashMap::new();
+        let mut renames = HashMap::new();
+        let now = Instant::now();
+
+        let from = Some(RenameHalf::From { tracker: None });
+        let to = Some(RenameHalf::To { tracker: None });
+        queue_change(&mut pending, &mut renames, event("/root/a.rs.tmp", FileOperation::Created, None), now);
+        queue_change(&mut pending, &mut renames, event("/root/a.rs.tmp", FileOperation::Deleted, from), now);
+        queue_change(&mut pending, &mut renames, event("/root/a.rs", FileOperation::Created, to), now);
+
+        assert_eq!(pending.len(), 1);
+        let change = &pending[&("s".to_string(), PathBuf::from("/root/a.rs"))];
+        assert_eq!(change.operation, FileOperation::Modified);
+        assert_eq!(change.old_path, None);
+    }
+
+    #[test]
+    fn test_should_ignore_dotfiles() {
+        let root = Path::new("/home/user/.config/project");
+        let patterns = vec![DOTFILES_PATTERN.to_string()];
+
+        assert!(FileWatcherManager::should_ignore(&root.join(".env"), root, &patterns));
+        assert!(FileWatcherManager::should_ignore(&root.join(".github/ci.yml"), root, &patterns));
+        // A hidden directory above the root doesn't hide the whole tree
+        assert!(!FileWatcherManager::should_ignore(&root.join("src/main.rs"), root, &patterns));
+        assert!(!FileWatcherManager::should_ignore(&root.join("src/main.rs"), root, &[]));
+    }
+
+    #[test]
+    fn test_mount_fs_type() {
+        let mounts = "\
+/dev/sda1 / ext4 rw,relatime 0 0
+server:/export /mnt/nfs nfs4 rw 0 0
+C:\\134 /mnt/c 9p rw 0 0
+//nas/share /mnt/my\\040share cifs rw 0 0
+";
+        assert_eq!(mount_fs_type(mounts, Path::new("/home/me/project")), Some("ext4"));
+        assert_eq!(mount_fs_type(mounts, Path::new("/mnt/nfs/project")), Some("nfs4"));
+        assert_eq!(mount_fs_type(mounts, Path::new("/mnt/c/Users/me")), Some("9p"));
+        assert_eq!(mount_fs_type(mounts, Path::new("/mnt/my share/repo")), Some("cifs"));
+        // A mount point matches whole components only
+        assert_eq!(mount_fs_type(mounts, Path::new("/mnt/nfsx")), Some("ext4"));
+    }
+
+    #[test]
+    fn test_polling_mode_parse() {
+        for mode in PollingMode::ALL {
+            assert_eq!(PollingMode::parse(mode.as_str()), Some(mode));
+        }
+        assert_eq!(PollingMode::parse("sometimes"), None);
+    }
+
+    #[test]
+    fn test_burst_tracker() {
+        let start = Instant::now();
+        let base = Duration::from_millis(DEBOUNCE_MS);
+        let mut burst = BurstTracker::new(start);
+
+        for _ in 1..BURST_THRESHOLD {
+            assert!(!burst.record(start, base));
+        }
+        assert!(burst.record(start, base));
+        let debounce = burst.debounce;
+        assert!(debounce >= Duration::from_millis(BURST_WINDOW_MS));
+
+        // Churn that keeps up in the next window raises the debounce
+        let next = start + Duration::from_millis(BURST_WINDOW_MS);
+        for _ in 0..BURST_THRESHOLD {
+            assert!(burst.record(next, base));
+        }
+        assert_eq!(burst.debounce, debounce * 2);
+
+        assert!(!burst.finished(next + debounce));
+        assert!(burst.finished(next + debounce * 2));
+    }
+
+    #[test]
+    fn test_bulk_summary_directories() {
+        let root = Path::new("/root");
+        let mut bulk = BulkSummary::new();
+        bulk.add(Path::new("/root/dist/a.js"), root, &FileOperation::Created);
+        bulk.add(Path::new("/root/dist/b/c.js"), root, &FileOperation::Created);
+        bulk.add(Path::new("/root/package-lock.json"), root, &FileOperation::Modified);
+
+        assert_eq!(bulk.events, 3);
+        assert_eq!(bulk.operations["created"], 2);
+        let top = bulk.top_directories();
+        assert_eq!((top[0].path.as_str(), top[0].count), ("dist", 2));
+        assert_eq!((top[1].path.as_str(), top[1].count), (".", 1));
+    }
+
+    fn settled(path: &str, operation: FileOperation, source: ChangeSource) -> SettledChange {
+        SettledChange {
+            session_id: "s".to_string(),
+            path: PathBuf::from(path),
+            root_path: PathBuf::from("/root"),
+            old_path: None,
+            operation,
+            source,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    #[test]
+    fn test_bulk_summary_keeps_latest_change_per_file() {
+        let mut bulk = BulkSummary::new();
+        bulk.settle(settled("/root/a.js", FileOperation::Created, ChangeSource::Claude));
+        bulk.settle(settled("/root/a.js", FileOperation::Modified, ChangeSource::External));
+        bulk.settle(settled("/root/b.js", FileOperation::Modified, ChangeSource::External));
+        bulk.settle(settled("/root/b.js", FileOperation::Deleted, ChangeSource::External));
+
+        assert_eq!(bulk.changes.len(), 2);
+        let a = &bulk.changes[Path::new("/root/a.js")];
+        assert_eq!(a.operation, FileOperation::Created);
+        assert_eq!(a.source, ChangeSource::Claude);
+        let b = &bulk.changes[Path::new("/root/b.js")];
+        assert_eq!(b.operation, FileOperation::Deleted);
+        assert_eq!(b.source, ChangeSource::External);
+    }
+}
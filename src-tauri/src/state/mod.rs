@@ -3,9 +3,19 @@
 //! Manages the global application state shared across commands.
 
 pub mod app_state;
+pub mod command_guard;
+pub mod command_metrics;
+pub mod file_diff;
 pub mod file_watcher;
+pub mod project_index;
+pub mod rate_limiter;
+pub mod script_runs;
+pub mod startup;
+pub mod watcher_benchmark;
 
 pub use app_state::*;
+pub use command_guard::with_command_guards;
+pub use startup::StartupTracker;
 // Re-export file watcher types that are used externally
 #[allow(unused_imports)]
 pub use file_watcher::FileWatcherManager;
@@ -0,0 +1,493 @@
+//! Project Index
+//!
+//! A lightweight, ctags-like index of a project's files and top-level symbols,
+//! used to suggest relevant files when composing a prompt.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+
+use crate::error::{AppError, ErrorCode};
+use crate::paths::PathNormalizer;
+
+/// Indexes older than this are refreshed in the background on lookup
+const STALE_AFTER: Duration = Duration::from_secs(300);
+
+/// Maximum files indexed per project
+const MAX_FILES: usize = 20_000;
+
+/// Files larger than this are indexed by path only
+const MAX_SYMBOL_FILE_BYTES: u64 = 512 * 1024;
+
+/// Directories never descended into
+const SKIP_DIRS: &[&str] = &[
+    ".git",
+    "node_modules",
+    ".next",
+    "target",
+    "dist",
+    "build",
+    ".idea",
+    ".vscode",
+    "__pycache__",
+    ".pytest_cache",
+    ".cargo",
+    "vendor",
+    "coverage",
+];
+
+/// File extensions included in the index
+const INDEXED_EXTENSIONS: &[&str] = &[
+    "rs", "ts", "tsx", "js", "jsx", "mjs", "cjs", "py", "go", "java", "kt", "swift", "rb", "c",
+    "h", "cpp", "hpp", "cs", "css", "scss", "html", "vue", "svelte", "md", "json", "toml",
+    "yaml", "yml", "sql",
+];
+
+/// Words ignored in lookup queries
+const STOPWORDS: &[&str] = &[
+    "the", "an", "of", "in", "for", "to", "and", "on", "with", "is", "my", "this", "that",
+    "where", "how", "what", "file", "files", "code",
+];
+
+/// A symbol definition found in a file
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Symbol {
+    pub name: String,
+    pub kind: String,
+    pub line: usize,
+}
+
+/// An indexed file
+#[derive(Debug)]
+struct IndexedFile {
+    /// Workspace-relative path
+    path: String,
+    symbols: Vec<Symbol>,
+}
+
+/// Index of a single project
+#[derive(Debug)]
+pub struct ProjectIndex {
+    files: Vec<IndexedFile>,
+    built_at: Instant,
+    /// RFC 3339 time the index was built
+    pub indexed_at: String,
+    /// Whether the file limit was hit
+    pub truncated: bool,
+}
+
+/// File suggested for a query
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextMatch {
+    pub path: String,
+    pub score: u32,
+    /// Symbols in the file that matched the query
+    pub symbols: Vec<Symbol>,
+}
+
+impl ProjectIndex {
+    /// Build an index by walking a project root
+    ///
+    /// Blocks on file I/O; call from a blocking task.
+    pub fn build(root: &Path) -> Result<Self, AppError> {
+        if !root.is_dir() {
+            return Err(AppError::directory_not_found(root.to_string_lossy()));
+        }
+
+        let normalizer = PathNormalizer::new(root);
+        let mut files = Vec::new();
+        let mut stack = vec![root.to_path_buf()];
+        let mut truncated = false;
+
+        'walk: while let Some(dir) = stack.pop() {
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    log::debug!("Skipping unreadable directory {}: {}", dir.display(), e);
+                    continue;
+                }
+            };
+
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Ok(file_type) = entry.file_type() else { continue };
+                let name = entry.file_name().to_string_lossy().to_string();
+
+                if file_type.is_dir() {
+                    if !SKIP_DIRS.contains(&name.as_str()) {
+                        stack.push(path);
+                    }
+                    continue;
+                }
+
+                let extension = path
+                    .extension()
+                    .map(|e| e.to_string_lossy().to_lowercase())
+                    .unwrap_or_default();
+                if !file_type.is_file() || !INDEXED_EXTENSIONS.contains(&extension.as_str()) {
+                    continue;
+                }
+
+                if files.len() >= MAX_FILES {
+                    truncated = true;
+                    break 'walk;
+                }
+
+                let small = entry.metadata().map(|m| m.len() <= MAX_SYMBOL_FILE_BYTES).unwrap_or(false);
+                let symbols = if small {
+                    std::fs::read_to_string(&path)
+                        .map(|content| extract_symbols(&extension, &content))
+                        .unwrap_or_default()
+                } else {
+                    Vec::new()
+                };
+
+                files.push(IndexedFile {
+                    path: normalizer.normalize(&path),
+                    symbols,
+                });
+            }
+        }
+
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(Self {
+            files,
+            built_at: Instant::now(),
+            indexed_at: chrono::Utc::now().to_rfc3339(),
+            truncated,
+        })
+    }
+
+    /// Number of indexed files
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Number of indexed symbols
+    pub fn symbol_count(&self) -> usize {
+        self.files.iter().map(|f| f.symbols.len()).sum()
+    }
+
+    /// Find the files most relevant to a free-text query
+    pub fn lookup(&self, query: &str, limit: usize) -> Vec<ContextMatch> {
+        let terms = query_terms(query);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches: Vec<ContextMatch> = self
+            .files
+            .iter()
+            .filter_map(|file| score_file(file, &terms))
+            .collect();
+
+        matches.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+        matches.truncate(limit);
+        matches
+    }
+}
+
+/// Score a file against query terms, or `None` if nothing matched
+fn score_file(file: &IndexedFile, terms: &[String]) -> Option<ContextMatch> {
+    let path = file.path.to_lowercase();
+    let file_name = path.rsplit('/').next().unwrap_or(&path);
+    let stem = file_name.split('.').next().unwrap_or(file_name);
+
+    let mut score = 0;
+    let mut matched_terms = 0;
+    let mut symbols: Vec<Symbol> = Vec::new();
+
+    for term in terms {
+        let mut term_score = if stem == term {
+            6
+        } else if stem.contains(term.as_str()) {
+            4
+        } else if path.contains(term.as_str()) {
+            2
+        } else {
+            0
+        };
+
+        let mut symbol_hits = 0;
+        for symbol in &file.symbols {
+            let name = symbol.name.to_lowercase();
+            if name.contains(term.as_str()) {
+                term_score += if name == *term { 3 } else { 1 };
+                symbol_hits += 1;
+                if symbols.len() < 5 && !symbols.iter().any(|s| s.name == symbol.name) {
+                    symbols.push(symbol.clone());
+                }
+                if symbol_hits >= 3 {
+                    break;
+                }
+            }
+        }
+
+        if term_score > 0 {
+            matched_terms += 1;
+            score += term_score;
+        }
+    }
+
+    if score == 0 {
+        return None;
+    }
+
+    // Prefer files that match every part of the query
+    score += 2 * matched_terms;
+
+    Some(ContextMatch {
+        path: file.path.clone(),
+        score,
+        symbols,
+    })
+}
+
+/// Split a query into lowercase search terms, breaking up camelCase and
+/// snake_case words and dropping stopwords
+fn query_terms(query: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut seen = HashSet::new();
+
+    for word in query.split(|c: char| !c.is_alphanumeric()) {
+        let mut current = String::new();
+        let mut previous_lower = false;
+        let mut parts = Vec::new();
+        for c in word.chars() {
+            if c.is_uppercase() && previous_lower {
+                parts.push(std::mem::take(&mut current));
+            }
+            previous_lower = c.is_lowercase() || c.is_ascii_digit();
+            current.extend(c.to_lowercase());
+        }
+        parts.push(current);
+
+        for part in parts {
+            if part.len() >= 2 && !STOPWORDS.contains(&part.as_str()) && seen.insert(part.clone()) {
+                terms.push(part);
+            }
+        }
+    }
+
+    terms
+}
+
+/// Extract symbol definitions with simple per-language line heuristics
+fn extract_symbols(extension: &str, content: &str) -> Vec<Symbol> {
+    let keywords: &[(&str, &str)] = match extension {
+        "rs" => &[
+            ("fn", "function"),
+            ("struct", "struct"),
+            ("enum", "enum"),
+            ("trait", "trait"),
+            ("mod", "module"),
+            ("type", "type"),
+            ("const", "constant"),
+            ("static", "constant"),
+            ("macro_rules!", "macro"),
+        ],
+        "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" | "vue" | "svelte" => &[
+            ("function", "function"),
+            ("function*", "function"),
+            ("class", "class"),
+            ("interface", "interface"),
+            ("type", "type"),
+            ("enum", "enum"),
+            ("const", "constant"),
+        ],
+        "py" => &[("def", "function"), ("class", "class")],
+        "go" => &[("func", "function"), ("type", "type")],
+        _ => return Vec::new(),
+    };
+
+    const MODIFIERS: &[&str] = &[
+        "pub", "pub(crate)", "pub(super)", "export", "default", "async", "unsafe", "extern",
+        "declare", "abstract",
+    ];
+
+    let mut symbols = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        let indented = trimmed.len() != line.len();
+        let mut tokens = trimmed.split_whitespace().skip_while(|t| MODIFIERS.contains(t));
+
+        let Some(keyword) = tokens.next() else { continue };
+        let Some(&(_, kind)) = keywords.iter().find(|(k, _)| *k == keyword) else { continue };
+
+        // Constants inside functions are noise
+        if kind == "constant" && indented {
+            continue;
+        }
+
+        let mut name_token = tokens.next();
+
+        // Skip Go method receivers: func (s *Server) Name(
+        if keyword == "func" && name_token.is_some_and(|t| t.starts_with('(')) {
+            let mut token = name_token;
+            while let Some(t) = token {
+                if t.ends_with(')') {
+                    break;
+                }
+                token = tokens.next();
+            }
+            name_token = tokens.next();
+        }
+
+        let name: String = name_token
+            .unwrap_or("")
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+            .collect();
+
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            continue;
+        }
+
+        symbols.push(Symbol {
+            name,
+            kind: kind.to_string(),
+            line: index + 1,
+        });
+    }
+
+    symbols
+}
+
+/// Per-project indexes, built lazily and refreshed in the background
+#[derive(Clone, Default)]
+pub struct ProjectIndexer {
+    indexes: Arc<RwLock<HashMap<String, Arc<ProjectIndex>>>>,
+    /// Projects with a build in progress
+    building: Arc<Mutex<HashSet<String>>>,
+}
+
+impl ProjectIndexer {
+    /// Create a new indexer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a project's index, building it first if there is none
+    ///
+    /// Stale indexes are returned as-is while a refresh runs in the background.
+    pub async fn get(&self, project_id: &str, root: PathBuf) -> Result<Arc<ProjectIndex>, AppError> {
+        let existing = self.indexes.read().await.get(project_id).cloned();
+
+        match existing {
+            Some(index) => {
+                if index.built_at.elapsed() > STALE_AFTER {
+                    let indexer = self.clone();
+                    let project_id = project_id.to_string();
+                    tokio::spawn(async move {
+                        if let Err(e) = indexer.rebuild(&project_id, root).await {
+                            log::warn!("Failed to refresh project index: {}", e);
+                        }
+                    });
+                }
+                Ok(index)
+            }
+            None => self.rebuild(project_id, root).await,
+        }
+    }
+
+    /// Rebuild a project's index now
+    pub async fn rebuild(&self, project_id: &str, root: PathBuf) -> Result<Arc<ProjectIndex>, AppError> {
+        if !self.building.lock().await.insert(project_id.to_string()) {
+            // Another build is running; wait for its result rather than duplicating work
+            loop {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                if !self.building.lock().await.contains(project_id) {
+                    break;
+                }
+            }
+            if let Some(index) = self.indexes.read().await.get(project_id).cloned() {
+                return Ok(index);
+            }
+            return Err(AppError::new(ErrorCode::Unknown, "Project indexing failed"));
+        }
+
+        let result = tokio::task::spawn_blocking(move || ProjectIndex::build(&root))
+            .await
+            .map_err(|e| AppError::new(ErrorCode::Unknown, format!("Indexing task failed: {}", e)))
+            .and_then(|r| r);
+
+        let index = match result {
+            Ok(index) => {
+                let index = Arc::new(index);
+                self.indexes.write().await.insert(project_id.to_string(), Arc::clone(&index));
+                log::info!(
+                    "Indexed project: {} files, {} symbols",
+                    index.file_count(),
+                    index.symbol_count()
+                );
+                Ok(index)
+            }
+            Err(e) => Err(e),
+        };
+
+        self.building.lock().await.remove(project_id);
+        index
+    }
+
+    /// Drop a project's index
+    pub async fn remove(&self, project_id: &str) {
+        self.indexes.write().await.remove(project_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_symbols() {
+        let rust = "pub struct AuthMiddleware;\nimpl AuthMiddleware {\n    pub async fn handle(&self) {}\n    const LOCAL: u8 = 1;\n}\npub(crate) const MAX: usize = 3;\n";
+        let names: Vec<_> = extract_symbols("rs", rust).into_iter().map(|s| (s.name, s.line)).collect();
+        assert_eq!(
+            names,
+            vec![("AuthMiddleware".to_string(), 1), ("handle".to_string(), 3), ("MAX".to_string(), 6)]
+        );
+
+        let go = "func (s *Server) ServeHTTP(w http.ResponseWriter) {}\nfunc main() {}\n";
+        let names: Vec<_> = extract_symbols("go", go).into_iter().map(|s| s.name).collect();
+        assert_eq!(names, vec!["ServeHTTP", "main"]);
+
+        let ts = "export default function useAuth() {}\nexport interface Session {}\n";
+        let names: Vec<_> = extract_symbols("ts", ts).into_iter().map(|s| s.name).collect();
+        assert_eq!(names, vec!["useAuth", "Session"]);
+    }
+
+    #[test]
+    fn test_lookup_ranks_path_and_symbol_matches() {
+        let file = |path: &str, symbols: &[&str]| IndexedFile {
+            path: path.to_string(),
+            symbols: symbols
+                .iter()
+                .map(|name| Symbol { name: name.to_string(), kind: "function".to_string(), line: 1 })
+                .collect(),
+        };
+        let index = ProjectIndex {
+            files: vec![
+                file("src/middleware/auth.ts", &["authMiddleware"]),
+                file("src/routes/login.ts", &["checkAuth"]),
+                file("src/utils/format.ts", &["formatDate"]),
+            ],
+            built_at: Instant::now(),
+            indexed_at: String::new(),
+            truncated: false,
+        };
+
+        assert_eq!(query_terms("the authMiddleware"), vec!["auth", "middleware"]);
+
+        let matches = index.lookup("the auth middleware", 10);
+        let paths: Vec<_> = matches.iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/middleware/auth.ts", "src/routes/login.ts"]);
+    }
+}
@@ -0,0 +1,103 @@
+//! IPC Rate Limiter
+//!
+//! Guards the command handler against runaway invocation loops from the
+//! frontend (e.g. an effect re-running on every render), rejecting excess
+//! calls before they reach the database pool.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Length of a rate limiting window
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// Default maximum calls per command per window
+const DEFAULT_IPC_RATE_LIMIT: usize = 50;
+
+/// Per-command fixed-window rate limiter
+#[derive(Clone)]
+pub struct IpcRateLimiter {
+    /// Maximum calls per command per window (0 = unlimited)
+    limit: Arc<AtomicUsize>,
+    /// Window start and call count keyed by command name
+    windows: Arc<Mutex<HashMap<String, (Instant, usize)>>>,
+}
+
+impl IpcRateLimiter {
+    /// Create a new rate limiter
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit: Arc::new(AtomicUsize::new(limit)),
+            windows: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Get the maximum calls per command per second (0 = unlimited)
+    pub fn limit(&self) -> usize {
+        self.limit.load(Ordering::SeqCst)
+    }
+
+    /// Set the maximum calls per command per second (0 = unlimited)
+    pub fn set_limit(&self, limit: usize) {
+        self.limit.store(limit, Ordering::SeqCst);
+    }
+
+    /// Record a call and return whether it is allowed
+    pub fn check(&self, command: &str) -> bool {
+        self.check_at(command, Instant::now())
+    }
+
+    fn check_at(&self, command: &str, now: Instant) -> bool {
+        let limit = self.limit();
+        if limit == 0 {
+            return true;
+        }
+
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        let (started, count) = windows
+            .entry(command.to_string())
+            .or_insert((now, 0));
+
+        if now.duration_since(*started) >= WINDOW {
+            *started = now;
+            *count = 0;
+        }
+
+        *count += 1;
+
+        // Only log the first rejection in each window to avoid flooding the log
+        if *count == limit + 1 {
+            log::warn!("Rate limiting IPC command {} ({} calls/s)", command, limit);
+        }
+
+        *count <= limit
+    }
+}
+
+impl Default for IpcRateLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_IPC_RATE_LIMIT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_per_command_and_window() {
+        let limiter = IpcRateLimiter::new(2);
+        let start = Instant::now();
+
+        assert!(limiter.check_at("dashboard_stats", start));
+        assert!(limiter.check_at("dashboard_stats", start));
+        assert!(!limiter.check_at("dashboard_stats", start));
+
+        // Other commands have their own budget
+        assert!(limiter.check_at("task_get_all", start));
+
+        // The budget resets once the window has passed
+        assert!(limiter.check_at("dashboard_stats", start + WINDOW));
+    }
+}
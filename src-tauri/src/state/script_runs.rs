@@ -0,0 +1,101 @@
+//! Running Project Scripts
+//!
+//! Tracks the script runs in progress so they can be listed and cancelled.
+//! The runs themselves are driven by `commands::script`; this only holds
+//! what each run's process is and the signal that tells a run to kill it.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tokio::sync::oneshot;
+
+/// A script run in progress
+#[derive(Debug, Clone)]
+pub struct RunningScript {
+    pub run_id: String,
+    pub project_id: String,
+    pub pid: Option<u32>,
+    pub cwd: String,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+}
+
+struct Entry {
+    info: RunningScript,
+    cancel: oneshot::Sender<()>,
+}
+
+/// In-progress script runs with their cancel signals, keyed by run ID
+#[derive(Default)]
+pub struct ScriptRuns {
+    running: Mutex<HashMap<String, Entry>>,
+}
+
+impl ScriptRuns {
+    /// Register a run and get the receiver that fires when it is cancelled
+    pub fn start(&self, info: RunningScript) -> oneshot::Receiver<()> {
+        let (tx, rx) = oneshot::channel();
+        self.lock().insert(info.run_id.clone(), Entry { info, cancel: tx });
+        rx
+    }
+
+    /// The runs in progress, oldest first
+    pub fn list(&self) -> Vec<RunningScript> {
+        let mut runs: Vec<RunningScript> = self.lock().values().map(|entry| entry.info.clone()).collect();
+        runs.sort_by_key(|run| run.started_at);
+        runs
+    }
+
+    /// Forget a run that has finished
+    pub fn finish(&self, run_id: &str) {
+        self.lock().remove(run_id);
+    }
+
+    /// Signal a run to stop; returns false if it isn't running
+    pub fn cancel(&self, run_id: &str) -> bool {
+        match self.lock().remove(run_id) {
+            Some(entry) => entry.cancel.send(()).is_ok(),
+            None => false,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Entry>> {
+        self.running.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(run_id: &str) -> RunningScript {
+        RunningScript {
+            run_id: run_id.to_string(),
+            project_id: "p1".to_string(),
+            pid: Some(42),
+            cwd: "/tmp".to_string(),
+            started_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_cancel() {
+        let runs = ScriptRuns::default();
+        let mut rx = runs.start(run("run-1"));
+
+        assert!(runs.cancel("run-1"));
+        assert!(rx.try_recv().is_ok());
+        // A run can only be cancelled once
+        assert!(!runs.cancel("run-1"));
+        assert!(!runs.cancel("unknown"));
+    }
+
+    #[test]
+    fn test_finish() {
+        let runs = ScriptRuns::default();
+        let _rx = runs.start(run("run-1"));
+        assert_eq!(runs.list().len(), 1);
+        runs.finish("run-1");
+        assert!(runs.list().is_empty());
+        assert!(!runs.cancel("run-1"));
+    }
+}
@@ -0,0 +1,76 @@
+//! Startup Status
+//!
+//! Tracks app initialization so the frontend can show an upgrading state
+//! while database migrations run, before the app state exists.
+
+use serde::Serialize;
+use std::sync::RwLock;
+
+use crate::db::MigrationProgress;
+
+/// Stage of app initialization
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StartupPhase {
+    Starting,
+    /// Applying database migrations
+    Migrating,
+    Ready,
+    Failed,
+}
+
+/// Snapshot of app initialization
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupStatus {
+    pub phase: StartupPhase,
+    /// Latest migration progress, if any migrations were pending
+    pub migration: Option<MigrationProgress>,
+    pub error: Option<String>,
+}
+
+/// Initialization status, managed before the app state is ready
+pub struct StartupTracker {
+    status: RwLock<StartupStatus>,
+}
+
+impl StartupTracker {
+    pub fn new() -> Self {
+        Self {
+            status: RwLock::new(StartupStatus {
+                phase: StartupPhase::Starting,
+                migration: None,
+                error: None,
+            }),
+        }
+    }
+
+    /// Get the current status
+    pub fn get(&self) -> StartupStatus {
+        self.status.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Record migration progress
+    pub fn set_migration(&self, progress: MigrationProgress) {
+        let mut status = self.status.write().unwrap_or_else(|e| e.into_inner());
+        status.phase = StartupPhase::Migrating;
+        status.migration = Some(progress);
+    }
+
+    /// Mark initialization finished, successfully or with an error
+    pub fn finish(&self, error: Option<String>) {
+        let mut status = self.status.write().unwrap_or_else(|e| e.into_inner());
+        status.phase = if error.is_some() {
+            StartupPhase::Failed
+        } else {
+            StartupPhase::Ready
+        };
+        status.error = error;
+    }
+}
+
+impl Default for StartupTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
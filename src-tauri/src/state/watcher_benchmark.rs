@@ -0,0 +1,236 @@
+//! File Watcher Benchmark
+//!
+//! Generates synthetic file churn in a scratch directory and measures how the
+//! platform's notify backend delivers it, for tuning the watcher's defaults.
+
+use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::error::{AppError, ErrorCode};
+use super::file_watcher::DEBOUNCE_MS;
+
+/// Time to let the backend register the watch before writing
+const WARMUP_MS: u64 = 250;
+
+/// Quiet period after which no more events are expected
+const SETTLE_MS: u64 = 1000;
+
+/// Event latency summary in milliseconds
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyStats {
+    pub min_ms: f64,
+    pub mean_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Benchmark results for the current platform
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatcherBenchmarkReport {
+    pub platform: String,
+    pub backend: String,
+    pub files_written: usize,
+    pub writes_per_file: usize,
+    pub write_duration_ms: f64,
+    /// Events delivered by the backend for the written files
+    pub raw_events: usize,
+    /// Events the watcher would emit after debouncing
+    pub debounced_events: usize,
+    pub debounce_ms: u64,
+    /// Share of raw events absorbed by debouncing (0-1)
+    pub debounce_reduction: f64,
+    /// Files that were written but never reported
+    pub dropped_files: usize,
+    /// Time from a file's creation to its first event
+    pub latency: LatencyStats,
+}
+
+/// Run the benchmark in a scratch directory under `parent`
+///
+/// Blocks while the churn is generated and settles; call from a blocking task.
+pub fn run_benchmark(
+    parent: &Path,
+    file_count: usize,
+    writes_per_file: usize,
+) -> Result<WatcherBenchmarkReport, AppError> {
+    if !parent.is_dir() {
+        return Err(AppError::directory_not_found(parent.to_string_lossy()));
+    }
+
+    // Canonicalize so paths match what backends like FSEvents report
+    let dir = parent
+        .canonicalize()?
+        .join(format!(".wingman-watch-bench-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir(&dir)?;
+
+    let result = run_in(&dir, file_count, writes_per_file);
+
+    if let Err(e) = std::fs::remove_dir_all(&dir) {
+        log::warn!("Failed to remove watcher benchmark directory: {}", e);
+    }
+
+    result
+}
+
+fn run_in(
+    dir: &Path,
+    file_count: usize,
+    writes_per_file: usize,
+) -> Result<WatcherBenchmarkReport, AppError> {
+    let (tx, rx) = mpsc::channel::<(PathBuf, Instant)>();
+
+    let mut watcher = RecommendedWatcher::new(
+        move |result: Result<Event, notify::Error>| {
+            if let Ok(event) = result {
+                if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+                    let now = Instant::now();
+                    for path in event.paths {
+                        let _ = tx.send((path, now));
+                    }
+                }
+            }
+        },
+        Config::default(),
+    )
+    .map_err(|e| AppError::new(ErrorCode::Unknown, format!("Failed to create watcher: {}", e)))?;
+
+    watcher
+        .watch(dir, RecursiveMode::Recursive)
+        .map_err(|e| AppError::new(ErrorCode::Unknown, format!("Failed to watch directory: {}", e)))?;
+
+    std::thread::sleep(Duration::from_millis(WARMUP_MS));
+
+    // Create each file and rewrite it in a burst, like an editor or formatter
+    let mut created: HashMap<PathBuf, Instant> = HashMap::new();
+    let started = Instant::now();
+    for i in 0..file_count {
+        let path = dir.join(format!("file-{}.txt", i));
+        created.insert(path.clone(), Instant::now());
+        for write in 0..writes_per_file {
+            std::fs::write(&path, format!("write {}\n", write))?;
+        }
+    }
+    let write_duration = started.elapsed();
+
+    // Collect events until the backend goes quiet
+    let mut events: HashMap<PathBuf, Vec<Instant>> = HashMap::new();
+    while let Ok((path, at)) = rx.recv_timeout(Duration::from_millis(SETTLE_MS)) {
+        if created.contains_key(&path) {
+            events.entry(path).or_default().push(at);
+        }
+    }
+    drop(watcher);
+
+    let debounce = Duration::from_millis(DEBOUNCE_MS);
+    let raw_events: usize = events.values().map(Vec::len).sum();
+    let debounced_events: usize = events
+        .values_mut()
+        .map(|times| {
+            times.sort();
+            debounced_count(times, debounce)
+        })
+        .sum();
+
+    let latencies: Vec<f64> = events
+        .iter()
+        .filter_map(|(path, times)| {
+            let created_at = created.get(path)?;
+            let first = times.first()?;
+            Some(first.saturating_duration_since(*created_at).as_secs_f64() * 1000.0)
+        })
+        .collect();
+
+    Ok(WatcherBenchmarkReport {
+        platform: std::env::consts::OS.to_string(),
+        backend: backend_name().to_string(),
+        files_written: file_count,
+        writes_per_file,
+        write_duration_ms: write_duration.as_secs_f64() * 1000.0,
+        raw_events,
+        debounced_events,
+        debounce_ms: DEBOUNCE_MS,
+        debounce_reduction: if raw_events == 0 {
+            0.0
+        } else {
+            1.0 - debounced_events as f64 / raw_events as f64
+        },
+        dropped_files: file_count - events.len(),
+        latency: latency_stats(latencies),
+    })
+}
+
+/// Count the events a trailing debounce would emit for one path's sorted
+/// event times
+fn debounced_count(times: &[Instant], window: Duration) -> usize {
+    if times.is_empty() {
+        return 0;
+    }
+
+    1 + times
+        .windows(2)
+        .filter(|pair| pair[1].duration_since(pair[0]) >= window)
+        .count()
+}
+
+/// Summarize latency samples
+fn latency_stats(mut samples: Vec<f64>) -> LatencyStats {
+    if samples.is_empty() {
+        return LatencyStats::default();
+    }
+
+    samples.sort_by(|a, b| a.total_cmp(b));
+    let p95_index = ((samples.len() as f64 * 0.95).ceil() as usize).saturating_sub(1);
+
+    LatencyStats {
+        min_ms: samples[0],
+        mean_ms: samples.iter().sum::<f64>() / samples.len() as f64,
+        p95_ms: samples[p95_index],
+        max_ms: samples[samples.len() - 1],
+    }
+}
+
+/// Name of the notify backend used on this platform
+fn backend_name() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "fsevents"
+    } else if cfg!(any(target_os = "linux", target_os = "android")) {
+        "inotify"
+    } else if cfg!(target_os = "windows") {
+        "ReadDirectoryChangesW"
+    } else if cfg!(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd", target_os = "dragonfly")) {
+        "kqueue"
+    } else {
+        "poll"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debounced_count_splits_on_quiet_gaps() {
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+        let window = Duration::from_millis(100);
+
+        assert_eq!(debounced_count(&[], window), 0);
+        assert_eq!(debounced_count(&[ms(0), ms(10), ms(20)], window), 1);
+        assert_eq!(debounced_count(&[ms(0), ms(10), ms(200), ms(250), ms(500)], window), 3);
+    }
+
+    #[test]
+    fn test_latency_stats() {
+        let stats = latency_stats((1..=20).map(f64::from).collect());
+        assert_eq!(stats.min_ms, 1.0);
+        assert_eq!(stats.mean_ms, 10.5);
+        assert_eq!(stats.p95_ms, 19.0);
+        assert_eq!(stats.max_ms, 20.0);
+    }
+}
@@ -0,0 +1,155 @@
+//! Login Shell Environment
+//!
+//! GUI apps launched from Finder or a desktop launcher don't inherit the PATH
+//! a terminal gets from the user's shell profile, so tools installed through
+//! nvm, asdf, Homebrew, and the like can't be found. The login shell's
+//! environment is read once at startup and used to find and spawn the CLI.
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+#[cfg(unix)]
+use std::time::Duration;
+
+/// Longest the login shell may take to print its environment
+#[cfg(unix)]
+const SHELL_TIMEOUT_SECS: u64 = 5;
+
+/// Printed before `env` so profile output (banners, MOTDs) can be skipped
+#[cfg(unix)]
+const ENV_MARKER: &str = "__WINGMAN_ENV__";
+
+/// The login shell's environment, once resolved
+static LOGIN_ENV: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// Read and cache the login shell's environment
+///
+/// Does nothing on Windows, where GUI apps get the full user PATH, or when
+/// the shell fails or times out; lookups then use the app's own environment.
+pub async fn init() {
+    if LOGIN_ENV.get().is_some() {
+        return;
+    }
+
+    match read_login_env().await {
+        Some(env) => {
+            log::info!("Loaded {} variables from the login shell environment", env.len());
+            let _ = LOGIN_ENV.set(env);
+        }
+        None => log::debug!("Login shell environment unavailable, using the app environment"),
+    }
+}
+
+/// PATH to search and spawn processes with
+///
+/// The login shell's entries come first, followed by any of the app's own
+/// that it lacks. `None` until the login environment has been read.
+pub fn search_path() -> Option<OsString> {
+    let login_path = LOGIN_ENV.get()?.get("PATH")?;
+    let app_path = std::env::var_os("PATH").unwrap_or_default();
+
+    let mut entries: Vec<PathBuf> = std::env::split_paths(login_path).collect();
+    for entry in std::env::split_paths(&app_path) {
+        if !entries.contains(&entry) {
+            entries.push(entry);
+        }
+    }
+
+    std::env::join_paths(entries).ok()
+}
+
+/// Find an executable in the app's PATH, falling back to the login shell's
+pub fn which(binary: &str) -> Option<PathBuf> {
+    which::which(binary).ok().or_else(|| {
+        let path = search_path()?;
+        let cwd = std::env::current_dir().ok()?;
+        which::which_in(binary, Some(path), cwd).ok()
+    })
+}
+
+/// Give a process the login shell's PATH so tools it runs can be found too
+pub fn apply(cmd: &mut tokio::process::Command) {
+    if let Some(path) = search_path() {
+        cmd.env("PATH", path);
+    }
+}
+
+#[cfg(unix)]
+async fn read_login_env() -> Option<HashMap<String, String>> {
+    let shell = std::env::var("SHELL").ok().filter(|s| !s.is_empty())?;
+
+    let output = tokio::time::timeout(
+        Duration::from_secs(SHELL_TIMEOUT_SECS),
+        tokio::process::Command::new(&shell)
+            .arg("-lic")
+            .arg(format!("echo {}; env", ENV_MARKER))
+            .stdin(std::process::Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await;
+
+    match output {
+        Ok(Ok(output)) if output.status.success() => {
+            let env = parse_env_output(&String::from_utf8_lossy(&output.stdout));
+            Some(env).filter(|env| env.contains_key("PATH"))
+        }
+        Ok(Ok(output)) => {
+            log::warn!("Login shell {} exited with {}", shell, output.status);
+            None
+        }
+        Ok(Err(e)) => {
+            log::warn!("Failed to run login shell {}: {}", shell, e);
+            None
+        }
+        Err(_) => {
+            log::warn!("Login shell {} took longer than {}s to start", shell, SHELL_TIMEOUT_SECS);
+            None
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn read_login_env() -> Option<HashMap<String, String>> {
+    None
+}
+
+/// Parse `env` output printed after the marker line
+///
+/// Lines that aren't `NAME=value` (like the continuation of a multi-line
+/// value) are skipped.
+#[cfg(unix)]
+fn parse_env_output(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .skip_while(|line| line.trim() != ENV_MARKER)
+        .skip(1)
+        .filter_map(|line| {
+            let (name, value) = line.split_once('=')?;
+            let valid_name = !name.is_empty()
+                && !name.starts_with(|c: char| c.is_ascii_digit())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            valid_name.then(|| (name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env_output() {
+        let output = format!(
+            "Welcome back!\nPATH=/not/this\n{}\nPATH=/usr/local/bin:/usr/bin\nHOME=/Users/me\n  continued line\nEMPTY=\n",
+            ENV_MARKER
+        );
+
+        let env = parse_env_output(&output);
+        assert_eq!(env.get("PATH").map(String::as_str), Some("/usr/local/bin:/usr/bin"));
+        assert_eq!(env.get("HOME").map(String::as_str), Some("/Users/me"));
+        assert_eq!(env.get("EMPTY").map(String::as_str), Some(""));
+        assert_eq!(env.len(), 3);
+    }
+}
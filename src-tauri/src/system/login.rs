@@ -0,0 +1,212 @@
+//! CLI Login
+//!
+//! Signing in to the CLI needs an interactive prompt and a browser round
+//! trip, so `claude login` is opened in a terminal window rather than run
+//! in the background. The terminal runs a small script that writes the exit
+//! code to a status file, which is polled to tell the frontend when login
+//! has finished.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_shell::ShellExt;
+
+use crate::error::{AppError, ErrorCode};
+use crate::events::{emit_event, event_names};
+
+/// How long to wait for the user to finish logging in
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// How often the status file is checked
+const STATUS_POLL: Duration = Duration::from_secs(1);
+
+#[cfg(target_os = "macos")]
+const SCRIPT_EXTENSION: &str = "command";
+#[cfg(windows)]
+const SCRIPT_EXTENSION: &str = "cmd";
+#[cfg(all(unix, not(target_os = "macos")))]
+const SCRIPT_EXTENSION: &str = "sh";
+
+/// Terminal emulators tried on Linux, with the flag that precedes the command
+#[cfg(all(unix, not(target_os = "macos")))]
+const LINUX_TERMINALS: &[(&str, &str)] = &[
+    ("x-terminal-emulator", "-e"),
+    ("gnome-terminal", "--"),
+    ("konsole", "-e"),
+    ("xfce4-terminal", "-x"),
+    ("xterm", "-e"),
+];
+
+/// Outcome of a login, sent as the `cli_login_finished` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliLoginPayload {
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub error: Option<String>,
+}
+
+/// Directory the login script and status file are written to
+pub fn work_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("login")
+}
+
+/// Open a terminal running `claude login`
+///
+/// Returns once the terminal is open; `cli_login_finished` is emitted when
+/// the login command exits or the wait times out.
+pub async fn start(app: &AppHandle, claude_path: &Path, work_dir: &Path) -> Result<(), AppError> {
+    tokio::fs::create_dir_all(work_dir).await?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let status_file = work_dir.join(format!("login-{}.status", id));
+    let script = work_dir.join(format!("login-{}.{}", id, SCRIPT_EXTENSION));
+    tokio::fs::write(&script, login_script(claude_path, &status_file)).await?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        tokio::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).await?;
+    }
+
+    let (program, args) = terminal_command(&script)?;
+    let (_events, _child) = app
+        .shell()
+        .command(program)
+        .args(args)
+        .spawn()
+        .map_err(|e| {
+            AppError::with_details(
+                ErrorCode::ClaudeCliError,
+                "Failed to open a terminal for login",
+                e.to_string(),
+            )
+        })?;
+
+    let app = app.clone();
+    tokio::spawn(async move {
+        let payload = wait_for_status(&status_file).await;
+        let _ = tokio::fs::remove_file(&status_file).await;
+        let _ = tokio::fs::remove_file(&script).await;
+        let _ = emit_event(&app, event_names::CLI_LOGIN_FINISHED, payload);
+    });
+
+    Ok(())
+}
+
+/// Poll for the exit code the login script writes
+async fn wait_for_status(status_file: &Path) -> CliLoginPayload {
+    let deadline = tokio::time::Instant::now() + LOGIN_TIMEOUT;
+
+    while tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(STATUS_POLL).await;
+
+        // The file may exist before the code is written to it
+        let Ok(contents) = tokio::fs::read_to_string(status_file).await else {
+            continue;
+        };
+        let contents = contents.trim();
+        if contents.is_empty() {
+            continue;
+        }
+
+        let exit_code = contents.parse::<i32>().ok();
+        return CliLoginPayload {
+            success: exit_code == Some(0),
+            exit_code,
+            error: (exit_code != Some(0)).then(|| "claude login did not complete".to_string()),
+        };
+    }
+
+    CliLoginPayload {
+        success: false,
+        exit_code: None,
+        error: Some("Timed out waiting for login to finish".to_string()),
+    }
+}
+
+/// Script run in the terminal
+#[cfg(unix)]
+fn login_script(claude_path: &Path, status_file: &Path) -> String {
+    format!(
+        "#!/bin/sh\n{} login\necho $? > {}\nprintf '\\nYou can close this window.\\n'\n",
+        sh_quote(&claude_path.to_string_lossy()),
+        sh_quote(&status_file.to_string_lossy()),
+    )
+}
+
+/// Script run in the terminal
+#[cfg(windows)]
+fn login_script(claude_path: &Path, status_file: &Path) -> String {
+    format!(
+        "@echo off\r\n\"{}\" login\r\necho %ERRORLEVEL%> \"{}\"\r\npause\r\n",
+        claude_path.display(),
+        status_file.display(),
+    )
+}
+
+/// Quote a string for a POSIX shell
+#[cfg(unix)]
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Program and arguments that open a terminal running the script
+#[cfg(target_os = "macos")]
+fn terminal_command(script: &Path) -> Result<(String, Vec<String>), AppError> {
+    // .command files open in Terminal
+    Ok((
+        "open".to_string(),
+        vec!["-a".to_string(), "Terminal".to_string(), script.to_string_lossy().to_string()],
+    ))
+}
+
+/// Program and arguments that open a terminal running the script
+#[cfg(windows)]
+fn terminal_command(script: &Path) -> Result<(String, Vec<String>), AppError> {
+    // The empty argument is the new window's title
+    Ok((
+        "cmd".to_string(),
+        vec![
+            "/C".to_string(),
+            "start".to_string(),
+            String::new(),
+            "cmd".to_string(),
+            "/C".to_string(),
+            script.to_string_lossy().to_string(),
+        ],
+    ))
+}
+
+/// Program and arguments that open a terminal running the script
+#[cfg(all(unix, not(target_os = "macos")))]
+fn terminal_command(script: &Path) -> Result<(String, Vec<String>), AppError> {
+    let (terminal, flag) = LINUX_TERMINALS
+        .iter()
+        .find(|(terminal, _)| which::which(terminal).is_ok())
+        .ok_or_else(|| {
+            AppError::with_details(
+                ErrorCode::ClaudeCliError,
+                "No terminal emulator found",
+                "Run `claude login` in a terminal to sign in",
+            )
+        })?;
+
+    Ok((
+        terminal.to_string(),
+        vec![flag.to_string(), "sh".to_string(), script.to_string_lossy().to_string()],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_login_script() {
+        let script = login_script(Path::new("/opt/it's/claude"), Path::new("/data/login/x.status"));
+        assert!(script.starts_with("#!/bin/sh\n'/opt/it'\\''s/claude' login\n"));
+        assert!(script.contains("echo $? > '/data/login/x.status'\n"));
+    }
+}
@@ -0,0 +1,6 @@
+//! System Integration Module
+//!
+//! Helpers for fitting in with the user's machine outside the app itself.
+
+pub mod env;
+pub mod login;
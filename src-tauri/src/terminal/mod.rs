@@ -0,0 +1,327 @@
+//! Session Terminals
+//!
+//! Interactive shells running in a pseudo-terminal, opened in a session's
+//! working directory so commands Claude suggests can be run without leaving
+//! the app. Output is streamed to the frontend as `terminal_output` events
+//! and input is written straight to the PTY, so the frontend can drive it
+//! with a terminal emulator widget.
+//!
+//! PTY reads and writes are blocking, so each terminal has a reader thread
+//! and writes happen on the blocking pool.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use portable_pty::{native_pty_system, Child, ChildKiller, CommandBuilder, MasterPty, PtySize};
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::error::{AppError, ErrorCode};
+use crate::events::{emit_event, event_names, TerminalOutputPayload};
+use crate::system;
+
+/// Size used when the frontend doesn't give one
+const DEFAULT_COLS: u16 = 80;
+const DEFAULT_ROWS: u16 = 24;
+
+/// Bytes read from the PTY at a time
+const READ_BUFFER_BYTES: usize = 8 * 1024;
+
+/// An open terminal
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalInfo {
+    pub id: String,
+    pub session_id: String,
+    pub shell: String,
+    pub working_directory: String,
+    pub pid: Option<u32>,
+    pub created_at: String,
+}
+
+struct Terminal {
+    info: TerminalInfo,
+    master: Box<dyn MasterPty + Send>,
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    child: Box<dyn Child + Send + Sync>,
+}
+
+/// Manages the open terminals
+pub struct TerminalManager {
+    terminals: Arc<Mutex<HashMap<String, Terminal>>>,
+}
+
+impl TerminalManager {
+    pub fn new() -> Self {
+        Self {
+            terminals: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Open a shell for a session in its working directory
+    pub fn create(
+        &self,
+        app: &AppHandle,
+        session_id: &str,
+        working_dir: &Path,
+        cols: Option<u16>,
+        rows: Option<u16>,
+    ) -> Result<TerminalInfo, AppError> {
+        if !working_dir.is_dir() {
+            return Err(AppError::new(
+                ErrorCode::DirectoryNotFound,
+                format!("Directory not found: {}", working_dir.display()),
+            ));
+        }
+
+        let pair = native_pty_system()
+            .openpty(pty_size(cols, rows))
+            .map_err(|e| terminal_error("Failed to open a terminal", e))?;
+
+        let shell = default_shell();
+        let mut cmd = CommandBuilder::new(&shell);
+        cmd.cwd(working_dir);
+        cmd.env("TERM", "xterm-256color");
+        if let Some(path) = system::env::search_path() {
+            cmd.env("PATH", path);
+        }
+
+        let child = pair
+            .slave
+            .spawn_command(cmd)
+            .map_err(|e| terminal_error("Failed to start the shell", e))?;
+        // The shell holds its own handle; keeping ours would stop EOF reaching the reader
+        drop(pair.slave);
+
+        let reader = pair
+            .master
+            .try_clone_reader()
+            .map_err(|e| terminal_error("Failed to read from the terminal", e))?;
+        let writer = pair
+            .master
+            .take_writer()
+            .map_err(|e| terminal_error("Failed to write to the terminal", e))?;
+
+        let info = TerminalInfo {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: session_id.to_string(),
+            shell,
+            working_directory: working_dir.to_string_lossy().to_string(),
+            pid: child.process_id(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        self.lock().insert(
+            info.id.clone(),
+            Terminal {
+                info: info.clone(),
+                master: pair.master,
+                writer: Arc::new(Mutex::new(writer)),
+                child,
+            },
+        );
+
+        let app = app.clone();
+        let terminals = self.terminals.clone();
+        let terminal_id = info.id.clone();
+        let session_id = session_id.to_string();
+        std::thread::spawn(move || stream_output(app, terminals, terminal_id, session_id, reader));
+
+        Ok(info)
+    }
+
+    /// Send input to a terminal
+    pub async fn write(&self, terminal_id: &str, data: &str) -> Result<(), AppError> {
+        let writer = self.get(terminal_id, |t| t.writer.clone())?;
+        let data = data.as_bytes().to_vec();
+
+        tokio::task::spawn_blocking(move || {
+            let mut writer = writer.lock().unwrap_or_else(|e| e.into_inner());
+            writer.write_all(&data)?;
+            writer.flush()
+        })
+        .await
+        .map_err(|e| terminal_error("Failed to write to the terminal", e))??;
+
+        Ok(())
+    }
+
+    /// Resize a terminal to match its view
+    pub fn resize(&self, terminal_id: &str, cols: u16, rows: u16) -> Result<(), AppError> {
+        if cols == 0 || rows == 0 {
+            return Err(AppError::invalid_input("Terminal size must be at least 1x1"));
+        }
+
+        let terminals = self.lock();
+        let terminal = terminals
+            .get(terminal_id)
+            .ok_or_else(|| not_found(terminal_id))?;
+        terminal
+            .master
+            .resize(pty_size(Some(cols), Some(rows)))
+            .map_err(|e| terminal_error("Failed to resize the terminal", e))
+    }
+
+    /// Kill a terminal's shell and close it
+    pub fn kill(&self, terminal_id: &str) -> Result<(), AppError> {
+        let mut terminal = self.lock().remove(terminal_id).ok_or_else(|| not_found(terminal_id))?;
+        if let Err(e) = terminal.child.kill() {
+            log::warn!("Failed to kill terminal {}: {}", terminal_id, e);
+        }
+        // Reap the shell without holding up the caller
+        std::thread::spawn(move || {
+            let _ = terminal.child.wait();
+        });
+        Ok(())
+    }
+
+    /// Close every terminal belonging to a session
+    pub fn kill_session(&self, session_id: &str) {
+        let ids: Vec<String> = self
+            .lock()
+            .values()
+            .filter(|t| t.info.session_id == session_id)
+            .map(|t| t.info.id.clone())
+            .collect();
+        for id in ids {
+            let _ = self.kill(&id);
+        }
+    }
+
+    /// List the open terminals, optionally only a session's
+    pub fn list(&self, session_id: Option<&str>) -> Vec<TerminalInfo> {
+        let mut terminals: Vec<TerminalInfo> = self
+            .lock()
+            .values()
+            .filter(|t| session_id.is_none_or(|id| t.info.session_id == id))
+            .map(|t| t.info.clone())
+            .collect();
+        terminals.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        terminals
+    }
+
+    fn get<T>(&self, terminal_id: &str, f: impl FnOnce(&Terminal) -> T) -> Result<T, AppError> {
+        self.lock().get(terminal_id).map(f).ok_or_else(|| not_found(terminal_id))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Terminal>> {
+        self.terminals.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for TerminalManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Forward PTY output until the shell exits, then report the exit
+fn stream_output(
+    app: AppHandle,
+    terminals: Arc<Mutex<HashMap<String, Terminal>>>,
+    terminal_id: String,
+    session_id: String,
+    mut reader: Box<dyn Read + Send>,
+) {
+    let emit = |data: String, exited: bool, exit_code: Option<u32>| {
+        let _ = emit_event(
+            &app,
+            event_names::TERMINAL_OUTPUT,
+            TerminalOutputPayload {
+                terminal_id: terminal_id.clone(),
+                session_id: session_id.clone(),
+                data,
+                exited,
+                exit_code,
+            },
+        );
+    };
+
+    let mut buffer = [0u8; READ_BUFFER_BYTES];
+    // Bytes of a character split across reads
+    let mut pending: Vec<u8> = Vec::new();
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => {
+                pending.extend_from_slice(&buffer[..n]);
+                let complete = utf8_prefix_len(&pending);
+                if complete > 0 {
+                    let text = String::from_utf8_lossy(&pending[..complete]).to_string();
+                    pending.drain(..complete);
+                    emit(text, false, None);
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(_) => break,
+        }
+    }
+
+    let terminal = terminals
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&terminal_id);
+    let exit_code = terminal.and_then(|mut t| t.child.wait().ok()).map(|status| status.exit_code());
+    emit(String::from_utf8_lossy(&pending).to_string(), true, exit_code);
+}
+
+/// Length of `bytes` up to any incomplete character at the end
+///
+/// Invalid bytes elsewhere count as complete; they become replacement
+/// characters rather than being held back.
+fn utf8_prefix_len(bytes: &[u8]) -> usize {
+    match std::str::from_utf8(bytes) {
+        Ok(_) => bytes.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => bytes.len(),
+    }
+}
+
+fn pty_size(cols: Option<u16>, rows: Option<u16>) -> PtySize {
+    PtySize {
+        rows: rows.unwrap_or(DEFAULT_ROWS),
+        cols: cols.unwrap_or(DEFAULT_COLS),
+        pixel_width: 0,
+        pixel_height: 0,
+    }
+}
+
+/// The user's shell
+fn default_shell() -> String {
+    #[cfg(windows)]
+    {
+        std::env::var("COMSPEC").unwrap_or_else(|_| "cmd.exe".to_string())
+    }
+    #[cfg(not(windows))]
+    {
+        std::env::var("SHELL")
+            .ok()
+            .filter(|shell| !shell.is_empty())
+            .unwrap_or_else(|| "/bin/sh".to_string())
+    }
+}
+
+fn not_found(terminal_id: &str) -> AppError {
+    AppError::new(ErrorCode::NotFound, format!("Terminal not found: {}", terminal_id))
+}
+
+fn terminal_error(message: &str, error: impl std::fmt::Display) -> AppError {
+    AppError::with_details(ErrorCode::TerminalError, message, error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf8_prefix_len() {
+        assert_eq!(utf8_prefix_len(b"ls -la\r\n"), 8);
+        // The first two bytes of a three-byte character wait for the rest
+        assert_eq!(utf8_prefix_len(&[b'a', 0xE2, 0x82]), 1);
+        assert_eq!(utf8_prefix_len(&[b'a', 0xE2, 0x82, 0xAC]), 4);
+        // Invalid bytes aren't held back
+        assert_eq!(utf8_prefix_len(&[b'a', 0xFF, b'b']), 3);
+    }
+}
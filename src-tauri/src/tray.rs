@@ -0,0 +1,238 @@
+//! System Tray
+//!
+//! A tray icon listing the running Claude sessions and whether each is busy,
+//! with quick actions that work while the main window is closed: open a
+//! running or the most recent session, stop every CLI, and pause the file
+//! watchers. The menu is rebuilt whenever a CLI process starts, exits, or
+//! turns busy or ready.
+
+use std::time::Duration;
+
+use tauri::menu::{Menu, MenuBuilder, MenuEvent, MenuItemBuilder};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Manager};
+
+use crate::commands;
+use crate::deep_link::{self, DeepLink};
+use crate::error::AppError;
+use crate::state::{AppState, ClaudeStatus};
+
+/// ID of the app's tray icon
+pub const TRAY_ID: &str = "main";
+
+/// Running sessions listed in the menu; the rest are only counted
+const MAX_LISTED_SESSIONS: usize = 10;
+
+/// Quiet period before rebuilding the menu, so a burst of changes rebuilds it once
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(200);
+
+const OPEN_APP: &str = "open_app";
+const OPEN_LAST_SESSION: &str = "open_last_session";
+const STOP_ALL: &str = "stop_all";
+const TOGGLE_WATCHERS: &str = "toggle_watchers";
+const QUIT: &str = "quit";
+/// Prefix of the items opening a running session, followed by its ID
+const SESSION_ITEM_PREFIX: &str = "session:";
+
+/// Add the tray icon, with a placeholder menu until the app state is ready
+pub fn init(app: &AppHandle) -> Result<(), AppError> {
+    let menu = MenuBuilder::new(app)
+        .item(&MenuItemBuilder::new("Starting…").enabled(false).build(app)?)
+        .separator()
+        .text(OPEN_APP, "Open Wingman")
+        .text(QUIT, "Quit Wingman")
+        .build()?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Wingman")
+        .menu(&menu)
+        .on_menu_event(on_menu_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+/// Keep the tray menu in step with the CLI processes
+///
+/// Call once the app state is managed.
+pub fn spawn_tray_updates(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut changes = app.state::<AppState>().cli_manager.subscribe_status();
+        loop {
+            if let Err(e) = refresh(&app).await {
+                log::warn!("Failed to update the tray menu: {}", e);
+            }
+            if changes.changed().await.is_err() {
+                break;
+            }
+            tokio::time::sleep(UPDATE_DEBOUNCE).await;
+            changes.borrow_and_update();
+        }
+    });
+}
+
+/// Rebuild the tray menu from the current processes
+async fn refresh(app: &AppHandle) -> Result<(), AppError> {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return Ok(());
+    };
+    let menu = build_menu(app).await?;
+    tray.set_menu(Some(menu))?;
+    Ok(())
+}
+
+async fn build_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, AppError> {
+    let state = app.state::<AppState>();
+    let processes = state.cli_manager.list_processes().await;
+    let busy = processes.iter().filter(|p| p.status == ClaudeStatus::Busy).count();
+    let paused = state.file_watcher.is_paused();
+
+    let mut menu = MenuBuilder::new(app)
+        .item(&MenuItemBuilder::new(status_line(processes.len(), busy)).enabled(false).build(app)?);
+
+    for process in processes.iter().take(MAX_LISTED_SESSIONS) {
+        let title: Option<String> = sqlx::query_scalar("SELECT title FROM sessions WHERE id = ?")
+            .bind(&process.session_id)
+            .fetch_optional(&state.db)
+            .await?;
+        let title = title.unwrap_or_else(|| process.session_id.clone());
+        menu = menu.text(
+            format!("{}{}", SESSION_ITEM_PREFIX, process.session_id),
+            session_label(&title, &process.status),
+        );
+    }
+    if processes.len() > MAX_LISTED_SESSIONS {
+        menu = menu.item(
+            &MenuItemBuilder::new(format!("and {} more", processes.len() - MAX_LISTED_SESSIONS))
+                .enabled(false)
+                .build(app)?,
+        );
+    }
+
+    let stop_all = MenuItemBuilder::with_id(STOP_ALL, "Stop All Sessions")
+        .enabled(!processes.is_empty())
+        .build(app)?;
+    let watchers = if paused { "Resume File Watchers" } else { "Pause File Watchers" };
+
+    let menu = menu
+        .separator()
+        .text(OPEN_APP, "Open Wingman")
+        .text(OPEN_LAST_SESSION, "Open Last Session")
+        .separator()
+        .item(&stop_all)
+        .text(TOGGLE_WATCHERS, watchers)
+        .separator()
+        .text(QUIT, "Quit Wingman")
+        .build()?;
+    Ok(menu)
+}
+
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+    let id = event.id().as_ref().to_string();
+    match id.as_str() {
+        OPEN_APP => deep_link::focus_main_window(app),
+        QUIT => app.exit(0),
+        _ => {
+            // Everything else needs the app state
+            if app.try_state::<AppState>().is_none() {
+                return;
+            }
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = run_action(&app, &id).await {
+                    log::warn!("Tray action {} failed: {}", id, e);
+                }
+            });
+        }
+    }
+}
+
+async fn run_action(app: &AppHandle, id: &str) -> Result<(), AppError> {
+    let state = app.state::<AppState>();
+
+    if let Some(session_id) = id.strip_prefix(SESSION_ITEM_PREFIX) {
+        open_session(app, session_id);
+        return Ok(());
+    }
+
+    match id {
+        OPEN_LAST_SESSION => {
+            let last: Option<String> = sqlx::query_scalar(
+                "SELECT id FROM sessions WHERE archived_at IS NULL ORDER BY updated_at DESC LIMIT 1",
+            )
+            .fetch_optional(&state.db)
+            .await?;
+            match last {
+                Some(session_id) => open_session(app, &session_id),
+                None => deep_link::focus_main_window(app),
+            }
+        }
+        STOP_ALL => {
+            for process in state.cli_manager.list_processes().await {
+                if let Err(e) = commands::session_stop_cli(app.clone(), app.state(), process.session_id.clone()).await {
+                    log::warn!("Failed to stop the CLI for session {}: {}", process.session_id, e);
+                }
+            }
+        }
+        TOGGLE_WATCHERS => {
+            state.file_watcher.set_paused(!state.file_watcher.is_paused());
+            // Not a CLI change, so the menu isn't rebuilt on its own
+            refresh(app).await?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Open a session the same way a `wingman://session/<id>` link does
+fn open_session(app: &AppHandle, session_id: &str) {
+    let link = DeepLink::OpenSession {
+        session_id: session_id.to_string(),
+    };
+    deep_link::spawn_handle(app, link.to_url());
+}
+
+/// Summary shown at the top of the menu
+fn status_line(running: usize, busy: usize) -> String {
+    match (running, busy) {
+        (0, _) => "No sessions running".to_string(),
+        (1, 0) => "1 session running".to_string(),
+        (running, 0) => format!("{} sessions running", running),
+        (1, _) => "1 session running, busy".to_string(),
+        (running, busy) => format!("{} sessions running, {} busy", running, busy),
+    }
+}
+
+/// Menu label for a running session
+fn session_label(title: &str, status: &ClaudeStatus) -> String {
+    let state = match status {
+        ClaudeStatus::Busy => "busy",
+        ClaudeStatus::Ready => "ready",
+        ClaudeStatus::Queued => "queued",
+        ClaudeStatus::Starting => "starting",
+        ClaudeStatus::Stopped => "stopped",
+        ClaudeStatus::Error => "error",
+    };
+    format!("{} ({})", title, state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_line() {
+        assert_eq!(status_line(0, 0), "No sessions running");
+        assert_eq!(status_line(1, 0), "1 session running");
+        assert_eq!(status_line(1, 1), "1 session running, busy");
+        assert_eq!(status_line(3, 2), "3 sessions running, 2 busy");
+    }
+
+    #[test]
+    fn test_session_label() {
+        assert_eq!(session_label("Fix login", &ClaudeStatus::Busy), "Fix login (busy)");
+        assert_eq!(session_label("Fix login", &ClaudeStatus::Ready), "Fix login (ready)");
+    }
+}
@@ -0,0 +1,566 @@
+//! Webhooks
+//!
+//! POSTs a JSON description of project events to user-configured URLs, so
+//! they can be piped into Slack, n8n, and the like. A webhook without a
+//! project receives events from every project. Payloads include a
+//! `wingman://` `link` to what the event is about (see [`crate::deep_link`]).
+//!
+//! Each delivery carries `X-Wingman-Event` and `X-Wingman-Delivery` headers,
+//! and, when the webhook has a secret, an `X-Wingman-Signature` header of
+//! `sha256=` followed by the hex HMAC-SHA256 of the body. Secrets are kept in
+//! the OS keychain, not the database. Deliveries that fail
+//! with a network error, a 429, or a 5xx are retried with backoff; the
+//! outcome of the last attempt is stored on the webhook, and every attempt
+//! is kept in `webhook_deliveries` so it can be inspected and resent.
+
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use reqwest::StatusCode;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager};
+
+use crate::deep_link::DeepLink;
+use crate::error::{AppError, ErrorCode};
+use crate::secrets;
+use crate::state::AppState;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Waits before each retry of a failed delivery
+const RETRY_DELAYS: [Duration; 3] = [Duration::from_secs(2), Duration::from_secs(10), Duration::from_secs(60)];
+
+/// Bytes of a response body kept with each attempt
+const MAX_RESPONSE_BODY: usize = 4096;
+
+/// Attempts kept per webhook; older ones are deleted
+const MAX_ATTEMPTS_KEPT: i64 = 200;
+
+/// Events webhooks can be sent for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WebhookEvent {
+    TaskCompleted,
+    SprintCompleted,
+    /// A session's CLI stopped or exited cleanly
+    SessionFinished,
+    /// Claude reported an error or its CLI exited unexpectedly
+    ClaudeError,
+    /// Sent by `webhook_test`; webhooks don't subscribe to it
+    Ping,
+}
+
+impl WebhookEvent {
+    /// Events a webhook can subscribe to
+    pub const ALL: [WebhookEvent; 4] = [
+        WebhookEvent::TaskCompleted,
+        WebhookEvent::SprintCompleted,
+        WebhookEvent::SessionFinished,
+        WebhookEvent::ClaudeError,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::TaskCompleted => "task_completed",
+            WebhookEvent::SprintCompleted => "sprint_completed",
+            WebhookEvent::SessionFinished => "session_finished",
+            WebhookEvent::ClaudeError => "claude_error",
+            WebhookEvent::Ping => "ping",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|event| event.as_str() == value)
+    }
+
+    /// Like `parse`, but also accepts `ping`, for events read back from the delivery history
+    pub fn parse_sent(value: &str) -> Option<Self> {
+        if value == WebhookEvent::Ping.as_str() {
+            return Some(WebhookEvent::Ping);
+        }
+        Self::parse(value)
+    }
+}
+
+/// What an event is about; unset fields don't apply to the event
+#[derive(Debug, Clone, Default)]
+pub struct WebhookContext {
+    pub project_id: Option<String>,
+    pub session_id: Option<String>,
+    pub task_id: Option<String>,
+    pub sprint_id: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Outcome of delivering an event to one webhook
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Delivery {
+    pub delivery_id: String,
+    pub success: bool,
+    /// Status of the last response, if any arrived
+    pub status_code: Option<u16>,
+    pub attempts: usize,
+    pub error: Option<String>,
+}
+
+/// One attempt at a delivery, as kept in the history
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryAttempt {
+    pub delivery_id: String,
+    pub webhook_id: String,
+    pub event: String,
+    /// The JSON body sent
+    pub payload: Value,
+    /// 1 for the first try
+    pub attempt: i64,
+    pub success: bool,
+    pub status_code: Option<i64>,
+    /// Start of the response body, if a response arrived
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+    pub created_at: String,
+}
+
+impl Delivery {
+    /// A delivery that couldn't be attempted
+    fn failed(error: String) -> Self {
+        Self {
+            delivery_id: uuid::Uuid::new_v4().to_string(),
+            success: false,
+            status_code: None,
+            attempts: 0,
+            error: Some(error),
+        }
+    }
+}
+
+/// Send an event to the webhooks subscribed to it, in the background
+///
+/// The project is looked up from the session when the context doesn't name
+/// one. Does nothing before the app state is ready or in safe mode.
+pub fn dispatch(app: &AppHandle, event: WebhookEvent, context: WebhookContext) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = dispatch_event(&app, event, context).await {
+            log::warn!("Failed to send {} webhooks: {}", event.as_str(), e);
+        }
+    });
+}
+
+async fn dispatch_event(app: &AppHandle, event: WebhookEvent, context: WebhookContext) -> Result<(), AppError> {
+    let Some(state) = app.try_state::<AppState>() else {
+        return Ok(());
+    };
+    // Safe mode runs without background services, webhooks included
+    if state.safe_mode {
+        return Ok(());
+    }
+    let db = &state.db;
+
+    let payload = build_payload(db, event, context).await?;
+    let project_id = payload["projectId"].as_str().map(str::to_string);
+
+    let webhooks = sqlx::query_as::<_, (String, String, bool)>(
+        r#"
+        SELECT w.id, w.url, w.has_secret
+        FROM webhooks w, json_each(w.events) e
+        WHERE w.enabled = 1 AND e.value = ?1 AND (w.project_id IS NULL OR w.project_id = ?2)
+        "#,
+    )
+    .bind(event.as_str())
+    .bind(&project_id)
+    .fetch_all(db)
+    .await?;
+
+    // Slow endpoints shouldn't hold up the others
+    for (id, url, has_secret) in webhooks {
+        let db = db.clone();
+        let payload = payload.clone();
+        tauri::async_runtime::spawn(async move {
+            let delivery = match signing_secret(&id, has_secret).await {
+                Ok(secret) => deliver(&db, &id, &url, secret.as_deref(), event, &payload, &RETRY_DELAYS).await,
+                Err(e) => Delivery::failed(e.to_string()),
+            };
+            if let Err(e) = record_delivery(&db, &id, &delivery).await {
+                log::warn!("Failed to record delivery for webhook {}: {}", id, e);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// A webhook's signing secret from the keychain
+///
+/// A webhook meant to be signed is never sent unsigned, so a missing
+/// secret is an error.
+pub(crate) async fn signing_secret(webhook_id: &str, has_secret: bool) -> Result<Option<String>, AppError> {
+    if !has_secret {
+        return Ok(None);
+    }
+    secrets::get(&secrets::webhook_secret_name(webhook_id))
+        .await?
+        .map(Some)
+        .ok_or_else(|| AppError::invalid_input("The webhook's signing secret is missing from the keychain"))
+}
+
+/// Move signing secrets still in the database into the keychain, in the background
+pub fn spawn_secret_migration(db: SqlitePool) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = move_secrets_to_keychain(&db).await {
+            log::warn!("Failed to move webhook secrets to the keychain: {}", e);
+        }
+    });
+}
+
+async fn move_secrets_to_keychain(db: &SqlitePool) -> Result<(), AppError> {
+    let webhooks = sqlx::query_as::<_, (String, String)>("SELECT id, secret FROM webhooks WHERE secret IS NOT NULL")
+        .fetch_all(db)
+        .await?;
+
+    for (id, secret) in webhooks {
+        // Stays in the database until the keychain has it
+        secrets::set(&secrets::webhook_secret_name(&id), &secret).await?;
+        sqlx::query("UPDATE webhooks SET secret = NULL, has_secret = 1 WHERE id = ?")
+            .bind(&id)
+            .execute(db)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Describe an event for a webhook body
+///
+/// Besides the raw fields, `text` has a one-line summary, which Slack
+/// incoming webhooks show as the message.
+pub(crate) async fn build_payload(
+    db: &SqlitePool,
+    event: WebhookEvent,
+    mut context: WebhookContext,
+) -> Result<Value, AppError> {
+    let mut session_title = None;
+    if let Some(session_id) = &context.session_id {
+        if let Some((project_id, title)) =
+            sqlx::query_as::<_, (Option<String>, Option<String>)>("SELECT project_id, title FROM sessions WHERE id = ?")
+                .bind(session_id)
+                .fetch_optional(db)
+                .await?
+        {
+            context.project_id = context.project_id.or(project_id);
+            session_title = title;
+        }
+    }
+
+    let project_name: Option<String> = match &context.project_id {
+        Some(project_id) => sqlx::query_scalar("SELECT name FROM projects WHERE id = ?")
+            .bind(project_id)
+            .fetch_optional(db)
+            .await?,
+        None => None,
+    };
+    let task_title: Option<String> = match &context.task_id {
+        Some(task_id) => sqlx::query_scalar("SELECT title FROM tasks WHERE id = ?")
+            .bind(task_id)
+            .fetch_optional(db)
+            .await?,
+        None => None,
+    };
+    let sprint_name: Option<String> = match &context.sprint_id {
+        Some(sprint_id) => sqlx::query_scalar("SELECT name FROM sprints WHERE id = ?")
+            .bind(sprint_id)
+            .fetch_optional(db)
+            .await?,
+        None => None,
+    };
+
+    let summary = match event {
+        WebhookEvent::TaskCompleted => format!("Task completed: {}", task_title.as_deref().unwrap_or("(untitled)")),
+        WebhookEvent::SprintCompleted => {
+            format!("Sprint completed: {}", sprint_name.as_deref().unwrap_or("(unnamed)"))
+        }
+        WebhookEvent::SessionFinished => {
+            format!("Session finished: {}", session_title.as_deref().unwrap_or("(untitled)"))
+        }
+        WebhookEvent::ClaudeError => {
+            format!("Claude error: {}", context.error.as_deref().unwrap_or("unknown error"))
+        }
+        WebhookEvent::Ping => "Wingman webhook test".to_string(),
+    };
+    let text = match &project_name {
+        Some(project_name) => format!("[{}] {}", project_name, summary),
+        None => summary,
+    };
+
+    // Opens the most specific thing the event is about in the app
+    let link = match (&context.project_id, &context.task_id, &context.session_id) {
+        (Some(project_id), Some(task_id), _) => Some(DeepLink::OpenTask {
+            project_id: project_id.clone(),
+            task_id: task_id.clone(),
+        }),
+        (_, _, Some(session_id)) => Some(DeepLink::OpenSession {
+            session_id: session_id.clone(),
+        }),
+        (Some(project_id), _, _) => Some(DeepLink::OpenProject {
+            project_id: project_id.clone(),
+        }),
+        _ => None,
+    };
+
+    Ok(json!({
+        "event": event.as_str(),
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "text": text,
+        "projectId": context.project_id,
+        "projectName": project_name,
+        "sessionId": context.session_id,
+        "sessionTitle": session_title,
+        "taskId": context.task_id,
+        "taskTitle": task_title,
+        "sprintId": context.sprint_id,
+        "sprintName": sprint_name,
+        "error": context.error,
+        "link": link.map(|link| link.to_url().to_string()),
+    }))
+}
+
+/// POST a payload, retrying after each of `retry_delays` while it fails with a retryable error
+///
+/// Each attempt is added to the webhook's delivery history.
+pub(crate) async fn deliver(
+    db: &SqlitePool,
+    webhook_id: &str,
+    url: &str,
+    secret: Option<&str>,
+    event: WebhookEvent,
+    payload: &Value,
+    retry_delays: &[Duration],
+) -> Delivery {
+    let delivery_id = uuid::Uuid::new_v4().to_string();
+    let body = payload.to_string();
+    let mut delivery = Delivery {
+        delivery_id: delivery_id.clone(),
+        success: false,
+        status_code: None,
+        attempts: 0,
+        error: None,
+    };
+
+    let http = match reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(concat!("Wingman/", env!("CARGO_PKG_VERSION")))
+        .build()
+    {
+        Ok(http) => http,
+        Err(e) => {
+            delivery.error = Some(format!("Failed to create HTTP client: {}", e));
+            return delivery;
+        }
+    };
+
+    for attempt in 0..=retry_delays.len() {
+        if attempt > 0 {
+            tokio::time::sleep(retry_delays[attempt - 1]).await;
+        }
+        delivery.attempts = attempt + 1;
+
+        let mut request = http
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header("X-Wingman-Event", event.as_str())
+            .header("X-Wingman-Delivery", &delivery_id)
+            .body(body.clone());
+        if let Some(secret) = secret {
+            request = request.header("X-Wingman-Signature", sign(secret, body.as_bytes()));
+        }
+
+        let mut response_body = None;
+        let retry = match request.send().await {
+            Ok(response) => {
+                let status = response.status();
+                delivery.status_code = Some(status.as_u16());
+                response_body = response.text().await.ok().map(|text| truncate_body(text, MAX_RESPONSE_BODY));
+                if status.is_success() {
+                    delivery.success = true;
+                    delivery.error = None;
+                } else {
+                    delivery.error = Some(format!("Endpoint returned {}", status));
+                }
+                !delivery.success && is_retryable(status)
+            }
+            Err(e) => {
+                delivery.status_code = None;
+                delivery.error = Some(format!("Request failed: {}", e));
+                true
+            }
+        };
+
+        let attempt = DeliveryAttempt {
+            delivery_id: delivery_id.clone(),
+            webhook_id: webhook_id.to_string(),
+            event: event.as_str().to_string(),
+            payload: payload.clone(),
+            attempt: delivery.attempts as i64,
+            success: delivery.success,
+            status_code: delivery.status_code.map(i64::from),
+            response_body,
+            error: delivery.error.clone(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+        if let Err(e) = record_attempt(db, &attempt).await {
+            log::warn!("Failed to record an attempt for webhook {}: {}", webhook_id, e);
+        }
+
+        if !retry {
+            break;
+        }
+    }
+
+    delivery
+}
+
+/// Add an attempt to the delivery history, dropping the webhook's oldest beyond the limit
+async fn record_attempt(db: &SqlitePool, attempt: &DeliveryAttempt) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        INSERT INTO webhook_deliveries
+            (delivery_id, webhook_id, event, payload, attempt, success, status_code, response_body, error, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&attempt.delivery_id)
+    .bind(&attempt.webhook_id)
+    .bind(&attempt.event)
+    .bind(attempt.payload.to_string())
+    .bind(attempt.attempt)
+    .bind(attempt.success)
+    .bind(attempt.status_code)
+    .bind(&attempt.response_body)
+    .bind(&attempt.error)
+    .bind(&attempt.created_at)
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM webhook_deliveries
+        WHERE webhook_id = ?1 AND id <= (
+            SELECT id FROM webhook_deliveries WHERE webhook_id = ?1
+            ORDER BY id DESC LIMIT 1 OFFSET ?2
+        )
+        "#,
+    )
+    .bind(&attempt.webhook_id)
+    .bind(MAX_ATTEMPTS_KEPT)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Cut a response body to at most `max` bytes, on a character boundary
+fn truncate_body(mut body: String, max: usize) -> String {
+    if body.len() > max {
+        let mut end = max;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        body.truncate(end);
+    }
+    body
+}
+
+pub(crate) async fn record_delivery(db: &SqlitePool, webhook_id: &str, delivery: &Delivery) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        UPDATE webhooks
+        SET last_delivery_at = ?, last_status_code = ?, last_error = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(chrono::Utc::now().to_rfc3339())
+    .bind(delivery.status_code)
+    .bind(&delivery.error)
+    .bind(webhook_id)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// `X-Wingman-Signature` value for a body
+fn sign(secret: &str, body: &[u8]) -> String {
+    // HMAC accepts keys of any length
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes any key length");
+    mac.update(body);
+    let hex: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("sha256={}", hex)
+}
+
+/// Whether a failed response is worth retrying
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::REQUEST_TIMEOUT || status.is_server_error()
+}
+
+/// Check a webhook URL is an absolute HTTP(S) URL
+pub fn validate_url(url: &str) -> Result<(), AppError> {
+    let parsed = reqwest::Url::parse(url.trim())
+        .map_err(|e| AppError::new(ErrorCode::InvalidInput, format!("Invalid webhook URL: {}", e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err(AppError::invalid_input("Webhook URL must be an http or https URL"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook_event_names() {
+        for event in WebhookEvent::ALL {
+            assert_eq!(WebhookEvent::parse(event.as_str()), Some(event));
+        }
+        assert_eq!(WebhookEvent::parse("ping"), None);
+    }
+
+    #[test]
+    fn test_sign() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_truncate_body() {
+        assert_eq!(truncate_body("short".to_string(), 10), "short");
+        assert_eq!(truncate_body("abcdef".to_string(), 4), "abcd");
+        // Never splits a multi-byte character
+        assert_eq!(truncate_body("aé".to_string(), 2), "a");
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable(StatusCode::BAD_GATEWAY));
+        assert!(!is_retryable(StatusCode::NOT_FOUND));
+        assert!(!is_retryable(StatusCode::UNAUTHORIZED));
+    }
+
+    #[test]
+    fn test_validate_url() {
+        assert!(validate_url("https://hooks.slack.com/services/T000/B000/XXX").is_ok());
+        assert!(validate_url("http://localhost:5678/webhook/wingman").is_ok());
+        assert!(validate_url("ftp://example.com").is_err());
+        assert!(validate_url("not a url").is_err());
+    }
+}
@@ -1,5 +1,5 @@
 import { memo, useCallback } from 'react';
-import { FilePlus, FileEdit, FileX } from 'lucide-react';
+import { FilePlus, FileEdit, FileX, FileSymlink } from 'lucide-react';
 import { Icon, Badge } from '@/components/shared';
 import { cn, formatRelativeTime, getFileName, getDirectory } from '@/utils';
 import type { ActivityEntry as ActivityEntryType } from '@/types';
@@ -22,6 +22,8 @@ function getOperationIcon(operation: string) {
       return FileEdit;
     case 'deleted':
       return FileX;
+    case 'renamed':
+      return FileSymlink;
     default:
       return FileEdit;
   }
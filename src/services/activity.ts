@@ -4,13 +4,22 @@
  */
 
 import { invokeCommand } from './tauri';
-import type { ActivityEntry, FileOperation, ActivitySource } from '@/types/activity.types';
+import type {
+  ActivityEntry,
+  ActivityPruneReport,
+  ActivityRetention,
+  ActivityStats,
+  FileOperation,
+  ProjectWatchSettings,
+  ActivitySource,
+} from '@/types/activity.types';
 
 export const activityService = {
   /**
    * Start watching a directory for file changes
+   * Watches the session's working directory when no directory is given
    */
-  startWatcher: (sessionId: string, directory: string, ignorePatterns?: string[]) =>
+  startWatcher: (sessionId: string, directory?: string, ignorePatterns?: string[]) =>
     invokeCommand<void>('file_watcher_start', {
       sessionId,
       path: directory,
@@ -23,6 +32,18 @@ export const activityService = {
   stopWatcher: (sessionId: string) =>
     invokeCommand<void>('file_watcher_stop', { sessionId }),
 
+  /**
+   * Watch another directory for a session whose watcher is running
+   */
+  addWatchedPath: (sessionId: string, directory: string) =>
+    invokeCommand<void>('file_watcher_add_path', { sessionId, path: directory }),
+
+  /**
+   * Stop watching a directory added with addWatchedPath
+   */
+  removeWatchedPath: (sessionId: string, directory: string) =>
+    invokeCommand<void>('file_watcher_remove_path', { sessionId, path: directory }),
+
   /**
    * Get activity entries for a session
    * @param sessionId - The session ID
@@ -49,9 +70,32 @@ export const activityService = {
   clearActivityLog: (sessionId: string) =>
     invokeCommand<void>('activity_clear', { sessionId }),
 
+  /**
+   * Count a session's or project's activity by operation, source, extension, and hour
+   */
+  getStats: (scope: { sessionId?: string; projectId?: string }, since?: string) =>
+    invokeCommand<ActivityStats>('activity_stats', { ...scope, since }),
+
+  /**
+   * Get how much activity is kept (0 = no limit)
+   */
+  getRetention: () =>
+    invokeCommand<ActivityRetention>('activity_get_retention'),
+
+  /**
+   * Set how much activity is kept (0 = no limit)
+   */
+  setRetention: (days: number, maxRowsPerSession: number) =>
+    invokeCommand<void>('activity_set_retention', { days, maxRowsPerSession }),
+
+  /**
+   * Delete activity outside the retention policy now
+   */
+  prune: () => invokeCommand<ActivityPruneReport>('activity_prune'),
+
   /**
    * Save an activity entry to the database
-   * Used for manual entries or when the file watcher is not running
+   * Only for manual entries; changes seen by the file watcher are saved by the backend
    */
   saveActivityEntry: (
     sessionId: string,
@@ -75,4 +119,25 @@ export const activityService = {
       sessionId,
       path,
     }),
+
+  /**
+   * Get a project's file watcher settings
+   */
+  getProjectWatchSettings: (projectId: string) =>
+    invokeCommand<ProjectWatchSettings>('project_watch_settings_get', { projectId }),
+
+  /**
+   * Store a project's file watcher settings, applied to watchers started afterwards
+   */
+  setProjectWatchSettings: (
+    projectId: string,
+    settings: { ignorePatterns?: string[]; debounceMs?: number; includeDotfiles?: boolean }
+  ) =>
+    invokeCommand<ProjectWatchSettings>('project_watch_settings_set', { projectId, ...settings }),
+
+  /**
+   * Reset a project's file watcher settings to the defaults
+   */
+  deleteProjectWatchSettings: (projectId: string) =>
+    invokeCommand<void>('project_watch_settings_delete', { projectId }),
 };
@@ -0,0 +1,48 @@
+/**
+ * API Server Service
+ * IPC commands for the local HTTP API other tools can drive Wingman through
+ */
+
+import { invokeCommand } from './tauri';
+
+export interface ApiServerStatus {
+  /** Starts with the app */
+  enabled: boolean;
+  /** False while enabled means the server failed to start */
+  running: boolean;
+  port: number;
+  /** Base URL of the routes, while running */
+  url: string | null;
+}
+
+export const apiServerService = {
+  /**
+   * Report whether the API server is on and where it listens
+   */
+  status: () =>
+    invokeCommand<ApiServerStatus>('api_server_status'),
+
+  /**
+   * Turn the API server on, optionally on a new port
+   */
+  enable: (port?: number) =>
+    invokeCommand<ApiServerStatus>('api_server_enable', { port }),
+
+  /**
+   * Stop the API server and keep it off
+   */
+  disable: () =>
+    invokeCommand<ApiServerStatus>('api_server_disable'),
+
+  /**
+   * Get the token clients send as `Authorization: Bearer <token>`
+   */
+  getToken: () =>
+    invokeCommand<string>('api_server_get_token'),
+
+  /**
+   * Replace the token; clients using the old one are refused
+   */
+  rotateToken: () =>
+    invokeCommand<string>('api_server_rotate_token'),
+};
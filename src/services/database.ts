@@ -0,0 +1,28 @@
+/**
+ * Database Service
+ * IPC commands for encrypting the database at rest
+ */
+
+import { invokeCommand } from './tauri';
+
+export interface DbEncryptionStatus {
+  /** Whether this build includes SQLCipher */
+  supported: boolean;
+  encrypted: boolean;
+  /** An encrypted copy replaces the database when the app restarts */
+  pending: boolean;
+}
+
+export const databaseService = {
+  /**
+   * Report whether the database is encrypted
+   */
+  encryptionStatus: () =>
+    invokeCommand<DbEncryptionStatus>('db_encryption_status'),
+
+  /**
+   * Encrypt the existing database; the app restarts to reopen it
+   */
+  encryptExisting: () =>
+    invokeCommand<DbEncryptionStatus>('db_encrypt_existing'),
+};
@@ -0,0 +1,25 @@
+/**
+ * Events Service
+ * IPC commands for replaying session events a closed window missed
+ */
+
+import { invokeCommand } from './tauri';
+
+/** A buffered `claude_output` or `file_changed` event */
+export interface BufferedEvent {
+  id: number;
+  event: string;
+  sessionId: string | null;
+  /** The event's payload, as it was emitted */
+  payload: unknown;
+  createdAt: string;
+}
+
+export const eventsService = {
+  /**
+   * Get events emitted after an ISO timestamp, oldest first; only buffered
+   * while the background_mode setting is on
+   */
+  replaySince: (since: string, sessionId?: string, limit?: number) =>
+    invokeCommand<BufferedEvent[]>('events_replay_since', { since, sessionId, limit }),
+};
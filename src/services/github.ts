@@ -0,0 +1,66 @@
+/**
+ * GitHub Service
+ * IPC commands for linking a project to a GitHub repository and importing its issues
+ */
+
+import { invokeCommand } from './tauri';
+
+/** A project's linked GitHub repository */
+export interface GithubIntegration {
+  projectId: string;
+  /** owner/name */
+  repo: string;
+  /** GitHub label names to task label names; an empty name drops the label */
+  labelMap: Record<string, string>;
+  closeIssuesOnDone: boolean;
+  lastImportedAt?: string;
+  updatedAt: string;
+}
+
+export interface GithubIntegrationRequest {
+  /** owner/name or a clone URL */
+  repo: string;
+  labelMap?: Record<string, string>;
+  closeIssuesOnDone?: boolean;
+}
+
+/** A problem with one issue that didn't stop the import */
+export interface ImportWarning {
+  line: number;
+  message: string;
+}
+
+/** Result of an import */
+export interface ImportApplyResult {
+  source: string;
+  taskIds: string[];
+  skipped: number;
+  labelsCreated: number;
+  warnings: ImportWarning[];
+}
+
+export const githubService = {
+  /**
+   * Get a project's linked repository, or null
+   */
+  getIntegration: (projectId: string) =>
+    invokeCommand<GithubIntegration | null>('github_integration_get', { projectId }),
+
+  /**
+   * Link a project to a repository, or change its link
+   */
+  setIntegration: (projectId: string, request: GithubIntegrationRequest) =>
+    invokeCommand<GithubIntegration>('github_integration_set', { projectId, request }),
+
+  /**
+   * Unlink a project from its repository, keeping imported tasks
+   */
+  deleteIntegration: (projectId: string) =>
+    invokeCommand<void>('github_integration_delete', { projectId }),
+
+  /**
+   * Import the linked repository's issues into the backlog; the token defaults to the saved one, then GITHUB_TOKEN
+   */
+  importIssues: (projectId: string, includeClosed?: boolean, token?: string) =>
+    invokeCommand<ImportApplyResult>('github_import_issues', { projectId, includeClosed, token }),
+};
@@ -5,3 +5,11 @@ export * from './system';
 export * from './settings';
 export * from './activity';
 export * from './projects';
+export * from './search';
+export * from './github';
+export * from './integrations';
+export * from './webhooks';
+export * from './secrets';
+export * from './database';
+export * from './apiServer';
+export * from './events';
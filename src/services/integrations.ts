@@ -0,0 +1,98 @@
+/**
+ * Integrations Service
+ * IPC commands for linking a project to Linear or Jira and syncing tasks with it
+ */
+
+import { invokeCommand } from './tauri';
+
+export type Tracker = 'linear' | 'jira';
+
+/** Where a project's issues live in a tracker */
+export type TrackerConfig =
+  | {
+      tracker: 'linear';
+      /** Team ID */
+      teamId: string;
+    }
+  | {
+      tracker: 'jira';
+      /** Site URL, like https://acme.atlassian.net */
+      baseUrl: string;
+      projectKey: string;
+      /** Account the API token belongs to */
+      email: string;
+    };
+
+/** A project's link to a tracker */
+export type TrackerIntegration = TrackerConfig & {
+  projectId: string;
+  lastSyncedAt?: string;
+  updatedAt: string;
+};
+
+export type SyncAction = 'created' | 'updated' | 'pushed' | 'conflict' | 'error';
+
+/** One thing a sync did, or couldn't do */
+export interface SyncLogEntry {
+  id: string;
+  tracker: Tracker;
+  taskId?: string;
+  externalId?: string;
+  action: SyncAction;
+  message: string;
+  createdAt: string;
+}
+
+/** Result of an import or push */
+export interface IntegrationSyncResult {
+  tracker: Tracker;
+  /** Tasks created from new issues */
+  created: number;
+  /** Tasks whose status was taken from the tracker */
+  updated: number;
+  /** Issues whose status was taken from the task */
+  pushed: number;
+  conflicts: number;
+  errors: number;
+  /** What this sync logged */
+  log: SyncLogEntry[];
+}
+
+export const integrationsService = {
+  /**
+   * Get a project's tracker links
+   */
+  getIntegrations: (projectId: string) =>
+    invokeCommand<TrackerIntegration[]>('integration_get_all', { projectId }),
+
+  /**
+   * Link a project to a tracker, or change its link to that tracker
+   */
+  setIntegration: (projectId: string, config: TrackerConfig) =>
+    invokeCommand<TrackerIntegration>('integration_set', { projectId, config }),
+
+  /**
+   * Unlink a project from a tracker, keeping imported tasks
+   */
+  deleteIntegration: (projectId: string, tracker: Tracker) =>
+    invokeCommand<void>('integration_delete', { projectId, tracker }),
+
+  /**
+   * Import new issues into a sprint or the backlog, and take status changes made in the tracker;
+   * the token defaults to the saved one, then LINEAR_API_KEY or JIRA_API_TOKEN
+   */
+  importIssues: (projectId: string, tracker: Tracker, sprintId?: string, token?: string) =>
+    invokeCommand<IntegrationSyncResult>('integration_import', { projectId, tracker, sprintId, token }),
+
+  /**
+   * Push task status changes to the tracker
+   */
+  pushStatus: (projectId: string, tracker: Tracker, token?: string) =>
+    invokeCommand<IntegrationSyncResult>('integration_push', { projectId, tracker, token }),
+
+  /**
+   * Get a project's sync log, newest first
+   */
+  getSyncLog: (projectId: string, limit?: number) =>
+    invokeCommand<SyncLogEntry[]>('integration_sync_log_get', { projectId, limit }),
+};
@@ -10,14 +10,23 @@ import type {
   SprintWithProgress,
   Task,
   DashboardStats,
+  FileActivityResponse,
   ProjectCreateRequest,
   ProjectUpdateRequest,
   MilestoneCreateRequest,
   MilestoneUpdateRequest,
   SprintCreateRequest,
   SprintUpdateRequest,
+  SprintCompleteResult,
+  NextSprintRequest,
   TaskCreateRequest,
   TaskUpdateRequest,
+  TaskBulkUpdateRequest,
+  TaskBulkResult,
+  TimeEntry,
+  RecurringTask,
+  RecurringTaskCreateRequest,
+  RecurringTaskUpdateRequest,
 } from '@/types';
 
 export const projectsService = {
@@ -108,6 +117,14 @@ export const projectsService = {
   updateSprint: (sprintId: string, request: SprintUpdateRequest) =>
     invokeCommand<SprintWithProgress>('sprint_update', { sprintId, request }),
 
+  /**
+   * Complete a sprint, optionally carrying unfinished tasks to an existing or new sprint
+   */
+  completeSprint: (
+    sprintId: string,
+    carryOver?: { carryOverTo?: string; nextSprint?: NextSprintRequest }
+  ) => invokeCommand<SprintCompleteResult>('sprint_complete', { sprintId, ...carryOver }),
+
   /**
    * Delete a sprint
    */
@@ -142,6 +159,18 @@ export const projectsService = {
   moveTask: (taskId: string, sprintId?: string) =>
     invokeCommand<void>('task_move', { taskId, sprintId }),
 
+  /**
+   * Update the sprint, status, or priority of several tasks at once
+   */
+  bulkUpdateTasks: (taskIds: string[], request: TaskBulkUpdateRequest) =>
+    invokeCommand<TaskBulkResult[]>('task_bulk_update', { taskIds, request }),
+
+  /**
+   * Move several tasks to a sprint, or to the backlog without one
+   */
+  bulkMoveTasks: (taskIds: string[], sprintId?: string) =>
+    invokeCommand<TaskBulkResult[]>('task_bulk_move', { taskIds, sprintId }),
+
   /**
    * Delete a task
    */
@@ -165,6 +194,54 @@ export const projectsService = {
   getDependencies: (taskId: string) =>
     invokeCommand<string[]>('task_get_dependencies', { taskId }),
 
+  // ============================================================================
+  // Time Tracking
+  // ============================================================================
+
+  /**
+   * Start a timer on a task
+   */
+  startTimer: (taskId: string) => invokeCommand<TimeEntry>('task_timer_start', { taskId }),
+
+  /**
+   * Stop a task's running timer
+   */
+  stopTimer: (taskId: string) => invokeCommand<TimeEntry>('task_timer_stop', { taskId }),
+
+  /**
+   * Get a task's time entries, newest first
+   */
+  getTimeEntries: (taskId: string) =>
+    invokeCommand<TimeEntry[]>('task_time_entries_get', { taskId }),
+
+  // ============================================================================
+  // Recurring Tasks
+  // ============================================================================
+
+  /**
+   * Create a task template that is created again on a schedule
+   */
+  createRecurringTask: (request: RecurringTaskCreateRequest) =>
+    invokeCommand<RecurringTask>('recurring_task_create', { request }),
+
+  /**
+   * Get a project's recurring tasks, next due first
+   */
+  getRecurringTasks: (projectId: string) =>
+    invokeCommand<RecurringTask[]>('recurring_task_list', { projectId }),
+
+  /**
+   * Update a recurring task's template or schedule
+   */
+  updateRecurringTask: (recurringTaskId: string, request: RecurringTaskUpdateRequest) =>
+    invokeCommand<RecurringTask>('recurring_task_update', { recurringTaskId, request }),
+
+  /**
+   * Delete a recurring task, keeping the tasks it created
+   */
+  deleteRecurringTask: (recurringTaskId: string) =>
+    invokeCommand<void>('recurring_task_delete', { recurringTaskId }),
+
   // ============================================================================
   // Dashboard
   // ============================================================================
@@ -174,4 +251,10 @@ export const projectsService = {
    */
   getDashboardStats: (projectId: string) =>
     invokeCommand<DashboardStats>('dashboard_stats', { projectId }),
+
+  /**
+   * Get a project's most-changed files and recent hotspots
+   */
+  getFileActivity: (projectId: string, limit?: number, hotspotHours?: number) =>
+    invokeCommand<FileActivityResponse>('dashboard_file_activity', { projectId, limit, hotspotHours }),
 };
@@ -0,0 +1,33 @@
+/**
+ * Search Service
+ * IPC command for searching sessions, messages, tasks, milestones, and files at once
+ */
+
+import { invokeCommand } from './tauri';
+
+/** Kind of entity a search result points to */
+export type SearchResultKind = 'session' | 'message' | 'task' | 'milestone' | 'file';
+
+/** A search hit */
+export interface SearchResult {
+  kind: SearchResultKind;
+  /** Entity ID, or the relative path for files */
+  id: string;
+  title: string;
+  /** Text around the match when it isn't in the title */
+  snippet?: string;
+  /** Session holding the entity; for files, the last session that changed it */
+  sessionId?: string;
+  projectId?: string;
+  /** Higher is better */
+  score: number;
+  updatedAt: string;
+}
+
+export const searchService = {
+  /**
+   * Search everything for a query, best matches first
+   */
+  search: (query: string, projectId?: string, limit?: number) =>
+    invokeCommand<SearchResult[]>('search_global', { query, projectId, limit }),
+};
@@ -0,0 +1,29 @@
+/**
+ * Secrets Service
+ * IPC commands for API tokens kept in the OS keychain rather than the database
+ */
+
+import { invokeCommand } from './tauri';
+
+/** Secrets that can be saved; webhook secrets are managed through their webhook */
+export type SecretName = 'github_token' | 'linear_api_key' | 'jira_api_token' | 'anthropic_api_key';
+
+export const secretsService = {
+  /**
+   * Save a secret, replacing any earlier value
+   */
+  set: (name: SecretName, value: string) =>
+    invokeCommand<void>('secret_set', { name, value }),
+
+  /**
+   * Read a secret, or null if it isn't saved
+   */
+  get: (name: SecretName) =>
+    invokeCommand<string | null>('secret_get', { name }),
+
+  /**
+   * Remove a saved secret
+   */
+  delete: (name: SecretName) =>
+    invokeCommand<void>('secret_delete', { name }),
+};
@@ -9,6 +9,8 @@ import type {
   SessionWithMessages,
   SessionSummary,
   SessionCreateRequest,
+  ToolCall,
+  ToolCallStats,
 } from '@/types';
 
 export const sessionsService = {
@@ -26,8 +28,10 @@ export const sessionsService = {
 
   /**
    * Delete a session and its messages
+   * Sessions in a worktree need to say whether its changes are merged or discarded
    */
-  delete: (sessionId: string) => invokeCommand<void>('session_delete', { sessionId }),
+  delete: (sessionId: string, worktreeAction?: 'merge' | 'discard') =>
+    invokeCommand<void>('session_delete', { sessionId, worktreeAction }),
 
   /**
    * Rename a session
@@ -35,11 +39,38 @@ export const sessionsService = {
   rename: (sessionId: string, title: string) =>
     invokeCommand<void>('session_rename', { sessionId, title }),
 
+  /**
+   * Fork a session into a new one with its messages up to and including a message
+   */
+  fork: (sessionId: string, messageId: string, title?: string) =>
+    invokeCommand<Session>('session_fork', { sessionId, messageId, title }),
+
   /**
    * List sessions with summaries
    */
-  list: (projectId?: string, limit = 50, offset = 0) =>
-    invokeCommand<SessionSummary[]>('session_list', { projectId, limit, offset }),
+  list: (projectId?: string, limit = 50, offset = 0, includeArchived = false) =>
+    invokeCommand<SessionSummary[]>('session_list', { projectId, limit, offset, includeArchived }),
+
+  /**
+   * Archive a session, keeping its messages
+   */
+  archive: (sessionId: string) => invokeCommand<void>('session_archive', { sessionId }),
+
+  /**
+   * Restore an archived session
+   */
+  unarchive: (sessionId: string) => invokeCommand<void>('session_unarchive', { sessionId }),
+
+  /**
+   * Pin a session to the top of the list, or unpin it
+   */
+  pin: (sessionId: string, pinned: boolean) =>
+    invokeCommand<void>('session_pin', { sessionId, pinned }),
+
+  /**
+   * Set the custom order of sessions in the list
+   */
+  reorder: (sessionIds: string[]) => invokeCommand<void>('session_reorder', { sessionIds }),
 
   /**
    * Start the Claude CLI process for a session
@@ -55,8 +86,14 @@ export const sessionsService = {
   /**
    * Send a message to Claude
    */
-  sendMessage: (sessionId: string, content: string) =>
-    invokeCommand<string>('session_send_message', { sessionId, content }),
+  sendMessage: (sessionId: string, content: string, attachments?: string[]) =>
+    invokeCommand<string>('session_send_message', { sessionId, content, attachments }),
+
+  /**
+   * Allow or deny a tool the CLI asked permission to run
+   */
+  respondPermission: (sessionId: string, requestId: string, allow: boolean, message?: string) =>
+    invokeCommand<void>('session_respond_permission', { sessionId, requestId, allow, message }),
 
   /**
    * Cancel the current response
@@ -86,4 +123,16 @@ export const sessionsService = {
       content,
       toolUsage: toolUsage ? JSON.stringify(toolUsage) : null,
     }),
+
+  /**
+   * List the tools Claude ran in a session or one message
+   */
+  getToolCalls: (options: { sessionId?: string; messageId?: string; toolName?: string; limit?: number }) =>
+    invokeCommand<ToolCall[]>('tool_calls_get', options),
+
+  /**
+   * Count a session's tool calls per tool
+   */
+  getToolStats: (sessionId: string) =>
+    invokeCommand<ToolCallStats[]>('tool_calls_stats', { sessionId }),
 };
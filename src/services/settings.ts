@@ -4,22 +4,51 @@
  */
 
 import { invokeCommand } from './tauri';
-import type { Settings } from '@/types';
+
+/** Keys accepted by the settings commands */
+export type SettingKey =
+  | 'theme'
+  | 'default_model'
+  | 'ignore_patterns'
+  | 'debounce_ms'
+  | 'telemetry_opt_in'
+  | 'watcher_polling'
+  | 'poll_interval_ms'
+  | 'auto_title_sessions'
+  | 'notification_settings'
+  | 'background_mode';
+
+/** Value of the notification_settings setting; missing fields take their defaults */
+export interface NotificationSettings {
+  enabled: boolean;
+  tasksDueSoon: boolean;
+  /** How far ahead of its due date a task is notified about, 1 to 336 */
+  dueSoonHours: number;
+  milestonesOverdue: boolean;
+}
+
+/** A setting's current value */
+export interface SettingValue {
+  key: SettingKey;
+  value: unknown;
+  /** Whether the value is the default because the setting is unset */
+  isDefault: boolean;
+}
 
 export const settingsService = {
   /**
-   * Get all settings
+   * Get a single setting
    */
-  get: () => invokeCommand<Settings>('settings_get'),
+  get: (key: SettingKey) => invokeCommand<SettingValue>('settings_get', { key }),
 
   /**
-   * Update settings (partial update)
+   * Get all settings, keyed by setting
    */
-  update: (settings: Partial<Settings>) =>
-    invokeCommand<Settings>('settings_update', { settings }),
+  getAll: () => invokeCommand<Record<SettingKey, unknown>>('settings_get_all'),
 
   /**
-   * Reset settings to defaults
+   * Set a setting; null resets it to its default
    */
-  reset: () => invokeCommand<Settings>('settings_reset'),
+  set: (key: SettingKey, value: unknown) =>
+    invokeCommand<SettingValue>('settings_set', { key, value }),
 };
@@ -11,6 +11,42 @@ export interface AppInfo {
   tauriVersion: string;
 }
 
+export interface MigrationProgress {
+  version: number;
+  completed: number;
+  total: number;
+}
+
+export interface StartupStatus {
+  phase: 'starting' | 'migrating' | 'ready' | 'failed';
+  migration?: MigrationProgress;
+  error?: string;
+}
+
+export interface ChildProcess {
+  pid?: number;
+  kind: 'cli_session' | 'terminal' | 'script';
+  /** Terminal ID for terminals, run ID for scripts */
+  id?: string;
+  sessionId?: string;
+  sessionTitle?: string;
+  projectId?: string;
+  workingDirectory: string;
+  /** The CLI status for CLI sessions, otherwise 'running' */
+  status: string;
+  startedAt: string;
+  uptimeSecs: number;
+}
+
+export interface TerminalInfo {
+  id: string;
+  sessionId: string;
+  shell: string;
+  workingDirectory: string;
+  pid?: number;
+  createdAt: string;
+}
+
 export interface CliStatus {
   installed: boolean;
   version?: string;
@@ -18,12 +54,63 @@ export interface CliStatus {
   error?: string;
 }
 
+export type LogLevel = 'TRACE' | 'DEBUG' | 'INFO' | 'WARN' | 'ERROR';
+
+export interface LogEntry {
+  timestamp: string;
+  level: LogLevel;
+  /** Module the record came from */
+  target: string;
+  message: string;
+}
+
+export type HealthStatus = 'ok' | 'warning' | 'error';
+
+export interface SystemHealth {
+  /** The worst status of any subsystem */
+  status: HealthStatus;
+  database: {
+    status: HealthStatus;
+    connections: number;
+    idleConnections: number;
+    maxConnections: number;
+    walBytes: number;
+    error?: string;
+  };
+  cli: {
+    status: HealthStatus;
+    runningSessions: number;
+    busySessions: number;
+    /** Processes that exited but haven't been reaped yet */
+    zombieProcesses: number;
+  };
+  watchers: {
+    status: HealthStatus;
+    activeWatchers: number;
+    limitedWatchers: number;
+    droppedEvents: number;
+    paused: boolean;
+  };
+  disk: {
+    status: HealthStatus;
+    dataDir: string;
+    availableBytes?: number;
+    totalBytes?: number;
+  };
+  checkedAt: string;
+}
+
 export const systemService = {
   /**
    * Check if Claude CLI is installed and get version
    */
   checkCli: () => invokeCommand<CliStatus>('system_check_cli'),
 
+  /**
+   * Use a specific Claude CLI executable; null goes back to PATH lookup
+   */
+  setCliPath: (path: string | null) => invokeCommand<CliStatus>('system_set_cli_path', { path }),
+
   /**
    * Open a URL in the default browser
    */
@@ -45,6 +132,76 @@ export const systemService = {
    */
   getAppInfo: () => invokeCommand<AppInfo>('system_get_app_info'),
 
+  /**
+   * Get initialization progress, including database upgrades
+   */
+  getStartupStatus: () => invokeCommand<StartupStatus>('system_get_startup_status'),
+
+  /**
+   * Get the last app log entries, oldest first, optionally at a minimum level
+   */
+  getLogs: (lines?: number, level?: LogLevel) =>
+    invokeCommand<LogEntry[]>('system_get_logs', { lines, level }),
+
+  /**
+   * Open the log directory in the system file manager
+   */
+  openLogDir: () => invokeCommand<void>('system_open_log_dir'),
+
+  /**
+   * Write a diagnostics zip for a support request; returns the path written
+   */
+  generateDiagnostics: (path: string) =>
+    invokeCommand<string>('system_generate_diagnostics', { path }),
+
+  /**
+   * Report the health of the database, CLI processes, file watchers, and disk
+   */
+  getHealth: () => invokeCommand<SystemHealth>('system_health'),
+
+  /**
+   * Open a terminal running `claude login`; completion arrives as a cli_login_finished event
+   */
+  cliLogin: () => invokeCommand<void>('system_cli_login'),
+
+  /**
+   * List the processes the app has spawned
+   */
+  listChildren: () => invokeCommand<ChildProcess[]>('system_list_children'),
+
+  /**
+   * Kill a process the app has spawned
+   */
+  killChild: (pid: number) => invokeCommand<void>('system_kill_child', { pid }),
+
+  /**
+   * Open a shell in a session's working directory; output arrives as terminal_output events
+   */
+  createTerminal: (sessionId: string, cols?: number, rows?: number) =>
+    invokeCommand<TerminalInfo>('terminal_create', { sessionId, cols, rows }),
+
+  /**
+   * Send input to a terminal
+   */
+  writeTerminal: (terminalId: string, data: string) =>
+    invokeCommand<void>('terminal_write', { terminalId, data }),
+
+  /**
+   * Resize a terminal
+   */
+  resizeTerminal: (terminalId: string, cols: number, rows: number) =>
+    invokeCommand<void>('terminal_resize', { terminalId, cols, rows }),
+
+  /**
+   * Kill a terminal's shell
+   */
+  killTerminal: (terminalId: string) => invokeCommand<void>('terminal_kill', { terminalId }),
+
+  /**
+   * List open terminals, optionally only a session's
+   */
+  listTerminals: (sessionId?: string) => invokeCommand<TerminalInfo[]>('terminal_list', { sessionId }),
+
   /**
    * Start watching a directory for file changes
    */
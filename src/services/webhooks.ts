@@ -0,0 +1,107 @@
+/**
+ * Webhooks Service
+ * IPC commands for managing the URLs project events are POSTed to
+ */
+
+import { invokeCommand } from './tauri';
+
+export type WebhookEvent = 'task_completed' | 'sprint_completed' | 'session_finished' | 'claude_error';
+
+/** A URL project events are POSTed to */
+export interface Webhook {
+  id: string;
+  /** Unset for webhooks receiving events from every project */
+  projectId?: string;
+  name: string;
+  url: string;
+  /** Whether deliveries are signed; the secret itself is never returned */
+  hasSecret: boolean;
+  events: WebhookEvent[];
+  enabled: boolean;
+  lastDeliveryAt?: string;
+  lastStatusCode?: number;
+  lastError?: string;
+  createdAt: string;
+}
+
+export interface WebhookCreateRequest {
+  /** Leave unset to receive events from every project */
+  projectId?: string;
+  name: string;
+  url: string;
+  /** Signs deliveries with HMAC-SHA256 in X-Wingman-Signature; kept in the OS keychain */
+  secret?: string;
+  events: WebhookEvent[];
+}
+
+/** Outcome of delivering an event to a webhook */
+export interface WebhookDelivery {
+  deliveryId: string;
+  success: boolean;
+  /** Status of the last response, if any arrived */
+  statusCode?: number;
+  attempts: number;
+  error?: string;
+}
+
+/** One attempt at a delivery, from a webhook's history */
+export interface WebhookDeliveryAttempt {
+  /** Shared by the retries of one delivery */
+  deliveryId: string;
+  webhookId: string;
+  event: WebhookEvent | 'ping';
+  /** The JSON body sent */
+  payload: unknown;
+  /** 1 for the first try */
+  attempt: number;
+  success: boolean;
+  statusCode?: number;
+  /** Start of the response body, if a response arrived */
+  responseBody?: string;
+  error?: string;
+  createdAt: string;
+}
+
+export const webhooksService = {
+  /**
+   * Add a webhook
+   */
+  create: (request: WebhookCreateRequest) =>
+    invokeCommand<Webhook>('webhook_create', { request }),
+
+  /**
+   * List a project's webhooks and those for every project, or all webhooks without a project
+   */
+  list: (projectId?: string) =>
+    invokeCommand<Webhook[]>('webhook_list', { projectId }),
+
+  /**
+   * Turn a webhook on or off without deleting it
+   */
+  setEnabled: (webhookId: string, enabled: boolean) =>
+    invokeCommand<void>('webhook_set_enabled', { webhookId, enabled }),
+
+  /**
+   * Delete a webhook
+   */
+  delete: (webhookId: string) =>
+    invokeCommand<void>('webhook_delete', { webhookId }),
+
+  /**
+   * Send a ping to a webhook once and report how it went
+   */
+  test: (webhookId: string) =>
+    invokeCommand<WebhookDelivery>('webhook_test', { webhookId }),
+
+  /**
+   * List a webhook's delivery attempts, newest first
+   */
+  listDeliveries: (webhookId: string, limit?: number) =>
+    invokeCommand<WebhookDeliveryAttempt[]>('webhook_list_deliveries', { webhookId, limit }),
+
+  /**
+   * Send an earlier delivery's payload again, once, as a new delivery
+   */
+  redeliver: (deliveryId: string) =>
+    invokeCommand<WebhookDelivery>('webhook_redeliver', { deliveryId }),
+};
@@ -3,7 +3,7 @@
  */
 
 /** File operation type */
-export type FileOperation = 'created' | 'modified' | 'deleted';
+export type FileOperation = 'created' | 'modified' | 'deleted' | 'renamed';
 
 /** Source of the file change */
 export type ActivitySource = 'claude' | 'external';
@@ -16,6 +16,8 @@ export interface ActivityEntry {
   operation: FileOperation;
   source: ActivitySource;
   timestamp: string;
+  /** Where a renamed file was before */
+  oldPath?: string | null;
 }
 
 /** Filter options for activity feed */
@@ -31,3 +33,45 @@ export interface ActivityState {
   filter: ActivityFilter;
   isWatching: boolean;
 }
+
+/** How much activity is kept; 0 means no limit */
+export interface ActivityRetention {
+  days: number;
+  maxRowsPerSession: number;
+}
+
+/** Rows removed by an activity prune */
+export interface ActivityPruneReport {
+  expired: number;
+  overLimit: number;
+  total: number;
+}
+
+/** A value and how many activity entries have it */
+export interface ActivityCount {
+  key: string;
+  count: number;
+}
+
+/** Activity totals for a session or project */
+export interface ActivityStats {
+  total: number;
+  byOperation: ActivityCount[];
+  bySource: ActivityCount[];
+  /** Lowercase extension, most changed first; "" for files without one */
+  byExtension: ActivityCount[];
+  /** UTC hour buckets, oldest first */
+  byHour: ActivityCount[];
+}
+
+/** A project's file watcher settings */
+export interface ProjectWatchSettings {
+  projectId: string;
+  /** Added to the global ignore patterns */
+  ignorePatterns: string[];
+  /** Overrides the global debounce when set */
+  debounceMs?: number;
+  includeDotfiles: boolean;
+  /** Unset while the project uses the defaults */
+  updatedAt?: string;
+}
@@ -9,6 +9,7 @@ export type ErrorCode =
   | 'NOT_FOUND'
   | 'INVALID_INPUT'
   | 'PERMISSION_DENIED'
+  | 'RATE_LIMITED'
 
   // Claude CLI
   | 'CLAUDE_CLI_NOT_FOUND'
@@ -16,6 +17,10 @@ export type ErrorCode =
   | 'CLAUDE_CLI_TIMEOUT'
   | 'CLAUDE_CLI_AUTH_REQUIRED'
 
+  // Git
+  | 'GIT_NOT_FOUND'
+  | 'GIT_ERROR'
+
   // Database
   | 'DATABASE_ERROR'
   | 'DATABASE_CONSTRAINT'
@@ -26,6 +31,10 @@ export type ErrorCode =
   | 'FILE_ACCESS_DENIED'
   | 'FILE_ALREADY_EXISTS'
   | 'DIRECTORY_NOT_FOUND'
+  | 'WATCHER_LIMIT_REACHED'
+
+  // Terminal
+  | 'TERMINAL_ERROR'
 
   // Network
   | 'NETWORK_ERROR'
@@ -56,12 +65,16 @@ export const ERROR_MESSAGES: Record<ErrorCode, string> = {
   NOT_FOUND: 'The requested resource was not found',
   INVALID_INPUT: 'Invalid input provided',
   PERMISSION_DENIED: 'Permission denied',
+  RATE_LIMITED: 'Too many requests, slow down',
 
   CLAUDE_CLI_NOT_FOUND: 'Claude CLI is not installed or not in PATH',
   CLAUDE_CLI_ERROR: 'Claude CLI encountered an error',
   CLAUDE_CLI_TIMEOUT: 'Claude CLI request timed out',
   CLAUDE_CLI_AUTH_REQUIRED: 'Claude CLI requires authentication',
 
+  GIT_NOT_FOUND: 'Git is not installed or not in PATH',
+  GIT_ERROR: 'Git command failed',
+
   DATABASE_ERROR: 'Database error occurred',
   DATABASE_CONSTRAINT: 'Database constraint violation',
   DATABASE_NOT_FOUND: 'Database record not found',
@@ -70,6 +83,9 @@ export const ERROR_MESSAGES: Record<ErrorCode, string> = {
   FILE_ACCESS_DENIED: 'File access denied',
   FILE_ALREADY_EXISTS: 'File already exists',
   DIRECTORY_NOT_FOUND: 'Directory not found',
+  WATCHER_LIMIT_REACHED: 'Too many directories to watch',
+
+  TERMINAL_ERROR: 'Terminal error occurred',
 
   NETWORK_ERROR: 'Network error occurred',
   TIMEOUT: 'Request timed out',
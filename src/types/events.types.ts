@@ -5,6 +5,7 @@
 
 import type { ClaudeStatus, ToolUsage } from './session.types';
 import type { FileOperation, ActivitySource } from './activity.types';
+import type { ErrorCode } from './errors.types';
 
 /** Claude output event payload */
 export interface ClaudeOutputPayload {
@@ -26,21 +27,109 @@ export interface ClaudeStatusPayload {
 export interface ClaudeErrorPayload {
   sessionId: string;
   error: string;
+  /** CLAUDE_CLI_AUTH_REQUIRED when the CLI needs to log in again */
+  code?: ErrorCode;
   recoverable: boolean;
 }
 
+/** Claude permission request event payload */
+export interface ClaudePermissionRequestPayload {
+  sessionId: string;
+  requestId: string;
+  toolName: string;
+  input: unknown;
+}
+
+/** CLI login finished event payload */
+export interface CliLoginFinishedPayload {
+  success: boolean;
+  exitCode?: number;
+  error?: string;
+}
+
+/** Hook output event payload; the final event for a run has `done` set */
+export interface HookOutputPayload {
+  hookId: string;
+  event: string;
+  sessionId?: string;
+  stream: 'stdout' | 'stderr';
+  line?: string;
+  exitCode?: number;
+  done: boolean;
+}
+
+/** Verification run event payload, for both started and finished events */
+export interface VerificationPayload {
+  runId: string;
+  sessionId: string;
+  projectId: string;
+  command: string;
+  status: 'running' | 'passed' | 'failed';
+  exitCode?: number;
+  files: string[];
+}
+
+/** Terminal output event payload; the last event for a terminal has `exited` set */
+export interface TerminalOutputPayload {
+  terminalId: string;
+  sessionId: string;
+  data: string;
+  exited: boolean;
+  exitCode?: number;
+}
+
+/** Script output event payload; the final event for a run has no stream */
+export interface ScriptOutputPayload {
+  runId: string;
+  scriptId: string;
+  stream?: 'stdout' | 'stderr';
+  line?: string;
+  status: 'running' | 'succeeded' | 'failed' | 'cancelled';
+  exitCode?: number;
+}
+
 /** File changed event payload */
 export interface FileChangedPayload {
   sessionId: string;
   path: string;
+  /** The watched directory the file is under */
+  rootPath: string;
   operation: FileOperation;
   source: ActivitySource;
   timestamp: string;
+  /** Unified diff of the change, when diff capture is enabled */
+  diff?: string | null;
+  /** Where a renamed file was before */
+  oldPath?: string | null;
+  oldRelativePath?: string | null;
+}
+
+/** Changes under one directory in a bulk change */
+export interface BulkDirectoryCount {
+  path: string;
+  count: number;
+}
+
+/** Bulk change event payload, sent instead of file_changed during heavy churn */
+export interface BulkChangePayload {
+  sessionId: string;
+  /** Changes coalesced, counting repeated changes to a file */
+  events: number;
+  created: number;
+  modified: number;
+  deleted: number;
+  renamed: number;
+  /** Directories with the most changes, relative to their watched root */
+  topDirectories: BulkDirectoryCount[];
+  startedAt: string;
+  finishedAt: string;
 }
 
 /** Session saved event payload */
 export interface SessionSavedPayload {
   sessionId: string;
+  /** Set when the session's title changed */
+  title?: string;
   timestamp: string;
 }
 
@@ -63,16 +152,45 @@ export interface UpdateProgressPayload {
   percent: number;
 }
 
+/** Recurring task run event payload, sent when a task is created from a recurring task */
+export interface RecurringTaskRunPayload {
+  recurringTaskId: string;
+  taskId: string;
+  projectId: string;
+  title: string;
+}
+
+/** Deep link event payload, sent when a wingman:// link is opened */
+export interface DeepLinkPayload {
+  url: string;
+  action: 'open_session' | 'open_project' | 'open_task' | 'task_created' | 'error';
+  projectId: string | null;
+  sessionId: string | null;
+  taskId: string | null;
+  /** Why the link couldn't be opened */
+  error: string | null;
+}
+
 /** Event name constants */
 export const EVENTS = {
+  BULK_CHANGE: 'bulk_change',
   CLAUDE_OUTPUT: 'claude_output',
   CLAUDE_STATUS: 'claude_status',
   CLAUDE_ERROR: 'claude_error',
+  CLAUDE_PERMISSION_REQUEST: 'claude_permission_request',
+  CLI_LOGIN_FINISHED: 'cli_login_finished',
+  DEEP_LINK: 'deep_link',
   FILE_CHANGED: 'file_changed',
+  HOOK_OUTPUT: 'hook_output',
+  RECURRING_TASK_RUN: 'recurring_task_run',
+  SCRIPT_OUTPUT: 'script_output',
   SESSION_SAVED: 'session_saved',
+  TERMINAL_OUTPUT: 'terminal_output',
   THEME_CHANGED: 'theme_changed',
   UPDATE_AVAILABLE: 'update_available',
   UPDATE_PROGRESS: 'update_progress',
+  VERIFICATION_FINISHED: 'verification_finished',
+  VERIFICATION_STARTED: 'verification_started',
 } as const;
 
 export type EventName = (typeof EVENTS)[keyof typeof EVENTS];
@@ -2,6 +2,8 @@
  * Project Management Types
  */
 
+import type { AppError } from './errors.types';
+
 /** Task status */
 export type TaskStatus = 'todo' | 'in_progress' | 'done';
 
@@ -62,10 +64,26 @@ export interface Task {
   status: TaskStatus;
   priority: TaskPriority;
   estimatedHours?: number;
+  /** RFC 3339 timestamp, or YYYY-MM-DD due by the end of that day */
+  dueDate?: string;
+  /** Hours recorded by the task's timers, including a running one */
+  actualHours: number;
+  /** When the task's running timer started */
+  timerStartedAt?: string;
   createdAt: string;
   updatedAt: string;
 }
 
+/** A span of time spent on a task */
+export interface TimeEntry {
+  id: string;
+  taskId: string;
+  startedAt: string;
+  /** Unset while the timer is running */
+  endedAt?: string;
+  durationSeconds?: number;
+}
+
 /** Task dependency */
 export interface TaskDependency {
   taskId: string;
@@ -79,6 +97,38 @@ export interface SprintWithProgress extends Sprint {
   progress: number;
 }
 
+/** A completed task that did not meet the definition of done */
+export interface TaskDodGap {
+  taskId: string;
+  title: string;
+  uncheckedItems: string[];
+}
+
+/** Sprint created to take a completed sprint's unfinished tasks */
+export interface NextSprintRequest {
+  milestoneId?: string;
+  name: string;
+  description?: string;
+  startDate?: string;
+  endDate?: string;
+}
+
+/** Sprint completion result */
+export interface SprintCompleteResult extends Sprint {
+  dodCompliance: {
+    doneTasks: number;
+    compliantTasks: number;
+    nonCompliant: TaskDodGap[];
+  };
+  rollover: {
+    completedTasks: number;
+    incompleteTasks: number;
+    /** Unfinished tasks moved to nextSprint */
+    movedTasks: number;
+    nextSprint?: Sprint;
+  };
+}
+
 /** Milestone with sprints */
 export interface MilestoneWithSprints extends Milestone {
   sprints: Sprint[];
@@ -93,6 +143,26 @@ export interface DashboardStats {
   nextMilestone: Milestone | null;
 }
 
+/** How often one file changed */
+export interface FileActivity {
+  path: string;
+  changes: number;
+  claudeChanges: number;
+  externalChanges: number;
+  /** Share of changes made by Claude, from 0 to 1 */
+  claudeRatio: number;
+  /** Write and edit tool calls targeting the file */
+  toolEdits: number;
+  lastChangedAt: string;
+}
+
+/** Most-changed files in a project, overall and recently */
+export interface FileActivityResponse {
+  mostEdited: FileActivity[];
+  hotspots: FileActivity[];
+  hotspotSince: string;
+}
+
 /** Request types for API calls */
 export interface ProjectCreateRequest {
   name: string;
@@ -147,6 +217,7 @@ export interface TaskCreateRequest {
   description?: string;
   priority?: TaskPriority;
   estimatedHours?: number;
+  dueDate?: string;
 }
 
 export interface TaskUpdateRequest {
@@ -156,4 +227,62 @@ export interface TaskUpdateRequest {
   status?: TaskStatus;
   priority?: TaskPriority;
   estimatedHours?: number;
+  /** An empty string clears the due date */
+  dueDate?: string;
+}
+
+/** Changes applied to every task in a bulk update */
+export interface TaskBulkUpdateRequest {
+  sprintId?: string;
+  status?: TaskStatus;
+  /** Kanban column; takes precedence over status */
+  statusId?: string;
+  priority?: TaskPriority;
+}
+
+/** Outcome for one task in a bulk operation */
+export interface TaskBulkResult {
+  taskId: string;
+  success: boolean;
+  error?: AppError;
+}
+
+/** Task template created again on a schedule */
+export interface RecurringTask {
+  id: string;
+  projectId: string;
+  /** `every <n> <minutes|hours|days|weeks>`, `@daily`-style shorthand, or a cron expression in local time */
+  rule: string;
+  title: string;
+  description?: string;
+  priority: TaskPriority;
+  estimatedHours?: number;
+  /** Put created tasks in the active sprint instead of the backlog */
+  addToActiveSprint: boolean;
+  enabled: boolean;
+  nextRunAt: string;
+  lastRunAt?: string;
+  lastTaskId?: string;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface RecurringTaskCreateRequest {
+  projectId: string;
+  rule: string;
+  title: string;
+  description?: string;
+  priority?: TaskPriority;
+  estimatedHours?: number;
+  addToActiveSprint?: boolean;
+}
+
+export interface RecurringTaskUpdateRequest {
+  rule?: string;
+  title?: string;
+  description?: string;
+  priority?: TaskPriority;
+  estimatedHours?: number;
+  addToActiveSprint?: boolean;
+  enabled?: boolean;
 }
@@ -28,6 +28,29 @@ export interface Message {
   createdAt: string;
 }
 
+/** A tool Claude ran, recorded as its events streamed in */
+export interface ToolCall {
+  id: string;
+  sessionId: string;
+  messageId: string;
+  toolName: string;
+  input: Record<string, unknown>;
+  result?: string;
+  status: 'running' | 'succeeded' | 'failed';
+  durationMs?: number;
+  startedAt: string;
+  finishedAt?: string;
+}
+
+/** How often a session used one tool */
+export interface ToolCallStats {
+  toolName: string;
+  calls: number;
+  failed: number;
+  avgDurationMs?: number;
+  totalDurationMs: number;
+}
+
 /** Chat session */
 export interface Session {
   id: string;
@@ -35,6 +58,18 @@ export interface Session {
   workingDirectory: string;
   projectId?: string;
   claudeStatus: ClaudeStatus;
+  /** Session this one was forked from */
+  parentSessionId?: string;
+  forkedFromMessageId?: string;
+  createdAt: string;
+  updatedAt: string;
+}
+
+/** Assistant text saved while streaming, kept when the response was never saved */
+export interface MessageDraft {
+  messageId: string;
+  content: string;
+  isComplete: boolean;
   createdAt: string;
   updatedAt: string;
 }
@@ -43,6 +78,7 @@ export interface Session {
 export interface SessionWithMessages {
   session: Session;
   messages: Message[];
+  drafts: MessageDraft[];
 }
 
 /** Summary of a session (for listing) */
@@ -54,6 +90,11 @@ export interface SessionSummary {
   projectName?: string;
   messageCount: number;
   lastMessage?: string;
+  /** Set while the session is archived */
+  archivedAt?: string;
+  /** Pinned sessions are listed first */
+  isPinned: boolean;
+  sortOrder?: number;
   createdAt: string;
   updatedAt: string;
 }